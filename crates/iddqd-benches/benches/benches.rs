@@ -12,17 +12,23 @@
 //! * `shrink_to_fit/...` — pre-fill, scatter ~50% holes, compact.
 //! * `ref_mut/id_ord_map` — `IdOrdMap`'s mutable-reference guard
 //!   overhead.
+//! * `concurrent/...` — several threads doing a read-heavy mix of
+//!   lookups and overwrites, comparing a single `Mutex<IdHashMap>`
+//!   against the sharded `ConcurrentIdHashMap`.
 
 use criterion::{
     BatchSize, BenchmarkId, Criterion, criterion_group, criterion_main,
 };
-use iddqd::{DefaultHashBuilder, IdHashMap, IdOrdMap};
+use iddqd::{ConcurrentIdHashMap, DefaultHashBuilder, IdHashMap, IdOrdMap};
 use iddqd_benches::{
     LARGE_RECORD_PAYLOAD, RecordBorrowedU32, RecordLargeBorrowedU32,
     RecordLargeOwnedU32, RecordOwnedU32,
 };
 use iddqd_test_utils::test_item::{TestItem, TestKey1};
-use std::collections::{BTreeMap, HashMap};
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::Mutex,
+};
 
 /// Size sweep for `get` benches. The routine is fast enough per
 /// iteration to cover several orders of magnitude.
@@ -37,6 +43,16 @@ const SIZES: &[usize] = &[100, 10_000, 100_000];
 /// Number of remove + reinsert pairs per churn iteration.
 const CHURN_OPS: usize = 1_000;
 
+/// Number of worker threads for the `concurrent/...` benches.
+const CONCURRENT_THREADS: &[usize] = &[1, 2, 4, 8];
+
+/// Number of keys pre-filled into the `concurrent/...` maps.
+const CONCURRENT_SIZE: u32 = 10_000;
+
+/// Operations per thread per `concurrent/...` iteration. One in eight is
+/// an overwrite; the rest are lookups.
+const CONCURRENT_OPS: u32 = 1_000;
+
 /// Size sweep for the `*_large` benchmarks, which have a ~1 KiB inline payload
 /// per record. The larger batch sizes are enough to blow past L3 cache on every
 /// consumer-class CPU, and exercise the realloc-and-memcpy cost that resizing
@@ -1040,6 +1056,80 @@ fn iter_id_ord_map_large(c: &mut Criterion) {
     group.finish();
 }
 
+fn concurrent_mutex_id_hash_map(c: &mut Criterion) {
+    let mut group = c.benchmark_group("concurrent/mutex_id_hash_map");
+    for &threads in CONCURRENT_THREADS {
+        group.bench_with_input(
+            BenchmarkId::from_parameter(threads),
+            &threads,
+            |b, &threads| {
+                let mut map = IdHashMap::new();
+                for i in 0..CONCURRENT_SIZE {
+                    map.insert_unique(record(i)).unwrap();
+                }
+                let map = Mutex::new(map);
+                b.iter(|| {
+                    std::thread::scope(|s| {
+                        for t in 0..threads as u32 {
+                            let map = &map;
+                            s.spawn(move || {
+                                for step in 0..CONCURRENT_OPS {
+                                    let key = (step * 31 + t * 7919)
+                                        % CONCURRENT_SIZE;
+                                    let mut map = map.lock().unwrap();
+                                    if step % 8 == 0 {
+                                        map.insert_overwrite(record(key));
+                                    } else {
+                                        std::hint::black_box(map.get(&key));
+                                    }
+                                }
+                            });
+                        }
+                    });
+                });
+            },
+        );
+    }
+    group.finish();
+}
+
+fn concurrent_sharded_id_hash_map(c: &mut Criterion) {
+    let mut group = c.benchmark_group("concurrent/sharded_id_hash_map");
+    for &threads in CONCURRENT_THREADS {
+        group.bench_with_input(
+            BenchmarkId::from_parameter(threads),
+            &threads,
+            |b, &threads| {
+                let map = ConcurrentIdHashMap::new();
+                for i in 0..CONCURRENT_SIZE {
+                    map.insert_overwrite(record(i));
+                }
+                b.iter(|| {
+                    std::thread::scope(|s| {
+                        for t in 0..threads as u32 {
+                            let map = &map;
+                            s.spawn(move || {
+                                for step in 0..CONCURRENT_OPS {
+                                    let key = (step * 31 + t * 7919)
+                                        % CONCURRENT_SIZE;
+                                    if step % 8 == 0 {
+                                        map.insert_overwrite(record(key));
+                                    } else {
+                                        map.get_with(key, |r| {
+                                            std::hint::black_box(r.index);
+                                        });
+                                    }
+                                }
+                            });
+                        }
+                    });
+                });
+            },
+        );
+    }
+    group.finish();
+}
+
 criterion_group!(
     benches,
     get_hash_map,
@@ -1078,5 +1168,7 @@ criterion_group!(
     shrink_to_fit_id_hash_map,
     shrink_to_fit_id_ord_map,
     ref_mut_id_ord_map,
+    concurrent_mutex_id_hash_map,
+    concurrent_sharded_id_hash_map,
 );
 criterion_main!(benches);
@@ -0,0 +1,357 @@
+//! Derive macros for the item traits in [iddqd](https://docs.rs/iddqd).
+//!
+//! Implementing [`IdHashItem`] or [`IdOrdItem`] by hand means picking a key,
+//! writing a `key` method, and calling [`id_upcast!`]. The derive macros in
+//! this crate generate all of that from an `#[id_key]` attribute on the key
+//! field.
+//!
+//! # Examples
+//!
+//! ```
+//! use iddqd::{IdHashMap, IdOrdMap};
+//! use iddqd_derive::{IdHashItem, IdOrdItem};
+//!
+//! #[derive(Debug, IdHashItem, IdOrdItem)]
+//! struct User {
+//!     #[id_key]
+//!     name: String,
+//!     age: u32,
+//! }
+//!
+//! let mut map = IdHashMap::new();
+//! map.insert_unique(User { name: "alice".to_owned(), age: 30 }).unwrap();
+//!
+//! // `String` keys are borrowed as `&str`.
+//! assert_eq!(map.get("alice").unwrap().age, 30);
+//!
+//! let mut map = IdOrdMap::new();
+//! map.insert_unique(User { name: "bob".to_owned(), age: 25 }).unwrap();
+//! map.insert_unique(User { name: "alice".to_owned(), age: 30 }).unwrap();
+//! let names: Vec<_> = map.iter().map(|user| user.name.as_str()).collect();
+//! assert_eq!(names, ["alice", "bob"]);
+//! ```
+//!
+//! # Key types
+//!
+//! The key type is picked based on the type of the `#[id_key]` field:
+//!
+//! | Field type                                       | Key type                 |
+//! |--------------------------------------------------|--------------------------|
+//! | `String`                                         | `&'a str`                |
+//! | Primitives such as `u32`, `char`, and `bool`     | The field type, copied   |
+//! | Shared references such as `&'x T`                | `&'x T`, copied          |
+//! | Any other type `T`                               | `&'a T`                  |
+//!
+//! To copy the key out of the item for other `Copy` types, write
+//! `#[id_key(copy)]`:
+//!
+//! ```
+//! use iddqd::IdHashMap;
+//! use iddqd_derive::IdHashItem;
+//!
+//! #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+//! struct UserId(u64);
+//!
+//! #[derive(Debug, IdHashItem)]
+//! struct User {
+//!     #[id_key(copy)]
+//!     id: UserId,
+//!     name: String,
+//! }
+//!
+//! let mut map = IdHashMap::new();
+//! map.insert_unique(User { id: UserId(1), name: "alice".to_owned() })
+//!     .unwrap();
+//! assert_eq!(map.get(&UserId(1)).unwrap().name, "alice");
+//! ```
+//!
+//! # Computed keys
+//!
+//! For keys that aren't a single field, put
+//! `#[id_key(method = ..., ty = ...)]` on the type instead. `method` is the
+//! name of a method that takes `&self` and returns the key, and `ty` is the
+//! key type. The key type can borrow from the item through the lifetime `'a`.
+//!
+//! ```
+//! use iddqd::IdHashMap;
+//! use iddqd_derive::IdHashItem;
+//!
+//! #[derive(Debug, IdHashItem)]
+//! #[id_key(method = full_name, ty = (&'a str, &'a str))]
+//! struct Person {
+//!     first: String,
+//!     last: String,
+//! }
+//!
+//! impl Person {
+//!     fn full_name(&self) -> (&str, &str) {
+//!         (&self.first, &self.last)
+//!     }
+//! }
+//!
+//! let mut map = IdHashMap::new();
+//! let person =
+//!     Person { first: "Ada".to_owned(), last: "Lovelace".to_owned() };
+//! map.insert_unique(person).unwrap();
+//! assert!(map.contains_key(&("Ada", "Lovelace")));
+//! ```
+//!
+//! Computed keys can also be used with enums.
+//!
+//! # Notes
+//!
+//! * The lifetime `'a` is reserved for the key type, so types with a lifetime
+//!   parameter named `'a` are not supported.
+//! * As with [`id_upcast!`], the key type must be covariant in `'a`. In
+//!   practice, this means that it must not contain `&'a mut` or
+//!   `Cell<&'a T>`.
+//! * The generated code refers to `::iddqd`, so `iddqd` must be a dependency
+//!   of the crate using the derives.
+//!
+//! [`IdHashItem`]: https://docs.rs/iddqd/latest/iddqd/trait.IdHashItem.html
+//! [`IdOrdItem`]: https://docs.rs/iddqd/latest/iddqd/trait.IdOrdItem.html
+//! [`id_upcast!`]: https://docs.rs/iddqd/latest/iddqd/macro.id_upcast.html
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{
+    Attribute, Data, DeriveInput, Field, Ident, Member, Type, parse_macro_input,
+};
+
+/// Derives `iddqd::IdHashItem`.
+///
+/// See the [crate documentation](crate) for details.
+#[proc_macro_derive(IdHashItem, attributes(id_key))]
+pub fn derive_id_hash_item(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(&input, "IdHashItem", quote! { ::iddqd::IdHashItem })
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Derives `iddqd::IdOrdItem`.
+///
+/// See the [crate documentation](crate) for details.
+#[proc_macro_derive(IdOrdItem, attributes(id_key))]
+pub fn derive_id_ord_item(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(&input, "IdOrdItem", quote! { ::iddqd::IdOrdItem })
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+fn expand(
+    input: &DeriveInput,
+    derive_name: &str,
+    trait_path: TokenStream2,
+) -> syn::Result<TokenStream2> {
+    let KeySpec { ty, expr } = KeySpec::from_input(input, derive_name)?;
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) =
+        input.generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics #trait_path for #name #ty_generics #where_clause {
+            type Key<'a>
+                = #ty
+            where
+                Self: 'a;
+
+            fn key(&self) -> Self::Key<'_> {
+                #expr
+            }
+
+            ::iddqd::id_upcast!();
+        }
+    })
+}
+
+/// The key type and the expression that produces it from `self`.
+struct KeySpec {
+    ty: TokenStream2,
+    expr: TokenStream2,
+}
+
+impl KeySpec {
+    fn from_input(input: &DeriveInput, derive_name: &str) -> syn::Result<Self> {
+        if let Some(param) =
+            input.generics.lifetimes().find(|param| param.lifetime.ident == "a")
+        {
+            return Err(syn::Error::new(
+                param.lifetime.span(),
+                format!(
+                    "#[derive({derive_name})] reserves the lifetime `'a` for \
+                     the key type; rename this lifetime parameter"
+                ),
+            ));
+        }
+
+        let mut type_attrs = input.attrs.iter().filter(|attr| is_id_key(attr));
+        let type_attr = type_attrs.next();
+        if let Some(attr) = type_attrs.next() {
+            return Err(syn::Error::new_spanned(
+                attr,
+                "duplicate `#[id_key]` attribute",
+            ));
+        }
+
+        let mut key_fields = Vec::new();
+        if let Data::Struct(data) = &input.data {
+            for (index, field) in data.fields.iter().enumerate() {
+                let mut attrs =
+                    field.attrs.iter().filter(|attr| is_id_key(attr));
+                if let Some(attr) = attrs.next() {
+                    if let Some(dup) = attrs.next() {
+                        return Err(syn::Error::new_spanned(
+                            dup,
+                            "duplicate `#[id_key]` attribute",
+                        ));
+                    }
+                    let member = match &field.ident {
+                        Some(ident) => Member::Named(ident.clone()),
+                        None => Member::Unnamed(index.into()),
+                    };
+                    key_fields.push((attr, member, field));
+                }
+            }
+        }
+
+        if let Some(attr) = type_attr {
+            if let Some((field_attr, _, _)) = key_fields.first() {
+                return Err(syn::Error::new_spanned(
+                    field_attr,
+                    "`#[id_key]` can't be used on a field if the type has \
+                     `#[id_key(method = ..., ty = ...)]`",
+                ));
+            }
+            return Self::from_type_attr(attr);
+        }
+
+        match key_fields.as_slice() {
+            [] => {
+                let message = match &input.data {
+                    Data::Struct(_) => format!(
+                        "#[derive({derive_name})] requires either a field \
+                         marked `#[id_key]`, or `#[id_key(method = ..., \
+                         ty = ...)]` on the type"
+                    ),
+                    Data::Enum(_) | Data::Union(_) => format!(
+                        "#[derive({derive_name})] on enums and unions \
+                         requires `#[id_key(method = ..., ty = ...)]` on the \
+                         type"
+                    ),
+                };
+                Err(syn::Error::new(input.ident.span(), message))
+            }
+            [(attr, member, field)] => Self::from_field(attr, member, field),
+            [_, (attr, _, _), ..] => Err(syn::Error::new_spanned(
+                attr,
+                "only one field can be marked `#[id_key]`",
+            )),
+        }
+    }
+
+    fn from_type_attr(attr: &Attribute) -> syn::Result<Self> {
+        let mut method: Option<Ident> = None;
+        let mut ty: Option<Type> = None;
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("method") {
+                method = Some(meta.value()?.parse()?);
+                Ok(())
+            } else if meta.path.is_ident("ty") {
+                ty = Some(meta.value()?.parse()?);
+                Ok(())
+            } else {
+                Err(meta.error(
+                    "unknown `id_key` option on a type: expected `method` or \
+                     `ty`",
+                ))
+            }
+        })?;
+
+        match (method, ty) {
+            (Some(method), Some(ty)) => Ok(Self {
+                ty: quote! { #ty },
+                expr: quote! { Self::#method(self) },
+            }),
+            (None, _) => Err(syn::Error::new_spanned(
+                attr,
+                "`#[id_key]` on a type requires `method = ...`",
+            )),
+            (_, None) => Err(syn::Error::new_spanned(
+                attr,
+                "`#[id_key]` on a type requires `ty = ...`",
+            )),
+        }
+    }
+
+    fn from_field(
+        attr: &Attribute,
+        member: &Member,
+        field: &Field,
+    ) -> syn::Result<Self> {
+        let mut copy = false;
+        if !matches!(attr.meta, syn::Meta::Path(_)) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("copy") {
+                    copy = true;
+                    Ok(())
+                } else {
+                    Err(meta.error(
+                        "unknown `id_key` option on a field: expected `copy`",
+                    ))
+                }
+            })?;
+        }
+
+        let field_ty = &field.ty;
+        let by_value =
+            Self { ty: quote! { #field_ty }, expr: quote! { self.#member } };
+        if copy {
+            return Ok(by_value);
+        }
+
+        match field_ty {
+            Type::Reference(reference) if reference.mutability.is_none() => {
+                Ok(by_value)
+            }
+            Type::Path(path) if path.qself.is_none() => {
+                if is_string(&path.path) {
+                    Ok(Self {
+                        ty: quote! { &'a ::core::primitive::str },
+                        expr: quote! { &self.#member },
+                    })
+                } else if is_primitive(&path.path) {
+                    Ok(by_value)
+                } else {
+                    Ok(Self::by_ref(field_ty, member))
+                }
+            }
+            _ => Ok(Self::by_ref(field_ty, member)),
+        }
+    }
+
+    fn by_ref(field_ty: &Type, member: &Member) -> Self {
+        Self { ty: quote! { &'a #field_ty }, expr: quote! { &self.#member } }
+    }
+}
+
+fn is_id_key(attr: &Attribute) -> bool {
+    attr.path().is_ident("id_key")
+}
+
+/// Returns true for `String`, `std::string::String`, and so on.
+fn is_string(path: &syn::Path) -> bool {
+    path.segments.last().is_some_and(|segment| {
+        segment.ident == "String" && segment.arguments.is_none()
+    })
+}
+
+fn is_primitive(path: &syn::Path) -> bool {
+    const PRIMITIVES: &[&str] = &[
+        "bool", "char", "u8", "u16", "u32", "u64", "u128", "usize", "i8",
+        "i16", "i32", "i64", "i128", "isize",
+    ];
+    path.get_ident().is_some_and(|ident| PRIMITIVES.iter().any(|p| ident == p))
+}
@@ -0,0 +1,165 @@
+//! Tests for the generated trait implementations.
+
+use iddqd::{IdHashItem, IdHashMap, IdOrdItem, IdOrdMap};
+use iddqd_derive::{IdHashItem, IdOrdItem};
+use std::{fmt::Debug, hash::Hash};
+
+#[derive(Debug, IdHashItem, IdOrdItem)]
+struct StringKey {
+    #[id_key]
+    name: String,
+    value: u32,
+}
+
+#[derive(Debug, IdHashItem, IdOrdItem)]
+struct PrimitiveKey {
+    #[id_key]
+    id: u32,
+}
+
+#[derive(Debug, IdHashItem, IdOrdItem)]
+struct TupleKey(u8, #[id_key] std::string::String);
+
+#[derive(Debug, IdHashItem, IdOrdItem)]
+struct RefKey<'s> {
+    #[id_key]
+    name: &'s str,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+struct Id(u64);
+
+#[derive(Debug, IdHashItem, IdOrdItem)]
+struct CopyKey {
+    #[id_key(copy)]
+    id: Id,
+}
+
+#[derive(Debug, IdHashItem, IdOrdItem)]
+struct ByRefKey {
+    #[id_key]
+    path: Vec<String>,
+}
+
+#[derive(Debug, IdHashItem, IdOrdItem)]
+struct GenericKey<T: Eq + Ord + Hash> {
+    #[id_key]
+    key: T,
+}
+
+#[derive(Debug, IdHashItem, IdOrdItem)]
+#[id_key(method = full_name, ty = (&'a str, &'a str))]
+struct MethodKey {
+    first: String,
+    last: String,
+}
+
+impl MethodKey {
+    fn full_name(&self) -> (&str, &str) {
+        (&self.first, &self.last)
+    }
+}
+
+#[derive(Debug, IdHashItem, IdOrdItem)]
+#[id_key(method = id, ty = u32)]
+enum EnumKey {
+    A { id: u32 },
+    B(u32),
+}
+
+impl EnumKey {
+    fn id(&self) -> u32 {
+        match self {
+            Self::A { id } | Self::B(id) => *id,
+        }
+    }
+}
+
+fn assert_hash_key<'a, T: IdHashItem>(item: &'a T, expected: T::Key<'a>)
+where
+    T::Key<'a>: Debug,
+{
+    assert_eq!(item.key(), expected);
+}
+
+fn assert_ord_key<'a, T: IdOrdItem>(item: &'a T, expected: T::Key<'a>)
+where
+    T::Key<'a>: Debug,
+{
+    assert_eq!(item.key(), expected);
+}
+
+#[test]
+fn key_types() {
+    let item = StringKey { name: "foo".to_owned(), value: 1 };
+    assert_hash_key(&item, "foo");
+    assert_ord_key(&item, "foo");
+
+    let item = PrimitiveKey { id: 5 };
+    assert_hash_key(&item, 5);
+    assert_ord_key(&item, 5);
+
+    let item = TupleKey(1, "foo".to_owned());
+    assert_eq!(item.0, 1);
+    assert_hash_key(&item, "foo");
+    assert_ord_key(&item, "foo");
+
+    let item = RefKey { name: "foo" };
+    assert_hash_key(&item, "foo");
+    assert_ord_key(&item, "foo");
+
+    let item = CopyKey { id: Id(3) };
+    assert_hash_key(&item, Id(3));
+    assert_ord_key(&item, Id(3));
+
+    let path = vec!["a".to_owned(), "b".to_owned()];
+    let item = ByRefKey { path: path.clone() };
+    assert_hash_key(&item, &path);
+    assert_ord_key(&item, &path);
+
+    let item = GenericKey { key: 'x' };
+    assert_hash_key(&item, &'x');
+    assert_ord_key(&item, &'x');
+
+    let item = MethodKey { first: "a".to_owned(), last: "b".to_owned() };
+    assert_hash_key(&item, ("a", "b"));
+    assert_ord_key(&item, ("a", "b"));
+
+    assert_hash_key(&EnumKey::A { id: 1 }, 1);
+    assert_ord_key(&EnumKey::B(2), 2);
+}
+
+#[test]
+fn maps() {
+    let mut hash_map = IdHashMap::new();
+    hash_map
+        .insert_unique(StringKey { name: "a".to_owned(), value: 1 })
+        .unwrap();
+    hash_map
+        .insert_unique(StringKey { name: "a".to_owned(), value: 2 })
+        .expect_err("duplicate key");
+    hash_map.get_mut("a").unwrap().value = 3;
+    assert_eq!(hash_map.get("a").unwrap().value, 3);
+
+    let mut ord_map = IdOrdMap::new();
+    ord_map.insert_unique(PrimitiveKey { id: 2 }).unwrap();
+    ord_map.insert_unique(PrimitiveKey { id: 1 }).unwrap();
+    let ids: Vec<_> = ord_map.iter().map(|item| item.id).collect();
+    assert_eq!(ids, [1, 2]);
+
+    let mut ord_map = IdOrdMap::new();
+    ord_map
+        .insert_unique(MethodKey {
+            first: "b".to_owned(),
+            last: "a".to_owned(),
+        })
+        .unwrap();
+    ord_map
+        .insert_unique(MethodKey {
+            first: "a".to_owned(),
+            last: "b".to_owned(),
+        })
+        .unwrap();
+    let first = ord_map.first().unwrap();
+    assert_eq!(first.full_name(), ("a", "b"));
+}
@@ -0,0 +1,7 @@
+//! UI tests.
+
+#[test]
+fn ui() {
+    let t = trybuild::TestCases::new();
+    t.compile_fail("tests/ui/invalid/*.rs");
+}
@@ -0,0 +1,22 @@
+use iddqd_derive::IdHashItem;
+
+#[derive(IdHashItem)]
+struct UnknownFieldOption {
+    #[id_key(clone)]
+    id: u32,
+}
+
+#[derive(IdHashItem)]
+#[id_key(method = key)]
+struct MissingTy {
+    id: u32,
+}
+
+#[derive(IdHashItem)]
+#[id_key(method = key, ty = u32)]
+struct MethodAndField {
+    #[id_key]
+    id: u32,
+}
+
+fn main() {}
@@ -0,0 +1,13 @@
+use iddqd_derive::IdHashItem;
+
+#[derive(IdHashItem)]
+struct Item {
+    id: u32,
+}
+
+#[derive(IdHashItem)]
+enum Enum {
+    A,
+}
+
+fn main() {}
@@ -0,0 +1,11 @@
+use iddqd_derive::IdOrdItem;
+
+#[derive(IdOrdItem)]
+struct Item {
+    #[id_key]
+    id: u32,
+    #[id_key]
+    name: String,
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use iddqd_derive::IdHashItem;
+
+#[derive(IdHashItem)]
+struct Item<'a> {
+    #[id_key]
+    name: &'a str,
+}
+
+fn main() {}
@@ -1,7 +1,6 @@
-#[cfg(feature = "std")]
-use iddqd::IdOrdItem;
 use iddqd::{
-    BiHashItem, IdHashItem, TriHashItem, bi_upcast, id_upcast, tri_upcast,
+    BiHashItem, IdHashItem, IdOrdItem, TriHashItem, bi_upcast, id_upcast,
+    tri_upcast,
 };
 use std::{borrow::Cow, path::Path};
 
@@ -26,7 +25,6 @@ impl<'a> IdHashItem for BorrowedItem<'a> {
     id_upcast!();
 }
 
-#[cfg(feature = "std")]
 impl<'a> IdOrdItem for BorrowedItem<'a> {
     type Key<'k>
         = &'a str
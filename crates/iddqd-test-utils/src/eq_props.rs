@@ -1,4 +1,7 @@
-use std::fmt;
+use std::{
+    fmt,
+    hash::{BuildHasher, Hash},
+};
 
 /// Assert equality properties.
 ///
@@ -24,3 +27,13 @@ pub fn assert_ne_props<T: Eq + fmt::Debug>(a: T, b: T) {
     assert_ne!(a, b, "a != b");
     assert_ne!(b, a, "b != a");
 }
+
+/// Assert that equal values hash the same.
+///
+/// Hash implementations for maps must not depend on the order in which items
+/// are stored.
+pub fn assert_hash_eq<T: Eq + Hash + fmt::Debug>(a: T, b: T) {
+    assert_eq!(a, b, "a == b");
+    let state = std::hash::RandomState::new();
+    assert_eq!(state.hash_one(&a), state.hash_one(&b), "hash(a) == hash(b)");
+}
@@ -119,6 +119,20 @@ impl NaiveMap {
         self.items.iter_mut().find(|e| e.key1 == key1 && e.key2 == key2)
     }
 
+    /// Returns the item matching both `key1` and `key3`, if any.
+    ///
+    /// Mirrors `TriHashMap::get13`.
+    pub fn get_unique13(&self, key1: u8, key3: &str) -> Option<&TestItem> {
+        self.items.iter().find(|e| e.key1 == key1 && e.key3 == key3)
+    }
+
+    /// Returns the item matching both `key2` and `key3`, if any.
+    ///
+    /// Mirrors `TriHashMap::get23`.
+    pub fn get_unique23(&self, key2: char, key3: &str) -> Option<&TestItem> {
+        self.items.iter().find(|e| e.key2 == key2 && e.key3 == key3)
+    }
+
     /// Removes and returns the item whose two keys both match, if any.
     pub fn remove_unique12(
         &mut self,
@@ -149,6 +163,32 @@ impl NaiveMap {
         indexes.iter().rev().map(|&i| self.items.remove(i)).collect()
     }
 
+    /// Removes and returns every item covered by a `TriHashMap` entry keyed on
+    /// `(key1, key2, key3)`, i.e., every item matching `key1`, `key2` *or*
+    /// `key3`.
+    ///
+    /// Mirrors `tri_hash_map::OccupiedEntry::remove`. An empty result
+    /// corresponds to a vacant entry.
+    pub fn entry_remove123(
+        &mut self,
+        key1: u8,
+        key2: char,
+        key3: &str,
+    ) -> Vec<TestItem> {
+        let indexes = self
+            .items
+            .iter()
+            .enumerate()
+            .filter_map(|(i, e)| {
+                (e.key1 == key1 || e.key2 == key2 || e.key3 == key3)
+                    .then_some(i)
+            })
+            .collect::<Vec<_>>();
+
+        // Remove in reverse so earlier indexes stay valid as items shift.
+        indexes.iter().rev().map(|&i| self.items.remove(i)).collect()
+    }
+
     /// Returns the item whose three keys all match, if any.
     ///
     /// Mirrors `TriHashMap::get_unique`: a hit requires `key1`, `key2`, *and*
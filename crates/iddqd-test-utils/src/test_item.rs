@@ -1,15 +1,17 @@
 use iddqd::{
-    BiHashItem, BiHashMap, IdHashItem, IdHashMap, TriHashItem, TriHashMap,
-    bi_hash_map, bi_upcast,
+    BiHashItem, BiHashMap, IdHashItem, IdHashMap, IdOrdItem, IdOrdMap,
+    TriHashItem, TriHashMap, bi_hash_map, bi_upcast,
     errors::DuplicateItem,
-    id_hash_map, id_upcast,
+    id_hash_map, id_ord_map, id_upcast,
     internal::{ValidateCompact, ValidationError},
     tri_hash_map, tri_upcast,
 };
-#[cfg(feature = "std")]
-use iddqd::{IdOrdItem, IdOrdMap, id_ord_map};
 use proptest::prelude::*;
-use std::{cell::Cell, fmt};
+use std::{
+    cell::Cell,
+    fmt,
+    hash::{Hash, Hasher},
+};
 use test_strategy::Arbitrary;
 
 thread_local! {
@@ -98,6 +100,17 @@ impl PartialEq<&TestItem> for TestItem {
     }
 }
 
+// Chaos is not hashed, since it contains cells. That's still consistent with
+// Eq: equal items always have equal keys and values.
+impl Hash for TestItem {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.key1.hash(state);
+        self.key2.hash(state);
+        self.key3.hash(state);
+        self.value.hash(state);
+    }
+}
+
 #[derive(Clone, Debug, Default, Eq, Ord, PartialEq, PartialOrd)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TestChaos {
@@ -302,7 +315,6 @@ impl IdHashItem for TestItem {
     id_upcast!();
 }
 
-#[cfg(feature = "std")]
 impl IdOrdItem for TestItem {
     // A bit weird to return a reference to a u8, but this makes sure
     // reference-based keys work properly.
@@ -639,7 +651,6 @@ where
     }
 }
 
-#[cfg(feature = "std")]
 impl<T> ItemMap<T> for IdOrdMap<T>
 where
     T: IdOrdItem + Clone,
@@ -874,7 +885,6 @@ impl<'a, T: IdHashItem> IntoRef<'a, T>
     }
 }
 
-#[cfg(feature = "std")]
 impl<'a, T: IdOrdItem> IntoRef<'a, T> for id_ord_map::RefMut<'a, T>
 where
     T::Key<'a>: std::hash::Hash,
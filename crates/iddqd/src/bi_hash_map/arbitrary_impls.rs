@@ -0,0 +1,34 @@
+//! [`Arbitrary`] implementation for [`BiHashMap`], for use with fuzzers.
+
+use super::{BiHashItem, BiHashMap};
+use crate::support::alloc::Allocator;
+use arbitrary::{Arbitrary, Result, Unstructured};
+use core::hash::BuildHasher;
+
+/// Generates a [`BiHashMap`] from a list of arbitrary items.
+///
+/// Items are inserted in order with [`BiHashMap::insert_overwrite`], so an
+/// item that shares any key with earlier items replaces them. Duplicates are
+/// silently collapsed rather than causing generation to fail.
+impl<'a, T, S, A> Arbitrary<'a> for BiHashMap<T, S, A>
+where
+    T: BiHashItem + Arbitrary<'a>,
+    S: Clone + BuildHasher + Default,
+    A: Clone + Allocator + Default,
+{
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let mut map = BiHashMap::with_hasher_in(S::default(), A::default());
+        for item in u.arbitrary_iter::<T>()? {
+            map.insert_overwrite(item?);
+        }
+        Ok(map)
+    }
+
+    fn arbitrary_take_rest(u: Unstructured<'a>) -> Result<Self> {
+        let mut map = BiHashMap::with_hasher_in(S::default(), A::default());
+        for item in u.arbitrary_take_rest_iter::<T>()? {
+            map.insert_overwrite(item?);
+        }
+        Ok(map)
+    }
+}
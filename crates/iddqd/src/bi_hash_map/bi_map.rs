@@ -0,0 +1,80 @@
+use super::BiHashMap;
+use crate::{
+    DefaultHashBuilder,
+    errors::DuplicateItem,
+    support::alloc::{Allocator, Global},
+};
+use core::hash::{BuildHasher, Hash};
+use equivalent::Equivalent;
+
+/// A one-to-one map between two sets of keys.
+///
+/// This is a [`BiHashMap`] whose items are `(K1, K2)` pairs. The helper methods
+/// below cover the common case of mapping, say, IDs to names and back, without
+/// having to define an item type and implement [`BiHashItem`] for it.
+///
+/// All of [`BiHashMap`]'s methods are also available. For example, to remove a
+/// pair, use [`BiHashMap::remove1`] or [`BiHashMap::remove2`]. For items that
+/// carry data beyond the two keys, use a [`BiHashMap`] directly.
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(feature = "default-hasher")] {
+/// use iddqd::BiMap;
+///
+/// let mut map = BiMap::new();
+/// map.insert(1, "alice".to_owned()).unwrap();
+/// map.insert(2, "bob".to_owned()).unwrap();
+///
+/// assert_eq!(map.get_by_left(&1), Some(&"alice".to_owned()));
+/// assert_eq!(map.get_by_right(&"bob".to_owned()), Some(&2));
+///
+/// // Each key can only appear once on its side.
+/// assert!(map.insert(3, "alice".to_owned()).is_err());
+/// # }
+/// ```
+///
+/// [`BiHashItem`]: crate::BiHashItem
+pub type BiMap<K1, K2, S = DefaultHashBuilder, A = Global> =
+    BiHashMap<(K1, K2), S, A>;
+
+impl<K1, K2, S, A> BiHashMap<(K1, K2), S, A>
+where
+    K1: Eq + Hash,
+    K2: Eq + Hash,
+    S: Clone + BuildHasher,
+    A: Allocator,
+{
+    /// Inserts a pair of keys into the map.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if either key is already present. In that case, the
+    /// map isn't changed. To replace the conflicting pairs instead, use
+    /// [`BiHashMap::insert_overwrite`].
+    #[expect(clippy::type_complexity)]
+    pub fn insert(
+        &mut self,
+        k1: K1,
+        k2: K2,
+    ) -> Result<(), DuplicateItem<(K1, K2), &(K1, K2)>> {
+        self.insert_unique((k1, k2))
+    }
+
+    /// Returns the right-hand key associated with the given left-hand key.
+    pub fn get_by_left<'a, Q>(&'a self, k1: &Q) -> Option<&'a K2>
+    where
+        Q: ?Sized + Hash + Equivalent<&'a K1>,
+    {
+        self.get1(k1).map(|(_, k2)| k2)
+    }
+
+    /// Returns the left-hand key associated with the given right-hand key.
+    pub fn get_by_right<'a, Q>(&'a self, k2: &Q) -> Option<&'a K1>
+    where
+        Q: ?Sized + Hash + Equivalent<&'a K2>,
+    {
+        self.get2(k2).map(|(k1, _)| k1)
+    }
+}
@@ -1,20 +1,22 @@
 use super::{
-    Entry, IntoIter, Iter, IterMut, OccupiedEntry, RefMut, VacantEntry,
+    Entry, IntoIter, Iter, IterMut, Key1s, Key2s, OccupiedEntry, RefMut,
+    SortedIter, VacantEntry,
     entry::OccupiedEntryRef,
     entry_indexes::{DisjointKeys, EntryIndexes},
     tables::BiHashMapTables,
 };
 use crate::{
-    BiHashItem, DefaultHashBuilder,
+    BiHashItem, CapacityBreakdown, DefaultHashBuilder, ExtendMode,
+    ImmutableKey,
     bi_hash_map::entry::OccupiedEntryMut,
-    errors::{DuplicateItem, TryReserveError},
+    errors::{DuplicateItem, MapIntegrityError, TryReserveError},
     internal::{ValidateCompact, ValidationError},
     support::{
         ItemIndex,
         alloc::{Allocator, Global, global_alloc},
         borrow::DormantMutRef,
         fmt_utils::StrDisplayAsDebug,
-        hash_table,
+        hash_table::detect_dup_or_insert,
         item_set::ItemSet,
         map_hash::MapHash,
     },
@@ -22,7 +24,7 @@ use crate::{
 use alloc::{collections::BTreeSet, vec::Vec};
 use core::{
     fmt,
-    hash::{BuildHasher, Hash},
+    hash::{BuildHasher, Hash, Hasher},
 };
 use equivalent::Equivalent;
 
@@ -231,6 +233,55 @@ impl<T: BiHashItem> BiHashMap<T> {
             ),
         }
     }
+
+    /// Creates a new `BiHashMap` with the given capacity, returning an error if
+    /// allocation fails.
+    ///
+    /// This is a fallible version of [`BiHashMap::with_capacity`].
+    ///
+    /// # Errors
+    ///
+    /// If the capacity overflows, or the allocator reports a failure, then an
+    /// error is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "default-hasher")] {
+    /// use iddqd::{BiHashItem, BiHashMap, bi_upcast};
+    ///
+    /// #[derive(Debug, PartialEq, Eq)]
+    /// struct Item {
+    ///     id: u32,
+    ///     name: String,
+    ///     value: i32,
+    /// }
+    ///
+    /// impl BiHashItem for Item {
+    ///     type K1<'a> = u32;
+    ///     type K2<'a> = &'a str;
+    ///
+    ///     fn key1(&self) -> Self::K1<'_> {
+    ///         self.id
+    ///     }
+    ///     fn key2(&self) -> Self::K2<'_> {
+    ///         &self.name
+    ///     }
+    ///     bi_upcast!();
+    /// }
+    ///
+    /// let map: BiHashMap<Item> = BiHashMap::try_with_capacity(10)
+    ///     .expect("allocation should succeed");
+    /// assert!(map.capacity() >= 10);
+    /// assert!(map.is_empty());
+    /// # }
+    /// ```
+    pub fn try_with_capacity(capacity: usize) -> Result<Self, TryReserveError> {
+        Self::try_with_capacity_and_hasher(
+            capacity,
+            DefaultHashBuilder::default(),
+        )
+    }
 }
 
 impl<T: BiHashItem, S: BuildHasher> BiHashMap<T, S> {
@@ -317,6 +368,59 @@ impl<T: BiHashItem, S: BuildHasher> BiHashMap<T, S> {
             ),
         }
     }
+
+    /// Creates a new `BiHashMap` with the given capacity and hasher, returning
+    /// an error if allocation fails.
+    ///
+    /// This is a fallible version of [`BiHashMap::with_capacity_and_hasher`].
+    ///
+    /// # Errors
+    ///
+    /// If the capacity overflows, or the allocator reports a failure, then an
+    /// error is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use iddqd::{BiHashItem, BiHashMap, bi_upcast};
+    /// use std::collections::hash_map::RandomState;
+    ///
+    /// #[derive(Debug, PartialEq, Eq)]
+    /// struct Item {
+    ///     id: u32,
+    ///     name: String,
+    ///     value: i32,
+    /// }
+    ///
+    /// impl BiHashItem for Item {
+    ///     type K1<'a> = u32;
+    ///     type K2<'a> = &'a str;
+    ///
+    ///     fn key1(&self) -> Self::K1<'_> {
+    ///         self.id
+    ///     }
+    ///     fn key2(&self) -> Self::K2<'_> {
+    ///         &self.name
+    ///     }
+    ///     bi_upcast!();
+    /// }
+    ///
+    /// let hasher = RandomState::new();
+    /// let map: BiHashMap<Item, _> =
+    ///     BiHashMap::try_with_capacity_and_hasher(10, hasher)
+    ///         .expect("allocation should succeed");
+    /// assert!(map.capacity() >= 10);
+    /// assert!(map.is_empty());
+    /// ```
+    pub fn try_with_capacity_and_hasher(
+        capacity: usize,
+        hasher: S,
+    ) -> Result<Self, TryReserveError> {
+        let mut map = Self::with_hasher(hasher);
+        map.items.try_reserve(capacity)?;
+        map.tables.try_reserve(capacity)?;
+        Ok(map)
+    }
 }
 
 #[cfg(feature = "default-hasher")]
@@ -424,6 +528,64 @@ impl<T: BiHashItem, A: Clone + Allocator> BiHashMap<T, DefaultHashBuilder, A> {
             ),
         }
     }
+
+    /// Creates an empty `BiHashMap` with the specified capacity using the given
+    /// allocator, returning an error if allocation fails.
+    ///
+    /// This is a fallible version of [`BiHashMap::with_capacity_in`].
+    ///
+    /// # Errors
+    ///
+    /// If the capacity overflows, or the allocator reports a failure, then an
+    /// error is returned.
+    ///
+    /// # Examples
+    ///
+    /// Using the [`bumpalo`](https://docs.rs/bumpalo) allocator:
+    ///
+    /// ```
+    /// # #[cfg(all(feature = "default-hasher", feature = "allocator-api2"))] {
+    /// use iddqd::{BiHashItem, BiHashMap, bi_upcast};
+    /// # use iddqd_test_utils::bumpalo;
+    ///
+    /// #[derive(Debug, PartialEq, Eq)]
+    /// struct Item {
+    ///     id: u32,
+    ///     name: String,
+    ///     value: i32,
+    /// }
+    ///
+    /// impl BiHashItem for Item {
+    ///     type K1<'a> = u32;
+    ///     type K2<'a> = &'a str;
+    ///
+    ///     fn key1(&self) -> Self::K1<'_> {
+    ///         self.id
+    ///     }
+    ///     fn key2(&self) -> Self::K2<'_> {
+    ///         &self.name
+    ///     }
+    ///     bi_upcast!();
+    /// }
+    ///
+    /// let bump = bumpalo::Bump::new();
+    /// let map: BiHashMap<Item, _, &bumpalo::Bump> =
+    ///     BiHashMap::try_with_capacity_in(10, &bump)
+    ///         .expect("allocation should succeed");
+    /// assert!(map.capacity() >= 10);
+    /// assert!(map.is_empty());
+    /// # }
+    /// ```
+    pub fn try_with_capacity_in(
+        capacity: usize,
+        alloc: A,
+    ) -> Result<Self, TryReserveError> {
+        Self::try_with_capacity_and_hasher_in(
+            capacity,
+            DefaultHashBuilder::default(),
+            alloc,
+        )
+    }
 }
 
 impl<T: BiHashItem, S: Clone + BuildHasher, A: Clone + Allocator>
@@ -538,6 +700,67 @@ impl<T: BiHashItem, S: Clone + BuildHasher, A: Clone + Allocator>
             ),
         }
     }
+
+    /// Creates a new, empty `BiHashMap` with the given capacity, hasher, and
+    /// allocator, returning an error if allocation fails.
+    ///
+    /// This is a fallible version of
+    /// [`BiHashMap::with_capacity_and_hasher_in`].
+    ///
+    /// # Errors
+    ///
+    /// If the capacity overflows, or the allocator reports a failure, then an
+    /// error is returned.
+    ///
+    /// # Examples
+    ///
+    /// Using the [`bumpalo`](https://docs.rs/bumpalo) allocator:
+    ///
+    /// ```
+    /// # #[cfg(feature = "allocator-api2")] {
+    /// use iddqd::{BiHashItem, BiHashMap, bi_upcast};
+    /// use std::collections::hash_map::RandomState;
+    /// # use iddqd_test_utils::bumpalo;
+    ///
+    /// #[derive(Debug, PartialEq, Eq)]
+    /// struct Item {
+    ///     id: u32,
+    ///     name: String,
+    ///     value: i32,
+    /// }
+    ///
+    /// impl BiHashItem for Item {
+    ///     type K1<'a> = u32;
+    ///     type K2<'a> = &'a str;
+    ///
+    ///     fn key1(&self) -> Self::K1<'_> {
+    ///         self.id
+    ///     }
+    ///     fn key2(&self) -> Self::K2<'_> {
+    ///         &self.name
+    ///     }
+    ///     bi_upcast!();
+    /// }
+    ///
+    /// let bump = bumpalo::Bump::new();
+    /// let hasher = RandomState::new();
+    /// let map: BiHashMap<Item, _, &bumpalo::Bump> =
+    ///     BiHashMap::try_with_capacity_and_hasher_in(10, hasher, &bump)
+    ///         .expect("allocation should succeed");
+    /// assert!(map.capacity() >= 10);
+    /// assert!(map.is_empty());
+    /// # }
+    /// ```
+    pub fn try_with_capacity_and_hasher_in(
+        capacity: usize,
+        hasher: S,
+        alloc: A,
+    ) -> Result<Self, TryReserveError> {
+        let mut map = Self::with_hasher_in(hasher, alloc);
+        map.items.try_reserve(capacity)?;
+        map.tables.try_reserve(capacity)?;
+        Ok(map)
+    }
 }
 
 impl<T: BiHashItem, S: Default + Clone + BuildHasher, A: Allocator + Default>
@@ -595,7 +818,10 @@ impl<T: BiHashItem, S: Default + Clone + BuildHasher, A: Allocator + Default>
     ///     Item { id: 1, name: "foo".to_string(), value: 42 },
     ///     Item { id: 1, name: "baz".to_string(), value: 99 },
     /// ];
-    /// assert!(BiHashMap::<Item>::from_iter_unique(duplicate_items).is_err());
+    /// let err = BiHashMap::<Item>::from_iter_unique(duplicate_items).unwrap_err();
+    /// assert_eq!(err.new_item().name, "baz");
+    /// assert_eq!(err.duplicates().len(), 1);
+    /// assert_eq!(err.duplicates()[0].name, "foo");
     /// # }
     /// ```
     pub fn from_iter_unique<I: IntoIterator<Item = T>>(
@@ -719,6 +945,88 @@ impl<T: BiHashItem, S: Clone + BuildHasher, A: Allocator> BiHashMap<T, S, A> {
         self.items.capacity()
     }
 
+    /// Returns a breakdown of the map's internal sizing, for debugging.
+    ///
+    /// This reports the length and capacity of the item storage, and the
+    /// capacity of each key table. It doesn't visit any items, so it's much
+    /// cheaper than [`Self::validate`].
+    pub fn debug_capacity_breakdown(&self) -> CapacityBreakdown {
+        CapacityBreakdown {
+            items_len: self.items.len(),
+            items_capacity: self.items.capacity(),
+            table_capacities: vec![
+                self.tables.k1_to_item.capacity(),
+                self.tables.k2_to_item.capacity(),
+            ],
+        }
+    }
+
+    /// Returns the number of items in the map, along with the number of items
+    /// the map can hold before one of its key tables needs to reallocate.
+    ///
+    /// The capacity is the smallest of the key tables' capacities. To see each
+    /// key table individually, use [`Self::occupancy1`] and
+    /// [`Self::occupancy2`].
+    ///
+    /// This is useful for tuning the capacity passed to `with_capacity` when
+    /// building other maps of a similar size.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "default-hasher")] {
+    /// use iddqd::{BiHashItem, BiHashMap, bi_upcast};
+    ///
+    /// #[derive(Debug, PartialEq, Eq)]
+    /// struct Item {
+    ///     id: u32,
+    ///     name: String,
+    /// }
+    ///
+    /// impl BiHashItem for Item {
+    ///     type K1<'a> = u32;
+    ///     type K2<'a> = &'a str;
+    ///
+    ///     fn key1(&self) -> Self::K1<'_> {
+    ///         self.id
+    ///     }
+    ///     fn key2(&self) -> Self::K2<'_> {
+    ///         &self.name
+    ///     }
+    ///     bi_upcast!();
+    /// }
+    ///
+    /// let mut map: BiHashMap<Item> = BiHashMap::with_capacity(10);
+    /// map.insert_unique(Item { id: 1, name: "foo".to_string() }).unwrap();
+    ///
+    /// let (len, capacity) = map.occupancy();
+    /// assert_eq!(len, 1);
+    /// assert!(capacity >= 10);
+    /// assert!(map.occupancy1().1 >= capacity);
+    /// assert!(map.occupancy2().1 >= capacity);
+    /// # }
+    /// ```
+    pub fn occupancy(&self) -> (usize, usize) {
+        let capacity = self
+            .tables
+            .k1_to_item
+            .capacity()
+            .min(self.tables.k2_to_item.capacity());
+        (self.items.len(), capacity)
+    }
+
+    /// Returns the number of items in the map, along with the number of items
+    /// the first key table can hold without reallocating.
+    pub fn occupancy1(&self) -> (usize, usize) {
+        (self.items.len(), self.tables.k1_to_item.capacity())
+    }
+
+    /// Returns the number of items in the map, along with the number of items
+    /// the second key table can hold without reallocating.
+    pub fn occupancy2(&self) -> (usize, usize) {
+        (self.items.len(), self.tables.k2_to_item.capacity())
+    }
+
     /// Returns true if the map contains no items.
     ///
     /// # Examples
@@ -952,17 +1260,9 @@ impl<T: BiHashItem, S: Clone + BuildHasher, A: Allocator> BiHashMap<T, S, A> {
     pub fn try_reserve(
         &mut self,
         additional: usize,
-    ) -> Result<(), crate::errors::TryReserveError> {
+    ) -> Result<(), TryReserveError> {
         self.items.try_reserve(additional)?;
-        self.tables
-            .k1_to_item
-            .try_reserve(additional)
-            .map_err(crate::errors::TryReserveError::from_hashbrown)?;
-        self.tables
-            .k2_to_item
-            .try_reserve(additional)
-            .map_err(crate::errors::TryReserveError::from_hashbrown)?;
-        Ok(())
+        self.tables.try_reserve(additional)
     }
 
     /// Shrinks the capacity of the map as much as possible. It will drop
@@ -1175,32 +1475,382 @@ impl<T: BiHashItem, S: Clone + BuildHasher, A: Allocator> BiHashMap<T, S, A> {
         IterMut::new(&self.tables, &mut self.items)
     }
 
-    /// Checks general invariants of the map.
+    /// Iterates over the first keys in the map.
     ///
-    /// The code below always upholds these invariants, but it's useful to have
-    /// an explicit check for tests.
-    #[doc(hidden)]
-    pub fn validate(
-        &self,
-        compactness: ValidateCompact,
-    ) -> Result<(), ValidationError>
-    where
-        T: fmt::Debug,
-    {
-        self.validate_structural(compactness)?;
-
-        // Check that the indexes are all correct.
-        //
-        // Unlike the structural checks, this re-looks up each key through the
-        // user `Hash`, so it only holds when that `Hash` is lawful.
-        for (ix, item) in self.items.iter() {
-            let key1 = item.key1();
-            let key2 = item.key2();
-
-            let Some(ix1) = self.find1_index(&key1) else {
-                return Err(ValidationError::general(format!(
-                    "item at index {ix} has no key1 index"
-                )));
+    /// Similar to [`HashMap`], the iteration order is arbitrary and not
+    /// guaranteed to be stable.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "default-hasher")] {
+    /// use iddqd::{BiHashItem, BiHashMap, bi_upcast};
+    ///
+    /// #[derive(Debug, PartialEq, Eq)]
+    /// struct Item {
+    ///     id: u32,
+    ///     name: String,
+    ///     value: i32,
+    /// }
+    ///
+    /// impl BiHashItem for Item {
+    ///     type K1<'a> = u32;
+    ///     type K2<'a> = &'a str;
+    ///
+    ///     fn key1(&self) -> Self::K1<'_> {
+    ///         self.id
+    ///     }
+    ///     fn key2(&self) -> Self::K2<'_> {
+    ///         &self.name
+    ///     }
+    ///     bi_upcast!();
+    /// }
+    ///
+    /// let mut map = BiHashMap::new();
+    /// map.insert_unique(Item { id: 1, name: "foo".to_string(), value: 42 })
+    ///     .unwrap();
+    /// map.insert_unique(Item { id: 2, name: "bar".to_string(), value: 99 })
+    ///     .unwrap();
+    ///
+    /// let mut keys: Vec<u32> = map.key1s().collect();
+    /// keys.sort();
+    /// assert_eq!(keys, [1, 2]);
+    /// # }
+    /// ```
+    ///
+    /// [`HashMap`]: std::collections::HashMap
+    #[inline]
+    pub fn key1s(&self) -> Key1s<'_, T> {
+        Key1s::new(self.iter())
+    }
+
+    /// Iterates over the second keys in the map.
+    ///
+    /// Similar to [`HashMap`], the iteration order is arbitrary and not
+    /// guaranteed to be stable.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "default-hasher")] {
+    /// use iddqd::{BiHashItem, BiHashMap, bi_upcast};
+    ///
+    /// #[derive(Debug, PartialEq, Eq)]
+    /// struct Item {
+    ///     id: u32,
+    ///     name: String,
+    ///     value: i32,
+    /// }
+    ///
+    /// impl BiHashItem for Item {
+    ///     type K1<'a> = u32;
+    ///     type K2<'a> = &'a str;
+    ///
+    ///     fn key1(&self) -> Self::K1<'_> {
+    ///         self.id
+    ///     }
+    ///     fn key2(&self) -> Self::K2<'_> {
+    ///         &self.name
+    ///     }
+    ///     bi_upcast!();
+    /// }
+    ///
+    /// let mut map = BiHashMap::new();
+    /// map.insert_unique(Item { id: 1, name: "foo".to_string(), value: 42 })
+    ///     .unwrap();
+    /// map.insert_unique(Item { id: 2, name: "bar".to_string(), value: 99 })
+    ///     .unwrap();
+    ///
+    /// let mut keys: Vec<&str> = map.key2s().collect();
+    /// keys.sort();
+    /// assert_eq!(keys, ["bar", "foo"]);
+    /// # }
+    /// ```
+    ///
+    /// [`HashMap`]: std::collections::HashMap
+    #[inline]
+    pub fn key2s(&self) -> Key2s<'_, T> {
+        Key2s::new(self.iter())
+    }
+
+    /// Iterates over the items in the map, in order of their key1s.
+    ///
+    /// This is useful for deterministic output, such as in logs or snapshot
+    /// tests. Each call collects references to the items (not the items
+    /// themselves) and sorts them, which takes *O*(*n* log *n*) time and
+    /// *O*(*n*) additional space.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "default-hasher")] {
+    /// use iddqd::{BiHashItem, BiHashMap, bi_upcast};
+    ///
+    /// #[derive(Debug, PartialEq, Eq)]
+    /// struct Item {
+    ///     id: u32,
+    ///     name: String,
+    ///     value: i32,
+    /// }
+    ///
+    /// impl BiHashItem for Item {
+    ///     type K1<'a> = u32;
+    ///     type K2<'a> = &'a str;
+    ///
+    ///     fn key1(&self) -> Self::K1<'_> {
+    ///         self.id
+    ///     }
+    ///     fn key2(&self) -> Self::K2<'_> {
+    ///         &self.name
+    ///     }
+    ///     bi_upcast!();
+    /// }
+    ///
+    /// let mut map = BiHashMap::new();
+    /// map.insert_unique(Item { id: 1, name: "foo".to_string(), value: 42 })
+    ///     .unwrap();
+    /// map.insert_unique(Item { id: 2, name: "bar".to_string(), value: 99 })
+    ///     .unwrap();
+    /// map.insert_unique(Item { id: 3, name: "baz".to_string(), value: 7 })
+    ///     .unwrap();
+    ///
+    /// let values: Vec<i32> =
+    ///     map.iter_sorted_by_key1().map(|item| item.value).collect();
+    /// assert_eq!(values, [42, 99, 7]);
+    /// # }
+    /// ```
+    pub fn iter_sorted_by_key1<'a>(&'a self) -> SortedIter<'a, T>
+    where
+        T::K1<'a>: Ord,
+    {
+        let mut sorted: Vec<&'a T> = self.items.values().collect();
+        // Keys are unique, so an unstable sort is deterministic.
+        sorted.sort_unstable_by(|&a, &b| a.key1().cmp(&b.key1()));
+        SortedIter::new(sorted)
+    }
+
+    /// Iterates over the items in the map, in order of their key2s.
+    ///
+    /// This is useful for deterministic output, such as in logs or snapshot
+    /// tests. Each call collects references to the items (not the items
+    /// themselves) and sorts them, which takes *O*(*n* log *n*) time and
+    /// *O*(*n*) additional space.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "default-hasher")] {
+    /// use iddqd::{BiHashItem, BiHashMap, bi_upcast};
+    ///
+    /// #[derive(Debug, PartialEq, Eq)]
+    /// struct Item {
+    ///     id: u32,
+    ///     name: String,
+    ///     value: i32,
+    /// }
+    ///
+    /// impl BiHashItem for Item {
+    ///     type K1<'a> = u32;
+    ///     type K2<'a> = &'a str;
+    ///
+    ///     fn key1(&self) -> Self::K1<'_> {
+    ///         self.id
+    ///     }
+    ///     fn key2(&self) -> Self::K2<'_> {
+    ///         &self.name
+    ///     }
+    ///     bi_upcast!();
+    /// }
+    ///
+    /// let mut map = BiHashMap::new();
+    /// map.insert_unique(Item { id: 1, name: "foo".to_string(), value: 42 })
+    ///     .unwrap();
+    /// map.insert_unique(Item { id: 2, name: "bar".to_string(), value: 99 })
+    ///     .unwrap();
+    /// map.insert_unique(Item { id: 3, name: "baz".to_string(), value: 7 })
+    ///     .unwrap();
+    ///
+    /// let values: Vec<i32> =
+    ///     map.iter_sorted_by_key2().map(|item| item.value).collect();
+    /// assert_eq!(values, [99, 7, 42]);
+    /// # }
+    /// ```
+    pub fn iter_sorted_by_key2<'a>(&'a self) -> SortedIter<'a, T>
+    where
+        T::K2<'a>: Ord,
+    {
+        let mut sorted: Vec<&'a T> = self.items.values().collect();
+        // Keys are unique, so an unstable sort is deterministic.
+        sorted.sort_unstable_by(|&a, &b| a.key2().cmp(&b.key2()));
+        SortedIter::new(sorted)
+    }
+
+    /// Iterates over the items in the map.
+    ///
+    /// This is the same as [`Self::iter`], and is provided for symmetry with
+    /// [`HashMap::values`].
+    ///
+    /// [`HashMap::values`]: std::collections::HashMap::values
+    #[inline]
+    pub fn values(&self) -> Iter<'_, T> {
+        self.iter()
+    }
+
+    /// Iterates over the items in the map, allowing for mutation.
+    ///
+    /// This is the same as [`Self::iter_mut`], and is provided for symmetry with
+    /// [`HashMap::values_mut`].
+    ///
+    /// [`HashMap::values_mut`]: std::collections::HashMap::values_mut
+    #[inline]
+    pub fn values_mut(&mut self) -> IterMut<'_, T, S, A> {
+        self.iter_mut()
+    }
+
+    /// Consumes the map, returning its items as a [`Vec`].
+    ///
+    /// Similar to [`HashMap`], the order of the items is arbitrary. This is
+    /// equivalent to `map.into_iter().collect::<Vec<_>>()`, and is provided to
+    /// make it explicit at the call site that the order doesn't matter.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "default-hasher")] {
+    /// use iddqd::{BiHashItem, BiHashMap, bi_upcast};
+    ///
+    /// #[derive(Debug, PartialEq, Eq)]
+    /// struct Item {
+    ///     id: u32,
+    ///     name: &'static str,
+    /// }
+    ///
+    /// impl BiHashItem for Item {
+    ///     type K1<'a> = u32;
+    ///     type K2<'a> = &'a str;
+    ///
+    ///     fn key1(&self) -> Self::K1<'_> {
+    ///         self.id
+    ///     }
+    ///     fn key2(&self) -> Self::K2<'_> {
+    ///         self.name
+    ///     }
+    ///     bi_upcast!();
+    /// }
+    ///
+    /// let mut map = BiHashMap::new();
+    /// map.insert_unique(Item { id: 2, name: "bar" }).unwrap();
+    /// map.insert_unique(Item { id: 1, name: "foo" }).unwrap();
+    ///
+    /// let mut items = map.into_vec();
+    /// items.sort_unstable_by_key(|item| item.id);
+    /// assert_eq!(
+    ///     items,
+    ///     vec![Item { id: 1, name: "foo" }, Item { id: 2, name: "bar" }],
+    /// );
+    /// # }
+    /// ```
+    ///
+    /// [`HashMap`]: std::collections::HashMap
+    pub fn into_vec(self) -> Vec<T> {
+        self.into_iter().collect()
+    }
+
+    /// Checks that the map's internal invariants hold.
+    ///
+    /// See [`MapIntegrityError`] for what this checks, and when it can fail.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "default-hasher")] {
+    /// use iddqd::{BiHashItem, BiHashMap, bi_upcast};
+    ///
+    /// #[derive(Debug, PartialEq, Eq)]
+    /// struct Item {
+    ///     id: u32,
+    ///     name: String,
+    ///     value: i32,
+    /// }
+    ///
+    /// impl BiHashItem for Item {
+    ///     type K1<'a> = u32;
+    ///     type K2<'a> = &'a str;
+    ///
+    ///     fn key1(&self) -> Self::K1<'_> {
+    ///         self.id
+    ///     }
+    ///     fn key2(&self) -> Self::K2<'_> {
+    ///         &self.name
+    ///     }
+    ///     bi_upcast!();
+    /// }
+    ///
+    /// let mut map = BiHashMap::new();
+    /// map.insert_unique(Item { id: 1, name: "foo".to_string(), value: 42 })
+    ///     .unwrap();
+    /// map.insert_unique(Item { id: 2, name: "bar".to_string(), value: 99 })
+    ///     .unwrap();
+    /// map.remove1(&1);
+    ///
+    /// assert_eq!(map.check_invariants(), Ok(()));
+    /// # }
+    /// ```
+    pub fn check_invariants(&self) -> Result<(), MapIntegrityError> {
+        MapIntegrityError::check_storage(&self.items)?;
+        MapIntegrityError::check_table(
+            "key1",
+            &self.items,
+            self.tables.k1_to_item.iter_hashed().map(|(_, ix)| ix),
+        )?;
+        MapIntegrityError::check_table(
+            "key2",
+            &self.items,
+            self.tables.k2_to_item.iter_hashed().map(|(_, ix)| ix),
+        )?;
+
+        for (ix, item) in self.items.iter() {
+            MapIntegrityError::check_lookup(
+                "key1",
+                ix,
+                self.find1_index(&item.key1()),
+            )?;
+            MapIntegrityError::check_lookup(
+                "key2",
+                ix,
+                self.find2_index(&item.key2()),
+            )?;
+        }
+
+        Ok(())
+    }
+
+    /// Checks general invariants of the map.
+    ///
+    /// The code below always upholds these invariants, but it's useful to have
+    /// an explicit check for tests.
+    #[doc(hidden)]
+    pub fn validate(
+        &self,
+        compactness: ValidateCompact,
+    ) -> Result<(), ValidationError>
+    where
+        T: fmt::Debug,
+    {
+        self.validate_structural(compactness)?;
+
+        // Check that the indexes are all correct.
+        //
+        // Unlike the structural checks, this re-looks up each key through the
+        // user `Hash`, so it only holds when that `Hash` is lawful.
+        for (ix, item) in self.items.iter() {
+            let key1 = item.key1();
+            let key2 = item.key2();
+
+            let Some(ix1) = self.find1_index(&key1) else {
+                return Err(ValidationError::general(format!(
+                    "item at index {ix} has no key1 index"
+                )));
             };
             let Some(ix2) = self.find2_index(&key2) else {
                 return Err(ValidationError::general(format!(
@@ -1284,28 +1934,294 @@ impl<T: BiHashItem, S: Clone + BuildHasher, A: Allocator> BiHashMap<T, S, A> {
     /// assert_eq!(removed[0].name, "foo");
     /// assert_eq!(removed[0].value, 42);
     ///
-    /// assert_eq!(map.len(), 2);
-    /// assert_eq!(map.get1(&1).unwrap().name, "baz");
+    /// assert_eq!(map.len(), 2);
+    /// assert_eq!(map.get1(&1).unwrap().name, "baz");
+    /// # }
+    /// ```
+    #[doc(alias = "insert")]
+    pub fn insert_overwrite(&mut self, value: T) -> Vec<T> {
+        let prepared = self.prepare_insert_overwrite(&value);
+
+        let mut duplicates = Vec::with_capacity(prepared.duplicate_count());
+
+        self.try_reserve_insert_overwrite_commit(
+            prepared.needs_new_item_slot(),
+        )
+        .expect("reserved space successfully");
+
+        self.commit_insert_overwrite(value, prepared, &mut duplicates);
+
+        duplicates
+    }
+
+    /// Inserts a value into the set, returning an error if any duplicates were
+    /// added.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "default-hasher")] {
+    /// use iddqd::{BiHashItem, BiHashMap, bi_upcast};
+    ///
+    /// #[derive(Debug, PartialEq, Eq)]
+    /// struct Item {
+    ///     id: u32,
+    ///     name: String,
+    ///     value: i32,
+    /// }
+    ///
+    /// impl BiHashItem for Item {
+    ///     type K1<'a> = u32;
+    ///     type K2<'a> = &'a str;
+    ///
+    ///     fn key1(&self) -> Self::K1<'_> {
+    ///         self.id
+    ///     }
+    ///     fn key2(&self) -> Self::K2<'_> {
+    ///         &self.name
+    ///     }
+    ///     bi_upcast!();
+    /// }
+    ///
+    /// let mut map = BiHashMap::new();
+    ///
+    /// // Successful insertion
+    /// assert!(
+    ///     map.insert_unique(Item { id: 1, name: "foo".to_string(), value: 42 })
+    ///         .is_ok()
+    /// );
+    /// assert!(
+    ///     map.insert_unique(Item { id: 2, name: "bar".to_string(), value: 99 })
+    ///         .is_ok()
+    /// );
+    ///
+    /// // Duplicate key1
+    /// assert!(
+    ///     map.insert_unique(Item { id: 1, name: "baz".to_string(), value: 100 })
+    ///         .is_err()
+    /// );
+    ///
+    /// // Duplicate key2
+    /// assert!(
+    ///     map.insert_unique(Item { id: 3, name: "foo".to_string(), value: 200 })
+    ///         .is_err()
+    /// );
+    /// # }
+    /// ```
+    pub fn insert_unique(
+        &mut self,
+        value: T,
+    ) -> Result<(), DuplicateItem<T, &T>> {
+        let _ = self.insert_unique_impl(value)?;
+        Ok(())
+    }
+
+    /// Extends the map with the contents of an iterator, handling duplicates
+    /// according to `mode`.
+    ///
+    /// With [`ExtendMode::Overwrite`], this behaves like calling
+    /// [`BiHashMap::insert_overwrite`] for each item, and never returns an
+    /// error. This is what the [`Extend`] implementation does.
+    ///
+    /// With [`ExtendMode::Unique`], this behaves like calling
+    /// [`BiHashMap::insert_unique`] for each item, stopping at the first item
+    /// that conflicts with an existing one. Items before the conflicting one
+    /// remain in the map, and the rest of the iterator is dropped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "default-hasher")] {
+    /// use iddqd::{BiHashItem, BiHashMap, ExtendMode, bi_upcast};
+    ///
+    /// #[derive(Debug, PartialEq, Eq)]
+    /// struct Item {
+    ///     id: u32,
+    ///     name: String,
+    ///     value: i32,
+    /// }
+    ///
+    /// impl BiHashItem for Item {
+    ///     type K1<'a> = u32;
+    ///     type K2<'a> = &'a str;
+    ///
+    ///     fn key1(&self) -> Self::K1<'_> {
+    ///         self.id
+    ///     }
+    ///     fn key2(&self) -> Self::K2<'_> {
+    ///         &self.name
+    ///     }
+    ///     bi_upcast!();
+    /// }
+    ///
+    /// let mut map = BiHashMap::new();
+    /// map.insert_unique(Item { id: 1, name: "foo".to_string(), value: 42 })
+    ///     .unwrap();
+    ///
+    /// // Unique mode stops at the first item that conflicts on either key.
+    /// let new_items = vec![
+    ///     Item { id: 2, name: "bar".to_string(), value: 20 },
+    ///     Item { id: 3, name: "foo".to_string(), value: 100 },
+    ///     Item { id: 4, name: "baz".to_string(), value: 30 },
+    /// ];
+    /// let err = map.extend_with(new_items, ExtendMode::Unique).unwrap_err();
+    /// assert_eq!(err.new_item().value, 100);
+    /// assert_eq!(err.duplicates()[0].value, 42);
+    /// assert_eq!(map.len(), 2);
+    /// assert!(map.contains_key1(&2));
+    /// assert!(!map.contains_key1(&4));
+    ///
+    /// // Overwrite mode replaces duplicates.
+    /// let new_items = vec![Item { id: 3, name: "foo".to_string(), value: 100 }];
+    /// map.extend_with(new_items, ExtendMode::Overwrite).unwrap();
+    /// assert_eq!(map.get2("foo").unwrap().id, 3);
+    /// assert!(!map.contains_key1(&1));
+    /// # }
+    /// ```
+    pub fn extend_with<I: IntoIterator<Item = T>>(
+        &mut self,
+        iter: I,
+        mode: ExtendMode,
+    ) -> Result<(), DuplicateItem<T, &T>> {
+        // Keys may already be present in the map, or multiple times in the
+        // iterator. Reserve the entire hint lower bound if the map is empty.
+        // Otherwise reserve half the hint (rounded up), so the map will only
+        // resize twice in the worst case.
+        let iter = iter.into_iter();
+        let reserve = if self.is_empty() {
+            iter.size_hint().0
+        } else {
+            iter.size_hint().0.div_ceil(2)
+        };
+        self.reserve(reserve);
+        match mode {
+            ExtendMode::Overwrite => {
+                for item in iter {
+                    self.insert_overwrite(item);
+                }
+            }
+            ExtendMode::Unique => {
+                for item in iter {
+                    if let Err((item, duplicates)) =
+                        self.insert_unique_or_dup_indexes(item)
+                    {
+                        return Err(DuplicateItem::__internal_new(
+                            item,
+                            duplicates
+                                .iter()
+                                .map(|ix| &self.items[*ix])
+                                .collect(),
+                        ));
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns true if the map contains a single item that matches both `key1` and `key2`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "default-hasher")] {
+    /// use iddqd::{BiHashItem, BiHashMap, bi_upcast};
+    ///
+    /// #[derive(Debug, PartialEq, Eq)]
+    /// struct Item {
+    ///     id: u32,
+    ///     name: String,
+    ///     value: i32,
+    /// }
+    ///
+    /// impl BiHashItem for Item {
+    ///     type K1<'a> = u32;
+    ///     type K2<'a> = &'a str;
+    ///
+    ///     fn key1(&self) -> Self::K1<'_> {
+    ///         self.id
+    ///     }
+    ///     fn key2(&self) -> Self::K2<'_> {
+    ///         &self.name
+    ///     }
+    ///     bi_upcast!();
+    /// }
+    ///
+    /// let mut map = BiHashMap::new();
+    /// map.insert_unique(Item { id: 1, name: "foo".to_string(), value: 42 }).unwrap();
+    /// map.insert_unique(Item { id: 2, name: "bar".to_string(), value: 99 }).unwrap();
+    ///
+    /// assert!(map.contains_key_unique(&1, &"foo"));
+    /// assert!(map.contains_key_unique(&2, &"bar"));
+    /// assert!(!map.contains_key_unique(&1, &"bar")); // key1 exists but key2 doesn't match
+    /// assert!(!map.contains_key_unique(&3, &"baz")); // neither key exists
+    /// # }
+    /// ```
+    pub fn contains_key_unique<'a, Q1, Q2>(
+        &'a self,
+        key1: &Q1,
+        key2: &Q2,
+    ) -> bool
+    where
+        Q1: Hash + Equivalent<T::K1<'a>> + ?Sized,
+        Q2: Hash + Equivalent<T::K2<'a>> + ?Sized,
+    {
+        self.get_unique(key1, key2).is_some()
+    }
+
+    /// Returns true if the map contains an item that matches `key1`, or an
+    /// item that matches `key2`.
+    ///
+    /// Unlike [`contains_key_unique`](Self::contains_key_unique), the two keys
+    /// don't have to belong to the same item. This is useful for checking
+    /// whether [`insert_unique`](Self::insert_unique) would fail.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "default-hasher")] {
+    /// use iddqd::{BiHashItem, BiHashMap, bi_upcast};
+    ///
+    /// #[derive(Debug, PartialEq, Eq)]
+    /// struct Item {
+    ///     id: u32,
+    ///     name: String,
+    ///     value: i32,
+    /// }
+    ///
+    /// impl BiHashItem for Item {
+    ///     type K1<'a> = u32;
+    ///     type K2<'a> = &'a str;
+    ///
+    ///     fn key1(&self) -> Self::K1<'_> {
+    ///         self.id
+    ///     }
+    ///     fn key2(&self) -> Self::K2<'_> {
+    ///         &self.name
+    ///     }
+    ///     bi_upcast!();
+    /// }
+    ///
+    /// let mut map = BiHashMap::new();
+    /// map.insert_unique(Item { id: 1, name: "foo".to_string(), value: 42 }).unwrap();
+    /// map.insert_unique(Item { id: 2, name: "bar".to_string(), value: 99 }).unwrap();
+    ///
+    /// assert!(map.contains_either(&1, &"foo"));
+    /// assert!(map.contains_either(&1, &"bar")); // keys from different items
+    /// assert!(map.contains_either(&3, &"bar")); // only key2 exists
+    /// assert!(!map.contains_either(&3, &"baz")); // neither key exists
     /// # }
     /// ```
-    #[doc(alias = "insert")]
-    pub fn insert_overwrite(&mut self, value: T) -> Vec<T> {
-        let prepared = self.prepare_insert_overwrite(&value);
-
-        let mut duplicates = Vec::with_capacity(prepared.duplicate_count());
-
-        self.try_reserve_insert_overwrite_commit(
-            prepared.needs_new_item_slot(),
-        )
-        .expect("reserved space successfully");
-
-        self.commit_insert_overwrite(value, prepared, &mut duplicates);
-
-        duplicates
+    pub fn contains_either<'a, Q1, Q2>(&'a self, key1: &Q1, key2: &Q2) -> bool
+    where
+        Q1: Hash + Equivalent<T::K1<'a>> + ?Sized,
+        Q2: Hash + Equivalent<T::K2<'a>> + ?Sized,
+    {
+        self.contains_key1(key1) || self.contains_key2(key2)
     }
 
-    /// Inserts a value into the set, returning an error if any duplicates were
-    /// added.
+    /// Gets a reference to the unique item associated with the given `key1` and
+    /// `key2`, if it exists.
     ///
     /// # Examples
     ///
@@ -1334,39 +2250,38 @@ impl<T: BiHashItem, S: Clone + BuildHasher, A: Allocator> BiHashMap<T, S, A> {
     /// }
     ///
     /// let mut map = BiHashMap::new();
+    /// map.insert_unique(Item { id: 1, name: "foo".to_string(), value: 42 }).unwrap();
+    /// map.insert_unique(Item { id: 2, name: "bar".to_string(), value: 99 }).unwrap();
     ///
-    /// // Successful insertion
-    /// assert!(
-    ///     map.insert_unique(Item { id: 1, name: "foo".to_string(), value: 42 })
-    ///         .is_ok()
-    /// );
-    /// assert!(
-    ///     map.insert_unique(Item { id: 2, name: "bar".to_string(), value: 99 })
-    ///         .is_ok()
-    /// );
-    ///
-    /// // Duplicate key1
-    /// assert!(
-    ///     map.insert_unique(Item { id: 1, name: "baz".to_string(), value: 100 })
-    ///         .is_err()
-    /// );
-    ///
-    /// // Duplicate key2
-    /// assert!(
-    ///     map.insert_unique(Item { id: 3, name: "foo".to_string(), value: 200 })
-    ///         .is_err()
-    /// );
+    /// assert_eq!(map.get_unique(&1, &"foo").unwrap().value, 42);
+    /// assert_eq!(map.get_unique(&2, &"bar").unwrap().value, 99);
+    /// assert!(map.get_unique(&1, &"bar").is_none()); // key1 exists but key2 doesn't match
+    /// assert!(map.get_unique(&3, &"baz").is_none()); // neither key exists
     /// # }
     /// ```
-    pub fn insert_unique(
-        &mut self,
-        value: T,
-    ) -> Result<(), DuplicateItem<T, &T>> {
-        let _ = self.insert_unique_impl(value)?;
-        Ok(())
+    pub fn get_unique<'a, Q1, Q2>(
+        &'a self,
+        key1: &Q1,
+        key2: &Q2,
+    ) -> Option<&'a T>
+    where
+        Q1: Hash + Equivalent<T::K1<'a>> + ?Sized,
+        Q2: Hash + Equivalent<T::K2<'a>> + ?Sized,
+    {
+        let index = self.find1_index(key1)?;
+        let item = &self.items[index];
+        if key2.equivalent(&item.key2()) { Some(item) } else { None }
     }
 
-    /// Returns true if the map contains a single item that matches both `key1` and `key2`.
+    /// Gets references to the items associated with `key1` and `key2`
+    /// separately.
+    ///
+    /// The first element of the returned tuple is the item matching `key1`,
+    /// and the second is the item matching `key2`. If a single item matches
+    /// both keys, it is returned in both positions.
+    ///
+    /// This is useful for reporting which existing items would conflict with
+    /// a new item before calling [`Self::insert_unique`].
     ///
     /// # Examples
     ///
@@ -1395,29 +2310,44 @@ impl<T: BiHashItem, S: Clone + BuildHasher, A: Allocator> BiHashMap<T, S, A> {
     /// }
     ///
     /// let mut map = BiHashMap::new();
-    /// map.insert_unique(Item { id: 1, name: "foo".to_string(), value: 42 }).unwrap();
-    /// map.insert_unique(Item { id: 2, name: "bar".to_string(), value: 99 }).unwrap();
+    /// map.insert_unique(Item { id: 1, name: "foo".to_string(), value: 42 })
+    ///     .unwrap();
+    /// map.insert_unique(Item { id: 2, name: "bar".to_string(), value: 99 })
+    ///     .unwrap();
     ///
-    /// assert!(map.contains_key_unique(&1, &"foo"));
-    /// assert!(map.contains_key_unique(&2, &"bar"));
-    /// assert!(!map.contains_key_unique(&1, &"bar")); // key1 exists but key2 doesn't match
-    /// assert!(!map.contains_key_unique(&3, &"baz")); // neither key exists
+    /// // key1 and key2 match different items.
+    /// let (by_key1, by_key2) = map.get_conflicts(&1, &"bar");
+    /// assert_eq!(by_key1.unwrap().value, 42);
+    /// assert_eq!(by_key2.unwrap().value, 99);
+    ///
+    /// // Only key2 matches an item.
+    /// let (by_key1, by_key2) = map.get_conflicts(&3, &"foo");
+    /// assert!(by_key1.is_none());
+    /// assert_eq!(by_key2.unwrap().value, 42);
+    ///
+    /// // Neither key matches, so an item with these keys can be inserted.
+    /// assert_eq!(map.get_conflicts(&3, &"baz"), (None, None));
     /// # }
     /// ```
-    pub fn contains_key_unique<'a, Q1, Q2>(
+    pub fn get_conflicts<'a, Q1, Q2>(
         &'a self,
         key1: &Q1,
         key2: &Q2,
-    ) -> bool
+    ) -> (Option<&'a T>, Option<&'a T>)
     where
         Q1: Hash + Equivalent<T::K1<'a>> + ?Sized,
         Q2: Hash + Equivalent<T::K2<'a>> + ?Sized,
     {
-        self.get_unique(key1, key2).is_some()
+        (self.find1(key1), self.find2(key2))
     }
 
-    /// Gets a reference to the unique item associated with the given `key1` and
-    /// `key2`, if it exists.
+    /// Gets mutable references to the items associated with `key1` and `key2`
+    /// separately.
+    ///
+    /// This is the mutable counterpart to [`Self::get_conflicts`]. The first
+    /// element of the returned tuple is the item matching `key1`, and the
+    /// second is the item matching `key2`. If a single item matches both keys,
+    /// it is returned in the first position, and the second is `None`.
     ///
     /// # Examples
     ///
@@ -1446,27 +2376,56 @@ impl<T: BiHashItem, S: Clone + BuildHasher, A: Allocator> BiHashMap<T, S, A> {
     /// }
     ///
     /// let mut map = BiHashMap::new();
-    /// map.insert_unique(Item { id: 1, name: "foo".to_string(), value: 42 }).unwrap();
-    /// map.insert_unique(Item { id: 2, name: "bar".to_string(), value: 99 }).unwrap();
+    /// map.insert_unique(Item { id: 1, name: "foo".to_string(), value: 42 })
+    ///     .unwrap();
+    /// map.insert_unique(Item { id: 2, name: "bar".to_string(), value: 99 })
+    ///     .unwrap();
     ///
-    /// assert_eq!(map.get_unique(&1, &"foo").unwrap().value, 42);
-    /// assert_eq!(map.get_unique(&2, &"bar").unwrap().value, 99);
-    /// assert!(map.get_unique(&1, &"bar").is_none()); // key1 exists but key2 doesn't match
-    /// assert!(map.get_unique(&3, &"baz").is_none()); // neither key exists
+    /// // key1 and key2 match different items.
+    /// let (by_key1, by_key2) = map.get_pair_mut(&1, "bar");
+    /// by_key1.unwrap().value += 1;
+    /// by_key2.unwrap().value += 1;
+    /// assert_eq!(map.get1(&1).unwrap().value, 43);
+    /// assert_eq!(map.get1(&2).unwrap().value, 100);
+    ///
+    /// // Both keys match the same item, which is returned once.
+    /// let (by_key1, by_key2) = map.get_pair_mut(&1, "foo");
+    /// assert_eq!(by_key1.unwrap().value, 43);
+    /// assert!(by_key2.is_none());
     /// # }
     /// ```
-    pub fn get_unique<'a, Q1, Q2>(
-        &'a self,
+    #[expect(clippy::type_complexity)]
+    pub fn get_pair_mut<'a, Q1, Q2>(
+        &'a mut self,
         key1: &Q1,
         key2: &Q2,
-    ) -> Option<&'a T>
+    ) -> (Option<RefMut<'a, T, S>>, Option<RefMut<'a, T, S>>)
     where
         Q1: Hash + Equivalent<T::K1<'a>> + ?Sized,
         Q2: Hash + Equivalent<T::K2<'a>> + ?Sized,
     {
-        let index = self.find1_index(key1)?;
-        let item = &self.items[index];
-        if key2.equivalent(&item.key2()) { Some(item) } else { None }
+        let (dormant_map, indexes) = {
+            let (map, dormant_map) = DormantMutRef::new(self);
+            let index1 = map.find1_index(key1);
+            let index2 = map.find2_index(key2);
+            let indexes = match (index1, index2) {
+                (None, None) => return (None, None),
+                (Some(index1), Some(index2)) if index1 == index2 => {
+                    EntryIndexes::Unique(index1)
+                }
+                (index1, index2) => EntryIndexes::NonUnique { index1, index2 },
+            };
+            (dormant_map, indexes)
+        };
+
+        // SAFETY: `map` is not used after this point.
+        let awakened_map = unsafe { dormant_map.awaken() };
+        match awakened_map.get_by_entry_index_mut(indexes) {
+            OccupiedEntryMut::Unique(item) => (Some(item), None),
+            OccupiedEntryMut::NonUnique { by_key1, by_key2 } => {
+                (by_key1, by_key2)
+            }
+        }
     }
 
     /// Gets a mutable reference to the unique item associated with the given
@@ -1636,6 +2595,28 @@ impl<T: BiHashItem, S: Clone + BuildHasher, A: Allocator> BiHashMap<T, S, A> {
         Some(RefMut::new(state, hashes, item))
     }
 
+    /// Gets a plain mutable reference to the value associated with the given
+    /// `key1`.
+    ///
+    /// Unlike [`Self::get1_mut`], this doesn't wrap the item in a
+    /// [`RefMut`] that checks the keys on drop. It is only available for items
+    /// that implement [`ImmutableKey`].
+    pub fn get1_mut_plain<'a, Q>(&'a mut self, key1: &Q) -> Option<&'a mut T>
+    where
+        Q: Hash + Equivalent<T::K1<'a>> + ?Sized,
+        T: ImmutableKey,
+    {
+        let (dormant_map, index) = {
+            let (map, dormant_map) = DormantMutRef::new(self);
+            let index = map.find1_index(key1)?;
+            (dormant_map, index)
+        };
+
+        // SAFETY: `map` is not used after this point.
+        let awakened_map = unsafe { dormant_map.awaken() };
+        Some(&mut awakened_map.items[index])
+    }
+
     /// Removes an item from the map by its `key1`.
     ///
     /// # Examples
@@ -1843,6 +2824,28 @@ impl<T: BiHashItem, S: Clone + BuildHasher, A: Allocator> BiHashMap<T, S, A> {
         Some(RefMut::new(state, hashes, item))
     }
 
+    /// Gets a plain mutable reference to the value associated with the given
+    /// `key2`.
+    ///
+    /// Unlike [`Self::get2_mut`], this doesn't wrap the item in a
+    /// [`RefMut`] that checks the keys on drop. It is only available for items
+    /// that implement [`ImmutableKey`].
+    pub fn get2_mut_plain<'a, Q>(&'a mut self, key2: &Q) -> Option<&'a mut T>
+    where
+        Q: Hash + Equivalent<T::K2<'a>> + ?Sized,
+        T: ImmutableKey,
+    {
+        let (dormant_map, index) = {
+            let (map, dormant_map) = DormantMutRef::new(self);
+            let index = map.find2_index(key2)?;
+            (dormant_map, index)
+        };
+
+        // SAFETY: `map` is not used after this point.
+        let awakened_map = unsafe { dormant_map.awaken() };
+        Some(&mut awakened_map.items[index])
+    }
+
     /// Removes an item from the map by its `key2`.
     ///
     /// # Examples
@@ -1900,19 +2903,154 @@ impl<T: BiHashItem, S: Clone + BuildHasher, A: Allocator> BiHashMap<T, S, A> {
         awakened_map.remove_by_index(remove_index)
     }
 
-    /// Retrieves an entry by its keys.
-    ///
-    /// Due to borrow checker limitations, this always accepts owned keys rather
-    /// than a borrowed form of them.
+    /// Retrieves an entry by its keys.
+    ///
+    /// Due to borrow checker limitations, this always accepts owned keys rather
+    /// than a borrowed form of them. To look up an entry by borrowed keys, use
+    /// [`BiHashMap::entry_ref`].
+    ///
+    /// # Differences from single-key entries
+    ///
+    /// The [`Entry`] returned by this method differs from those provided
+    /// for the other map types, because it is possible for one of the two keys
+    /// provided to correspond to an existing entry, while the other does not.
+    ///
+    /// For more information, and examples covering non-unique entries, see the
+    /// type-level documentation for [`Entry`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "default-hasher")] {
+    /// use iddqd::{BiHashItem, BiHashMap, bi_hash_map, bi_upcast};
+    ///
+    /// #[derive(Debug, PartialEq, Eq)]
+    /// struct Item {
+    ///     id: u32,
+    ///     name: String,
+    ///     value: i32,
+    /// }
+    ///
+    /// impl BiHashItem for Item {
+    ///     type K1<'a> = u32;
+    ///     type K2<'a> = &'a str;
+    ///
+    ///     fn key1(&self) -> Self::K1<'_> {
+    ///         self.id
+    ///     }
+    ///     fn key2(&self) -> Self::K2<'_> {
+    ///         &self.name
+    ///     }
+    ///     bi_upcast!();
+    /// }
+    ///
+    /// let mut map = BiHashMap::new();
+    /// map.insert_unique(Item { id: 1, name: "foo".to_string(), value: 42 })
+    ///     .unwrap();
+    ///
+    /// // Get an existing entry.
+    /// match map.entry(1, "foo") {
+    ///     bi_hash_map::Entry::Occupied(entry) => {
+    ///         assert_eq!(entry.get().as_unique().unwrap().value, 42);
+    ///     }
+    ///     bi_hash_map::Entry::Vacant(_) => panic!("Should be occupied"),
+    /// }
+    ///
+    /// // Try to get a non-existing entry.
+    /// match map.entry(2, "bar") {
+    ///     bi_hash_map::Entry::Occupied(_) => panic!("Should be vacant"),
+    ///     bi_hash_map::Entry::Vacant(entry) => {
+    ///         entry.insert(Item { id: 2, name: "bar".to_string(), value: 99 });
+    ///     }
+    /// }
+    ///
+    /// assert_eq!(map.len(), 2);
+    /// # }
+    /// ```
+    ///
+    /// For an expanded example, see the type-level documentation for [`Entry`].
+    pub fn entry<'a>(
+        &'a mut self,
+        key1: T::K1<'_>,
+        key2: T::K2<'_>,
+    ) -> Entry<'a, T, S, A> {
+        // Why does this always take owned keys? Well, it would seem like we
+        // should be able to pass in any Q1 and Q2 that are equivalent. That
+        // results in *this* code compiling fine, but callers have trouble using
+        // it because the borrow checker believes the keys are borrowed for the
+        // full 'a rather than a shorter lifetime.
+        //
+        // By accepting owned keys, we can use the upcast functions to convert
+        // them to a shorter lifetime (so this function accepts T::K1<'_> rather
+        // than T::K1<'a>).
+        //
+        // Really, the solution here is to allow GATs to require covariant
+        // parameters. If that were allowed, the borrow checker should be able
+        // to figure out that keys don't need to be borrowed for the full 'a,
+        // just for some shorter lifetime.
+        let (map, dormant_map) = DormantMutRef::new(self);
+        let key1 = T::upcast_key1(key1);
+        let key2 = T::upcast_key2(key2);
+        let (index1, index2) = {
+            // index1 and index2 are explicitly typed to show that it has a
+            // trivial Drop impl that doesn't capture anything from map.
+            let index1: Option<ItemIndex> = map.tables.k1_to_item.find_index(
+                &map.tables.state,
+                &key1,
+                |index| map.items[index].key1(),
+            );
+            let index2: Option<ItemIndex> = map.tables.k2_to_item.find_index(
+                &map.tables.state,
+                &key2,
+                |index| map.items[index].key2(),
+            );
+            (index1, index2)
+        };
+
+        match (index1, index2) {
+            (Some(index1), Some(index2)) if index1 == index2 => {
+                // The item is already in the map.
+                drop(key1);
+                Entry::Occupied(
+                    // SAFETY: `map` is not used after this point.
+                    unsafe {
+                        OccupiedEntry::new(
+                            dormant_map,
+                            EntryIndexes::Unique(index1),
+                        )
+                    },
+                )
+            }
+            (None, None) => {
+                let hashes = map.tables.make_hashes::<T>(&key1, &key2);
+                Entry::Vacant(
+                    // SAFETY: `map` is not used after this point.
+                    unsafe { VacantEntry::new(dormant_map, hashes) },
+                )
+            }
+            (index1, index2) => Entry::Occupied(
+                // SAFETY: `map` is not used after this point.
+                unsafe {
+                    OccupiedEntry::new(
+                        dormant_map,
+                        EntryIndexes::NonUnique { index1, index2 },
+                    )
+                },
+            ),
+        }
+    }
+
+    /// Retrieves an entry by borrowed forms of its keys.
     ///
-    /// # Differences from single-key entries
-    ///
-    /// The [`Entry`] returned by this method differs from those provided
-    /// for the other map types, because it is possible for one of the two keys
-    /// provided to correspond to an existing entry, while the other does not.
+    /// This is like [`BiHashMap::entry`], except that it accepts any `Q1` and
+    /// `Q2` equivalent to the keys, so looking up an entry doesn't require
+    /// constructing owned keys. The keys are only used to find existing items
+    /// and to compute hashes, and are not retained by the returned [`Entry`].
     ///
-    /// For more information, and examples covering non-unique entries, see the
-    /// type-level documentation for [`Entry`].
+    /// As with [`BiHashMap::get1`] and [`BiHashMap::get2`], `Q1` and `Q2` are
+    /// tied to the lifetime of the map borrow. For keys that borrow from the
+    /// item, such as `&'a str`, pass the unsized form (here `str`) so that
+    /// the query can be dropped while the entry is still alive.
     ///
     /// # Examples
     ///
@@ -1944,16 +3082,17 @@ impl<T: BiHashItem, S: Clone + BuildHasher, A: Allocator> BiHashMap<T, S, A> {
     /// map.insert_unique(Item { id: 1, name: "foo".to_string(), value: 42 })
     ///     .unwrap();
     ///
-    /// // Get an existing entry.
-    /// match map.entry(1, "foo") {
+    /// // Look up an existing entry through a borrowed `str`.
+    /// let name = String::from("foo");
+    /// match map.entry_ref(&1, name.as_str()) {
     ///     bi_hash_map::Entry::Occupied(entry) => {
     ///         assert_eq!(entry.get().as_unique().unwrap().value, 42);
     ///     }
     ///     bi_hash_map::Entry::Vacant(_) => panic!("Should be occupied"),
     /// }
     ///
-    /// // Try to get a non-existing entry.
-    /// match map.entry(2, "bar") {
+    /// // Insert into a vacant entry.
+    /// match map.entry_ref(&2, "bar") {
     ///     bi_hash_map::Entry::Occupied(_) => panic!("Should be vacant"),
     ///     bi_hash_map::Entry::Vacant(entry) => {
     ///         entry.insert(Item { id: 2, name: "bar".to_string(), value: 99 });
@@ -1963,50 +3102,55 @@ impl<T: BiHashItem, S: Clone + BuildHasher, A: Allocator> BiHashMap<T, S, A> {
     /// assert_eq!(map.len(), 2);
     /// # }
     /// ```
-    ///
-    /// For an expanded example, see the type-level documentation for [`Entry`].
-    pub fn entry<'a>(
+    pub fn entry_ref<'a, Q1, Q2>(
         &'a mut self,
-        key1: T::K1<'_>,
-        key2: T::K2<'_>,
-    ) -> Entry<'a, T, S, A> {
-        // Why does this always take owned keys? Well, it would seem like we
-        // should be able to pass in any Q1 and Q2 that are equivalent. That
-        // results in *this* code compiling fine, but callers have trouble using
-        // it because the borrow checker believes the keys are borrowed for the
-        // full 'a rather than a shorter lifetime.
-        //
-        // By accepting owned keys, we can use the upcast functions to convert
-        // them to a shorter lifetime (so this function accepts T::K1<'_> rather
-        // than T::K1<'a>).
+        key1: &Q1,
+        key2: &Q2,
+    ) -> Entry<'a, T, S, A>
+    where
+        Q1: Hash + Equivalent<T::K1<'a>> + ?Sized,
+        Q2: Hash + Equivalent<T::K2<'a>> + ?Sized,
+    {
+        // Soundness: `key1` and `key2` are only used within this block, to
+        // find indexes and to compute hashes. Neither the indexes nor the
+        // hashes borrow from the keys or from `map`, so `map` is not used
+        // after this point and it is sound to hand `dormant_map` to the
+        // entry.
         //
-        // Really, the solution here is to allow GATs to require covariant
-        // parameters. If that were allowed, the borrow checker should be able
-        // to figure out that keys don't need to be borrowed for the full 'a,
-        // just for some shorter lifetime.
+        // The `Equivalent` contract requires that `Q1` and `Q2` hash the same
+        // way as the corresponding keys, so the hashes stored in a
+        // `VacantEntry` match those that `VacantEntry::insert` checks against
+        // the item's own keys. (If a caller violates that contract, insertion
+        // panics, just like inserting an item whose keys don't match the
+        // entry.)
         let (map, dormant_map) = DormantMutRef::new(self);
-        let key1 = T::upcast_key1(key1);
-        let key2 = T::upcast_key2(key2);
-        let (index1, index2) = {
-            // index1 and index2 are explicitly typed to show that it has a
+        let (index1, index2, hashes) = {
+            // index1 and index2 are explicitly typed to show that they have a
             // trivial Drop impl that doesn't capture anything from map.
             let index1: Option<ItemIndex> = map.tables.k1_to_item.find_index(
                 &map.tables.state,
-                &key1,
+                key1,
                 |index| map.items[index].key1(),
             );
             let index2: Option<ItemIndex> = map.tables.k2_to_item.find_index(
                 &map.tables.state,
-                &key2,
+                key2,
                 |index| map.items[index].key2(),
             );
-            (index1, index2)
+            let hashes = if index1.is_none() && index2.is_none() {
+                Some([
+                    MapHash::new(map.tables.state.hash_one(key1)),
+                    MapHash::new(map.tables.state.hash_one(key2)),
+                ])
+            } else {
+                None
+            };
+            (index1, index2, hashes)
         };
 
-        match (index1, index2) {
-            (Some(index1), Some(index2)) if index1 == index2 => {
+        match (index1, index2, hashes) {
+            (Some(index1), Some(index2), _) if index1 == index2 => {
                 // The item is already in the map.
-                drop(key1);
                 Entry::Occupied(
                     // SAFETY: `map` is not used after this point.
                     unsafe {
@@ -2017,14 +3161,11 @@ impl<T: BiHashItem, S: Clone + BuildHasher, A: Allocator> BiHashMap<T, S, A> {
                     },
                 )
             }
-            (None, None) => {
-                let hashes = map.tables.make_hashes::<T>(&key1, &key2);
-                Entry::Vacant(
-                    // SAFETY: `map` is not used after this point.
-                    unsafe { VacantEntry::new(dormant_map, hashes) },
-                )
-            }
-            (index1, index2) => Entry::Occupied(
+            (None, None, Some(hashes)) => Entry::Vacant(
+                // SAFETY: `map` is not used after this point.
+                unsafe { VacantEntry::new(dormant_map, hashes) },
+            ),
+            (index1, index2, _) => Entry::Occupied(
                 // SAFETY: `map` is not used after this point.
                 unsafe {
                     OccupiedEntry::new(
@@ -2036,11 +3177,191 @@ impl<T: BiHashItem, S: Clone + BuildHasher, A: Allocator> BiHashMap<T, S, A> {
         }
     }
 
+    /// Consumes the map, moving its items into a new map that uses `alloc`.
+    ///
+    /// Items are moved rather than cloned, and the key tables are rebuilt in
+    /// the new allocator. The new map uses the same hasher as this one. This is
+    /// useful for moving a map between arenas, for example between processing
+    /// phases.
+    ///
+    /// Requires the `allocator-api2` feature to be enabled.
+    ///
+    /// # Examples
+    ///
+    /// Using the [`bumpalo`](https://docs.rs/bumpalo) allocator:
+    ///
+    /// ```
+    /// # #[cfg(feature = "allocator-api2")] {
+    /// use iddqd::{BiHashItem, BiHashMap, bi_upcast};
+    /// use std::collections::hash_map::RandomState;
+    /// # use iddqd_test_utils::bumpalo;
+    ///
+    /// #[derive(Debug, PartialEq, Eq)]
+    /// struct Item {
+    ///     id: u32,
+    ///     name: String,
+    /// }
+    ///
+    /// impl BiHashItem for Item {
+    ///     type K1<'a> = u32;
+    ///     type K2<'a> = &'a str;
+    ///
+    ///     fn key1(&self) -> Self::K1<'_> {
+    ///         self.id
+    ///     }
+    ///     fn key2(&self) -> Self::K2<'_> {
+    ///         &self.name
+    ///     }
+    ///     bi_upcast!();
+    /// }
+    ///
+    /// let phase1 = bumpalo::Bump::new();
+    /// let mut map: BiHashMap<Item, _, &bumpalo::Bump> =
+    ///     BiHashMap::with_hasher_in(RandomState::new(), &phase1);
+    /// map.insert_unique(Item { id: 1, name: "foo".to_string() }).unwrap();
+    ///
+    /// // Move the map into a second arena, so the first one can be reset.
+    /// let phase2 = bumpalo::Bump::new();
+    /// let map = map.into_alloc(&phase2);
+    /// drop(phase1);
+    /// assert_eq!(map.get1(&1).unwrap().name, "foo");
+    /// assert_eq!(map.get2("foo").unwrap().id, 1);
+    /// # }
+    /// ```
+    pub fn into_alloc<A2: Clone + Allocator>(
+        self,
+        alloc: A2,
+    ) -> BiHashMap<T, S, A2> {
+        let mut map = BiHashMap::with_capacity_and_hasher_in(
+            self.len(),
+            self.tables.state,
+            alloc,
+        );
+        for item in self.items.into_values() {
+            // Keys in this map are already unique, and capacity has been
+            // reserved up front, so there's no need to check for duplicates.
+            let hashes =
+                map.tables.make_hashes::<T>(&item.key1(), &item.key2());
+            map.insert_unique_with_prepared_hashes(item, hashes);
+        }
+
+        map
+    }
+
+    /// Consumes the map, transforming each item with `f` and collecting the
+    /// results into a new map.
+    ///
+    /// The new map uses the same hasher and allocator as this one. Keys may
+    /// change under `f`, as long as the transformed items remain unique. If a
+    /// transformed item conflicts with one produced earlier, this returns a
+    /// [`DuplicateItem`] error containing the new item and the conflicting
+    /// items, and drops the rest of the map.
+    ///
+    /// To overwrite duplicates instead, use `into_iter().map(f).collect()`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "default-hasher")] {
+    /// use iddqd::{BiHashItem, BiHashMap, bi_upcast};
+    ///
+    /// #[derive(Debug)]
+    /// struct Raw {
+    ///     id: u32,
+    ///     name: String,
+    /// }
+    ///
+    /// impl BiHashItem for Raw {
+    ///     type K1<'a> = u32;
+    ///     type K2<'a> = &'a str;
+    ///     fn key1(&self) -> Self::K1<'_> {
+    ///         self.id
+    ///     }
+    ///     fn key2(&self) -> Self::K2<'_> {
+    ///         &self.name
+    ///     }
+    ///     bi_upcast!();
+    /// }
+    ///
+    /// #[derive(Debug)]
+    /// struct Processed {
+    ///     id: u32,
+    ///     name: String,
+    ///     len: usize,
+    /// }
+    ///
+    /// impl BiHashItem for Processed {
+    ///     type K1<'a> = u32;
+    ///     type K2<'a> = &'a str;
+    ///     fn key1(&self) -> Self::K1<'_> {
+    ///         self.id
+    ///     }
+    ///     fn key2(&self) -> Self::K2<'_> {
+    ///         &self.name
+    ///     }
+    ///     bi_upcast!();
+    /// }
+    ///
+    /// let mut raw = BiHashMap::new();
+    /// raw.insert_unique(Raw { id: 1, name: "foo".to_string() }).unwrap();
+    /// raw.insert_unique(Raw { id: 2, name: "hello".to_string() }).unwrap();
+    ///
+    /// let processed = raw
+    ///     .map(|r| Processed { id: r.id, len: r.name.len(), name: r.name })
+    ///     .expect("keys are preserved");
+    /// assert_eq!(processed.get1(&1).unwrap().len, 3);
+    /// assert_eq!(processed.get2("hello").unwrap().len, 5);
+    ///
+    /// // Mapping every item to the same key2 produces an error.
+    /// let err = processed
+    ///     .map(|p| Processed { id: p.id, name: "same".to_string(), len: p.len })
+    ///     .unwrap_err();
+    /// assert_eq!(err.duplicates().len(), 1);
+    /// # }
+    /// ```
+    pub fn map<U, F>(
+        self,
+        mut f: F,
+    ) -> Result<BiHashMap<U, S, A>, DuplicateItem<U>>
+    where
+        U: BiHashItem,
+        F: FnMut(T) -> U,
+        A: Clone,
+    {
+        let alloc = self.items.allocator().clone();
+        let mut map = BiHashMap::with_capacity_and_hasher_in(
+            self.len(),
+            self.tables.state,
+            alloc,
+        );
+        for item in self.items.into_values() {
+            if let Err((value, indexes)) =
+                map.insert_unique_or_dup_indexes(f(item))
+            {
+                // As in `from_iter_unique`, removal produces owned duplicates
+                // without requiring U: Clone.
+                let duplicates = indexes
+                    .iter()
+                    .map(|ix| {
+                        map.remove_by_index(*ix)
+                            .expect("duplicate index is present")
+                    })
+                    .collect();
+                return Err(DuplicateItem::__internal_new(value, duplicates));
+            }
+        }
+
+        Ok(map)
+    }
+
     /// Retains only the elements specified by the predicate.
     ///
     /// In other words, remove all items `T` for which `f(RefMut<T>)` returns
     /// false. The elements are visited in an arbitrary order.
     ///
+    /// The remaining items are compacted afterwards, so that no holes are left
+    /// behind by the removed ones. The map's capacity is unchanged.
+    ///
     /// # Examples
     ///
     /// ```
@@ -2172,9 +3493,96 @@ impl<T: BiHashItem, S: Clone + BuildHasher, A: Allocator> BiHashMap<T, S, A> {
             }
         });
 
+        // Fill the holes left by removed items, so that the map stays
+        // compact.
+        //
+        // SAFETY: The closure above, which held the only reborrows of
+        // `dormant_items`, has returned.
+        let items = unsafe { dormant_items.awaken() };
+        let remap = items.compact();
+        if !remap.is_identity() {
+            self.tables.k1_to_item.remap_indexes(&remap);
+            self.tables.k2_to_item.remap_indexes(&remap);
+        }
+
         // Anything in `removed_item` is implicitly dropped now.
     }
 
+    /// Removes all items for which `f` returns true, returning them.
+    ///
+    /// This is the inverse of [`Self::retain`], except that the predicate takes
+    /// `&T` and the removed items are returned rather than dropped. Both key
+    /// tables are updated as items are removed. The items are visited, and
+    /// returned, in an arbitrary order.
+    ///
+    /// If `f` panics, no items are removed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "default-hasher")] {
+    /// use iddqd::{BiHashItem, BiHashMap, bi_upcast};
+    ///
+    /// #[derive(Debug, PartialEq, Eq)]
+    /// struct Item {
+    ///     id: u32,
+    ///     name: String,
+    ///     group: u32,
+    /// }
+    ///
+    /// impl BiHashItem for Item {
+    ///     type K1<'a> = u32;
+    ///     type K2<'a> = &'a str;
+    ///
+    ///     fn key1(&self) -> Self::K1<'_> {
+    ///         self.id
+    ///     }
+    ///     fn key2(&self) -> Self::K2<'_> {
+    ///         &self.name
+    ///     }
+    ///     bi_upcast!();
+    /// }
+    ///
+    /// let mut map = BiHashMap::new();
+    /// map.insert_unique(Item { id: 1, name: "foo".to_string(), group: 1 })
+    ///     .unwrap();
+    /// map.insert_unique(Item { id: 2, name: "bar".to_string(), group: 2 })
+    ///     .unwrap();
+    /// map.insert_unique(Item { id: 3, name: "baz".to_string(), group: 1 })
+    ///     .unwrap();
+    ///
+    /// // Invalidate everything in group 1.
+    /// let mut removed = map.remove_where(|item| item.group == 1);
+    /// removed.sort_by_key(|item| item.id);
+    /// let ids: Vec<_> = removed.iter().map(|item| item.id).collect();
+    /// assert_eq!(ids, vec![1, 3]);
+    ///
+    /// // Both key tables have been updated.
+    /// assert_eq!(map.len(), 1);
+    /// assert!(map.get1(&1).is_none());
+    /// assert!(map.get2("baz").is_none());
+    /// assert_eq!(map.get2("bar").unwrap().id, 2);
+    /// # }
+    /// ```
+    pub fn remove_where<F>(&mut self, mut f: F) -> Vec<T>
+    where
+        F: FnMut(&T) -> bool,
+    {
+        // Find all the matching indexes first, so that a panic in `f` leaves
+        // the map untouched.
+        let indexes: Vec<ItemIndex> = self
+            .items
+            .iter()
+            .filter_map(|(index, item)| f(item).then_some(index))
+            .collect();
+        indexes
+            .into_iter()
+            .map(|index| {
+                self.remove_by_index(index).expect("index is present in items")
+            })
+            .collect()
+    }
+
     fn find1<'a, Q>(&'a self, k: &Q) -> Option<&'a T>
     where
         Q: Hash + Equivalent<T::K1<'a>> + ?Sized,
@@ -2768,21 +4176,23 @@ impl<T: BiHashItem + Eq, S: Clone + BuildHasher, A: Allocator> Eq
 {
 }
 
-fn detect_dup_or_insert<'a, A: Allocator>(
-    item: hash_table::Entry<'a, A>,
-    duplicates: &mut BTreeSet<ItemIndex>,
-) -> Option<hash_table::VacantEntry<'a, A>> {
-    match item {
-        hash_table::Entry::Vacant(slot) => Some(slot),
-        hash_table::Entry::Occupied(slot) => {
-            duplicates.insert(slot.get());
-            None
-        }
+/// The hash of a `BiHashMap` doesn't depend on the order its items are stored
+/// in, so it is consistent with the `PartialEq` implementation.
+///
+/// Each item is hashed with a fixed-seed hasher, independent of the map's own
+/// hasher, and the results are combined with a commutative sum. As a result,
+/// equal maps hash the same even if they use hashers with different seeds.
+impl<T: BiHashItem + Hash, S: Clone + BuildHasher, A: Allocator> Hash
+    for BiHashMap<T, S, A>
+{
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.items.hash_unordered(state);
     }
 }
 
-/// The `Extend` implementation overwrites duplicates. In the future, there will
-/// also be an `extend_unique` method that will return an error.
+/// The `Extend` implementation overwrites duplicates. To return an error on
+/// duplicates instead, use [`BiHashMap::extend_with`] with
+/// [`ExtendMode::Unique`].
 ///
 /// # Examples
 ///
@@ -2828,20 +4238,8 @@ impl<T: BiHashItem, S: Clone + BuildHasher, A: Allocator> Extend<T>
     for BiHashMap<T, S, A>
 {
     fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
-        // Keys may already be present in the map, or multiple times in the
-        // iterator. Reserve the entire hint lower bound if the map is empty.
-        // Otherwise reserve half the hint (rounded up), so the map will only
-        // resize twice in the worst case.
-        let iter = iter.into_iter();
-        let reserve = if self.is_empty() {
-            iter.size_hint().0
-        } else {
-            iter.size_hint().0.div_ceil(2)
-        };
-        self.reserve(reserve);
-        for item in iter {
-            self.insert_overwrite(item);
-        }
+        // Overwrite mode never returns an error.
+        let _ = self.extend_with(iter, ExtendMode::Overwrite);
     }
 }
 
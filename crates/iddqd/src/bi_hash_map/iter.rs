@@ -6,6 +6,7 @@ use crate::{
         item_set::{self, ItemSet},
     },
 };
+use alloc::vec::{self, Vec};
 use core::{hash::BuildHasher, iter::FusedIterator};
 
 /// An iterator over the elements of a [`BiHashMap`] by shared reference.
@@ -35,6 +36,11 @@ impl<'a, T: BiHashItem> Iterator for Iter<'a, T> {
     fn next(&mut self) -> Option<Self::Item> {
         self.inner.next()
     }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
 }
 
 impl<T: BiHashItem> ExactSizeIterator for Iter<'_, T> {
@@ -46,6 +52,133 @@ impl<T: BiHashItem> ExactSizeIterator for Iter<'_, T> {
 
 impl<T: BiHashItem> FusedIterator for Iter<'_, T> {}
 
+/// An iterator over the first keys of a [`BiHashMap`].
+///
+/// Created by [`BiHashMap::key1s`].
+///
+/// Similar to [`HashMap`], the iteration order is arbitrary and not guaranteed
+/// to be stable.
+///
+/// [`BiHashMap`]: crate::BiHashMap
+/// [`BiHashMap::key1s`]: crate::BiHashMap::key1s
+/// [`HashMap`]: std::collections::HashMap
+#[derive(Clone, Debug, Default)]
+pub struct Key1s<'a, T: BiHashItem> {
+    inner: Iter<'a, T>,
+}
+
+impl<'a, T: BiHashItem> Key1s<'a, T> {
+    pub(super) fn new(inner: Iter<'a, T>) -> Self {
+        Self { inner }
+    }
+}
+
+impl<'a, T: BiHashItem> Iterator for Key1s<'a, T> {
+    type Item = T::K1<'a>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(T::key1)
+    }
+}
+
+impl<T: BiHashItem> ExactSizeIterator for Key1s<'_, T> {
+    #[inline]
+    fn len(&self) -> usize {
+        self.inner.len()
+    }
+}
+
+impl<T: BiHashItem> FusedIterator for Key1s<'_, T> {}
+
+/// An iterator over the second keys of a [`BiHashMap`].
+///
+/// Created by [`BiHashMap::key2s`].
+///
+/// Similar to [`HashMap`], the iteration order is arbitrary and not guaranteed
+/// to be stable.
+///
+/// [`BiHashMap`]: crate::BiHashMap
+/// [`BiHashMap::key2s`]: crate::BiHashMap::key2s
+/// [`HashMap`]: std::collections::HashMap
+#[derive(Clone, Debug, Default)]
+pub struct Key2s<'a, T: BiHashItem> {
+    inner: Iter<'a, T>,
+}
+
+impl<'a, T: BiHashItem> Key2s<'a, T> {
+    pub(super) fn new(inner: Iter<'a, T>) -> Self {
+        Self { inner }
+    }
+}
+
+impl<'a, T: BiHashItem> Iterator for Key2s<'a, T> {
+    type Item = T::K2<'a>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(T::key2)
+    }
+}
+
+impl<T: BiHashItem> ExactSizeIterator for Key2s<'_, T> {
+    #[inline]
+    fn len(&self) -> usize {
+        self.inner.len()
+    }
+}
+
+impl<T: BiHashItem> FusedIterator for Key2s<'_, T> {}
+
+/// An iterator over the elements of a [`BiHashMap`] in key order.
+///
+/// Created by [`BiHashMap::iter_sorted_by_key1`] and
+/// [`BiHashMap::iter_sorted_by_key2`].
+///
+/// [`BiHashMap`]: crate::BiHashMap
+/// [`BiHashMap::iter_sorted_by_key1`]: crate::BiHashMap::iter_sorted_by_key1
+/// [`BiHashMap::iter_sorted_by_key2`]: crate::BiHashMap::iter_sorted_by_key2
+#[derive(Clone, Debug)]
+pub struct SortedIter<'a, T: BiHashItem> {
+    inner: vec::IntoIter<&'a T>,
+}
+
+impl<'a, T: BiHashItem> SortedIter<'a, T> {
+    pub(super) fn new(sorted: Vec<&'a T>) -> Self {
+        Self { inner: sorted.into_iter() }
+    }
+}
+
+impl<'a, T: BiHashItem> Iterator for SortedIter<'a, T> {
+    type Item = &'a T;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next()
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<T: BiHashItem> DoubleEndedIterator for SortedIter<'_, T> {
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.inner.next_back()
+    }
+}
+
+impl<T: BiHashItem> ExactSizeIterator for SortedIter<'_, T> {
+    #[inline]
+    fn len(&self) -> usize {
+        self.inner.len()
+    }
+}
+
+impl<T: BiHashItem> FusedIterator for SortedIter<'_, T> {}
+
 /// An iterator over the elements of a [`BiHashMap`] by mutable reference.
 /// Created by [`BiHashMap::iter_mut`].
 ///
@@ -90,6 +223,11 @@ impl<'a, T: BiHashItem, S: Clone + BuildHasher, A: Allocator> Iterator
         let hashes = self.tables.make_hashes::<T>(&next.key1(), &next.key2());
         Some(RefMut::new(self.tables.state.clone(), hashes, next))
     }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
 }
 
 impl<T: BiHashItem, S: Clone + BuildHasher, A: Allocator> ExactSizeIterator
@@ -133,6 +271,11 @@ impl<T: BiHashItem, A: Allocator> Iterator for IntoIter<T, A> {
     fn next(&mut self) -> Option<Self::Item> {
         self.inner.next()
     }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
 }
 
 impl<T: BiHashItem, A: Allocator> ExactSizeIterator for IntoIter<T, A> {
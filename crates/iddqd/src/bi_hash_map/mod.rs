@@ -2,6 +2,9 @@
 //!
 //! For more information, see [`BiHashMap`].
 
+#[cfg(feature = "arbitrary")]
+mod arbitrary_impls;
+mod bi_map;
 #[cfg(feature = "daft")]
 mod daft_impls;
 mod entry;
@@ -10,7 +13,11 @@ pub(crate) mod imp;
 mod iter;
 #[cfg(feature = "proptest")]
 mod proptest_impls;
+#[cfg(feature = "rayon")]
+mod rayon_impls;
 mod ref_mut;
+#[cfg(feature = "schemars1")]
+mod schemars1_impls;
 #[cfg(feature = "schemars08")]
 mod schemars_impls;
 #[cfg(feature = "serde")]
@@ -18,13 +25,14 @@ mod serde_impls;
 mod tables;
 pub(crate) mod trait_defs;
 
+pub use bi_map::BiMap;
 #[cfg(feature = "daft")]
 pub use daft_impls::{ByK1, ByK2, Diff, MapLeaf};
 pub use entry::{
     Entry, OccupiedEntry, OccupiedEntryMut, OccupiedEntryRef, VacantEntry,
 };
 pub use imp::BiHashMap;
-pub use iter::{IntoIter, Iter, IterMut};
+pub use iter::{IntoIter, Iter, IterMut, Key1s, Key2s, SortedIter};
 #[cfg(all(feature = "proptest", feature = "default-hasher"))]
 pub use proptest_impls::prop_strategy;
 #[cfg(feature = "proptest")]
@@ -32,6 +40,8 @@ pub use proptest_impls::{
     BiHashMapStrategy, BiHashMapValueTree, prop_strategy_with_hasher,
     prop_strategy_with_hasher_in,
 };
+#[cfg(feature = "rayon")]
+pub use rayon_impls::ParIter;
 pub use ref_mut::RefMut;
 #[cfg(feature = "serde")]
 pub use serde_impls::BiHashMapAsMap;
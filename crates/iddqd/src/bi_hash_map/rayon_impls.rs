@@ -0,0 +1,79 @@
+//! Parallel iteration over [`BiHashMap`]s with [`rayon`].
+
+use super::{BiHashItem, BiHashMap};
+use crate::support::{alloc::Allocator, item_set::ParValues};
+use rayon::iter::{
+    IntoParallelIterator, ParallelIterator, plumbing::UnindexedConsumer,
+};
+
+/// A parallel iterator over the elements of an [`BiHashMap`] by shared
+/// reference.
+///
+/// Created by calling [`par_iter`] (or [`into_par_iter`] on a reference) with
+/// the `rayon` feature enabled.
+///
+/// Similar to [`BiHashMap::iter`], the iteration order is arbitrary.
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(feature = "default-hasher")] {
+/// use iddqd::{BiHashItem, BiHashMap, bi_upcast};
+/// use rayon::prelude::*;
+///
+/// struct Item {
+///     id: u32,
+///     name: String,
+///     value: u64,
+/// }
+///
+/// impl BiHashItem for Item {
+///     type K1<'a> = u32;
+///     type K2<'a> = &'a str;
+///     fn key1(&self) -> Self::K1<'_> {
+///         self.id
+///     }
+///     fn key2(&self) -> Self::K2<'_> {
+///         &self.name
+///     }
+///     bi_upcast!();
+/// }
+///
+/// let map: BiHashMap<Item> = (0..100)
+///     .map(|id| Item { id, name: id.to_string(), value: u64::from(id) * 2 })
+///     .collect();
+/// let total: u64 = map.par_iter().map(|item| item.value).sum();
+/// assert_eq!(total, 9900);
+/// # }
+/// ```
+///
+/// [`par_iter`]: rayon::iter::IntoParallelRefIterator::par_iter
+/// [`into_par_iter`]: rayon::iter::IntoParallelIterator::into_par_iter
+#[derive(Clone, Debug)]
+pub struct ParIter<'a, T: BiHashItem> {
+    inner: ParValues<'a, T>,
+}
+
+impl<'a, T: BiHashItem + Sync> ParallelIterator for ParIter<'a, T> {
+    type Item = &'a T;
+
+    #[inline]
+    fn drive_unindexed<C>(self, consumer: C) -> C::Result
+    where
+        C: UnindexedConsumer<Self::Item>,
+    {
+        self.inner.drive_unindexed(consumer)
+    }
+}
+
+impl<'a, T: BiHashItem + Sync, S, A: Allocator> IntoParallelIterator
+    for &'a BiHashMap<T, S, A>
+{
+    type Iter = ParIter<'a, T>;
+    type Item = &'a T;
+
+    #[inline]
+    fn into_par_iter(self) -> Self::Iter {
+        ParIter { inner: self.items.par_values() }
+    }
+}
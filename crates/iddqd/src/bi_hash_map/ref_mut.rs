@@ -73,6 +73,11 @@ impl<'a, T: BiHashItem, S: Clone + BuildHasher> RefMut<'a, T, S> {
     }
 
     /// Converts this `RefMut` into a `&'a T`.
+    ///
+    /// This gives up the ability to mutate the item, which is useful for
+    /// returning a shared reference once mutation is done. The keys are
+    /// checked one last time before the reference is returned, so this panics
+    /// if they were changed through this `RefMut`.
     pub fn into_ref(mut self) -> &'a T {
         let inner = self.inner.take().unwrap();
         inner.into_ref()
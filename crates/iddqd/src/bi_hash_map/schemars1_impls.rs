@@ -0,0 +1,61 @@
+//! Schemars 1.x implementations for BiHashMap.
+
+use crate::{
+    bi_hash_map::{
+        imp::BiHashMap, serde_impls::BiHashMapAsMap, trait_defs::BiHashItem,
+    },
+    support::{
+        alloc::Allocator,
+        schemars1_utils::{create_map_schema, create_object_schema},
+    },
+};
+use alloc::borrow::Cow;
+use schemars1::{JsonSchema, Schema, SchemaGenerator};
+
+impl<T, S, A> JsonSchema for BiHashMap<T, S, A>
+where
+    T: JsonSchema + BiHashItem,
+    A: Allocator,
+{
+    fn schema_name() -> Cow<'static, str> {
+        alloc::format!("BiHashMap_of_{}", T::schema_name()).into()
+    }
+
+    fn schema_id() -> Cow<'static, str> {
+        alloc::format!("iddqd::BiHashMap<{}>", T::schema_id()).into()
+    }
+
+    fn json_schema(generator: &mut SchemaGenerator) -> Schema {
+        create_map_schema::<T>("BiHashMap", "iddqd::BiHashMap", generator)
+    }
+
+    fn inline_schema() -> bool {
+        true
+    }
+}
+
+impl<T, S, A> JsonSchema for BiHashMapAsMap<T, S, A>
+where
+    T: JsonSchema + BiHashItem,
+    A: Allocator,
+{
+    fn schema_name() -> Cow<'static, str> {
+        alloc::format!("BiHashMapAsMap_of_{}", T::schema_name()).into()
+    }
+
+    fn schema_id() -> Cow<'static, str> {
+        alloc::format!("iddqd::BiHashMapAsMap<{}>", T::schema_id()).into()
+    }
+
+    fn json_schema(generator: &mut SchemaGenerator) -> Schema {
+        create_object_schema::<T>(
+            "BiHashMapAsMap",
+            "iddqd::BiHashMap",
+            generator,
+        )
+    }
+
+    fn inline_schema() -> bool {
+        true
+    }
+}
@@ -1,5 +1,6 @@
 use crate::{
     BiHashItem,
+    errors::TryReserveError,
     internal::{ValidateCompact, ValidationError},
     support::{
         alloc::{Allocator, Global, global_alloc},
@@ -73,3 +74,18 @@ impl<S: Clone + BuildHasher, A: Allocator> BiHashMapTables<S, A> {
         [h1, h2]
     }
 }
+
+impl<S, A: Allocator> BiHashMapTables<S, A> {
+    pub(super) fn try_reserve(
+        &mut self,
+        additional: usize,
+    ) -> Result<(), TryReserveError> {
+        self.k1_to_item
+            .try_reserve(additional)
+            .map_err(TryReserveError::from_hashbrown)?;
+        self.k2_to_item
+            .try_reserve(additional)
+            .map_err(TryReserveError::from_hashbrown)?;
+        Ok(())
+    }
+}
@@ -165,3 +165,31 @@ macro_rules! impl_for_box {
 impl_for_box!(Box<T>);
 impl_for_box!(Rc<T>);
 impl_for_box!(Arc<T>);
+
+/// A pair of keys is a [`BiHashItem`] with no other data, keyed by references
+/// to its two elements.
+///
+/// This is what [`BiMap`] stores.
+///
+/// [`BiMap`]: crate::BiMap
+impl<K1: Eq + Hash, K2: Eq + Hash> BiHashItem for (K1, K2) {
+    type K1<'a>
+        = &'a K1
+    where
+        Self: 'a;
+
+    type K2<'a>
+        = &'a K2
+    where
+        Self: 'a;
+
+    fn key1(&self) -> Self::K1<'_> {
+        &self.0
+    }
+
+    fn key2(&self) -> Self::K2<'_> {
+        &self.1
+    }
+
+    crate::bi_upcast!();
+}
@@ -0,0 +1,1444 @@
+use super::{BiOrdItem, IntoIter, Iter, Range, RefMut, tables::BiOrdMapTables};
+use crate::{
+    DefaultHashBuilder, ExtendMode,
+    errors::DuplicateItem,
+    internal::{ValidateCompact, ValidationError},
+    support::{
+        ItemIndex,
+        alloc::{Allocator, Global, global_alloc},
+        borrow::DormantMutRef,
+        item_set::ItemSet,
+    },
+};
+use alloc::{collections::BTreeSet, vec::Vec};
+use core::{
+    fmt,
+    hash::{BuildHasher, Hash, Hasher},
+    ops::RangeBounds,
+};
+use equivalent::{Comparable, Equivalent};
+
+/// An ordered map with two keys, where the first key is kept in sorted order
+/// and the second key is hashed.
+///
+/// `BiOrdMap` is a bijective (1:1) map like [`BiHashMap`], but iterates in
+/// order of the first key like [`IdOrdMap`]. This is useful for the common
+/// pattern of a sorted primary ID along with lookups by a secondary key such
+/// as a name.
+///
+/// The storage mechanism is a list of items with an embedded free chain, with
+/// indexes to occupied slots stored in a B-Tree map for the first key and a
+/// hash table for the second key.
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(feature = "default-hasher")] {
+/// use iddqd::{BiOrdItem, BiOrdMap, bi_upcast};
+///
+/// #[derive(Debug, PartialEq, Eq)]
+/// struct Host {
+///     id: u32,
+///     name: String,
+/// }
+///
+/// // Implement BiOrdItem to define the two keys.
+/// impl BiOrdItem for Host {
+///     type K1<'a> = u32;
+///     type K2<'a> = &'a str;
+///
+///     fn key1(&self) -> Self::K1<'_> {
+///         self.id
+///     }
+///
+///     fn key2(&self) -> Self::K2<'_> {
+///         &self.name
+///     }
+///
+///     bi_upcast!();
+/// }
+///
+/// let mut map = BiOrdMap::new();
+/// map.insert_unique(Host { id: 20, name: "db".to_string() }).unwrap();
+/// map.insert_unique(Host { id: 10, name: "web".to_string() }).unwrap();
+///
+/// // Look up items by either key.
+/// assert_eq!(map.get1(&20).unwrap().name, "db");
+/// assert_eq!(map.get2("web").unwrap().id, 10);
+///
+/// // Iteration is in order of the first key.
+/// let names: Vec<_> = map.iter().map(|host| host.name.as_str()).collect();
+/// assert_eq!(names, ["web", "db"]);
+/// # }
+/// ```
+///
+/// [`BiHashMap`]: crate::BiHashMap
+/// [`IdOrdMap`]: crate::IdOrdMap
+#[derive(Clone)]
+pub struct BiOrdMap<T, S = DefaultHashBuilder, A: Allocator = Global> {
+    // As with IdOrdMap, items and the hash table for the second key are
+    // stored using `A`, but the B-Tree for the first key always uses the
+    // global allocator.
+    pub(super) items: ItemSet<T, A>,
+    // Invariant: the values (ItemIndex) in these tables are valid indexes into
+    // `items`, and are a 1:1 mapping.
+    pub(super) tables: BiOrdMapTables<S, A>,
+}
+
+impl<T: BiOrdItem, S: Default, A: Allocator + Default> Default
+    for BiOrdMap<T, S, A>
+{
+    fn default() -> Self {
+        Self {
+            items: ItemSet::new_in(A::default()),
+            tables: BiOrdMapTables::default(),
+        }
+    }
+}
+
+#[cfg(feature = "default-hasher")]
+impl<T: BiOrdItem> BiOrdMap<T> {
+    /// Creates a new, empty `BiOrdMap`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "default-hasher")] {
+    /// use iddqd::{BiOrdItem, BiOrdMap, bi_upcast};
+    ///
+    /// #[derive(Debug, PartialEq, Eq)]
+    /// struct Host {
+    ///     id: u32,
+    ///     name: String,
+    /// }
+    ///
+    /// impl BiOrdItem for Host {
+    ///     type K1<'a> = u32;
+    ///     type K2<'a> = &'a str;
+    ///
+    ///     fn key1(&self) -> Self::K1<'_> {
+    ///         self.id
+    ///     }
+    ///
+    ///     fn key2(&self) -> Self::K2<'_> {
+    ///         &self.name
+    ///     }
+    ///
+    ///     bi_upcast!();
+    /// }
+    ///
+    /// let map: BiOrdMap<Host> = BiOrdMap::new();
+    /// assert!(map.is_empty());
+    /// # }
+    /// ```
+    #[inline]
+    pub fn new() -> Self {
+        Self::with_hasher(DefaultHashBuilder::default())
+    }
+
+    /// Creates a new `BiOrdMap` with the given capacity.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "default-hasher")] {
+    /// use iddqd::{BiOrdItem, BiOrdMap, bi_upcast};
+    ///
+    /// #[derive(Debug, PartialEq, Eq)]
+    /// struct Host {
+    ///     id: u32,
+    ///     name: String,
+    /// }
+    ///
+    /// impl BiOrdItem for Host {
+    ///     type K1<'a> = u32;
+    ///     type K2<'a> = &'a str;
+    ///
+    ///     fn key1(&self) -> Self::K1<'_> {
+    ///         self.id
+    ///     }
+    ///
+    ///     fn key2(&self) -> Self::K2<'_> {
+    ///         &self.name
+    ///     }
+    ///
+    ///     bi_upcast!();
+    /// }
+    ///
+    /// let map: BiOrdMap<Host> = BiOrdMap::with_capacity(10);
+    /// assert!(map.capacity() >= 10);
+    /// assert!(map.is_empty());
+    /// # }
+    /// ```
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self::with_capacity_and_hasher(capacity, DefaultHashBuilder::default())
+    }
+}
+
+impl<T: BiOrdItem, S> BiOrdMap<T, S> {
+    /// Creates a new, empty `BiOrdMap` with the given hasher for the second
+    /// key.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use iddqd::{BiOrdItem, BiOrdMap, bi_upcast};
+    /// use std::collections::hash_map::RandomState;
+    ///
+    /// #[derive(Debug, PartialEq, Eq)]
+    /// struct Host {
+    ///     id: u32,
+    ///     name: String,
+    /// }
+    ///
+    /// impl BiOrdItem for Host {
+    ///     type K1<'a> = u32;
+    ///     type K2<'a> = &'a str;
+    ///
+    ///     fn key1(&self) -> Self::K1<'_> {
+    ///         self.id
+    ///     }
+    ///
+    ///     fn key2(&self) -> Self::K2<'_> {
+    ///         &self.name
+    ///     }
+    ///
+    ///     bi_upcast!();
+    /// }
+    ///
+    /// let map: BiOrdMap<Host, RandomState> =
+    ///     BiOrdMap::with_hasher(RandomState::new());
+    /// assert!(map.is_empty());
+    /// ```
+    pub const fn with_hasher(hasher: S) -> Self {
+        Self {
+            items: ItemSet::new(),
+            tables: BiOrdMapTables::with_hasher(hasher),
+        }
+    }
+
+    /// Creates a new `BiOrdMap` with the given capacity and hasher for the
+    /// second key.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use iddqd::{BiOrdItem, BiOrdMap, bi_upcast};
+    /// use std::collections::hash_map::RandomState;
+    ///
+    /// #[derive(Debug, PartialEq, Eq)]
+    /// struct Host {
+    ///     id: u32,
+    ///     name: String,
+    /// }
+    ///
+    /// impl BiOrdItem for Host {
+    ///     type K1<'a> = u32;
+    ///     type K2<'a> = &'a str;
+    ///
+    ///     fn key1(&self) -> Self::K1<'_> {
+    ///         self.id
+    ///     }
+    ///
+    ///     fn key2(&self) -> Self::K2<'_> {
+    ///         &self.name
+    ///     }
+    ///
+    ///     bi_upcast!();
+    /// }
+    ///
+    /// let map: BiOrdMap<Host, RandomState> =
+    ///     BiOrdMap::with_capacity_and_hasher(10, RandomState::new());
+    /// assert!(map.capacity() >= 10);
+    /// assert!(map.is_empty());
+    /// ```
+    pub fn with_capacity_and_hasher(capacity: usize, hasher: S) -> Self {
+        Self {
+            items: ItemSet::with_capacity_in(capacity, global_alloc()),
+            tables: BiOrdMapTables::with_capacity_and_hasher_in(
+                capacity,
+                hasher,
+                global_alloc(),
+            ),
+        }
+    }
+}
+
+#[cfg(feature = "default-hasher")]
+impl<T: BiOrdItem, A: Clone + Allocator> BiOrdMap<T, DefaultHashBuilder, A> {
+    /// Creates a new, empty `BiOrdMap` using the given allocator.
+    ///
+    /// Requires the `allocator-api2` feature to be enabled. Items and the hash
+    /// table for the second key are stored using `alloc`, while the B-Tree for
+    /// the first key always uses the global allocator.
+    ///
+    /// # Examples
+    ///
+    /// Using the [`bumpalo`](https://docs.rs/bumpalo) allocator:
+    ///
+    /// ```
+    /// # #[cfg(all(feature = "default-hasher", feature = "allocator-api2"))] {
+    /// use iddqd::{BiOrdItem, BiOrdMap, bi_upcast};
+    /// # use iddqd_test_utils::bumpalo;
+    ///
+    /// #[derive(Debug, PartialEq, Eq)]
+    /// struct Host {
+    ///     id: u32,
+    ///     name: String,
+    /// }
+    ///
+    /// impl BiOrdItem for Host {
+    ///     type K1<'a> = u32;
+    ///     type K2<'a> = &'a str;
+    ///
+    ///     fn key1(&self) -> Self::K1<'_> {
+    ///         self.id
+    ///     }
+    ///
+    ///     fn key2(&self) -> Self::K2<'_> {
+    ///         &self.name
+    ///     }
+    ///
+    ///     bi_upcast!();
+    /// }
+    ///
+    /// // Define a new allocator.
+    /// let bump = bumpalo::Bump::new();
+    /// // Create a new BiOrdMap using the allocator.
+    /// let map: BiOrdMap<Host, _, &bumpalo::Bump> = BiOrdMap::new_in(&bump);
+    /// assert!(map.is_empty());
+    /// # }
+    /// ```
+    pub fn new_in(alloc: A) -> Self {
+        Self::with_hasher_in(DefaultHashBuilder::default(), alloc)
+    }
+
+    /// Creates an empty `BiOrdMap` with the specified capacity using the given
+    /// allocator.
+    ///
+    /// Requires the `allocator-api2` feature to be enabled.
+    ///
+    /// # Examples
+    ///
+    /// Using the [`bumpalo`](https://docs.rs/bumpalo) allocator:
+    ///
+    /// ```
+    /// # #[cfg(all(feature = "default-hasher", feature = "allocator-api2"))] {
+    /// use iddqd::{BiOrdItem, BiOrdMap, bi_upcast};
+    /// # use iddqd_test_utils::bumpalo;
+    ///
+    /// #[derive(Debug, PartialEq, Eq)]
+    /// struct Host {
+    ///     id: u32,
+    ///     name: String,
+    /// }
+    ///
+    /// impl BiOrdItem for Host {
+    ///     type K1<'a> = u32;
+    ///     type K2<'a> = &'a str;
+    ///
+    ///     fn key1(&self) -> Self::K1<'_> {
+    ///         self.id
+    ///     }
+    ///
+    ///     fn key2(&self) -> Self::K2<'_> {
+    ///         &self.name
+    ///     }
+    ///
+    ///     bi_upcast!();
+    /// }
+    ///
+    /// // Define a new allocator.
+    /// let bump = bumpalo::Bump::new();
+    /// // Create a new BiOrdMap with capacity using the allocator.
+    /// let map: BiOrdMap<Host, _, &bumpalo::Bump> =
+    ///     BiOrdMap::with_capacity_in(10, &bump);
+    /// assert!(map.capacity() >= 10);
+    /// assert!(map.is_empty());
+    /// # }
+    /// ```
+    pub fn with_capacity_in(capacity: usize, alloc: A) -> Self {
+        Self::with_capacity_and_hasher_in(
+            capacity,
+            DefaultHashBuilder::default(),
+            alloc,
+        )
+    }
+}
+
+impl<T: BiOrdItem, S, A: Clone + Allocator> BiOrdMap<T, S, A> {
+    /// Creates a new, empty `BiOrdMap` with the given hasher for the second
+    /// key and the given allocator.
+    ///
+    /// Requires the `allocator-api2` feature to be enabled.
+    ///
+    /// # Examples
+    ///
+    /// Using the [`bumpalo`](https://docs.rs/bumpalo) allocator:
+    ///
+    /// ```
+    /// # #[cfg(feature = "allocator-api2")] {
+    /// use iddqd::{BiOrdItem, BiOrdMap, bi_upcast};
+    /// use std::collections::hash_map::RandomState;
+    /// # use iddqd_test_utils::bumpalo;
+    ///
+    /// #[derive(Debug, PartialEq, Eq)]
+    /// struct Host {
+    ///     id: u32,
+    ///     name: String,
+    /// }
+    ///
+    /// impl BiOrdItem for Host {
+    ///     type K1<'a> = u32;
+    ///     type K2<'a> = &'a str;
+    ///
+    ///     fn key1(&self) -> Self::K1<'_> {
+    ///         self.id
+    ///     }
+    ///
+    ///     fn key2(&self) -> Self::K2<'_> {
+    ///         &self.name
+    ///     }
+    ///
+    ///     bi_upcast!();
+    /// }
+    ///
+    /// // Define a new allocator.
+    /// let bump = bumpalo::Bump::new();
+    /// let hasher = RandomState::new();
+    /// // Create a new BiOrdMap with hasher using the allocator.
+    /// let map: BiOrdMap<Host, _, &bumpalo::Bump> =
+    ///     BiOrdMap::with_hasher_in(hasher, &bump);
+    /// assert!(map.is_empty());
+    /// # }
+    /// ```
+    pub fn with_hasher_in(hasher: S, alloc: A) -> Self {
+        Self {
+            items: ItemSet::new_in(alloc.clone()),
+            tables: BiOrdMapTables::with_hasher_in(hasher, alloc),
+        }
+    }
+
+    /// Creates a new, empty `BiOrdMap` with the given capacity, hasher for the
+    /// second key, and allocator.
+    ///
+    /// Requires the `allocator-api2` feature to be enabled.
+    ///
+    /// # Examples
+    ///
+    /// Using the [`bumpalo`](https://docs.rs/bumpalo) allocator:
+    ///
+    /// ```
+    /// # #[cfg(feature = "allocator-api2")] {
+    /// use iddqd::{BiOrdItem, BiOrdMap, bi_upcast};
+    /// use std::collections::hash_map::RandomState;
+    /// # use iddqd_test_utils::bumpalo;
+    ///
+    /// #[derive(Debug, PartialEq, Eq)]
+    /// struct Host {
+    ///     id: u32,
+    ///     name: String,
+    /// }
+    ///
+    /// impl BiOrdItem for Host {
+    ///     type K1<'a> = u32;
+    ///     type K2<'a> = &'a str;
+    ///
+    ///     fn key1(&self) -> Self::K1<'_> {
+    ///         self.id
+    ///     }
+    ///
+    ///     fn key2(&self) -> Self::K2<'_> {
+    ///         &self.name
+    ///     }
+    ///
+    ///     bi_upcast!();
+    /// }
+    ///
+    /// // Define a new allocator.
+    /// let bump = bumpalo::Bump::new();
+    /// let hasher = RandomState::new();
+    /// // Create a new BiOrdMap with capacity and hasher using the allocator.
+    /// let map: BiOrdMap<Host, _, &bumpalo::Bump> =
+    ///     BiOrdMap::with_capacity_and_hasher_in(10, hasher, &bump);
+    /// assert!(map.capacity() >= 10);
+    /// assert!(map.is_empty());
+    /// # }
+    /// ```
+    pub fn with_capacity_and_hasher_in(
+        capacity: usize,
+        hasher: S,
+        alloc: A,
+    ) -> Self {
+        Self {
+            items: ItemSet::with_capacity_in(capacity, alloc.clone()),
+            tables: BiOrdMapTables::with_capacity_and_hasher_in(
+                capacity, hasher, alloc,
+            ),
+        }
+    }
+}
+
+impl<T: BiOrdItem, S, A: Allocator> BiOrdMap<T, S, A> {
+    /// Returns the allocator.
+    ///
+    /// Requires the `allocator-api2` feature to be enabled.
+    ///
+    /// # Examples
+    ///
+    /// Using the [`bumpalo`](https://docs.rs/bumpalo) allocator:
+    ///
+    /// ```
+    /// # #[cfg(all(feature = "default-hasher", feature = "allocator-api2"))] {
+    /// use iddqd::{BiOrdItem, BiOrdMap, bi_upcast};
+    /// # use iddqd_test_utils::bumpalo;
+    ///
+    /// #[derive(Debug, PartialEq, Eq)]
+    /// struct Host {
+    ///     id: u32,
+    ///     name: String,
+    /// }
+    ///
+    /// impl BiOrdItem for Host {
+    ///     type K1<'a> = u32;
+    ///     type K2<'a> = &'a str;
+    ///
+    ///     fn key1(&self) -> Self::K1<'_> {
+    ///         self.id
+    ///     }
+    ///
+    ///     fn key2(&self) -> Self::K2<'_> {
+    ///         &self.name
+    ///     }
+    ///
+    ///     bi_upcast!();
+    /// }
+    ///
+    /// // Define a new allocator.
+    /// let bump = bumpalo::Bump::new();
+    /// // Create a new BiOrdMap using the allocator.
+    /// let map: BiOrdMap<Host, _, &bumpalo::Bump> = BiOrdMap::new_in(&bump);
+    /// let _allocator = map.allocator();
+    /// # }
+    /// ```
+    #[inline]
+    pub fn allocator(&self) -> &A {
+        self.items.allocator()
+    }
+
+    /// Returns the currently allocated capacity of the map.
+    pub fn capacity(&self) -> usize {
+        self.items.capacity()
+    }
+
+    /// Reserves capacity for at least `additional` more items to be inserted
+    /// in the `BiOrdMap`.
+    ///
+    /// As with [`IdOrdMap::reserve`], this reserves space in the item storage
+    /// and in the hash table for the second key, but not in the B-Tree for
+    /// the first key.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the new capacity overflows [`isize::MAX`] bytes.
+    ///
+    /// [`IdOrdMap::reserve`]: crate::IdOrdMap::reserve
+    pub fn reserve(&mut self, additional: usize) {
+        self.items.reserve(additional);
+        self.tables.k2_to_item.reserve(additional);
+    }
+
+    /// Returns true if the map is empty.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// Returns the number of items in the map.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    /// Clears the map, removing all items.
+    pub fn clear(&mut self) {
+        // As with the other maps, clear the indexes before dropping items, so
+        // that a panicking `Drop` can't leave the tables pointing to removed
+        // item slots.
+        self.tables.k1_to_item.clear();
+        self.tables.k2_to_item.clear();
+        self.items.clear();
+    }
+
+    /// Iterates over the items in the map, in order of the first key.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "default-hasher")] {
+    /// use iddqd::{BiOrdItem, BiOrdMap, bi_upcast};
+    ///
+    /// #[derive(Debug, PartialEq, Eq)]
+    /// struct Host {
+    ///     id: u32,
+    ///     name: String,
+    /// }
+    ///
+    /// impl BiOrdItem for Host {
+    ///     type K1<'a> = u32;
+    ///     type K2<'a> = &'a str;
+    ///
+    ///     fn key1(&self) -> Self::K1<'_> {
+    ///         self.id
+    ///     }
+    ///
+    ///     fn key2(&self) -> Self::K2<'_> {
+    ///         &self.name
+    ///     }
+    ///
+    ///     bi_upcast!();
+    /// }
+    ///
+    /// let mut map = BiOrdMap::new();
+    /// map.insert_unique(Host { id: 2, name: "db".to_string() }).unwrap();
+    /// map.insert_unique(Host { id: 1, name: "web".to_string() }).unwrap();
+    ///
+    /// let ids: Vec<u32> = map.iter().map(|host| host.id).collect();
+    /// assert_eq!(ids, [1, 2]);
+    /// # }
+    /// ```
+    #[inline]
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter::new(&self.items, &self.tables)
+    }
+
+    /// Iterates over the items whose first keys fall within `range`, in order
+    /// of the first key.
+    ///
+    /// Unlike [`BTreeMap::range`], this doesn't panic if the start of the
+    /// range is greater than the end: the result is simply empty.
+    ///
+    /// [`BTreeMap::range`]: std::collections::BTreeMap::range
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "default-hasher")] {
+    /// use iddqd::{BiOrdItem, BiOrdMap, bi_upcast};
+    ///
+    /// #[derive(Debug, PartialEq, Eq)]
+    /// struct Host {
+    ///     id: u32,
+    ///     name: String,
+    /// }
+    ///
+    /// impl BiOrdItem for Host {
+    ///     type K1<'a> = u32;
+    ///     type K2<'a> = &'a str;
+    ///
+    ///     fn key1(&self) -> Self::K1<'_> {
+    ///         self.id
+    ///     }
+    ///
+    ///     fn key2(&self) -> Self::K2<'_> {
+    ///         &self.name
+    ///     }
+    ///
+    ///     bi_upcast!();
+    /// }
+    ///
+    /// let mut map = BiOrdMap::new();
+    /// for (id, name) in [(1, "web"), (5, "db"), (9, "cache")] {
+    ///     map.insert_unique(Host { id, name: name.to_string() }).unwrap();
+    /// }
+    ///
+    /// let names: Vec<_> =
+    ///     map.range(2..=9).map(|host| host.name.as_str()).collect();
+    /// assert_eq!(names, ["db", "cache"]);
+    /// # }
+    /// ```
+    pub fn range<'a, Q, R>(&'a self, range: R) -> Range<'a, T>
+    where
+        Q: ?Sized + Comparable<T::K1<'a>>,
+        R: RangeBounds<Q>,
+    {
+        let iter = self
+            .tables
+            .k1_to_item
+            .range(range, |index| self.items[index].key1());
+        Range::new(&self.items, iter)
+    }
+
+    /// Returns true if the map contains the given `key1`.
+    pub fn contains_key1<'a, Q>(&'a self, key1: &Q) -> bool
+    where
+        Q: ?Sized + Comparable<T::K1<'a>>,
+    {
+        self.find1_index(key1).is_some()
+    }
+
+    /// Gets a reference to the value associated with the given `key1`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "default-hasher")] {
+    /// use iddqd::{BiOrdItem, BiOrdMap, bi_upcast};
+    ///
+    /// #[derive(Debug, PartialEq, Eq)]
+    /// struct Host {
+    ///     id: u32,
+    ///     name: String,
+    /// }
+    ///
+    /// impl BiOrdItem for Host {
+    ///     type K1<'a> = u32;
+    ///     type K2<'a> = &'a str;
+    ///
+    ///     fn key1(&self) -> Self::K1<'_> {
+    ///         self.id
+    ///     }
+    ///
+    ///     fn key2(&self) -> Self::K2<'_> {
+    ///         &self.name
+    ///     }
+    ///
+    ///     bi_upcast!();
+    /// }
+    ///
+    /// let mut map = BiOrdMap::new();
+    /// map.insert_unique(Host { id: 2, name: "db".to_string() }).unwrap();
+    /// map.insert_unique(Host { id: 1, name: "web".to_string() }).unwrap();
+    ///
+    /// assert_eq!(map.get1(&1).unwrap().name, "web");
+    /// assert!(map.get1(&3).is_none());
+    /// # }
+    /// ```
+    pub fn get1<'a, Q>(&'a self, key1: &Q) -> Option<&'a T>
+    where
+        Q: ?Sized + Comparable<T::K1<'a>>,
+    {
+        self.find1_index(key1).map(|ix| &self.items[ix])
+    }
+
+    /// Removes an item from the map by its `key1`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "default-hasher")] {
+    /// use iddqd::{BiOrdItem, BiOrdMap, bi_upcast};
+    ///
+    /// #[derive(Debug, PartialEq, Eq)]
+    /// struct Host {
+    ///     id: u32,
+    ///     name: String,
+    /// }
+    ///
+    /// impl BiOrdItem for Host {
+    ///     type K1<'a> = u32;
+    ///     type K2<'a> = &'a str;
+    ///
+    ///     fn key1(&self) -> Self::K1<'_> {
+    ///         self.id
+    ///     }
+    ///
+    ///     fn key2(&self) -> Self::K2<'_> {
+    ///         &self.name
+    ///     }
+    ///
+    ///     bi_upcast!();
+    /// }
+    ///
+    /// let mut map = BiOrdMap::new();
+    /// map.insert_unique(Host { id: 2, name: "db".to_string() }).unwrap();
+    /// map.insert_unique(Host { id: 1, name: "web".to_string() }).unwrap();
+    ///
+    /// let removed = map.remove1(&1).unwrap();
+    /// assert_eq!(removed.name, "web");
+    /// assert!(map.get2("web").is_none());
+    /// assert_eq!(map.len(), 1);
+    /// # }
+    /// ```
+    pub fn remove1<'a, Q>(&'a mut self, key1: &Q) -> Option<T>
+    where
+        Q: ?Sized + Comparable<T::K1<'a>>,
+    {
+        let (dormant_map, remove_index) = {
+            let (map, dormant_map) = DormantMutRef::new(self);
+            let remove_index = map.find1_index(key1)?;
+            (dormant_map, remove_index)
+        };
+
+        // SAFETY: `map` is not used after this point.
+        let awakened_map = unsafe { dormant_map.awaken() };
+        awakened_map.remove_by_index(remove_index)
+    }
+
+    fn find1_index<'a, Q>(&'a self, k: &Q) -> Option<ItemIndex>
+    where
+        Q: ?Sized + Comparable<T::K1<'a>>,
+    {
+        self.tables.k1_to_item.find_index(k, |index| self.items[index].key1())
+    }
+}
+
+impl<T: BiOrdItem, S: BuildHasher, A: Allocator> BiOrdMap<T, S, A> {
+    /// Checks general invariants of the map.
+    ///
+    /// The code below always upholds these invariants, but it's useful to have
+    /// an explicit check for tests.
+    #[doc(hidden)]
+    pub fn validate(
+        &self,
+        compactness: ValidateCompact,
+    ) -> Result<(), ValidationError> {
+        self.items.validate(compactness)?;
+        self.tables.validate(self.len(), compactness)?;
+
+        // Check that each item can be found by both of its keys.
+        for (ix, item) in self.items.iter() {
+            let Some(ix1) = self.find1_index(&item.key1()) else {
+                return Err(ValidationError::general(format!(
+                    "item at index {ix} has no key1 index"
+                )));
+            };
+            let Some(ix2) = self.find2_index(&item.key2()) else {
+                return Err(ValidationError::general(format!(
+                    "item at index {ix} has no key2 index"
+                )));
+            };
+
+            if ix1 != ix || ix2 != ix {
+                return Err(ValidationError::general(format!(
+                    "item at index {ix} has inconsistent indexes: {ix1}/{ix2}"
+                )));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Inserts a value into the map, returning an error if any duplicates
+    /// were added.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "default-hasher")] {
+    /// use iddqd::{BiOrdItem, BiOrdMap, bi_upcast};
+    ///
+    /// #[derive(Debug, PartialEq, Eq)]
+    /// struct Host {
+    ///     id: u32,
+    ///     name: String,
+    /// }
+    ///
+    /// impl BiOrdItem for Host {
+    ///     type K1<'a> = u32;
+    ///     type K2<'a> = &'a str;
+    ///
+    ///     fn key1(&self) -> Self::K1<'_> {
+    ///         self.id
+    ///     }
+    ///
+    ///     fn key2(&self) -> Self::K2<'_> {
+    ///         &self.name
+    ///     }
+    ///
+    ///     bi_upcast!();
+    /// }
+    ///
+    /// let mut map = BiOrdMap::new();
+    /// map.insert_unique(Host { id: 2, name: "db".to_string() }).unwrap();
+    /// map.insert_unique(Host { id: 1, name: "web".to_string() }).unwrap();
+    ///
+    /// // Duplicate first key
+    /// assert!(
+    ///     map.insert_unique(Host { id: 1, name: "mail".to_string() }).is_err()
+    /// );
+    /// // Duplicate second key
+    /// assert!(map.insert_unique(Host { id: 3, name: "db".to_string() }).is_err());
+    /// assert_eq!(map.len(), 2);
+    /// # }
+    /// ```
+    pub fn insert_unique(
+        &mut self,
+        value: T,
+    ) -> Result<(), DuplicateItem<T, &T>> {
+        match self.insert_unique_or_dup_indexes(value) {
+            Ok(_) => Ok(()),
+            Err((value, duplicates)) => Err(DuplicateItem::__internal_new(
+                value,
+                duplicates.iter().map(|ix| &self.items[*ix]).collect(),
+            )),
+        }
+    }
+
+    /// Inserts a value into the map, removing and returning any conflicting
+    /// items.
+    ///
+    /// The new value can conflict with up to two existing items: one by the
+    /// first key and one by the second.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "default-hasher")] {
+    /// use iddqd::{BiOrdItem, BiOrdMap, bi_upcast};
+    ///
+    /// #[derive(Debug, PartialEq, Eq)]
+    /// struct Host {
+    ///     id: u32,
+    ///     name: String,
+    /// }
+    ///
+    /// impl BiOrdItem for Host {
+    ///     type K1<'a> = u32;
+    ///     type K2<'a> = &'a str;
+    ///
+    ///     fn key1(&self) -> Self::K1<'_> {
+    ///         self.id
+    ///     }
+    ///
+    ///     fn key2(&self) -> Self::K2<'_> {
+    ///         &self.name
+    ///     }
+    ///
+    ///     bi_upcast!();
+    /// }
+    ///
+    /// let mut map = BiOrdMap::new();
+    /// map.insert_unique(Host { id: 1, name: "web".to_string() }).unwrap();
+    /// map.insert_unique(Host { id: 2, name: "db".to_string() }).unwrap();
+    ///
+    /// // Conflicts with "web" by the first key and "db" by the second.
+    /// let removed = map.insert_overwrite(Host { id: 1, name: "db".to_string() });
+    /// assert_eq!(removed.len(), 2);
+    /// assert_eq!(map.len(), 1);
+    /// assert_eq!(map.get2("db").unwrap().id, 1);
+    /// # }
+    /// ```
+    #[doc(alias = "insert")]
+    pub fn insert_overwrite(&mut self, value: T) -> Vec<T> {
+        // Look up both keys before changing the map, so that a panic in user
+        // code leaves the map unchanged.
+        let index1 = self.find1_index(&value.key1());
+        let mut index2 = self.find2_index(&value.key2());
+        if index1 == index2 {
+            index2 = None;
+        }
+
+        let duplicates = [index1, index2]
+            .into_iter()
+            .flatten()
+            .map(|index| {
+                self.remove_by_index(index).expect("index was found above")
+            })
+            .collect();
+
+        if self.insert_unique_or_dup_indexes(value).is_err() {
+            panic!("conflicting items were removed above");
+        }
+
+        duplicates
+    }
+
+    /// Extends the map with the contents of an iterator, handling duplicates
+    /// according to `mode`.
+    ///
+    /// With [`ExtendMode::Overwrite`], this behaves like calling
+    /// [`BiOrdMap::insert_overwrite`] for each item, and never returns an
+    /// error. This is what the [`Extend`] implementation does.
+    ///
+    /// With [`ExtendMode::Unique`], this behaves like calling
+    /// [`BiOrdMap::insert_unique`] for each item, stopping at the first item
+    /// that conflicts with an existing one. Items before the conflicting one
+    /// remain in the map, and the rest of the iterator is dropped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "default-hasher")] {
+    /// use iddqd::{BiOrdItem, BiOrdMap, ExtendMode, bi_upcast};
+    ///
+    /// #[derive(Debug, PartialEq, Eq)]
+    /// struct Host {
+    ///     id: u32,
+    ///     name: String,
+    /// }
+    ///
+    /// impl BiOrdItem for Host {
+    ///     type K1<'a> = u32;
+    ///     type K2<'a> = &'a str;
+    ///
+    ///     fn key1(&self) -> Self::K1<'_> {
+    ///         self.id
+    ///     }
+    ///
+    ///     fn key2(&self) -> Self::K2<'_> {
+    ///         &self.name
+    ///     }
+    ///
+    ///     bi_upcast!();
+    /// }
+    ///
+    /// let mut map = BiOrdMap::new();
+    /// map.insert_unique(Host { id: 1, name: "web".to_string() }).unwrap();
+    ///
+    /// // Unique mode stops at the first item that conflicts on either key.
+    /// let new_items = vec![
+    ///     Host { id: 2, name: "db".to_string() },
+    ///     Host { id: 3, name: "web".to_string() },
+    ///     Host { id: 4, name: "mail".to_string() },
+    /// ];
+    /// let err = map.extend_with(new_items, ExtendMode::Unique).unwrap_err();
+    /// assert_eq!(err.new_item().id, 3);
+    /// assert_eq!(err.duplicates()[0].id, 1);
+    /// assert_eq!(map.len(), 2);
+    /// assert!(!map.contains_key1(&4));
+    ///
+    /// // Overwrite mode replaces duplicates.
+    /// let new_items = vec![Host { id: 3, name: "web".to_string() }];
+    /// map.extend_with(new_items, ExtendMode::Overwrite).unwrap();
+    /// assert_eq!(map.get2("web").unwrap().id, 3);
+    /// assert!(!map.contains_key1(&1));
+    /// # }
+    /// ```
+    pub fn extend_with<I: IntoIterator<Item = T>>(
+        &mut self,
+        iter: I,
+        mode: ExtendMode,
+    ) -> Result<(), DuplicateItem<T, &T>> {
+        // Keys may already be present in the map, or multiple times in the
+        // iterator. Reserve the entire hint lower bound if the map is empty.
+        // Otherwise reserve half the hint (rounded up), so the map will only
+        // resize twice in the worst case.
+        let iter = iter.into_iter();
+        let reserve = if self.is_empty() {
+            iter.size_hint().0
+        } else {
+            iter.size_hint().0.div_ceil(2)
+        };
+        self.reserve(reserve);
+        match mode {
+            ExtendMode::Overwrite => {
+                for item in iter {
+                    self.insert_overwrite(item);
+                }
+            }
+            ExtendMode::Unique => {
+                for item in iter {
+                    if let Err((item, duplicates)) =
+                        self.insert_unique_or_dup_indexes(item)
+                    {
+                        return Err(DuplicateItem::__internal_new(
+                            item,
+                            duplicates
+                                .iter()
+                                .map(|ix| &self.items[*ix])
+                                .collect(),
+                        ));
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn insert_unique_or_dup_indexes(
+        &mut self,
+        value: T,
+    ) -> Result<ItemIndex, (T, BTreeSet<ItemIndex>)> {
+        let mut duplicates = BTreeSet::new();
+
+        // Check for duplicates *before* inserting the new item, because we
+        // don't want to partially insert the new item and then have to roll
+        // back.
+        let hash2 = {
+            let k1 = value.key1();
+            let k2 = value.key2();
+            if let Some(index) = self.find1_index(&k1) {
+                duplicates.insert(index);
+            }
+            if let Some(index) = self.find2_index(&k2) {
+                duplicates.insert(index);
+            }
+            self.tables.k2_to_item.compute_hash(&self.tables.state, k2)
+        };
+
+        if !duplicates.is_empty() {
+            return Err((value, duplicates));
+        }
+
+        // Reserve space in the hash table first, so that the only fallible
+        // step after the item set push is the B-Tree commit. (See
+        // `IdOrdMap::insert_known_unique_impl` for the ordering rationale.)
+        self.tables.k2_to_item.reserve(1);
+
+        let grow_handle = self.items.assert_can_grow();
+        let next_index = grow_handle.next_index();
+        let key1 = value.key1();
+        let insert =
+            self.tables.k1_to_item.prepare_insert(next_index, &key1, |index| {
+                grow_handle[index].key1()
+            });
+        drop(key1);
+
+        grow_handle.insert(value);
+        insert.insert();
+        self.tables.k2_to_item.insert_prehashed_unchecked(hash2, next_index);
+
+        Ok(next_index)
+    }
+
+    /// Returns true if the map contains the given `key2`.
+    pub fn contains_key2<'a, Q>(&'a self, key2: &Q) -> bool
+    where
+        Q: ?Sized + Hash + Equivalent<T::K2<'a>>,
+    {
+        self.find2_index(key2).is_some()
+    }
+
+    /// Gets a reference to the value associated with the given `key2`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "default-hasher")] {
+    /// use iddqd::{BiOrdItem, BiOrdMap, bi_upcast};
+    ///
+    /// #[derive(Debug, PartialEq, Eq)]
+    /// struct Host {
+    ///     id: u32,
+    ///     name: String,
+    /// }
+    ///
+    /// impl BiOrdItem for Host {
+    ///     type K1<'a> = u32;
+    ///     type K2<'a> = &'a str;
+    ///
+    ///     fn key1(&self) -> Self::K1<'_> {
+    ///         self.id
+    ///     }
+    ///
+    ///     fn key2(&self) -> Self::K2<'_> {
+    ///         &self.name
+    ///     }
+    ///
+    ///     bi_upcast!();
+    /// }
+    ///
+    /// let mut map = BiOrdMap::new();
+    /// map.insert_unique(Host { id: 2, name: "db".to_string() }).unwrap();
+    /// map.insert_unique(Host { id: 1, name: "web".to_string() }).unwrap();
+    ///
+    /// assert_eq!(map.get2("db").unwrap().id, 2);
+    /// assert!(map.get2("mail").is_none());
+    /// # }
+    /// ```
+    pub fn get2<'a, Q>(&'a self, key2: &Q) -> Option<&'a T>
+    where
+        Q: ?Sized + Hash + Equivalent<T::K2<'a>>,
+    {
+        self.find2_index(key2).map(|ix| &self.items[ix])
+    }
+
+    /// Removes an item from the map by its `key2`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "default-hasher")] {
+    /// use iddqd::{BiOrdItem, BiOrdMap, bi_upcast};
+    ///
+    /// #[derive(Debug, PartialEq, Eq)]
+    /// struct Host {
+    ///     id: u32,
+    ///     name: String,
+    /// }
+    ///
+    /// impl BiOrdItem for Host {
+    ///     type K1<'a> = u32;
+    ///     type K2<'a> = &'a str;
+    ///
+    ///     fn key1(&self) -> Self::K1<'_> {
+    ///         self.id
+    ///     }
+    ///
+    ///     fn key2(&self) -> Self::K2<'_> {
+    ///         &self.name
+    ///     }
+    ///
+    ///     bi_upcast!();
+    /// }
+    ///
+    /// let mut map = BiOrdMap::new();
+    /// map.insert_unique(Host { id: 2, name: "db".to_string() }).unwrap();
+    /// map.insert_unique(Host { id: 1, name: "web".to_string() }).unwrap();
+    ///
+    /// let removed = map.remove2("db").unwrap();
+    /// assert_eq!(removed.id, 2);
+    /// assert!(map.get1(&2).is_none());
+    /// assert_eq!(map.len(), 1);
+    /// # }
+    /// ```
+    pub fn remove2<'a, Q>(&'a mut self, key2: &Q) -> Option<T>
+    where
+        Q: ?Sized + Hash + Equivalent<T::K2<'a>>,
+    {
+        let (dormant_map, remove_index) = {
+            let (map, dormant_map) = DormantMutRef::new(self);
+            let remove_index = map.find2_index(key2)?;
+            (dormant_map, remove_index)
+        };
+
+        // SAFETY: `map` is not used after this point.
+        let awakened_map = unsafe { dormant_map.awaken() };
+        awakened_map.remove_by_index(remove_index)
+    }
+
+    fn find2_index<'a, Q>(&'a self, k: &Q) -> Option<ItemIndex>
+    where
+        Q: ?Sized + Hash + Equivalent<T::K2<'a>>,
+    {
+        self.tables
+            .k2_to_item
+            .find_index(&self.tables.state, k, |index| self.items[index].key2())
+    }
+}
+
+impl<T: BiOrdItem, S: Clone + BuildHasher, A: Allocator> BiOrdMap<T, S, A> {
+    /// Gets a mutable reference to the value associated with the given
+    /// `key1`.
+    ///
+    /// The first key type must implement [`Hash`], so that the returned
+    /// [`RefMut`] can check that neither key was changed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "default-hasher")] {
+    /// use iddqd::{BiOrdItem, BiOrdMap, bi_upcast};
+    ///
+    /// #[derive(Debug, PartialEq, Eq)]
+    /// struct Host {
+    ///     id: u32,
+    ///     name: String,
+    ///     port: u16,
+    /// }
+    ///
+    /// impl BiOrdItem for Host {
+    ///     type K1<'a> = u32;
+    ///     type K2<'a> = &'a str;
+    ///
+    ///     fn key1(&self) -> Self::K1<'_> {
+    ///         self.id
+    ///     }
+    ///
+    ///     fn key2(&self) -> Self::K2<'_> {
+    ///         &self.name
+    ///     }
+    ///
+    ///     bi_upcast!();
+    /// }
+    ///
+    /// let mut map = BiOrdMap::new();
+    /// map.insert_unique(Host { id: 1, name: "web".to_string(), port: 80 })
+    ///     .unwrap();
+    ///
+    /// if let Some(mut host) = map.get1_mut(&1) {
+    ///     host.port = 443;
+    /// }
+    /// if let Some(mut host) = map.get2_mut("web") {
+    ///     host.port += 1;
+    /// }
+    ///
+    /// assert_eq!(map.get1(&1).unwrap().port, 444);
+    /// # }
+    /// ```
+    pub fn get1_mut<'a, Q>(&'a mut self, key1: &Q) -> Option<RefMut<'a, T, S>>
+    where
+        Q: ?Sized + Comparable<T::K1<'a>>,
+        T::K1<'a>: Hash,
+    {
+        let (dormant_map, index) = {
+            let (map, dormant_map) = DormantMutRef::new(self);
+            let index = map.find1_index(key1)?;
+            (dormant_map, index)
+        };
+
+        // SAFETY: `map` is not used after this point.
+        let awakened_map = unsafe { dormant_map.awaken() };
+        awakened_map.get_by_index_mut(index)
+    }
+
+    /// Gets a mutable reference to the value associated with the given
+    /// `key2`.
+    ///
+    /// The first key type must implement [`Hash`], so that the returned
+    /// [`RefMut`] can check that neither key was changed.
+    pub fn get2_mut<'a, Q>(&'a mut self, key2: &Q) -> Option<RefMut<'a, T, S>>
+    where
+        Q: ?Sized + Hash + Equivalent<T::K2<'a>>,
+        T::K1<'a>: Hash,
+    {
+        let (dormant_map, index) = {
+            let (map, dormant_map) = DormantMutRef::new(self);
+            let index = map.find2_index(key2)?;
+            (dormant_map, index)
+        };
+
+        // SAFETY: `map` is not used after this point.
+        let awakened_map = unsafe { dormant_map.awaken() };
+        awakened_map.get_by_index_mut(index)
+    }
+
+    fn get_by_index_mut<'a>(
+        &'a mut self,
+        index: ItemIndex,
+    ) -> Option<RefMut<'a, T, S>>
+    where
+        T::K1<'a>: Hash,
+    {
+        let k1_state = self.tables.k1_to_item.state().clone();
+        let state = self.tables.state.clone();
+        let (hashes, dormant) = {
+            let item: &'a mut T = self.items.get_mut(index)?;
+            let (item, dormant) = DormantMutRef::new(item);
+            let hashes = self.tables.make_hashes(item);
+            (hashes, dormant)
+        };
+
+        // SAFETY: item is no longer used after the above point.
+        let item = unsafe { dormant.awaken() };
+        Some(RefMut::new(k1_state, state, hashes, item))
+    }
+}
+
+impl<T: BiOrdItem, S, A: Allocator> BiOrdMap<T, S, A> {
+    fn remove_by_index(&mut self, remove_index: ItemIndex) -> Option<T> {
+        // As with IdOrdMap and BiHashMap, locate both table entries while
+        // `self.items` still holds the value, falling back to exact-index
+        // cleanup if a lookup misses.
+        let item = self.items.get(remove_index)?;
+        let key1 = item.key1();
+        let remove = self.tables.k1_to_item.prepare_remove(
+            remove_index,
+            &key1,
+            |index| self.items[index].key1(),
+        );
+        drop(key1);
+        if !remove.remove() {
+            self.tables.k1_to_item.remove_exact(remove_index);
+        }
+
+        self.tables.k2_to_item.remove_by_index(remove_index);
+
+        Some(
+            self.items
+                .remove(remove_index)
+                .expect("items[remove_index] was Occupied above"),
+        )
+    }
+}
+
+impl<'a, T: BiOrdItem, S, A: Allocator> fmt::Debug for BiOrdMap<T, S, A>
+where
+    T: fmt::Debug,
+    T::K1<'a>: fmt::Debug,
+    T: 'a,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut map = f.debug_map();
+
+        for item in self.iter() {
+            let key = item.key1();
+
+            // SAFETY:
+            //
+            // * Lifetime extension: for a type T and two lifetime params 'a and
+            //   'b, T<'a> and T<'b> aren't guaranteed to have the same layout,
+            //   but (a) that is true today and (b) it would be shocking and
+            //   break half the Rust ecosystem if that were to change in the
+            //   future.
+            // * We only use key within the scope of this block before immediately
+            //   dropping it. In particular, map.entry calls key.fmt() without
+            //   holding a reference to it.
+            let key: T::K1<'a> =
+                unsafe { core::mem::transmute::<T::K1<'_>, T::K1<'a>>(key) };
+
+            map.entry(&key, &item);
+        }
+        map.finish()
+    }
+}
+
+impl<T: BiOrdItem + PartialEq, S, A: Allocator> PartialEq
+    for BiOrdMap<T, S, A>
+{
+    fn eq(&self, other: &Self) -> bool {
+        // Items are stored in order of the first key, so we can just walk over
+        // both iterators.
+        if self.items.len() != other.items.len() {
+            return false;
+        }
+
+        self.iter().zip(other.iter()).all(|(item1, item2)| item1 == item2)
+    }
+}
+
+// The Eq bound on T ensures that the BiOrdMap forms an equivalence class.
+impl<T: BiOrdItem + Eq, S, A: Allocator> Eq for BiOrdMap<T, S, A> {}
+
+/// Items are hashed in order of their first key, consistent with the
+/// `PartialEq` implementation.
+impl<T: BiOrdItem + Hash, S, A: Allocator> Hash for BiOrdMap<T, S, A> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        state.write_usize(self.len());
+        for item in self {
+            item.hash(state);
+        }
+    }
+}
+
+impl<'a, T: BiOrdItem, S, A: Allocator> IntoIterator for &'a BiOrdMap<T, S, A> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<T: BiOrdItem, S, A: Allocator> IntoIterator for BiOrdMap<T, S, A> {
+    type Item = T;
+    type IntoIter = IntoIter<T, A>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter::new(self.items, self.tables)
+    }
+}
+
+impl<T: BiOrdItem, S: BuildHasher, A: Allocator> Extend<T>
+    for BiOrdMap<T, S, A>
+{
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        // Overwrite mode never returns an error.
+        let _ = self.extend_with(iter, ExtendMode::Overwrite);
+    }
+}
+
+/// The `FromIterator` implementation for `BiOrdMap` overwrites duplicate
+/// items.
+impl<T: BiOrdItem, S: Default + BuildHasher, A: Allocator + Default>
+    FromIterator<T> for BiOrdMap<T, S, A>
+{
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut map = BiOrdMap::default();
+        map.extend(iter);
+        map
+    }
+}
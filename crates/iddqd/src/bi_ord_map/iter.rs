@@ -0,0 +1,159 @@
+use super::{BiOrdItem, tables::BiOrdMapTables};
+use crate::support::{
+    ItemIndex,
+    alloc::{Allocator, Global},
+    btree_table,
+    item_set::{ConsumingItemSet, ItemSet, ItemSlots},
+};
+use core::iter::FusedIterator;
+
+/// An iterator over the elements of a [`BiOrdMap`] by shared reference.
+///
+/// Created by [`BiOrdMap::iter`], and ordered by the first key.
+///
+/// [`BiOrdMap`]: crate::BiOrdMap
+/// [`BiOrdMap::iter`]: crate::BiOrdMap::iter
+#[derive(Clone, Debug)]
+pub struct Iter<'a, T: BiOrdItem> {
+    items: ItemSlots<'a, T>,
+    iter: btree_table::Iter<'a>,
+}
+
+impl<'a, T: BiOrdItem> Iter<'a, T> {
+    pub(super) fn new<S, A: Allocator>(
+        items: &'a ItemSet<T, A>,
+        tables: &'a BiOrdMapTables<S, A>,
+    ) -> Self {
+        Self { items: items.slots(), iter: tables.k1_to_item.iter() }
+    }
+}
+
+impl<'a, T: BiOrdItem> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let index = self.iter.next()?;
+        Some(self.items.item(index))
+    }
+}
+
+impl<T: BiOrdItem> ExactSizeIterator for Iter<'_, T> {
+    #[inline]
+    fn len(&self) -> usize {
+        self.iter.len()
+    }
+}
+
+// btree_set::Iter is a FusedIterator, so Iter is as well.
+impl<T: BiOrdItem> FusedIterator for Iter<'_, T> {}
+
+/// An iterator over a sub-range of the elements of a [`BiOrdMap`] by shared
+/// reference.
+///
+/// Created by [`BiOrdMap::range`], and ordered by the first key. The iterator
+/// is double-ended, so it can also walk the range from the top down.
+///
+/// [`BiOrdMap`]: crate::BiOrdMap
+/// [`BiOrdMap::range`]: crate::BiOrdMap::range
+#[derive(Clone, Debug)]
+pub struct Range<'a, T: BiOrdItem> {
+    items: ItemSlots<'a, T>,
+    iter: btree_table::Range<'a>,
+}
+
+impl<'a, T: BiOrdItem> Range<'a, T> {
+    pub(super) fn new<A: Allocator>(
+        items: &'a ItemSet<T, A>,
+        iter: btree_table::Range<'a>,
+    ) -> Self {
+        Self { items: items.slots(), iter }
+    }
+}
+
+impl<'a, T: BiOrdItem> Iterator for Range<'a, T> {
+    type Item = &'a T;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let index = self.iter.next()?;
+        Some(self.items.item(index))
+    }
+}
+
+impl<T: BiOrdItem> DoubleEndedIterator for Range<'_, T> {
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let index = self.iter.next_back()?;
+        Some(self.items.item(index))
+    }
+}
+
+// btree_map::Range is a FusedIterator, so Range is as well.
+impl<T: BiOrdItem> FusedIterator for Range<'_, T> {}
+
+/// An iterator over the elements of a [`BiOrdMap`] by ownership.
+///
+/// Created by [`BiOrdMap::into_iter`], and ordered by the first key.
+///
+/// [`BiOrdMap`]: crate::BiOrdMap
+/// [`BiOrdMap::into_iter`]: crate::BiOrdMap::into_iter
+#[derive(Debug)]
+pub struct IntoIter<T: BiOrdItem, A: Allocator = Global> {
+    items: ConsumingItemSet<T, A>,
+    iter: btree_table::IntoIter,
+}
+
+impl<T: BiOrdItem, A: Allocator> IntoIter<T, A> {
+    pub(super) fn new<S>(
+        items: ItemSet<T, A>,
+        tables: BiOrdMapTables<S, A>,
+    ) -> Self {
+        Self {
+            items: items.into_consuming(),
+            iter: tables.k1_to_item.into_iter(),
+        }
+    }
+
+    fn take(&mut self, index: ItemIndex) -> T {
+        // As with `id_ord_map::IntoIter`, the B-tree's indexes are never
+        // revisited, so we can take directly from the consuming view.
+        self.items
+            .take(index)
+            .unwrap_or_else(|| panic!("index {index} not found in items"))
+    }
+}
+
+impl<T: BiOrdItem, A: Allocator> Iterator for IntoIter<T, A> {
+    type Item = T;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let index = self.iter.next()?;
+        Some(self.take(index))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.len();
+        (len, Some(len))
+    }
+}
+
+impl<T: BiOrdItem, A: Allocator> DoubleEndedIterator for IntoIter<T, A> {
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let index = self.iter.next_back()?;
+        Some(self.take(index))
+    }
+}
+
+impl<T: BiOrdItem, A: Allocator> ExactSizeIterator for IntoIter<T, A> {
+    #[inline]
+    fn len(&self) -> usize {
+        self.iter.len()
+    }
+}
+
+// btree_map::IntoIter is a FusedIterator, so IntoIter is as well.
+impl<T: BiOrdItem, A: Allocator> FusedIterator for IntoIter<T, A> {}
@@ -0,0 +1,15 @@
+//! An ordered map with two keys, where the first key is kept in sorted order
+//! and the second key is hashed.
+//!
+//! For more information, see [`BiOrdMap`].
+
+pub(crate) mod imp;
+mod iter;
+mod ref_mut;
+mod tables;
+pub(crate) mod trait_defs;
+
+pub use imp::BiOrdMap;
+pub use iter::{IntoIter, Iter, Range};
+pub use ref_mut::RefMut;
+pub use trait_defs::BiOrdItem;
@@ -0,0 +1,160 @@
+use crate::{
+    BiOrdItem, DefaultHashBuilder,
+    support::{btree_table::HashState, map_hash::MapHash},
+};
+use core::{
+    fmt,
+    hash::{BuildHasher, Hash},
+    ops::{Deref, DerefMut},
+};
+
+/// A mutable reference to a [`BiOrdMap`] item.
+///
+/// This is a wrapper around a `&mut T` that panics when dropped, if the
+/// borrowed value's keys have changed since the wrapper was created.
+///
+/// # Change detection
+///
+/// It is illegal to change the keys of a borrowed `&mut T`. `RefMut` attempts
+/// to enforce this invariant, and as part of that, it requires that the first
+/// key type implement [`Hash`]. (The second key type is always hashed.)
+///
+/// `RefMut` stores the `Hash` output of keys at creation time, and recomputes
+/// these hashes when it is dropped or when [`Self::into_ref`] is called. If a
+/// key changes, there's a small but non-negligible chance that its hash value
+/// stays the same[^collision-chance]. In that case, the map will no longer
+/// function correctly and might panic on access. This will not introduce memory
+/// safety issues, however.
+///
+/// It is also possible to deliberately write pathological `Hash`
+/// implementations that collide more often. (Don't do this.)
+///
+/// Also, `RefMut`'s hash detection will not function if [`mem::forget`] is
+/// called on it. If a key is changed and `mem::forget` is then called on the
+/// `RefMut`, the [`BiOrdMap`] will no longer function correctly and might panic
+/// on access. This will not introduce memory safety issues, however.
+///
+/// The issues here are similar to using interior mutability (e.g. `RefCell` or
+/// `Mutex`) to mutate keys in a regular `HashMap`.
+///
+/// [`mem::forget`]: std::mem::forget
+///
+/// [^collision-chance]: The output of `Hash` is a [`u64`], so the probability
+/// of an individual hash colliding by chance is 1/2⁶⁴. Due to the [birthday
+/// problem], the probability of a collision by chance reaches 10⁻⁶ within
+/// around 6 × 10⁶ elements.
+///
+/// [`BiOrdMap`]: crate::BiOrdMap
+/// [birthday problem]: https://en.wikipedia.org/wiki/Birthday_problem#Probability_table
+pub struct RefMut<'a, T: BiOrdItem, S: Clone + BuildHasher = DefaultHashBuilder>
+where
+    T::K1<'a>: Hash,
+{
+    inner: Option<RefMutInner<'a, T, S>>,
+}
+
+impl<'a, T: BiOrdItem, S: Clone + BuildHasher> RefMut<'a, T, S>
+where
+    T::K1<'a>: Hash,
+{
+    pub(super) fn new(
+        k1_state: HashState,
+        state: S,
+        hashes: [MapHash; 2],
+        borrowed: &'a mut T,
+    ) -> Self {
+        let inner = RefMutInner { k1_state, state, hashes, borrowed };
+        Self { inner: Some(inner) }
+    }
+
+    /// Converts this `RefMut` into a `&'a T`.
+    ///
+    /// This gives up the ability to mutate the item, which is useful for
+    /// returning a shared reference once mutation is done. The keys are
+    /// checked one last time before the reference is returned, so this panics
+    /// if they were changed through this `RefMut`.
+    pub fn into_ref(mut self) -> &'a T {
+        let inner = self.inner.take().unwrap();
+        inner.into_ref()
+    }
+}
+
+impl<'a, T: BiOrdItem, S: Clone + BuildHasher> Drop for RefMut<'a, T, S>
+where
+    T::K1<'a>: Hash,
+{
+    fn drop(&mut self) {
+        if let Some(inner) = self.inner.take() {
+            inner.into_ref();
+        }
+    }
+}
+
+impl<'a, T: BiOrdItem, S: Clone + BuildHasher> Deref for RefMut<'a, T, S>
+where
+    T::K1<'a>: Hash,
+{
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        self.inner.as_ref().unwrap().borrowed
+    }
+}
+
+impl<'a, T: BiOrdItem, S: Clone + BuildHasher> DerefMut for RefMut<'a, T, S>
+where
+    T::K1<'a>: Hash,
+{
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.inner.as_mut().unwrap().borrowed
+    }
+}
+
+impl<'a, T: BiOrdItem + fmt::Debug, S: Clone + BuildHasher> fmt::Debug
+    for RefMut<'a, T, S>
+where
+    T::K1<'a>: Hash,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.inner {
+            Some(ref inner) => inner.fmt(f),
+            None => {
+                f.debug_struct("RefMut").field("borrowed", &"missing").finish()
+            }
+        }
+    }
+}
+
+struct RefMutInner<'a, T: BiOrdItem, S> {
+    k1_state: HashState,
+    state: S,
+    hashes: [MapHash; 2],
+    borrowed: &'a mut T,
+}
+
+impl<'a, T: BiOrdItem, S: BuildHasher> RefMutInner<'a, T, S>
+where
+    T::K1<'a>: Hash,
+{
+    fn into_ref(self) -> &'a T {
+        let key1: T::K1<'_> = self.borrowed.key1();
+        // SAFETY: The key is borrowed, then dropped immediately. T is valid for
+        // 'a so T::K1 is valid for 'a.
+        let key1: T::K1<'a> =
+            unsafe { core::mem::transmute::<T::K1<'_>, T::K1<'a>>(key1) };
+        if !self.hashes[0].is_same_hash(&self.k1_state, &key1) {
+            panic!("key1 changed during RefMut borrow");
+        }
+        if !self.hashes[1].is_same_hash(&self.state, self.borrowed.key2()) {
+            panic!("key2 changed during RefMut borrow");
+        }
+
+        self.borrowed
+    }
+}
+
+impl<T: BiOrdItem + fmt::Debug, S> fmt::Debug for RefMutInner<'_, T, S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.borrowed.fmt(f)
+    }
+}
@@ -0,0 +1,78 @@
+use super::BiOrdItem;
+use crate::{
+    internal::{ValidateCompact, ValidationError},
+    support::{
+        alloc::{Allocator, Global, global_alloc},
+        btree_table::MapBTreeTable,
+        hash_table::MapHashTable,
+        map_hash::MapHash,
+    },
+};
+use core::hash::{BuildHasher, Hash};
+
+#[derive(Clone, Debug, Default)]
+pub(super) struct BiOrdMapTables<S, A: Allocator> {
+    pub(super) state: S,
+    // The B-Tree always uses the global allocator; see `BiOrdMap::items`.
+    pub(super) k1_to_item: MapBTreeTable,
+    pub(super) k2_to_item: MapHashTable<A>,
+}
+
+impl<S> BiOrdMapTables<S, Global> {
+    pub(super) const fn with_hasher(hasher: S) -> Self {
+        Self {
+            state: hasher,
+            k1_to_item: MapBTreeTable::new(),
+            k2_to_item: MapHashTable::new_in(global_alloc()),
+        }
+    }
+}
+
+impl<S, A: Allocator> BiOrdMapTables<S, A> {
+    pub(super) const fn with_hasher_in(hasher: S, alloc: A) -> Self {
+        Self {
+            state: hasher,
+            k1_to_item: MapBTreeTable::new(),
+            k2_to_item: MapHashTable::new_in(alloc),
+        }
+    }
+
+    pub(super) fn with_capacity_and_hasher_in(
+        capacity: usize,
+        hasher: S,
+        alloc: A,
+    ) -> Self {
+        Self {
+            state: hasher,
+            k1_to_item: MapBTreeTable::new(),
+            k2_to_item: MapHashTable::with_capacity_in(capacity, alloc),
+        }
+    }
+
+    pub(super) fn validate(
+        &self,
+        expected_len: usize,
+        compactness: ValidateCompact,
+    ) -> Result<(), ValidationError> {
+        self.k1_to_item.validate(expected_len, compactness).map_err(
+            |error| ValidationError::Table { name: "k1_to_item", error },
+        )?;
+        self.k2_to_item.validate(expected_len, compactness).map_err(
+            |error| ValidationError::Table { name: "k2_to_item", error },
+        )?;
+
+        Ok(())
+    }
+
+    pub(super) fn make_hashes<'a, T>(&self, item: &'a T) -> [MapHash; 2]
+    where
+        T: 'a + BiOrdItem,
+        T::K1<'a>: Hash,
+        S: BuildHasher,
+    {
+        [
+            self.k1_to_item.compute_hash(item.key1()),
+            MapHash::new(self.state.hash_one(item.key2())),
+        ]
+    }
+}
@@ -2,6 +2,10 @@
 //!
 //! These types are shared across all map implementations in this crate.
 
+use crate::{
+    internal::ValidateCompact,
+    support::{ItemIndex, alloc::Allocator, item_set::ItemSet},
+};
 use alloc::vec::Vec;
 use core::fmt;
 
@@ -63,6 +67,87 @@ impl<T: fmt::Debug, D: fmt::Debug> fmt::Display for DuplicateItem<T, D> {
 
 impl<T: fmt::Debug, D: fmt::Debug> core::error::Error for DuplicateItem<T, D> {}
 
+/// The error type for [`IdHashMap::try_from_std_hashmap`].
+///
+/// [`IdHashMap::try_from_std_hashmap`]: crate::IdHashMap::try_from_std_hashmap
+#[derive(Debug)]
+pub enum FromStdHashMapError<K, T> {
+    /// An item's key does not match the key it was stored under.
+    KeyMismatch(KeyMismatch<K, T>),
+
+    /// Two items are stored under different keys, but their item keys are
+    /// equal.
+    ///
+    /// This can happen if converting an item's key into the standard library
+    /// map's key type is lossy in one direction: for example, with a
+    /// case-insensitive item key that converts into a `String`.
+    ///
+    /// The existing item is removed from the partially built map and returned
+    /// as the duplicate.
+    Duplicate(DuplicateItem<T>),
+}
+
+impl<K: fmt::Debug, T: fmt::Debug> fmt::Display for FromStdHashMapError<K, T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            FromStdHashMapError::KeyMismatch(error) => error.fmt(f),
+            FromStdHashMapError::Duplicate(error) => error.fmt(f),
+        }
+    }
+}
+
+impl<K: fmt::Debug, T: fmt::Debug> core::error::Error
+    for FromStdHashMapError<K, T>
+{
+}
+
+/// An item's key does not match the key it was stored under in another
+/// collection.
+///
+/// Returned within [`FromStdHashMapError`].
+#[derive(Debug)]
+pub struct KeyMismatch<K, T> {
+    key: K,
+    item: T,
+}
+
+impl<K, T> KeyMismatch<K, T> {
+    /// Creates a new `KeyMismatch` error.
+    #[doc(hidden)]
+    pub fn __internal_new(key: K, item: T) -> Self {
+        KeyMismatch { key, item }
+    }
+
+    /// Returns the key that the item was stored under.
+    #[inline]
+    pub fn key(&self) -> &K {
+        &self.key
+    }
+
+    /// Returns the item whose key does not match.
+    #[inline]
+    pub fn item(&self) -> &T {
+        &self.item
+    }
+
+    /// Converts self into its constituent parts.
+    pub fn into_parts(self) -> (K, T) {
+        (self.key, self.item)
+    }
+}
+
+impl<K: fmt::Debug, T: fmt::Debug> fmt::Display for KeyMismatch<K, T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "item: {:?} does not match the key it was stored under: {:?}",
+            self.item, self.key
+        )
+    }
+}
+
+impl<K: fmt::Debug, T: fmt::Debug> core::error::Error for KeyMismatch<K, T> {}
+
 /// The error type for `try_reserve` methods.
 ///
 /// This wraps the underlying allocation error from the hash table implementation.
@@ -127,3 +212,175 @@ impl fmt::Display for TryReserveError {
 }
 
 impl core::error::Error for TryReserveError {}
+
+/// An inconsistency in a map's internal state, as reported by
+/// `check_invariants`.
+///
+/// Each map in this crate has a `check_invariants` method, which checks that:
+///
+/// * the map's item storage is well-formed;
+/// * each key table holds exactly one entry per item, with no orphaned or
+///   duplicate entries; and
+/// * each item can be found by looking up each of its own keys.
+///
+/// The maps always uphold these invariants as long as the `Hash`, `Eq`, and
+/// `Ord` implementations of their keys are lawful, so `check_invariants`
+/// never returns an error in practice. It is useful for asserting map
+/// integrity in tests, for example for maps built by deserializing
+/// adversarial input. A `MapIntegrityError` means that either a key
+/// implementation is unlawful, or there's a bug in this crate.
+///
+/// `check_invariants` visits every item and looks it up by each of its keys,
+/// so it is considerably more expensive than most other operations on a map.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum MapIntegrityError {
+    /// The map's item storage is internally inconsistent.
+    ///
+    /// Key implementations can't cause this, so it always indicates a bug in
+    /// this crate.
+    CorruptStorage,
+
+    /// A key table holds an index that doesn't point to an item.
+    OrphanedIndex {
+        /// The key table the index was found in, e.g. `"key1"`.
+        table: &'static str,
+        /// The orphaned index.
+        index: usize,
+    },
+
+    /// A key table holds the same index more than once.
+    DuplicateEntry {
+        /// The key table the index was found in, e.g. `"key1"`.
+        table: &'static str,
+        /// The duplicated index.
+        index: usize,
+    },
+
+    /// A key table has fewer entries than the map has items.
+    LengthMismatch {
+        /// The key table, e.g. `"key1"`.
+        table: &'static str,
+        /// The number of items in the map.
+        expected: usize,
+        /// The number of entries in the key table.
+        actual: usize,
+    },
+
+    /// An item could not be found by looking up its own key.
+    MissingKey {
+        /// The key table the lookup was performed in, e.g. `"key1"`.
+        table: &'static str,
+    },
+
+    /// Looking up an item by its own key found a different item. This
+    /// typically means that two items have keys which compare equal.
+    KeyMismatch {
+        /// The key table the lookup was performed in, e.g. `"key1"`.
+        table: &'static str,
+    },
+}
+
+impl MapIntegrityError {
+    /// Checks that `items` is well-formed.
+    pub(crate) fn check_storage<T, A: Allocator>(
+        items: &ItemSet<T, A>,
+    ) -> Result<(), Self> {
+        items
+            .validate(ValidateCompact::NonCompact)
+            .map_err(|_| MapIntegrityError::CorruptStorage)
+    }
+
+    /// Checks that `indexes`, the entries of the key table `table`, point to
+    /// each item in `items` exactly once.
+    pub(crate) fn check_table<T, A: Allocator>(
+        table: &'static str,
+        items: &ItemSet<T, A>,
+        indexes: impl Iterator<Item = ItemIndex>,
+    ) -> Result<(), Self> {
+        let mut indexes: Vec<_> = indexes.collect();
+        indexes.sort_unstable();
+        let mut prev = None;
+        for &index in &indexes {
+            let as_usize = index.as_u32() as usize;
+            if items.get(index).is_none() {
+                return Err(MapIntegrityError::OrphanedIndex {
+                    table,
+                    index: as_usize,
+                });
+            }
+            if prev == Some(index) {
+                return Err(MapIntegrityError::DuplicateEntry {
+                    table,
+                    index: as_usize,
+                });
+            }
+            prev = Some(index);
+        }
+
+        // With no orphaned or duplicate entries, a length mismatch means some
+        // items are missing from the table.
+        if indexes.len() != items.len() {
+            return Err(MapIntegrityError::LengthMismatch {
+                table,
+                expected: items.len(),
+                actual: indexes.len(),
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Checks that looking up the item at `expected` by its own key in
+    /// `table` found it.
+    pub(crate) fn check_lookup(
+        table: &'static str,
+        expected: ItemIndex,
+        found: Option<ItemIndex>,
+    ) -> Result<(), Self> {
+        match found {
+            Some(found) if found == expected => Ok(()),
+            Some(_) => Err(MapIntegrityError::KeyMismatch { table }),
+            None => Err(MapIntegrityError::MissingKey { table }),
+        }
+    }
+}
+
+impl fmt::Display for MapIntegrityError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MapIntegrityError::CorruptStorage => {
+                write!(f, "map item storage is inconsistent")
+            }
+            MapIntegrityError::OrphanedIndex { table, index } => {
+                write!(
+                    f,
+                    "table {table} holds index {index}, which doesn't point \
+                     to an item"
+                )
+            }
+            MapIntegrityError::DuplicateEntry { table, index } => {
+                write!(f, "table {table} holds index {index} more than once")
+            }
+            MapIntegrityError::LengthMismatch { table, expected, actual } => {
+                write!(
+                    f,
+                    "table {table} has {actual} entries, but the map has \
+                     {expected} items"
+                )
+            }
+            MapIntegrityError::MissingKey { table } => {
+                write!(f, "item not found by its own key in table {table}")
+            }
+            MapIntegrityError::KeyMismatch { table } => {
+                write!(
+                    f,
+                    "lookup of item by its own key in table {table} \
+                     found a different item"
+                )
+            }
+        }
+    }
+}
+
+impl core::error::Error for MapIntegrityError {}
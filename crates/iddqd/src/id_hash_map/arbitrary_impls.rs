@@ -0,0 +1,34 @@
+//! [`Arbitrary`] implementation for [`IdHashMap`], for use with fuzzers.
+
+use super::{IdHashItem, IdHashMap};
+use crate::support::alloc::Allocator;
+use arbitrary::{Arbitrary, Result, Unstructured};
+use core::hash::BuildHasher;
+
+/// Generates an [`IdHashMap`] from a list of arbitrary items.
+///
+/// Items are inserted in order with [`IdHashMap::insert_overwrite`], so if
+/// several items have the same key, only the last one is kept. Duplicates are
+/// silently collapsed rather than causing generation to fail.
+impl<'a, T, S, A> Arbitrary<'a> for IdHashMap<T, S, A>
+where
+    T: IdHashItem + Arbitrary<'a>,
+    S: Clone + BuildHasher + Default,
+    A: Clone + Allocator + Default,
+{
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let mut map = IdHashMap::with_hasher_in(S::default(), A::default());
+        for item in u.arbitrary_iter::<T>()? {
+            map.insert_overwrite(item?);
+        }
+        Ok(map)
+    }
+
+    fn arbitrary_take_rest(u: Unstructured<'a>) -> Result<Self> {
+        let mut map = IdHashMap::with_hasher_in(S::default(), A::default());
+        for item in u.arbitrary_take_rest_iter::<T>()? {
+            map.insert_overwrite(item?);
+        }
+        Ok(map)
+    }
+}
@@ -0,0 +1,443 @@
+//! A sharded, thread-safe wrapper around [`IdHashMap`].
+
+use super::{IdHashItem, IdHashMap};
+use crate::{DefaultHashBuilder, errors::DuplicateItem};
+use alloc::{boxed::Box, vec::Vec};
+use core::{
+    fmt,
+    hash::{BuildHasher, Hash},
+};
+use std::sync::{PoisonError, RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+/// A thread-safe hash map where the key is part of the value.
+///
+/// A `ConcurrentIdHashMap` is split into a fixed number of shards, each of
+/// which is an [`IdHashMap`] behind a [`RwLock`]. An item's shard is chosen
+/// by hashing its key, so operations on keys in different shards can proceed
+/// in parallel, and any number of readers can access a shard at the same
+/// time.
+///
+/// All methods take `&self`, so a `ConcurrentIdHashMap` can be shared across
+/// threads, e.g. via an [`Arc`](std::sync::Arc).
+///
+/// Requires the `concurrent` feature to be enabled.
+///
+/// # Lookups
+///
+/// Lookup methods accept an owned key (`T::Key<'_>`) rather than a borrowed
+/// query. Since each lookup happens under a shard lock that is local to the
+/// method, the borrow checker can't express that an arbitrary
+/// [`Equivalent`](crate::Equivalent) query is valid for the duration of the
+/// lock. (This is the same reason that [`IdHashMap::entry`] takes an owned
+/// key.)
+///
+/// # Lock poisoning
+///
+/// The operations on [`IdHashMap`] leave it in a consistent state even if a
+/// panic occurs partway through (for example, in a user-provided `Hash`
+/// implementation). For that reason, a `ConcurrentIdHashMap` ignores lock
+/// poisoning: a panic on one thread does not prevent other threads from
+/// continuing to use the map.
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(feature = "default-hasher")] {
+/// use iddqd::{ConcurrentIdHashMap, IdHashItem, id_upcast};
+/// use std::{sync::Arc, thread};
+///
+/// #[derive(Clone, Debug, PartialEq, Eq)]
+/// struct Item {
+///     id: u32,
+///     value: String,
+/// }
+///
+/// impl IdHashItem for Item {
+///     type Key<'a> = u32;
+///     fn key(&self) -> Self::Key<'_> {
+///         self.id
+///     }
+///     id_upcast!();
+/// }
+///
+/// let map = Arc::new(ConcurrentIdHashMap::new());
+///
+/// let handles: Vec<_> = (0..4)
+///     .map(|i| {
+///         let map = Arc::clone(&map);
+///         thread::spawn(move || {
+///             map.insert_unique(Item { id: i, value: format!("item {i}") })
+///                 .unwrap();
+///         })
+///     })
+///     .collect();
+/// for handle in handles {
+///     handle.join().unwrap();
+/// }
+///
+/// assert_eq!(map.len(), 4);
+/// assert_eq!(map.get(2).unwrap().value, "item 2");
+/// # }
+/// ```
+pub struct ConcurrentIdHashMap<T, S = DefaultHashBuilder> {
+    shards: Box<[RwLock<IdHashMap<T, S>>]>,
+    // Invariant: every shard uses a clone of this hasher, so an item's hash
+    // within its shard matches the hash used to pick the shard.
+    state: S,
+}
+
+#[cfg(feature = "default-hasher")]
+impl<T: IdHashItem> ConcurrentIdHashMap<T> {
+    /// Creates a new, empty `ConcurrentIdHashMap` with a default number of
+    /// shards.
+    ///
+    /// The default number of shards is a small multiple of the available
+    /// parallelism on this machine.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "default-hasher")] {
+    /// use iddqd::{ConcurrentIdHashMap, IdHashItem, id_upcast};
+    ///
+    /// #[derive(Clone, Debug, PartialEq, Eq)]
+    /// struct Item {
+    ///     id: u32,
+    /// }
+    ///
+    /// impl IdHashItem for Item {
+    ///     type Key<'a> = u32;
+    ///     fn key(&self) -> Self::Key<'_> {
+    ///         self.id
+    ///     }
+    ///     id_upcast!();
+    /// }
+    ///
+    /// let map: ConcurrentIdHashMap<Item> = ConcurrentIdHashMap::new();
+    /// assert!(map.is_empty());
+    /// assert!(map.shard_count() >= 1);
+    /// # }
+    /// ```
+    pub fn new() -> Self {
+        Self::with_shard_count_and_hasher(
+            default_shard_count(),
+            DefaultHashBuilder::default(),
+        )
+    }
+
+    /// Creates a new, empty `ConcurrentIdHashMap` with the given number of
+    /// shards.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `shard_count` is zero.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "default-hasher")] {
+    /// use iddqd::{ConcurrentIdHashMap, IdHashItem, id_upcast};
+    ///
+    /// #[derive(Clone, Debug, PartialEq, Eq)]
+    /// struct Item {
+    ///     id: u32,
+    /// }
+    ///
+    /// impl IdHashItem for Item {
+    ///     type Key<'a> = u32;
+    ///     fn key(&self) -> Self::Key<'_> {
+    ///         self.id
+    ///     }
+    ///     id_upcast!();
+    /// }
+    ///
+    /// let map: ConcurrentIdHashMap<Item> =
+    ///     ConcurrentIdHashMap::with_shard_count(16);
+    /// assert_eq!(map.shard_count(), 16);
+    /// # }
+    /// ```
+    pub fn with_shard_count(shard_count: usize) -> Self {
+        Self::with_shard_count_and_hasher(
+            shard_count,
+            DefaultHashBuilder::default(),
+        )
+    }
+}
+
+#[cfg(feature = "default-hasher")]
+impl<T: IdHashItem> Default for ConcurrentIdHashMap<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: IdHashItem, S: Clone + BuildHasher> ConcurrentIdHashMap<T, S> {
+    /// Creates a new, empty `ConcurrentIdHashMap` with a default number of
+    /// shards, using the given hasher.
+    pub fn with_hasher(hasher: S) -> Self {
+        Self::with_shard_count_and_hasher(default_shard_count(), hasher)
+    }
+
+    /// Creates a new, empty `ConcurrentIdHashMap` with the given number of
+    /// shards and hasher.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `shard_count` is zero.
+    pub fn with_shard_count_and_hasher(shard_count: usize, hasher: S) -> Self {
+        assert!(shard_count > 0, "shard_count must be non-zero");
+        let shards = (0..shard_count)
+            .map(|_| RwLock::new(IdHashMap::with_hasher(hasher.clone())))
+            .collect();
+        Self { shards, state: hasher }
+    }
+
+    /// Returns the number of shards in the map.
+    #[inline]
+    pub fn shard_count(&self) -> usize {
+        self.shards.len()
+    }
+
+    /// Returns the number of items in the map.
+    ///
+    /// Shards are visited one at a time, so if other threads are concurrently
+    /// modifying the map, the result may not reflect any single point in
+    /// time.
+    pub fn len(&self) -> usize {
+        self.shards.iter().map(|shard| read(shard).len()).sum()
+    }
+
+    /// Returns true if the map contains no items.
+    ///
+    /// Like [`len`](Self::len), this is not an atomic snapshot if other
+    /// threads are concurrently modifying the map.
+    pub fn is_empty(&self) -> bool {
+        self.shards.iter().all(|shard| read(shard).is_empty())
+    }
+
+    /// Removes all items from the map.
+    pub fn clear(&self) {
+        for shard in self.shards.iter() {
+            write(shard).clear();
+        }
+    }
+
+    /// Returns true if the map contains an item with the given key.
+    pub fn contains_key(&self, key: T::Key<'_>) -> bool {
+        let shard = read(self.shard_for(&key));
+        shard.contains_key(&T::upcast_key(key))
+    }
+
+    /// Returns a clone of the item with the given key.
+    ///
+    /// To access an item without cloning it, use
+    /// [`get_with`](Self::get_with).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "default-hasher")] {
+    /// use iddqd::{ConcurrentIdHashMap, IdHashItem, id_upcast};
+    ///
+    /// #[derive(Clone, Debug, PartialEq, Eq)]
+    /// struct Item {
+    ///     id: String,
+    ///     value: u32,
+    /// }
+    ///
+    /// impl IdHashItem for Item {
+    ///     type Key<'a> = &'a str;
+    ///     fn key(&self) -> Self::Key<'_> {
+    ///         &self.id
+    ///     }
+    ///     id_upcast!();
+    /// }
+    ///
+    /// let map = ConcurrentIdHashMap::new();
+    /// map.insert_unique(Item { id: "foo".to_string(), value: 42 }).unwrap();
+    ///
+    /// assert_eq!(map.get("foo").unwrap().value, 42);
+    /// assert!(map.get("bar").is_none());
+    /// # }
+    /// ```
+    pub fn get(&self, key: T::Key<'_>) -> Option<T>
+    where
+        T: Clone,
+    {
+        self.get_with(key, T::clone)
+    }
+
+    /// Calls `f` with a reference to the item with the given key, returning
+    /// its result.
+    ///
+    /// The item's shard is read-locked while `f` runs. `f` must not access
+    /// the same map mutably, since doing so may deadlock.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "default-hasher")] {
+    /// use iddqd::{ConcurrentIdHashMap, IdHashItem, id_upcast};
+    ///
+    /// #[derive(Debug, PartialEq, Eq)]
+    /// struct Item {
+    ///     id: String,
+    ///     value: u32,
+    /// }
+    ///
+    /// impl IdHashItem for Item {
+    ///     type Key<'a> = &'a str;
+    ///     fn key(&self) -> Self::Key<'_> {
+    ///         &self.id
+    ///     }
+    ///     id_upcast!();
+    /// }
+    ///
+    /// let map = ConcurrentIdHashMap::new();
+    /// map.insert_overwrite(Item { id: "foo".to_string(), value: 42 });
+    ///
+    /// assert_eq!(map.get_with("foo", |item| item.value), Some(42));
+    /// assert_eq!(map.get_with("bar", |item| item.value), None);
+    /// # }
+    /// ```
+    pub fn get_with<F, R>(&self, key: T::Key<'_>, f: F) -> Option<R>
+    where
+        F: FnOnce(&T) -> R,
+    {
+        let shard = read(self.shard_for(&key));
+        shard.get(&T::upcast_key(key)).map(f)
+    }
+
+    /// Inserts a value into the map, returning an error if an item with the
+    /// same key is already present.
+    ///
+    /// The error contains clones of the conflicting items, since references
+    /// to them can't outlive the shard's lock.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "default-hasher")] {
+    /// use iddqd::{ConcurrentIdHashMap, IdHashItem, id_upcast};
+    ///
+    /// #[derive(Clone, Debug, PartialEq, Eq)]
+    /// struct Item {
+    ///     id: String,
+    ///     value: u32,
+    /// }
+    ///
+    /// impl IdHashItem for Item {
+    ///     type Key<'a> = &'a str;
+    ///     fn key(&self) -> Self::Key<'_> {
+    ///         &self.id
+    ///     }
+    ///     id_upcast!();
+    /// }
+    ///
+    /// let map = ConcurrentIdHashMap::new();
+    /// map.insert_unique(Item { id: "foo".to_string(), value: 42 }).unwrap();
+    ///
+    /// let error = map
+    ///     .insert_unique(Item { id: "foo".to_string(), value: 20 })
+    ///     .unwrap_err();
+    /// assert_eq!(error.duplicates()[0].value, 42);
+    /// # }
+    /// ```
+    pub fn insert_unique(&self, value: T) -> Result<(), DuplicateItem<T>>
+    where
+        T: Clone,
+    {
+        let shard = self.shard_for(&value.key());
+        write(shard).insert_unique(value).map_err(DuplicateItem::into_owned)
+    }
+
+    /// Inserts a value into the map, removing and returning the conflicting
+    /// item, if any.
+    pub fn insert_overwrite(&self, value: T) -> Option<T> {
+        let shard = self.shard_for(&value.key());
+        write(shard).insert_overwrite(value)
+    }
+
+    /// Removes the item with the given key from the map, returning it if it
+    /// was present.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "default-hasher")] {
+    /// use iddqd::{ConcurrentIdHashMap, IdHashItem, id_upcast};
+    ///
+    /// #[derive(Debug, PartialEq, Eq)]
+    /// struct Item {
+    ///     id: String,
+    ///     value: u32,
+    /// }
+    ///
+    /// impl IdHashItem for Item {
+    ///     type Key<'a> = &'a str;
+    ///     fn key(&self) -> Self::Key<'_> {
+    ///         &self.id
+    ///     }
+    ///     id_upcast!();
+    /// }
+    ///
+    /// let map = ConcurrentIdHashMap::new();
+    /// map.insert_overwrite(Item { id: "foo".to_string(), value: 42 });
+    ///
+    /// assert_eq!(map.remove("foo").unwrap().value, 42);
+    /// assert!(map.remove("foo").is_none());
+    /// # }
+    /// ```
+    pub fn remove(&self, key: T::Key<'_>) -> Option<T> {
+        let mut shard = write(self.shard_for(&key));
+        shard.remove(&T::upcast_key(key))
+    }
+
+    /// Consumes the map, returning its shards.
+    ///
+    /// Every item is in exactly one shard.
+    pub fn into_shards(self) -> Vec<IdHashMap<T, S>> {
+        self.shards
+            .into_vec()
+            .into_iter()
+            .map(|shard| {
+                shard.into_inner().unwrap_or_else(PoisonError::into_inner)
+            })
+            .collect()
+    }
+
+    fn shard_for<K: Hash>(&self, key: &K) -> &RwLock<IdHashMap<T, S>> {
+        let hash = self.state.hash_one(key);
+        // hashbrown picks buckets using the low bits of the hash, and stores
+        // the top 7 bits as a tag. Pick the shard using the bits in between,
+        // so that items within a shard are still spread across buckets.
+        let ix = ((hash >> 32) as usize) % self.shards.len();
+        &self.shards[ix]
+    }
+}
+
+impl<T: IdHashItem + fmt::Debug, S: Clone + BuildHasher> fmt::Debug
+    for ConcurrentIdHashMap<T, S>
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut list = f.debug_list();
+        for shard in self.shards.iter() {
+            list.entries(read(shard).iter());
+        }
+        list.finish()
+    }
+}
+
+fn default_shard_count() -> usize {
+    let parallelism =
+        std::thread::available_parallelism().map_or(1, |n| n.get());
+    (parallelism * 4).next_power_of_two()
+}
+
+fn read<T>(lock: &RwLock<T>) -> RwLockReadGuard<'_, T> {
+    lock.read().unwrap_or_else(PoisonError::into_inner)
+}
+
+fn write<T>(lock: &RwLock<T>) -> RwLockWriteGuard<'_, T> {
+    lock.write().unwrap_or_else(PoisonError::into_inner)
+}
@@ -69,6 +69,28 @@ impl<'a, T: IdHashItem, S: Clone + BuildHasher, A: Allocator>
         }
     }
 
+    /// Ensures a value is in the entry by inserting the result of the fallible
+    /// default function if empty, and returns a mutable reference to the value
+    /// in the entry.
+    ///
+    /// If the entry is vacant and `default` returns an error, the error is
+    /// returned and the map is left unchanged.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the key hashes to a different value than the one passed
+    /// into [`IdHashMap::entry`].
+    #[inline]
+    pub fn or_try_insert_with<E, F: FnOnce() -> Result<T, E>>(
+        self,
+        default: F,
+    ) -> Result<RefMut<'a, T, S>, E> {
+        match self {
+            Entry::Occupied(entry) => Ok(entry.into_mut()),
+            Entry::Vacant(entry) => Ok(entry.insert(default()?)),
+        }
+    }
+
     /// Provides in-place mutable access to an occupied entry before any
     /// potential inserts into the map.
     #[inline]
@@ -130,7 +152,7 @@ impl<'a, T: IdHashItem, S: Clone + BuildHasher, A: Allocator>
         let Ok(index) = map.insert_unique_impl(value) else {
             panic!("key already present in map");
         };
-        map.get_by_index_mut(index).expect("index is known to be valid")
+        map.get_by_item_index_mut(index).expect("index is known to be valid")
     }
 
     /// Sets the entry to a new value without checking for duplicates or
@@ -219,7 +241,7 @@ impl<'a, T: IdHashItem, S: Clone + BuildHasher, A: Allocator>
         // SAFETY: The safety assumption behind `Self::new` guarantees that the
         // original reference to the map is not used at this point.
         unsafe { self.map.reborrow_shared() }
-            .get_by_index(self.index)
+            .get_by_item_index(self.index)
             .expect("index is known to be valid")
     }
 
@@ -231,7 +253,7 @@ impl<'a, T: IdHashItem, S: Clone + BuildHasher, A: Allocator>
         // SAFETY: The safety assumption behind `Self::new` guarantees that the
         // original reference to the map is not used at this point.
         unsafe { self.map.reborrow() }
-            .get_by_index_mut(self.index)
+            .get_by_item_index_mut(self.index)
             .expect("index is known to be valid")
     }
 
@@ -243,7 +265,7 @@ impl<'a, T: IdHashItem, S: Clone + BuildHasher, A: Allocator>
         // SAFETY: The safety assumption behind `Self::new` guarantees that the
         // original reference to the map is not used at this point.
         unsafe { self.map.awaken() }
-            .get_by_index(self.index)
+            .get_by_item_index(self.index)
             .expect("index is known to be valid")
     }
 
@@ -255,7 +277,7 @@ impl<'a, T: IdHashItem, S: Clone + BuildHasher, A: Allocator>
         // SAFETY: The safety assumption behind `Self::new` guarantees that the
         // original reference to the map is not used at this point.
         unsafe { self.map.awaken() }
-            .get_by_index_mut(self.index)
+            .get_by_item_index_mut(self.index)
             .expect("index is known to be valid")
     }
 
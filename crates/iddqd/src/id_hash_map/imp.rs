@@ -1,10 +1,12 @@
 use super::{
-    Entry, IdHashItem, IntoIter, Iter, IterMut, OccupiedEntry, RefMut,
+    Difference, Drain, Entry, IdHashItem, Intersection, IntoIter,
+    IntoIterDropping, IntoIterIndexed, IntoKeys, Iter, IterMut, Keys,
+    OccupiedEntry, RawTableView, RefMut, SymmetricDifference, Union,
     VacantEntry, tables::IdHashMapTables,
 };
 use crate::{
-    DefaultHashBuilder,
-    errors::DuplicateItem,
+    CapacityBreakdown, DefaultHashBuilder, ExtendMode, ImmutableKey,
+    errors::{DuplicateItem, MapIntegrityError, TryReserveError},
     internal::{ValidateCompact, ValidationError},
     support::{
         ItemIndex,
@@ -15,10 +17,10 @@ use crate::{
         map_hash::MapHash,
     },
 };
-use alloc::collections::BTreeSet;
+use alloc::{collections::BTreeSet, vec::Vec};
 use core::{
     fmt,
-    hash::{BuildHasher, Hash},
+    hash::{BuildHasher, Hash, Hasher},
 };
 use equivalent::Equivalent;
 
@@ -64,12 +66,106 @@ use equivalent::Equivalent;
 /// assert!(map.get("baz").is_none());
 /// # }
 /// ```
-#[derive(Clone)]
+///
+/// # Copy-on-write snapshots
+///
+/// `IdHashMap` is [`Clone`], so many near-identical snapshots of a map can be
+/// shared cheaply by wrapping it in an [`Arc`](alloc::sync::Arc). Clones of
+/// the `Arc` only bump a reference count, reads go through the `Arc` directly,
+/// and [`Arc::make_mut`](alloc::sync::Arc::make_mut) performs a deep copy
+/// only on the first mutation of a shared snapshot.
+///
+/// ```
+/// # #[cfg(feature = "default-hasher")] {
+/// use iddqd::{IdHashItem, IdHashMap, id_upcast};
+/// use std::sync::Arc;
+///
+/// #[derive(Clone, Debug, PartialEq, Eq)]
+/// struct Config {
+///     name: String,
+///     value: u32,
+/// }
+///
+/// impl IdHashItem for Config {
+///     type Key<'a> = &'a str;
+///     fn key(&self) -> Self::Key<'_> {
+///         &self.name
+///     }
+///     id_upcast!();
+/// }
+///
+/// let mut v1 = IdHashMap::new();
+/// v1.insert_unique(Config { name: "foo".to_string(), value: 1 }).unwrap();
+/// let v1 = Arc::new(v1);
+///
+/// // Cloning the snapshot is cheap: no items are copied.
+/// let mut v2 = Arc::clone(&v1);
+/// assert!(Arc::ptr_eq(&v1, &v2));
+///
+/// // The first mutation copies the map, leaving v1 untouched.
+/// Arc::make_mut(&mut v2).get_mut("foo").unwrap().value = 2;
+/// assert_eq!(v1.get("foo").unwrap().value, 1);
+/// assert_eq!(v2.get("foo").unwrap().value, 2);
+/// # }
+/// ```
+///
+/// # Memory usage
+///
+/// An `IdHashMap` consists of a list of items, sized to exactly the requested
+/// capacity, along with a hash table of item indexes. The hash table is a
+/// [`hashbrown`] table, which has a fixed maximum load factor of 7/8, and
+/// whose number of buckets is always a power of two. The load factor is not
+/// configurable, since hashbrown does not support that.
+///
+/// For memory-constrained uses, the most effective knobs are:
+///
+/// * Using [`with_capacity`](Self::with_capacity) (or
+///   [`reserve`](Self::reserve)) with the exact number of items expected,
+///   to avoid the amortized doubling that occurs on growth.
+/// * Calling [`shrink_to_fit`](Self::shrink_to_fit) once a map has been
+///   fully built, which shrinks the item list to exactly the number of
+///   items, and the hash table to the smallest power-of-two bucket count that
+///   fits them.
+///
+/// Since each bucket only stores an item index and a cached hash, the hash
+/// table is usually much smaller than the item list.
+///
+/// [`hashbrown`]: https://docs.rs/hashbrown
 pub struct IdHashMap<T, S = DefaultHashBuilder, A: Allocator = Global> {
     pub(super) items: ItemSet<T, A>,
     pub(super) tables: IdHashMapTables<S, A>,
 }
 
+impl<T: Clone, S: Clone, A: Clone + Allocator> Clone for IdHashMap<T, S, A> {
+    /// Clones the map without rehashing any keys.
+    ///
+    /// The item storage and the hash table of item indexes are cloned as they
+    /// are, so the only user code called is `T::clone` and `S::clone`.
+    fn clone(&self) -> Self {
+        Self { items: self.items.clone(), tables: self.tables.clone() }
+    }
+
+    /// Overwrites `self` with a clone of `source`, reusing `self`'s existing
+    /// allocations where possible.
+    ///
+    /// `self` keeps its own allocator. If cloning the hasher or an item
+    /// panics, `self` is left empty.
+    ///
+    /// This is useful for repeatedly copying a map into the same destination,
+    /// for example when double-buffering snapshots, without reallocating each
+    /// time. (`ToOwned::clone_into` calls this method.)
+    fn clone_from(&mut self, source: &Self) {
+        // Empty the map first, so that if cloning the hasher or an item
+        // panics, the map is left empty rather than with a table that doesn't
+        // match its items.
+        self.tables.key_to_item.clear();
+        self.items.clear();
+        self.tables.state.clone_from(&source.tables.state);
+        self.items.clone_from(&source.items);
+        self.tables.key_to_item.clone_from(&source.tables.key_to_item);
+    }
+}
+
 impl<T: IdHashItem, S: Default, A: Allocator + Default> Default
     for IdHashMap<T, S, A>
 {
@@ -152,6 +248,49 @@ impl<T: IdHashItem> IdHashMap<T> {
             ),
         }
     }
+
+    /// Creates a new `IdHashMap` with the given capacity, returning an error if
+    /// allocation fails.
+    ///
+    /// This is a fallible version of [`IdHashMap::with_capacity`].
+    ///
+    /// # Errors
+    ///
+    /// If the capacity overflows, or the allocator reports a failure, then an
+    /// error is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "default-hasher")] {
+    /// use iddqd::{IdHashItem, IdHashMap, id_upcast};
+    ///
+    /// #[derive(Debug, PartialEq, Eq, Hash)]
+    /// struct Item {
+    ///     id: String,
+    ///     value: u32,
+    /// }
+    ///
+    /// impl IdHashItem for Item {
+    ///     type Key<'a> = &'a str;
+    ///     fn key(&self) -> Self::Key<'_> {
+    ///         &self.id
+    ///     }
+    ///     id_upcast!();
+    /// }
+    ///
+    /// let map: IdHashMap<Item> = IdHashMap::try_with_capacity(10)
+    ///     .expect("allocation should succeed");
+    /// assert!(map.capacity() >= 10);
+    /// assert!(map.is_empty());
+    /// # }
+    /// ```
+    pub fn try_with_capacity(capacity: usize) -> Result<Self, TryReserveError> {
+        Self::try_with_capacity_and_hasher(
+            capacity,
+            DefaultHashBuilder::default(),
+        )
+    }
 }
 
 impl<T: IdHashItem, S: BuildHasher> IdHashMap<T, S> {
@@ -226,6 +365,53 @@ impl<T: IdHashItem, S: BuildHasher> IdHashMap<T, S> {
             ),
         }
     }
+
+    /// Creates a new `IdHashMap` with the given capacity and hasher, returning
+    /// an error if allocation fails.
+    ///
+    /// This is a fallible version of [`IdHashMap::with_capacity_and_hasher`].
+    ///
+    /// # Errors
+    ///
+    /// If the capacity overflows, or the allocator reports a failure, then an
+    /// error is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use iddqd::{IdHashItem, IdHashMap, id_upcast};
+    /// use std::collections::hash_map::RandomState;
+    ///
+    /// #[derive(Debug, PartialEq, Eq, Hash)]
+    /// struct Item {
+    ///     id: String,
+    ///     value: u32,
+    /// }
+    ///
+    /// impl IdHashItem for Item {
+    ///     type Key<'a> = &'a str;
+    ///     fn key(&self) -> Self::Key<'_> {
+    ///         &self.id
+    ///     }
+    ///     id_upcast!();
+    /// }
+    ///
+    /// let hasher = RandomState::new();
+    /// let map: IdHashMap<Item, _> =
+    ///     IdHashMap::try_with_capacity_and_hasher(10, hasher)
+    ///         .expect("allocation should succeed");
+    /// assert!(map.capacity() >= 10);
+    /// assert!(map.is_empty());
+    /// ```
+    pub fn try_with_capacity_and_hasher(
+        capacity: usize,
+        hasher: S,
+    ) -> Result<Self, TryReserveError> {
+        let mut map = Self::with_hasher(hasher);
+        map.items.try_reserve(capacity)?;
+        map.tables.try_reserve(capacity)?;
+        Ok(map)
+    }
 }
 
 #[cfg(feature = "default-hasher")]
@@ -317,6 +503,58 @@ impl<T: IdHashItem, A: Clone + Allocator> IdHashMap<T, DefaultHashBuilder, A> {
             ),
         }
     }
+
+    /// Creates an empty `IdHashMap` with the specified capacity using the given
+    /// allocator, returning an error if allocation fails.
+    ///
+    /// This is a fallible version of [`IdHashMap::with_capacity_in`].
+    ///
+    /// # Errors
+    ///
+    /// If the capacity overflows, or the allocator reports a failure, then an
+    /// error is returned.
+    ///
+    /// # Examples
+    ///
+    /// Using the [`bumpalo`](https://docs.rs/bumpalo) allocator:
+    ///
+    /// ```
+    /// # #[cfg(all(feature = "default-hasher", feature = "allocator-api2"))] {
+    /// use iddqd::{IdHashItem, IdHashMap, id_upcast};
+    /// # use iddqd_test_utils::bumpalo;
+    ///
+    /// #[derive(Debug, PartialEq, Eq, Hash)]
+    /// struct Item {
+    ///     id: String,
+    ///     value: u32,
+    /// }
+    ///
+    /// impl IdHashItem for Item {
+    ///     type Key<'a> = &'a str;
+    ///     fn key(&self) -> Self::Key<'_> {
+    ///         &self.id
+    ///     }
+    ///     id_upcast!();
+    /// }
+    ///
+    /// let bump = bumpalo::Bump::new();
+    /// let map: IdHashMap<Item, _, &bumpalo::Bump> =
+    ///     IdHashMap::try_with_capacity_in(10, &bump)
+    ///         .expect("allocation should succeed");
+    /// assert!(map.capacity() >= 10);
+    /// assert!(map.is_empty());
+    /// # }
+    /// ```
+    pub fn try_with_capacity_in(
+        capacity: usize,
+        alloc: A,
+    ) -> Result<Self, TryReserveError> {
+        Self::try_with_capacity_and_hasher_in(
+            capacity,
+            DefaultHashBuilder::default(),
+            alloc,
+        )
+    }
 }
 
 impl<T: IdHashItem, S: BuildHasher, A: Clone + Allocator> IdHashMap<T, S, A> {
@@ -415,6 +653,61 @@ impl<T: IdHashItem, S: BuildHasher, A: Clone + Allocator> IdHashMap<T, S, A> {
             ),
         }
     }
+
+    /// Creates a new, empty `IdHashMap` with the given capacity, hasher, and
+    /// allocator, returning an error if allocation fails.
+    ///
+    /// This is a fallible version of
+    /// [`IdHashMap::with_capacity_and_hasher_in`].
+    ///
+    /// # Errors
+    ///
+    /// If the capacity overflows, or the allocator reports a failure, then an
+    /// error is returned.
+    ///
+    /// # Examples
+    ///
+    /// Using the [`bumpalo`](https://docs.rs/bumpalo) allocator:
+    ///
+    /// ```
+    /// # #[cfg(feature = "allocator-api2")] {
+    /// use iddqd::{IdHashItem, IdHashMap, id_upcast};
+    /// use std::collections::hash_map::RandomState;
+    /// # use iddqd_test_utils::bumpalo;
+    ///
+    /// #[derive(Debug, PartialEq, Eq, Hash)]
+    /// struct Item {
+    ///     id: String,
+    ///     value: u32,
+    /// }
+    ///
+    /// impl IdHashItem for Item {
+    ///     type Key<'a> = &'a str;
+    ///     fn key(&self) -> Self::Key<'_> {
+    ///         &self.id
+    ///     }
+    ///     id_upcast!();
+    /// }
+    ///
+    /// let bump = bumpalo::Bump::new();
+    /// let hasher = RandomState::new();
+    /// let map: IdHashMap<Item, _, &bumpalo::Bump> =
+    ///     IdHashMap::try_with_capacity_and_hasher_in(10, hasher, &bump)
+    ///         .expect("allocation should succeed");
+    /// assert!(map.capacity() >= 10);
+    /// assert!(map.is_empty());
+    /// # }
+    /// ```
+    pub fn try_with_capacity_and_hasher_in(
+        capacity: usize,
+        hasher: S,
+        alloc: A,
+    ) -> Result<Self, TryReserveError> {
+        let mut map = Self::with_hasher_in(hasher, alloc);
+        map.items.try_reserve(capacity)?;
+        map.tables.try_reserve(capacity)?;
+        Ok(map)
+    }
 }
 
 impl<T: IdHashItem, S: Default + Clone + BuildHasher, A: Allocator + Default>
@@ -490,6 +783,105 @@ impl<T: IdHashItem, S: Default + Clone + BuildHasher, A: Allocator + Default>
 
         Ok(map)
     }
+
+    /// Creates a new `IdHashMap` from a standard library [`HashMap`] of items,
+    /// checking that each item's key matches the key it is stored under.
+    ///
+    /// Each item's key is converted into `K` via [`Into`], and compared
+    /// against the item's key in `map`. If any keys don't match,
+    /// [`FromStdHashMapError::KeyMismatch`] is returned with the first
+    /// mismatched entry found, and the rest of `map` is dropped.
+    ///
+    /// Distinct keys in `map` don't guarantee distinct item keys, since the
+    /// conversion into `K` can be lossy (for example, with case-insensitive
+    /// item keys). If two items have equal keys,
+    /// [`FromStdHashMapError::Duplicate`] is returned.
+    ///
+    /// As with [`into_keys`](Self::into_keys), the `Into<K>` bound can only be
+    /// satisfied when `T` is `'static`.
+    ///
+    /// Requires the `std` feature to be enabled.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(all(feature = "default-hasher", feature = "std"))] {
+    /// use iddqd::{
+    ///     IdHashItem, IdHashMap, errors::FromStdHashMapError, id_upcast,
+    /// };
+    /// use std::collections::HashMap;
+    ///
+    /// #[derive(Debug, PartialEq, Eq)]
+    /// struct Item {
+    ///     id: String,
+    ///     value: u32,
+    /// }
+    ///
+    /// impl IdHashItem for Item {
+    ///     type Key<'a> = &'a str;
+    ///     fn key(&self) -> Self::Key<'_> {
+    ///         &self.id
+    ///     }
+    ///     id_upcast!();
+    /// }
+    ///
+    /// let mut std_map = HashMap::new();
+    /// std_map.insert("foo".to_string(), Item { id: "foo".to_string(), value: 42 });
+    /// let map = IdHashMap::<Item>::try_from_std_hashmap(std_map).unwrap();
+    /// assert_eq!(map.get("foo").unwrap().value, 42);
+    ///
+    /// // An item stored under a different key is rejected.
+    /// let mut std_map = HashMap::new();
+    /// std_map.insert("bar".to_string(), Item { id: "foo".to_string(), value: 42 });
+    /// let error = IdHashMap::<Item>::try_from_std_hashmap(std_map).unwrap_err();
+    /// let FromStdHashMapError::KeyMismatch(error) = error else {
+    ///     panic!("expected a key mismatch");
+    /// };
+    /// assert_eq!(error.key(), "bar");
+    /// assert_eq!(error.item().id, "foo");
+    /// # }
+    /// ```
+    ///
+    /// [`HashMap`]: std::collections::HashMap
+    /// [`FromStdHashMapError::KeyMismatch`]: crate::errors::FromStdHashMapError::KeyMismatch
+    /// [`FromStdHashMapError::Duplicate`]: crate::errors::FromStdHashMapError::Duplicate
+    #[cfg(feature = "std")]
+    pub fn try_from_std_hashmap<K, S2>(
+        map: std::collections::HashMap<K, T, S2>,
+    ) -> Result<Self, crate::errors::FromStdHashMapError<K, T>>
+    where
+        K: Eq,
+        for<'k> T::Key<'k>: Into<K>,
+    {
+        use crate::errors::{FromStdHashMapError, KeyMismatch};
+
+        let mut out = Self::default();
+        out.reserve(map.len());
+        for (key, item) in map {
+            if item.key().into() != key {
+                return Err(FromStdHashMapError::KeyMismatch(
+                    KeyMismatch::__internal_new(key, item),
+                ));
+            }
+            if let Err((item, duplicates)) =
+                out.insert_unique_or_dup_indexes(item)
+            {
+                // `out` is about to be dropped, so move the duplicates out of
+                // it rather than requiring `T: Clone`.
+                let duplicates = duplicates
+                    .into_iter()
+                    .map(|index| {
+                        out.remove_by_index(index)
+                            .expect("duplicate index is present in the map")
+                    })
+                    .collect();
+                return Err(FromStdHashMapError::Duplicate(
+                    DuplicateItem::__internal_new(item, duplicates),
+                ));
+            }
+        }
+        Ok(out)
+    }
 }
 
 impl<T: IdHashItem, S: Clone + BuildHasher, A: Allocator> IdHashMap<T, S, A> {
@@ -566,7 +958,24 @@ impl<T: IdHashItem, S: Clone + BuildHasher, A: Allocator> IdHashMap<T, S, A> {
         self.items.capacity()
     }
 
-    /// Returns true if the map is empty.
+    /// Returns a breakdown of the map's internal sizing, for debugging.
+    ///
+    /// This reports the length and capacity of the item storage, and the
+    /// capacity of the key table. It doesn't visit any items, so it's much
+    /// cheaper than [`Self::validate`].
+    pub fn debug_capacity_breakdown(&self) -> CapacityBreakdown {
+        CapacityBreakdown {
+            items_len: self.items.len(),
+            items_capacity: self.items.capacity(),
+            table_capacities: vec![self.tables.key_to_item.capacity()],
+        }
+    }
+
+    /// Returns the number of items in the map, along with the number of items
+    /// the key table can hold without reallocating.
+    ///
+    /// This is useful for tuning the capacity passed to `with_capacity` when
+    /// building other maps of a similar size.
     ///
     /// # Examples
     ///
@@ -588,19 +997,19 @@ impl<T: IdHashItem, S: Clone + BuildHasher, A: Allocator> IdHashMap<T, S, A> {
     ///     id_upcast!();
     /// }
     ///
-    /// let mut map = IdHashMap::new();
-    /// assert!(map.is_empty());
-    ///
+    /// let mut map: IdHashMap<Item> = IdHashMap::with_capacity(10);
     /// map.insert_unique(Item { id: "foo".to_string(), value: 42 }).unwrap();
-    /// assert!(!map.is_empty());
+    ///
+    /// let (len, capacity) = map.occupancy();
+    /// assert_eq!(len, 1);
+    /// assert!(capacity >= 10);
     /// # }
     /// ```
-    #[inline]
-    pub fn is_empty(&self) -> bool {
-        self.items.is_empty()
+    pub fn occupancy(&self) -> (usize, usize) {
+        (self.items.len(), self.tables.key_to_item.capacity())
     }
 
-    /// Returns the number of items in the map.
+    /// Returns true if the map is empty.
     ///
     /// # Examples
     ///
@@ -623,21 +1032,55 @@ impl<T: IdHashItem, S: Clone + BuildHasher, A: Allocator> IdHashMap<T, S, A> {
     /// }
     ///
     /// let mut map = IdHashMap::new();
-    /// assert_eq!(map.len(), 0);
+    /// assert!(map.is_empty());
     ///
     /// map.insert_unique(Item { id: "foo".to_string(), value: 42 }).unwrap();
-    /// assert_eq!(map.len(), 1);
-    ///
-    /// map.insert_unique(Item { id: "bar".to_string(), value: 20 }).unwrap();
-    /// assert_eq!(map.len(), 2);
+    /// assert!(!map.is_empty());
     /// # }
     /// ```
     #[inline]
-    pub fn len(&self) -> usize {
-        self.items.len()
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
     }
 
-    /// Clears the map, removing all items.
+    /// Returns the number of items in the map.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "default-hasher")] {
+    /// use iddqd::{IdHashItem, IdHashMap, id_upcast};
+    ///
+    /// #[derive(Debug, PartialEq, Eq, Hash)]
+    /// struct Item {
+    ///     id: String,
+    ///     value: u32,
+    /// }
+    ///
+    /// impl IdHashItem for Item {
+    ///     type Key<'a> = &'a str;
+    ///     fn key(&self) -> Self::Key<'_> {
+    ///         &self.id
+    ///     }
+    ///     id_upcast!();
+    /// }
+    ///
+    /// let mut map = IdHashMap::new();
+    /// assert_eq!(map.len(), 0);
+    ///
+    /// map.insert_unique(Item { id: "foo".to_string(), value: 42 }).unwrap();
+    /// assert_eq!(map.len(), 1);
+    ///
+    /// map.insert_unique(Item { id: "bar".to_string(), value: 20 }).unwrap();
+    /// assert_eq!(map.len(), 2);
+    /// # }
+    /// ```
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    /// Clears the map, removing all items.
     ///
     /// # Examples
     ///
@@ -677,6 +1120,51 @@ impl<T: IdHashItem, S: Clone + BuildHasher, A: Allocator> IdHashMap<T, S, A> {
         self.items.clear();
     }
 
+    /// Clears the map, returning all items as an iterator.
+    ///
+    /// The map keeps its allocated capacity. It is empty as soon as this
+    /// method returns, even if the iterator is leaked. If the iterator is
+    /// dropped before it is exhausted, the remaining items are dropped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "default-hasher")] {
+    /// use iddqd::{IdHashItem, IdHashMap, id_upcast};
+    ///
+    /// #[derive(Debug, PartialEq, Eq, Hash)]
+    /// struct Item {
+    ///     id: String,
+    ///     value: u32,
+    /// }
+    ///
+    /// impl IdHashItem for Item {
+    ///     type Key<'a> = &'a str;
+    ///     fn key(&self) -> Self::Key<'_> {
+    ///         &self.id
+    ///     }
+    ///     id_upcast!();
+    /// }
+    ///
+    /// let mut map = IdHashMap::with_capacity(16);
+    /// map.insert_unique(Item { id: "foo".to_string(), value: 42 }).unwrap();
+    /// map.insert_unique(Item { id: "bar".to_string(), value: 20 }).unwrap();
+    /// let capacity = map.capacity();
+    ///
+    /// let mut values: Vec<u32> = map.drain().map(|item| item.value).collect();
+    /// values.sort();
+    /// assert_eq!(values, [20, 42]);
+    ///
+    /// assert!(map.is_empty());
+    /// assert_eq!(map.capacity(), capacity);
+    /// # }
+    /// ```
+    pub fn drain(&mut self) -> Drain<'_, T, A> {
+        // Clear the index first, so that it never points at drained slots.
+        self.tables.key_to_item.clear();
+        Drain::new(&mut self.items)
+    }
+
     /// Reserves capacity for at least `additional` more elements to be inserted
     /// in the `IdHashMap`. The collection may reserve more space to
     /// speculatively avoid frequent reallocations. After calling `reserve`,
@@ -768,13 +1256,9 @@ impl<T: IdHashItem, S: Clone + BuildHasher, A: Allocator> IdHashMap<T, S, A> {
     pub fn try_reserve(
         &mut self,
         additional: usize,
-    ) -> Result<(), crate::errors::TryReserveError> {
+    ) -> Result<(), TryReserveError> {
         self.items.try_reserve(additional)?;
-        self.tables
-            .key_to_item
-            .try_reserve(additional)
-            .map_err(crate::errors::TryReserveError::from_hashbrown)?;
-        Ok(())
+        self.tables.try_reserve(additional)
     }
 
     /// Shrinks the capacity of the map as much as possible. It will drop
@@ -960,65 +1444,85 @@ impl<T: IdHashItem, S: Clone + BuildHasher, A: Allocator> IdHashMap<T, S, A> {
         IterMut::new(&self.tables, &mut self.items)
     }
 
-    /// Checks general invariants of the map.
+    /// Iterates over the keys in the map.
     ///
-    /// The code below always upholds these invariants, but it's useful to have
-    /// an explicit check for tests.
-    #[doc(hidden)]
-    pub fn validate(
-        &self,
-        compactness: ValidateCompact,
-    ) -> Result<(), ValidationError>
-    where
-        T: fmt::Debug,
-    {
-        self.validate_structural(compactness)?;
-
-        // Check that the indexes are all correct.
-        //
-        // Unlike the structural checks above, this re-looks up each key through
-        // the user `Hash`, so it only holds when that `Hash` is lawful.
-        for (ix, item) in self.items.iter() {
-            let key = item.key();
-            let Some(ix1) = self.find_index(&key) else {
-                return Err(ValidationError::general(format!(
-                    "item at index {ix} has no key1 index"
-                )));
-            };
-
-            if ix1 != ix {
-                return Err(ValidationError::General(format!(
-                    "item at index {ix} has mismatched indexes: ix1: {ix1}",
-                )));
-            }
-        }
+    /// Similar to [`HashMap`], the iteration order is arbitrary and not
+    /// guaranteed to be stable.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "default-hasher")] {
+    /// use iddqd::{IdHashItem, IdHashMap, id_upcast};
+    ///
+    /// #[derive(Debug, PartialEq, Eq, Hash)]
+    /// struct Item {
+    ///     id: String,
+    ///     value: u32,
+    /// }
+    ///
+    /// impl IdHashItem for Item {
+    ///     type Key<'a> = &'a str;
+    ///     fn key(&self) -> Self::Key<'_> {
+    ///         &self.id
+    ///     }
+    ///     id_upcast!();
+    /// }
+    ///
+    /// let mut map = IdHashMap::new();
+    /// map.insert_unique(Item { id: "foo".to_string(), value: 42 }).unwrap();
+    /// map.insert_unique(Item { id: "bar".to_string(), value: 20 }).unwrap();
+    ///
+    /// let mut keys: Vec<&str> = map.keys().collect();
+    /// keys.sort();
+    /// assert_eq!(keys, ["bar", "foo"]);
+    /// # }
+    /// ```
+    ///
+    /// [`HashMap`]: std::collections::HashMap
+    #[inline]
+    pub fn keys(&self) -> Keys<'_, T> {
+        Keys::new(self.iter())
+    }
 
-        Ok(())
+    /// Iterates over the items in the map.
+    ///
+    /// This is the same as [`Self::iter`], and is provided for symmetry with
+    /// [`HashMap::values`].
+    ///
+    /// [`HashMap::values`]: std::collections::HashMap::values
+    #[inline]
+    pub fn values(&self) -> Iter<'_, T> {
+        self.iter()
     }
 
-    /// Checks the structural invariants of the map:
+    /// Iterates over the items in the map, allowing for mutation.
     ///
-    /// * The item set is well-formed.
-    /// * The hash table holds exactly one entry per live item, with no
-    ///   duplicate `ItemIndex`es.
+    /// This is the same as [`Self::iter_mut`], and is provided for symmetry with
+    /// [`HashMap::values_mut`].
     ///
-    /// Unlike [`validate`](Self::validate), this does not re-look-up keys
-    /// through the user `Hash`, so it holds regardless of whether that `Hash`
-    /// is lawful. A buggy hasher can desync the logical key→item mapping, but
-    /// it must never break these structural invariants! Doing so would be
-    /// unsoundness, e.g. duplicate indexes enabling mutable aliasing.
-    #[doc(hidden)]
-    pub fn validate_structural(
-        &self,
-        compactness: ValidateCompact,
-    ) -> Result<(), ValidationError> {
-        self.items.validate(compactness)?;
-        self.tables.validate(self.len(), compactness)?;
-        Ok(())
+    /// [`HashMap::values_mut`]: std::collections::HashMap::values_mut
+    #[inline]
+    pub fn values_mut(&mut self) -> IterMut<'_, T, S, A> {
+        self.iter_mut()
     }
 
-    /// Inserts a value into the map, removing and returning the conflicting
-    /// item, if any.
+    /// Consumes the map, returning an iterator over its keys.
+    ///
+    /// Keys borrow from the items they belong to, so once an item is dropped
+    /// its key can no longer be used. The returned iterator instead converts
+    /// each key into the owned type `K` via [`Into`] before dropping the item.
+    /// For example, a `&str` key can be collected as a
+    /// [`String`](alloc::string::String), and `Copy` keys such as integers can
+    /// be returned as-is.
+    ///
+    /// Since the conversion must work for a key borrowed for any lifetime, the
+    /// `Into<K>` bound can only be satisfied when `T` is `'static`. For items
+    /// that borrow non-`'static` data, use [`IntoIterator::into_iter`] and
+    /// convert each key within a closure instead.
+    ///
+    /// Similar to [`HashMap`], the iteration order is arbitrary and not
+    /// guaranteed to be stable.
     ///
     /// # Examples
     ///
@@ -1041,39 +1545,37 @@ impl<T: IdHashItem, S: Clone + BuildHasher, A: Allocator> IdHashMap<T, S, A> {
     /// }
     ///
     /// let mut map = IdHashMap::new();
+    /// map.insert_unique(Item { id: "foo".to_string(), value: 42 }).unwrap();
+    /// map.insert_unique(Item { id: "bar".to_string(), value: 20 }).unwrap();
     ///
-    /// // First insertion returns None
-    /// let old = map.insert_overwrite(Item { id: "foo".to_string(), value: 42 });
-    /// assert!(old.is_none());
-    ///
-    /// // Second insertion with same key returns the old value
-    /// let old = map.insert_overwrite(Item { id: "foo".to_string(), value: 100 });
-    /// assert_eq!(old.unwrap().value, 42);
-    /// assert_eq!(map.get("foo").unwrap().value, 100);
+    /// // `&str` keys are converted into owned `String`s.
+    /// let mut keys: Vec<String> = map.into_keys().collect();
+    /// keys.sort();
+    /// assert_eq!(keys, ["bar", "foo"]);
     /// # }
     /// ```
-    #[doc(alias = "insert")]
-    pub fn insert_overwrite(&mut self, value: T) -> Option<T> {
-        // Go through the entry API so all user code is called before any table
-        // mutation. A panic in user code therefore leaves the map in its
-        // pre-call state.
-        //
-        // In the vacant case, the Entry lookup has already established that the
-        // key is unique. Calling `vacant.insert_entry` would route back through
-        // `insert_unique_impl` and check for duplicates again, while
-        // `vacant.insert` would also create a `RefMut` and re-hash the key. We
-        // use `vacant.insert_known_unique` instead, which avoids both.
-        match self.entry(value.key()) {
-            Entry::Occupied(mut occupied) => Some(occupied.insert(value)),
-            Entry::Vacant(vacant) => {
-                vacant.insert_known_unique(value);
-                None
-            }
-        }
+    ///
+    /// [`HashMap`]: std::collections::HashMap
+    #[inline]
+    pub fn into_keys<K>(self) -> IntoKeys<T, K, A>
+    where
+        for<'k> T::Key<'k>: Into<K>,
+    {
+        IntoKeys::new(self.items)
     }
 
-    /// Inserts a value into the set, returning an error if any duplicates were
-    /// added.
+    /// Consumes the map, returning an iterator over each item along with its
+    /// index.
+    ///
+    /// The index of each item is the one returned by
+    /// [`get_index_of`](Self::get_index_of) just before the map was consumed.
+    /// Indexes are unique, but not necessarily contiguous: slots vacated by
+    /// earlier removals are skipped. Call
+    /// [`shrink_to_fit`](Self::shrink_to_fit) first to number the items from
+    /// `0` to `len() - 1`.
+    ///
+    /// Similar to [`HashMap`], the iteration order is arbitrary and not
+    /// guaranteed to be stable.
     ///
     /// # Examples
     ///
@@ -1096,40 +1598,42 @@ impl<T: IdHashItem, S: Clone + BuildHasher, A: Allocator> IdHashMap<T, S, A> {
     /// }
     ///
     /// let mut map = IdHashMap::new();
-    ///
-    /// // First insertion succeeds
-    /// assert!(
-    ///     map.insert_unique(Item { id: "foo".to_string(), value: 42 }).is_ok()
-    /// );
-    ///
-    /// // Second insertion with different key succeeds
-    /// assert!(
-    ///     map.insert_unique(Item { id: "bar".to_string(), value: 20 }).is_ok()
-    /// );
-    ///
-    /// // Third insertion with duplicate key fails
-    /// assert!(
-    ///     map.insert_unique(Item { id: "foo".to_string(), value: 100 }).is_err()
-    /// );
+    /// map.insert_unique(Item { id: "foo".to_string(), value: 42 }).unwrap();
+    /// map.insert_unique(Item { id: "bar".to_string(), value: 20 }).unwrap();
+    /// let bar_index = map.get_index_of("bar").unwrap();
+    ///
+    /// let (index, item) = map
+    ///     .into_iter_indexed()
+    ///     .find(|(_, item)| item.id == "bar")
+    ///     .unwrap();
+    /// assert_eq!(index, bar_index);
+    /// assert_eq!(item.value, 20);
     /// # }
     /// ```
-    pub fn insert_unique(
-        &mut self,
-        value: T,
-    ) -> Result<(), DuplicateItem<T, &T>> {
-        let _ = self.insert_unique_impl(value)?;
-        Ok(())
+    ///
+    /// [`HashMap`]: std::collections::HashMap
+    #[inline]
+    pub fn into_iter_indexed(self) -> IntoIterIndexed<T, A> {
+        IntoIterIndexed::new(self.items)
     }
 
-    /// Returns true if the map contains the given key.
+    /// Consumes the map, returning a standard library [`HashMap`] from each
+    /// item's key to the item.
+    ///
+    /// As with [`into_keys`](Self::into_keys), each key is converted into the
+    /// owned type `K` via [`Into`], and the `Into<K>` bound can only be
+    /// satisfied when `T` is `'static`.
+    ///
+    /// Requires the `std` feature to be enabled.
     ///
     /// # Examples
     ///
     /// ```
-    /// # #[cfg(feature = "default-hasher")] {
+    /// # #[cfg(all(feature = "default-hasher", feature = "std"))] {
     /// use iddqd::{IdHashItem, IdHashMap, id_upcast};
+    /// use std::collections::HashMap;
     ///
-    /// #[derive(Debug, PartialEq, Eq, Hash)]
+    /// #[derive(Debug, PartialEq, Eq)]
     /// struct Item {
     ///     id: String,
     ///     value: u32,
@@ -1146,24 +1650,1619 @@ impl<T: IdHashItem, S: Clone + BuildHasher, A: Allocator> IdHashMap<T, S, A> {
     /// let mut map = IdHashMap::new();
     /// map.insert_unique(Item { id: "foo".to_string(), value: 42 }).unwrap();
     ///
-    /// assert!(map.contains_key("foo"));
-    /// assert!(!map.contains_key("bar"));
+    /// let std_map: HashMap<String, Item> = map.into_std_hashmap();
+    /// assert_eq!(std_map["foo"].value, 42);
     /// # }
     /// ```
-    pub fn contains_key<'a, Q>(&'a self, key1: &Q) -> bool
+    ///
+    /// [`HashMap`]: std::collections::HashMap
+    #[cfg(feature = "std")]
+    pub fn into_std_hashmap<K>(self) -> std::collections::HashMap<K, T>
     where
-        Q: ?Sized + Hash + Equivalent<T::Key<'a>>,
+        K: Eq + Hash,
+        for<'k> T::Key<'k>: Into<K>,
     {
-        self.find_index(key1).is_some()
+        let mut out = std::collections::HashMap::with_capacity(self.len());
+        for item in self {
+            out.insert(item.key().into(), item);
+        }
+        out
     }
 
-    /// Gets a reference to the value associated with the given key.
+    /// Consumes the map, returning an iterator over its items.
+    ///
+    /// This is the same as [`IntoIterator::into_iter`], and is provided for
+    /// symmetry with [`HashMap::into_values`].
+    ///
+    /// [`HashMap::into_values`]: std::collections::HashMap::into_values
+    #[inline]
+    pub fn into_values(self) -> IntoIter<T, A> {
+        IntoIter::new(self.items)
+    }
+
+    /// Consumes the map, returning an iterator over its items that frees
+    /// backing storage as it goes.
+    ///
+    /// [`IntoIterator::into_iter`] holds on to the map's full allocation until
+    /// the iterator is dropped, even if only a few items remain. This method
+    /// instead drops the key table up front, and shrinks the item storage
+    /// whenever it becomes less than half full. This bounds peak memory when
+    /// items are handed off elsewhere (for example, into a bounded channel)
+    /// as they're yielded, at the cost of occasional reallocations.
+    ///
+    /// Every item is yielded exactly once. The iteration order is arbitrary,
+    /// and may differ from [`IntoIterator::into_iter`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "default-hasher")] {
+    /// use iddqd::{IdHashItem, IdHashMap, id_upcast};
+    ///
+    /// #[derive(Debug, PartialEq, Eq)]
+    /// struct Item {
+    ///     id: u32,
+    ///     payload: Vec<u8>,
+    /// }
+    ///
+    /// impl IdHashItem for Item {
+    ///     type Key<'a> = u32;
+    ///     fn key(&self) -> Self::Key<'_> {
+    ///         self.id
+    ///     }
+    ///     id_upcast!();
+    /// }
+    ///
+    /// let mut map = IdHashMap::new();
+    /// for id in 0..1024 {
+    ///     map.insert_unique(Item { id, payload: vec![0; 16] }).unwrap();
+    /// }
+    ///
+    /// let mut iter = map.into_iter_dropping();
+    /// let initial_capacity = iter.capacity();
+    /// let first_half: Vec<_> = iter.by_ref().take(768).collect();
+    /// assert_eq!(first_half.len(), 768);
+    /// assert_eq!(iter.len(), 256);
+    ///
+    /// // Storage has been released along the way.
+    /// assert!(iter.capacity() < initial_capacity);
+    /// # }
+    /// ```
+    pub fn into_iter_dropping(self) -> IntoIterDropping<T, A> {
+        IntoIterDropping::new(self.items)
+    }
+
+    /// Consumes the map, returning its items as a [`Vec`].
+    ///
+    /// Similar to [`HashMap`], the order of the items is arbitrary. This is
+    /// equivalent to `map.into_iter().collect::<Vec<_>>()`, and is provided to
+    /// make it explicit at the call site that the order doesn't matter.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "default-hasher")] {
+    /// use iddqd::{IdHashItem, IdHashMap, id_upcast};
+    ///
+    /// #[derive(Debug, PartialEq, Eq)]
+    /// struct Item {
+    ///     id: u32,
+    ///     value: &'static str,
+    /// }
+    ///
+    /// impl IdHashItem for Item {
+    ///     type Key<'a> = u32;
+    ///     fn key(&self) -> Self::Key<'_> {
+    ///         self.id
+    ///     }
+    ///     id_upcast!();
+    /// }
+    ///
+    /// let mut map = IdHashMap::new();
+    /// map.insert_unique(Item { id: 2, value: "bar" }).unwrap();
+    /// map.insert_unique(Item { id: 1, value: "foo" }).unwrap();
+    ///
+    /// let mut items = map.into_vec();
+    /// items.sort_unstable_by_key(|item| item.id);
+    /// assert_eq!(
+    ///     items,
+    ///     vec![Item { id: 1, value: "foo" }, Item { id: 2, value: "bar" }],
+    /// );
+    /// # }
+    /// ```
+    ///
+    /// [`HashMap`]: std::collections::HashMap
+    pub fn into_vec(self) -> Vec<T> {
+        self.into_iter().collect()
+    }
+
+    /// Checks that the map's internal invariants hold.
+    ///
+    /// See [`MapIntegrityError`] for what this checks, and when it can fail.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "default-hasher")] {
+    /// use iddqd::{IdHashItem, IdHashMap, id_upcast};
+    ///
+    /// #[derive(Debug, PartialEq, Eq, Hash)]
+    /// struct Item {
+    ///     id: String,
+    ///     value: u32,
+    /// }
+    ///
+    /// impl IdHashItem for Item {
+    ///     type Key<'a> = &'a str;
+    ///     fn key(&self) -> Self::Key<'_> {
+    ///         &self.id
+    ///     }
+    ///     id_upcast!();
+    /// }
+    ///
+    /// let mut map = IdHashMap::new();
+    /// map.insert_unique(Item { id: "foo".to_string(), value: 42 }).unwrap();
+    /// map.insert_unique(Item { id: "bar".to_string(), value: 20 }).unwrap();
+    /// map.remove("foo");
+    ///
+    /// assert_eq!(map.check_invariants(), Ok(()));
+    /// # }
+    /// ```
+    pub fn check_invariants(&self) -> Result<(), MapIntegrityError> {
+        MapIntegrityError::check_storage(&self.items)?;
+        MapIntegrityError::check_table(
+            "key",
+            &self.items,
+            self.tables.key_to_item.iter_hashed().map(|(_, ix)| ix),
+        )?;
+
+        for (ix, item) in self.items.iter() {
+            let found = self.find_index(&item.key());
+            MapIntegrityError::check_lookup("key", ix, found)?;
+        }
+
+        Ok(())
+    }
+
+    /// Checks general invariants of the map.
+    ///
+    /// The code below always upholds these invariants, but it's useful to have
+    /// an explicit check for tests.
+    #[doc(hidden)]
+    pub fn validate(
+        &self,
+        compactness: ValidateCompact,
+    ) -> Result<(), ValidationError>
+    where
+        T: fmt::Debug,
+    {
+        self.validate_structural(compactness)?;
+
+        // Check that the indexes are all correct.
+        //
+        // Unlike the structural checks above, this re-looks up each key through
+        // the user `Hash`, so it only holds when that `Hash` is lawful.
+        for (ix, item) in self.items.iter() {
+            let key = item.key();
+            let Some(ix1) = self.find_index(&key) else {
+                return Err(ValidationError::general(format!(
+                    "item at index {ix} has no key1 index"
+                )));
+            };
+
+            if ix1 != ix {
+                return Err(ValidationError::General(format!(
+                    "item at index {ix} has mismatched indexes: ix1: {ix1}",
+                )));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Checks the structural invariants of the map:
+    ///
+    /// * The item set is well-formed.
+    /// * The hash table holds exactly one entry per live item, with no
+    ///   duplicate `ItemIndex`es.
+    ///
+    /// Unlike [`validate`](Self::validate), this does not re-look-up keys
+    /// through the user `Hash`, so it holds regardless of whether that `Hash`
+    /// is lawful. A buggy hasher can desync the logical key→item mapping, but
+    /// it must never break these structural invariants! Doing so would be
+    /// unsoundness, e.g. duplicate indexes enabling mutable aliasing.
+    #[doc(hidden)]
+    pub fn validate_structural(
+        &self,
+        compactness: ValidateCompact,
+    ) -> Result<(), ValidationError> {
+        self.items.validate(compactness)?;
+        self.tables.validate(self.len(), compactness)?;
+        Ok(())
+    }
+
+    /// Returns a read-only view into the internal layout of the map.
+    ///
+    /// This is an escape hatch for building specialized indexes on top of an
+    /// `IdHashMap`. See [`RawTableView`] for the layout it exposes and the
+    /// invariants it upholds.
+    ///
+    /// This method is hidden from the documentation and is **not** covered by
+    /// semver: the layout may change in any release.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "default-hasher")] {
+    /// use iddqd::{IdHashItem, IdHashMap, id_upcast};
+    /// use std::hash::BuildHasher;
+    ///
+    /// #[derive(Debug, PartialEq, Eq)]
+    /// struct Item {
+    ///     id: u32,
+    ///     value: &'static str,
+    /// }
+    ///
+    /// impl IdHashItem for Item {
+    ///     type Key<'a> = u32;
+    ///     fn key(&self) -> Self::Key<'_> {
+    ///         self.id
+    ///     }
+    ///     id_upcast!();
+    /// }
+    ///
+    /// let mut map = IdHashMap::new();
+    /// map.insert_unique(Item { id: 1, value: "a" }).unwrap();
+    /// map.insert_unique(Item { id: 2, value: "b" }).unwrap();
+    ///
+    /// let raw = map.raw_table();
+    /// assert_eq!(raw.slots().count(), 2);
+    /// for (hash, slot) in raw.key_table() {
+    ///     let item = raw.get_slot(slot).unwrap();
+    ///     assert_eq!(hash, raw.hasher().hash_one(item.key()));
+    /// }
+    /// # }
+    /// ```
+    #[doc(hidden)]
+    pub fn raw_table(&self) -> RawTableView<'_, T, S, A> {
+        RawTableView::new(&self.items, &self.tables)
+    }
+
+    /// Inserts a value into the map, removing and returning the conflicting
+    /// item, if any.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "default-hasher")] {
+    /// use iddqd::{IdHashItem, IdHashMap, id_upcast};
+    ///
+    /// #[derive(Debug, PartialEq, Eq, Hash)]
+    /// struct Item {
+    ///     id: String,
+    ///     value: u32,
+    /// }
+    ///
+    /// impl IdHashItem for Item {
+    ///     type Key<'a> = &'a str;
+    ///     fn key(&self) -> Self::Key<'_> {
+    ///         &self.id
+    ///     }
+    ///     id_upcast!();
+    /// }
+    ///
+    /// let mut map = IdHashMap::new();
+    ///
+    /// // First insertion returns None
+    /// let old = map.insert_overwrite(Item { id: "foo".to_string(), value: 42 });
+    /// assert!(old.is_none());
+    ///
+    /// // Second insertion with same key returns the old value
+    /// let old = map.insert_overwrite(Item { id: "foo".to_string(), value: 100 });
+    /// assert_eq!(old.unwrap().value, 42);
+    /// assert_eq!(map.get("foo").unwrap().value, 100);
+    /// # }
+    /// ```
+    #[doc(alias = "insert")]
+    pub fn insert_overwrite(&mut self, value: T) -> Option<T> {
+        // Go through the entry API so all user code is called before any table
+        // mutation. A panic in user code therefore leaves the map in its
+        // pre-call state.
+        //
+        // In the vacant case, the Entry lookup has already established that the
+        // key is unique. Calling `vacant.insert_entry` would route back through
+        // `insert_unique_impl` and check for duplicates again, while
+        // `vacant.insert` would also create a `RefMut` and re-hash the key. We
+        // use `vacant.insert_known_unique` instead, which avoids both.
+        match self.entry(value.key()) {
+            Entry::Occupied(mut occupied) => Some(occupied.insert(value)),
+            Entry::Vacant(vacant) => {
+                vacant.insert_known_unique(value);
+                None
+            }
+        }
+    }
+
+    /// Inserts a value into the set, returning an error if any duplicates were
+    /// added.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "default-hasher")] {
+    /// use iddqd::{IdHashItem, IdHashMap, id_upcast};
+    ///
+    /// #[derive(Debug, PartialEq, Eq, Hash)]
+    /// struct Item {
+    ///     id: String,
+    ///     value: u32,
+    /// }
+    ///
+    /// impl IdHashItem for Item {
+    ///     type Key<'a> = &'a str;
+    ///     fn key(&self) -> Self::Key<'_> {
+    ///         &self.id
+    ///     }
+    ///     id_upcast!();
+    /// }
+    ///
+    /// let mut map = IdHashMap::new();
+    ///
+    /// // First insertion succeeds
+    /// assert!(
+    ///     map.insert_unique(Item { id: "foo".to_string(), value: 42 }).is_ok()
+    /// );
+    ///
+    /// // Second insertion with different key succeeds
+    /// assert!(
+    ///     map.insert_unique(Item { id: "bar".to_string(), value: 20 }).is_ok()
+    /// );
+    ///
+    /// // Third insertion with duplicate key fails
+    /// assert!(
+    ///     map.insert_unique(Item { id: "foo".to_string(), value: 100 }).is_err()
+    /// );
+    /// # }
+    /// ```
+    pub fn insert_unique(
+        &mut self,
+        value: T,
+    ) -> Result<(), DuplicateItem<T, &T>> {
+        let _ = self.insert_unique_impl(value)?;
+        Ok(())
+    }
+
+    /// Inserts a value into the map, returning a reference to the newly
+    /// inserted item, or an error if the key is already present.
+    ///
+    /// This is like [`IdHashMap::insert_unique`], except that it saves a
+    /// separate lookup after a successful insertion. It is similar to the
+    /// standard library's unstable `HashMap::try_insert`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "default-hasher")] {
+    /// use iddqd::{IdHashItem, IdHashMap, id_upcast};
+    ///
+    /// #[derive(Debug, PartialEq, Eq, Hash)]
+    /// struct Item {
+    ///     id: String,
+    ///     value: u32,
+    /// }
+    ///
+    /// impl IdHashItem for Item {
+    ///     type Key<'a> = &'a str;
+    ///     fn key(&self) -> Self::Key<'_> {
+    ///         &self.id
+    ///     }
+    ///     id_upcast!();
+    /// }
+    ///
+    /// let mut map = IdHashMap::new();
+    ///
+    /// // A successful insertion returns the newly stored item.
+    /// let item = map.try_insert(Item { id: "foo".to_string(), value: 42 });
+    /// assert_eq!(item.unwrap().value, 42);
+    ///
+    /// // A duplicate key returns the existing item in the error.
+    /// let error =
+    ///     map.try_insert(Item { id: "foo".to_string(), value: 100 }).unwrap_err();
+    /// assert_eq!(error.new_item().value, 100);
+    /// assert_eq!(error.duplicates()[0].value, 42);
+    /// # }
+    /// ```
+    pub fn try_insert(&mut self, value: T) -> Result<&T, DuplicateItem<T, &T>> {
+        let (dormant_map, index) = {
+            let (map, dormant_map) = DormantMutRef::new(self);
+            let index = map.insert_unique_impl(value)?;
+            (dormant_map, index)
+        };
+
+        // SAFETY: `map` is not used after this point.
+        let awakened_map = unsafe { dormant_map.awaken() };
+        Ok(&awakened_map.items[index])
+    }
+
+    /// Extends the map with the contents of an iterator, handling duplicates
+    /// according to `mode`.
+    ///
+    /// With [`ExtendMode::Overwrite`], this behaves like calling
+    /// [`IdHashMap::insert_overwrite`] for each item, and never returns an
+    /// error. This is what the [`Extend`] implementation does.
+    ///
+    /// With [`ExtendMode::Unique`], this behaves like calling
+    /// [`IdHashMap::insert_unique`] for each item, stopping at the first item
+    /// that conflicts with an existing one. Items before the conflicting one
+    /// remain in the map, and the rest of the iterator is dropped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "default-hasher")] {
+    /// use iddqd::{ExtendMode, IdHashItem, IdHashMap, id_upcast};
+    ///
+    /// #[derive(Debug, PartialEq, Eq)]
+    /// struct Item {
+    ///     id: String,
+    ///     value: u32,
+    /// }
+    ///
+    /// impl IdHashItem for Item {
+    ///     type Key<'a> = &'a str;
+    ///     fn key(&self) -> Self::Key<'_> {
+    ///         &self.id
+    ///     }
+    ///     id_upcast!();
+    /// }
+    ///
+    /// let mut map = IdHashMap::new();
+    /// map.insert_unique(Item { id: "foo".to_string(), value: 42 }).unwrap();
+    ///
+    /// // Unique mode stops at the first duplicate.
+    /// let new_items = vec![
+    ///     Item { id: "bar".to_string(), value: 20 },
+    ///     Item { id: "foo".to_string(), value: 100 },
+    ///     Item { id: "baz".to_string(), value: 30 },
+    /// ];
+    /// let err = map.extend_with(new_items, ExtendMode::Unique).unwrap_err();
+    /// assert_eq!(err.new_item().value, 100);
+    /// assert_eq!(err.duplicates()[0].value, 42);
+    /// assert_eq!(map.len(), 2);
+    /// assert!(map.contains_key("bar"));
+    /// assert!(!map.contains_key("baz"));
+    ///
+    /// // Overwrite mode replaces duplicates.
+    /// let new_items = vec![Item { id: "foo".to_string(), value: 100 }];
+    /// map.extend_with(new_items, ExtendMode::Overwrite).unwrap();
+    /// assert_eq!(map.get("foo").unwrap().value, 100);
+    /// # }
+    /// ```
+    pub fn extend_with<I: IntoIterator<Item = T>>(
+        &mut self,
+        iter: I,
+        mode: ExtendMode,
+    ) -> Result<(), DuplicateItem<T, &T>> {
+        // Keys may already be present in the map, or multiple times in the
+        // iterator. Reserve the entire hint lower bound if the map is empty.
+        // Otherwise reserve half the hint (rounded up), so the map will only
+        // resize twice in the worst case.
+        let iter = iter.into_iter();
+        let reserve = if self.is_empty() {
+            iter.size_hint().0
+        } else {
+            iter.size_hint().0.div_ceil(2)
+        };
+        self.reserve(reserve);
+        match mode {
+            ExtendMode::Overwrite => {
+                for item in iter {
+                    self.insert_overwrite(item);
+                }
+            }
+            ExtendMode::Unique => {
+                for item in iter {
+                    if let Err((item, duplicates)) =
+                        self.insert_unique_or_dup_indexes(item)
+                    {
+                        return Err(DuplicateItem::__internal_new(
+                            item,
+                            duplicates
+                                .iter()
+                                .map(|ix| &self.items[*ix])
+                                .collect(),
+                        ));
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Moves all items from `other` into `self`, calling `resolve` to pick
+    /// the item to keep when a key is present in both maps.
+    ///
+    /// For each key present in both maps, `resolve` is called with the
+    /// existing item and the incoming item, in that order, and the item it
+    /// returns is stored in the map. This generalizes
+    /// [`insert_overwrite`](Self::insert_overwrite), which always keeps the
+    /// incoming item.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `resolve` returns an item with a different key from the two
+    /// items passed in.
+    ///
+    /// If `resolve` panics, the existing item for that key is dropped, along
+    /// with the rest of `other`. The map remains valid.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "default-hasher")] {
+    /// use iddqd::{IdHashItem, IdHashMap, id_hash_map, id_upcast};
+    ///
+    /// #[derive(Debug, PartialEq, Eq)]
+    /// struct Record {
+    ///     id: u32,
+    ///     version: u32,
+    /// }
+    ///
+    /// impl IdHashItem for Record {
+    ///     type Key<'a> = u32;
+    ///     fn key(&self) -> Self::Key<'_> {
+    ///         self.id
+    ///     }
+    ///     id_upcast!();
+    /// }
+    ///
+    /// let mut shard1 = id_hash_map! {
+    ///     Record { id: 1, version: 3 },
+    ///     Record { id: 2, version: 1 },
+    /// };
+    /// let shard2 = id_hash_map! {
+    ///     Record { id: 2, version: 5 },
+    ///     Record { id: 3, version: 2 },
+    /// };
+    ///
+    /// // Keep the record with the higher version.
+    /// shard1.merge(shard2, |existing, incoming| {
+    ///     if incoming.version > existing.version { incoming } else { existing }
+    /// });
+    /// assert_eq!(shard1.len(), 3);
+    /// assert_eq!(shard1.get(&2).unwrap().version, 5);
+    /// # }
+    /// ```
+    pub fn merge<S2, A2, F>(
+        &mut self,
+        other: IdHashMap<T, S2, A2>,
+        mut resolve: F,
+    ) where
+        S2: Clone + BuildHasher,
+        A2: Allocator,
+        F: FnMut(T, T) -> T,
+    {
+        // As in `extend_with`, reserve half the incoming items (rounded up)
+        // unless the map is empty.
+        let reserve =
+            if self.is_empty() { other.len() } else { other.len().div_ceil(2) };
+        self.reserve(reserve);
+        for item in other {
+            // Remember the hash of the incoming key, so we can check that
+            // `resolve` didn't return an item with a different key.
+            let hash = self.make_hash(&item);
+            match self.entry(item.key()) {
+                Entry::Vacant(vacant) => vacant.insert_known_unique(item),
+                Entry::Occupied(occupied) => {
+                    // Remove the existing item before calling user code, so
+                    // that a panic in `resolve` leaves the map consistent.
+                    let existing = occupied.remove();
+                    let chosen = resolve(existing, item);
+                    if !hash.is_same_hash(&self.tables.state, chosen.key()) {
+                        panic!("key hashes do not match");
+                    }
+                    if self.insert_unique_impl(chosen).is_err() {
+                        panic!("key already present in map");
+                    }
+                }
+            }
+        }
+    }
+
+    /// Returns true if the map contains the given key.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "default-hasher")] {
+    /// use iddqd::{IdHashItem, IdHashMap, id_upcast};
+    ///
+    /// #[derive(Debug, PartialEq, Eq, Hash)]
+    /// struct Item {
+    ///     id: String,
+    ///     value: u32,
+    /// }
+    ///
+    /// impl IdHashItem for Item {
+    ///     type Key<'a> = &'a str;
+    ///     fn key(&self) -> Self::Key<'_> {
+    ///         &self.id
+    ///     }
+    ///     id_upcast!();
+    /// }
+    ///
+    /// let mut map = IdHashMap::new();
+    /// map.insert_unique(Item { id: "foo".to_string(), value: 42 }).unwrap();
+    ///
+    /// assert!(map.contains_key("foo"));
+    /// assert!(!map.contains_key("bar"));
+    /// # }
+    /// ```
+    pub fn contains_key<'a, Q>(&'a self, key1: &Q) -> bool
+    where
+        Q: ?Sized + Hash + Equivalent<T::Key<'a>>,
+    {
+        self.find_index(key1).is_some()
+    }
+
+    /// Returns true if the map contains the key built by `f`.
+    ///
+    /// The key is built lazily: if the map is empty, `f` is never called. This
+    /// is most useful with composite keys that are expensive to assemble.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "default-hasher")] {
+    /// use iddqd::{IdHashItem, IdHashMap, id_upcast};
+    ///
+    /// #[derive(Debug, PartialEq, Eq)]
+    /// struct Artifact {
+    ///     name: String,
+    ///     version: u32,
+    ///     size: u64,
+    /// }
+    ///
+    /// #[derive(Debug, PartialEq, Eq, Hash)]
+    /// struct ArtifactKey<'a> {
+    ///     name: &'a str,
+    ///     version: u32,
+    /// }
+    ///
+    /// impl IdHashItem for Artifact {
+    ///     type Key<'a> = ArtifactKey<'a>;
+    ///     fn key(&self) -> Self::Key<'_> {
+    ///         ArtifactKey { name: &self.name, version: self.version }
+    ///     }
+    ///     id_upcast!();
+    /// }
+    ///
+    /// let mut map = IdHashMap::new();
+    /// assert!(!map.contains_key_by(|| unreachable!("map is empty")));
+    ///
+    /// map.insert_unique(Artifact {
+    ///     name: "foo".to_string(),
+    ///     version: 1,
+    ///     size: 1024,
+    /// })
+    /// .unwrap();
+    ///
+    /// let name = String::from("foo");
+    /// assert!(map.contains_key_by(|| ArtifactKey { name: &name, version: 1 }));
+    /// assert!(!map.contains_key_by(|| ArtifactKey { name: &name, version: 2 }));
+    /// # }
+    /// ```
+    pub fn contains_key_by<'a, F>(&'a self, f: F) -> bool
+    where
+        F: FnOnce() -> T::Key<'a>,
+    {
+        if self.is_empty() {
+            return false;
+        }
+        self.contains_key(&f())
+    }
+
+    /// Returns a lazy iterator over the items in `self` whose keys are also
+    /// present in `other`.
+    ///
+    /// Items are compared by key only. The items are yielded from `self`,
+    /// even if the item in `other` with the same key differs.
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "default-hasher")] {
+    /// use iddqd::{IdHashItem, IdHashMap, id_hash_map, id_upcast};
+    ///
+    /// #[derive(Debug, PartialEq, Eq)]
+    /// struct Item {
+    ///     id: u32,
+    ///     value: &'static str,
+    /// }
+    ///
+    /// impl IdHashItem for Item {
+    ///     type Key<'a> = u32;
+    ///     fn key(&self) -> Self::Key<'_> {
+    ///         self.id
+    ///     }
+    ///     id_upcast!();
+    /// }
+    ///
+    /// let old = id_hash_map! {
+    ///     Item { id: 1, value: "a" },
+    ///     Item { id: 2, value: "b" },
+    /// };
+    /// let new = id_hash_map! {
+    ///     Item { id: 2, value: "B" },
+    ///     Item { id: 3, value: "c" },
+    /// };
+    ///
+    /// let common: Vec<_> = old.intersection(&new).collect();
+    /// assert_eq!(common, [&Item { id: 2, value: "b" }]);
+    /// # }
+    /// ```
+    pub fn intersection<'a>(
+        &'a self,
+        other: &'a IdHashMap<T, S, A>,
+    ) -> Intersection<'a, T, S, A> {
+        Intersection::new(self.items.values(), other)
+    }
+
+    /// Returns a lazy iterator over the items in `self` whose keys are not
+    /// present in `other`.
+    ///
+    /// Items are compared by key only.
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "default-hasher")] {
+    /// use iddqd::{IdHashItem, IdHashMap, id_hash_map, id_upcast};
+    ///
+    /// #[derive(Debug, PartialEq, Eq)]
+    /// struct Item {
+    ///     id: u32,
+    ///     value: &'static str,
+    /// }
+    ///
+    /// impl IdHashItem for Item {
+    ///     type Key<'a> = u32;
+    ///     fn key(&self) -> Self::Key<'_> {
+    ///         self.id
+    ///     }
+    ///     id_upcast!();
+    /// }
+    ///
+    /// let old = id_hash_map! {
+    ///     Item { id: 1, value: "a" },
+    ///     Item { id: 2, value: "b" },
+    /// };
+    /// let new = id_hash_map! {
+    ///     Item { id: 2, value: "B" },
+    ///     Item { id: 3, value: "c" },
+    /// };
+    ///
+    /// let removed: Vec<_> = old.difference(&new).collect();
+    /// assert_eq!(removed, [&Item { id: 1, value: "a" }]);
+    ///
+    /// let added: Vec<_> = new.difference(&old).collect();
+    /// assert_eq!(added, [&Item { id: 3, value: "c" }]);
+    /// # }
+    /// ```
+    pub fn difference<'a>(
+        &'a self,
+        other: &'a IdHashMap<T, S, A>,
+    ) -> Difference<'a, T, S, A> {
+        Difference::new(self.items.values(), other)
+    }
+
+    /// Returns a lazy iterator over the items whose keys are present in
+    /// exactly one of `self` and `other`.
+    ///
+    /// Items are compared by key only. Items from `self` are yielded first,
+    /// followed by items from `other`.
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "default-hasher")] {
+    /// use iddqd::{IdHashItem, IdHashMap, id_hash_map, id_upcast};
+    ///
+    /// #[derive(Debug, PartialEq, Eq)]
+    /// struct Item {
+    ///     id: u32,
+    ///     value: &'static str,
+    /// }
+    ///
+    /// impl IdHashItem for Item {
+    ///     type Key<'a> = u32;
+    ///     fn key(&self) -> Self::Key<'_> {
+    ///         self.id
+    ///     }
+    ///     id_upcast!();
+    /// }
+    ///
+    /// let old = id_hash_map! {
+    ///     Item { id: 1, value: "a" },
+    ///     Item { id: 2, value: "b" },
+    /// };
+    /// let new = id_hash_map! {
+    ///     Item { id: 2, value: "B" },
+    ///     Item { id: 3, value: "c" },
+    /// };
+    ///
+    /// let mut changed: Vec<_> =
+    ///     old.symmetric_difference(&new).map(|item| item.id).collect();
+    /// changed.sort();
+    /// assert_eq!(changed, [1, 3]);
+    /// # }
+    /// ```
+    pub fn symmetric_difference<'a>(
+        &'a self,
+        other: &'a IdHashMap<T, S, A>,
+    ) -> SymmetricDifference<'a, T, S, A> {
+        SymmetricDifference::new(
+            Difference::new(self.items.values(), other),
+            Difference::new(other.items.values(), self),
+        )
+    }
+
+    /// Returns a lazy iterator over the items in `self` and `other`, yielding
+    /// each key once.
+    ///
+    /// Items are compared by key only. All items from `self` are yielded
+    /// first, followed by the items from `other` whose keys are not in
+    /// `self`.
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "default-hasher")] {
+    /// use iddqd::{IdHashItem, IdHashMap, id_hash_map, id_upcast};
+    ///
+    /// #[derive(Debug, PartialEq, Eq)]
+    /// struct Item {
+    ///     id: u32,
+    ///     value: &'static str,
+    /// }
+    ///
+    /// impl IdHashItem for Item {
+    ///     type Key<'a> = u32;
+    ///     fn key(&self) -> Self::Key<'_> {
+    ///         self.id
+    ///     }
+    ///     id_upcast!();
+    /// }
+    ///
+    /// let old = id_hash_map! {
+    ///     Item { id: 1, value: "a" },
+    ///     Item { id: 2, value: "b" },
+    /// };
+    /// let new = id_hash_map! {
+    ///     Item { id: 2, value: "B" },
+    ///     Item { id: 3, value: "c" },
+    /// };
+    ///
+    /// let mut all: Vec<_> = old.union(&new).map(|item| item.value).collect();
+    /// all.sort();
+    /// assert_eq!(all, ["a", "b", "c"]);
+    /// # }
+    /// ```
+    pub fn union<'a>(
+        &'a self,
+        other: &'a IdHashMap<T, S, A>,
+    ) -> Union<'a, T, S, A> {
+        Union::new(
+            self.items.values(),
+            Difference::new(other.items.values(), self),
+        )
+    }
+
+    /// Gets a reference to the value associated with the given key.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "default-hasher")] {
+    /// use iddqd::{IdHashItem, IdHashMap, id_upcast};
+    ///
+    /// #[derive(Debug, PartialEq, Eq, Hash)]
+    /// struct Item {
+    ///     id: String,
+    ///     value: u32,
+    /// }
+    ///
+    /// impl IdHashItem for Item {
+    ///     type Key<'a> = &'a str;
+    ///     fn key(&self) -> Self::Key<'_> {
+    ///         &self.id
+    ///     }
+    ///     id_upcast!();
+    /// }
+    ///
+    /// let mut map = IdHashMap::new();
+    /// map.insert_unique(Item { id: "foo".to_string(), value: 42 }).unwrap();
+    ///
+    /// assert_eq!(map.get("foo").unwrap().value, 42);
+    /// assert!(map.get("bar").is_none());
+    /// # }
+    /// ```
+    pub fn get<'a, Q>(&'a self, key: &Q) -> Option<&'a T>
+    where
+        Q: ?Sized + Hash + Equivalent<T::Key<'a>>,
+    {
+        self.find_index(key).map(|ix| &self.items[ix])
+    }
+
+    /// Returns the index of the slot holding the item with the given key, if
+    /// it exists.
+    ///
+    /// The index identifies the slot holding the item in the map's item
+    /// storage. It can later be passed to
+    /// [`get_by_index`](Self::get_by_index), and is the same index returned by
+    /// [`remove_full`](Self::remove_full).
+    ///
+    /// An item's index is stable for as long as the item remains in the map,
+    /// including across insertions and removals of other items, with these
+    /// exceptions:
+    ///
+    /// * [`retain`](Self::retain), [`shrink_to_fit`](Self::shrink_to_fit),
+    ///   and [`shrink_to`](Self::shrink_to) compact the map, and may renumber
+    ///   every item.
+    /// * Once an item is removed, its index may be reused by a later
+    ///   insertion.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "default-hasher")] {
+    /// use iddqd::{IdHashItem, IdHashMap, id_upcast};
+    ///
+    /// #[derive(Debug, PartialEq, Eq, Hash)]
+    /// struct Item {
+    ///     id: String,
+    ///     value: u32,
+    /// }
+    ///
+    /// impl IdHashItem for Item {
+    ///     type Key<'a> = &'a str;
+    ///     fn key(&self) -> Self::Key<'_> {
+    ///         &self.id
+    ///     }
+    ///     id_upcast!();
+    /// }
+    ///
+    /// let mut map = IdHashMap::new();
+    /// map.insert_unique(Item { id: "foo".to_string(), value: 42 }).unwrap();
+    /// map.insert_unique(Item { id: "bar".to_string(), value: 20 }).unwrap();
+    ///
+    /// let index = map.get_index_of("bar").unwrap();
+    ///
+    /// // Removing another item doesn't change the index.
+    /// map.remove("foo");
+    /// assert_eq!(map.get_index_of("bar"), Some(index));
+    /// assert_eq!(map.get_by_index(index).unwrap().value, 20);
+    ///
+    /// assert_eq!(map.get_index_of("foo"), None);
+    /// # }
+    /// ```
+    pub fn get_index_of<'a, Q>(&'a self, key: &Q) -> Option<usize>
+    where
+        Q: ?Sized + Hash + Equivalent<T::Key<'a>>,
+    {
+        self.find_index(key).map(|ix| ix.as_u32() as usize)
+    }
+
+    /// Gets a reference to the item at the given slot index, if the slot is
+    /// occupied.
+    ///
+    /// Indexes are obtained from [`get_index_of`](Self::get_index_of); see
+    /// its documentation for when indexes are stable.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "default-hasher")] {
+    /// use iddqd::{IdHashItem, IdHashMap, id_upcast};
+    ///
+    /// #[derive(Debug, PartialEq, Eq, Hash)]
+    /// struct Item {
+    ///     id: String,
+    ///     value: u32,
+    /// }
+    ///
+    /// impl IdHashItem for Item {
+    ///     type Key<'a> = &'a str;
+    ///     fn key(&self) -> Self::Key<'_> {
+    ///         &self.id
+    ///     }
+    ///     id_upcast!();
+    /// }
+    ///
+    /// let mut map = IdHashMap::new();
+    /// map.insert_unique(Item { id: "foo".to_string(), value: 42 }).unwrap();
+    ///
+    /// let index = map.get_index_of("foo").unwrap();
+    /// assert_eq!(map.get_by_index(index).unwrap().value, 42);
+    ///
+    /// // A vacant or out-of-range index returns None.
+    /// map.remove("foo");
+    /// assert!(map.get_by_index(index).is_none());
+    /// assert!(map.get_by_index(usize::MAX).is_none());
+    /// # }
+    /// ```
+    pub fn get_by_index(&self, index: usize) -> Option<&T> {
+        let index = u32::try_from(index).ok()?;
+        self.items.get(ItemIndex::new(index))
+    }
+
+    /// Gets references to the values associated with each of the given keys.
+    ///
+    /// The result is an array of the same length as `keys`, where each element
+    /// is the result of looking up the corresponding key. Keys may repeat.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "default-hasher")] {
+    /// use iddqd::{IdHashItem, IdHashMap, id_upcast};
+    ///
+    /// #[derive(Debug, PartialEq, Eq, Hash)]
+    /// struct Item {
+    ///     id: String,
+    ///     value: u32,
+    /// }
+    ///
+    /// impl IdHashItem for Item {
+    ///     type Key<'a> = &'a str;
+    ///     fn key(&self) -> Self::Key<'_> {
+    ///         &self.id
+    ///     }
+    ///     id_upcast!();
+    /// }
+    ///
+    /// let mut map = IdHashMap::new();
+    /// map.insert_unique(Item { id: "foo".to_string(), value: 42 }).unwrap();
+    /// map.insert_unique(Item { id: "bar".to_string(), value: 20 }).unwrap();
+    ///
+    /// let [foo, bar, baz] = map.get_many(["foo", "bar", "baz"]);
+    /// assert_eq!(foo.unwrap().value, 42);
+    /// assert_eq!(bar.unwrap().value, 20);
+    /// assert!(baz.is_none());
+    /// # }
+    /// ```
+    pub fn get_many<'a, const N: usize, Q>(
+        &'a self,
+        keys: [&Q; N],
+    ) -> [Option<&'a T>; N]
+    where
+        Q: ?Sized + Hash + Equivalent<T::Key<'a>>,
+    {
+        keys.map(|key| self.get(key))
+    }
+
+    /// Gets a mutable reference to the value associated with the given key.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "default-hasher")] {
+    /// use iddqd::{IdHashItem, IdHashMap, id_upcast};
+    ///
+    /// #[derive(Debug, PartialEq, Eq, Hash)]
+    /// struct Item {
+    ///     id: String,
+    ///     value: u32,
+    /// }
+    ///
+    /// impl IdHashItem for Item {
+    ///     type Key<'a> = &'a str;
+    ///     fn key(&self) -> Self::Key<'_> {
+    ///         &self.id
+    ///     }
+    ///     id_upcast!();
+    /// }
+    ///
+    /// let mut map = IdHashMap::new();
+    /// map.insert_unique(Item { id: "foo".to_string(), value: 42 }).unwrap();
+    ///
+    /// if let Some(mut item) = map.get_mut("foo") {
+    ///     item.value = 100;
+    /// }
+    ///
+    /// assert_eq!(map.get("foo").unwrap().value, 100);
+    /// assert!(map.get_mut("bar").is_none());
+    /// # }
+    /// ```
+    pub fn get_mut<'a, Q>(&'a mut self, key: &Q) -> Option<RefMut<'a, T, S>>
+    where
+        Q: ?Sized + Hash + Equivalent<T::Key<'a>>,
+    {
+        let (dormant_map, index) = {
+            let (map, dormant_map) = DormantMutRef::new(self);
+            let index = map.find_index(key)?;
+            (dormant_map, index)
+        };
+
+        // SAFETY: `map` is not used after this point.
+        let awakened_map = unsafe { dormant_map.awaken() };
+        let item = &mut awakened_map.items[index];
+        let state = awakened_map.tables.state.clone();
+        let hashes = awakened_map.tables.make_hash(item);
+        Some(RefMut::new(state, hashes, item))
+    }
+
+    /// Gets mutable references to the values associated with each of the given
+    /// keys.
+    ///
+    /// The result is an array of the same length as `keys`, where each element
+    /// is the result of looking up the corresponding key. This allows several
+    /// items to be modified at once.
+    ///
+    /// # Panics
+    ///
+    /// Panics if any two keys refer to the same item. Keys that aren't present
+    /// in the map don't count towards this check.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "default-hasher")] {
+    /// use iddqd::{IdHashItem, IdHashMap, id_upcast};
+    ///
+    /// #[derive(Debug, PartialEq, Eq, Hash)]
+    /// struct Account {
+    ///     id: String,
+    ///     balance: u32,
+    /// }
+    ///
+    /// impl IdHashItem for Account {
+    ///     type Key<'a> = &'a str;
+    ///     fn key(&self) -> Self::Key<'_> {
+    ///         &self.id
+    ///     }
+    ///     id_upcast!();
+    /// }
+    ///
+    /// let mut map = IdHashMap::new();
+    /// map.insert_unique(Account { id: "alice".to_string(), balance: 100 })
+    ///     .unwrap();
+    /// map.insert_unique(Account { id: "bob".to_string(), balance: 20 }).unwrap();
+    ///
+    /// // Transfer between two accounts.
+    /// let [Some(mut alice), Some(mut bob), None] =
+    ///     map.get_disjoint_mut(["alice", "bob", "carol"])
+    /// else {
+    ///     panic!("alice and bob should be present, carol should not");
+    /// };
+    /// alice.balance -= 30;
+    /// bob.balance += 30;
+    /// drop((alice, bob));
+    ///
+    /// assert_eq!(map.get("alice").unwrap().balance, 70);
+    /// assert_eq!(map.get("bob").unwrap().balance, 50);
+    /// # }
+    /// ```
+    pub fn get_disjoint_mut<'a, const N: usize, Q>(
+        &'a mut self,
+        keys: [&Q; N],
+    ) -> [Option<RefMut<'a, T, S>>; N]
+    where
+        Q: ?Sized + Hash + Equivalent<T::Key<'a>>,
+    {
+        let (dormant_map, indexes) = {
+            let (map, dormant_map) = DormantMutRef::new(self);
+            let indexes = keys.map(|key| map.find_index(key));
+            (dormant_map, indexes)
+        };
+
+        for (i, index) in indexes.iter().enumerate() {
+            if let Some(index) = index {
+                if indexes[..i].contains(&Some(*index)) {
+                    panic!("duplicate keys passed to get_disjoint_mut");
+                }
+            }
+        }
+
+        // SAFETY: `map` is not used after this point.
+        let awakened_map = unsafe { dormant_map.awaken() };
+        let tables = &awakened_map.tables;
+        // Missing keys are looked up as `ItemIndex::SENTINEL`, which is never
+        // a valid index, so `get_disjoint_mut` returns `None` for them.
+        let items = awakened_map.items.get_disjoint_mut(
+            indexes
+                .each_ref()
+                .map(|index| index.as_ref().unwrap_or(&ItemIndex::SENTINEL)),
+        );
+        items.map(|item| {
+            item.map(|item| {
+                let hash = tables.make_hash(item);
+                RefMut::new(tables.state.clone(), hash, item)
+            })
+        })
+    }
+
+    /// Calls a closure with a mutable reference to the value associated with
+    /// the given key, returning the closure's result.
+    ///
+    /// This is an alternative to [`Self::get_mut`] for scoped edits. The hash
+    /// stored for the item is reused, and the key is checked once after the
+    /// closure returns, so keys are hashed twice in total rather than three
+    /// times.
+    ///
+    /// Returns `None` without calling the closure if the key isn't present.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the closure changes the item's key. See [`RefMut`] for the
+    /// limits of this check.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "default-hasher")] {
+    /// use iddqd::{IdHashItem, IdHashMap, id_upcast};
+    ///
+    /// #[derive(Debug, PartialEq, Eq, Hash)]
+    /// struct Item {
+    ///     id: String,
+    ///     value: u32,
+    /// }
+    ///
+    /// impl IdHashItem for Item {
+    ///     type Key<'a> = &'a str;
+    ///     fn key(&self) -> Self::Key<'_> {
+    ///         &self.id
+    ///     }
+    ///     id_upcast!();
+    /// }
+    ///
+    /// let mut map = IdHashMap::new();
+    /// map.insert_unique(Item { id: "foo".to_string(), value: 42 }).unwrap();
+    ///
+    /// let old =
+    ///     map.with_mut("foo", |item| std::mem::replace(&mut item.value, 100));
+    /// assert_eq!(old, Some(42));
+    /// assert_eq!(map.get("foo").unwrap().value, 100);
+    /// assert_eq!(map.with_mut("bar", |item| item.value), None);
+    /// # }
+    /// ```
+    pub fn with_mut<'a, Q, R, F>(&'a mut self, key: &Q, f: F) -> Option<R>
+    where
+        Q: ?Sized + Hash + Equivalent<T::Key<'a>>,
+        F: FnOnce(&mut T) -> R,
+    {
+        let (dormant_map, index, hash) = {
+            let (map, dormant_map) = DormantMutRef::new(self);
+            let (index, hash) = map.tables.key_to_item.find_index_hashed(
+                &map.tables.state,
+                key,
+                |index| map.items[index].key(),
+            )?;
+            (dormant_map, index, hash)
+        };
+
+        // SAFETY: `map` is not used after this point.
+        let awakened_map = unsafe { dormant_map.awaken() };
+        let item = &mut awakened_map.items[index];
+        let ret = f(item);
+        if !hash.is_same_hash(&awakened_map.tables.state, item.key()) {
+            panic!("key changed during with_mut call");
+        }
+        Some(ret)
+    }
+
+    /// Gets a plain mutable reference to the value associated with the given
+    /// key.
+    ///
+    /// Unlike [`Self::get_mut`], this doesn't wrap the item in a [`RefMut`]
+    /// that checks the key on drop, so there's no need to hash the key twice.
+    /// It is only available for items that implement [`ImmutableKey`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "default-hasher")] {
+    /// use iddqd::{IdHashItem, IdHashMap, ImmutableKey, id_upcast};
+    ///
+    /// #[derive(Debug)]
+    /// struct Item {
+    ///     id: u32,
+    ///     value: u32,
+    /// }
+    ///
+    /// impl IdHashItem for Item {
+    ///     type Key<'a> = u32;
+    ///     fn key(&self) -> Self::Key<'_> {
+    ///         self.id
+    ///     }
+    ///     id_upcast!();
+    /// }
+    ///
+    /// // This program never changes `id` through a mutable reference.
+    /// impl ImmutableKey for Item {}
+    ///
+    /// let mut map = IdHashMap::new();
+    /// map.insert_unique(Item { id: 1, value: 42 }).unwrap();
+    ///
+    /// map.get_mut_plain(&1).unwrap().value = 100;
+    /// assert_eq!(map.get(&1).unwrap().value, 100);
+    /// assert!(map.get_mut_plain(&2).is_none());
+    /// # }
+    /// ```
+    pub fn get_mut_plain<'a, Q>(&'a mut self, key: &Q) -> Option<&'a mut T>
+    where
+        Q: ?Sized + Hash + Equivalent<T::Key<'a>>,
+        T: ImmutableKey,
+    {
+        let (dormant_map, index) = {
+            let (map, dormant_map) = DormantMutRef::new(self);
+            let index = map.find_index(key)?;
+            (dormant_map, index)
+        };
+
+        // SAFETY: `map` is not used after this point.
+        let awakened_map = unsafe { dormant_map.awaken() };
+        Some(&mut awakened_map.items[index])
+    }
+
+    /// Removes an item from the map by its key.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "default-hasher")] {
+    /// use iddqd::{IdHashItem, IdHashMap, id_upcast};
+    ///
+    /// #[derive(Debug, PartialEq, Eq, Hash)]
+    /// struct Item {
+    ///     id: String,
+    ///     value: u32,
+    /// }
+    ///
+    /// impl IdHashItem for Item {
+    ///     type Key<'a> = &'a str;
+    ///     fn key(&self) -> Self::Key<'_> {
+    ///         &self.id
+    ///     }
+    ///     id_upcast!();
+    /// }
+    ///
+    /// let mut map = IdHashMap::new();
+    /// map.insert_unique(Item { id: "foo".to_string(), value: 42 }).unwrap();
+    ///
+    /// let removed = map.remove("foo");
+    /// assert_eq!(removed.unwrap().value, 42);
+    /// assert!(map.is_empty());
+    ///
+    /// // Removing non-existent key returns None
+    /// assert!(map.remove("bar").is_none());
+    /// # }
+    /// ```
+    pub fn remove<'a, Q>(&'a mut self, key: &Q) -> Option<T>
+    where
+        Q: ?Sized + Hash + Equivalent<T::Key<'a>>,
+    {
+        let (dormant_map, remove_index) = {
+            let (map, dormant_map) = DormantMutRef::new(self);
+            let remove_index = map.find_index(key)?;
+            (dormant_map, remove_index)
+        };
+        // SAFETY: `map` is not used after this point.
+        let awakened_map = unsafe { dormant_map.awaken() };
+        awakened_map.remove_by_index(remove_index)
+    }
+
+    /// Removes an item from the map by its key, returning the index of the
+    /// slot it occupied along with the item.
+    ///
+    /// The index is the same slot index returned by
+    /// [`get_index_of`](Self::get_index_of). After removal, the slot is vacant
+    /// and may be reused by a later insertion.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "default-hasher")] {
+    /// use iddqd::{IdHashItem, IdHashMap, id_upcast};
+    ///
+    /// #[derive(Debug, PartialEq, Eq, Hash)]
+    /// struct Item {
+    ///     id: String,
+    ///     value: u32,
+    /// }
+    ///
+    /// impl IdHashItem for Item {
+    ///     type Key<'a> = &'a str;
+    ///     fn key(&self) -> Self::Key<'_> {
+    ///         &self.id
+    ///     }
+    ///     id_upcast!();
+    /// }
+    ///
+    /// let mut map = IdHashMap::new();
+    /// map.insert_unique(Item { id: "foo".to_string(), value: 42 }).unwrap();
+    /// map.insert_unique(Item { id: "bar".to_string(), value: 20 }).unwrap();
+    ///
+    /// let (index, removed) = map.remove_full("foo").unwrap();
+    /// assert_eq!(index, 0);
+    /// assert_eq!(removed.value, 42);
+    ///
+    /// // Removing non-existent key returns None
+    /// assert!(map.remove_full("baz").is_none());
+    /// # }
+    /// ```
+    pub fn remove_full<'a, Q>(&'a mut self, key: &Q) -> Option<(usize, T)>
+    where
+        Q: ?Sized + Hash + Equivalent<T::Key<'a>>,
+    {
+        let (dormant_map, remove_index) = {
+            let (map, dormant_map) = DormantMutRef::new(self);
+            let remove_index = map.find_index(key)?;
+            (dormant_map, remove_index)
+        };
+        // SAFETY: `map` is not used after this point.
+        let awakened_map = unsafe { dormant_map.awaken() };
+        let item = awakened_map.remove_by_index(remove_index)?;
+        Some((remove_index.as_u32() as usize, item))
+    }
+
+    /// Removes the items for each of the given keys, returning the removed
+    /// items in the order of `keys`.
+    ///
+    /// Keys that aren't present in the map, or that were already removed by
+    /// an earlier key in `keys`, are skipped.
+    ///
+    /// All keys are looked up before any item is removed, so if iterating over
+    /// `keys` panics, the map is left unchanged.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "default-hasher")] {
+    /// use iddqd::{IdHashItem, IdHashMap, id_upcast};
+    ///
+    /// #[derive(Debug, PartialEq, Eq, Hash)]
+    /// struct Item {
+    ///     id: String,
+    ///     value: u32,
+    /// }
+    ///
+    /// impl IdHashItem for Item {
+    ///     type Key<'a> = &'a str;
+    ///     fn key(&self) -> Self::Key<'_> {
+    ///         &self.id
+    ///     }
+    ///     id_upcast!();
+    /// }
+    ///
+    /// let mut map = IdHashMap::new();
+    /// map.insert_unique(Item { id: "foo".to_string(), value: 42 }).unwrap();
+    /// map.insert_unique(Item { id: "bar".to_string(), value: 20 }).unwrap();
+    /// map.insert_unique(Item { id: "baz".to_string(), value: 30 }).unwrap();
+    ///
+    /// let removed = map.remove_all(["baz", "missing", "foo"]);
+    /// let values: Vec<_> = removed.iter().map(|item| item.value).collect();
+    /// assert_eq!(values, [30, 42]);
+    /// assert_eq!(map.len(), 1);
+    /// assert!(map.contains_key("bar"));
+    /// # }
+    /// ```
+    pub fn remove_all<'a, 'q, Q, I>(&'a mut self, keys: I) -> Vec<T>
+    where
+        Q: 'q + ?Sized + Hash + Equivalent<T::Key<'a>>,
+        I: IntoIterator<Item = &'q Q>,
+    {
+        let (dormant_map, remove_indexes) = {
+            let (map, dormant_map) = DormantMutRef::new(self);
+            let remove_indexes: Vec<_> = keys
+                .into_iter()
+                .filter_map(|key| map.find_index(key))
+                .collect();
+            (dormant_map, remove_indexes)
+        };
+        // SAFETY: `map` is not used after this point.
+        let awakened_map = unsafe { dormant_map.awaken() };
+        // A key that appears more than once maps to the same index every time.
+        // `remove_by_index` returns `None` for an index that has already been
+        // vacated, so repeated keys are skipped.
+        remove_indexes
+            .into_iter()
+            .filter_map(|index| awakened_map.remove_by_index(index))
+            .collect()
+    }
+
+    /// Retrieves an entry by its key.
+    ///
+    /// Due to borrow checker limitations, this always accepts a `T::Key<'_>`
+    /// by value rather than an arbitrary borrowed form of it. For `Copy` keys
+    /// such as `u32`, that just means passing the key itself. To look up an
+    /// entry without constructing a `T::Key<'_>`, use
+    /// [`IdHashMap::entry_ref`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "default-hasher")] {
+    /// use iddqd::{IdHashItem, IdHashMap, id_upcast};
+    ///
+    /// #[derive(Debug, PartialEq, Eq, Hash)]
+    /// struct Item {
+    ///     id: String,
+    ///     value: u32,
+    /// }
+    ///
+    /// impl IdHashItem for Item {
+    ///     type Key<'a> = &'a str;
+    ///     fn key(&self) -> Self::Key<'_> {
+    ///         &self.id
+    ///     }
+    ///     id_upcast!();
+    /// }
+    ///
+    /// let mut map = IdHashMap::new();
+    ///
+    /// // Use entry API for conditional insertion
+    /// map.entry("foo").or_insert(Item { id: "foo".to_string(), value: 42 });
+    /// map.entry("bar").or_insert(Item { id: "bar".to_string(), value: 20 });
+    ///
+    /// assert_eq!(map.len(), 2);
+    /// # }
+    /// ```
+    pub fn entry<'a>(&'a mut self, key: T::Key<'_>) -> Entry<'a, T, S, A> {
+        // Why does this always take an owned key? Well, it would seem like we
+        // should be able to pass in any Q that is equivalent. That results in
+        // *this* code compiling fine, but callers have trouble using it because
+        // the borrow checker believes the keys are borrowed for the full 'a
+        // rather than a shorter lifetime.
+        //
+        // By accepting owned keys, we can use the upcast functions to convert
+        // them to a shorter lifetime (so this function accepts T::Key<'_>
+        // rather than T::Key<'a>).
+        //
+        // Really, the solution here is to allow GATs to require covariant
+        // parameters. If that were allowed, the borrow checker should be able
+        // to figure out that keys don't need to be borrowed for the full 'a,
+        // just for some shorter lifetime.
+        let (map, dormant_map) = DormantMutRef::new(self);
+        let key = T::upcast_key(key);
+        {
+            // index is explicitly typed to show that it has a trivial Drop impl
+            // that doesn't capture anything from map.
+            let index: Option<ItemIndex> = map.tables.key_to_item.find_index(
+                &map.tables.state,
+                &key,
+                |index| map.items[index].key(),
+            );
+            if let Some(index) = index {
+                drop(key);
+                return Entry::Occupied(
+                    // SAFETY: `map` is not used after this point.
+                    unsafe { OccupiedEntry::new(dormant_map, index) },
+                );
+            }
+        }
+        let hash = map.make_key_hash(&key);
+        Entry::Vacant(
+            // SAFETY: `map` is not used after this point.
+            unsafe { VacantEntry::new(dormant_map, hash) },
+        )
+    }
+
+    /// Retrieves an entry by a borrowed form of its `key`.
+    ///
+    /// This is like [`IdHashMap::entry`], except that it accepts any `Q`
+    /// equivalent to the key, so looking up an entry doesn't require
+    /// constructing an owned key. The key is only used to find an existing
+    /// item and to compute a hash, and is not retained by the returned
+    /// [`Entry`].
+    ///
+    /// As with [`IdHashMap::get`], `Q` is tied to the lifetime of the map
+    /// borrow. For keys that borrow from the item, such as `&'a str`, pass the
+    /// unsized form (here `str`) so that the query can be dropped while the
+    /// entry is still alive.
     ///
     /// # Examples
     ///
     /// ```
     /// # #[cfg(feature = "default-hasher")] {
-    /// use iddqd::{IdHashItem, IdHashMap, id_upcast};
+    /// use iddqd::{IdHashItem, IdHashMap, id_hash_map, id_upcast};
     ///
     /// #[derive(Debug, PartialEq, Eq, Hash)]
     /// struct Item {
@@ -1182,18 +3281,72 @@ impl<T: IdHashItem, S: Clone + BuildHasher, A: Allocator> IdHashMap<T, S, A> {
     /// let mut map = IdHashMap::new();
     /// map.insert_unique(Item { id: "foo".to_string(), value: 42 }).unwrap();
     ///
-    /// assert_eq!(map.get("foo").unwrap().value, 42);
-    /// assert!(map.get("bar").is_none());
+    /// // Look up an entry through a borrowed `str`.
+    /// let query = String::from("foo");
+    /// match map.entry_ref(query.as_str()) {
+    ///     id_hash_map::Entry::Occupied(mut entry) => {
+    ///         entry.get_mut().value = 99;
+    ///     }
+    ///     id_hash_map::Entry::Vacant(_) => panic!("Should be occupied"),
+    /// }
+    ///
+    /// match map.entry_ref("bar") {
+    ///     id_hash_map::Entry::Occupied(_) => panic!("Should be vacant"),
+    ///     id_hash_map::Entry::Vacant(entry) => {
+    ///         entry.insert(Item { id: "bar".to_string(), value: 7 });
+    ///     }
+    /// }
+    ///
+    /// assert_eq!(map.get("foo").unwrap().value, 99);
+    /// assert_eq!(map.get("bar").unwrap().value, 7);
     /// # }
     /// ```
-    pub fn get<'a, Q>(&'a self, key: &Q) -> Option<&'a T>
+    pub fn entry_ref<'a, Q>(&'a mut self, key: &Q) -> Entry<'a, T, S, A>
     where
         Q: ?Sized + Hash + Equivalent<T::Key<'a>>,
     {
-        self.find_index(key).map(|ix| &self.items[ix])
+        // Soundness: `key` is only used within this block, to find an index
+        // and to compute a hash. Neither the index nor the hash borrow from
+        // `key` or from `map`, so `map` is not used after this point and it is
+        // sound to hand `dormant_map` to the entry.
+        //
+        // The `Equivalent` contract requires that `Q` hashes the same way as
+        // the key, so the hash stored in a `VacantEntry` matches the one that
+        // `VacantEntry::insert` checks against the item's own key.
+        let (map, dormant_map) = DormantMutRef::new(self);
+        // index is explicitly typed to show that it has a trivial Drop impl
+        // that doesn't capture anything from map.
+        let index: Option<ItemIndex> = map.tables.key_to_item.find_index(
+            &map.tables.state,
+            key,
+            |index| map.items[index].key(),
+        );
+        match index {
+            Some(index) => Entry::Occupied(
+                // SAFETY: `map` is not used after this point.
+                unsafe { OccupiedEntry::new(dormant_map, index) },
+            ),
+            None => {
+                let hash = MapHash::new(map.tables.state.hash_one(key));
+                Entry::Vacant(
+                    // SAFETY: `map` is not used after this point.
+                    unsafe { VacantEntry::new(dormant_map, hash) },
+                )
+            }
+        }
     }
 
-    /// Gets a mutable reference to the value associated with the given key.
+    /// Gets a mutable reference to the item with the given key, inserting an
+    /// item built from the key if it isn't present.
+    ///
+    /// This is useful for "increment-or-create" patterns over items whose
+    /// non-key fields have sensible defaults. The new item is created via
+    /// `T::from(key)`, and the key of the resulting item must match `key`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the item created by `T::from(key)` has a key that hashes to
+    /// a different value than `key`.
     ///
     /// # Examples
     ///
@@ -1202,12 +3355,12 @@ impl<T: IdHashItem, S: Clone + BuildHasher, A: Allocator> IdHashMap<T, S, A> {
     /// use iddqd::{IdHashItem, IdHashMap, id_upcast};
     ///
     /// #[derive(Debug, PartialEq, Eq, Hash)]
-    /// struct Item {
+    /// struct Counter {
     ///     id: String,
-    ///     value: u32,
+    ///     count: u32,
     /// }
     ///
-    /// impl IdHashItem for Item {
+    /// impl IdHashItem for Counter {
     ///     type Key<'a> = &'a str;
     ///     fn key(&self) -> Self::Key<'_> {
     ///         &self.id
@@ -1215,36 +3368,45 @@ impl<T: IdHashItem, S: Clone + BuildHasher, A: Allocator> IdHashMap<T, S, A> {
     ///     id_upcast!();
     /// }
     ///
-    /// let mut map = IdHashMap::new();
-    /// map.insert_unique(Item { id: "foo".to_string(), value: 42 }).unwrap();
+    /// impl From<&str> for Counter {
+    ///     fn from(id: &str) -> Self {
+    ///         Counter { id: id.to_string(), count: 0 }
+    ///     }
+    /// }
     ///
-    /// if let Some(mut item) = map.get_mut("foo") {
-    ///     item.value = 100;
+    /// let mut map = IdHashMap::<Counter>::new();
+    /// for word in ["foo", "bar", "foo"] {
+    ///     map.get_or_insert_default(word).count += 1;
     /// }
     ///
-    /// assert_eq!(map.get("foo").unwrap().value, 100);
-    /// assert!(map.get_mut("bar").is_none());
+    /// assert_eq!(map.get("foo").unwrap().count, 2);
+    /// assert_eq!(map.get("bar").unwrap().count, 1);
     /// # }
     /// ```
-    pub fn get_mut<'a, Q>(&'a mut self, key: &Q) -> Option<RefMut<'a, T, S>>
+    pub fn get_or_insert_default<'k>(
+        &mut self,
+        key: T::Key<'k>,
+    ) -> RefMut<'_, T, S>
     where
-        Q: ?Sized + Hash + Equivalent<T::Key<'a>>,
+        T: From<T::Key<'k>>,
+        T::Key<'k>: Clone,
     {
-        let (dormant_map, index) = {
-            let (map, dormant_map) = DormantMutRef::new(self);
-            let index = map.find_index(key)?;
-            (dormant_map, index)
-        };
-
-        // SAFETY: `map` is not used after this point.
-        let awakened_map = unsafe { dormant_map.awaken() };
-        let item = &mut awakened_map.items[index];
-        let state = awakened_map.tables.state.clone();
-        let hashes = awakened_map.tables.make_hash(item);
-        Some(RefMut::new(state, hashes, item))
+        match self.entry(key.clone()) {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => entry.insert(T::from(key)),
+        }
     }
 
-    /// Removes an item from the map by its key.
+    /// Gets a reference to the item with the given key, inserting the result
+    /// of `f` if it isn't present.
+    ///
+    /// This is a shorthand for the common "look up or compute" pattern, similar
+    /// to `map.entry(key).or_insert_with(f)` but returning a shared reference.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `f` is called and returns an item whose key doesn't match
+    /// `key`.
     ///
     /// # Examples
     ///
@@ -1269,100 +3431,217 @@ impl<T: IdHashItem, S: Clone + BuildHasher, A: Allocator> IdHashMap<T, S, A> {
     /// let mut map = IdHashMap::new();
     /// map.insert_unique(Item { id: "foo".to_string(), value: 42 }).unwrap();
     ///
-    /// let removed = map.remove("foo");
-    /// assert_eq!(removed.unwrap().value, 42);
-    /// assert!(map.is_empty());
+    /// // An existing item is returned as-is, without calling the closure.
+    /// let item = map.get_or_insert_with("foo", || unreachable!());
+    /// assert_eq!(item.value, 42);
     ///
-    /// // Removing non-existent key returns None
-    /// assert!(map.remove("bar").is_none());
+    /// // A missing item is computed and inserted.
+    /// let item = map.get_or_insert_with("bar", || Item {
+    ///     id: "bar".to_string(),
+    ///     value: 20,
+    /// });
+    /// assert_eq!(item.value, 20);
+    /// assert_eq!(map.len(), 2);
     /// # }
     /// ```
-    pub fn remove<'a, Q>(&'a mut self, key: &Q) -> Option<T>
-    where
-        Q: ?Sized + Hash + Equivalent<T::Key<'a>>,
-    {
-        let (dormant_map, remove_index) = {
-            let (map, dormant_map) = DormantMutRef::new(self);
-            let remove_index = map.find_index(key)?;
-            (dormant_map, remove_index)
+    pub fn get_or_insert_with<F: FnOnce() -> T>(
+        &mut self,
+        key: T::Key<'_>,
+        f: F,
+    ) -> &T {
+        // As with `entry`, accepting an owned key lets us upcast it to a
+        // lifetime that ends before the map is mutated below.
+        let key = T::upcast_key(key);
+        let index = self.tables.key_to_item.find_index(
+            &self.tables.state,
+            &key,
+            |index| self.items[index].key(),
+        );
+        let index = match index {
+            Some(index) => {
+                drop(key);
+                index
+            }
+            None => {
+                let value = f();
+                // As in `replace_at_index`, we check the key before inserting
+                // the value, to avoid leaving the map in an inconsistent
+                // state.
+                if T::upcast_key(key) != value.key() {
+                    panic!(
+                        "must insert a value with \
+                         the same key used for the lookup"
+                    );
+                }
+                let Ok(index) = self.insert_unique_impl(value) else {
+                    panic!("key already present in map");
+                };
+                index
+            }
         };
-        // SAFETY: `map` is not used after this point.
-        let awakened_map = unsafe { dormant_map.awaken() };
-        awakened_map.remove_by_index(remove_index)
+        &self.items[index]
     }
 
-    /// Retrieves an entry by its key.
+    /// Consumes the map, moving its items into a new map that uses `alloc`.
     ///
-    /// Due to borrow checker limitations, this always accepts an owned key
-    /// rather than a borrowed form of it.
+    /// Items are moved rather than cloned, and the key tables are rebuilt in
+    /// the new allocator. The new map uses the same hasher as this one. This is
+    /// useful for moving a map between arenas, for example between processing
+    /// phases.
+    ///
+    /// Requires the `allocator-api2` feature to be enabled.
     ///
     /// # Examples
     ///
+    /// Using the [`bumpalo`](https://docs.rs/bumpalo) allocator:
+    ///
     /// ```
-    /// # #[cfg(feature = "default-hasher")] {
+    /// # #[cfg(feature = "allocator-api2")] {
     /// use iddqd::{IdHashItem, IdHashMap, id_upcast};
+    /// use std::collections::hash_map::RandomState;
+    /// # use iddqd_test_utils::bumpalo;
     ///
-    /// #[derive(Debug, PartialEq, Eq, Hash)]
+    /// #[derive(Debug, PartialEq, Eq)]
     /// struct Item {
-    ///     id: String,
-    ///     value: u32,
+    ///     id: u32,
+    ///     value: String,
     /// }
     ///
     /// impl IdHashItem for Item {
-    ///     type Key<'a> = &'a str;
+    ///     type Key<'a> = u32;
     ///     fn key(&self) -> Self::Key<'_> {
-    ///         &self.id
+    ///         self.id
     ///     }
     ///     id_upcast!();
     /// }
     ///
-    /// let mut map = IdHashMap::new();
+    /// let phase1 = bumpalo::Bump::new();
+    /// let mut map: IdHashMap<Item, _, &bumpalo::Bump> =
+    ///     IdHashMap::with_hasher_in(RandomState::new(), &phase1);
+    /// map.insert_unique(Item { id: 1, value: "foo".to_string() }).unwrap();
     ///
-    /// // Use entry API for conditional insertion
-    /// map.entry("foo").or_insert(Item { id: "foo".to_string(), value: 42 });
-    /// map.entry("bar").or_insert(Item { id: "bar".to_string(), value: 20 });
+    /// // Move the map into a second arena, so the first one can be reset.
+    /// let phase2 = bumpalo::Bump::new();
+    /// let map = map.into_alloc(&phase2);
+    /// assert_eq!(map.get(&1).unwrap().value, "foo");
+    /// drop(phase1);
+    /// assert_eq!(map.len(), 1);
+    /// # }
+    /// ```
+    pub fn into_alloc<A2: Clone + Allocator>(
+        self,
+        alloc: A2,
+    ) -> IdHashMap<T, S, A2> {
+        let mut map = IdHashMap::with_capacity_and_hasher_in(
+            self.len(),
+            self.tables.state,
+            alloc,
+        );
+        for item in self.items.into_values() {
+            // Keys in this map are already unique, and capacity has been
+            // reserved up front, so there's no need to check for duplicates.
+            let hash = map.tables.make_hash(&item);
+            let next_index = map.items.assert_can_grow().insert(item);
+            map.tables.key_to_item.insert_prehashed_unchecked(hash, next_index);
+        }
+
+        map
+    }
+
+    /// Consumes the map, transforming each item with `f` and collecting the
+    /// results into a new map.
     ///
-    /// assert_eq!(map.len(), 2);
+    /// The new map uses the same hasher and allocator as this one. Keys may
+    /// change under `f`, as long as the transformed items remain unique. If a
+    /// transformed item conflicts with one produced earlier, this returns a
+    /// [`DuplicateItem`] error containing the new item and the conflicting
+    /// items, and drops the rest of the map.
+    ///
+    /// To overwrite duplicates instead, use `into_iter().map(f).collect()`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "default-hasher")] {
+    /// use iddqd::{IdHashItem, IdHashMap, id_upcast};
+    ///
+    /// #[derive(Debug)]
+    /// struct Raw {
+    ///     id: u32,
+    ///     text: String,
+    /// }
+    ///
+    /// impl IdHashItem for Raw {
+    ///     type Key<'a> = u32;
+    ///     fn key(&self) -> Self::Key<'_> {
+    ///         self.id
+    ///     }
+    ///     id_upcast!();
+    /// }
+    ///
+    /// #[derive(Debug)]
+    /// struct Processed {
+    ///     id: u32,
+    ///     len: usize,
+    /// }
+    ///
+    /// impl IdHashItem for Processed {
+    ///     type Key<'a> = u32;
+    ///     fn key(&self) -> Self::Key<'_> {
+    ///         self.id
+    ///     }
+    ///     id_upcast!();
+    /// }
+    ///
+    /// let mut raw = IdHashMap::new();
+    /// raw.insert_unique(Raw { id: 1, text: "foo".to_string() }).unwrap();
+    /// raw.insert_unique(Raw { id: 2, text: "hello".to_string() }).unwrap();
+    ///
+    /// let processed = raw
+    ///     .map(|r| Processed { id: r.id, len: r.text.len() })
+    ///     .expect("keys are preserved");
+    /// assert_eq!(processed.get(&1).unwrap().len, 3);
+    /// assert_eq!(processed.get(&2).unwrap().len, 5);
+    ///
+    /// // Mapping every item to the same key produces an error.
+    /// let err = processed.map(|p| Processed { id: 0, len: p.len }).unwrap_err();
+    /// assert_eq!(err.duplicates().len(), 1);
     /// # }
     /// ```
-    pub fn entry<'a>(&'a mut self, key: T::Key<'_>) -> Entry<'a, T, S, A> {
-        // Why does this always take an owned key? Well, it would seem like we
-        // should be able to pass in any Q that is equivalent. That results in
-        // *this* code compiling fine, but callers have trouble using it because
-        // the borrow checker believes the keys are borrowed for the full 'a
-        // rather than a shorter lifetime.
-        //
-        // By accepting owned keys, we can use the upcast functions to convert
-        // them to a shorter lifetime (so this function accepts T::Key<'_>
-        // rather than T::Key<'a>).
-        //
-        // Really, the solution here is to allow GATs to require covariant
-        // parameters. If that were allowed, the borrow checker should be able
-        // to figure out that keys don't need to be borrowed for the full 'a,
-        // just for some shorter lifetime.
-        let (map, dormant_map) = DormantMutRef::new(self);
-        let key = T::upcast_key(key);
-        {
-            // index is explicitly typed to show that it has a trivial Drop impl
-            // that doesn't capture anything from map.
-            let index: Option<ItemIndex> = map.tables.key_to_item.find_index(
-                &map.tables.state,
-                &key,
-                |index| map.items[index].key(),
-            );
-            if let Some(index) = index {
-                drop(key);
-                return Entry::Occupied(
-                    // SAFETY: `map` is not used after this point.
-                    unsafe { OccupiedEntry::new(dormant_map, index) },
-                );
+    pub fn map<U, F>(
+        self,
+        mut f: F,
+    ) -> Result<IdHashMap<U, S, A>, DuplicateItem<U>>
+    where
+        U: IdHashItem,
+        F: FnMut(T) -> U,
+        A: Clone,
+    {
+        let alloc = self.items.allocator().clone();
+        let mut map = IdHashMap::with_capacity_and_hasher_in(
+            self.len(),
+            self.tables.state,
+            alloc,
+        );
+        for item in self.items.into_values() {
+            let value = f(item);
+            // Go through the Entry API, as in `from_iter_unique`, so that the
+            // error can hold owned duplicates without requiring U: Clone.
+            match map.entry(value.key()) {
+                Entry::Occupied(entry) => {
+                    let duplicate = entry.remove();
+                    return Err(DuplicateItem::__internal_new(
+                        value,
+                        vec![duplicate],
+                    ));
+                }
+                Entry::Vacant(entry) => {
+                    entry.insert_known_unique(value);
+                }
             }
         }
-        let hash = map.make_key_hash(&key);
-        Entry::Vacant(
-            // SAFETY: `map` is not used after this point.
-            unsafe { VacantEntry::new(dormant_map, hash) },
-        )
+
+        Ok(map)
     }
 
     /// Retains only the elements specified by the predicate.
@@ -1370,6 +3649,9 @@ impl<T: IdHashItem, S: Clone + BuildHasher, A: Allocator> IdHashMap<T, S, A> {
     /// In other words, remove all items `T` for which `f(RefMut<T>)` returns
     /// false. The elements are visited in an arbitrary order.
     ///
+    /// The remaining items are compacted afterwards, so that no holes are left
+    /// behind by the removed ones. The map's capacity is unchanged.
+    ///
     /// # Examples
     ///
     /// ```
@@ -1470,9 +3752,83 @@ impl<T: IdHashItem, S: Clone + BuildHasher, A: Allocator> IdHashMap<T, S, A> {
             }
         });
 
+        // Fill the holes left by removed items, so that the map stays
+        // compact.
+        //
+        // SAFETY: The closure above, which held the only reborrows of
+        // `dormant_items`, has returned.
+        let items = unsafe { dormant_items.awaken() };
+        let remap = items.compact();
+        if !remap.is_identity() {
+            self.tables.key_to_item.remap_indexes(&remap);
+        }
+
         // Anything in `removed_item` is implicitly dropped now.
     }
 
+    /// Removes all items for which `f` returns true, returning them.
+    ///
+    /// This is the inverse of [`Self::retain`], except that the predicate takes
+    /// `&T` and the removed items are returned rather than dropped. The key
+    /// table is updated as items are removed. The items are visited, and
+    /// returned, in an arbitrary order.
+    ///
+    /// If `f` panics, no items are removed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "default-hasher")] {
+    /// use iddqd::{IdHashItem, IdHashMap, id_upcast};
+    ///
+    /// #[derive(Debug, PartialEq, Eq)]
+    /// struct Item {
+    ///     id: u32,
+    ///     stale: bool,
+    /// }
+    ///
+    /// impl IdHashItem for Item {
+    ///     type Key<'a> = u32;
+    ///     fn key(&self) -> Self::Key<'_> {
+    ///         self.id
+    ///     }
+    ///     id_upcast!();
+    /// }
+    ///
+    /// let mut map = IdHashMap::new();
+    /// map.insert_unique(Item { id: 1, stale: true }).unwrap();
+    /// map.insert_unique(Item { id: 2, stale: false }).unwrap();
+    /// map.insert_unique(Item { id: 3, stale: true }).unwrap();
+    ///
+    /// let mut removed = map.remove_where(|item| item.stale);
+    /// removed.sort_by_key(|item| item.id);
+    /// assert_eq!(
+    ///     removed,
+    ///     vec![Item { id: 1, stale: true }, Item { id: 3, stale: true }],
+    /// );
+    /// assert_eq!(map.len(), 1);
+    /// assert!(map.contains_key(&2));
+    /// # }
+    /// ```
+    pub fn remove_where<F>(&mut self, mut f: F) -> Vec<T>
+    where
+        F: FnMut(&T) -> bool,
+    {
+        // Find all the matching indexes first, so that a panic in `f` leaves
+        // the map untouched.
+        let indexes: Vec<ItemIndex> = self
+            .items
+            .iter()
+            .filter_map(|(index, item)| f(item).then_some(index))
+            .collect();
+        indexes
+            .into_iter()
+            .map(|index| {
+                self.remove_by_index(index).expect("index is present in items")
+            })
+            .collect()
+    }
+
     fn find_index<'a, Q>(&'a self, k: &Q) -> Option<ItemIndex>
     where
         Q: Hash + Equivalent<T::Key<'a>> + ?Sized,
@@ -1490,11 +3846,11 @@ impl<T: IdHashItem, S: Clone + BuildHasher, A: Allocator> IdHashMap<T, S, A> {
         self.tables.make_key_hash::<T>(key)
     }
 
-    pub(super) fn get_by_index(&self, index: ItemIndex) -> Option<&T> {
+    pub(super) fn get_by_item_index(&self, index: ItemIndex) -> Option<&T> {
         self.items.get(index)
     }
 
-    pub(super) fn get_by_index_mut(
+    pub(super) fn get_by_item_index_mut(
         &mut self,
         index: ItemIndex,
     ) -> Option<RefMut<'_, T, S>> {
@@ -1508,6 +3864,19 @@ impl<T: IdHashItem, S: Clone + BuildHasher, A: Allocator> IdHashMap<T, S, A> {
         &mut self,
         value: T,
     ) -> Result<ItemIndex, DuplicateItem<T, &T>> {
+        match self.insert_unique_or_dup_indexes(value) {
+            Ok(index) => Ok(index),
+            Err((value, duplicates)) => Err(DuplicateItem::__internal_new(
+                value,
+                duplicates.iter().map(|ix| &self.items[*ix]).collect(),
+            )),
+        }
+    }
+
+    fn insert_unique_or_dup_indexes(
+        &mut self,
+        value: T,
+    ) -> Result<ItemIndex, (T, BTreeSet<ItemIndex>)> {
         let mut duplicates = BTreeSet::new();
 
         // Check for duplicates *before* inserting the new item, because we
@@ -1529,10 +3898,7 @@ impl<T: IdHashItem, S: Clone + BuildHasher, A: Allocator> IdHashMap<T, S, A> {
         };
 
         if !duplicates.is_empty() {
-            return Err(DuplicateItem::__internal_new(
-                value,
-                duplicates.iter().map(|ix| &self.items[*ix]).collect(),
-            ));
+            return Err((value, duplicates));
         }
 
         let next_index = self.items.assert_can_grow().insert(value);
@@ -1543,12 +3909,12 @@ impl<T: IdHashItem, S: Clone + BuildHasher, A: Allocator> IdHashMap<T, S, A> {
 
     pub(super) fn try_reserve_insert_overwrite_commit(
         &mut self,
-    ) -> Result<(), crate::errors::TryReserveError> {
+    ) -> Result<(), TryReserveError> {
         self.items.try_reserve(1)?;
         self.tables
             .key_to_item
             .try_reserve(1)
-            .map_err(crate::errors::TryReserveError::from_hashbrown)?;
+            .map_err(TryReserveError::from_hashbrown)?;
         Ok(())
     }
 
@@ -1600,8 +3966,10 @@ impl<T: IdHashItem, S: Clone + BuildHasher, A: Allocator> IdHashMap<T, S, A> {
     pub(super) fn replace_at_index(&mut self, index: ItemIndex, value: T) -> T {
         // We check the key before removing it, to avoid leaving the map in an
         // inconsistent state.
-        let old_key =
-            self.get_by_index(index).expect("index is known to be valid").key();
+        let old_key = self
+            .get_by_item_index(index)
+            .expect("index is known to be valid")
+            .key();
         if T::upcast_key(old_key) != value.key() {
             panic!(
                 "must insert a value with \
@@ -1696,8 +4064,23 @@ impl<T: IdHashItem + Eq, S: Clone + BuildHasher, A: Allocator> Eq
 {
 }
 
-/// The `Extend` implementation overwrites duplicates. In the future, there will
-/// also be an `extend_unique` method that will return an error.
+/// The hash of a `IdHashMap` doesn't depend on the order its items are stored
+/// in, so it is consistent with the `PartialEq` implementation.
+///
+/// Each item is hashed with a fixed-seed hasher, independent of the map's own
+/// hasher, and the results are combined with a commutative sum. As a result,
+/// equal maps hash the same even if they use hashers with different seeds.
+impl<T: IdHashItem + Hash, S: Clone + BuildHasher, A: Allocator> Hash
+    for IdHashMap<T, S, A>
+{
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.items.hash_unordered(state);
+    }
+}
+
+/// The `Extend` implementation overwrites duplicates. To return an error on
+/// duplicates instead, use [`IdHashMap::extend_with`] with
+/// [`ExtendMode::Unique`].
 ///
 /// # Examples
 ///
@@ -1738,20 +4121,8 @@ impl<T: IdHashItem, S: Clone + BuildHasher, A: Allocator> Extend<T>
     for IdHashMap<T, S, A>
 {
     fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
-        // Keys may already be present in the map, or multiple times in the
-        // iterator. Reserve the entire hint lower bound if the map is empty.
-        // Otherwise reserve half the hint (rounded up), so the map will only
-        // resize twice in the worst case.
-        let iter = iter.into_iter();
-        let reserve = if self.is_empty() {
-            iter.size_hint().0
-        } else {
-            iter.size_hint().0.div_ceil(2)
-        };
-        self.reserve(reserve);
-        for item in iter {
-            self.insert_overwrite(item);
-        }
+        // Overwrite mode never returns an error.
+        let _ = self.extend_with(iter, ExtendMode::Overwrite);
     }
 }
 
@@ -1961,7 +4332,7 @@ mod tests {
     }
     impl Eq for CountedKey {}
 
-    #[derive(Debug)]
+    #[derive(Clone, Debug)]
     struct CountedItem {
         id: u32,
     }
@@ -2013,4 +4384,32 @@ mod tests {
         map.validate(ValidateCompact::NonCompact)
             .expect("map remains valid after reserve");
     }
+
+    #[test]
+    fn clone_does_not_rehash() {
+        let mut map = IdHashMap::<CountedItem, _>::with_hasher(
+            foldhash::fast::FixedState::with_seed(0),
+        );
+        for id in 0..32 {
+            let _ = map.insert_overwrite(CountedItem { id });
+        }
+        // Leave some vacant slots, which the clone should preserve.
+        map.remove(&CountedKey(3));
+        map.remove(&CountedKey(17));
+
+        USER_HASH_CALLS.with(|c| c.set(0));
+        let cloned = map.clone();
+        let mut cloned_from =
+            IdHashMap::with_hasher(foldhash::fast::FixedState::with_seed(1));
+        cloned_from.clone_from(&map);
+        let user_calls = USER_HASH_CALLS.with(Cell::get);
+        assert_eq!(user_calls, 0, "cloning must not invoke user `Hash`");
+
+        cloned
+            .validate(ValidateCompact::NonCompact)
+            .expect("cloned map is valid");
+        cloned_from
+            .validate(ValidateCompact::NonCompact)
+            .expect("clone_from target is valid");
+    }
 }
@@ -1,4 +1,4 @@
-use super::{RefMut, tables::IdHashMapTables};
+use super::{IdHashMap, RefMut, tables::IdHashMapTables};
 use crate::{
     DefaultHashBuilder, IdHashItem,
     support::{
@@ -6,7 +6,12 @@ use crate::{
         item_set::{self, ItemSet},
     },
 };
-use core::{hash::BuildHasher, iter::FusedIterator};
+use core::{
+    fmt,
+    hash::BuildHasher,
+    iter::{Chain, FusedIterator},
+    marker::PhantomData,
+};
 
 /// An iterator over the elements of a [`IdHashMap`] by shared reference.
 /// Created by [`IdHashMap::iter`].
@@ -35,6 +40,11 @@ impl<'a, T: IdHashItem> Iterator for Iter<'a, T> {
     fn next(&mut self) -> Option<Self::Item> {
         self.inner.next()
     }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
 }
 
 impl<T: IdHashItem> ExactSizeIterator for Iter<'_, T> {
@@ -46,6 +56,45 @@ impl<T: IdHashItem> ExactSizeIterator for Iter<'_, T> {
 
 impl<T: IdHashItem> FusedIterator for Iter<'_, T> {}
 
+/// An iterator over the keys of an [`IdHashMap`].
+///
+/// Created by [`IdHashMap::keys`].
+///
+/// Similar to [`HashMap`], the iteration order is arbitrary and not guaranteed
+/// to be stable.
+///
+/// [`IdHashMap`]: crate::IdHashMap
+/// [`IdHashMap::keys`]: crate::IdHashMap::keys
+/// [`HashMap`]: std::collections::HashMap
+#[derive(Clone, Debug, Default)]
+pub struct Keys<'a, T: IdHashItem> {
+    inner: Iter<'a, T>,
+}
+
+impl<'a, T: IdHashItem> Keys<'a, T> {
+    pub(super) fn new(inner: Iter<'a, T>) -> Self {
+        Self { inner }
+    }
+}
+
+impl<'a, T: IdHashItem> Iterator for Keys<'a, T> {
+    type Item = T::Key<'a>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(T::key)
+    }
+}
+
+impl<T: IdHashItem> ExactSizeIterator for Keys<'_, T> {
+    #[inline]
+    fn len(&self) -> usize {
+        self.inner.len()
+    }
+}
+
+impl<T: IdHashItem> FusedIterator for Keys<'_, T> {}
+
 /// An iterator over the elements of a [`IdHashMap`] by mutable reference.
 /// Created by [`IdHashMap::iter_mut`].
 ///
@@ -88,6 +137,11 @@ impl<'a, T: IdHashItem, S: Clone + BuildHasher, A: Allocator> Iterator
         let hashes = self.tables.make_hash(next);
         Some(RefMut::new(self.tables.state.clone(), hashes, next))
     }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
 }
 
 impl<T: IdHashItem, S: Clone + BuildHasher, A: Allocator> ExactSizeIterator
@@ -131,6 +185,11 @@ impl<T: IdHashItem, A: Allocator> Iterator for IntoIter<T, A> {
     fn next(&mut self) -> Option<Self::Item> {
         self.inner.next()
     }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
 }
 
 impl<T: IdHashItem, A: Allocator> ExactSizeIterator for IntoIter<T, A> {
@@ -141,3 +200,515 @@ impl<T: IdHashItem, A: Allocator> ExactSizeIterator for IntoIter<T, A> {
 }
 
 impl<T: IdHashItem, A: Allocator> FusedIterator for IntoIter<T, A> {}
+
+/// An iterator over the elements of a [`IdHashMap`] by ownership, along with
+/// their indexes. Created by [`IdHashMap::into_iter_indexed`].
+///
+/// Similar to [`HashMap`], the iteration order is arbitrary and not guaranteed
+/// to be stable.
+///
+/// [`IdHashMap`]: crate::IdHashMap
+/// [`IdHashMap::into_iter_indexed`]: crate::IdHashMap::into_iter_indexed
+/// [`HashMap`]: std::collections::HashMap
+#[derive(Debug)]
+pub struct IntoIterIndexed<T: IdHashItem, A: Allocator = Global> {
+    inner: item_set::IntoIter<T, A>,
+}
+
+impl<T: IdHashItem, A: Allocator> IntoIterIndexed<T, A> {
+    pub(crate) fn new(items: ItemSet<T, A>) -> Self {
+        Self { inner: items.into_iter_indexed() }
+    }
+}
+
+impl<T: IdHashItem, A: Allocator> Iterator for IntoIterIndexed<T, A> {
+    type Item = (usize, T);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let (index, item) = self.inner.next()?;
+        Some((index.as_u32() as usize, item))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<T: IdHashItem, A: Allocator> ExactSizeIterator for IntoIterIndexed<T, A> {
+    #[inline]
+    fn len(&self) -> usize {
+        self.inner.len()
+    }
+}
+
+impl<T: IdHashItem, A: Allocator> FusedIterator for IntoIterIndexed<T, A> {}
+
+/// An iterator over the keys of an [`IdHashMap`] by ownership. Created by
+/// [`IdHashMap::into_keys`].
+///
+/// Keys of type [`IdHashItem::Key`] borrow from their items, so they can't
+/// outlive the items that produced them. This iterator takes each item out of
+/// the map, converts its key into the owned type `K`, and then drops the item.
+///
+/// Similar to [`HashMap`], the iteration order is arbitrary and not guaranteed
+/// to be stable.
+///
+/// [`IdHashMap`]: crate::IdHashMap
+/// [`IdHashMap::into_keys`]: crate::IdHashMap::into_keys
+/// [`HashMap`]: std::collections::HashMap
+#[derive(Debug)]
+pub struct IntoKeys<T: IdHashItem, K, A: Allocator = Global> {
+    inner: IntoIter<T, A>,
+    _marker: PhantomData<fn() -> K>,
+}
+
+impl<T: IdHashItem, K, A: Allocator> IntoKeys<T, K, A> {
+    pub(crate) fn new(items: ItemSet<T, A>) -> Self {
+        Self { inner: IntoIter::new(items), _marker: PhantomData }
+    }
+}
+
+impl<T: IdHashItem, K, A: Allocator> Iterator for IntoKeys<T, K, A>
+where
+    for<'k> T::Key<'k>: Into<K>,
+{
+    type Item = K;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        // The key borrows from `item`, so it must be converted before `item`
+        // is dropped at the end of this function.
+        let item = self.inner.next()?;
+        Some(item.key().into())
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<T: IdHashItem, K, A: Allocator> ExactSizeIterator for IntoKeys<T, K, A>
+where
+    for<'k> T::Key<'k>: Into<K>,
+{
+    #[inline]
+    fn len(&self) -> usize {
+        self.inner.len()
+    }
+}
+
+impl<T: IdHashItem, K, A: Allocator> FusedIterator for IntoKeys<T, K, A> where
+    for<'k> T::Key<'k>: Into<K>
+{
+}
+
+/// An iterator over the elements of a [`IdHashMap`] by ownership, which frees
+/// backing storage as it goes. Created by [`IdHashMap::into_iter_dropping`].
+///
+/// Similar to [`HashMap`], the iteration order is arbitrary and not guaranteed
+/// to be stable.
+///
+/// [`IdHashMap`]: crate::IdHashMap
+/// [`IdHashMap::into_iter_dropping`]: crate::IdHashMap::into_iter_dropping
+/// [`HashMap`]: std::collections::HashMap
+#[derive(Debug)]
+pub struct IntoIterDropping<T: IdHashItem, A: Allocator = Global> {
+    inner: item_set::IntoValuesShrinking<T, A>,
+}
+
+impl<T: IdHashItem, A: Allocator> IntoIterDropping<T, A> {
+    pub(crate) fn new(items: ItemSet<T, A>) -> Self {
+        Self { inner: items.into_values_shrinking() }
+    }
+
+    /// Returns the number of item slots the iterator currently has allocated.
+    ///
+    /// This shrinks as the iterator is consumed.
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.inner.capacity()
+    }
+}
+
+impl<T: IdHashItem, A: Allocator> Iterator for IntoIterDropping<T, A> {
+    type Item = T;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next()
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<T: IdHashItem, A: Allocator> ExactSizeIterator for IntoIterDropping<T, A> {
+    #[inline]
+    fn len(&self) -> usize {
+        self.inner.len()
+    }
+}
+
+impl<T: IdHashItem, A: Allocator> FusedIterator for IntoIterDropping<T, A> {}
+
+/// A draining iterator over the elements of an [`IdHashMap`]. Created by
+/// [`IdHashMap::drain`].
+///
+/// The map is emptied as soon as the iterator is created, keeping its
+/// allocated capacity. If the iterator is dropped before it is exhausted, the
+/// remaining items are dropped as well.
+///
+/// Similar to [`HashMap`], the iteration order is arbitrary and not guaranteed
+/// to be stable.
+///
+/// [`IdHashMap`]: crate::IdHashMap
+/// [`IdHashMap::drain`]: crate::IdHashMap::drain
+/// [`HashMap`]: std::collections::HashMap
+#[derive(Debug)]
+pub struct Drain<'a, T: IdHashItem, A: Allocator = Global> {
+    inner: item_set::Drain<'a, T, A>,
+}
+
+impl<'a, T: IdHashItem, A: Allocator> Drain<'a, T, A> {
+    pub(super) fn new(items: &'a mut ItemSet<T, A>) -> Self {
+        Self { inner: items.drain() }
+    }
+}
+
+impl<T: IdHashItem, A: Allocator> Iterator for Drain<'_, T, A> {
+    type Item = T;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next()
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<T: IdHashItem, A: Allocator> ExactSizeIterator for Drain<'_, T, A> {
+    #[inline]
+    fn len(&self) -> usize {
+        self.inner.len()
+    }
+}
+
+impl<T: IdHashItem, A: Allocator> FusedIterator for Drain<'_, T, A> {}
+
+/// A lazy iterator over the items of an [`IdHashMap`] whose keys are also
+/// present in another map.
+///
+/// Created by [`IdHashMap::intersection`].
+///
+/// Similar to [`HashSet`], the iteration order is arbitrary and not guaranteed
+/// to be stable.
+///
+/// [`IdHashMap`]: crate::IdHashMap
+/// [`IdHashMap::intersection`]: crate::IdHashMap::intersection
+/// [`HashSet`]: std::collections::HashSet
+pub struct Intersection<
+    'a,
+    T: IdHashItem,
+    S = DefaultHashBuilder,
+    A: Allocator = Global,
+> {
+    iter: item_set::Values<'a, T>,
+    other: &'a IdHashMap<T, S, A>,
+}
+
+impl<'a, T: IdHashItem, S, A: Allocator> Intersection<'a, T, S, A> {
+    pub(super) fn new(
+        iter: item_set::Values<'a, T>,
+        other: &'a IdHashMap<T, S, A>,
+    ) -> Self {
+        Self { iter, other }
+    }
+}
+
+impl<T: IdHashItem, S, A: Allocator> Clone for Intersection<'_, T, S, A> {
+    fn clone(&self) -> Self {
+        Self { iter: self.iter.clone(), other: self.other }
+    }
+}
+
+impl<T, S, A> fmt::Debug for Intersection<'_, T, S, A>
+where
+    T: IdHashItem + fmt::Debug,
+    S: Clone + BuildHasher,
+    A: Allocator,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.clone()).finish()
+    }
+}
+
+impl<'a, T, S, A> Iterator for Intersection<'a, T, S, A>
+where
+    T: IdHashItem,
+    S: Clone + BuildHasher,
+    A: Allocator,
+{
+    type Item = &'a T;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let other = self.other;
+        self.iter.find(|item| other.contains_key(&item.key()))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (_, upper) = self.iter.size_hint();
+        (0, upper)
+    }
+}
+
+impl<T, S, A> FusedIterator for Intersection<'_, T, S, A>
+where
+    T: IdHashItem,
+    S: Clone + BuildHasher,
+    A: Allocator,
+{
+}
+
+/// A lazy iterator over the items of an [`IdHashMap`] whose keys are not
+/// present in another map.
+///
+/// Created by [`IdHashMap::difference`].
+///
+/// Similar to [`HashSet`], the iteration order is arbitrary and not guaranteed
+/// to be stable.
+///
+/// [`IdHashMap`]: crate::IdHashMap
+/// [`IdHashMap::difference`]: crate::IdHashMap::difference
+/// [`HashSet`]: std::collections::HashSet
+pub struct Difference<
+    'a,
+    T: IdHashItem,
+    S = DefaultHashBuilder,
+    A: Allocator = Global,
+> {
+    iter: item_set::Values<'a, T>,
+    other: &'a IdHashMap<T, S, A>,
+}
+
+impl<'a, T: IdHashItem, S, A: Allocator> Difference<'a, T, S, A> {
+    pub(super) fn new(
+        iter: item_set::Values<'a, T>,
+        other: &'a IdHashMap<T, S, A>,
+    ) -> Self {
+        Self { iter, other }
+    }
+}
+
+impl<T: IdHashItem, S, A: Allocator> Clone for Difference<'_, T, S, A> {
+    fn clone(&self) -> Self {
+        Self { iter: self.iter.clone(), other: self.other }
+    }
+}
+
+impl<T, S, A> fmt::Debug for Difference<'_, T, S, A>
+where
+    T: IdHashItem + fmt::Debug,
+    S: Clone + BuildHasher,
+    A: Allocator,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.clone()).finish()
+    }
+}
+
+impl<'a, T, S, A> Iterator for Difference<'a, T, S, A>
+where
+    T: IdHashItem,
+    S: Clone + BuildHasher,
+    A: Allocator,
+{
+    type Item = &'a T;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let other = self.other;
+        self.iter.find(|item| !other.contains_key(&item.key()))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (_, upper) = self.iter.size_hint();
+        (0, upper)
+    }
+}
+
+impl<T, S, A> FusedIterator for Difference<'_, T, S, A>
+where
+    T: IdHashItem,
+    S: Clone + BuildHasher,
+    A: Allocator,
+{
+}
+
+/// A lazy iterator over the items of two [`IdHashMap`]s whose keys are
+/// present in exactly one of the maps.
+///
+/// Created by [`IdHashMap::symmetric_difference`].
+///
+/// Similar to [`HashSet`], the iteration order is arbitrary and not guaranteed
+/// to be stable.
+///
+/// [`IdHashMap`]: crate::IdHashMap
+/// [`IdHashMap::symmetric_difference`]: crate::IdHashMap::symmetric_difference
+/// [`HashSet`]: std::collections::HashSet
+pub struct SymmetricDifference<
+    'a,
+    T: IdHashItem,
+    S = DefaultHashBuilder,
+    A: Allocator = Global,
+> {
+    iter: Chain<Difference<'a, T, S, A>, Difference<'a, T, S, A>>,
+}
+
+impl<'a, T, S, A> SymmetricDifference<'a, T, S, A>
+where
+    T: IdHashItem,
+    S: Clone + BuildHasher,
+    A: Allocator,
+{
+    pub(super) fn new(
+        a: Difference<'a, T, S, A>,
+        b: Difference<'a, T, S, A>,
+    ) -> Self {
+        Self { iter: a.chain(b) }
+    }
+}
+
+impl<T: IdHashItem, S, A: Allocator> Clone
+    for SymmetricDifference<'_, T, S, A>
+{
+    fn clone(&self) -> Self {
+        Self { iter: self.iter.clone() }
+    }
+}
+
+impl<T, S, A> fmt::Debug for SymmetricDifference<'_, T, S, A>
+where
+    T: IdHashItem + fmt::Debug,
+    S: Clone + BuildHasher,
+    A: Allocator,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.clone()).finish()
+    }
+}
+
+impl<'a, T, S, A> Iterator for SymmetricDifference<'a, T, S, A>
+where
+    T: IdHashItem,
+    S: Clone + BuildHasher,
+    A: Allocator,
+{
+    type Item = &'a T;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.next()
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<T, S, A> FusedIterator for SymmetricDifference<'_, T, S, A>
+where
+    T: IdHashItem,
+    S: Clone + BuildHasher,
+    A: Allocator,
+{
+}
+
+/// A lazy iterator over the items of two [`IdHashMap`]s, yielding each key
+/// once.
+///
+/// Created by [`IdHashMap::union`].
+///
+/// Similar to [`HashSet`], the iteration order is arbitrary and not guaranteed
+/// to be stable.
+///
+/// [`IdHashMap`]: crate::IdHashMap
+/// [`IdHashMap::union`]: crate::IdHashMap::union
+/// [`HashSet`]: std::collections::HashSet
+pub struct Union<
+    'a,
+    T: IdHashItem,
+    S = DefaultHashBuilder,
+    A: Allocator = Global,
+> {
+    iter: Chain<item_set::Values<'a, T>, Difference<'a, T, S, A>>,
+}
+
+impl<'a, T, S, A> Union<'a, T, S, A>
+where
+    T: IdHashItem,
+    S: Clone + BuildHasher,
+    A: Allocator,
+{
+    pub(super) fn new(
+        a: item_set::Values<'a, T>,
+        b: Difference<'a, T, S, A>,
+    ) -> Self {
+        Self { iter: a.chain(b) }
+    }
+}
+
+impl<T: IdHashItem, S, A: Allocator> Clone for Union<'_, T, S, A> {
+    fn clone(&self) -> Self {
+        Self { iter: self.iter.clone() }
+    }
+}
+
+impl<T, S, A> fmt::Debug for Union<'_, T, S, A>
+where
+    T: IdHashItem + fmt::Debug,
+    S: Clone + BuildHasher,
+    A: Allocator,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.clone()).finish()
+    }
+}
+
+impl<'a, T, S, A> Iterator for Union<'a, T, S, A>
+where
+    T: IdHashItem,
+    S: Clone + BuildHasher,
+    A: Allocator,
+{
+    type Item = &'a T;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.next()
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<T, S, A> FusedIterator for Union<'_, T, S, A>
+where
+    T: IdHashItem,
+    S: Clone + BuildHasher,
+    A: Allocator,
+{
+}
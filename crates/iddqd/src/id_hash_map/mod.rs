@@ -2,6 +2,10 @@
 //!
 //! For more information, see [`IdHashMap`].
 
+#[cfg(feature = "arbitrary")]
+mod arbitrary_impls;
+#[cfg(feature = "concurrent")]
+mod concurrent;
 #[cfg(feature = "daft")]
 mod daft_impls;
 mod entry;
@@ -9,7 +13,12 @@ pub(crate) mod imp;
 mod iter;
 #[cfg(feature = "proptest")]
 mod proptest_impls;
+mod raw_table;
+#[cfg(feature = "rayon")]
+mod rayon_impls;
 mod ref_mut;
+#[cfg(feature = "schemars1")]
+mod schemars1_impls;
 #[cfg(feature = "schemars08")]
 mod schemars_impls;
 #[cfg(feature = "serde")]
@@ -17,11 +26,16 @@ mod serde_impls;
 mod tables;
 pub(crate) mod trait_defs;
 
+#[cfg(feature = "concurrent")]
+pub use concurrent::ConcurrentIdHashMap;
 #[cfg(feature = "daft")]
 pub use daft_impls::Diff;
 pub use entry::{Entry, OccupiedEntry, VacantEntry};
 pub use imp::IdHashMap;
-pub use iter::{IntoIter, Iter, IterMut};
+pub use iter::{
+    Difference, Drain, Intersection, IntoIter, IntoIterDropping,
+    IntoIterIndexed, IntoKeys, Iter, IterMut, Keys, SymmetricDifference, Union,
+};
 #[cfg(all(feature = "proptest", feature = "default-hasher"))]
 pub use proptest_impls::prop_strategy;
 #[cfg(feature = "proptest")]
@@ -29,6 +43,10 @@ pub use proptest_impls::{
     IdHashMapStrategy, IdHashMapValueTree, prop_strategy_with_hasher,
     prop_strategy_with_hasher_in,
 };
+#[doc(hidden)]
+pub use raw_table::RawTableView;
+#[cfg(feature = "rayon")]
+pub use rayon_impls::ParIter;
 pub use ref_mut::RefMut;
 #[cfg(feature = "serde")]
 pub use serde_impls::IdHashMapAsMap;
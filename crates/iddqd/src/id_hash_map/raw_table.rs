@@ -0,0 +1,105 @@
+//! Read-only access to the internal layout of an [`IdHashMap`].
+//!
+//! [`IdHashMap`]: super::IdHashMap
+
+use super::tables::IdHashMapTables;
+use crate::support::{ItemIndex, alloc::Allocator, item_set::ItemSet};
+use core::fmt;
+
+/// A read-only view into the internal layout of an
+/// [`IdHashMap`](super::IdHashMap).
+///
+/// Returned by [`IdHashMap::raw_table`](super::IdHashMap::raw_table). This is
+/// an escape hatch for building specialized indexes on top of an
+/// `IdHashMap`, and is **not** covered by semver: the layout it exposes may
+/// change in any release.
+///
+/// # Layout
+///
+/// The map stores items in a set of slots, each identified by a `u32` index:
+///
+/// * Slot indexes are in `0..slot_count()`. Each slot is either occupied by
+///   an item or vacant.
+/// * An item's slot index is stable for as long as the item remains in the
+///   map, except that `retain`, `shrink_to_fit` and `shrink_to` compact the
+///   slots and may renumber every item.
+/// * A vacant slot may be reused by a later insertion.
+///
+/// Separately, the key table maps keys to slots:
+///
+/// * The key table holds exactly one entry per occupied slot, and no entries
+///   for vacant slots.
+/// * Each entry stores the slot index together with the hash of that item's
+///   key, as computed by [`hasher`](Self::hasher). The order of entries is
+///   unspecified.
+///
+/// A view borrows the map immutably, so these invariants hold for the
+/// lifetime of the view.
+pub struct RawTableView<'a, T, S, A: Allocator> {
+    items: &'a ItemSet<T, A>,
+    tables: &'a IdHashMapTables<S, A>,
+}
+
+impl<'a, T, S, A: Allocator> RawTableView<'a, T, S, A> {
+    pub(super) fn new(
+        items: &'a ItemSet<T, A>,
+        tables: &'a IdHashMapTables<S, A>,
+    ) -> Self {
+        Self { items, tables }
+    }
+
+    /// Returns the number of slots, occupied or vacant.
+    #[inline]
+    pub fn slot_count(&self) -> usize {
+        self.items.slot_count()
+    }
+
+    /// Returns the item in the given slot, or `None` if the slot is vacant
+    /// or out of range.
+    #[inline]
+    pub fn get_slot(&self, index: u32) -> Option<&'a T> {
+        self.items.get(ItemIndex::new(index))
+    }
+
+    /// Returns an iterator over `(slot index, item)` pairs for occupied
+    /// slots, in slot order.
+    pub fn slots(
+        &self,
+    ) -> impl Iterator<Item = (u32, &'a T)> + use<'a, T, S, A> {
+        self.items.iter().map(|(ix, item)| (ix.as_u32(), item))
+    }
+
+    /// Returns an iterator over `(key hash, slot index)` pairs in the key
+    /// table, in unspecified order.
+    pub fn key_table(
+        &self,
+    ) -> impl Iterator<Item = (u64, u32)> + use<'a, T, S, A> {
+        self.tables
+            .key_to_item
+            .iter_hashed()
+            .map(|(hash, ix)| (hash, ix.as_u32()))
+    }
+
+    /// Returns the hasher used to compute key hashes.
+    #[inline]
+    pub fn hasher(&self) -> &'a S {
+        &self.tables.state
+    }
+}
+
+impl<T, S, A: Allocator> Clone for RawTableView<'_, T, S, A> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T, S, A: Allocator> Copy for RawTableView<'_, T, S, A> {}
+
+impl<T, S, A: Allocator> fmt::Debug for RawTableView<'_, T, S, A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RawTableView")
+            .field("len", &self.items.len())
+            .field("slot_count", &self.items.slot_count())
+            .finish_non_exhaustive()
+    }
+}
@@ -0,0 +1,73 @@
+//! Parallel iteration over [`IdHashMap`]s with [`rayon`].
+
+use super::{IdHashItem, IdHashMap};
+use crate::support::{alloc::Allocator, item_set::ParValues};
+use rayon::iter::{
+    IntoParallelIterator, ParallelIterator, plumbing::UnindexedConsumer,
+};
+
+/// A parallel iterator over the elements of an [`IdHashMap`] by shared
+/// reference.
+///
+/// Created by calling [`par_iter`] (or [`into_par_iter`] on a reference) with
+/// the `rayon` feature enabled.
+///
+/// Similar to [`IdHashMap::iter`], the iteration order is arbitrary.
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(feature = "default-hasher")] {
+/// use iddqd::{IdHashItem, IdHashMap, id_upcast};
+/// use rayon::prelude::*;
+///
+/// struct Item {
+///     id: u32,
+///     value: u64,
+/// }
+///
+/// impl IdHashItem for Item {
+///     type Key<'a> = u32;
+///     fn key(&self) -> Self::Key<'_> {
+///         self.id
+///     }
+///     id_upcast!();
+/// }
+///
+/// let map: IdHashMap<Item> =
+///     (0..100).map(|id| Item { id, value: u64::from(id) * 2 }).collect();
+/// let total: u64 = map.par_iter().map(|item| item.value).sum();
+/// assert_eq!(total, 9900);
+/// # }
+/// ```
+///
+/// [`par_iter`]: rayon::iter::IntoParallelRefIterator::par_iter
+/// [`into_par_iter`]: rayon::iter::IntoParallelIterator::into_par_iter
+#[derive(Clone, Debug)]
+pub struct ParIter<'a, T: IdHashItem> {
+    inner: ParValues<'a, T>,
+}
+
+impl<'a, T: IdHashItem + Sync> ParallelIterator for ParIter<'a, T> {
+    type Item = &'a T;
+
+    #[inline]
+    fn drive_unindexed<C>(self, consumer: C) -> C::Result
+    where
+        C: UnindexedConsumer<Self::Item>,
+    {
+        self.inner.drive_unindexed(consumer)
+    }
+}
+
+impl<'a, T: IdHashItem + Sync, S, A: Allocator> IntoParallelIterator
+    for &'a IdHashMap<T, S, A>
+{
+    type Iter = ParIter<'a, T>;
+    type Item = &'a T;
+
+    #[inline]
+    fn into_par_iter(self) -> Self::Iter {
+        ParIter { inner: self.items.par_values() }
+    }
+}
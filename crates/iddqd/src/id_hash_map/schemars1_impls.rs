@@ -0,0 +1,61 @@
+//! Schemars 1.x implementations for IdHashMap.
+
+use crate::{
+    id_hash_map::{
+        imp::IdHashMap, serde_impls::IdHashMapAsMap, trait_defs::IdHashItem,
+    },
+    support::{
+        alloc::Allocator,
+        schemars1_utils::{create_map_schema, create_object_schema},
+    },
+};
+use alloc::borrow::Cow;
+use schemars1::{JsonSchema, Schema, SchemaGenerator};
+
+impl<T, S, A> JsonSchema for IdHashMap<T, S, A>
+where
+    T: JsonSchema + IdHashItem,
+    A: Allocator,
+{
+    fn schema_name() -> Cow<'static, str> {
+        alloc::format!("IdHashMap_of_{}", T::schema_name()).into()
+    }
+
+    fn schema_id() -> Cow<'static, str> {
+        alloc::format!("iddqd::IdHashMap<{}>", T::schema_id()).into()
+    }
+
+    fn json_schema(generator: &mut SchemaGenerator) -> Schema {
+        create_map_schema::<T>("IdHashMap", "iddqd::IdHashMap", generator)
+    }
+
+    fn inline_schema() -> bool {
+        true
+    }
+}
+
+impl<T, S, A> JsonSchema for IdHashMapAsMap<T, S, A>
+where
+    T: JsonSchema + IdHashItem,
+    A: Allocator,
+{
+    fn schema_name() -> Cow<'static, str> {
+        alloc::format!("IdHashMapAsMap_of_{}", T::schema_name()).into()
+    }
+
+    fn schema_id() -> Cow<'static, str> {
+        alloc::format!("iddqd::IdHashMapAsMap<{}>", T::schema_id()).into()
+    }
+
+    fn json_schema(generator: &mut SchemaGenerator) -> Schema {
+        create_object_schema::<T>(
+            "IdHashMapAsMap",
+            "iddqd::IdHashMap",
+            generator,
+        )
+    }
+
+    fn inline_schema() -> bool {
+        true
+    }
+}
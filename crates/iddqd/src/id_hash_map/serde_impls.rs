@@ -89,6 +89,13 @@ where
 /// against the value. (In general, verification is not possible because the key
 /// type has a lifetime parameter embedded in it.)
 ///
+/// The indexes are deliberately not part of the serialized form, so there is
+/// no way to skip rebuilding them on load. Stored key hashes would only be
+/// meaningful for the exact hasher state that produced them (the
+/// [`DefaultHashBuilder`] is randomly seeded per process), and checking stored
+/// indexes against the items requires hashing and comparing every key: the
+/// same work as rebuilding them.
+///
 /// The `fmt::Debug` bound on `T` ensures better error reporting.
 impl<
     'de,
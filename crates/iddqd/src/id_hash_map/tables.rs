@@ -1,16 +1,31 @@
 use crate::{
     IdHashItem,
+    errors::TryReserveError,
     internal::{ValidateCompact, ValidationError},
     support::{alloc::Allocator, hash_table::MapHashTable, map_hash::MapHash},
 };
 use core::hash::BuildHasher;
 
-#[derive(Clone, Debug, Default)]
+#[derive(Debug, Default)]
 pub(super) struct IdHashMapTables<S, A: Allocator> {
     pub(super) state: S,
     pub(super) key_to_item: MapHashTable<A>,
 }
 
+impl<S: Clone, A: Clone + Allocator> Clone for IdHashMapTables<S, A> {
+    fn clone(&self) -> Self {
+        Self {
+            state: self.state.clone(),
+            key_to_item: self.key_to_item.clone(),
+        }
+    }
+
+    fn clone_from(&mut self, source: &Self) {
+        self.state.clone_from(&source.state);
+        self.key_to_item.clone_from(&source.key_to_item);
+    }
+}
+
 impl<S: BuildHasher, A: Allocator> IdHashMapTables<S, A> {
     #[cfg(feature = "daft")]
     pub(crate) fn hasher(&self) -> &S {
@@ -56,3 +71,15 @@ impl<S: BuildHasher, A: Allocator> IdHashMapTables<S, A> {
         self.key_to_item.compute_hash(&self.state, key)
     }
 }
+
+impl<S, A: Allocator> IdHashMapTables<S, A> {
+    pub(super) fn try_reserve(
+        &mut self,
+        additional: usize,
+    ) -> Result<(), TryReserveError> {
+        self.key_to_item
+            .try_reserve(additional)
+            .map_err(TryReserveError::from_hashbrown)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,32 @@
+//! [`Arbitrary`] implementation for [`IdOrdMap`], for use with fuzzers.
+
+use super::{IdOrdItem, IdOrdMap};
+use crate::support::alloc::Allocator;
+use arbitrary::{Arbitrary, Result, Unstructured};
+
+/// Generates an [`IdOrdMap`] from a list of arbitrary items.
+///
+/// Items are inserted in order with [`IdOrdMap::insert_overwrite`], so if
+/// several items have the same key, only the last one is kept. Duplicates are
+/// silently collapsed rather than causing generation to fail.
+impl<'a, T, A> Arbitrary<'a> for IdOrdMap<T, A>
+where
+    T: IdOrdItem + Arbitrary<'a>,
+    A: Allocator + Default,
+{
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let mut map = IdOrdMap::new_in(A::default());
+        for item in u.arbitrary_iter::<T>()? {
+            map.insert_overwrite(item?);
+        }
+        Ok(map)
+    }
+
+    fn arbitrary_take_rest(u: Unstructured<'a>) -> Result<Self> {
+        let mut map = IdOrdMap::new_in(A::default());
+        for item in u.arbitrary_take_rest_iter::<T>()? {
+            map.insert_overwrite(item?);
+        }
+        Ok(map)
+    }
+}
@@ -0,0 +1,233 @@
+use super::{IdOrdItem, IdOrdMap, RefMut};
+use crate::support::{
+    ItemIndex,
+    alloc::{Allocator, Global},
+};
+use core::{fmt, hash::Hash};
+
+/// A cursor over an [`IdOrdMap`] that allows mutation.
+///
+/// A cursor points to a gap between two items in the map, or before the first
+/// item, or after the last one. It can move in either direction, and items
+/// can be inserted into or removed from either side of the gap.
+///
+/// Created by [`IdOrdMap::lower_bound_mut`] and
+/// [`IdOrdMap::upper_bound_mut`]. The API is modeled after the cursor API on
+/// [`BTreeMap`], which is currently unstable. Unlike that API, each movement
+/// is a lookup in the map's B-tree, and so takes *O*(log *n*) time.
+///
+/// [`IdOrdMap`]: crate::IdOrdMap
+/// [`IdOrdMap::lower_bound_mut`]: crate::IdOrdMap::lower_bound_mut
+/// [`IdOrdMap::upper_bound_mut`]: crate::IdOrdMap::upper_bound_mut
+/// [`BTreeMap`]: std::collections::BTreeMap
+pub struct CursorMut<'a, T: IdOrdItem, A: Allocator = Global> {
+    map: &'a mut IdOrdMap<T, A>,
+    // The items on either side of the gap, if any.
+    prev: Option<ItemIndex>,
+    next: Option<ItemIndex>,
+}
+
+impl<'a, T: IdOrdItem, A: Allocator> CursorMut<'a, T, A> {
+    pub(super) fn new(
+        map: &'a mut IdOrdMap<T, A>,
+        prev: Option<ItemIndex>,
+        next: Option<ItemIndex>,
+    ) -> Self {
+        Self { map, prev, next }
+    }
+
+    /// Returns the item after the cursor, without moving the cursor.
+    pub fn peek_next(&self) -> Option<&T> {
+        self.next.map(|index| &self.map.items[index])
+    }
+
+    /// Returns the item before the cursor, without moving the cursor.
+    pub fn peek_prev(&self) -> Option<&T> {
+        self.prev.map(|index| &self.map.items[index])
+    }
+
+    /// Returns a mutable reference to the item after the cursor, without
+    /// moving the cursor.
+    ///
+    /// The key of the item must not be changed. For more, see [`RefMut`].
+    pub fn peek_next_mut<'b>(&'b mut self) -> Option<RefMut<'b, T>>
+    where
+        T::Key<'b>: Hash,
+    {
+        self.map.get_by_index_mut(self.next?)
+    }
+
+    /// Returns a mutable reference to the item before the cursor, without
+    /// moving the cursor.
+    ///
+    /// The key of the item must not be changed. For more, see [`RefMut`].
+    pub fn peek_prev_mut<'b>(&'b mut self) -> Option<RefMut<'b, T>>
+    where
+        T::Key<'b>: Hash,
+    {
+        self.map.get_by_index_mut(self.prev?)
+    }
+
+    /// Moves the cursor past the next item, and returns that item.
+    ///
+    /// If the cursor is after the last item, returns `None` and leaves the
+    /// cursor in place.
+    // The returned item borrows from the cursor, so `Iterator` can't be
+    // implemented. The name matches the `BTreeMap` cursor API.
+    #[expect(clippy::should_implement_trait)]
+    pub fn next(&mut self) -> Option<&T> {
+        let next = self.next?;
+        let after = self.index_after(next);
+        self.prev = Some(next);
+        self.next = after;
+        Some(&self.map.items[next])
+    }
+
+    /// Moves the cursor before the previous item, and returns that item.
+    ///
+    /// If the cursor is before the first item, returns `None` and leaves the
+    /// cursor in place.
+    pub fn prev(&mut self) -> Option<&T> {
+        let prev = self.prev?;
+        let before = self.index_before(prev);
+        self.next = Some(prev);
+        self.prev = before;
+        Some(&self.map.items[prev])
+    }
+
+    /// Removes the item after the cursor, and returns it.
+    ///
+    /// The cursor stays in the same gap, which is now followed by the item
+    /// after the removed one.
+    pub fn remove_next(&mut self) -> Option<T> {
+        let next = self.next?;
+        let after = self.index_after(next);
+        let item = self
+            .map
+            .remove_by_index(next)
+            .expect("the cursor's next index is valid");
+        self.next = after;
+        Some(item)
+    }
+
+    /// Removes the item before the cursor, and returns it.
+    ///
+    /// The cursor stays in the same gap, which is now preceded by the item
+    /// before the removed one.
+    pub fn remove_prev(&mut self) -> Option<T> {
+        let prev = self.prev?;
+        let before = self.index_before(prev);
+        let item = self
+            .map
+            .remove_by_index(prev)
+            .expect("the cursor's previous index is valid");
+        self.prev = before;
+        Some(item)
+    }
+
+    /// Inserts an item into the gap, leaving the cursor before it.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the item's key isn't strictly greater than the key
+    /// of the item before the cursor, and strictly less than the key of the
+    /// item after it. In that case, the map isn't changed.
+    pub fn insert_after(
+        &mut self,
+        value: T,
+    ) -> Result<(), UnorderedItemError<T>> {
+        let value = self.check_order(value)?;
+        self.next = Some(self.map.insert_known_unique_impl(value));
+        Ok(())
+    }
+
+    /// Inserts an item into the gap, leaving the cursor after it.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the item's key isn't strictly greater than the key
+    /// of the item before the cursor, and strictly less than the key of the
+    /// item after it. In that case, the map isn't changed.
+    pub fn insert_before(
+        &mut self,
+        value: T,
+    ) -> Result<(), UnorderedItemError<T>> {
+        let value = self.check_order(value)?;
+        self.prev = Some(self.map.insert_known_unique_impl(value));
+        Ok(())
+    }
+
+    fn check_order(&self, value: T) -> Result<T, UnorderedItemError<T>> {
+        // Since the map is sorted, an item that fits strictly between its
+        // neighbors can't be a duplicate of any other item.
+        let in_order = {
+            let key = value.key();
+            self.prev.is_none_or(|prev| self.map.items[prev].key() < key)
+                && self.next.is_none_or(|next| key < self.map.items[next].key())
+        };
+        if in_order {
+            Ok(value)
+        } else {
+            Err(UnorderedItemError { item: value })
+        }
+    }
+
+    fn index_after(&self, index: ItemIndex) -> Option<ItemIndex> {
+        self.map
+            .tables
+            .key_to_item
+            .next_index(index, |index| self.map.items[index].key())
+    }
+
+    fn index_before(&self, index: ItemIndex) -> Option<ItemIndex> {
+        self.map
+            .tables
+            .key_to_item
+            .prev_index(index, |index| self.map.items[index].key())
+    }
+}
+
+impl<T: IdOrdItem + fmt::Debug, A: Allocator> fmt::Debug
+    for CursorMut<'_, T, A>
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CursorMut")
+            .field("prev", &self.peek_prev())
+            .field("next", &self.peek_next())
+            .finish()
+    }
+}
+
+/// The error returned by [`CursorMut::insert_after`] and
+/// [`CursorMut::insert_before`] when the item's key doesn't fit between the
+/// items on either side of the cursor.
+#[derive(Debug)]
+pub struct UnorderedItemError<T> {
+    item: T,
+}
+
+impl<T> UnorderedItemError<T> {
+    /// Returns the item that was attempted to be inserted.
+    #[inline]
+    pub fn item(&self) -> &T {
+        &self.item
+    }
+
+    /// Converts self into the item that was attempted to be inserted.
+    #[inline]
+    pub fn into_item(self) -> T {
+        self.item
+    }
+}
+
+impl<T: fmt::Debug> fmt::Display for UnorderedItemError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "item {:?} is out of order with the items around the cursor",
+            self.item
+        )
+    }
+}
+
+impl<T: fmt::Debug> core::error::Error for UnorderedItemError<T> {}
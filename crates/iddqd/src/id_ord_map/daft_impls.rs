@@ -1,19 +1,23 @@
 //! `Diffable` implementation.
 
 use super::{IdOrdItem, IdOrdMap};
-use crate::support::daft_utils::IdLeaf;
+use crate::support::{
+    alloc::{Allocator, Global},
+    daft_utils::IdLeaf,
+};
 use core::fmt;
 use daft::Diffable;
 use equivalent::Comparable;
 
-impl<T: IdOrdItem> Diffable for IdOrdMap<T> {
+impl<T: IdOrdItem, A: Clone + Allocator> Diffable for IdOrdMap<T, A> {
     type Diff<'a>
-        = Diff<'a, T>
+        = Diff<'a, T, A>
     where
-        T: 'a;
+        T: 'a,
+        A: 'a;
 
     fn diff<'daft>(&'daft self, other: &'daft Self) -> Self::Diff<'daft> {
-        let mut diff = Diff::new();
+        let mut diff = Diff::new_in(self.allocator().clone());
         for item in self {
             if let Some(other_item) = other.get(&item.key()) {
                 diff.common.insert_overwrite(IdLeaf::new(item, other_item));
@@ -75,20 +79,20 @@ impl<T: IdOrdItem> Diffable for IdOrdMap<T> {
 /// ```
 ///
 /// [`Diffable`]: daft::Diffable
-pub struct Diff<'daft, T: ?Sized + IdOrdItem> {
+pub struct Diff<'daft, T: ?Sized + IdOrdItem, A: Allocator = Global> {
     /// Entries common to both maps.
     ///
     /// Items are stored as [`IdLeaf`]s to references.
-    pub common: IdOrdMap<IdLeaf<&'daft T>>,
+    pub common: IdOrdMap<IdLeaf<&'daft T>, A>,
 
     /// Added entries.
-    pub added: IdOrdMap<&'daft T>,
+    pub added: IdOrdMap<&'daft T, A>,
 
     /// Removed entries.
-    pub removed: IdOrdMap<&'daft T>,
+    pub removed: IdOrdMap<&'daft T, A>,
 }
 
-impl<'a, 'daft, T> fmt::Debug for Diff<'daft, T>
+impl<'a, 'daft, T, A: Allocator> fmt::Debug for Diff<'daft, T, A>
 where
     T: ?Sized + IdOrdItem + fmt::Debug,
     T::Key<'a>: fmt::Debug,
@@ -115,7 +119,18 @@ impl<'daft, T: ?Sized + IdOrdItem> Diff<'daft, T> {
     }
 }
 
-impl<'daft, T: ?Sized + IdOrdItem + Eq> Diff<'daft, T> {
+impl<'daft, T: ?Sized + IdOrdItem, A: Clone + Allocator> Diff<'daft, T, A> {
+    /// Creates a new, empty `Diff` with the given allocator.
+    pub fn new_in(alloc: A) -> Self {
+        Self {
+            common: IdOrdMap::new_in(alloc.clone()),
+            added: IdOrdMap::new_in(alloc.clone()),
+            removed: IdOrdMap::new_in(alloc),
+        }
+    }
+}
+
+impl<'daft, T: ?Sized + IdOrdItem + Eq, A: Allocator> Diff<'daft, T, A> {
     /// Returns an iterator over unchanged keys and values.
     pub fn unchanged(&self) -> impl Iterator<Item = &'daft T> + '_ {
         self.common
@@ -184,9 +199,15 @@ impl<'daft, T: ?Sized + IdOrdItem + Eq> Diff<'daft, T> {
 
 // Note: not deriving Default here because we don't want to require
 // T to be Default.
-impl<'daft, T: IdOrdItem> Default for Diff<'daft, T> {
+impl<'daft, T: IdOrdItem, A: Allocator + Default> Default
+    for Diff<'daft, T, A>
+{
     fn default() -> Self {
-        Self::new()
+        Self {
+            common: IdOrdMap::default(),
+            added: IdOrdMap::default(),
+            removed: IdOrdMap::default(),
+        }
     }
 }
 
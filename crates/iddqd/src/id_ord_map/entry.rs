@@ -1,16 +1,20 @@
 use super::{IdOrdItem, IdOrdMap, RefMut};
-use crate::support::{ItemIndex, borrow::DormantMutRef};
+use crate::support::{
+    ItemIndex,
+    alloc::{Allocator, Global},
+    borrow::DormantMutRef,
+};
 use core::{fmt, hash::Hash};
 
 /// An implementation of the Entry API for [`IdOrdMap`].
-pub enum Entry<'a, T: IdOrdItem> {
+pub enum Entry<'a, T: IdOrdItem, A: Allocator = Global> {
     /// A vacant entry.
-    Vacant(VacantEntry<'a, T>),
+    Vacant(VacantEntry<'a, T, A>),
     /// An occupied entry.
-    Occupied(OccupiedEntry<'a, T>),
+    Occupied(OccupiedEntry<'a, T, A>),
 }
 
-impl<'a, T: IdOrdItem> fmt::Debug for Entry<'a, T> {
+impl<'a, T: IdOrdItem, A: Allocator> fmt::Debug for Entry<'a, T, A> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             Entry::Vacant(entry) => {
@@ -23,7 +27,7 @@ impl<'a, T: IdOrdItem> fmt::Debug for Entry<'a, T> {
     }
 }
 
-impl<'a, T: IdOrdItem> Entry<'a, T> {
+impl<'a, T: IdOrdItem, A: Allocator> Entry<'a, T, A> {
     /// Ensures a value is in the entry by inserting the default if empty, and
     /// returns a shared reference to the value in the entry.
     ///
@@ -136,18 +140,18 @@ impl<'a, T: IdOrdItem> Entry<'a, T> {
 }
 
 /// A vacant entry.
-pub struct VacantEntry<'a, T: IdOrdItem> {
-    map: DormantMutRef<'a, IdOrdMap<T>>,
+pub struct VacantEntry<'a, T: IdOrdItem, A: Allocator = Global> {
+    map: DormantMutRef<'a, IdOrdMap<T, A>>,
 }
 
-impl<'a, T: IdOrdItem> fmt::Debug for VacantEntry<'a, T> {
+impl<'a, T: IdOrdItem, A: Allocator> fmt::Debug for VacantEntry<'a, T, A> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("VacantEntry").finish_non_exhaustive()
     }
 }
 
-impl<'a, T: IdOrdItem> VacantEntry<'a, T> {
-    pub(super) unsafe fn new(map: DormantMutRef<'a, IdOrdMap<T>>) -> Self {
+impl<'a, T: IdOrdItem, A: Allocator> VacantEntry<'a, T, A> {
+    pub(super) unsafe fn new(map: DormantMutRef<'a, IdOrdMap<T, A>>) -> Self {
         VacantEntry { map }
     }
 
@@ -186,7 +190,7 @@ impl<'a, T: IdOrdItem> VacantEntry<'a, T> {
 
     /// Sets the value of the entry, and returns an `OccupiedEntry`.
     #[inline]
-    pub fn insert_entry(mut self, value: T) -> OccupiedEntry<'a, T> {
+    pub fn insert_entry(mut self, value: T) -> OccupiedEntry<'a, T, A> {
         let index = {
             // SAFETY: The safety assumption behind `Self::new` guarantees that the
             // original reference to the map is not used at this point.
@@ -205,13 +209,13 @@ impl<'a, T: IdOrdItem> VacantEntry<'a, T> {
 
 /// A view into an occupied entry in an [`IdOrdMap`]. Part of the [`Entry`]
 /// enum.
-pub struct OccupiedEntry<'a, T: IdOrdItem> {
-    map: DormantMutRef<'a, IdOrdMap<T>>,
+pub struct OccupiedEntry<'a, T: IdOrdItem, A: Allocator = Global> {
+    map: DormantMutRef<'a, IdOrdMap<T, A>>,
     // index is a valid index into the map's internal hash table.
     index: ItemIndex,
 }
 
-impl<'a, T: IdOrdItem> fmt::Debug for OccupiedEntry<'a, T> {
+impl<'a, T: IdOrdItem, A: Allocator> fmt::Debug for OccupiedEntry<'a, T, A> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("OccupiedEntry")
             .field("index", &self.index)
@@ -219,13 +223,13 @@ impl<'a, T: IdOrdItem> fmt::Debug for OccupiedEntry<'a, T> {
     }
 }
 
-impl<'a, T: IdOrdItem> OccupiedEntry<'a, T> {
+impl<'a, T: IdOrdItem, A: Allocator> OccupiedEntry<'a, T, A> {
     /// # Safety
     ///
     /// After self is created, the original reference created by
     /// `DormantMutRef::new` must not be used.
     pub(super) unsafe fn new(
-        map: DormantMutRef<'a, IdOrdMap<T>>,
+        map: DormantMutRef<'a, IdOrdMap<T, A>>,
         index: ItemIndex,
     ) -> Self {
         OccupiedEntry { map, index }
@@ -1,22 +1,27 @@
 use super::{
-    Entry, IdOrdItem, IntoIter, Iter, IterMut, OccupiedEntry, RefMut,
-    VacantEntry, tables::IdOrdMapTables,
+    CursorMut, Drain, Entry, IdOrdItem, IntoIter, IntoKeys, Iter, IterBy,
+    IterMut, Keys, OccupiedEntry, Range, RefMut, VacantEntry,
+    tables::IdOrdMapTables,
 };
 use crate::{
-    errors::DuplicateItem,
+    CapacityBreakdown, ExtendMode, ImmutableKey,
+    errors::{DuplicateItem, MapIntegrityError},
     internal::{ValidateChaos, ValidateCompact, ValidationError},
     support::{
         ItemIndex,
-        alloc::{Global, global_alloc},
+        alloc::{Allocator, Global, global_alloc},
         borrow::DormantMutRef,
+        btree_table::MapBTreeTable,
         item_set::ItemSet,
         map_hash::MapHash,
     },
 };
-use alloc::collections::BTreeSet;
+use alloc::{collections::BTreeSet, vec::Vec};
 use core::{
+    cmp::Ordering,
     fmt,
-    hash::{BuildHasher, Hash},
+    hash::{BuildHasher, Hash, Hasher},
+    ops::{Bound, RangeBounds},
 };
 use equivalent::{Comparable, Equivalent};
 
@@ -62,18 +67,21 @@ use equivalent::{Comparable, Equivalent};
 /// # }
 /// ```
 #[derive(Clone)]
-pub struct IdOrdMap<T> {
-    // We don't expose an allocator trait here because it isn't stable with
-    // std's BTreeMap.
-    pub(super) items: ItemSet<T, Global>,
+pub struct IdOrdMap<T, A: Allocator = Global> {
+    // Items are stored with `A`, but the B-Tree index always uses the global
+    // allocator. std's BTreeMap, which stores the indexes when the `std`
+    // feature is enabled, doesn't support custom allocators on stable Rust, and
+    // the bundled B-Tree used without std follows suit so that the index
+    // behaves the same either way.
+    pub(super) items: ItemSet<T, A>,
     // Invariant: the values (ItemIndex) in these tables are valid indexes into
     // `items`, and are a 1:1 mapping.
     pub(super) tables: IdOrdMapTables,
 }
 
-impl<T: IdOrdItem> Default for IdOrdMap<T> {
+impl<T: IdOrdItem, A: Allocator + Default> Default for IdOrdMap<T, A> {
     fn default() -> Self {
-        Self::new()
+        Self::new_in(A::default())
     }
 }
 
@@ -145,13 +153,23 @@ impl<T: IdOrdItem> IdOrdMap<T> {
             tables: IdOrdMapTables::new(),
         }
     }
+}
 
-    /// Returns the currently allocated capacity of the map.
+impl<T: IdOrdItem, A: Allocator> IdOrdMap<T, A> {
+    /// Creates a new, empty `IdOrdMap` using the given allocator.
+    ///
+    /// Requires the `allocator-api2` feature to be enabled. Items are stored
+    /// using `alloc`, while the B-Tree index over them always uses the global
+    /// allocator.
     ///
     /// # Examples
     ///
+    /// Using the [`bumpalo`](https://docs.rs/bumpalo) allocator:
+    ///
     /// ```
+    /// # #[cfg(feature = "allocator-api2")] {
     /// use iddqd::{IdOrdItem, IdOrdMap, id_upcast};
+    /// # use iddqd_test_utils::bumpalo;
     ///
     /// #[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
     /// struct Item {
@@ -169,14 +187,67 @@ impl<T: IdOrdItem> IdOrdMap<T> {
     ///     id_upcast!();
     /// }
     ///
-    /// let map: IdOrdMap<Item> = IdOrdMap::with_capacity(10);
+    /// // Define a new allocator.
+    /// let bump = bumpalo::Bump::new();
+    /// // Create a new IdOrdMap using the allocator.
+    /// let map: IdOrdMap<Item, &bumpalo::Bump> = IdOrdMap::new_in(&bump);
+    /// assert!(map.is_empty());
+    /// # }
+    /// ```
+    #[inline]
+    pub const fn new_in(alloc: A) -> Self {
+        Self { items: ItemSet::new_in(alloc), tables: IdOrdMapTables::new() }
+    }
+
+    /// Creates an empty `IdOrdMap` with the specified capacity using the given
+    /// allocator.
+    ///
+    /// Requires the `allocator-api2` feature to be enabled. The capacity will
+    /// be used to initialize the underlying item set.
+    ///
+    /// # Examples
+    ///
+    /// Using the [`bumpalo`](https://docs.rs/bumpalo) allocator:
+    ///
+    /// ```
+    /// # #[cfg(feature = "allocator-api2")] {
+    /// use iddqd::{IdOrdItem, IdOrdMap, id_upcast};
+    /// # use iddqd_test_utils::bumpalo;
+    ///
+    /// #[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
+    /// struct Item {
+    ///     id: String,
+    ///     value: u32,
+    /// }
+    ///
+    /// impl IdOrdItem for Item {
+    ///     type Key<'a> = &'a str;
+    ///
+    ///     fn key(&self) -> Self::Key<'_> {
+    ///         &self.id
+    ///     }
+    ///
+    ///     id_upcast!();
+    /// }
+    ///
+    /// // Define a new allocator.
+    /// let bump = bumpalo::Bump::new();
+    /// // Create a new IdOrdMap with capacity using the allocator.
+    /// let map: IdOrdMap<Item, &bumpalo::Bump> =
+    ///     IdOrdMap::with_capacity_in(10, &bump);
     /// assert!(map.capacity() >= 10);
+    /// assert!(map.is_empty());
+    /// # }
     /// ```
-    pub fn capacity(&self) -> usize {
-        // There's no self.tables.capacity.
-        self.items.capacity()
+    pub fn with_capacity_in(capacity: usize, alloc: A) -> Self {
+        Self {
+            items: ItemSet::with_capacity_in(capacity, alloc),
+            tables: IdOrdMapTables::new(),
+        }
     }
+}
 
+impl<T: IdOrdItem, A: Allocator + Default> IdOrdMap<T, A> {
     /// Constructs a new `IdOrdMap` from an iterator of values, rejecting
     /// duplicates.
     ///
@@ -209,7 +280,7 @@ impl<T: IdOrdItem> IdOrdMap<T> {
     /// ];
     ///
     /// // Successful creation with unique keys
-    /// let map = IdOrdMap::from_iter_unique(items).unwrap();
+    /// let map = IdOrdMap::<Item>::from_iter_unique(items).unwrap();
     /// assert_eq!(map.len(), 2);
     /// assert_eq!(map.get("foo").unwrap().value, 42);
     ///
@@ -218,13 +289,14 @@ impl<T: IdOrdItem> IdOrdMap<T> {
     ///     Item { id: "foo".to_string(), value: 42 },
     ///     Item { id: "foo".to_string(), value: 99 },
     /// ];
-    /// assert!(IdOrdMap::from_iter_unique(duplicate_items).is_err());
+    /// assert!(IdOrdMap::<Item>::from_iter_unique(duplicate_items).is_err());
     /// ```
     pub fn from_iter_unique<I: IntoIterator<Item = T>>(
         iter: I,
     ) -> Result<Self, DuplicateItem<T>> {
         let iter = iter.into_iter();
-        let mut map = IdOrdMap::with_capacity(iter.size_hint().0);
+        let mut map = Self::default();
+        map.reserve(iter.size_hint().0);
         for value in iter {
             // It would be nice to use insert_unique here, but that would return
             // a `DuplicateItem<T, &T>`, which can only be converted into an
@@ -246,6 +318,93 @@ impl<T: IdOrdItem> IdOrdMap<T> {
 
         Ok(map)
     }
+}
+
+impl<T: IdOrdItem, A: Allocator> IdOrdMap<T, A> {
+    /// Returns the allocator.
+    ///
+    /// Requires the `allocator-api2` feature to be enabled.
+    ///
+    /// # Examples
+    ///
+    /// Using the [`bumpalo`](https://docs.rs/bumpalo) allocator:
+    ///
+    /// ```
+    /// # #[cfg(feature = "allocator-api2")] {
+    /// use iddqd::{IdOrdItem, IdOrdMap, id_upcast};
+    /// # use iddqd_test_utils::bumpalo;
+    ///
+    /// #[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
+    /// struct Item {
+    ///     id: String,
+    ///     value: u32,
+    /// }
+    ///
+    /// impl IdOrdItem for Item {
+    ///     type Key<'a> = &'a str;
+    ///
+    ///     fn key(&self) -> Self::Key<'_> {
+    ///         &self.id
+    ///     }
+    ///
+    ///     id_upcast!();
+    /// }
+    ///
+    /// // Define a new allocator.
+    /// let bump = bumpalo::Bump::new();
+    /// // Create a new IdOrdMap using the allocator.
+    /// let map: IdOrdMap<Item, &bumpalo::Bump> = IdOrdMap::new_in(&bump);
+    /// let _allocator = map.allocator();
+    /// # }
+    /// ```
+    pub fn allocator(&self) -> &A {
+        self.items.allocator()
+    }
+
+    /// Returns the currently allocated capacity of the map.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use iddqd::{IdOrdItem, IdOrdMap, id_upcast};
+    ///
+    /// #[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
+    /// struct Item {
+    ///     id: String,
+    ///     value: u32,
+    /// }
+    ///
+    /// impl IdOrdItem for Item {
+    ///     type Key<'a> = &'a str;
+    ///
+    ///     fn key(&self) -> Self::Key<'_> {
+    ///         &self.id
+    ///     }
+    ///
+    ///     id_upcast!();
+    /// }
+    ///
+    /// let map: IdOrdMap<Item> = IdOrdMap::with_capacity(10);
+    /// assert!(map.capacity() >= 10);
+    /// ```
+    pub fn capacity(&self) -> usize {
+        // There's no self.tables.capacity.
+        self.items.capacity()
+    }
+
+    /// Returns a breakdown of the map's internal sizing, for debugging.
+    ///
+    /// This reports the length and capacity of the item storage. The key table
+    /// is a B-tree, which doesn't have a capacity, so
+    /// [`CapacityBreakdown::table_capacities`] is always empty. This doesn't
+    /// visit any items, so it's much cheaper than [`Self::validate`].
+    pub fn debug_capacity_breakdown(&self) -> CapacityBreakdown {
+        CapacityBreakdown {
+            items_len: self.items.len(),
+            items_capacity: self.items.capacity(),
+            table_capacities: Vec::new(),
+        }
+    }
 
     /// Returns true if the map is empty.
     ///
@@ -356,6 +515,51 @@ impl<T: IdOrdItem> IdOrdMap<T> {
         self.items.clear();
     }
 
+    /// Removes all items from the map, returning them in key order as an
+    /// iterator.
+    ///
+    /// The map keeps its allocated capacity. Each item is removed from the map
+    /// as it is yielded, so this is a good fit for merge-style algorithms that
+    /// consume several sorted maps at once. If the returned iterator is dropped
+    /// before it is exhausted, the remaining items are dropped and the map is
+    /// left empty.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use iddqd::{IdOrdItem, IdOrdMap, id_upcast};
+    ///
+    /// #[derive(Debug, PartialEq, Eq)]
+    /// struct Item {
+    ///     id: u32,
+    ///     value: &'static str,
+    /// }
+    ///
+    /// impl IdOrdItem for Item {
+    ///     type Key<'a> = u32;
+    ///     fn key(&self) -> Self::Key<'_> {
+    ///         self.id
+    ///     }
+    ///     id_upcast!();
+    /// }
+    ///
+    /// let mut map = IdOrdMap::new();
+    /// map.insert_unique(Item { id: 3, value: "baz" }).unwrap();
+    /// map.insert_unique(Item { id: 1, value: "foo" }).unwrap();
+    /// map.insert_unique(Item { id: 2, value: "bar" }).unwrap();
+    ///
+    /// let mut drain = map.drain();
+    /// assert_eq!(drain.next(), Some(Item { id: 1, value: "foo" }));
+    /// assert_eq!(drain.next_back(), Some(Item { id: 3, value: "baz" }));
+    /// // Dropping the iterator drops the remaining items.
+    /// drop(drain);
+    ///
+    /// assert!(map.is_empty());
+    /// ```
+    pub fn drain(&mut self) -> Drain<'_, T, A> {
+        Drain::new(&mut self.items, &mut self.tables)
+    }
+
     /// Reserves capacity for at least `additional` more elements to be inserted
     /// in the `IdOrdMap`. The collection may reserve more space to
     /// speculatively avoid frequent reallocations. After calling `reserve`,
@@ -599,75 +803,7 @@ impl<T: IdOrdItem> IdOrdMap<T> {
         IterMut::new(&mut self.items, &self.tables)
     }
 
-    /// Checks general invariants of the map.
-    ///
-    /// The code below always upholds these invariants, but it's useful to have
-    /// an explicit check for tests.
-    #[doc(hidden)]
-    pub fn validate(
-        &self,
-        compactness: ValidateCompact,
-        chaos: ValidateChaos,
-    ) -> Result<(), ValidationError>
-    where
-        T: fmt::Debug,
-    {
-        self.items.validate(compactness)?;
-        self.tables.validate(self.len(), compactness)?;
-
-        // Check that the indexes are all correct.
-
-        for (ix, item) in self.items.iter() {
-            let key = item.key();
-            let ix1 = match chaos {
-                ValidateChaos::Yes => {
-                    // Fall back to a linear search.
-                    self.linear_search_index(&key)
-                }
-                ValidateChaos::No => {
-                    // Use the B-Tree table to find the index.
-                    self.find_index(&key)
-                }
-            };
-            let Some(ix1) = ix1 else {
-                return Err(ValidationError::general(format!(
-                    "item at index {ix} has no key1 index"
-                )));
-            };
-
-            if ix1 != ix {
-                return Err(ValidationError::General(format!(
-                    "item at index {ix} has mismatched indexes: ix1: {ix1}",
-                )));
-            }
-        }
-
-        Ok(())
-    }
-
-    /// Checks the structural invariants of the map:
-    ///
-    /// * The item set is well-formed.
-    /// * The B-tree table holds exactly one entry per live item, with no
-    ///   duplicate `ItemIndex`es.
-    ///
-    /// Unlike [`validate`](Self::validate), this does not re-look-up keys
-    /// through the user `Ord`, so it holds regardless of whether that `Ord` is
-    /// lawful. A buggy comparator can desync the logical key to item mapping,
-    /// but it must never break these structural invariants! Doing so would
-    /// cause unsoundness, e.g. duplicate indexes enabling mutable aliasing.
-    #[doc(hidden)]
-    pub fn validate_structural(
-        &self,
-        compactness: ValidateCompact,
-    ) -> Result<(), ValidationError> {
-        self.items.validate(compactness)?;
-        self.tables.validate(self.len(), compactness)?;
-        Ok(())
-    }
-
-    /// Inserts a value into the set, returning an error if any duplicates were
-    /// added.
+    /// Iterates over the keys in the map, in ascending order.
     ///
     /// # Examples
     ///
@@ -691,30 +827,57 @@ impl<T: IdOrdItem> IdOrdMap<T> {
     /// }
     ///
     /// let mut map = IdOrdMap::new();
+    /// map.insert_unique(Item { id: "charlie".to_string(), value: 30 }).unwrap();
+    /// map.insert_unique(Item { id: "alice".to_string(), value: 42 }).unwrap();
+    /// map.insert_unique(Item { id: "bob".to_string(), value: 99 }).unwrap();
     ///
-    /// // Successful insertion
-    /// assert!(
-    ///     map.insert_unique(Item { id: "foo".to_string(), value: 42 }).is_ok()
-    /// );
-    /// assert!(
-    ///     map.insert_unique(Item { id: "bar".to_string(), value: 99 }).is_ok()
-    /// );
-    ///
-    /// // Duplicate key
-    /// assert!(
-    ///     map.insert_unique(Item { id: "foo".to_string(), value: 100 }).is_err()
-    /// );
+    /// let keys: Vec<&str> = map.keys().collect();
+    /// assert_eq!(keys, ["alice", "bob", "charlie"]);
     /// ```
-    pub fn insert_unique(
-        &mut self,
-        value: T,
-    ) -> Result<(), DuplicateItem<T, &T>> {
-        let _ = self.insert_unique_impl(value)?;
-        Ok(())
+    #[inline]
+    pub fn keys(&self) -> Keys<'_, T> {
+        Keys::new(self.iter())
     }
 
-    /// Inserts a value into the map, removing and returning the conflicting
-    /// item, if any.
+    /// Iterates over the items in the map, in key order.
+    ///
+    /// This is the same as [`Self::iter`], and is provided for symmetry with
+    /// [`BTreeMap::values`].
+    ///
+    /// [`BTreeMap::values`]: std::collections::BTreeMap::values
+    #[inline]
+    pub fn values(&self) -> Iter<'_, T> {
+        self.iter()
+    }
+
+    /// Iterates over the items in the map in key order, allowing for mutation.
+    ///
+    /// This is the same as [`Self::iter_mut`], and is provided for symmetry with
+    /// [`BTreeMap::values_mut`].
+    ///
+    /// [`BTreeMap::values_mut`]: std::collections::BTreeMap::values_mut
+    #[inline]
+    pub fn values_mut<'a>(&'a mut self) -> IterMut<'a, T>
+    where
+        T::Key<'a>: Hash,
+    {
+        self.iter_mut()
+    }
+
+    /// Consumes the map, returning an iterator over its keys in ascending
+    /// order.
+    ///
+    /// Keys borrow from the items they belong to, so once an item is dropped
+    /// its key can no longer be used. The returned iterator instead converts
+    /// each key into the owned type `K` via [`Into`] before dropping the item.
+    /// For example, a `&str` key can be collected as a
+    /// [`String`](alloc::string::String), and `Copy` keys such as integers can
+    /// be returned as-is.
+    ///
+    /// Since the conversion must work for a key borrowed for any lifetime, the
+    /// `Into<K>` bound can only be satisfied when `T` is `'static`. For items
+    /// that borrow non-`'static` data, use [`IntoIterator::into_iter`] and
+    /// convert each key within a closure instead.
     ///
     /// # Examples
     ///
@@ -738,19 +901,404 @@ impl<T: IdOrdItem> IdOrdMap<T> {
     /// }
     ///
     /// let mut map = IdOrdMap::new();
+    /// map.insert_unique(Item { id: "charlie".to_string(), value: 30 }).unwrap();
+    /// map.insert_unique(Item { id: "alice".to_string(), value: 42 }).unwrap();
+    /// map.insert_unique(Item { id: "bob".to_string(), value: 99 }).unwrap();
     ///
-    /// // First insertion - no conflict
-    /// let old = map.insert_overwrite(Item { id: "foo".to_string(), value: 42 });
-    /// assert!(old.is_none());
+    /// // `&str` keys are converted into owned `String`s.
+    /// let keys: Vec<String> = map.into_keys().collect();
+    /// assert_eq!(keys, ["alice", "bob", "charlie"]);
+    /// ```
+    #[inline]
+    pub fn into_keys<K>(self) -> IntoKeys<T, K, A>
+    where
+        for<'k> T::Key<'k>: Into<K>,
+    {
+        IntoKeys::new(self.items, self.tables)
+    }
+
+    /// Consumes the map, returning an iterator over its items in key order.
     ///
-    /// // Overwrite existing key - returns old value
-    /// let old = map.insert_overwrite(Item { id: "foo".to_string(), value: 99 });
-    /// assert!(old.is_some());
-    /// assert_eq!(old.unwrap().value, 42);
+    /// This is the same as [`IntoIterator::into_iter`], and is provided for
+    /// symmetry with [`BTreeMap::into_values`].
     ///
-    /// // Verify new value is in the map
-    /// assert_eq!(map.get("foo").unwrap().value, 99);
-    /// ```
+    /// [`BTreeMap::into_values`]: std::collections::BTreeMap::into_values
+    #[inline]
+    pub fn into_values(self) -> IntoIter<T, A> {
+        IntoIter::new(self.items, self.tables)
+    }
+
+    /// Consumes the map, returning its items as a [`Vec`] in key order.
+    ///
+    /// This is the same as [`IdOrdMap::into_sorted_vec`], since an `IdOrdMap`
+    /// always iterates in key order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use iddqd::{IdOrdItem, IdOrdMap, id_upcast};
+    ///
+    /// #[derive(Debug, PartialEq, Eq)]
+    /// struct Item {
+    ///     id: u32,
+    ///     value: &'static str,
+    /// }
+    ///
+    /// impl IdOrdItem for Item {
+    ///     type Key<'a> = u32;
+    ///     fn key(&self) -> Self::Key<'_> {
+    ///         self.id
+    ///     }
+    ///     id_upcast!();
+    /// }
+    ///
+    /// let mut map = IdOrdMap::new();
+    /// map.insert_unique(Item { id: 2, value: "bar" }).unwrap();
+    /// map.insert_unique(Item { id: 1, value: "foo" }).unwrap();
+    ///
+    /// assert_eq!(
+    ///     map.into_vec(),
+    ///     vec![Item { id: 1, value: "foo" }, Item { id: 2, value: "bar" }],
+    /// );
+    /// ```
+    pub fn into_vec(self) -> Vec<T> {
+        self.into_iter().collect()
+    }
+
+    /// Consumes the map, returning its items as a [`Vec`] sorted by key.
+    ///
+    /// Items in an `IdOrdMap` are always ordered by key, so this is the same
+    /// as [`IdOrdMap::into_vec`]. It is provided to make it explicit at the
+    /// call site that the order of the result matters.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use iddqd::{IdOrdItem, IdOrdMap, id_upcast};
+    ///
+    /// #[derive(Debug, PartialEq, Eq)]
+    /// struct Item {
+    ///     id: u32,
+    ///     value: &'static str,
+    /// }
+    ///
+    /// impl IdOrdItem for Item {
+    ///     type Key<'a> = u32;
+    ///     fn key(&self) -> Self::Key<'_> {
+    ///         self.id
+    ///     }
+    ///     id_upcast!();
+    /// }
+    ///
+    /// let map: IdOrdMap<_> = [
+    ///     Item { id: 3, value: "baz" },
+    ///     Item { id: 1, value: "foo" },
+    ///     Item { id: 2, value: "bar" },
+    /// ]
+    /// .into_iter()
+    /// .collect();
+    ///
+    /// let ids: Vec<_> =
+    ///     map.into_sorted_vec().iter().map(|item| item.id).collect();
+    /// assert_eq!(ids, vec![1, 2, 3]);
+    /// ```
+    pub fn into_sorted_vec(self) -> Vec<T> {
+        self.into_vec()
+    }
+
+    /// Checks that the map's internal invariants hold.
+    ///
+    /// See [`MapIntegrityError`] for what this checks, and when it can fail.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use iddqd::{IdOrdItem, IdOrdMap, id_upcast};
+    ///
+    /// #[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
+    /// struct Item {
+    ///     id: String,
+    ///     value: u32,
+    /// }
+    ///
+    /// impl IdOrdItem for Item {
+    ///     type Key<'a> = &'a str;
+    ///
+    ///     fn key(&self) -> Self::Key<'_> {
+    ///         &self.id
+    ///     }
+    ///
+    ///     id_upcast!();
+    /// }
+    ///
+    /// let mut map = IdOrdMap::new();
+    /// map.insert_unique(Item { id: "foo".to_string(), value: 42 }).unwrap();
+    /// map.insert_unique(Item { id: "bar".to_string(), value: 20 }).unwrap();
+    /// map.remove("foo");
+    ///
+    /// assert_eq!(map.check_invariants(), Ok(()));
+    /// ```
+    pub fn check_invariants(&self) -> Result<(), MapIntegrityError> {
+        MapIntegrityError::check_storage(&self.items)?;
+        MapIntegrityError::check_table(
+            "key",
+            &self.items,
+            self.tables.key_to_item.iter(),
+        )?;
+
+        for (ix, item) in self.items.iter() {
+            let found = self.find_index(&item.key());
+            MapIntegrityError::check_lookup("key", ix, found)?;
+        }
+
+        Ok(())
+    }
+
+    /// Checks general invariants of the map.
+    ///
+    /// The code below always upholds these invariants, but it's useful to have
+    /// an explicit check for tests.
+    #[doc(hidden)]
+    pub fn validate(
+        &self,
+        compactness: ValidateCompact,
+        chaos: ValidateChaos,
+    ) -> Result<(), ValidationError>
+    where
+        T: fmt::Debug,
+    {
+        self.items.validate(compactness)?;
+        self.tables.validate(self.len(), compactness)?;
+
+        // Check that the indexes are all correct.
+
+        for (ix, item) in self.items.iter() {
+            let key = item.key();
+            let ix1 = match chaos {
+                ValidateChaos::Yes => {
+                    // Fall back to a linear search.
+                    self.linear_search_index(&key)
+                }
+                ValidateChaos::No => {
+                    // Use the B-Tree table to find the index.
+                    self.find_index(&key)
+                }
+            };
+            let Some(ix1) = ix1 else {
+                return Err(ValidationError::general(format!(
+                    "item at index {ix} has no key1 index"
+                )));
+            };
+
+            if ix1 != ix {
+                return Err(ValidationError::General(format!(
+                    "item at index {ix} has mismatched indexes: ix1: {ix1}",
+                )));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Checks the structural invariants of the map:
+    ///
+    /// * The item set is well-formed.
+    /// * The B-tree table holds exactly one entry per live item, with no
+    ///   duplicate `ItemIndex`es.
+    ///
+    /// Unlike [`validate`](Self::validate), this does not re-look-up keys
+    /// through the user `Ord`, so it holds regardless of whether that `Ord` is
+    /// lawful. A buggy comparator can desync the logical key to item mapping,
+    /// but it must never break these structural invariants! Doing so would
+    /// cause unsoundness, e.g. duplicate indexes enabling mutable aliasing.
+    #[doc(hidden)]
+    pub fn validate_structural(
+        &self,
+        compactness: ValidateCompact,
+    ) -> Result<(), ValidationError> {
+        self.items.validate(compactness)?;
+        self.tables.validate(self.len(), compactness)?;
+        Ok(())
+    }
+
+    /// Inserts a value into the set, returning an error if any duplicates were
+    /// added.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use iddqd::{IdOrdItem, IdOrdMap, id_upcast};
+    ///
+    /// #[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
+    /// struct Item {
+    ///     id: String,
+    ///     value: u32,
+    /// }
+    ///
+    /// impl IdOrdItem for Item {
+    ///     type Key<'a> = &'a str;
+    ///
+    ///     fn key(&self) -> Self::Key<'_> {
+    ///         &self.id
+    ///     }
+    ///
+    ///     id_upcast!();
+    /// }
+    ///
+    /// let mut map = IdOrdMap::new();
+    ///
+    /// // Successful insertion
+    /// assert!(
+    ///     map.insert_unique(Item { id: "foo".to_string(), value: 42 }).is_ok()
+    /// );
+    /// assert!(
+    ///     map.insert_unique(Item { id: "bar".to_string(), value: 99 }).is_ok()
+    /// );
+    ///
+    /// // Duplicate key
+    /// assert!(
+    ///     map.insert_unique(Item { id: "foo".to_string(), value: 100 }).is_err()
+    /// );
+    /// ```
+    pub fn insert_unique(
+        &mut self,
+        value: T,
+    ) -> Result<(), DuplicateItem<T, &T>> {
+        let _ = self.insert_unique_impl(value)?;
+        Ok(())
+    }
+
+    /// Extends the map with the contents of an iterator, handling duplicates
+    /// according to `mode`.
+    ///
+    /// With [`ExtendMode::Overwrite`], this behaves like calling
+    /// [`IdOrdMap::insert_overwrite`] for each item, and never returns an
+    /// error. This is what the [`Extend`] implementation does.
+    ///
+    /// With [`ExtendMode::Unique`], this behaves like calling
+    /// [`IdOrdMap::insert_unique`] for each item, stopping at the first item
+    /// that conflicts with an existing one. Items before the conflicting one
+    /// remain in the map, and the rest of the iterator is dropped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use iddqd::{ExtendMode, IdOrdItem, IdOrdMap, id_upcast};
+    ///
+    /// #[derive(Debug, PartialEq, Eq)]
+    /// struct Item {
+    ///     id: String,
+    ///     value: u32,
+    /// }
+    ///
+    /// impl IdOrdItem for Item {
+    ///     type Key<'a> = &'a str;
+    ///     fn key(&self) -> Self::Key<'_> {
+    ///         &self.id
+    ///     }
+    ///     id_upcast!();
+    /// }
+    ///
+    /// let mut map = IdOrdMap::new();
+    /// map.insert_unique(Item { id: "foo".to_string(), value: 42 }).unwrap();
+    ///
+    /// // Unique mode stops at the first duplicate.
+    /// let new_items = vec![
+    ///     Item { id: "bar".to_string(), value: 20 },
+    ///     Item { id: "foo".to_string(), value: 100 },
+    ///     Item { id: "baz".to_string(), value: 30 },
+    /// ];
+    /// let err = map.extend_with(new_items, ExtendMode::Unique).unwrap_err();
+    /// assert_eq!(err.new_item().value, 100);
+    /// assert_eq!(err.duplicates()[0].value, 42);
+    /// assert_eq!(map.len(), 2);
+    /// assert!(map.contains_key("bar"));
+    /// assert!(!map.contains_key("baz"));
+    ///
+    /// // Overwrite mode replaces duplicates.
+    /// let new_items = vec![Item { id: "foo".to_string(), value: 100 }];
+    /// map.extend_with(new_items, ExtendMode::Overwrite).unwrap();
+    /// assert_eq!(map.get("foo").unwrap().value, 100);
+    /// ```
+    pub fn extend_with<I: IntoIterator<Item = T>>(
+        &mut self,
+        iter: I,
+        mode: ExtendMode,
+    ) -> Result<(), DuplicateItem<T, &T>> {
+        // Keys may already be present in the map, or multiple times in the
+        // iterator. Reserve the entire hint lower bound if the map is empty.
+        // Otherwise reserve half the hint (rounded up), so the map will only
+        // resize twice in the worst case.
+        let iter = iter.into_iter();
+        let reserve = if self.is_empty() {
+            iter.size_hint().0
+        } else {
+            iter.size_hint().0.div_ceil(2)
+        };
+        self.reserve(reserve);
+        match mode {
+            ExtendMode::Overwrite => {
+                for item in iter {
+                    self.insert_overwrite(item);
+                }
+            }
+            ExtendMode::Unique => {
+                for item in iter {
+                    if let Err((item, duplicates)) =
+                        self.insert_unique_or_dup_indexes(item)
+                    {
+                        return Err(DuplicateItem::__internal_new(
+                            item,
+                            duplicates
+                                .iter()
+                                .map(|ix| &self.items[*ix])
+                                .collect(),
+                        ));
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Inserts a value into the map, removing and returning the conflicting
+    /// item, if any.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use iddqd::{IdOrdItem, IdOrdMap, id_upcast};
+    ///
+    /// #[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
+    /// struct Item {
+    ///     id: String,
+    ///     value: u32,
+    /// }
+    ///
+    /// impl IdOrdItem for Item {
+    ///     type Key<'a> = &'a str;
+    ///
+    ///     fn key(&self) -> Self::Key<'_> {
+    ///         &self.id
+    ///     }
+    ///
+    ///     id_upcast!();
+    /// }
+    ///
+    /// let mut map = IdOrdMap::new();
+    ///
+    /// // First insertion - no conflict
+    /// let old = map.insert_overwrite(Item { id: "foo".to_string(), value: 42 });
+    /// assert!(old.is_none());
+    ///
+    /// // Overwrite existing key - returns old value
+    /// let old = map.insert_overwrite(Item { id: "foo".to_string(), value: 99 });
+    /// assert!(old.is_some());
+    /// assert_eq!(old.unwrap().value, 42);
+    ///
+    /// // Verify new value is in the map
+    /// assert_eq!(map.get("foo").unwrap().value, 99);
+    /// ```
     #[doc(alias = "insert")]
     pub fn insert_overwrite(&mut self, value: T) -> Option<T> {
         // Go through the entry API so all user code is called before any table
@@ -843,6 +1391,329 @@ impl<T: IdOrdItem> IdOrdMap<T> {
         self.find(key)
     }
 
+    /// Searches the map using a custom comparator, returning a matching item if
+    /// one is found.
+    ///
+    /// `f` should return the ordering of the given item relative to the target,
+    /// similar to [`slice::binary_search_by`]. If more than one item matches,
+    /// any one of them may be returned.
+    ///
+    /// The search follows the internal B-tree, so `f` must be consistent with
+    /// the map's key ordering: all items for which `f` returns
+    /// [`Ordering::Less`] must sort before all items for which it returns
+    /// [`Ordering::Equal`], which in turn must sort before all items for which
+    /// it returns [`Ordering::Greater`]. If this precondition is not met, the
+    /// returned result is unspecified, but the map remains valid.
+    ///
+    /// This is useful for searching by a projection of the key, or by a prefix
+    /// of a composite key.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use iddqd::{IdOrdItem, IdOrdMap, id_upcast};
+    ///
+    /// #[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
+    /// struct Item {
+    ///     id: String,
+    ///     value: u32,
+    /// }
+    ///
+    /// impl IdOrdItem for Item {
+    ///     type Key<'a> = &'a str;
+    ///
+    ///     fn key(&self) -> Self::Key<'_> {
+    ///         &self.id
+    ///     }
+    ///
+    ///     id_upcast!();
+    /// }
+    ///
+    /// let mut map = IdOrdMap::new();
+    /// map.insert_unique(Item { id: "apple".to_string(), value: 1 }).unwrap();
+    /// map.insert_unique(Item { id: "banana".to_string(), value: 2 }).unwrap();
+    /// map.insert_unique(Item { id: "cherry".to_string(), value: 3 }).unwrap();
+    ///
+    /// // Search by the first character of the key, which is consistent with
+    /// // the key ordering.
+    /// let item = map.search_by(|item| item.id.chars().next().cmp(&Some('b')));
+    /// assert_eq!(item.unwrap().value, 2);
+    ///
+    /// let item = map.search_by(|item| item.id.chars().next().cmp(&Some('z')));
+    /// assert!(item.is_none());
+    /// ```
+    pub fn search_by<F>(&self, mut f: F) -> Option<&T>
+    where
+        F: FnMut(&T) -> Ordering,
+    {
+        let index = self
+            .tables
+            .key_to_item
+            .find_index_by(|index| f(&self.items[index]))?;
+        self.get_by_index(index)
+    }
+
+    /// Returns an iterator over the items whose keys fall within `range`, in
+    /// key order.
+    ///
+    /// The returned iterator is double-ended, so `.rev()` walks the range from
+    /// the top down.
+    ///
+    /// Unlike [`BTreeMap::range`], this does not panic if the start of the
+    /// range is greater than its end; the iterator is empty instead.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use iddqd::{IdOrdItem, IdOrdMap, id_upcast};
+    ///
+    /// #[derive(Debug, PartialEq, Eq)]
+    /// struct Event {
+    ///     timestamp: u64,
+    ///     name: &'static str,
+    /// }
+    ///
+    /// impl IdOrdItem for Event {
+    ///     type Key<'a> = u64;
+    ///
+    ///     fn key(&self) -> Self::Key<'_> {
+    ///         self.timestamp
+    ///     }
+    ///
+    ///     id_upcast!();
+    /// }
+    ///
+    /// let mut map = IdOrdMap::new();
+    /// for (timestamp, name) in [(10, "a"), (20, "b"), (30, "c"), (40, "d")] {
+    ///     map.insert_unique(Event { timestamp, name }).unwrap();
+    /// }
+    ///
+    /// let names: Vec<_> = map.range(15..=30).map(|e| e.name).collect();
+    /// assert_eq!(names, ["b", "c"]);
+    ///
+    /// // The two most recent events before timestamp 40.
+    /// let recent: Vec<_> =
+    ///     map.range(..40).rev().take(2).map(|e| e.name).collect();
+    /// assert_eq!(recent, ["c", "b"]);
+    /// ```
+    ///
+    /// [`BTreeMap::range`]: std::collections::BTreeMap::range
+    pub fn range<'a, Q, R>(&'a self, range: R) -> Range<'a, T>
+    where
+        Q: ?Sized + Comparable<T::Key<'a>>,
+        R: RangeBounds<Q>,
+    {
+        let iter = self
+            .tables
+            .key_to_item
+            .range(range, |index| self.items[index].key());
+        Range::new(&self.items, iter)
+    }
+
+    /// Returns an iterator over the items, ordered by a key derived from each
+    /// item with `f`.
+    ///
+    /// This is useful for iterating over the map in an order other than the
+    /// one given by [`T::Key`]. Items for which `f` returns equal values are
+    /// returned in key order.
+    ///
+    /// The order is not maintained as the map changes. Instead, each call
+    /// sorts the indexes of the items (not the items themselves), which takes
+    /// *O*(*n* log *n*) time and *O*(*n*) additional space.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use iddqd::{IdOrdItem, IdOrdMap, id_upcast};
+    ///
+    /// #[derive(Debug, PartialEq, Eq)]
+    /// struct Event {
+    ///     id: u32,
+    ///     timestamp: u64,
+    /// }
+    ///
+    /// impl IdOrdItem for Event {
+    ///     type Key<'a> = u32;
+    ///
+    ///     fn key(&self) -> Self::Key<'_> {
+    ///         self.id
+    ///     }
+    ///
+    ///     id_upcast!();
+    /// }
+    ///
+    /// let mut map = IdOrdMap::new();
+    /// for (id, timestamp) in [(1, 30), (2, 10), (3, 20)] {
+    ///     map.insert_unique(Event { id, timestamp }).unwrap();
+    /// }
+    ///
+    /// let ids: Vec<_> = map.iter_by(|e| e.timestamp).map(|e| e.id).collect();
+    /// assert_eq!(ids, [2, 3, 1]);
+    ///
+    /// // The iterator is double-ended.
+    /// let latest = map.iter_by(|e| e.timestamp).next_back().unwrap();
+    /// assert_eq!(latest.id, 1);
+    /// ```
+    ///
+    /// [`T::Key`]: crate::IdOrdItem::Key
+    pub fn iter_by<'a, K, F>(&'a self, mut f: F) -> IterBy<'a, T>
+    where
+        K: Ord,
+        F: FnMut(&'a T) -> K,
+    {
+        // Start from key order so that the stable sort breaks ties by key.
+        let mut indexes: Vec<ItemIndex> =
+            self.tables.key_to_item.iter().collect();
+        indexes.sort_by_key(|&index| f(&self.items[index]));
+        IterBy::new(&self.items, indexes)
+    }
+
+    /// Returns a cursor pointing at the gap before the first item above
+    /// `bound`.
+    ///
+    /// * `Bound::Included(key)`: the cursor is before the first item with a
+    ///   key greater than or equal to `key`.
+    /// * `Bound::Excluded(key)`: the cursor is before the first item with a
+    ///   key greater than `key`.
+    /// * `Bound::Unbounded`: the cursor is before the first item in the map.
+    ///
+    /// The cursor can be used to walk the map in order, and to insert or remove
+    /// items relative to its position. For more, see [`CursorMut`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use iddqd::{IdOrdItem, IdOrdMap, id_upcast};
+    /// use std::ops::Bound;
+    ///
+    /// #[derive(Debug, PartialEq, Eq)]
+    /// struct Event {
+    ///     time: u32,
+    ///     name: &'static str,
+    /// }
+    ///
+    /// impl IdOrdItem for Event {
+    ///     type Key<'a> = u32;
+    ///     fn key(&self) -> Self::Key<'_> {
+    ///         self.time
+    ///     }
+    ///     id_upcast!();
+    /// }
+    ///
+    /// let mut map = IdOrdMap::new();
+    /// for (time, name) in [(10, "a"), (20, "b"), (30, "c"), (40, "d")] {
+    ///     map.insert_unique(Event { time, name }).unwrap();
+    /// }
+    ///
+    /// // Walk the map from time 20 onwards, removing every other event.
+    /// let mut cursor = map.lower_bound_mut(Bound::Included(&20));
+    /// assert_eq!(cursor.peek_prev().unwrap().name, "a");
+    /// while cursor.remove_next().is_some() {
+    ///     cursor.next();
+    /// }
+    ///
+    /// // Insert an event at the end, after "d".
+    /// cursor.insert_before(Event { time: 50, name: "e" }).unwrap();
+    /// // Events must be inserted in order.
+    /// assert!(cursor.insert_after(Event { time: 45, name: "x" }).is_err());
+    ///
+    /// let names: Vec<_> = map.iter().map(|e| e.name).collect();
+    /// assert_eq!(names, ["a", "c", "e"]);
+    /// ```
+    pub fn lower_bound_mut<'a, Q>(
+        &'a mut self,
+        bound: Bound<&Q>,
+    ) -> CursorMut<'a, T, A>
+    where
+        Q: ?Sized + Comparable<T::Key<'a>>,
+    {
+        let (map, dormant_map) = DormantMutRef::new(self);
+        let (prev, next) = {
+            let map: &'a Self = map;
+            let table = &map.tables.key_to_item;
+            let lookup = |index| map.items[index].key();
+            let next = table.lower_bound(bound, lookup);
+            let prev = match next {
+                Some(next) => table.prev_index(next, lookup),
+                None => table.last(),
+            };
+            (prev, next)
+        };
+
+        // SAFETY: `map` is not used after this point.
+        let map = unsafe { dormant_map.awaken() };
+        CursorMut::new(map, prev, next)
+    }
+
+    /// Returns a cursor pointing at the gap after the last item below `bound`.
+    ///
+    /// * `Bound::Included(key)`: the cursor is after the last item with a key
+    ///   less than or equal to `key`.
+    /// * `Bound::Excluded(key)`: the cursor is after the last item with a key
+    ///   less than `key`.
+    /// * `Bound::Unbounded`: the cursor is after the last item in the map.
+    ///
+    /// For more, see [`CursorMut`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use iddqd::{IdOrdItem, IdOrdMap, id_upcast};
+    /// use std::ops::Bound;
+    ///
+    /// #[derive(Debug, PartialEq, Eq)]
+    /// struct Event {
+    ///     time: u32,
+    ///     name: &'static str,
+    /// }
+    ///
+    /// impl IdOrdItem for Event {
+    ///     type Key<'a> = u32;
+    ///     fn key(&self) -> Self::Key<'_> {
+    ///         self.time
+    ///     }
+    ///     id_upcast!();
+    /// }
+    ///
+    /// let mut map = IdOrdMap::new();
+    /// for (time, name) in [(10, "a"), (20, "b"), (30, "c"), (40, "d")] {
+    ///     map.insert_unique(Event { time, name }).unwrap();
+    /// }
+    ///
+    /// // Walk the map backwards from time 30.
+    /// let mut cursor = map.upper_bound_mut(Bound::Included(&30));
+    /// assert_eq!(cursor.peek_next().unwrap().name, "d");
+    /// let mut names = Vec::new();
+    /// while let Some(event) = cursor.prev() {
+    ///     names.push(event.name);
+    /// }
+    /// assert_eq!(names, ["c", "b", "a"]);
+    /// ```
+    pub fn upper_bound_mut<'a, Q>(
+        &'a mut self,
+        bound: Bound<&Q>,
+    ) -> CursorMut<'a, T, A>
+    where
+        Q: ?Sized + Comparable<T::Key<'a>>,
+    {
+        let (map, dormant_map) = DormantMutRef::new(self);
+        let (prev, next) = {
+            let map: &'a Self = map;
+            let table = &map.tables.key_to_item;
+            let lookup = |index| map.items[index].key();
+            let prev = table.upper_bound(bound, lookup);
+            let next = match prev {
+                Some(prev) => table.next_index(prev, lookup),
+                None => table.first(),
+            };
+            (prev, next)
+        };
+
+        // SAFETY: `map` is not used after this point.
+        let map = unsafe { dormant_map.awaken() };
+        CursorMut::new(map, prev, next)
+    }
+
     /// Gets a mutable reference to the item associated with the given `key`.
     ///
     /// # Examples
@@ -901,6 +1772,58 @@ impl<T: IdOrdItem> IdOrdMap<T> {
         Some(RefMut::new(state, hash, item))
     }
 
+    /// Gets a plain mutable reference to the value associated with the given
+    /// key.
+    ///
+    /// Unlike [`Self::get_mut`], this doesn't wrap the item in a [`RefMut`]
+    /// that checks the key on drop, so there's no need to hash the key twice.
+    /// It is only available for items that implement [`ImmutableKey`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use iddqd::{IdOrdItem, IdOrdMap, ImmutableKey, id_upcast};
+    ///
+    /// #[derive(Debug)]
+    /// struct Item {
+    ///     id: u32,
+    ///     value: u32,
+    /// }
+    ///
+    /// impl IdOrdItem for Item {
+    ///     type Key<'a> = u32;
+    ///     fn key(&self) -> Self::Key<'_> {
+    ///         self.id
+    ///     }
+    ///     id_upcast!();
+    /// }
+    ///
+    /// // This program never changes `id` through a mutable reference.
+    /// impl ImmutableKey for Item {}
+    ///
+    /// let mut map = IdOrdMap::new();
+    /// map.insert_unique(Item { id: 1, value: 42 }).unwrap();
+    ///
+    /// map.get_mut_plain(&1).unwrap().value = 100;
+    /// assert_eq!(map.get(&1).unwrap().value, 100);
+    /// assert!(map.get_mut_plain(&2).is_none());
+    /// ```
+    pub fn get_mut_plain<'a, Q>(&'a mut self, key: &Q) -> Option<&'a mut T>
+    where
+        Q: ?Sized + Comparable<T::Key<'a>>,
+        T: ImmutableKey,
+    {
+        let (dormant_map, index) = {
+            let (map, dormant_map) = DormantMutRef::new(self);
+            let index = map.find_index(key)?;
+            (dormant_map, index)
+        };
+
+        // SAFETY: `map` is not used after this point.
+        let awakened_map = unsafe { dormant_map.awaken() };
+        Some(&mut awakened_map.items[index])
+    }
+
     /// Removes an item from the map by its `key`.
     ///
     /// # Examples
@@ -953,7 +1876,8 @@ impl<T: IdOrdItem> IdOrdMap<T> {
     /// Retrieves an entry by its `key`.
     ///
     /// Due to borrow checker limitations, this always accepts an owned key rather
-    /// than a borrowed form.
+    /// than a borrowed form. To look up an entry by a borrowed form of the key,
+    /// use [`IdOrdMap::entry_ref`].
     ///
     /// # Examples
     ///
@@ -996,7 +1920,7 @@ impl<T: IdOrdItem> IdOrdMap<T> {
     ///
     /// assert_eq!(map.get("foo").unwrap().value, 99);
     /// ```
-    pub fn entry<'a>(&'a mut self, key: T::Key<'_>) -> Entry<'a, T> {
+    pub fn entry<'a>(&'a mut self, key: T::Key<'_>) -> Entry<'a, T, A> {
         // Why does this always take an owned key? Well, it would seem like we
         // should be able to pass in any Q that is equivalent. That results in
         // *this* code compiling fine, but callers have trouble using it because
@@ -1034,6 +1958,89 @@ impl<T: IdOrdItem> IdOrdMap<T> {
         )
     }
 
+    /// Retrieves an entry by a borrowed form of its `key`.
+    ///
+    /// This is like [`IdOrdMap::entry`], except that it accepts any `Q` that is
+    /// [`Comparable`] to the key, so looking up an entry doesn't require
+    /// constructing an owned key. The key is only used to probe the B-tree, and
+    /// is not retained by the returned [`Entry`].
+    ///
+    /// As with [`IdOrdMap::entry`], a vacant entry doesn't check that the
+    /// inserted item's key matches `key`; it only checks that the item's key
+    /// isn't already present in the map.
+    ///
+    /// `Q` is tied to the lifetime of the map borrow, as with
+    /// [`IdOrdMap::get`]. For keys that borrow from the item, such as
+    /// `&'a str`, pass the unsized form (here `str`) so that the query can be
+    /// dropped while the entry is still alive.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use iddqd::{IdOrdItem, IdOrdMap, id_ord_map, id_upcast};
+    ///
+    /// #[derive(Debug, PartialEq, Eq)]
+    /// struct Item {
+    ///     id: String,
+    ///     value: u32,
+    /// }
+    ///
+    /// impl IdOrdItem for Item {
+    ///     type Key<'a> = &'a str;
+    ///
+    ///     fn key(&self) -> Self::Key<'_> {
+    ///         &self.id
+    ///     }
+    ///
+    ///     id_upcast!();
+    /// }
+    ///
+    /// let mut map = IdOrdMap::new();
+    /// map.insert_unique(Item { id: "foo".to_string(), value: 42 }).unwrap();
+    ///
+    /// // Look up an entry through a borrowed `str`.
+    /// let query = String::from("foo");
+    /// match map.entry_ref(query.as_str()) {
+    ///     id_ord_map::Entry::Occupied(mut entry) => {
+    ///         entry.get_mut().value = 99;
+    ///     }
+    ///     id_ord_map::Entry::Vacant(_) => panic!("Should be occupied"),
+    /// }
+    ///
+    /// match map.entry_ref("bar") {
+    ///     id_ord_map::Entry::Occupied(_) => panic!("Should be vacant"),
+    ///     id_ord_map::Entry::Vacant(entry) => {
+    ///         entry.insert(Item { id: "bar".to_string(), value: 7 });
+    ///     }
+    /// }
+    ///
+    /// assert_eq!(map.get("foo").unwrap().value, 99);
+    /// assert_eq!(map.get("bar").unwrap().value, 7);
+    /// ```
+    pub fn entry_ref<'a, Q>(&'a mut self, key: &Q) -> Entry<'a, T, A>
+    where
+        Q: ?Sized + Comparable<T::Key<'a>>,
+    {
+        // Soundness: `key` is only used within this block, to find an index.
+        // The index doesn't borrow from `key` or from `map`, so `map` is not
+        // used after this point and it is sound to hand `dormant_map` to the
+        // entry.
+        let (map, dormant_map) = DormantMutRef::new(self);
+        // index is explicitly typed to show that it has a trivial Drop impl
+        // that doesn't capture anything from map.
+        let index: Option<ItemIndex> = map.find_index(key);
+        match index {
+            Some(index) => Entry::Occupied(
+                // SAFETY: `map` is not used after this point.
+                unsafe { OccupiedEntry::new(dormant_map, index) },
+            ),
+            None => Entry::Vacant(
+                // SAFETY: `map` is not used after this point.
+                unsafe { VacantEntry::new(dormant_map) },
+            ),
+        }
+    }
+
     /// Returns the first item in the map. The key of this item is the minimum
     /// key in the map.
     ///
@@ -1077,6 +2084,55 @@ impl<T: IdOrdItem> IdOrdMap<T> {
         self.tables.key_to_item.first().map(|index| &self.items[index])
     }
 
+    /// Returns the first item in the map along with its key. The key is the
+    /// minimum key in the map.
+    ///
+    /// This is equivalent to calling [`Self::first`] and then
+    /// [`IdOrdItem::key`] on the result, and mirrors
+    /// [`BTreeMap::first_key_value`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use iddqd::{IdOrdItem, IdOrdMap, id_upcast};
+    ///
+    /// #[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
+    /// struct Item {
+    ///     id: String,
+    ///     value: u32,
+    /// }
+    ///
+    /// impl IdOrdItem for Item {
+    ///     type Key<'a> = &'a str;
+    ///
+    ///     fn key(&self) -> Self::Key<'_> {
+    ///         &self.id
+    ///     }
+    ///
+    ///     id_upcast!();
+    /// }
+    ///
+    /// let mut map = IdOrdMap::new();
+    /// map.insert_unique(Item { id: "charlie".to_string(), value: 30 }).unwrap();
+    /// map.insert_unique(Item { id: "alice".to_string(), value: 42 }).unwrap();
+    /// map.insert_unique(Item { id: "bob".to_string(), value: 99 }).unwrap();
+    ///
+    /// let (key, item) = map.first_key_value().unwrap();
+    /// assert_eq!(key, "alice");
+    /// assert_eq!(item.value, 42);
+    ///
+    /// // Empty map returns None.
+    /// let empty_map: IdOrdMap<Item> = IdOrdMap::new();
+    /// assert!(empty_map.first_key_value().is_none());
+    /// ```
+    ///
+    /// [`BTreeMap::first_key_value`]: std::collections::BTreeMap::first_key_value
+    #[inline]
+    pub fn first_key_value(&self) -> Option<(T::Key<'_>, &T)> {
+        let item = self.first()?;
+        Some((item.key(), item))
+    }
+
     /// Returns the first entry in the map for in-place manipulation. The key of
     /// this entry is the minimum key in the map.
     ///
@@ -1113,7 +2169,7 @@ impl<T: IdOrdItem> IdOrdMap<T> {
     ///
     /// assert_eq!(map.get("alice").unwrap().value, 100);
     /// ```
-    pub fn first_entry(&mut self) -> Option<OccupiedEntry<'_, T>> {
+    pub fn first_entry(&mut self) -> Option<OccupiedEntry<'_, T, A>> {
         let index = self.tables.key_to_item.first()?;
         let (_, dormant_map) = DormantMutRef::new(self);
         Some(
@@ -1214,6 +2270,55 @@ impl<T: IdOrdItem> IdOrdMap<T> {
         self.tables.key_to_item.last().map(|index| &self.items[index])
     }
 
+    /// Returns the last item in the map along with its key. The key is the
+    /// maximum key in the map.
+    ///
+    /// This is equivalent to calling [`Self::last`] and then
+    /// [`IdOrdItem::key`] on the result, and mirrors
+    /// [`BTreeMap::last_key_value`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use iddqd::{IdOrdItem, IdOrdMap, id_upcast};
+    ///
+    /// #[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
+    /// struct Item {
+    ///     id: String,
+    ///     value: u32,
+    /// }
+    ///
+    /// impl IdOrdItem for Item {
+    ///     type Key<'a> = &'a str;
+    ///
+    ///     fn key(&self) -> Self::Key<'_> {
+    ///         &self.id
+    ///     }
+    ///
+    ///     id_upcast!();
+    /// }
+    ///
+    /// let mut map = IdOrdMap::new();
+    /// map.insert_unique(Item { id: "charlie".to_string(), value: 30 }).unwrap();
+    /// map.insert_unique(Item { id: "alice".to_string(), value: 42 }).unwrap();
+    /// map.insert_unique(Item { id: "bob".to_string(), value: 99 }).unwrap();
+    ///
+    /// let (key, item) = map.last_key_value().unwrap();
+    /// assert_eq!(key, "charlie");
+    /// assert_eq!(item.value, 30);
+    ///
+    /// // Empty map returns None.
+    /// let empty_map: IdOrdMap<Item> = IdOrdMap::new();
+    /// assert!(empty_map.last_key_value().is_none());
+    /// ```
+    ///
+    /// [`BTreeMap::last_key_value`]: std::collections::BTreeMap::last_key_value
+    #[inline]
+    pub fn last_key_value(&self) -> Option<(T::Key<'_>, &T)> {
+        let item = self.last()?;
+        Some((item.key(), item))
+    }
+
     /// Returns the last entry in the map for in-place manipulation. The key of
     /// this entry is the maximum key in the map.
     ///
@@ -1250,7 +2355,7 @@ impl<T: IdOrdItem> IdOrdMap<T> {
     ///
     /// assert_eq!(map.get("charlie").unwrap().value, 200);
     /// ```
-    pub fn last_entry(&mut self) -> Option<OccupiedEntry<'_, T>> {
+    pub fn last_entry(&mut self) -> Option<OccupiedEntry<'_, T, A>> {
         let index = self.tables.key_to_item.last()?;
         let (_, dormant_map) = DormantMutRef::new(self);
         Some(
@@ -1308,11 +2413,246 @@ impl<T: IdOrdItem> IdOrdMap<T> {
         self.remove_by_index(index)
     }
 
+    /// Splits the map in two at the given key, returning a new map with all
+    /// items whose keys are greater than or equal to `key`.
+    ///
+    /// Items with keys less than `key` remain in `self`. Both maps are compact
+    /// afterwards. This matches [`BTreeMap::split_off`].
+    ///
+    /// The items moved to the new map are already in key order, so its index
+    /// is built in a single pass rather than by inserting items one at a time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use iddqd::{IdOrdItem, IdOrdMap, id_upcast};
+    ///
+    /// #[derive(Debug, PartialEq, Eq)]
+    /// struct Event {
+    ///     timestamp: u64,
+    ///     name: &'static str,
+    /// }
+    ///
+    /// impl IdOrdItem for Event {
+    ///     type Key<'a> = u64;
+    ///
+    ///     fn key(&self) -> Self::Key<'_> {
+    ///         self.timestamp
+    ///     }
+    ///
+    ///     id_upcast!();
+    /// }
+    ///
+    /// let mut before = IdOrdMap::new();
+    /// for (timestamp, name) in [(10, "a"), (20, "b"), (30, "c"), (40, "d")] {
+    ///     before.insert_unique(Event { timestamp, name }).unwrap();
+    /// }
+    ///
+    /// let after = before.split_off(&25);
+    ///
+    /// let names: Vec<_> = before.iter().map(|e| e.name).collect();
+    /// assert_eq!(names, ["a", "b"]);
+    /// let names: Vec<_> = after.iter().map(|e| e.name).collect();
+    /// assert_eq!(names, ["c", "d"]);
+    /// ```
+    ///
+    /// [`BTreeMap::split_off`]: std::collections::BTreeMap::split_off
+    pub fn split_off<'a, Q>(&'a mut self, key: &Q) -> IdOrdMap<T, A>
+    where
+        Q: ?Sized + Comparable<T::Key<'a>>,
+        A: Clone,
+    {
+        let (dormant_map, split_indexes) = {
+            let (map, dormant_map) = DormantMutRef::new(self);
+            let split_indexes: Vec<ItemIndex> = map
+                .tables
+                .key_to_item
+                .range::<_, Q, _, _>(
+                    (Bound::Included(key), Bound::Unbounded),
+                    |index| map.items[index].key(),
+                )
+                .collect();
+            (dormant_map, split_indexes)
+        };
+
+        // SAFETY: `map` is not used after this point.
+        let map = unsafe { dormant_map.awaken() };
+        let mut other = IdOrdMap::with_capacity_in(
+            split_indexes.len(),
+            map.items.allocator().clone(),
+        );
+
+        // The split indexes are exactly the tail of the B-tree, so they can be
+        // popped off without any comparisons.
+        for _ in 0..split_indexes.len() {
+            map.tables.key_to_item.pop_last();
+        }
+        for &index in &split_indexes {
+            let item = map.items.remove(index).expect("split index is valid");
+            other.items.assert_can_grow().insert(item);
+        }
+        let remap = map.items.compact();
+        map.tables.key_to_item.remap_indexes(&remap);
+
+        // Building the index calls into user code, so do it last, once `self`
+        // is consistent again.
+        let items = &other.items;
+        other.tables.key_to_item = MapBTreeTable::from_sorted_indexes(
+            items.iter().map(|(index, _)| index),
+            |index| items[index].key(),
+        );
+        other
+    }
+
+    /// Moves all items from `other` into `self`, leaving `other` empty.
+    ///
+    /// If an item in `other` has the same key as an item in `self`, the item
+    /// from `other` replaces it, like [`BTreeMap::append`]. The replaced item
+    /// is dropped.
+    ///
+    /// If `self` is empty, the two maps are swapped without moving any items.
+    /// Otherwise, items are moved over one at a time in key order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use iddqd::{IdOrdItem, IdOrdMap, id_upcast};
+    ///
+    /// #[derive(Debug, PartialEq, Eq)]
+    /// struct Item {
+    ///     id: u32,
+    ///     value: &'static str,
+    /// }
+    ///
+    /// impl IdOrdItem for Item {
+    ///     type Key<'a> = u32;
+    ///     fn key(&self) -> Self::Key<'_> {
+    ///         self.id
+    ///     }
+    ///     id_upcast!();
+    /// }
+    ///
+    /// let mut a = IdOrdMap::new();
+    /// a.insert_unique(Item { id: 1, value: "a1" }).unwrap();
+    /// a.insert_unique(Item { id: 2, value: "a2" }).unwrap();
+    ///
+    /// let mut b = IdOrdMap::new();
+    /// b.insert_unique(Item { id: 2, value: "b2" }).unwrap();
+    /// b.insert_unique(Item { id: 3, value: "b3" }).unwrap();
+    ///
+    /// a.append(&mut b);
+    /// assert!(b.is_empty());
+    ///
+    /// // The item from `b` replaced the one in `a` with the same key.
+    /// let values: Vec<_> = a.iter().map(|item| item.value).collect();
+    /// assert_eq!(values, ["a1", "b2", "b3"]);
+    /// ```
+    ///
+    /// [`BTreeMap::append`]: std::collections::BTreeMap::append
+    pub fn append(&mut self, other: &mut IdOrdMap<T, A>) {
+        if self.is_empty() {
+            core::mem::swap(self, other);
+            return;
+        }
+        self.reserve(other.len());
+        for item in other.drain() {
+            self.insert_overwrite(item);
+        }
+    }
+
+    /// Consumes the map, transforming each item with `f` and collecting the
+    /// results into a new map.
+    ///
+    /// Keys may change under `f`, as long as the transformed items remain
+    /// unique. If a transformed item conflicts with one produced earlier, this
+    /// returns a [`DuplicateItem`] error containing the new item and the
+    /// conflicting item, and drops the rest of the map.
+    ///
+    /// To overwrite duplicates instead, use `into_iter().map(f).collect()`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use iddqd::{IdOrdItem, IdOrdMap, id_upcast};
+    ///
+    /// #[derive(Debug)]
+    /// struct Raw {
+    ///     id: u32,
+    ///     text: String,
+    /// }
+    ///
+    /// impl IdOrdItem for Raw {
+    ///     type Key<'a> = u32;
+    ///     fn key(&self) -> Self::Key<'_> {
+    ///         self.id
+    ///     }
+    ///     id_upcast!();
+    /// }
+    ///
+    /// #[derive(Debug)]
+    /// struct Processed {
+    ///     id: u32,
+    ///     len: usize,
+    /// }
+    ///
+    /// impl IdOrdItem for Processed {
+    ///     type Key<'a> = u32;
+    ///     fn key(&self) -> Self::Key<'_> {
+    ///         self.id
+    ///     }
+    ///     id_upcast!();
+    /// }
+    ///
+    /// let mut raw = IdOrdMap::new();
+    /// raw.insert_unique(Raw { id: 1, text: "foo".to_string() }).unwrap();
+    /// raw.insert_unique(Raw { id: 2, text: "hello".to_string() }).unwrap();
+    ///
+    /// let processed = raw
+    ///     .map(|r| Processed { id: r.id, len: r.text.len() })
+    ///     .expect("keys are preserved");
+    /// let lens: Vec<_> = processed.iter().map(|p| p.len).collect();
+    /// assert_eq!(lens, [3, 5]);
+    ///
+    /// // Mapping every item to the same key produces an error.
+    /// let err = processed.map(|p| Processed { id: 0, len: p.len }).unwrap_err();
+    /// assert_eq!(err.duplicates().len(), 1);
+    /// ```
+    pub fn map<U, F>(self, f: F) -> Result<IdOrdMap<U, A>, DuplicateItem<U>>
+    where
+        U: IdOrdItem,
+        F: FnMut(T) -> U,
+        A: Clone,
+    {
+        let alloc = self.items.allocator().clone();
+        let mut map = IdOrdMap::with_capacity_in(self.len(), alloc);
+        for value in self.into_iter().map(f) {
+            // Go through the Entry API, as in `from_iter_unique`, so that the
+            // error can hold owned duplicates without requiring U: Clone.
+            match map.entry(value.key()) {
+                Entry::Occupied(entry) => {
+                    let duplicate = entry.remove();
+                    return Err(DuplicateItem::__internal_new(
+                        value,
+                        vec![duplicate],
+                    ));
+                }
+                Entry::Vacant(_) => {
+                    map.insert_known_unique_impl(value);
+                }
+            }
+        }
+
+        Ok(map)
+    }
+
     /// Retains only the elements specified by the predicate.
     ///
     /// In other words, remove all items `T` for which `f(RefMut<T>)` returns
     /// false. The elements are visited in ascending key order.
     ///
+    /// The remaining items are compacted afterwards, so that no holes are left
+    /// behind by the removed ones. The map's capacity is unchanged.
+    ///
     /// # Examples
     ///
     /// ```
@@ -1414,9 +2754,81 @@ impl<T: IdOrdItem> IdOrdMap<T> {
             }
         });
 
+        // Fill the holes left by removed items, so that the map stays
+        // compact.
+        //
+        // SAFETY: The closure above, which held the only reborrows of
+        // `dormant_items`, has returned.
+        let items = unsafe { dormant_items.awaken() };
+        let remap = items.compact();
+        if !remap.is_identity() {
+            self.tables.key_to_item.remap_indexes(&remap);
+        }
+
         // Anything in `removed_item` is implicitly dropped now.
     }
 
+    /// Removes all items for which `f` returns true, returning them in key
+    /// order.
+    ///
+    /// This is the inverse of [`Self::retain`], except that the predicate
+    /// takes `&T` and the removed items are returned rather than dropped.
+    /// Items are visited in key order.
+    ///
+    /// If `f` panics, no items are removed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use iddqd::{IdOrdItem, IdOrdMap, id_upcast};
+    ///
+    /// #[derive(Debug, PartialEq, Eq)]
+    /// struct Item {
+    ///     id: u32,
+    ///     stale: bool,
+    /// }
+    ///
+    /// impl IdOrdItem for Item {
+    ///     type Key<'a> = u32;
+    ///     fn key(&self) -> Self::Key<'_> {
+    ///         self.id
+    ///     }
+    ///     id_upcast!();
+    /// }
+    ///
+    /// let mut map = IdOrdMap::new();
+    /// map.insert_unique(Item { id: 3, stale: true }).unwrap();
+    /// map.insert_unique(Item { id: 2, stale: false }).unwrap();
+    /// map.insert_unique(Item { id: 1, stale: true }).unwrap();
+    ///
+    /// let removed = map.remove_where(|item| item.stale);
+    /// assert_eq!(
+    ///     removed,
+    ///     vec![Item { id: 1, stale: true }, Item { id: 3, stale: true }],
+    /// );
+    /// assert_eq!(map.len(), 1);
+    /// assert!(map.contains_key(&2));
+    /// ```
+    pub fn remove_where<F>(&mut self, mut f: F) -> Vec<T>
+    where
+        F: FnMut(&T) -> bool,
+    {
+        // Find all the matching indexes first, so that a panic in `f` leaves
+        // the map untouched.
+        let indexes: Vec<ItemIndex> = self
+            .tables
+            .key_to_item
+            .iter()
+            .filter(|&index| f(&self.items[index]))
+            .collect();
+        indexes
+            .into_iter()
+            .map(|index| {
+                self.remove_by_index(index).expect("index is present in items")
+            })
+            .collect()
+    }
+
     fn find<'a, Q>(&'a self, k: &Q) -> Option<&'a T>
     where
         Q: ?Sized + Comparable<T::Key<'a>>,
@@ -1468,6 +2880,19 @@ impl<T: IdOrdItem> IdOrdMap<T> {
         &mut self,
         value: T,
     ) -> Result<ItemIndex, DuplicateItem<T, &T>> {
+        match self.insert_unique_or_dup_indexes(value) {
+            Ok(index) => Ok(index),
+            Err((value, duplicates)) => Err(DuplicateItem::__internal_new(
+                value,
+                duplicates.iter().map(|ix| &self.items[*ix]).collect(),
+            )),
+        }
+    }
+
+    fn insert_unique_or_dup_indexes(
+        &mut self,
+        value: T,
+    ) -> Result<ItemIndex, (T, BTreeSet<ItemIndex>)> {
         let mut duplicates = BTreeSet::new();
 
         // Check for duplicates *before* inserting the new item, because we
@@ -1487,10 +2912,7 @@ impl<T: IdOrdItem> IdOrdMap<T> {
 
             if !duplicates.is_empty() {
                 drop(key);
-                return Err(DuplicateItem::__internal_new(
-                    value,
-                    duplicates.iter().map(|ix| &self.items[*ix]).collect(),
-                ));
+                return Err((value, duplicates));
             }
         }
 
@@ -1502,7 +2924,7 @@ impl<T: IdOrdItem> IdOrdMap<T> {
     /// Only call this after verifying that `value` does not conflict with any
     /// existing item. Callers that haven't determined uniqueness should use
     /// `insert_unique_impl` instead.
-    fn insert_known_unique_impl(&mut self, value: T) -> ItemIndex {
+    pub(super) fn insert_known_unique_impl(&mut self, value: T) -> ItemIndex {
         // Take the `GrowHandle` now, after the caller has checked that `value`
         // does not conflict with any existing item, but before the B-tree
         // mutation. With this approach, a panic from `assert_can_grow` (which
@@ -1598,7 +3020,7 @@ impl<T: IdOrdItem> IdOrdMap<T> {
     }
 }
 
-impl<'a, T: IdOrdItem> fmt::Debug for IdOrdMap<T>
+impl<'a, T: IdOrdItem, A: Allocator> fmt::Debug for IdOrdMap<T, A>
 where
     T: fmt::Debug,
     T::Key<'a>: fmt::Debug,
@@ -1629,7 +3051,7 @@ where
     }
 }
 
-impl<T: IdOrdItem + PartialEq> PartialEq for IdOrdMap<T> {
+impl<T: IdOrdItem + PartialEq, A: Allocator> PartialEq for IdOrdMap<T, A> {
     fn eq(&self, other: &Self) -> bool {
         // Items are stored in sorted order, so we can just walk over both
         // iterators.
@@ -1645,30 +3067,80 @@ impl<T: IdOrdItem + PartialEq> PartialEq for IdOrdMap<T> {
 }
 
 // The Eq bound on T ensures that the IdOrdMap forms an equivalence class.
-impl<T: IdOrdItem + Eq> Eq for IdOrdMap<T> {}
+impl<T: IdOrdItem + Eq, A: Allocator> Eq for IdOrdMap<T, A> {}
+
+/// Items are hashed in order of their key, consistent with the
+/// `PartialEq` implementation.
+impl<T: IdOrdItem + Hash, A: Allocator> Hash for IdOrdMap<T, A> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        state.write_usize(self.len());
+        for item in self {
+            item.hash(state);
+        }
+    }
+}
+
+/// Maps are compared lexicographically as sequences of items in key order, in
+/// the same way as [`BTreeMap`](std::collections::BTreeMap).
+///
+/// # Examples
+///
+/// ```
+/// use iddqd::{IdOrdItem, IdOrdMap, id_upcast};
+///
+/// #[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
+/// struct Item {
+///     id: u32,
+///     value: &'static str,
+/// }
+///
+/// impl IdOrdItem for Item {
+///     type Key<'a> = u32;
+///     fn key(&self) -> Self::Key<'_> {
+///         self.id
+///     }
+///     id_upcast!();
+/// }
+///
+/// let a: IdOrdMap<Item> =
+///     [Item { id: 1, value: "a" }, Item { id: 2, value: "b" }]
+///         .into_iter()
+///         .collect();
+/// let b: IdOrdMap<Item> =
+///     [Item { id: 1, value: "a" }, Item { id: 3, value: "c" }]
+///         .into_iter()
+///         .collect();
+/// let prefix: IdOrdMap<Item> =
+///     [Item { id: 1, value: "a" }].into_iter().collect();
+///
+/// assert!(a < b);
+/// assert!(prefix < a);
+/// ```
+impl<T: IdOrdItem + PartialOrd, A: Allocator> PartialOrd for IdOrdMap<T, A> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        // Items are stored in sorted order, so we can compare the iterators
+        // directly.
+        self.iter().partial_cmp(other.iter())
+    }
+}
 
-/// The `Extend` implementation overwrites duplicates. In the future, there will
-/// also be an `extend_unique` method that will return an error.
-impl<T: IdOrdItem> Extend<T> for IdOrdMap<T> {
+impl<T: IdOrdItem + Ord, A: Allocator> Ord for IdOrdMap<T, A> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.iter().cmp(other.iter())
+    }
+}
+
+/// The `Extend` implementation overwrites duplicates. To return an error on
+/// duplicates instead, use [`IdOrdMap::extend_with`] with
+/// [`ExtendMode::Unique`].
+impl<T: IdOrdItem, A: Allocator> Extend<T> for IdOrdMap<T, A> {
     fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
-        // Keys may already be present in the map, or multiple times in the
-        // iterator. Reserve the entire hint lower bound if the map is empty.
-        // Otherwise reserve half the hint (rounded up), so the map will only
-        // resize twice in the worst case.
-        let iter = iter.into_iter();
-        let reserve = if self.is_empty() {
-            iter.size_hint().0
-        } else {
-            iter.size_hint().0.div_ceil(2)
-        };
-        self.reserve(reserve);
-        for item in iter {
-            self.insert_overwrite(item);
-        }
+        // Overwrite mode never returns an error.
+        let _ = self.extend_with(iter, ExtendMode::Overwrite);
     }
 }
 
-impl<'a, T: IdOrdItem> IntoIterator for &'a IdOrdMap<T> {
+impl<'a, T: IdOrdItem, A: Allocator> IntoIterator for &'a IdOrdMap<T, A> {
     type Item = &'a T;
     type IntoIter = Iter<'a, T>;
 
@@ -1678,7 +3150,7 @@ impl<'a, T: IdOrdItem> IntoIterator for &'a IdOrdMap<T> {
     }
 }
 
-impl<'a, T: IdOrdItem> IntoIterator for &'a mut IdOrdMap<T>
+impl<'a, T: IdOrdItem, A: Allocator> IntoIterator for &'a mut IdOrdMap<T, A>
 where
     T::Key<'a>: Hash,
 {
@@ -1691,9 +3163,9 @@ where
     }
 }
 
-impl<T: IdOrdItem> IntoIterator for IdOrdMap<T> {
+impl<T: IdOrdItem, A: Allocator> IntoIterator for IdOrdMap<T, A> {
     type Item = T;
-    type IntoIter = IntoIter<T>;
+    type IntoIter = IntoIter<T, A>;
 
     #[inline]
     fn into_iter(self) -> Self::IntoIter {
@@ -1738,9 +3210,9 @@ impl<T: IdOrdItem> IntoIterator for IdOrdMap<T> {
 /// assert_eq!(map.get("foo").unwrap().value, 100); // last value wins
 /// assert_eq!(map.get("bar").unwrap().value, 20);
 /// ```
-impl<T: IdOrdItem> FromIterator<T> for IdOrdMap<T> {
+impl<T: IdOrdItem, A: Allocator + Default> FromIterator<T> for IdOrdMap<T, A> {
     fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
-        let mut map = IdOrdMap::new();
+        let mut map = Self::default();
         map.extend(iter);
         map
     }
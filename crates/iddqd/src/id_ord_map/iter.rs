@@ -1,11 +1,13 @@
 use super::{IdOrdItem, RefMut, tables::IdOrdMapTables};
 use crate::support::{
-    alloc::Global,
+    ItemIndex,
+    alloc::{Allocator, Global},
     borrow::DormantMutRef,
     btree_table,
-    item_set::{ConsumingItemSet, ItemSet, ItemSlotsPtr},
+    item_set::{ConsumingItemSet, ItemSet, ItemSlots, ItemSlotsPtr},
 };
-use core::{hash::Hash, iter::FusedIterator};
+use alloc::vec::{self, Vec};
+use core::{hash::Hash, iter::FusedIterator, marker::PhantomData};
 
 /// An iterator over the elements of an [`IdOrdMap`] by shared reference.
 ///
@@ -15,16 +17,16 @@ use core::{hash::Hash, iter::FusedIterator};
 /// [`IdOrdMap::iter`]: crate::IdOrdMap::iter
 #[derive(Clone, Debug)]
 pub struct Iter<'a, T: IdOrdItem> {
-    items: &'a ItemSet<T, Global>,
+    items: ItemSlots<'a, T>,
     iter: btree_table::Iter<'a>,
 }
 
 impl<'a, T: IdOrdItem> Iter<'a, T> {
-    pub(super) fn new(
-        items: &'a ItemSet<T, Global>,
+    pub(super) fn new<A: Allocator>(
+        items: &'a ItemSet<T, A>,
         tables: &'a IdOrdMapTables,
     ) -> Self {
-        Self { items, iter: tables.key_to_item.iter() }
+        Self { items: items.slots(), iter: tables.key_to_item.iter() }
     }
 }
 
@@ -34,7 +36,21 @@ impl<'a, T: IdOrdItem> Iterator for Iter<'a, T> {
     #[inline]
     fn next(&mut self) -> Option<Self::Item> {
         let index = self.iter.next()?;
-        Some(&self.items[index])
+        Some(self.items.item(index))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.len();
+        (len, Some(len))
+    }
+}
+
+impl<T: IdOrdItem> DoubleEndedIterator for Iter<'_, T> {
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let index = self.iter.next_back()?;
+        Some(self.items.item(index))
     }
 }
 
@@ -48,6 +64,140 @@ impl<T: IdOrdItem> ExactSizeIterator for Iter<'_, T> {
 // btree_set::Iter is a FusedIterator, so Iter is as well.
 impl<T: IdOrdItem> FusedIterator for Iter<'_, T> {}
 
+/// An iterator over the keys of an [`IdOrdMap`].
+///
+/// Created by [`IdOrdMap::keys`], and ordered by keys.
+///
+/// [`IdOrdMap`]: crate::IdOrdMap
+/// [`IdOrdMap::keys`]: crate::IdOrdMap::keys
+#[derive(Clone, Debug)]
+pub struct Keys<'a, T: IdOrdItem> {
+    inner: Iter<'a, T>,
+}
+
+impl<'a, T: IdOrdItem> Keys<'a, T> {
+    pub(super) fn new(inner: Iter<'a, T>) -> Self {
+        Self { inner }
+    }
+}
+
+impl<'a, T: IdOrdItem> Iterator for Keys<'a, T> {
+    type Item = T::Key<'a>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(T::key)
+    }
+}
+
+impl<T: IdOrdItem> ExactSizeIterator for Keys<'_, T> {
+    #[inline]
+    fn len(&self) -> usize {
+        self.inner.len()
+    }
+}
+
+impl<T: IdOrdItem> FusedIterator for Keys<'_, T> {}
+
+/// An iterator over a sub-range of the elements of an [`IdOrdMap`] by shared
+/// reference.
+///
+/// Created by [`IdOrdMap::range`], and ordered by keys. The iterator is
+/// double-ended, so it can also walk the range from the top down.
+///
+/// [`IdOrdMap`]: crate::IdOrdMap
+/// [`IdOrdMap::range`]: crate::IdOrdMap::range
+#[derive(Clone, Debug)]
+pub struct Range<'a, T: IdOrdItem> {
+    items: ItemSlots<'a, T>,
+    iter: btree_table::Range<'a>,
+}
+
+impl<'a, T: IdOrdItem> Range<'a, T> {
+    pub(super) fn new<A: Allocator>(
+        items: &'a ItemSet<T, A>,
+        iter: btree_table::Range<'a>,
+    ) -> Self {
+        Self { items: items.slots(), iter }
+    }
+}
+
+impl<'a, T: IdOrdItem> Iterator for Range<'a, T> {
+    type Item = &'a T;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let index = self.iter.next()?;
+        Some(self.items.item(index))
+    }
+}
+
+impl<T: IdOrdItem> DoubleEndedIterator for Range<'_, T> {
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let index = self.iter.next_back()?;
+        Some(self.items.item(index))
+    }
+}
+
+// btree_map::Range is a FusedIterator, so Range is as well.
+impl<T: IdOrdItem> FusedIterator for Range<'_, T> {}
+
+/// An iterator over the elements of an [`IdOrdMap`], ordered by a key derived
+/// from each item.
+///
+/// Created by [`IdOrdMap::iter_by`].
+///
+/// [`IdOrdMap`]: crate::IdOrdMap
+/// [`IdOrdMap::iter_by`]: crate::IdOrdMap::iter_by
+#[derive(Clone, Debug)]
+pub struct IterBy<'a, T: IdOrdItem> {
+    items: ItemSlots<'a, T>,
+    iter: vec::IntoIter<ItemIndex>,
+}
+
+impl<'a, T: IdOrdItem> IterBy<'a, T> {
+    pub(super) fn new<A: Allocator>(
+        items: &'a ItemSet<T, A>,
+        indexes: Vec<ItemIndex>,
+    ) -> Self {
+        Self { items: items.slots(), iter: indexes.into_iter() }
+    }
+}
+
+impl<'a, T: IdOrdItem> Iterator for IterBy<'a, T> {
+    type Item = &'a T;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let index = self.iter.next()?;
+        Some(self.items.item(index))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<T: IdOrdItem> DoubleEndedIterator for IterBy<'_, T> {
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let index = self.iter.next_back()?;
+        Some(self.items.item(index))
+    }
+}
+
+impl<T: IdOrdItem> ExactSizeIterator for IterBy<'_, T> {
+    #[inline]
+    fn len(&self) -> usize {
+        self.iter.len()
+    }
+}
+
+// vec::IntoIter is a FusedIterator, so IterBy is as well.
+impl<T: IdOrdItem> FusedIterator for IterBy<'_, T> {}
+
 /// An iterator over the elements of a [`IdOrdMap`] by mutable reference.
 ///
 /// This iterator returns [`RefMut`] instances.
@@ -70,8 +220,8 @@ impl<'a, T: IdOrdItem> IterMut<'a, T>
 where
     T::Key<'a>: Hash,
 {
-    pub(super) fn new(
-        items: &'a mut ItemSet<T, Global>,
+    pub(super) fn new<A: Allocator>(
+        items: &'a mut ItemSet<T, A>,
         tables: &'a IdOrdMapTables,
     ) -> Self {
         Self {
@@ -80,20 +230,14 @@ where
             iter: tables.key_to_item.iter(),
         }
     }
-}
 
-impl<'a, T: IdOrdItem + 'a> Iterator for IterMut<'a, T>
-where
-    T::Key<'a>: Hash,
-{
-    type Item = RefMut<'a, T>;
-
-    #[inline]
-    fn next(&mut self) -> Option<Self::Item> {
-        let index = self.iter.next()?;
-
-        // SAFETY: The B-tree is a set, so each call to `self.iter.next()`
-        // yields a distinct `index`. Therefore the `&mut T` references that
+    /// Returns a `RefMut` for an index yielded by `self.iter`.
+    fn make_ref_mut(&mut self, index: ItemIndex) -> RefMut<'a, T>
+    where
+        T: 'a,
+    {
+        // SAFETY: The B-tree is a set, and `self.iter` yields each index at
+        // most once from either end. Therefore the `&mut T` references that
         // `get_mut` hands out across iterations never alias.
         let item: &'a mut T = unsafe { self.items.get_mut(index) };
 
@@ -110,7 +254,37 @@ where
         // different allocation and does not alias.
         let item = unsafe { dormant.awaken() };
 
-        Some(RefMut::new(self.tables.state().clone(), hash, item))
+        RefMut::new(self.tables.state().clone(), hash, item)
+    }
+}
+
+impl<'a, T: IdOrdItem + 'a> Iterator for IterMut<'a, T>
+where
+    T::Key<'a>: Hash,
+{
+    type Item = RefMut<'a, T>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let index = self.iter.next()?;
+        Some(self.make_ref_mut(index))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.len();
+        (len, Some(len))
+    }
+}
+
+impl<'a, T: IdOrdItem + 'a> DoubleEndedIterator for IterMut<'a, T>
+where
+    T::Key<'a>: Hash,
+{
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let index = self.iter.next_back()?;
+        Some(self.make_ref_mut(index))
     }
 }
 
@@ -136,35 +310,175 @@ impl<'a, T: IdOrdItem + 'a> FusedIterator for IterMut<'a, T> where
 /// [`IdOrdMap`]: crate::IdOrdMap
 /// [`IdOrdMap::into_iter`]: crate::IdOrdMap::into_iter
 #[derive(Debug)]
-pub struct IntoIter<T: IdOrdItem> {
-    items: ConsumingItemSet<T, Global>,
+pub struct IntoIter<T: IdOrdItem, A: Allocator = Global> {
+    items: ConsumingItemSet<T, A>,
     iter: btree_table::IntoIter,
 }
 
-impl<T: IdOrdItem> IntoIter<T> {
-    pub(super) fn new(
-        items: ItemSet<T, Global>,
-        tables: IdOrdMapTables,
-    ) -> Self {
+impl<T: IdOrdItem, A: Allocator> IntoIter<T, A> {
+    pub(super) fn new(items: ItemSet<T, A>, tables: IdOrdMapTables) -> Self {
         Self {
             items: items.into_consuming(),
             iter: tables.key_to_item.into_iter(),
         }
     }
+
+    fn take(&mut self, index: ItemIndex) -> T {
+        // We own `self.items` and the B-tree's indexes are never revisited, so
+        // we can take directly from the consuming view.
+        self.items
+            .take(index)
+            .unwrap_or_else(|| panic!("index {index} not found in items"))
+    }
 }
 
-impl<T: IdOrdItem> Iterator for IntoIter<T> {
+impl<T: IdOrdItem, A: Allocator> Iterator for IntoIter<T, A> {
     type Item = T;
 
     #[inline]
     fn next(&mut self) -> Option<Self::Item> {
         let index = self.iter.next()?;
-        // We own `self.items` and the B-tree's indexes are never revisited, so
-        // we can take directly from the consuming view.
+        Some(self.take(index))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.len();
+        (len, Some(len))
+    }
+}
+
+impl<T: IdOrdItem, A: Allocator> DoubleEndedIterator for IntoIter<T, A> {
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let index = self.iter.next_back()?;
+        Some(self.take(index))
+    }
+}
+
+impl<T: IdOrdItem, A: Allocator> ExactSizeIterator for IntoIter<T, A> {
+    #[inline]
+    fn len(&self) -> usize {
+        self.iter.len()
+    }
+}
+
+// btree_map::IntoIter is a FusedIterator, so IntoIter is as well.
+impl<T: IdOrdItem, A: Allocator> FusedIterator for IntoIter<T, A> {}
+
+/// An iterator over the keys of an [`IdOrdMap`] by ownership.
+///
+/// Created by [`IdOrdMap::into_keys`], and ordered by keys.
+///
+/// Keys of type [`IdOrdItem::Key`] borrow from their items, so they can't
+/// outlive the items that produced them. This iterator takes each item out of
+/// the map, converts its key into the owned type `K`, and then drops the item.
+///
+/// [`IdOrdMap`]: crate::IdOrdMap
+/// [`IdOrdMap::into_keys`]: crate::IdOrdMap::into_keys
+#[derive(Debug)]
+pub struct IntoKeys<T: IdOrdItem, K, A: Allocator = Global> {
+    inner: IntoIter<T, A>,
+    _marker: PhantomData<fn() -> K>,
+}
+
+impl<T: IdOrdItem, K, A: Allocator> IntoKeys<T, K, A> {
+    pub(super) fn new(items: ItemSet<T, A>, tables: IdOrdMapTables) -> Self {
+        Self { inner: IntoIter::new(items, tables), _marker: PhantomData }
+    }
+}
+
+impl<T: IdOrdItem, K, A: Allocator> Iterator for IntoKeys<T, K, A>
+where
+    for<'k> T::Key<'k>: Into<K>,
+{
+    type Item = K;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        // The key borrows from `item`, so it must be converted before `item`
+        // is dropped at the end of this function.
+        let item = self.inner.next()?;
+        Some(item.key().into())
+    }
+}
+
+/// A draining iterator over the elements of an [`IdOrdMap`].
+///
+/// Created by [`IdOrdMap::drain`], and ordered by keys. The iterator is
+/// double-ended, so it can also drain the map from the top down.
+///
+/// Each item is removed from the map as it is yielded. If the iterator is
+/// dropped before it is exhausted, the remaining items are dropped as well,
+/// and the map is left empty.
+///
+/// [`IdOrdMap`]: crate::IdOrdMap
+/// [`IdOrdMap::drain`]: crate::IdOrdMap::drain
+#[derive(Debug)]
+pub struct Drain<'a, T: IdOrdItem, A: Allocator = Global> {
+    items: &'a mut ItemSet<T, A>,
+    tables: &'a mut IdOrdMapTables,
+}
+
+impl<'a, T: IdOrdItem, A: Allocator> Drain<'a, T, A> {
+    pub(super) fn new(
+        items: &'a mut ItemSet<T, A>,
+        tables: &'a mut IdOrdMapTables,
+    ) -> Self {
+        Self { items, tables }
+    }
+}
+
+impl<T: IdOrdItem, A: Allocator> Iterator for Drain<'_, T, A> {
+    type Item = T;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        // Remove the index before the item, so the map stays consistent even
+        // if the caller leaks this iterator.
+        let index = self.tables.key_to_item.pop_first()?;
         let next = self
             .items
-            .take(index)
+            .remove(index)
+            .unwrap_or_else(|| panic!("index {index} not found in items"));
+        Some(next)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.len();
+        (len, Some(len))
+    }
+}
+
+impl<T: IdOrdItem, A: Allocator> DoubleEndedIterator for Drain<'_, T, A> {
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let index = self.tables.key_to_item.pop_last()?;
+        let next = self
+            .items
+            .remove(index)
             .unwrap_or_else(|| panic!("index {index} not found in items"));
         Some(next)
     }
 }
+
+impl<T: IdOrdItem, A: Allocator> ExactSizeIterator for Drain<'_, T, A> {
+    #[inline]
+    fn len(&self) -> usize {
+        self.tables.key_to_item.len()
+    }
+}
+
+impl<T: IdOrdItem, A: Allocator> FusedIterator for Drain<'_, T, A> {}
+
+impl<T: IdOrdItem, A: Allocator> Drop for Drain<'_, T, A> {
+    fn drop(&mut self) {
+        // Drop any remaining items, so that the map is left empty. Clearing
+        // the index first means that a panic in an item's `Drop` can't leave indexes
+        // pointing at removed slots (the same as `IdOrdMap::clear`).
+        self.tables.key_to_item.clear();
+        // This keeps the allocation, and resets the free list.
+        self.items.clear();
+    }
+}
@@ -2,6 +2,9 @@
 //!
 //! For more information, see [`IdOrdMap`].
 
+#[cfg(feature = "arbitrary")]
+mod arbitrary_impls;
+mod cursor;
 #[cfg(feature = "daft")]
 mod daft_impls;
 mod entry;
@@ -10,6 +13,8 @@ mod iter;
 #[cfg(feature = "proptest")]
 mod proptest_impls;
 mod ref_mut;
+#[cfg(feature = "schemars1")]
+mod schemars1_impls;
 #[cfg(feature = "schemars08")]
 mod schemars_impls;
 #[cfg(feature = "serde")]
@@ -17,13 +22,16 @@ mod serde_impls;
 mod tables;
 pub(crate) mod trait_defs;
 
+pub use cursor::{CursorMut, UnorderedItemError};
 #[cfg(feature = "daft")]
 pub use daft_impls::Diff;
 pub use entry::{Entry, OccupiedEntry, VacantEntry};
 pub use imp::IdOrdMap;
-pub use iter::{IntoIter, Iter, IterMut};
+pub use iter::{Drain, IntoIter, IntoKeys, Iter, IterBy, IterMut, Keys, Range};
 #[cfg(feature = "proptest")]
-pub use proptest_impls::{IdOrdMapStrategy, IdOrdMapValueTree, prop_strategy};
+pub use proptest_impls::{
+    IdOrdMapStrategy, IdOrdMapValueTree, prop_strategy, prop_strategy_in,
+};
 pub use ref_mut::RefMut;
 #[cfg(feature = "serde")]
 pub use serde_impls::IdOrdMapAsMap;
@@ -1,6 +1,10 @@
 //! Proptest strategies for generating [`IdOrdMap`]s with random inputs.
 
-use crate::{IdOrdItem, id_ord_map::IdOrdMap};
+use crate::{
+    IdOrdItem,
+    id_ord_map::IdOrdMap,
+    support::alloc::{Allocator, Global, global_alloc},
+};
 use core::fmt;
 use proptest::{
     arbitrary::{Arbitrary, StrategyFor, any_with},
@@ -14,14 +18,15 @@ use proptest::{
 /// Created by the [`prop_strategy()`] function.
 #[must_use = "strategies do nothing unless used"]
 #[derive(Clone)]
-pub struct IdOrdMapStrategy<T>
+pub struct IdOrdMapStrategy<T, A = Global>
 where
     T: Strategy,
 {
     inner: VecStrategy<T>,
+    allocator: A,
 }
 
-impl<T> fmt::Debug for IdOrdMapStrategy<T>
+impl<T, A> fmt::Debug for IdOrdMapStrategy<T, A>
 where
     T: Strategy,
 {
@@ -72,36 +77,93 @@ where
 pub fn prop_strategy<T: Strategy>(
     element: T,
     size: impl Into<SizeRange>,
-) -> IdOrdMapStrategy<T> {
-    IdOrdMapStrategy { inner: proptest::collection::vec(element, size) }
+) -> IdOrdMapStrategy<T, Global> {
+    IdOrdMapStrategy {
+        inner: proptest::collection::vec(element, size),
+        allocator: global_alloc(),
+    }
+}
+
+/// Creates a strategy to generate [`IdOrdMap`]s with a custom allocator.
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(feature = "allocator-api2")] {
+/// use allocator_api2::alloc::Global;
+/// use iddqd::{IdOrdItem, IdOrdMap, id_ord_map, id_upcast};
+/// use proptest::{
+///     arbitrary::any, strategy::Strategy, test_runner::TestRunner,
+/// };
+///
+/// #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+/// struct Person {
+///     id: u32,
+///     name: String,
+/// }
+///
+/// impl IdOrdItem for Person {
+///     type Key<'a> = u32;
+///
+///     fn key(&self) -> Self::Key<'_> {
+///         self.id
+///     }
+///     id_upcast!();
+/// }
+///
+/// // Create a strategy with a custom allocator.
+/// let allocator = Global;
+/// let strategy = id_ord_map::prop_strategy_in(
+///     (any::<u32>(), any::<String>())
+///         .prop_map(|(id, name)| Person { id, name }),
+///     1..=4,
+///     allocator,
+/// );
+///
+/// // The strategy can be used in proptest contexts.
+/// let mut runner = TestRunner::default();
+/// let _tree = strategy.new_tree(&mut runner).unwrap();
+/// # }
+/// ```
+pub fn prop_strategy_in<T: Strategy, A>(
+    element: T,
+    size: impl Into<SizeRange>,
+    allocator: A,
+) -> IdOrdMapStrategy<T, A> {
+    IdOrdMapStrategy {
+        inner: proptest::collection::vec(element, size),
+        allocator,
+    }
 }
 
-impl<'a, T> Strategy for IdOrdMapStrategy<T>
+impl<'a, T, A> Strategy for IdOrdMapStrategy<T, A>
 where
     T: Strategy,
     T::Value: 'a + IdOrdItem,
     <T::Value as IdOrdItem>::Key<'a>: fmt::Debug,
+    A: Clone + Allocator,
 {
-    type Tree = IdOrdMapValueTree<T::Tree>;
-    type Value = IdOrdMap<T::Value>;
+    type Tree = IdOrdMapValueTree<T::Tree, A>;
+    type Value = IdOrdMap<T::Value, A>;
 
     fn new_tree(&self, runner: &mut TestRunner) -> NewTree<Self> {
         let inner = self.inner.new_tree(runner)?;
 
-        Ok(IdOrdMapValueTree { inner })
+        Ok(IdOrdMapValueTree { inner, allocator: self.allocator.clone() })
     }
 }
 
 /// `ValueTree` corresponding to [`IdOrdMapStrategy`].
 #[derive(Clone)]
-pub struct IdOrdMapValueTree<T>
+pub struct IdOrdMapValueTree<T, A = Global>
 where
     T: ValueTree,
 {
     inner: VecValueTree<T>,
+    allocator: A,
 }
 
-impl<T> fmt::Debug for IdOrdMapValueTree<T>
+impl<T, A> fmt::Debug for IdOrdMapValueTree<T, A>
 where
     T: ValueTree + fmt::Debug,
 {
@@ -112,17 +174,19 @@ where
     }
 }
 
-impl<'a, T> ValueTree for IdOrdMapValueTree<T>
+impl<'a, T, A> ValueTree for IdOrdMapValueTree<T, A>
 where
     T: ValueTree,
     T::Value: 'a + IdOrdItem,
     <T::Value as IdOrdItem>::Key<'a>: fmt::Debug,
+    A: Clone + Allocator,
 {
-    type Value = IdOrdMap<T::Value>;
+    type Value = IdOrdMap<T::Value, A>;
 
     fn current(&self) -> Self::Value {
         let items = self.inner.current();
-        let mut map = IdOrdMap::new();
+        let mut map =
+            IdOrdMap::with_capacity_in(items.len(), self.allocator.clone());
 
         for item in items {
             // Use insert_overwrite to handle duplicate keys.
@@ -141,16 +205,17 @@ where
     }
 }
 
-impl<'a, T> Arbitrary for IdOrdMap<T>
+impl<'a, T, A> Arbitrary for IdOrdMap<T, A>
 where
     T: 'a + IdOrdItem + Arbitrary,
     <T as IdOrdItem>::Key<'a>: fmt::Debug,
+    A: Clone + Allocator + Default,
 {
     type Parameters = (SizeRange, T::Parameters);
-    type Strategy = IdOrdMapStrategy<StrategyFor<T>>;
+    type Strategy = IdOrdMapStrategy<StrategyFor<T>, A>;
 
     fn arbitrary_with(args: Self::Parameters) -> Self::Strategy {
         let (size, element_args) = args;
-        prop_strategy(any_with::<T>(element_args), size)
+        prop_strategy_in(any_with::<T>(element_args), size, A::default())
     }
 }
@@ -1,5 +1,5 @@
 use super::IdOrdItem;
-use crate::support::map_hash::MapHash;
+use crate::support::{btree_table::HashState, map_hash::MapHash};
 use core::{
     fmt,
     hash::Hash,
@@ -56,7 +56,7 @@ where
     T::Key<'a>: Hash,
 {
     pub(super) fn new(
-        state: foldhash::fast::FixedState,
+        state: HashState,
         hash: MapHash,
         borrowed: &'a mut T,
     ) -> Self {
@@ -65,6 +65,11 @@ where
     }
 
     /// Converts this `RefMut` into a `&'a T`.
+    ///
+    /// This gives up the ability to mutate the item, which is useful for
+    /// returning a shared reference once mutation is done. The key is
+    /// checked one last time before the reference is returned, so this panics
+    /// if it was changed through this `RefMut`.
     pub fn into_ref(mut self) -> &'a T {
         let inner = self.inner.take().unwrap();
         inner.into_ref()
@@ -128,7 +133,7 @@ where
 }
 
 struct RefMutInner<'a, T: IdOrdItem> {
-    state: foldhash::fast::FixedState,
+    state: HashState,
     hash: MapHash,
     borrowed: &'a mut T,
 }
@@ -142,7 +147,7 @@ where
         // SAFETY: The key is borrowed, then dropped immediately. T is valid for
         // 'a so T::Key is valid for 'a.
         let key: T::Key<'a> =
-            unsafe { std::mem::transmute::<T::Key<'_>, T::Key<'a>>(key) };
+            unsafe { core::mem::transmute::<T::Key<'_>, T::Key<'a>>(key) };
         if !self.hash.is_same_hash(&self.state, &key) {
             panic!("key changed during RefMut borrow");
         }
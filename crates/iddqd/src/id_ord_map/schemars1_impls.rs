@@ -0,0 +1,57 @@
+//! Schemars 1.x implementations for IdOrdMap.
+
+use crate::{
+    id_ord_map::{
+        imp::IdOrdMap, serde_impls::IdOrdMapAsMap, trait_defs::IdOrdItem,
+    },
+    support::{
+        alloc::Allocator,
+        schemars1_utils::{create_map_schema, create_object_schema},
+    },
+};
+use alloc::borrow::Cow;
+use schemars1::{JsonSchema, Schema, SchemaGenerator};
+
+impl<T, A> JsonSchema for IdOrdMap<T, A>
+where
+    T: JsonSchema + IdOrdItem,
+    A: Allocator,
+{
+    fn schema_name() -> Cow<'static, str> {
+        alloc::format!("IdOrdMap_of_{}", T::schema_name()).into()
+    }
+
+    fn schema_id() -> Cow<'static, str> {
+        alloc::format!("iddqd::IdOrdMap<{}>", T::schema_id()).into()
+    }
+
+    fn json_schema(generator: &mut SchemaGenerator) -> Schema {
+        create_map_schema::<T>("IdOrdMap", "iddqd::IdOrdMap", generator)
+    }
+
+    fn inline_schema() -> bool {
+        true
+    }
+}
+
+impl<T, A> JsonSchema for IdOrdMapAsMap<T, A>
+where
+    T: JsonSchema + IdOrdItem,
+    A: Allocator,
+{
+    fn schema_name() -> Cow<'static, str> {
+        alloc::format!("IdOrdMapAsMap_of_{}", T::schema_name()).into()
+    }
+
+    fn schema_id() -> Cow<'static, str> {
+        alloc::format!("iddqd::IdOrdMapAsMap<{}>", T::schema_id()).into()
+    }
+
+    fn json_schema(generator: &mut SchemaGenerator) -> Schema {
+        create_object_schema::<T>("IdOrdMapAsMap", "iddqd::IdOrdMap", generator)
+    }
+
+    fn inline_schema() -> bool {
+        true
+    }
+}
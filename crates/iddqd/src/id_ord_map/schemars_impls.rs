@@ -4,14 +4,18 @@ use crate::{
     id_ord_map::{
         imp::IdOrdMap, serde_impls::IdOrdMapAsMap, trait_defs::IdOrdItem,
     },
-    support::schemars_utils::{create_map_schema, create_object_schema},
+    support::{
+        alloc::Allocator,
+        schemars_utils::{create_map_schema, create_object_schema},
+    },
 };
 use alloc::string::String;
 use schemars::{JsonSchema, r#gen::SchemaGenerator, schema::Schema};
 
-impl<T> JsonSchema for IdOrdMap<T>
+impl<T, A> JsonSchema for IdOrdMap<T, A>
 where
     T: JsonSchema + IdOrdItem,
+    A: Allocator,
 {
     fn schema_name() -> String {
         alloc::format!("IdOrdMap_of_{}", T::schema_name())
@@ -26,9 +30,10 @@ where
     }
 }
 
-impl<T> JsonSchema for IdOrdMapAsMap<T>
+impl<T, A> JsonSchema for IdOrdMapAsMap<T, A>
 where
     T: JsonSchema + IdOrdItem,
+    A: Allocator,
 {
     fn schema_name() -> String {
         alloc::format!("IdOrdMapAsMap_of_{}", T::schema_name())
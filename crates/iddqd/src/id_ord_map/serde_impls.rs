@@ -1,9 +1,14 @@
 use super::{IdOrdItem, IdOrdMap};
-use crate::support::size_hint::cautious;
+use crate::support::{
+    ItemIndex,
+    alloc::{Allocator, Global},
+    btree_table::MapBTreeTable,
+    size_hint::cautious,
+};
 use core::{fmt, marker::PhantomData};
 use serde_core::{
     Deserialize, Deserializer, Serialize, Serializer,
-    de::{MapAccess, SeqAccess, Visitor},
+    de::{self, MapAccess, SeqAccess, Visitor},
     ser::{SerializeMap, SerializeSeq},
 };
 
@@ -57,7 +62,7 @@ use serde_core::{
 ///     r#"[{"id":1,"name":"Alice","email":"alice@example.com"}]"#,
 /// );
 /// ```
-impl<T: IdOrdItem> Serialize for IdOrdMap<T>
+impl<T: IdOrdItem, A: Allocator> Serialize for IdOrdMap<T, A>
 where
     T: Serialize,
 {
@@ -77,7 +82,8 @@ where
 /// rebuilding the indexes and producing an error if there are any duplicates.
 ///
 /// The `fmt::Debug` bound on `T` ensures better error reporting.
-impl<'de, T: IdOrdItem + fmt::Debug> Deserialize<'de> for IdOrdMap<T>
+impl<'de, T: IdOrdItem + fmt::Debug, A: Default + Allocator> Deserialize<'de>
+    for IdOrdMap<T, A>
 where
     T: Deserialize<'de>,
 {
@@ -85,19 +91,37 @@ where
     where
         D: Deserializer<'de>,
     {
-        deserializer.deserialize_any(SeqVisitor { _marker: PhantomData })
+        deserializer.deserialize_any(SeqVisitor {
+            _marker: PhantomData,
+            alloc: A::default(),
+        })
+    }
+}
+
+impl<'de, T: IdOrdItem + fmt::Debug + Deserialize<'de>, A: Allocator>
+    IdOrdMap<T, A>
+{
+    /// Deserializes from a list of items, allocating new storage within the
+    /// provided allocator.
+    pub fn deserialize_in<D: Deserializer<'de>>(
+        deserializer: D,
+        alloc: A,
+    ) -> Result<Self, D::Error> {
+        deserializer.deserialize_any(SeqVisitor { _marker: PhantomData, alloc })
     }
 }
 
-struct SeqVisitor<T> {
+struct SeqVisitor<T, A> {
     _marker: PhantomData<fn() -> T>,
+    alloc: A,
 }
 
-impl<'de, T> Visitor<'de> for SeqVisitor<T>
+impl<'de, T, A> Visitor<'de> for SeqVisitor<T, A>
 where
     T: IdOrdItem + Deserialize<'de> + fmt::Debug,
+    A: Allocator,
 {
-    type Value = IdOrdMap<T>;
+    type Value = IdOrdMap<T, A>;
 
     fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
         formatter
@@ -111,14 +135,14 @@ where
     where
         Access: SeqAccess<'de>,
     {
-        let mut map = IdOrdMap::with_capacity(cautious::<T>(seq.size_hint()));
+        let mut builder =
+            SortedBuilder::new(cautious::<T>(seq.size_hint()), self.alloc);
 
         while let Some(element) = seq.next_element()? {
-            map.insert_unique(element)
-                .map_err(serde_core::de::Error::custom)?;
+            builder.push(element)?;
         }
 
-        Ok(map)
+        Ok(builder.finish())
     }
 
     fn visit_map<Access>(
@@ -128,16 +152,77 @@ where
     where
         Access: MapAccess<'de>,
     {
-        let mut map =
-            IdOrdMap::with_capacity(cautious::<T>(map_access.size_hint()));
+        let mut builder = SortedBuilder::new(
+            cautious::<T>(map_access.size_hint()),
+            self.alloc,
+        );
 
         while let Some((_, value)) =
             map_access.next_entry::<serde_core::de::IgnoredAny, T>()?
         {
-            map.insert_unique(value).map_err(serde_core::de::Error::custom)?;
+            builder.push(value)?;
         }
 
-        Ok(map)
+        Ok(builder.finish())
+    }
+}
+
+/// Builds an `IdOrdMap` during deserialization.
+///
+/// `IdOrdMap` serializes items in key order, so input is usually sorted. While
+/// that holds, items are appended to the item set without touching the B-tree,
+/// and the index is bulk-built once at the end. The first out-of-order item
+/// falls back to building the index and inserting one item at a time, which
+/// also handles duplicate detection.
+struct SortedBuilder<T, A: Allocator> {
+    map: IdOrdMap<T, A>,
+    // The index of the most recently appended item, while still sorted.
+    last: Option<ItemIndex>,
+    // Whether items have been appended without being added to the index.
+    unindexed: bool,
+}
+
+impl<T: IdOrdItem + fmt::Debug, A: Allocator> SortedBuilder<T, A> {
+    fn new(capacity: usize, alloc: A) -> Self {
+        Self {
+            map: IdOrdMap::with_capacity_in(capacity, alloc),
+            last: None,
+            unindexed: true,
+        }
+    }
+
+    fn push<E: de::Error>(&mut self, value: T) -> Result<(), E> {
+        if self.unindexed {
+            let in_order = match self.last {
+                Some(last) => self.map.items[last].key() < value.key(),
+                None => true,
+            };
+            if in_order {
+                let index = self.map.items.assert_can_grow().insert(value);
+                self.last = Some(index);
+                return Ok(());
+            }
+            self.build_index();
+        }
+
+        self.map.insert_unique(value).map_err(E::custom)?;
+        Ok(())
+    }
+
+    fn build_index(&mut self) {
+        let items = &self.map.items;
+        self.map.tables.key_to_item = MapBTreeTable::from_sorted_indexes(
+            items.iter().map(|(index, _)| index),
+            |index| items[index].key(),
+        );
+        self.unindexed = false;
+    }
+
+    fn finish(mut self) -> IdOrdMap<T, A> {
+        if self.unindexed {
+            self.build_index();
+        }
+        self.map
     }
 }
 
@@ -177,19 +262,21 @@ where
 ///
 /// - For serialization, the key type must implement [`Serialize`].
 /// - For JSON serialization, the key should be string-like or convertible to a string key.
-pub struct IdOrdMapAsMap<T> {
-    _marker: PhantomData<fn() -> T>,
+pub struct IdOrdMapAsMap<T, A: Allocator = Global> {
+    _marker: PhantomData<fn() -> (T, A)>,
 }
 
-struct MapVisitorAsMap<T> {
+struct MapVisitorAsMap<T, A> {
     _marker: PhantomData<fn() -> T>,
+    alloc: A,
 }
 
-impl<'de, T> Visitor<'de> for MapVisitorAsMap<T>
+impl<'de, T, A> Visitor<'de> for MapVisitorAsMap<T, A>
 where
     T: IdOrdItem + Deserialize<'de> + fmt::Debug,
+    A: Allocator,
 {
-    type Value = IdOrdMap<T>;
+    type Value = IdOrdMap<T, A>;
 
     fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
         formatter.write_str("a map with items representing an IdOrdMap")
@@ -202,23 +289,25 @@ where
     where
         Access: MapAccess<'de>,
     {
-        let mut map =
-            IdOrdMap::with_capacity(cautious::<T>(map_access.size_hint()));
+        let mut builder = SortedBuilder::new(
+            cautious::<T>(map_access.size_hint()),
+            self.alloc,
+        );
 
         while let Some((_, value)) =
             map_access.next_entry::<serde_core::de::IgnoredAny, T>()?
         {
-            map.insert_unique(value).map_err(serde_core::de::Error::custom)?;
+            builder.push(value)?;
         }
 
-        Ok(map)
+        Ok(builder.finish())
     }
 }
 
-impl<T> IdOrdMapAsMap<T> {
+impl<T, A: Allocator> IdOrdMapAsMap<T, A> {
     /// Serializes an `IdOrdMap` as a JSON object/map using `key()` as keys.
     pub fn serialize<'a, Ser>(
-        map: &IdOrdMap<T>,
+        map: &IdOrdMap<T, A>,
         serializer: Ser,
     ) -> Result<Ser::Ok, Ser::Error>
     where
@@ -247,11 +336,17 @@ impl<T> IdOrdMapAsMap<T> {
     }
 
     /// Deserializes an `IdOrdMap` from a JSON object/map.
-    pub fn deserialize<'de, D>(deserializer: D) -> Result<IdOrdMap<T>, D::Error>
+    pub fn deserialize<'de, D>(
+        deserializer: D,
+    ) -> Result<IdOrdMap<T, A>, D::Error>
     where
         T: IdOrdItem + Deserialize<'de> + fmt::Debug,
+        A: Default,
         D: Deserializer<'de>,
     {
-        deserializer.deserialize_map(MapVisitorAsMap { _marker: PhantomData })
+        deserializer.deserialize_map(MapVisitorAsMap {
+            _marker: PhantomData,
+            alloc: A::default(),
+        })
     }
 }
@@ -1,7 +1,10 @@
 use super::IdOrdItem;
 use crate::{
     internal::{ValidateCompact, ValidationError},
-    support::{btree_table::MapBTreeTable, map_hash::MapHash},
+    support::{
+        btree_table::{HashState, MapBTreeTable},
+        map_hash::MapHash,
+    },
 };
 use core::hash::Hash;
 
@@ -15,7 +18,7 @@ impl IdOrdMapTables {
         Self { key_to_item: MapBTreeTable::new() }
     }
 
-    pub(super) fn state(&self) -> &foldhash::fast::FixedState {
+    pub(super) fn state(&self) -> &HashState {
         self.key_to_item.state()
     }
 
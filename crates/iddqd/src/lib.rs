@@ -6,14 +6,18 @@
 //! - [`IdHashMap`]: A hash map where keys are borrowed from values.
 //! - [`BiHashMap`]: A bijective (1:1) hash map with two keys, borrowed from
 //!   values.
+//! - [`BiOrdMap`]: A bijective (1:1) map with two keys, borrowed from values,
+//!   where the first key is sorted and the second key is hashed.
 //! - [`TriHashMap`]: A trijective (1:1:1) hash map with three keys, borrowed
 //!   from values.
+//! - [`QuadHashMap`]: A 1:1:1:1 hash map with four keys, borrowed from values.
 //!
 //! # Usage
 //!
 //! * Pick your ID map type.
 //! * Depending on the ID map type, implement [`IdOrdItem`], [`IdHashItem`],
-//!   [`BiHashItem`], or [`TriHashItem`] for your value type.
+//!   [`BiHashItem`], [`BiOrdItem`], [`TriHashItem`], or [`QuadHashItem`] for
+//!   your value type.
 //! * Store values in the ID map type.
 //!
 //! ## Features
@@ -54,7 +58,6 @@
 //! An example for [`IdOrdMap`]:
 //!
 //! ```
-//! # #[cfg(feature = "std")] {
 //! use iddqd::{IdOrdItem, IdOrdMap, id_upcast};
 //!
 //! #[derive(Debug)]
@@ -90,7 +93,6 @@
 //! for user in &users {
 //!     println!("User {}: {}", user.name, user.age);
 //! }
-//! # }
 //! ```
 //!
 //! Keys don't have to be borrowed from the value. For smaller `Copy` types,
@@ -98,7 +100,6 @@
 //! [`IdOrdMap`] with a small integer key:
 //!
 //! ```
-//! # #[cfg(feature = "std")] {
 //! # use iddqd::{IdOrdMap, IdOrdItem, id_upcast};
 //! struct Record {
 //!     id: u32,
@@ -117,7 +118,6 @@
 //! }
 //!
 //! // ...
-//! # }
 //! ```
 //!
 //! An example for [`IdHashMap`], showing a complex borrowed key. Here,
@@ -276,6 +276,8 @@
 //! There's a blanket implementation of [`Equivalent`] and [`Comparable`] for
 //! [`Borrow`], so if your type already implements [`Borrow`], there aren't any
 //! extra steps to take.
+//! Otherwise, the [`impl_equivalent_borrowed!`] macro can generate these
+//! implementations for an owned form of a borrowed key type.
 //!
 //! # Testing and verification
 //!
@@ -307,18 +309,31 @@
 //!
 //! Most of this crate is no-std compatible, though [`alloc`] is required.
 //!
-//! The [`IdOrdMap`] type is not currently no-std compatible due to its use of a
-//! thread-local. This thread-local is just a way to work around a limitation in
-//! std's `BTreeMap` API, though. Either a custom B-Tree implementation, or a
-//! platform-specific notion of thread locals, would suffice to make
-//! [`IdOrdMap`] no-std compatible.
+//! With the `std` feature enabled, [`IdOrdMap`] and [`BiOrdMap`] use std's
+//! `BTreeMap` to store their indexes, with a thread-local used to work around a
+//! limitation in its API. Without `std`, they instead use a B-Tree bundled with
+//! this crate, which doesn't need the thread-local. Either way, lookups,
+//! insertions and removals take `O(log n)` time.
+//!
+//! [`IdOrdMap`] and [`BiOrdMap`] accept a custom allocator for their items
+//! (and, for [`BiOrdMap`], the hash table for the second key), but with or
+//! without `std` the B-Tree index always uses the global allocator. std's
+//! `BTreeMap` does not support custom allocators on stable Rust, and the
+//! ordered maps behave the same regardless of which B-Tree backs them.
 //!
 //! # Optional features
 //!
 //! - `allocator-api2`: Enables support for custom allocators via the
 //!   [`allocator_api2`] crate. Both global and scoped/arena allocators
-//!   (such as `bumpalo`) are supported. Custom allocators are not currently
-//!   supported by `IdOrdMap`.
+//!   (such as `bumpalo`) are supported. For [`IdOrdMap`] and [`BiOrdMap`], the
+//!   B-Tree index always uses the global allocator; see
+//!   [No-std compatibility] for why.
+//! - `arbitrary`: Implements [`arbitrary`]'s `Arbitrary` for [`IdHashMap`],
+//!   [`BiHashMap`], [`TriHashMap`], and [`IdOrdMap`], for use with fuzzers.
+//!   Items with duplicate keys are silently collapsed, with later items
+//!   replacing earlier ones. *Not enabled by default.*
+//! - `concurrent`: Enables [`ConcurrentIdHashMap`], a sharded, thread-safe
+//!   wrapper around [`IdHashMap`]. Implies `std`. *Not enabled by default.*
 //! - `daft`: Enables [`daft`] support for all ID map types. *Not enabled by
 //!   default.*
 //! - `default-hasher`: Enables the `DefaultHashBuilder` type. Disable this
@@ -327,9 +342,15 @@
 //! - `proptest`: Enables [`proptest`] support for all ID map types, providing
 //!   [`Arbitrary`] implementations and strategies for property-based testing.
 //!   *Not enabled by default.*
+//! - `rayon`: Enables parallel iteration over [`IdHashMap`], [`BiHashMap`],
+//!   and [`TriHashMap`] by shared reference, via [`rayon`]'s
+//!   `IntoParallelIterator`. *Not enabled by default.*
 //! - `schemars08`: Enables [`schemars`] support for all ID map types,
 //!   including support for [automatic replacement] through [`typify`] or
 //!   [`dropshot`]. *Not enabled by default.*
+//! - `schemars1`: Like `schemars08`, but for version 1 of [`schemars`]. The
+//!   generated schemas have the same shape, including the `x-rust-type`
+//!   extension used by [`typify`]. *Not enabled by default.*
 //! - `serde`: Enables serde support for all ID map types. *Not enabled by
 //!   default.*
 //! - `std`: Enables std support. *Enabled by default.*
@@ -371,6 +392,9 @@
 //! [`typify`]: https://crates.io/crates/typify
 //! [`dropshot`]: https://crates.io/crates/dropshot
 //! [`Arbitrary`]: proptest::arbitrary::Arbitrary
+//! [`rayon`]: https://crates.io/crates/rayon
+//! [`arbitrary`]: https://crates.io/crates/arbitrary
+//! [No-std compatibility]: #no-std-compatibility
 
 #![no_std]
 #![cfg_attr(doc_cfg, feature(doc_cfg))]
@@ -387,29 +411,36 @@ extern crate std;
 mod macros;
 
 pub mod bi_hash_map;
+pub mod bi_ord_map;
 pub mod errors;
 pub mod id_hash_map;
-#[cfg(feature = "std")]
 pub mod id_ord_map;
 #[doc(hidden)]
 pub mod internal;
 #[cfg(soteria)]
 mod proofs;
+pub mod quad_hash_map;
+#[cfg(feature = "serde")]
+pub mod serde;
 mod support;
 pub mod tri_hash_map;
 
-pub use bi_hash_map::{imp::BiHashMap, trait_defs::BiHashItem};
-// Re-exports of equivalent traits. Comparable is only used by IdOrdMap, hence
-// is restricted to std.
-#[cfg(feature = "std")]
+pub use bi_hash_map::{BiMap, imp::BiHashMap, trait_defs::BiHashItem};
+pub use bi_ord_map::{imp::BiOrdMap, trait_defs::BiOrdItem};
+// Re-exports of equivalent traits.
 #[doc(no_inline)]
 pub use equivalent::Comparable;
 #[doc(no_inline)]
 pub use equivalent::Equivalent;
+#[cfg(feature = "concurrent")]
+pub use id_hash_map::ConcurrentIdHashMap;
 pub use id_hash_map::{imp::IdHashMap, trait_defs::IdHashItem};
-#[cfg(feature = "std")]
 pub use id_ord_map::{imp::IdOrdMap, trait_defs::IdOrdItem};
+pub use quad_hash_map::{imp::QuadHashMap, trait_defs::QuadHashItem};
 #[cfg(feature = "daft")]
 pub use support::daft_utils::IdLeaf;
-pub use support::hash_builder::DefaultHashBuilder;
+pub use support::{
+    capacity_breakdown::CapacityBreakdown, extend_mode::ExtendMode,
+    hash_builder::DefaultHashBuilder, immutable_key::ImmutableKey,
+};
 pub use tri_hash_map::{imp::TriHashMap, trait_defs::TriHashItem};
@@ -87,7 +87,6 @@ macro_rules! id_hash_map {
 /// # Examples
 ///
 /// ```
-/// # #[cfg(feature = "std")] {
 /// use iddqd::{IdOrdItem, id_ord_map, id_upcast};
 ///
 /// #[derive(Debug)]
@@ -110,9 +109,7 @@ macro_rules! id_hash_map {
 /// };
 /// assert_eq!(map.get(&1).unwrap().name, "Alice");
 /// assert_eq!(map.get(&2).unwrap().name, "Bob");
-/// # }
 /// ```
-#[cfg(feature = "std")]
 #[macro_export]
 macro_rules! id_ord_map {
     ($($item:expr,)+) => { $crate::id_ord_map!($($item),+) };
@@ -298,6 +295,97 @@ macro_rules! tri_hash_map {
     };
 }
 
+/// Creates a [`QuadHashMap`](crate::QuadHashMap) from a list of items.
+///
+/// An optional [`BuildHasher`](core::hash::BuildHasher) that implements
+/// `Default` can be provided as the first argument, followed by a semicolon.
+///
+/// # Panics
+///
+/// Panics if the list of items has duplicate keys. For better error handling,
+/// the item is required to implement `Debug`.
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(feature = "default-hasher")] {
+/// use iddqd::{QuadHashItem, quad_hash_map, quad_upcast};
+///
+/// #[derive(Debug)]
+/// struct Record {
+///     uuid: u128,
+///     name: String,
+///     serial: String,
+///     slot: u8,
+/// }
+///
+/// impl QuadHashItem for Record {
+///     type K1<'a> = u128;
+///     type K2<'a> = &'a str;
+///     type K3<'a> = &'a str;
+///     type K4<'a> = u8;
+///     fn key1(&self) -> Self::K1<'_> {
+///         self.uuid
+///     }
+///     fn key2(&self) -> Self::K2<'_> {
+///         &self.name
+///     }
+///     fn key3(&self) -> Self::K3<'_> {
+///         &self.serial
+///     }
+///     fn key4(&self) -> Self::K4<'_> {
+///         self.slot
+///     }
+///     quad_upcast!();
+/// }
+///
+/// let map = quad_hash_map! {
+///     Record { uuid: 1, name: "sled-a".to_string(), serial: "SN-0001".to_string(), slot: 0 },
+///     Record { uuid: 2, name: "sled-b".to_string(), serial: "SN-0002".to_string(), slot: 1 },
+/// };
+/// assert_eq!(map.get1(&1).unwrap().name, "sled-a");
+/// assert_eq!(map.get2("sled-b").unwrap().uuid, 2);
+/// assert_eq!(map.get3("SN-0001").unwrap().slot, 0);
+/// assert_eq!(map.get4(&1).unwrap().serial, "SN-0002");
+///
+/// // With a custom hasher:
+/// let map = quad_hash_map! {
+///     foldhash::quality::RandomState;
+///     Record { uuid: 3, name: "sled-c".to_string(), serial: "SN-0003".to_string(), slot: 2 },
+/// };
+/// assert_eq!(map.get1(&3).unwrap().name, "sled-c");
+/// # }
+/// ```
+#[macro_export]
+macro_rules! quad_hash_map {
+    ($($item:expr,)+) => { $crate::quad_hash_map!($($item),+) };
+    ($($item:expr),*) => {
+        {
+            // Note: `stringify!($key)` is just here to consume the repetition,
+            // but we throw away that string literal during constant evaluation.
+            const CAP: usize = <[()]>::len(&[$({ stringify!($item); }),*]);
+            let mut map = $crate::QuadHashMap::with_capacity(CAP);
+            $(
+                map.insert_unique($item).unwrap();
+            )*
+            map
+        }
+    };
+    ($H:ty; $($item:expr,)+) => { $crate::quad_hash_map!($H; $($item),+) };
+    ($H:ty; $($item:expr),*) => {
+        {
+            // Note: `stringify!($key)` is just here to consume the repetition,
+            // but we throw away that string literal during constant evaluation.
+            const CAP: usize = <[()]>::len(&[$({ stringify!($item); }),*]);
+            let mut map = $crate::QuadHashMap::with_capacity_and_hasher(CAP, <$H>::default());
+            $(
+                map.insert_unique($item).unwrap();
+            )*
+            map
+        }
+    };
+}
+
 /// Implement upcasts for [`IdOrdMap`] or [`IdHashMap`].
 ///
 /// The maps in this crate require that the key types' lifetimes are covariant.
@@ -323,7 +411,7 @@ macro_rules! id_upcast {
     };
 }
 
-/// Implement upcasts for [`BiHashMap`].
+/// Implement upcasts for [`BiHashMap`] or [`BiOrdMap`].
 ///
 /// The maps in this crate require that the key types' lifetimes are covariant.
 /// This macro assists with implementing this requirement.
@@ -332,6 +420,7 @@ macro_rules! id_upcast {
 /// well.
 ///
 /// [`BiHashMap`]: crate::BiHashMap
+/// [`BiOrdMap`]: crate::BiOrdMap
 #[macro_export]
 macro_rules! bi_upcast {
     () => {
@@ -401,6 +490,195 @@ macro_rules! tri_upcast {
     };
 }
 
+/// Implement upcasts for [`QuadHashMap`].
+///
+/// The maps in this crate require that the key types' lifetimes are covariant.
+/// This macro assists with implementing this requirement.
+///
+/// The macro is optional, and these implementations can be written by hand as
+/// well.
+///
+/// [`QuadHashMap`]: crate::QuadHashMap
+#[macro_export]
+macro_rules! quad_upcast {
+    () => {
+        #[inline]
+        fn upcast_key1<'short, 'long: 'short>(
+            long: Self::K1<'long>,
+        ) -> Self::K1<'short>
+        where
+            Self: 'long,
+        {
+            long
+        }
+
+        #[inline]
+        fn upcast_key2<'short, 'long: 'short>(
+            long: Self::K2<'long>,
+        ) -> Self::K2<'short>
+        where
+            Self: 'long,
+        {
+            long
+        }
+
+        #[inline]
+        fn upcast_key3<'short, 'long: 'short>(
+            long: Self::K3<'long>,
+        ) -> Self::K3<'short>
+        where
+            Self: 'long,
+        {
+            long
+        }
+
+        #[inline]
+        fn upcast_key4<'short, 'long: 'short>(
+            long: Self::K4<'long>,
+        ) -> Self::K4<'short>
+        where
+            Self: 'long,
+        {
+            long
+        }
+    };
+}
+
+/// Implements [`Equivalent`] and [`Comparable`] between an owned key type and
+/// a borrowed key type.
+///
+/// The maps in this crate are looked up by a key type that typically borrows
+/// from the item, such as `ArtifactKey<'a>` below. To look items up by an
+/// owned form of the key, that owned form must implement [`Equivalent`] (and
+/// for [`IdOrdMap`], [`Comparable`]) against the borrowed key type.
+///
+/// Given an owned type, a borrowed type with a single lifetime parameter, and a
+/// closure-like expression that borrows the owned type as the borrowed one,
+/// this macro generates:
+///
+/// * `Equivalent` in both directions, comparing via the borrowed type's
+///   `PartialEq` implementation.
+/// * `Comparable` in both directions, comparing via the borrowed type's `Ord`
+///   implementation. These implementations only apply if the borrowed type
+///   implements `Ord`.
+///
+/// For hash map lookups, the owned type must also hash the same way as the
+/// borrowed type. This is typically done by deriving `Hash` on both, with
+/// fields in the same order.
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(feature = "default-hasher")] {
+/// use iddqd::{
+///     IdHashItem, IdHashMap, id_upcast, impl_equivalent_borrowed,
+/// };
+///
+/// struct Artifact {
+///     name: String,
+///     version: String,
+///     data: Vec<u8>,
+/// }
+///
+/// #[derive(Eq, Hash, PartialEq, PartialOrd, Ord)]
+/// struct ArtifactKey<'a> {
+///     name: &'a str,
+///     version: &'a str,
+/// }
+///
+/// impl IdHashItem for Artifact {
+///     type Key<'a> = ArtifactKey<'a>;
+///     fn key(&self) -> Self::Key<'_> {
+///         ArtifactKey { name: &self.name, version: &self.version }
+///     }
+///     id_upcast!();
+/// }
+///
+/// // The fields are in the same order as ArtifactKey's fields, so it
+/// // hashes the same way.
+/// #[derive(Hash)]
+/// struct OwnedArtifactKey {
+///     name: String,
+///     version: String,
+/// }
+///
+/// impl_equivalent_borrowed!(
+///     OwnedArtifactKey => ArtifactKey<'a>,
+///     |key| ArtifactKey { name: &key.name, version: &key.version },
+/// );
+///
+/// let mut artifacts = IdHashMap::new();
+/// artifacts
+///     .insert_unique(Artifact {
+///         name: "artifact1".to_owned(),
+///         version: "1.0".to_owned(),
+///         data: b"data1".to_vec(),
+///     })
+///     .unwrap_or_else(|_| panic!("no duplicates"));
+///
+/// let owned_key = OwnedArtifactKey {
+///     name: "artifact1".to_owned(),
+///     version: "1.0".to_owned(),
+/// };
+/// assert_eq!(artifacts.get(&owned_key).unwrap().data, b"data1");
+/// # }
+/// ```
+///
+/// [`Equivalent`]: crate::Equivalent
+/// [`Comparable`]: crate::Comparable
+/// [`IdOrdMap`]: crate::IdOrdMap
+#[macro_export]
+macro_rules! impl_equivalent_borrowed {
+    (
+        $owned:ty => $borrowed:ident<$lt:lifetime>,
+        |$key:ident| $to_borrowed:expr $(,)?
+    ) => {
+        impl<$lt> $crate::Equivalent<$borrowed<$lt>> for $owned {
+            #[inline]
+            fn equivalent(&self, other: &$borrowed<$lt>) -> bool {
+                let $key = self;
+                let this: $borrowed<'_> = $to_borrowed;
+                this == *other
+            }
+        }
+
+        impl<$lt> $crate::Equivalent<$owned> for $borrowed<$lt> {
+            #[inline]
+            fn equivalent(&self, other: &$owned) -> bool {
+                let $key = other;
+                let other: $borrowed<'_> = $to_borrowed;
+                *self == other
+            }
+        }
+
+        impl<$lt> $crate::Comparable<$borrowed<$lt>> for $owned
+        where
+            for<'__k> $borrowed<'__k>: ::core::cmp::Ord,
+        {
+            #[inline]
+            fn compare(&self, other: &$borrowed<$lt>) -> ::core::cmp::Ordering {
+                let $key = self;
+                let this: $borrowed<'_> = $to_borrowed;
+                let other: &$borrowed<'_> = other;
+                ::core::cmp::Ord::cmp(&this, other)
+            }
+        }
+
+        impl<$lt> $crate::Comparable<$owned> for $borrowed<$lt>
+        where
+            for<'__k> $borrowed<'__k>: ::core::cmp::Ord,
+        {
+            #[inline]
+            fn compare(&self, other: &$owned) -> ::core::cmp::Ordering {
+                let $key = other;
+                let other: $borrowed<'_> = $to_borrowed;
+                let this: &$borrowed<'_> = self;
+                ::core::cmp::Ord::cmp(this, &other)
+            }
+        }
+    };
+}
+
 // Internal macro to implement diffs.
 #[cfg(feature = "daft")]
 macro_rules! impl_diff_ref_cast {
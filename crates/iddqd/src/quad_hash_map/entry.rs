@@ -0,0 +1,664 @@
+use super::{QuadHashItem, QuadHashMap, RefMut, entry_indexes::EntryIndexes};
+use crate::{
+    DefaultHashBuilder,
+    support::{
+        alloc::{Allocator, Global},
+        borrow::DormantMutRef,
+        map_hash::MapHash,
+    },
+};
+use alloc::vec::Vec;
+use core::{fmt, hash::BuildHasher};
+
+/// An implementation of the Entry API for [`QuadHashMap`].
+///
+/// As with [`tri_hash_map::Entry`], some of the four keys provided may
+/// correspond to existing entries while others do not, and the keys may
+/// correspond to up to four different items.
+///
+/// [`VacantEntry`] corresponds to situations where none of the keys are
+/// present. To insert an entry corresponding to the four keys, use
+/// [`VacantEntry::insert`].
+///
+/// [`OccupiedEntry`] represents situations where either the keys correspond to
+/// different entries, or where only some of the keys are present.
+/// [`OccupiedEntry::get`] and [`OccupiedEntry::get_mut`] return an
+/// [`OccupiedEntryRef`] or [`OccupiedEntryMut`] enum that can be matched on.
+/// Since an item can only be borrowed mutably once, [`OccupiedEntryMut`]
+/// returns an item matched by several keys only for the first of them.
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(feature = "default-hasher")] {
+/// use iddqd::{QuadHashItem, QuadHashMap, quad_hash_map, quad_upcast};
+///
+/// #[derive(Debug, PartialEq, Eq)]
+/// struct Record {
+///     uuid: u128,
+///     name: String,
+///     serial: String,
+///     slot: u8,
+/// }
+///
+/// impl QuadHashItem for Record {
+///     type K1<'a> = u128;
+///     type K2<'a> = &'a str;
+///     type K3<'a> = &'a str;
+///     type K4<'a> = u8;
+///
+///     fn key1(&self) -> Self::K1<'_> {
+///         self.uuid
+///     }
+///     fn key2(&self) -> Self::K2<'_> {
+///         &self.name
+///     }
+///     fn key3(&self) -> Self::K3<'_> {
+///         &self.serial
+///     }
+///     fn key4(&self) -> Self::K4<'_> {
+///         self.slot
+///     }
+///     quad_upcast!();
+/// }
+///
+/// fn record(uuid: u128, name: &str, serial: &str, slot: u8) -> Record {
+///     Record {
+///         uuid,
+///         name: name.to_string(),
+///         serial: serial.to_string(),
+///         slot,
+///     }
+/// }
+///
+/// let mut map = QuadHashMap::new();
+/// map.insert_unique(record(1, "sled-a", "SN-0001", 0)).unwrap();
+/// map.insert_unique(record(2, "sled-b", "SN-0002", 1)).unwrap();
+///
+/// // All four keys point to the same item, so the entry is unique.
+/// match map.entry(1, "sled-a", "SN-0001", 0) {
+///     quad_hash_map::Entry::Occupied(entry) => {
+///         assert!(entry.is_unique());
+///         assert_eq!(entry.get().as_unique().unwrap().slot, 0);
+///     }
+///     quad_hash_map::Entry::Vacant(_) => panic!("Should be occupied"),
+/// }
+///
+/// // An entry is non-unique when its keys point to different items, or when
+/// // only some of them are present. Here, slot 1 belongs to "sled-b", and
+/// // the serial number is not present.
+/// match map.entry(1, "sled-a", "SN-0003", 1) {
+///     quad_hash_map::Entry::Occupied(mut entry) => {
+///         assert!(entry.is_non_unique());
+///         let entry_ref = entry.get();
+///         assert_eq!(entry_ref.by_key1().unwrap().name, "sled-a");
+///         assert_eq!(entry_ref.by_key3(), None);
+///         assert_eq!(entry_ref.by_key4().unwrap().name, "sled-b");
+///
+///         // Inserting overwrites whichever items the keys matched, returning
+///         // them.
+///         let mut replaced = entry.insert(record(1, "sled-a", "SN-0003", 1));
+///         replaced.sort_by_key(|item| item.uuid);
+///         assert_eq!(replaced.len(), 2);
+///         assert_eq!(replaced[0].serial, "SN-0001");
+///         assert_eq!(replaced[1].serial, "SN-0002");
+///         assert!(entry.is_unique());
+///     }
+///     quad_hash_map::Entry::Vacant(_) => panic!("Should be occupied"),
+/// }
+///
+/// assert_eq!(map.len(), 1);
+/// assert_eq!(map.get4(&1).unwrap().serial, "SN-0003");
+/// # }
+/// ```
+///
+/// [`tri_hash_map::Entry`]: crate::tri_hash_map::Entry
+pub enum Entry<
+    'a,
+    T: QuadHashItem,
+    S = DefaultHashBuilder,
+    A: Allocator = Global,
+> {
+    /// A vacant entry: none of the provided keys are present.
+    Vacant(VacantEntry<'a, T, S, A>),
+    /// An occupied entry where at least one of the keys is present in the map.
+    Occupied(OccupiedEntry<'a, T, S, A>),
+}
+
+impl<'a, T: QuadHashItem, S, A: Allocator> fmt::Debug for Entry<'a, T, S, A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Entry::Vacant(entry) => {
+                f.debug_tuple("Vacant").field(entry).finish()
+            }
+            Entry::Occupied(entry) => {
+                f.debug_tuple("Occupied").field(entry).finish()
+            }
+        }
+    }
+}
+
+impl<'a, T: QuadHashItem, S: Clone + BuildHasher, A: Allocator>
+    Entry<'a, T, S, A>
+{
+    /// Ensures a value is in the entry by inserting the default if empty, and
+    /// returns a mutable reference to the value in the entry.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the key hashes to a different value than the one passed
+    /// into [`QuadHashMap::entry`].
+    #[inline]
+    pub fn or_insert(self, default: T) -> OccupiedEntryMut<'a, T, S> {
+        match self {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => {
+                OccupiedEntryMut::Unique(entry.insert(default))
+            }
+        }
+    }
+
+    /// Ensures a value is in the entry by inserting the result of the default
+    /// function if empty, and returns a mutable reference to the value in the
+    /// entry.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the key hashes to a different value than the one passed
+    /// into [`QuadHashMap::entry`].
+    #[inline]
+    pub fn or_insert_with<F: FnOnce() -> T>(
+        self,
+        default: F,
+    ) -> OccupiedEntryMut<'a, T, S> {
+        match self {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => {
+                OccupiedEntryMut::Unique(entry.insert(default()))
+            }
+        }
+    }
+
+    /// Provides in-place mutable access to occupied entries before any
+    /// potential inserts into the map.
+    ///
+    /// `F` is called once for each distinct item that matches the provided
+    /// keys.
+    #[inline]
+    pub fn and_modify<F>(self, f: F) -> Self
+    where
+        F: FnMut(RefMut<'_, T, S>),
+    {
+        match self {
+            Entry::Occupied(mut entry) => {
+                entry.get_mut().for_each(f);
+                Entry::Occupied(entry)
+            }
+            Entry::Vacant(entry) => Entry::Vacant(entry),
+        }
+    }
+}
+
+/// A vacant entry.
+pub struct VacantEntry<
+    'a,
+    T: QuadHashItem,
+    S = DefaultHashBuilder,
+    A: Allocator = Global,
+> {
+    map: DormantMutRef<'a, QuadHashMap<T, S, A>>,
+    hashes: [MapHash; 4],
+}
+
+impl<'a, T: QuadHashItem, S, A: Allocator> fmt::Debug
+    for VacantEntry<'a, T, S, A>
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("VacantEntry")
+            .field("hashes", &self.hashes)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<'a, T: QuadHashItem, S: Clone + BuildHasher, A: Allocator>
+    VacantEntry<'a, T, S, A>
+{
+    /// # Safety
+    ///
+    /// After self is created, the original reference created by
+    /// `DormantMutRef::new` must not be used.
+    pub(super) unsafe fn new(
+        map: DormantMutRef<'a, QuadHashMap<T, S, A>>,
+        hashes: [MapHash; 4],
+    ) -> Self {
+        VacantEntry { map, hashes }
+    }
+
+    /// Sets the entry to a new value, returning a mutable reference to the
+    /// value.
+    ///
+    /// # Panics
+    ///
+    /// Panics if any of the value's keys hash to a different value than the
+    /// corresponding key passed into [`QuadHashMap::entry`].
+    pub fn insert(self, value: T) -> RefMut<'a, T, S> {
+        // SAFETY: The safety assumption behind `Self::new` guarantees that the
+        // original reference to the map is not used at this point.
+        let map = unsafe { self.map.awaken() };
+        check_hashes(&map.tables.state, &self.hashes, &value);
+        let Ok(index) = map.insert_unique_impl(value) else {
+            panic!("key already present in map");
+        };
+        map.get_by_index_mut(index).expect("index is known to be valid")
+    }
+
+    /// Sets the value of the entry, and returns an `OccupiedEntry`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if any of the value's keys hash to a different value than the
+    /// corresponding key passed into [`QuadHashMap::entry`].
+    #[inline]
+    pub fn insert_entry(mut self, value: T) -> OccupiedEntry<'a, T, S, A> {
+        let index = {
+            // SAFETY: The safety assumption behind `Self::new` guarantees that the
+            // original reference to the map is not used at this point.
+            let map = unsafe { self.map.reborrow() };
+            check_hashes(&map.tables.state, &self.hashes, &value);
+            let Ok(index) = map.insert_unique_impl(value) else {
+                panic!("key already present in map");
+            };
+            index
+        };
+
+        // SAFETY: map, as well as anything that was borrowed from it, is
+        // dropped once the above block exits.
+        unsafe { OccupiedEntry::new(self.map, EntryIndexes::Unique(index)) }
+    }
+}
+
+fn check_hashes<T: QuadHashItem, S: BuildHasher>(
+    state: &S,
+    hashes: &[MapHash; 4],
+    value: &T,
+) {
+    if !hashes[0].is_same_hash(state, value.key1()) {
+        panic!("key1 hashes do not match");
+    }
+    if !hashes[1].is_same_hash(state, value.key2()) {
+        panic!("key2 hashes do not match");
+    }
+    if !hashes[2].is_same_hash(state, value.key3()) {
+        panic!("key3 hashes do not match");
+    }
+    if !hashes[3].is_same_hash(state, value.key4()) {
+        panic!("key4 hashes do not match");
+    }
+}
+
+/// A view into an occupied entry in a [`QuadHashMap`]. Part of the [`Entry`]
+/// enum.
+pub struct OccupiedEntry<
+    'a,
+    T: QuadHashItem,
+    S = DefaultHashBuilder,
+    A: Allocator = Global,
+> {
+    map: DormantMutRef<'a, QuadHashMap<T, S, A>>,
+    indexes: EntryIndexes,
+}
+
+impl<'a, T: QuadHashItem, S, A: Allocator> fmt::Debug
+    for OccupiedEntry<'a, T, S, A>
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("OccupiedEntry")
+            .field("indexes", &self.indexes)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<'a, T: QuadHashItem, S: Clone + BuildHasher, A: Allocator>
+    OccupiedEntry<'a, T, S, A>
+{
+    /// # Safety
+    ///
+    /// After self is created, the original reference created by
+    /// `DormantMutRef::new` must not be used.
+    pub(super) unsafe fn new(
+        map: DormantMutRef<'a, QuadHashMap<T, S, A>>,
+        indexes: EntryIndexes,
+    ) -> Self {
+        OccupiedEntry { map, indexes }
+    }
+
+    /// Returns true if the entry is unique.
+    ///
+    /// Since [`QuadHashMap`] is keyed by four keys, it's possible for
+    /// `OccupiedEntry` to match up to four separate items. This function
+    /// returns true if the entry is unique, meaning all keys point to exactly
+    /// one item.
+    pub fn is_unique(&self) -> bool {
+        self.indexes.is_unique()
+    }
+
+    /// Returns true if the `OccupiedEntry` represents more than one item, or if
+    /// some keys are not present.
+    #[inline]
+    pub fn is_non_unique(&self) -> bool {
+        !self.is_unique()
+    }
+
+    /// Returns references to values that match the provided keys.
+    ///
+    /// If you need a reference to `T` that may outlive the destruction of the
+    /// `Entry` value, see [`into_ref`](Self::into_ref).
+    pub fn get(&self) -> OccupiedEntryRef<'_, T> {
+        // SAFETY: The safety assumption behind `Self::new` guarantees that the
+        // original reference to the map is not used at this point.
+        let map = unsafe { self.map.reborrow_shared() };
+        map.get_by_entry_index(self.indexes)
+    }
+
+    /// Returns mutable references to values that match the provided keys.
+    ///
+    /// If you need a reference to `T` that may outlive the destruction of the
+    /// `Entry` value, see [`into_mut`](Self::into_mut).
+    pub fn get_mut(&mut self) -> OccupiedEntryMut<'_, T, S> {
+        // SAFETY: The safety assumption behind `Self::new` guarantees that the
+        // original reference to the map is not used at this point.
+        let map = unsafe { self.map.reborrow() };
+        map.get_by_entry_index_mut(self.indexes)
+    }
+
+    /// Converts self into shared references to items that match the provided
+    /// keys.
+    ///
+    /// If you need multiple references to the `OccupiedEntry`, see
+    /// [`get`](Self::get).
+    pub fn into_ref(self) -> OccupiedEntryRef<'a, T> {
+        // SAFETY: The safety assumption behind `Self::new` guarantees that the
+        // original reference to the map is not used at this point.
+        let map = unsafe { self.map.awaken() };
+        map.get_by_entry_index(self.indexes)
+    }
+
+    /// Converts self into mutable references to items that match the provided
+    /// keys.
+    ///
+    /// If you need multiple references to the `OccupiedEntry`, see
+    /// [`get_mut`](Self::get_mut).
+    pub fn into_mut(self) -> OccupiedEntryMut<'a, T, S> {
+        // SAFETY: The safety assumption behind `Self::new` guarantees that the
+        // original reference to the map is not used at this point.
+        let map = unsafe { self.map.awaken() };
+        map.get_by_entry_index_mut(self.indexes)
+    }
+
+    /// Sets the entry to a new value, returning all values that conflict.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the passed-in key is different from the key of the entry.
+    pub fn insert(&mut self, value: T) -> Vec<T> {
+        // SAFETY: The safety assumption behind `Self::new` guarantees that the
+        // original reference to the map is not used at this point.
+        //
+        // Note that `replace_at_indexes` panics if the keys don't match.
+        let map = unsafe { self.map.reborrow() };
+        let (index, old_items) = map.replace_at_indexes(self.indexes, value);
+        self.indexes = EntryIndexes::Unique(index);
+        old_items
+    }
+
+    /// Takes ownership of the values from the map.
+    pub fn remove(mut self) -> Vec<T> {
+        // SAFETY: The safety assumption behind `Self::new` guarantees that the
+        // original reference to the map is not used at this point.
+        let map = unsafe { self.map.reborrow() };
+        map.remove_by_entry_index(self.indexes)
+    }
+}
+
+/// A view into an occupied entry in a [`QuadHashMap`].
+///
+/// Returned by [`OccupiedEntry::get`].
+#[derive(Debug)]
+pub enum OccupiedEntryRef<'a, T: QuadHashItem> {
+    /// All keys point to the same entry.
+    Unique(&'a T),
+
+    /// The keys point to different entries, or some keys are not present.
+    ///
+    /// At least one of `by_key1`, `by_key2`, `by_key3` and `by_key4` is
+    /// `Some`. Several of them may refer to the same item.
+    NonUnique {
+        /// The value fetched by the first key.
+        by_key1: Option<&'a T>,
+
+        /// The value fetched by the second key.
+        by_key2: Option<&'a T>,
+
+        /// The value fetched by the third key.
+        by_key3: Option<&'a T>,
+
+        /// The value fetched by the fourth key.
+        by_key4: Option<&'a T>,
+    },
+}
+
+impl<'a, T: QuadHashItem> OccupiedEntryRef<'a, T> {
+    /// Returns true if the entry is unique.
+    ///
+    /// Since [`QuadHashMap`] is keyed by four keys, it's possible for
+    /// `OccupiedEntry` to match up to four separate items. This function
+    /// returns true if the entry is unique, meaning all keys point to exactly
+    /// one item.
+    #[inline]
+    pub fn is_unique(&self) -> bool {
+        matches!(self, Self::Unique(_))
+    }
+
+    /// Returns true if the `OccupiedEntryRef` represents more than one item, or
+    /// if some keys are not present.
+    #[inline]
+    pub fn is_non_unique(&self) -> bool {
+        matches!(self, Self::NonUnique { .. })
+    }
+
+    /// Returns a reference to the value if it is unique.
+    #[inline]
+    pub fn as_unique(&self) -> Option<&'a T> {
+        match self {
+            Self::Unique(v) => Some(v),
+            Self::NonUnique { .. } => None,
+        }
+    }
+
+    /// Returns a reference to the value fetched by the first key.
+    #[inline]
+    pub fn by_key1(&self) -> Option<&'a T> {
+        match self {
+            Self::Unique(v) => Some(v),
+            Self::NonUnique { by_key1, .. } => *by_key1,
+        }
+    }
+
+    /// Returns a reference to the value fetched by the second key.
+    #[inline]
+    pub fn by_key2(&self) -> Option<&'a T> {
+        match self {
+            Self::Unique(v) => Some(v),
+            Self::NonUnique { by_key2, .. } => *by_key2,
+        }
+    }
+
+    /// Returns a reference to the value fetched by the third key.
+    #[inline]
+    pub fn by_key3(&self) -> Option<&'a T> {
+        match self {
+            Self::Unique(v) => Some(v),
+            Self::NonUnique { by_key3, .. } => *by_key3,
+        }
+    }
+
+    /// Returns a reference to the value fetched by the fourth key.
+    #[inline]
+    pub fn by_key4(&self) -> Option<&'a T> {
+        match self {
+            Self::Unique(v) => Some(v),
+            Self::NonUnique { by_key4, .. } => *by_key4,
+        }
+    }
+}
+
+/// A mutable view into an occupied entry in a [`QuadHashMap`].
+///
+/// Returned by [`OccupiedEntry::get_mut`].
+pub enum OccupiedEntryMut<
+    'a,
+    T: QuadHashItem,
+    S: Clone + BuildHasher = DefaultHashBuilder,
+> {
+    /// All keys point to the same entry.
+    Unique(RefMut<'a, T, S>),
+
+    /// The keys point to different entries, or some keys are not present.
+    ///
+    /// Each item is borrowed at most once: if several keys point to the same
+    /// item, only the first of them is `Some`.
+    NonUnique {
+        /// The value fetched by the first key.
+        by_key1: Option<RefMut<'a, T, S>>,
+
+        /// The value fetched by the second key, if it differs from the value
+        /// fetched by the first key.
+        by_key2: Option<RefMut<'a, T, S>>,
+
+        /// The value fetched by the third key, if it differs from the values
+        /// fetched by the first and second keys.
+        by_key3: Option<RefMut<'a, T, S>>,
+
+        /// The value fetched by the fourth key, if it differs from the values
+        /// fetched by the first three keys.
+        by_key4: Option<RefMut<'a, T, S>>,
+    },
+}
+
+impl<'a, T: QuadHashItem + fmt::Debug, S: Clone + BuildHasher> fmt::Debug
+    for OccupiedEntryMut<'a, T, S>
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OccupiedEntryMut::Unique(ref_mut) => {
+                f.debug_tuple("Unique").field(ref_mut).finish()
+            }
+            OccupiedEntryMut::NonUnique {
+                by_key1,
+                by_key2,
+                by_key3,
+                by_key4,
+            } => f
+                .debug_struct("NonUnique")
+                .field("by_key1", by_key1)
+                .field("by_key2", by_key2)
+                .field("by_key3", by_key3)
+                .field("by_key4", by_key4)
+                .finish(),
+        }
+    }
+}
+
+impl<'a, T: QuadHashItem, S: Clone + BuildHasher> OccupiedEntryMut<'a, T, S> {
+    /// Returns true if the entry is unique.
+    #[inline]
+    pub fn is_unique(&self) -> bool {
+        matches!(self, Self::Unique(_))
+    }
+
+    /// Returns true if the `OccupiedEntryMut` represents more than one item, or
+    /// if some keys are not present.
+    #[inline]
+    pub fn is_non_unique(&self) -> bool {
+        matches!(self, Self::NonUnique { .. })
+    }
+
+    /// Returns a reference to the value if it is unique.
+    #[inline]
+    pub fn as_unique(&mut self) -> Option<RefMut<'_, T, S>> {
+        match self {
+            Self::Unique(v) => Some(v.reborrow()),
+            Self::NonUnique { .. } => None,
+        }
+    }
+
+    /// Returns a mutable reference to the value fetched by the first key.
+    #[inline]
+    pub fn by_key1(&mut self) -> Option<RefMut<'_, T, S>> {
+        match self {
+            Self::Unique(v) => Some(v.reborrow()),
+            Self::NonUnique { by_key1, .. } => {
+                by_key1.as_mut().map(|v| v.reborrow())
+            }
+        }
+    }
+
+    /// Returns a mutable reference to the value fetched by the second key.
+    ///
+    /// For a non-unique entry, returns `None` if the value is also fetched by
+    /// the first key.
+    #[inline]
+    pub fn by_key2(&mut self) -> Option<RefMut<'_, T, S>> {
+        match self {
+            Self::Unique(v) => Some(v.reborrow()),
+            Self::NonUnique { by_key2, .. } => {
+                by_key2.as_mut().map(|v| v.reborrow())
+            }
+        }
+    }
+
+    /// Returns a mutable reference to the value fetched by the third key.
+    ///
+    /// For a non-unique entry, returns `None` if the value is also fetched by
+    /// the first or second key.
+    #[inline]
+    pub fn by_key3(&mut self) -> Option<RefMut<'_, T, S>> {
+        match self {
+            Self::Unique(v) => Some(v.reborrow()),
+            Self::NonUnique { by_key3, .. } => {
+                by_key3.as_mut().map(|v| v.reborrow())
+            }
+        }
+    }
+
+    /// Returns a mutable reference to the value fetched by the fourth key.
+    ///
+    /// For a non-unique entry, returns `None` if the value is also fetched by
+    /// any of the first three keys.
+    #[inline]
+    pub fn by_key4(&mut self) -> Option<RefMut<'_, T, S>> {
+        match self {
+            Self::Unique(v) => Some(v.reborrow()),
+            Self::NonUnique { by_key4, .. } => {
+                by_key4.as_mut().map(|v| v.reborrow())
+            }
+        }
+    }
+
+    /// Calls a callback for each distinct value.
+    pub fn for_each<F>(&mut self, mut f: F)
+    where
+        F: FnMut(RefMut<'_, T, S>),
+    {
+        match self {
+            Self::Unique(v) => f(v.reborrow()),
+            Self::NonUnique { by_key1, by_key2, by_key3, by_key4 } => {
+                for v in
+                    [by_key1, by_key2, by_key3, by_key4].into_iter().flatten()
+                {
+                    f(v.reborrow());
+                }
+            }
+        }
+    }
+}
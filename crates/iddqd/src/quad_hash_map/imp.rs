@@ -0,0 +1,4527 @@
+use super::{
+    IntoIter, Iter, IterMut, RefMut,
+    entry::{
+        Entry, OccupiedEntry, OccupiedEntryMut, OccupiedEntryRef, VacantEntry,
+    },
+    entry_indexes::EntryIndexes,
+    tables::QuadHashMapTables,
+};
+use crate::{
+    CapacityBreakdown, DefaultHashBuilder, ExtendMode, QuadHashItem,
+    errors::{DuplicateItem, MapIntegrityError, TryReserveError},
+    internal::ValidationError,
+    support::{
+        ItemIndex,
+        alloc::{Allocator, Global, global_alloc},
+        borrow::DormantMutRef,
+        fmt_utils::StrDisplayAsDebug,
+        hash_table::detect_dup_or_insert,
+        item_set::ItemSet,
+        map_hash::MapHash,
+    },
+};
+use alloc::{collections::BTreeSet, vec::Vec};
+use core::{
+    fmt,
+    hash::{BuildHasher, Hash, Hasher},
+};
+use equivalent::Equivalent;
+
+#[derive(Debug)]
+#[must_use]
+struct PreparedDuplicate {
+    index: ItemIndex,
+    hashes: [MapHash; 4],
+}
+
+impl PreparedDuplicate {
+    fn from_indexes<const N: usize>(
+        indexes: [Option<ItemIndex>; N],
+        mut prepare: impl FnMut(ItemIndex) -> Self,
+    ) -> Vec<Self> {
+        let mut duplicates = Vec::new();
+
+        for index in indexes.into_iter().flatten() {
+            if duplicates
+                .iter()
+                .any(|duplicate: &PreparedDuplicate| duplicate.index == index)
+            {
+                continue;
+            }
+
+            duplicates.push(prepare(index));
+        }
+
+        duplicates
+    }
+}
+
+#[derive(Debug)]
+#[must_use]
+struct PreparedInsertOverwrite {
+    index1: Option<ItemIndex>,
+    index2: Option<ItemIndex>,
+    index3: Option<ItemIndex>,
+    index4: Option<ItemIndex>,
+    duplicates: Vec<PreparedDuplicate>,
+    hashes: [MapHash; 4],
+}
+
+impl PreparedInsertOverwrite {
+    #[inline]
+    fn duplicate_count(&self) -> usize {
+        self.duplicates.len()
+    }
+
+    #[inline]
+    fn needs_new_item_slot(&self) -> bool {
+        self.duplicates.is_empty()
+    }
+}
+
+/// A 1:1:1:1 map for four keys and a value.
+///
+/// The storage mechanism is a list of items with an embedded free chain, with
+/// indexes to occupied slots stored in four hash tables. This allows for
+/// efficient lookups by any of the four keys, while preventing duplicates.
+///
+/// `QuadHashMap` follows the same design as [`TriHashMap`], with one more key.
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(feature = "default-hasher")] {
+/// use iddqd::{QuadHashItem, QuadHashMap, quad_upcast};
+///
+/// #[derive(Debug, PartialEq, Eq)]
+/// struct Record {
+///     uuid: u128,
+///     name: String,
+///     serial: String,
+///     slot: u8,
+/// }
+///
+/// // Implement QuadHashItem to define the four key types.
+/// impl QuadHashItem for Record {
+///     type K1<'a> = u128;
+///     type K2<'a> = &'a str;
+///     type K3<'a> = &'a str;
+///     type K4<'a> = u8;
+///
+///     fn key1(&self) -> Self::K1<'_> {
+///         self.uuid
+///     }
+///
+///     fn key2(&self) -> Self::K2<'_> {
+///         &self.name
+///     }
+///
+///     fn key3(&self) -> Self::K3<'_> {
+///         &self.serial
+///     }
+///
+///     fn key4(&self) -> Self::K4<'_> {
+///         self.slot
+///     }
+///
+///     quad_upcast!();
+/// }
+///
+/// // Create a QuadHashMap and insert items.
+/// let mut records = QuadHashMap::new();
+/// records
+///     .insert_unique(Record {
+///         uuid: 1,
+///         name: "sled-a".to_string(),
+///         serial: "SN-0001".to_string(),
+///         slot: 0,
+///     })
+///     .unwrap();
+///
+/// // Lookup by any of the four keys.
+/// assert_eq!(records.get1(&1).unwrap().name, "sled-a");
+/// assert_eq!(records.get2("sled-a").unwrap().uuid, 1);
+/// assert_eq!(records.get3("SN-0001").unwrap().slot, 0);
+/// assert_eq!(records.get4(&0).unwrap().serial, "SN-0001");
+/// # }
+/// ```
+///
+/// [`TriHashMap`]: crate::TriHashMap
+#[derive(Clone)]
+pub struct QuadHashMap<T, S = DefaultHashBuilder, A: Allocator = Global> {
+    pub(super) items: ItemSet<T, A>,
+    // Invariant: the values (ItemIndex) in these tables are valid indexes into
+    // `items`, and are a 1:1 mapping.
+    pub(super) tables: QuadHashMapTables<S, A>,
+}
+
+impl<T: QuadHashItem, S: Default, A: Allocator + Default> Default
+    for QuadHashMap<T, S, A>
+{
+    fn default() -> Self {
+        Self {
+            items: ItemSet::with_capacity_in(0, A::default()),
+            tables: QuadHashMapTables::default(),
+        }
+    }
+}
+
+#[cfg(feature = "default-hasher")]
+impl<T: QuadHashItem> QuadHashMap<T> {
+    /// Creates a new, empty `QuadHashMap`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "default-hasher")] {
+    /// use iddqd::{QuadHashItem, QuadHashMap, quad_upcast};
+    ///
+    /// #[derive(Debug, PartialEq, Eq)]
+    /// struct Record {
+    ///     uuid: u128,
+    ///     name: String,
+    ///     serial: String,
+    ///     slot: u8,
+    /// }
+    ///
+    /// impl QuadHashItem for Record {
+    ///     type K1<'a> = u128;
+    ///     type K2<'a> = &'a str;
+    ///     type K3<'a> = &'a str;
+    ///     type K4<'a> = u8;
+    ///
+    ///     fn key1(&self) -> Self::K1<'_> {
+    ///         self.uuid
+    ///     }
+    ///     fn key2(&self) -> Self::K2<'_> {
+    ///         &self.name
+    ///     }
+    ///     fn key3(&self) -> Self::K3<'_> {
+    ///         &self.serial
+    ///     }
+    ///     fn key4(&self) -> Self::K4<'_> {
+    ///         self.slot
+    ///     }
+    ///     quad_upcast!();
+    /// }
+    ///
+    /// fn record(uuid: u128, name: &str, serial: &str, slot: u8) -> Record {
+    ///     Record {
+    ///         uuid,
+    ///         name: name.to_string(),
+    ///         serial: serial.to_string(),
+    ///         slot,
+    ///     }
+    /// }
+    ///
+    /// let map: QuadHashMap<Record> = QuadHashMap::new();
+    /// assert!(map.is_empty());
+    /// assert_eq!(map.len(), 0);
+    /// # }
+    /// ```
+    #[inline]
+    pub fn new() -> Self {
+        Self { items: ItemSet::new(), tables: QuadHashMapTables::default() }
+    }
+
+    /// Creates a new `QuadHashMap` with the given capacity.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "default-hasher")] {
+    /// use iddqd::{QuadHashItem, QuadHashMap, quad_upcast};
+    ///
+    /// #[derive(Debug, PartialEq, Eq)]
+    /// struct Record {
+    ///     uuid: u128,
+    ///     name: String,
+    ///     serial: String,
+    ///     slot: u8,
+    /// }
+    ///
+    /// impl QuadHashItem for Record {
+    ///     type K1<'a> = u128;
+    ///     type K2<'a> = &'a str;
+    ///     type K3<'a> = &'a str;
+    ///     type K4<'a> = u8;
+    ///
+    ///     fn key1(&self) -> Self::K1<'_> {
+    ///         self.uuid
+    ///     }
+    ///     fn key2(&self) -> Self::K2<'_> {
+    ///         &self.name
+    ///     }
+    ///     fn key3(&self) -> Self::K3<'_> {
+    ///         &self.serial
+    ///     }
+    ///     fn key4(&self) -> Self::K4<'_> {
+    ///         self.slot
+    ///     }
+    ///     quad_upcast!();
+    /// }
+    ///
+    /// fn record(uuid: u128, name: &str, serial: &str, slot: u8) -> Record {
+    ///     Record {
+    ///         uuid,
+    ///         name: name.to_string(),
+    ///         serial: serial.to_string(),
+    ///         slot,
+    ///     }
+    /// }
+    ///
+    /// let map: QuadHashMap<Record> = QuadHashMap::with_capacity(10);
+    /// assert!(map.capacity() >= 10);
+    /// assert!(map.is_empty());
+    /// # }
+    /// ```
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            items: ItemSet::with_capacity_in(capacity, global_alloc()),
+            tables: QuadHashMapTables::with_capacity_and_hasher_in(
+                capacity,
+                DefaultHashBuilder::default(),
+                global_alloc(),
+            ),
+        }
+    }
+
+    /// Creates a new `QuadHashMap` with the given capacity, returning an error if
+    /// allocation fails.
+    ///
+    /// This is a fallible version of [`QuadHashMap::with_capacity`].
+    ///
+    /// # Errors
+    ///
+    /// If the capacity overflows, or the allocator reports a failure, then an
+    /// error is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "default-hasher")] {
+    /// use iddqd::{QuadHashItem, QuadHashMap, quad_upcast};
+    ///
+    /// #[derive(Debug, PartialEq, Eq)]
+    /// struct Record {
+    ///     uuid: u128,
+    ///     name: String,
+    ///     serial: String,
+    ///     slot: u8,
+    /// }
+    ///
+    /// impl QuadHashItem for Record {
+    ///     type K1<'a> = u128;
+    ///     type K2<'a> = &'a str;
+    ///     type K3<'a> = &'a str;
+    ///     type K4<'a> = u8;
+    ///
+    ///     fn key1(&self) -> Self::K1<'_> {
+    ///         self.uuid
+    ///     }
+    ///     fn key2(&self) -> Self::K2<'_> {
+    ///         &self.name
+    ///     }
+    ///     fn key3(&self) -> Self::K3<'_> {
+    ///         &self.serial
+    ///     }
+    ///     fn key4(&self) -> Self::K4<'_> {
+    ///         self.slot
+    ///     }
+    ///     quad_upcast!();
+    /// }
+    ///
+    /// fn record(uuid: u128, name: &str, serial: &str, slot: u8) -> Record {
+    ///     Record {
+    ///         uuid,
+    ///         name: name.to_string(),
+    ///         serial: serial.to_string(),
+    ///         slot,
+    ///     }
+    /// }
+    ///
+    /// let map: QuadHashMap<Record> = QuadHashMap::try_with_capacity(10)
+    ///     .expect("allocation should succeed");
+    /// assert!(map.capacity() >= 10);
+    /// assert!(map.is_empty());
+    /// # }
+    /// ```
+    pub fn try_with_capacity(capacity: usize) -> Result<Self, TryReserveError> {
+        Self::try_with_capacity_and_hasher(
+            capacity,
+            DefaultHashBuilder::default(),
+        )
+    }
+}
+
+impl<T: QuadHashItem, S: BuildHasher> QuadHashMap<T, S> {
+    /// Creates a new, empty `QuadHashMap` with the given hasher.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use iddqd::{QuadHashItem, QuadHashMap, quad_upcast};
+    /// use std::collections::hash_map::RandomState;
+    ///
+    /// #[derive(Debug, PartialEq, Eq)]
+    /// struct Record {
+    ///     uuid: u128,
+    ///     name: String,
+    ///     serial: String,
+    ///     slot: u8,
+    /// }
+    ///
+    /// impl QuadHashItem for Record {
+    ///     type K1<'a> = u128;
+    ///     type K2<'a> = &'a str;
+    ///     type K3<'a> = &'a str;
+    ///     type K4<'a> = u8;
+    ///
+    ///     fn key1(&self) -> Self::K1<'_> {
+    ///         self.uuid
+    ///     }
+    ///     fn key2(&self) -> Self::K2<'_> {
+    ///         &self.name
+    ///     }
+    ///     fn key3(&self) -> Self::K3<'_> {
+    ///         &self.serial
+    ///     }
+    ///     fn key4(&self) -> Self::K4<'_> {
+    ///         self.slot
+    ///     }
+    ///     quad_upcast!();
+    /// }
+    ///
+    /// fn record(uuid: u128, name: &str, serial: &str, slot: u8) -> Record {
+    ///     Record {
+    ///         uuid,
+    ///         name: name.to_string(),
+    ///         serial: serial.to_string(),
+    ///         slot,
+    ///     }
+    /// }
+    ///
+    /// let map: QuadHashMap<Record, RandomState> =
+    ///     QuadHashMap::with_hasher(RandomState::new());
+    /// assert!(map.is_empty());
+    /// ```
+    pub const fn with_hasher(hasher: S) -> Self {
+        Self {
+            items: ItemSet::new(),
+            tables: QuadHashMapTables::with_hasher(hasher),
+        }
+    }
+
+    /// Creates a new `QuadHashMap` with the given capacity and hasher.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use iddqd::{QuadHashItem, QuadHashMap, quad_upcast};
+    /// use std::collections::hash_map::RandomState;
+    ///
+    /// #[derive(Debug, PartialEq, Eq)]
+    /// struct Record {
+    ///     uuid: u128,
+    ///     name: String,
+    ///     serial: String,
+    ///     slot: u8,
+    /// }
+    ///
+    /// impl QuadHashItem for Record {
+    ///     type K1<'a> = u128;
+    ///     type K2<'a> = &'a str;
+    ///     type K3<'a> = &'a str;
+    ///     type K4<'a> = u8;
+    ///
+    ///     fn key1(&self) -> Self::K1<'_> {
+    ///         self.uuid
+    ///     }
+    ///     fn key2(&self) -> Self::K2<'_> {
+    ///         &self.name
+    ///     }
+    ///     fn key3(&self) -> Self::K3<'_> {
+    ///         &self.serial
+    ///     }
+    ///     fn key4(&self) -> Self::K4<'_> {
+    ///         self.slot
+    ///     }
+    ///     quad_upcast!();
+    /// }
+    ///
+    /// fn record(uuid: u128, name: &str, serial: &str, slot: u8) -> Record {
+    ///     Record {
+    ///         uuid,
+    ///         name: name.to_string(),
+    ///         serial: serial.to_string(),
+    ///         slot,
+    ///     }
+    /// }
+    ///
+    /// let map: QuadHashMap<Record, RandomState> =
+    ///     QuadHashMap::with_capacity_and_hasher(10, RandomState::new());
+    /// assert!(map.capacity() >= 10);
+    /// assert!(map.is_empty());
+    /// ```
+    pub fn with_capacity_and_hasher(capacity: usize, hasher: S) -> Self {
+        Self {
+            items: ItemSet::with_capacity_in(capacity, global_alloc()),
+            tables: QuadHashMapTables::with_capacity_and_hasher_in(
+                capacity,
+                hasher,
+                global_alloc(),
+            ),
+        }
+    }
+
+    /// Creates a new `QuadHashMap` with the given capacity and hasher,
+    /// returning an error if allocation fails.
+    ///
+    /// This is a fallible version of [`QuadHashMap::with_capacity_and_hasher`].
+    ///
+    /// # Errors
+    ///
+    /// If the capacity overflows, or the allocator reports a failure, then an
+    /// error is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use iddqd::{QuadHashItem, QuadHashMap, quad_upcast};
+    /// use std::collections::hash_map::RandomState;
+    ///
+    /// #[derive(Debug, PartialEq, Eq)]
+    /// struct Record {
+    ///     uuid: u128,
+    ///     name: String,
+    ///     serial: String,
+    ///     slot: u8,
+    /// }
+    ///
+    /// impl QuadHashItem for Record {
+    ///     type K1<'a> = u128;
+    ///     type K2<'a> = &'a str;
+    ///     type K3<'a> = &'a str;
+    ///     type K4<'a> = u8;
+    ///
+    ///     fn key1(&self) -> Self::K1<'_> {
+    ///         self.uuid
+    ///     }
+    ///     fn key2(&self) -> Self::K2<'_> {
+    ///         &self.name
+    ///     }
+    ///     fn key3(&self) -> Self::K3<'_> {
+    ///         &self.serial
+    ///     }
+    ///     fn key4(&self) -> Self::K4<'_> {
+    ///         self.slot
+    ///     }
+    ///     quad_upcast!();
+    /// }
+    ///
+    /// fn record(uuid: u128, name: &str, serial: &str, slot: u8) -> Record {
+    ///     Record {
+    ///         uuid,
+    ///         name: name.to_string(),
+    ///         serial: serial.to_string(),
+    ///         slot,
+    ///     }
+    /// }
+    ///
+    /// let hasher = RandomState::new();
+    /// let map: QuadHashMap<Record, _> =
+    ///     QuadHashMap::try_with_capacity_and_hasher(10, hasher)
+    ///         .expect("allocation should succeed");
+    /// assert!(map.capacity() >= 10);
+    /// assert!(map.is_empty());
+    /// ```
+    pub fn try_with_capacity_and_hasher(
+        capacity: usize,
+        hasher: S,
+    ) -> Result<Self, TryReserveError> {
+        let mut map = Self::with_hasher(hasher);
+        map.items.try_reserve(capacity)?;
+        map.tables.try_reserve(capacity)?;
+        Ok(map)
+    }
+}
+
+#[cfg(feature = "default-hasher")]
+impl<T: QuadHashItem, A: Clone + Allocator>
+    QuadHashMap<T, DefaultHashBuilder, A>
+{
+    /// Creates a new, empty `QuadHashMap` using the given allocator.
+    ///
+    /// Requires the `allocator-api2` feature to be enabled.
+    ///
+    /// # Examples
+    ///
+    /// Using the [`bumpalo`](https://docs.rs/bumpalo) allocator:
+    ///
+    /// ```
+    /// # #[cfg(all(feature = "default-hasher", feature = "allocator-api2"))] {
+    /// use iddqd::{QuadHashItem, QuadHashMap, quad_upcast};
+    /// # use iddqd_test_utils::bumpalo;
+    ///
+    /// #[derive(Debug, PartialEq, Eq)]
+    /// struct Record {
+    ///     uuid: u128,
+    ///     name: String,
+    ///     serial: String,
+    ///     slot: u8,
+    /// }
+    ///
+    /// impl QuadHashItem for Record {
+    ///     type K1<'a> = u128;
+    ///     type K2<'a> = &'a str;
+    ///     type K3<'a> = &'a str;
+    ///     type K4<'a> = u8;
+    ///
+    ///     fn key1(&self) -> Self::K1<'_> {
+    ///         self.uuid
+    ///     }
+    ///     fn key2(&self) -> Self::K2<'_> {
+    ///         &self.name
+    ///     }
+    ///     fn key3(&self) -> Self::K3<'_> {
+    ///         &self.serial
+    ///     }
+    ///     fn key4(&self) -> Self::K4<'_> {
+    ///         self.slot
+    ///     }
+    ///     quad_upcast!();
+    /// }
+    ///
+    /// // Define a new allocator.
+    /// let bump = bumpalo::Bump::new();
+    /// // Create a new QuadHashMap using the allocator.
+    /// let map: QuadHashMap<Record, _, &bumpalo::Bump> =
+    ///     QuadHashMap::new_in(&bump);
+    /// assert!(map.is_empty());
+    /// # }
+    /// ```
+    pub fn new_in(alloc: A) -> Self {
+        Self {
+            items: ItemSet::with_capacity_in(0, alloc.clone()),
+            tables: QuadHashMapTables::with_capacity_and_hasher_in(
+                0,
+                DefaultHashBuilder::default(),
+                alloc,
+            ),
+        }
+    }
+
+    /// Creates an empty `QuadHashMap` with the specified capacity using the
+    /// given allocator.
+    ///
+    /// Requires the `allocator-api2` feature to be enabled.
+    ///
+    /// # Examples
+    ///
+    /// Using the [`bumpalo`](https://docs.rs/bumpalo) allocator:
+    ///
+    /// ```
+    /// # #[cfg(all(feature = "default-hasher", feature = "allocator-api2"))] {
+    /// use iddqd::{QuadHashItem, QuadHashMap, quad_upcast};
+    /// # use iddqd_test_utils::bumpalo;
+    ///
+    /// #[derive(Debug, PartialEq, Eq)]
+    /// struct Record {
+    ///     uuid: u128,
+    ///     name: String,
+    ///     serial: String,
+    ///     slot: u8,
+    /// }
+    ///
+    /// impl QuadHashItem for Record {
+    ///     type K1<'a> = u128;
+    ///     type K2<'a> = &'a str;
+    ///     type K3<'a> = &'a str;
+    ///     type K4<'a> = u8;
+    ///
+    ///     fn key1(&self) -> Self::K1<'_> {
+    ///         self.uuid
+    ///     }
+    ///     fn key2(&self) -> Self::K2<'_> {
+    ///         &self.name
+    ///     }
+    ///     fn key3(&self) -> Self::K3<'_> {
+    ///         &self.serial
+    ///     }
+    ///     fn key4(&self) -> Self::K4<'_> {
+    ///         self.slot
+    ///     }
+    ///     quad_upcast!();
+    /// }
+    ///
+    /// // Define a new allocator.
+    /// let bump = bumpalo::Bump::new();
+    /// // Create a new QuadHashMap with capacity using the allocator.
+    /// let map: QuadHashMap<Record, _, &bumpalo::Bump> =
+    ///     QuadHashMap::with_capacity_in(10, &bump);
+    /// assert!(map.capacity() >= 10);
+    /// assert!(map.is_empty());
+    /// # }
+    /// ```
+    pub fn with_capacity_in(capacity: usize, alloc: A) -> Self {
+        Self {
+            items: ItemSet::with_capacity_in(capacity, alloc.clone()),
+            tables: QuadHashMapTables::with_capacity_and_hasher_in(
+                capacity,
+                DefaultHashBuilder::default(),
+                alloc,
+            ),
+        }
+    }
+
+    /// Creates an empty `QuadHashMap` with the specified capacity using the
+    /// given allocator, returning an error if allocation fails.
+    ///
+    /// This is a fallible version of [`QuadHashMap::with_capacity_in`].
+    ///
+    /// # Errors
+    ///
+    /// If the capacity overflows, or the allocator reports a failure, then an
+    /// error is returned.
+    ///
+    /// # Examples
+    ///
+    /// Using the [`bumpalo`](https://docs.rs/bumpalo) allocator:
+    ///
+    /// ```
+    /// # #[cfg(all(feature = "default-hasher", feature = "allocator-api2"))] {
+    /// use iddqd::{QuadHashItem, QuadHashMap, quad_upcast};
+    /// # use iddqd_test_utils::bumpalo;
+    ///
+    /// #[derive(Debug, PartialEq, Eq)]
+    /// struct Record {
+    ///     uuid: u128,
+    ///     name: String,
+    ///     serial: String,
+    ///     slot: u8,
+    /// }
+    ///
+    /// impl QuadHashItem for Record {
+    ///     type K1<'a> = u128;
+    ///     type K2<'a> = &'a str;
+    ///     type K3<'a> = &'a str;
+    ///     type K4<'a> = u8;
+    ///
+    ///     fn key1(&self) -> Self::K1<'_> {
+    ///         self.uuid
+    ///     }
+    ///     fn key2(&self) -> Self::K2<'_> {
+    ///         &self.name
+    ///     }
+    ///     fn key3(&self) -> Self::K3<'_> {
+    ///         &self.serial
+    ///     }
+    ///     fn key4(&self) -> Self::K4<'_> {
+    ///         self.slot
+    ///     }
+    ///     quad_upcast!();
+    /// }
+    ///
+    /// fn record(uuid: u128, name: &str, serial: &str, slot: u8) -> Record {
+    ///     Record {
+    ///         uuid,
+    ///         name: name.to_string(),
+    ///         serial: serial.to_string(),
+    ///         slot,
+    ///     }
+    /// }
+    ///
+    /// let bump = bumpalo::Bump::new();
+    /// let map: QuadHashMap<Record, _, &bumpalo::Bump> =
+    ///     QuadHashMap::try_with_capacity_in(10, &bump)
+    ///         .expect("allocation should succeed");
+    /// assert!(map.capacity() >= 10);
+    /// assert!(map.is_empty());
+    /// # }
+    /// ```
+    pub fn try_with_capacity_in(
+        capacity: usize,
+        alloc: A,
+    ) -> Result<Self, TryReserveError> {
+        Self::try_with_capacity_and_hasher_in(
+            capacity,
+            DefaultHashBuilder::default(),
+            alloc,
+        )
+    }
+}
+
+impl<T: QuadHashItem, S: Clone + BuildHasher, A: Clone + Allocator>
+    QuadHashMap<T, S, A>
+{
+    /// Creates a new, empty `QuadHashMap` with the given hasher and allocator.
+    ///
+    /// Requires the `allocator-api2` feature to be enabled.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "allocator-api2")] {
+    /// use iddqd::{QuadHashItem, QuadHashMap, quad_upcast};
+    /// use std::collections::hash_map::RandomState;
+    /// # use iddqd_test_utils::bumpalo;
+    ///
+    /// #[derive(Debug, PartialEq, Eq)]
+    /// struct Record {
+    ///     uuid: u128,
+    ///     name: String,
+    ///     serial: String,
+    ///     slot: u8,
+    /// }
+    ///
+    /// impl QuadHashItem for Record {
+    ///     type K1<'a> = u128;
+    ///     type K2<'a> = &'a str;
+    ///     type K3<'a> = &'a str;
+    ///     type K4<'a> = u8;
+    ///
+    ///     fn key1(&self) -> Self::K1<'_> {
+    ///         self.uuid
+    ///     }
+    ///     fn key2(&self) -> Self::K2<'_> {
+    ///         &self.name
+    ///     }
+    ///     fn key3(&self) -> Self::K3<'_> {
+    ///         &self.serial
+    ///     }
+    ///     fn key4(&self) -> Self::K4<'_> {
+    ///         self.slot
+    ///     }
+    ///     quad_upcast!();
+    /// }
+    ///
+    /// fn record(uuid: u128, name: &str, serial: &str, slot: u8) -> Record {
+    ///     Record {
+    ///         uuid,
+    ///         name: name.to_string(),
+    ///         serial: serial.to_string(),
+    ///         slot,
+    ///     }
+    /// }
+    ///
+    /// // Define a new allocator.
+    /// let bump = bumpalo::Bump::new();
+    /// let hasher = RandomState::new();
+    /// // Create a new QuadHashMap with hasher using the allocator.
+    /// let map: QuadHashMap<Record, _, &bumpalo::Bump> =
+    ///     QuadHashMap::with_hasher_in(hasher, &bump);
+    /// assert!(map.is_empty());
+    /// # }
+    /// ```
+    pub fn with_hasher_in(hasher: S, alloc: A) -> Self {
+        Self {
+            items: ItemSet::with_capacity_in(0, alloc.clone()),
+            tables: QuadHashMapTables::with_capacity_and_hasher_in(
+                0, hasher, alloc,
+            ),
+        }
+    }
+
+    /// Creates a new, empty `QuadHashMap` with the given capacity, hasher, and
+    /// allocator.
+    ///
+    /// Requires the `allocator-api2` feature to be enabled.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "allocator-api2")] {
+    /// use iddqd::{QuadHashItem, QuadHashMap, quad_upcast};
+    /// use std::collections::hash_map::RandomState;
+    /// # use iddqd_test_utils::bumpalo;
+    ///
+    /// #[derive(Debug, PartialEq, Eq)]
+    /// struct Record {
+    ///     uuid: u128,
+    ///     name: String,
+    ///     serial: String,
+    ///     slot: u8,
+    /// }
+    ///
+    /// impl QuadHashItem for Record {
+    ///     type K1<'a> = u128;
+    ///     type K2<'a> = &'a str;
+    ///     type K3<'a> = &'a str;
+    ///     type K4<'a> = u8;
+    ///
+    ///     fn key1(&self) -> Self::K1<'_> {
+    ///         self.uuid
+    ///     }
+    ///     fn key2(&self) -> Self::K2<'_> {
+    ///         &self.name
+    ///     }
+    ///     fn key3(&self) -> Self::K3<'_> {
+    ///         &self.serial
+    ///     }
+    ///     fn key4(&self) -> Self::K4<'_> {
+    ///         self.slot
+    ///     }
+    ///     quad_upcast!();
+    /// }
+    ///
+    /// fn record(uuid: u128, name: &str, serial: &str, slot: u8) -> Record {
+    ///     Record {
+    ///         uuid,
+    ///         name: name.to_string(),
+    ///         serial: serial.to_string(),
+    ///         slot,
+    ///     }
+    /// }
+    ///
+    /// // Define a new allocator.
+    /// let bump = bumpalo::Bump::new();
+    /// let hasher = RandomState::new();
+    /// // Create a new QuadHashMap with capacity and hasher using the allocator.
+    /// let map: QuadHashMap<Record, _, &bumpalo::Bump> =
+    ///     QuadHashMap::with_capacity_and_hasher_in(10, hasher, &bump);
+    /// assert!(map.capacity() >= 10);
+    /// assert!(map.is_empty());
+    /// # }
+    /// ```
+    pub fn with_capacity_and_hasher_in(
+        capacity: usize,
+        hasher: S,
+        alloc: A,
+    ) -> Self {
+        Self {
+            items: ItemSet::with_capacity_in(capacity, alloc.clone()),
+            tables: QuadHashMapTables::with_capacity_and_hasher_in(
+                capacity, hasher, alloc,
+            ),
+        }
+    }
+
+    /// Creates a new, empty `QuadHashMap` with the given capacity, hasher, and
+    /// allocator, returning an error if allocation fails.
+    ///
+    /// This is a fallible version of
+    /// [`QuadHashMap::with_capacity_and_hasher_in`].
+    ///
+    /// # Errors
+    ///
+    /// If the capacity overflows, or the allocator reports a failure, then an
+    /// error is returned.
+    ///
+    /// # Examples
+    ///
+    /// Using the [`bumpalo`](https://docs.rs/bumpalo) allocator:
+    ///
+    /// ```
+    /// # #[cfg(feature = "allocator-api2")] {
+    /// use iddqd::{QuadHashItem, QuadHashMap, quad_upcast};
+    /// use std::collections::hash_map::RandomState;
+    /// # use iddqd_test_utils::bumpalo;
+    ///
+    /// #[derive(Debug, PartialEq, Eq)]
+    /// struct Record {
+    ///     uuid: u128,
+    ///     name: String,
+    ///     serial: String,
+    ///     slot: u8,
+    /// }
+    ///
+    /// impl QuadHashItem for Record {
+    ///     type K1<'a> = u128;
+    ///     type K2<'a> = &'a str;
+    ///     type K3<'a> = &'a str;
+    ///     type K4<'a> = u8;
+    ///
+    ///     fn key1(&self) -> Self::K1<'_> {
+    ///         self.uuid
+    ///     }
+    ///     fn key2(&self) -> Self::K2<'_> {
+    ///         &self.name
+    ///     }
+    ///     fn key3(&self) -> Self::K3<'_> {
+    ///         &self.serial
+    ///     }
+    ///     fn key4(&self) -> Self::K4<'_> {
+    ///         self.slot
+    ///     }
+    ///     quad_upcast!();
+    /// }
+    ///
+    /// fn record(uuid: u128, name: &str, serial: &str, slot: u8) -> Record {
+    ///     Record {
+    ///         uuid,
+    ///         name: name.to_string(),
+    ///         serial: serial.to_string(),
+    ///         slot,
+    ///     }
+    /// }
+    ///
+    /// let bump = bumpalo::Bump::new();
+    /// let hasher = RandomState::new();
+    /// let map: QuadHashMap<Record, _, &bumpalo::Bump> =
+    ///     QuadHashMap::try_with_capacity_and_hasher_in(10, hasher, &bump)
+    ///         .expect("allocation should succeed");
+    /// assert!(map.capacity() >= 10);
+    /// assert!(map.is_empty());
+    /// # }
+    /// ```
+    pub fn try_with_capacity_and_hasher_in(
+        capacity: usize,
+        hasher: S,
+        alloc: A,
+    ) -> Result<Self, TryReserveError> {
+        let mut map = Self::with_hasher_in(hasher, alloc);
+        map.items.try_reserve(capacity)?;
+        map.tables.try_reserve(capacity)?;
+        Ok(map)
+    }
+}
+
+impl<T: QuadHashItem, S: Default + Clone + BuildHasher, A: Allocator + Default>
+    QuadHashMap<T, S, A>
+{
+    /// Creates a new `QuadHashMap` from an iterator of values, rejecting
+    /// duplicates.
+    ///
+    /// A value conflicts when any of its keys matches an already-inserted
+    /// item, so a single value can collide with up to four distinct existing
+    /// items (one per key). On the first conflict, this returns a
+    /// [`DuplicateItem`] error containing the new value and every conflicting
+    /// item.
+    ///
+    /// To overwrite duplicates instead, use [`QuadHashMap::from_iter`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "default-hasher")] {
+    /// use iddqd::{QuadHashItem, QuadHashMap, quad_upcast};
+    ///
+    /// #[derive(Debug, PartialEq, Eq)]
+    /// struct Record {
+    ///     uuid: u128,
+    ///     name: String,
+    ///     serial: String,
+    ///     slot: u8,
+    /// }
+    ///
+    /// impl QuadHashItem for Record {
+    ///     type K1<'a> = u128;
+    ///     type K2<'a> = &'a str;
+    ///     type K3<'a> = &'a str;
+    ///     type K4<'a> = u8;
+    ///
+    ///     fn key1(&self) -> Self::K1<'_> {
+    ///         self.uuid
+    ///     }
+    ///     fn key2(&self) -> Self::K2<'_> {
+    ///         &self.name
+    ///     }
+    ///     fn key3(&self) -> Self::K3<'_> {
+    ///         &self.serial
+    ///     }
+    ///     fn key4(&self) -> Self::K4<'_> {
+    ///         self.slot
+    ///     }
+    ///     quad_upcast!();
+    /// }
+    ///
+    /// fn record(uuid: u128, name: &str, serial: &str, slot: u8) -> Record {
+    ///     Record {
+    ///         uuid,
+    ///         name: name.to_string(),
+    ///         serial: serial.to_string(),
+    ///         slot,
+    ///     }
+    /// }
+    ///
+    /// // Successful creation with unique keys.
+    /// let items = vec![
+    ///     record(1, "sled-a", "SN-0001", 0),
+    ///     record(2, "sled-b", "SN-0002", 1),
+    /// ];
+    /// let map: QuadHashMap<Record> = QuadHashMap::from_iter_unique(items).unwrap();
+    /// assert_eq!(map.len(), 2);
+    /// assert_eq!(map.get1(&1).unwrap().name, "sled-a");
+    ///
+    /// // Error with a duplicate key4.
+    /// let duplicate_items = vec![
+    ///     record(1, "sled-a", "SN-0001", 0),
+    ///     record(2, "sled-b", "SN-0002", 0),
+    /// ];
+    /// let err =
+    ///     QuadHashMap::<Record>::from_iter_unique(duplicate_items).unwrap_err();
+    /// assert_eq!(err.new_item().uuid, 2);
+    /// assert_eq!(err.duplicates().len(), 1);
+    /// assert_eq!(err.duplicates()[0].uuid, 1);
+    /// # }
+    /// ```
+    pub fn from_iter_unique<I: IntoIterator<Item = T>>(
+        iter: I,
+    ) -> Result<Self, DuplicateItem<T>> {
+        let iter = iter.into_iter();
+        let mut map = Self::default();
+        map.reserve(iter.size_hint().0);
+        for value in iter {
+            if let Err((value, indexes)) =
+                map.insert_unique_or_dup_indexes(value)
+            {
+                // Removal produces owned duplicates, so that we don't need to
+                // specify `T: Clone` here.
+                let duplicates = indexes
+                    .iter()
+                    .map(|ix| {
+                        map.remove_by_index(*ix)
+                            .expect("duplicate index is present")
+                    })
+                    .collect();
+                return Err(DuplicateItem::__internal_new(value, duplicates));
+            }
+        }
+
+        Ok(map)
+    }
+}
+
+impl<T: QuadHashItem, S: Clone + BuildHasher, A: Allocator>
+    QuadHashMap<T, S, A>
+{
+    /// Returns the allocator.
+    #[inline]
+    pub fn allocator(&self) -> &A {
+        self.items.allocator()
+    }
+
+    /// Returns the currently allocated capacity of the map.
+    pub fn capacity(&self) -> usize {
+        // items and tables.capacity might theoretically diverge: use
+        // items.capacity.
+        self.items.capacity()
+    }
+
+    /// Returns a breakdown of the map's internal sizing, for debugging.
+    ///
+    /// This reports the length and capacity of the item storage, and the
+    /// capacity of each key table. It doesn't visit any items, so it's much
+    /// cheaper than [`Self::validate`].
+    pub fn debug_capacity_breakdown(&self) -> CapacityBreakdown {
+        CapacityBreakdown {
+            items_len: self.items.len(),
+            items_capacity: self.items.capacity(),
+            table_capacities: vec![
+                self.tables.k1_to_item.capacity(),
+                self.tables.k2_to_item.capacity(),
+                self.tables.k3_to_item.capacity(),
+                self.tables.k4_to_item.capacity(),
+            ],
+        }
+    }
+
+    /// Returns true if the map is empty.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "default-hasher")] {
+    /// use iddqd::{QuadHashItem, QuadHashMap, quad_upcast};
+    ///
+    /// #[derive(Debug, PartialEq, Eq)]
+    /// struct Record {
+    ///     uuid: u128,
+    ///     name: String,
+    ///     serial: String,
+    ///     slot: u8,
+    /// }
+    ///
+    /// impl QuadHashItem for Record {
+    ///     type K1<'a> = u128;
+    ///     type K2<'a> = &'a str;
+    ///     type K3<'a> = &'a str;
+    ///     type K4<'a> = u8;
+    ///
+    ///     fn key1(&self) -> Self::K1<'_> {
+    ///         self.uuid
+    ///     }
+    ///     fn key2(&self) -> Self::K2<'_> {
+    ///         &self.name
+    ///     }
+    ///     fn key3(&self) -> Self::K3<'_> {
+    ///         &self.serial
+    ///     }
+    ///     fn key4(&self) -> Self::K4<'_> {
+    ///         self.slot
+    ///     }
+    ///     quad_upcast!();
+    /// }
+    ///
+    /// fn record(uuid: u128, name: &str, serial: &str, slot: u8) -> Record {
+    ///     Record {
+    ///         uuid,
+    ///         name: name.to_string(),
+    ///         serial: serial.to_string(),
+    ///         slot,
+    ///     }
+    /// }
+    ///
+    /// let mut map = QuadHashMap::new();
+    /// assert!(map.is_empty());
+    ///
+    /// map.insert_unique(record(1, "sled-a", "SN-0001", 0)).unwrap();
+    /// assert!(!map.is_empty());
+    /// # }
+    /// ```
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// Returns the number of items in the map.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "default-hasher")] {
+    /// use iddqd::{QuadHashItem, QuadHashMap, quad_upcast};
+    ///
+    /// #[derive(Debug, PartialEq, Eq)]
+    /// struct Record {
+    ///     uuid: u128,
+    ///     name: String,
+    ///     serial: String,
+    ///     slot: u8,
+    /// }
+    ///
+    /// impl QuadHashItem for Record {
+    ///     type K1<'a> = u128;
+    ///     type K2<'a> = &'a str;
+    ///     type K3<'a> = &'a str;
+    ///     type K4<'a> = u8;
+    ///
+    ///     fn key1(&self) -> Self::K1<'_> {
+    ///         self.uuid
+    ///     }
+    ///     fn key2(&self) -> Self::K2<'_> {
+    ///         &self.name
+    ///     }
+    ///     fn key3(&self) -> Self::K3<'_> {
+    ///         &self.serial
+    ///     }
+    ///     fn key4(&self) -> Self::K4<'_> {
+    ///         self.slot
+    ///     }
+    ///     quad_upcast!();
+    /// }
+    ///
+    /// fn record(uuid: u128, name: &str, serial: &str, slot: u8) -> Record {
+    ///     Record {
+    ///         uuid,
+    ///         name: name.to_string(),
+    ///         serial: serial.to_string(),
+    ///         slot,
+    ///     }
+    /// }
+    ///
+    /// let mut map = QuadHashMap::new();
+    /// assert_eq!(map.len(), 0);
+    ///
+    /// map.insert_unique(record(1, "sled-a", "SN-0001", 0)).unwrap();
+    /// map.insert_unique(record(2, "sled-b", "SN-0002", 1)).unwrap();
+    /// assert_eq!(map.len(), 2);
+    /// # }
+    /// ```
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    /// Clears the map, removing all items.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "default-hasher")] {
+    /// use iddqd::{QuadHashItem, QuadHashMap, quad_upcast};
+    ///
+    /// #[derive(Debug, PartialEq, Eq)]
+    /// struct Record {
+    ///     uuid: u128,
+    ///     name: String,
+    ///     serial: String,
+    ///     slot: u8,
+    /// }
+    ///
+    /// impl QuadHashItem for Record {
+    ///     type K1<'a> = u128;
+    ///     type K2<'a> = &'a str;
+    ///     type K3<'a> = &'a str;
+    ///     type K4<'a> = u8;
+    ///
+    ///     fn key1(&self) -> Self::K1<'_> {
+    ///         self.uuid
+    ///     }
+    ///     fn key2(&self) -> Self::K2<'_> {
+    ///         &self.name
+    ///     }
+    ///     fn key3(&self) -> Self::K3<'_> {
+    ///         &self.serial
+    ///     }
+    ///     fn key4(&self) -> Self::K4<'_> {
+    ///         self.slot
+    ///     }
+    ///     quad_upcast!();
+    /// }
+    ///
+    /// fn record(uuid: u128, name: &str, serial: &str, slot: u8) -> Record {
+    ///     Record {
+    ///         uuid,
+    ///         name: name.to_string(),
+    ///         serial: serial.to_string(),
+    ///         slot,
+    ///     }
+    /// }
+    ///
+    /// let mut map = QuadHashMap::new();
+    /// map.insert_unique(record(1, "sled-a", "SN-0001", 0)).unwrap();
+    /// map.insert_unique(record(2, "sled-b", "SN-0002", 1)).unwrap();
+    /// assert_eq!(map.len(), 2);
+    ///
+    /// map.clear();
+    /// assert!(map.is_empty());
+    /// # }
+    /// ```
+    pub fn clear(&mut self) {
+        // Clear the internal indexes before dropping items. This way, if a user
+        // `Drop` panics during `self.items.clear()`, the tables cannot retain
+        // indexes pointing to removed item slots.
+        self.tables.k1_to_item.clear();
+        self.tables.k2_to_item.clear();
+        self.tables.k3_to_item.clear();
+        self.tables.k4_to_item.clear();
+        self.items.clear();
+    }
+
+    /// Reserves capacity for at least `additional` more elements to be inserted
+    /// in the `QuadHashMap`. The collection may reserve more space to
+    /// speculatively avoid frequent reallocations. After calling `reserve`,
+    /// capacity will be greater than or equal to `self.len() + additional`.
+    /// Does nothing if capacity is already sufficient.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the new capacity overflows [`isize::MAX`] bytes, and
+    /// [`abort`]s the program in case of an allocation error.
+    ///
+    /// [`isize::MAX`]: https://doc.rust-lang.org/std/primitive.isize.html
+    /// [`abort`]: https://doc.rust-lang.org/alloc/alloc/fn.handle_alloc_error.html
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "default-hasher")] {
+    /// use iddqd::{QuadHashItem, QuadHashMap, quad_upcast};
+    ///
+    /// #[derive(Debug, PartialEq, Eq)]
+    /// struct Record {
+    ///     uuid: u128,
+    ///     name: String,
+    ///     serial: String,
+    ///     slot: u8,
+    /// }
+    ///
+    /// impl QuadHashItem for Record {
+    ///     type K1<'a> = u128;
+    ///     type K2<'a> = &'a str;
+    ///     type K3<'a> = &'a str;
+    ///     type K4<'a> = u8;
+    ///
+    ///     fn key1(&self) -> Self::K1<'_> {
+    ///         self.uuid
+    ///     }
+    ///     fn key2(&self) -> Self::K2<'_> {
+    ///         &self.name
+    ///     }
+    ///     fn key3(&self) -> Self::K3<'_> {
+    ///         &self.serial
+    ///     }
+    ///     fn key4(&self) -> Self::K4<'_> {
+    ///         self.slot
+    ///     }
+    ///     quad_upcast!();
+    /// }
+    ///
+    /// fn record(uuid: u128, name: &str, serial: &str, slot: u8) -> Record {
+    ///     Record {
+    ///         uuid,
+    ///         name: name.to_string(),
+    ///         serial: serial.to_string(),
+    ///         slot,
+    ///     }
+    /// }
+    ///
+    /// let mut map: QuadHashMap<Record> = QuadHashMap::new();
+    /// map.reserve(100);
+    /// assert!(map.capacity() >= 100);
+    /// # }
+    /// ```
+    pub fn reserve(&mut self, additional: usize) {
+        self.items.reserve(additional);
+        self.tables.k1_to_item.reserve(additional);
+        self.tables.k2_to_item.reserve(additional);
+        self.tables.k3_to_item.reserve(additional);
+        self.tables.k4_to_item.reserve(additional);
+    }
+
+    /// Tries to reserve capacity for at least `additional` more items to be
+    /// inserted in the `QuadHashMap`. The collection may reserve more space to
+    /// speculatively avoid frequent reallocations. After calling `try_reserve`,
+    /// capacity will be greater than or equal to `self.len() + additional` if
+    /// it returns `Ok(())`. Does nothing if capacity is already sufficient.
+    ///
+    /// # Errors
+    ///
+    /// If the capacity overflows, or the allocator reports a failure, then an
+    /// error is returned.
+    ///
+    /// # Notes
+    ///
+    /// If reservation fails partway through, some internal structures may have
+    /// already increased their capacity. The map remains in a valid state but
+    /// may have uneven capacities across its internal structures.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "default-hasher")] {
+    /// use iddqd::{QuadHashItem, QuadHashMap, quad_upcast};
+    ///
+    /// #[derive(Debug, PartialEq, Eq)]
+    /// struct Record {
+    ///     uuid: u128,
+    ///     name: String,
+    ///     serial: String,
+    ///     slot: u8,
+    /// }
+    ///
+    /// impl QuadHashItem for Record {
+    ///     type K1<'a> = u128;
+    ///     type K2<'a> = &'a str;
+    ///     type K3<'a> = &'a str;
+    ///     type K4<'a> = u8;
+    ///
+    ///     fn key1(&self) -> Self::K1<'_> {
+    ///         self.uuid
+    ///     }
+    ///     fn key2(&self) -> Self::K2<'_> {
+    ///         &self.name
+    ///     }
+    ///     fn key3(&self) -> Self::K3<'_> {
+    ///         &self.serial
+    ///     }
+    ///     fn key4(&self) -> Self::K4<'_> {
+    ///         self.slot
+    ///     }
+    ///     quad_upcast!();
+    /// }
+    ///
+    /// fn record(uuid: u128, name: &str, serial: &str, slot: u8) -> Record {
+    ///     Record {
+    ///         uuid,
+    ///         name: name.to_string(),
+    ///         serial: serial.to_string(),
+    ///         slot,
+    ///     }
+    /// }
+    ///
+    /// let mut map: QuadHashMap<Record> = QuadHashMap::new();
+    /// map.try_reserve(100).expect("allocation should succeed");
+    /// assert!(map.capacity() >= 100);
+    /// # }
+    /// ```
+    pub fn try_reserve(
+        &mut self,
+        additional: usize,
+    ) -> Result<(), TryReserveError> {
+        self.items.try_reserve(additional)?;
+        self.tables.try_reserve(additional)
+    }
+
+    /// Shrinks the capacity of the map as much as possible. It will drop
+    /// down as much as possible while maintaining the internal rules
+    /// and possibly leaving some space in accordance with the resize policy.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "default-hasher")] {
+    /// use iddqd::{QuadHashItem, QuadHashMap, quad_upcast};
+    ///
+    /// #[derive(Debug, PartialEq, Eq)]
+    /// struct Record {
+    ///     uuid: u128,
+    ///     name: String,
+    ///     serial: String,
+    ///     slot: u8,
+    /// }
+    ///
+    /// impl QuadHashItem for Record {
+    ///     type K1<'a> = u128;
+    ///     type K2<'a> = &'a str;
+    ///     type K3<'a> = &'a str;
+    ///     type K4<'a> = u8;
+    ///
+    ///     fn key1(&self) -> Self::K1<'_> {
+    ///         self.uuid
+    ///     }
+    ///     fn key2(&self) -> Self::K2<'_> {
+    ///         &self.name
+    ///     }
+    ///     fn key3(&self) -> Self::K3<'_> {
+    ///         &self.serial
+    ///     }
+    ///     fn key4(&self) -> Self::K4<'_> {
+    ///         self.slot
+    ///     }
+    ///     quad_upcast!();
+    /// }
+    ///
+    /// fn record(uuid: u128, name: &str, serial: &str, slot: u8) -> Record {
+    ///     Record {
+    ///         uuid,
+    ///         name: name.to_string(),
+    ///         serial: serial.to_string(),
+    ///         slot,
+    ///     }
+    /// }
+    ///
+    /// let mut map: QuadHashMap<Record> = QuadHashMap::with_capacity(100);
+    /// map.insert_unique(record(1, "sled-a", "SN-0001", 0)).unwrap();
+    /// map.insert_unique(record(2, "sled-b", "SN-0002", 1)).unwrap();
+    /// assert!(map.capacity() >= 100);
+    /// map.shrink_to_fit();
+    /// assert!(map.capacity() >= 2);
+    /// # }
+    /// ```
+    pub fn shrink_to_fit(&mut self) {
+        // As with TriHashMap, compact the item set first, then remap the
+        // tables, and only then shrink capacities. An allocator panic during
+        // either shrink leaves the tables and items in sync, because the remap
+        // has already been committed.
+        let remap = self.items.compact();
+        if !remap.is_identity() {
+            self.tables.k1_to_item.remap_indexes(&remap);
+            self.tables.k2_to_item.remap_indexes(&remap);
+            self.tables.k3_to_item.remap_indexes(&remap);
+            self.tables.k4_to_item.remap_indexes(&remap);
+        }
+        self.items.shrink_capacity_to_fit();
+        self.tables.k1_to_item.shrink_to_fit();
+        self.tables.k2_to_item.shrink_to_fit();
+        self.tables.k3_to_item.shrink_to_fit();
+        self.tables.k4_to_item.shrink_to_fit();
+    }
+
+    /// Shrinks the capacity of the map with a lower limit. It will drop
+    /// down no lower than the supplied limit while maintaining the internal
+    /// rules and possibly leaving some space in accordance with the resize
+    /// policy.
+    ///
+    /// If the current capacity is less than the lower limit, this is a no-op.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "default-hasher")] {
+    /// use iddqd::{QuadHashItem, QuadHashMap, quad_upcast};
+    ///
+    /// #[derive(Debug, PartialEq, Eq)]
+    /// struct Record {
+    ///     uuid: u128,
+    ///     name: String,
+    ///     serial: String,
+    ///     slot: u8,
+    /// }
+    ///
+    /// impl QuadHashItem for Record {
+    ///     type K1<'a> = u128;
+    ///     type K2<'a> = &'a str;
+    ///     type K3<'a> = &'a str;
+    ///     type K4<'a> = u8;
+    ///
+    ///     fn key1(&self) -> Self::K1<'_> {
+    ///         self.uuid
+    ///     }
+    ///     fn key2(&self) -> Self::K2<'_> {
+    ///         &self.name
+    ///     }
+    ///     fn key3(&self) -> Self::K3<'_> {
+    ///         &self.serial
+    ///     }
+    ///     fn key4(&self) -> Self::K4<'_> {
+    ///         self.slot
+    ///     }
+    ///     quad_upcast!();
+    /// }
+    ///
+    /// fn record(uuid: u128, name: &str, serial: &str, slot: u8) -> Record {
+    ///     Record {
+    ///         uuid,
+    ///         name: name.to_string(),
+    ///         serial: serial.to_string(),
+    ///         slot,
+    ///     }
+    /// }
+    ///
+    /// let mut map: QuadHashMap<Record> = QuadHashMap::with_capacity(100);
+    /// map.insert_unique(record(1, "sled-a", "SN-0001", 0)).unwrap();
+    /// map.insert_unique(record(2, "sled-b", "SN-0002", 1)).unwrap();
+    /// assert!(map.capacity() >= 100);
+    /// map.shrink_to(10);
+    /// assert!(map.capacity() >= 10);
+    /// map.shrink_to(0);
+    /// assert!(map.capacity() >= 2);
+    /// # }
+    /// ```
+    pub fn shrink_to(&mut self, min_capacity: usize) {
+        // See `shrink_to_fit` for the rationale behind the sequence.
+        let remap = self.items.compact();
+        if !remap.is_identity() {
+            self.tables.k1_to_item.remap_indexes(&remap);
+            self.tables.k2_to_item.remap_indexes(&remap);
+            self.tables.k3_to_item.remap_indexes(&remap);
+            self.tables.k4_to_item.remap_indexes(&remap);
+        }
+        self.items.shrink_capacity_to(min_capacity);
+        self.tables.k1_to_item.shrink_to(min_capacity);
+        self.tables.k2_to_item.shrink_to(min_capacity);
+        self.tables.k3_to_item.shrink_to(min_capacity);
+        self.tables.k4_to_item.shrink_to(min_capacity);
+    }
+
+    /// Iterates over the items in the map.
+    ///
+    /// Similar to [`HashMap`], the iteration order is arbitrary and not
+    /// guaranteed to be stable.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "default-hasher")] {
+    /// use iddqd::{QuadHashItem, QuadHashMap, quad_upcast};
+    ///
+    /// #[derive(Debug, PartialEq, Eq)]
+    /// struct Record {
+    ///     uuid: u128,
+    ///     name: String,
+    ///     serial: String,
+    ///     slot: u8,
+    /// }
+    ///
+    /// impl QuadHashItem for Record {
+    ///     type K1<'a> = u128;
+    ///     type K2<'a> = &'a str;
+    ///     type K3<'a> = &'a str;
+    ///     type K4<'a> = u8;
+    ///
+    ///     fn key1(&self) -> Self::K1<'_> {
+    ///         self.uuid
+    ///     }
+    ///     fn key2(&self) -> Self::K2<'_> {
+    ///         &self.name
+    ///     }
+    ///     fn key3(&self) -> Self::K3<'_> {
+    ///         &self.serial
+    ///     }
+    ///     fn key4(&self) -> Self::K4<'_> {
+    ///         self.slot
+    ///     }
+    ///     quad_upcast!();
+    /// }
+    ///
+    /// fn record(uuid: u128, name: &str, serial: &str, slot: u8) -> Record {
+    ///     Record {
+    ///         uuid,
+    ///         name: name.to_string(),
+    ///         serial: serial.to_string(),
+    ///         slot,
+    ///     }
+    /// }
+    ///
+    /// let mut map = QuadHashMap::new();
+    /// map.insert_unique(record(1, "sled-a", "SN-0001", 0)).unwrap();
+    /// map.insert_unique(record(2, "sled-b", "SN-0002", 1)).unwrap();
+    ///
+    /// let mut slots: Vec<u8> = map.iter().map(|record| record.slot).collect();
+    /// slots.sort();
+    /// assert_eq!(slots, [0, 1]);
+    /// # }
+    /// ```
+    ///
+    /// [`HashMap`]: std::collections::HashMap
+    #[inline]
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter::new(&self.items)
+    }
+
+    /// Iterates over the items in the map, allowing for mutation.
+    ///
+    /// Similar to [`HashMap`], the iteration order is arbitrary and not
+    /// guaranteed to be stable.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "default-hasher")] {
+    /// use iddqd::{QuadHashItem, QuadHashMap, quad_upcast};
+    ///
+    /// #[derive(Debug, PartialEq, Eq)]
+    /// struct Sled {
+    ///     uuid: u128,
+    ///     name: String,
+    ///     serial: String,
+    ///     slot: u8,
+    ///     power_on: bool,
+    /// }
+    ///
+    /// impl QuadHashItem for Sled {
+    ///     type K1<'a> = u128;
+    ///     type K2<'a> = &'a str;
+    ///     type K3<'a> = &'a str;
+    ///     type K4<'a> = u8;
+    ///
+    ///     fn key1(&self) -> Self::K1<'_> {
+    ///         self.uuid
+    ///     }
+    ///     fn key2(&self) -> Self::K2<'_> {
+    ///         &self.name
+    ///     }
+    ///     fn key3(&self) -> Self::K3<'_> {
+    ///         &self.serial
+    ///     }
+    ///     fn key4(&self) -> Self::K4<'_> {
+    ///         self.slot
+    ///     }
+    ///     quad_upcast!();
+    /// }
+    ///
+    /// let mut map = QuadHashMap::new();
+    /// map.insert_unique(Sled {
+    ///     uuid: 1,
+    ///     name: "sled-a".to_string(),
+    ///     serial: "SN-0001".to_string(),
+    ///     slot: 0,
+    ///     power_on: false,
+    /// })
+    /// .unwrap();
+    ///
+    /// for mut sled in map.iter_mut() {
+    ///     sled.power_on = true;
+    /// }
+    ///
+    /// assert!(map.get1(&1).unwrap().power_on);
+    /// # }
+    /// ```
+    ///
+    /// [`HashMap`]: std::collections::HashMap
+    #[inline]
+    pub fn iter_mut(&mut self) -> IterMut<'_, T, S, A> {
+        IterMut::new(&self.tables, &mut self.items)
+    }
+
+    /// Iterates over the items in the map.
+    ///
+    /// This is the same as [`Self::iter`], and is provided for symmetry with
+    /// [`HashMap::values`].
+    ///
+    /// [`HashMap::values`]: std::collections::HashMap::values
+    #[inline]
+    pub fn values(&self) -> Iter<'_, T> {
+        self.iter()
+    }
+
+    /// Iterates over the items in the map, allowing for mutation.
+    ///
+    /// This is the same as [`Self::iter_mut`], and is provided for symmetry with
+    /// [`HashMap::values_mut`].
+    ///
+    /// [`HashMap::values_mut`]: std::collections::HashMap::values_mut
+    #[inline]
+    pub fn values_mut(&mut self) -> IterMut<'_, T, S, A> {
+        self.iter_mut()
+    }
+
+    /// Consumes the map, returning its items as a [`Vec`].
+    ///
+    /// Similar to [`HashMap`], the order of the items is arbitrary. This is
+    /// equivalent to `map.into_iter().collect::<Vec<_>>()`, and is provided to
+    /// make it explicit at the call site that the order doesn't matter.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "default-hasher")] {
+    /// use iddqd::{QuadHashItem, QuadHashMap, quad_upcast};
+    ///
+    /// #[derive(Debug, PartialEq, Eq)]
+    /// struct Record {
+    ///     uuid: u128,
+    ///     name: String,
+    ///     serial: String,
+    ///     slot: u8,
+    /// }
+    ///
+    /// impl QuadHashItem for Record {
+    ///     type K1<'a> = u128;
+    ///     type K2<'a> = &'a str;
+    ///     type K3<'a> = &'a str;
+    ///     type K4<'a> = u8;
+    ///
+    ///     fn key1(&self) -> Self::K1<'_> {
+    ///         self.uuid
+    ///     }
+    ///     fn key2(&self) -> Self::K2<'_> {
+    ///         &self.name
+    ///     }
+    ///     fn key3(&self) -> Self::K3<'_> {
+    ///         &self.serial
+    ///     }
+    ///     fn key4(&self) -> Self::K4<'_> {
+    ///         self.slot
+    ///     }
+    ///     quad_upcast!();
+    /// }
+    ///
+    /// fn record(uuid: u128, name: &str, serial: &str, slot: u8) -> Record {
+    ///     Record {
+    ///         uuid,
+    ///         name: name.to_string(),
+    ///         serial: serial.to_string(),
+    ///         slot,
+    ///     }
+    /// }
+    ///
+    /// let mut map = QuadHashMap::new();
+    /// map.insert_unique(record(2, "sled-b", "SN-0002", 1)).unwrap();
+    /// map.insert_unique(record(1, "sled-a", "SN-0001", 0)).unwrap();
+    ///
+    /// let mut items = map.into_vec();
+    /// items.sort_unstable_by_key(|item| item.uuid);
+    /// assert_eq!(
+    ///     items,
+    ///     vec![
+    ///         record(1, "sled-a", "SN-0001", 0),
+    ///         record(2, "sled-b", "SN-0002", 1),
+    ///     ],
+    /// );
+    /// # }
+    /// ```
+    ///
+    /// [`HashMap`]: std::collections::HashMap
+    pub fn into_vec(self) -> Vec<T> {
+        self.into_iter().collect()
+    }
+
+    /// Checks that the map's internal invariants hold.
+    ///
+    /// See [`MapIntegrityError`] for what this checks, and when it can fail.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "default-hasher")] {
+    /// use iddqd::{QuadHashItem, QuadHashMap, quad_upcast};
+    ///
+    /// #[derive(Debug, PartialEq, Eq)]
+    /// struct Record {
+    ///     uuid: u128,
+    ///     name: String,
+    ///     serial: String,
+    ///     slot: u8,
+    /// }
+    ///
+    /// impl QuadHashItem for Record {
+    ///     type K1<'a> = u128;
+    ///     type K2<'a> = &'a str;
+    ///     type K3<'a> = &'a str;
+    ///     type K4<'a> = u8;
+    ///
+    ///     fn key1(&self) -> Self::K1<'_> {
+    ///         self.uuid
+    ///     }
+    ///     fn key2(&self) -> Self::K2<'_> {
+    ///         &self.name
+    ///     }
+    ///     fn key3(&self) -> Self::K3<'_> {
+    ///         &self.serial
+    ///     }
+    ///     fn key4(&self) -> Self::K4<'_> {
+    ///         self.slot
+    ///     }
+    ///     quad_upcast!();
+    /// }
+    ///
+    /// fn record(uuid: u128, name: &str, serial: &str, slot: u8) -> Record {
+    ///     Record {
+    ///         uuid,
+    ///         name: name.to_string(),
+    ///         serial: serial.to_string(),
+    ///         slot,
+    ///     }
+    /// }
+    ///
+    /// let mut map = QuadHashMap::new();
+    /// map.insert_unique(record(1, "sled-a", "SN-0001", 0)).unwrap();
+    /// map.insert_unique(record(2, "sled-b", "SN-0002", 1)).unwrap();
+    /// map.remove1(&1);
+    ///
+    /// assert_eq!(map.check_invariants(), Ok(()));
+    /// # }
+    /// ```
+    pub fn check_invariants(&self) -> Result<(), MapIntegrityError> {
+        MapIntegrityError::check_storage(&self.items)?;
+        MapIntegrityError::check_table(
+            "key1",
+            &self.items,
+            self.tables.k1_to_item.iter_hashed().map(|(_, ix)| ix),
+        )?;
+        MapIntegrityError::check_table(
+            "key2",
+            &self.items,
+            self.tables.k2_to_item.iter_hashed().map(|(_, ix)| ix),
+        )?;
+        MapIntegrityError::check_table(
+            "key3",
+            &self.items,
+            self.tables.k3_to_item.iter_hashed().map(|(_, ix)| ix),
+        )?;
+        MapIntegrityError::check_table(
+            "key4",
+            &self.items,
+            self.tables.k4_to_item.iter_hashed().map(|(_, ix)| ix),
+        )?;
+
+        for (ix, item) in self.items.iter() {
+            MapIntegrityError::check_lookup(
+                "key1",
+                ix,
+                self.find1_index(&item.key1()),
+            )?;
+            MapIntegrityError::check_lookup(
+                "key2",
+                ix,
+                self.find2_index(&item.key2()),
+            )?;
+            MapIntegrityError::check_lookup(
+                "key3",
+                ix,
+                self.find3_index(&item.key3()),
+            )?;
+            MapIntegrityError::check_lookup(
+                "key4",
+                ix,
+                self.find4_index(&item.key4()),
+            )?;
+        }
+
+        Ok(())
+    }
+
+    /// Checks general invariants of the map.
+    ///
+    /// The code below always upholds these invariants, but it's useful to have
+    /// an explicit check for tests.
+    #[doc(hidden)]
+    pub fn validate(
+        &self,
+        compactness: crate::internal::ValidateCompact,
+    ) -> Result<(), ValidationError>
+    where
+        T: fmt::Debug,
+    {
+        self.validate_structural(compactness)?;
+
+        // Check that the indexes are all correct.
+        //
+        // Unlike the structural checks, this re-looks up each key through the
+        // user `Hash`, so it only holds when that `Hash` is lawful.
+        for (ix, item) in self.items.iter() {
+            let Some(ix1) = self.find1_index(&item.key1()) else {
+                return Err(ValidationError::general(format!(
+                    "item at index {ix} has no key1 index"
+                )));
+            };
+            let Some(ix2) = self.find2_index(&item.key2()) else {
+                return Err(ValidationError::general(format!(
+                    "item at index {ix} has no key2 index"
+                )));
+            };
+            let Some(ix3) = self.find3_index(&item.key3()) else {
+                return Err(ValidationError::general(format!(
+                    "item at index {ix} has no key3 index"
+                )));
+            };
+            let Some(ix4) = self.find4_index(&item.key4()) else {
+                return Err(ValidationError::general(format!(
+                    "item at index {ix} has no key4 index"
+                )));
+            };
+
+            if ix1 != ix || ix2 != ix || ix3 != ix || ix4 != ix {
+                return Err(ValidationError::general(format!(
+                    "item at index {ix} has inconsistent indexes: \
+                     {ix1}/{ix2}/{ix3}/{ix4}"
+                )));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Checks the structural invariants of the map:
+    ///
+    /// * The item set is well-formed.
+    /// * Each per-key hash table holds exactly one entry per live item, with no
+    ///   duplicate `ItemIndex`es.
+    ///
+    /// Unlike [`validate`](Self::validate), this does not re-look-up keys
+    /// through the user `Hash`, so it holds regardless of whether that `Hash`
+    /// is lawful.
+    #[doc(hidden)]
+    pub fn validate_structural(
+        &self,
+        compactness: crate::internal::ValidateCompact,
+    ) -> Result<(), ValidationError> {
+        self.items.validate(compactness)?;
+        self.tables.validate(self.len(), compactness)?;
+        Ok(())
+    }
+
+    /// Inserts a value into the map, removing any conflicting items and
+    /// returning a list of those items.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "default-hasher")] {
+    /// use iddqd::{QuadHashItem, QuadHashMap, quad_upcast};
+    ///
+    /// #[derive(Debug, PartialEq, Eq)]
+    /// struct Record {
+    ///     uuid: u128,
+    ///     name: String,
+    ///     serial: String,
+    ///     slot: u8,
+    /// }
+    ///
+    /// impl QuadHashItem for Record {
+    ///     type K1<'a> = u128;
+    ///     type K2<'a> = &'a str;
+    ///     type K3<'a> = &'a str;
+    ///     type K4<'a> = u8;
+    ///
+    ///     fn key1(&self) -> Self::K1<'_> {
+    ///         self.uuid
+    ///     }
+    ///     fn key2(&self) -> Self::K2<'_> {
+    ///         &self.name
+    ///     }
+    ///     fn key3(&self) -> Self::K3<'_> {
+    ///         &self.serial
+    ///     }
+    ///     fn key4(&self) -> Self::K4<'_> {
+    ///         self.slot
+    ///     }
+    ///     quad_upcast!();
+    /// }
+    ///
+    /// fn record(uuid: u128, name: &str, serial: &str, slot: u8) -> Record {
+    ///     Record {
+    ///         uuid,
+    ///         name: name.to_string(),
+    ///         serial: serial.to_string(),
+    ///         slot,
+    ///     }
+    /// }
+    ///
+    /// let mut map = QuadHashMap::new();
+    ///
+    /// // First insertion - no conflicts
+    /// let overwritten = map.insert_overwrite(record(1, "sled-a", "SN-0001", 0));
+    /// assert!(overwritten.is_empty());
+    ///
+    /// // Conflicts on uuid and slot with the same item - returns the old item
+    /// let overwritten = map.insert_overwrite(record(1, "sled-c", "SN-0003", 0));
+    /// assert_eq!(overwritten.len(), 1);
+    /// assert_eq!(overwritten[0].name, "sled-a");
+    /// # }
+    /// ```
+    #[doc(alias = "insert")]
+    pub fn insert_overwrite(&mut self, value: T) -> Vec<T> {
+        let prepared = self.prepare_insert_overwrite(&value);
+
+        let mut duplicates = Vec::with_capacity(prepared.duplicate_count());
+
+        self.try_reserve_insert_overwrite_commit(
+            prepared.needs_new_item_slot(),
+        )
+        .expect("reserved space successfully");
+
+        self.commit_insert_overwrite(value, prepared, &mut duplicates);
+
+        duplicates
+    }
+
+    /// Inserts a value into the set, returning an error if any duplicates were
+    /// added.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "default-hasher")] {
+    /// use iddqd::{QuadHashItem, QuadHashMap, quad_upcast};
+    ///
+    /// #[derive(Debug, PartialEq, Eq)]
+    /// struct Record {
+    ///     uuid: u128,
+    ///     name: String,
+    ///     serial: String,
+    ///     slot: u8,
+    /// }
+    ///
+    /// impl QuadHashItem for Record {
+    ///     type K1<'a> = u128;
+    ///     type K2<'a> = &'a str;
+    ///     type K3<'a> = &'a str;
+    ///     type K4<'a> = u8;
+    ///
+    ///     fn key1(&self) -> Self::K1<'_> {
+    ///         self.uuid
+    ///     }
+    ///     fn key2(&self) -> Self::K2<'_> {
+    ///         &self.name
+    ///     }
+    ///     fn key3(&self) -> Self::K3<'_> {
+    ///         &self.serial
+    ///     }
+    ///     fn key4(&self) -> Self::K4<'_> {
+    ///         self.slot
+    ///     }
+    ///     quad_upcast!();
+    /// }
+    ///
+    /// fn record(uuid: u128, name: &str, serial: &str, slot: u8) -> Record {
+    ///     Record {
+    ///         uuid,
+    ///         name: name.to_string(),
+    ///         serial: serial.to_string(),
+    ///         slot,
+    ///     }
+    /// }
+    ///
+    /// let mut map = QuadHashMap::new();
+    /// map.insert_unique(record(1, "sled-a", "SN-0001", 0)).unwrap();
+    ///
+    /// // A new item with four distinct keys can be inserted.
+    /// assert!(map.insert_unique(record(2, "sled-b", "SN-0002", 1)).is_ok());
+    ///
+    /// // Each of these conflicts with an existing item on one key.
+    /// assert!(map.insert_unique(record(1, "sled-c", "SN-0003", 2)).is_err());
+    /// assert!(map.insert_unique(record(3, "sled-a", "SN-0003", 2)).is_err());
+    /// assert!(map.insert_unique(record(3, "sled-c", "SN-0001", 2)).is_err());
+    /// assert!(map.insert_unique(record(3, "sled-c", "SN-0003", 0)).is_err());
+    /// assert_eq!(map.len(), 2);
+    /// # }
+    /// ```
+    pub fn insert_unique(
+        &mut self,
+        value: T,
+    ) -> Result<(), DuplicateItem<T, &T>> {
+        let _ = self.insert_unique_impl(value)?;
+        Ok(())
+    }
+
+    /// Extends the map with the contents of an iterator, handling duplicates
+    /// according to `mode`.
+    ///
+    /// With [`ExtendMode::Overwrite`], this behaves like calling
+    /// [`QuadHashMap::insert_overwrite`] for each item, and never returns an
+    /// error. This is what the [`Extend`] implementation does.
+    ///
+    /// With [`ExtendMode::Unique`], this behaves like calling
+    /// [`QuadHashMap::insert_unique`] for each item, stopping at the first
+    /// item that conflicts with an existing one. Items before the conflicting
+    /// one remain in the map, and the rest of the iterator is dropped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "default-hasher")] {
+    /// use iddqd::{ExtendMode, QuadHashItem, QuadHashMap, quad_upcast};
+    ///
+    /// #[derive(Debug, PartialEq, Eq)]
+    /// struct Record {
+    ///     uuid: u128,
+    ///     name: String,
+    ///     serial: String,
+    ///     slot: u8,
+    /// }
+    ///
+    /// impl QuadHashItem for Record {
+    ///     type K1<'a> = u128;
+    ///     type K2<'a> = &'a str;
+    ///     type K3<'a> = &'a str;
+    ///     type K4<'a> = u8;
+    ///
+    ///     fn key1(&self) -> Self::K1<'_> {
+    ///         self.uuid
+    ///     }
+    ///     fn key2(&self) -> Self::K2<'_> {
+    ///         &self.name
+    ///     }
+    ///     fn key3(&self) -> Self::K3<'_> {
+    ///         &self.serial
+    ///     }
+    ///     fn key4(&self) -> Self::K4<'_> {
+    ///         self.slot
+    ///     }
+    ///     quad_upcast!();
+    /// }
+    ///
+    /// fn record(uuid: u128, name: &str, serial: &str, slot: u8) -> Record {
+    ///     Record {
+    ///         uuid,
+    ///         name: name.to_string(),
+    ///         serial: serial.to_string(),
+    ///         slot,
+    ///     }
+    /// }
+    ///
+    /// let mut map = QuadHashMap::new();
+    /// map.insert_unique(record(1, "sled-a", "SN-0001", 0)).unwrap();
+    ///
+    /// // Unique mode stops at the first item that conflicts on any key.
+    /// let new_items = vec![
+    ///     record(2, "sled-b", "SN-0002", 1),
+    ///     record(3, "sled-c", "SN-0003", 0),
+    ///     record(4, "sled-d", "SN-0004", 3),
+    /// ];
+    /// let err = map.extend_with(new_items, ExtendMode::Unique).unwrap_err();
+    /// assert_eq!(err.new_item().uuid, 3);
+    /// assert_eq!(err.duplicates()[0].uuid, 1);
+    /// assert_eq!(map.len(), 2);
+    /// assert!(map.contains_key1(&2));
+    /// assert!(!map.contains_key1(&4));
+    ///
+    /// // Overwrite mode replaces duplicates.
+    /// let new_items = vec![record(3, "sled-c", "SN-0003", 0)];
+    /// map.extend_with(new_items, ExtendMode::Overwrite).unwrap();
+    /// assert_eq!(map.get4(&0).unwrap().uuid, 3);
+    /// assert!(!map.contains_key1(&1));
+    /// # }
+    /// ```
+    pub fn extend_with<I: IntoIterator<Item = T>>(
+        &mut self,
+        iter: I,
+        mode: ExtendMode,
+    ) -> Result<(), DuplicateItem<T, &T>> {
+        // Keys may already be present in the map, or multiple times in the
+        // iterator. Reserve the entire hint lower bound if the map is empty.
+        // Otherwise reserve half the hint (rounded up), so the map will only
+        // resize twice in the worst case.
+        let iter = iter.into_iter();
+        let reserve = if self.is_empty() {
+            iter.size_hint().0
+        } else {
+            iter.size_hint().0.div_ceil(2)
+        };
+        self.reserve(reserve);
+        match mode {
+            ExtendMode::Overwrite => {
+                for item in iter {
+                    self.insert_overwrite(item);
+                }
+            }
+            ExtendMode::Unique => {
+                for item in iter {
+                    if let Err((item, duplicates)) =
+                        self.insert_unique_or_dup_indexes(item)
+                    {
+                        return Err(DuplicateItem::__internal_new(
+                            item,
+                            duplicates
+                                .iter()
+                                .map(|ix| &self.items[*ix])
+                                .collect(),
+                        ));
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns true if the map contains a single item that matches all four
+    /// keys.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "default-hasher")] {
+    /// use iddqd::{QuadHashItem, QuadHashMap, quad_upcast};
+    ///
+    /// #[derive(Debug, PartialEq, Eq)]
+    /// struct Record {
+    ///     uuid: u128,
+    ///     name: String,
+    ///     serial: String,
+    ///     slot: u8,
+    /// }
+    ///
+    /// impl QuadHashItem for Record {
+    ///     type K1<'a> = u128;
+    ///     type K2<'a> = &'a str;
+    ///     type K3<'a> = &'a str;
+    ///     type K4<'a> = u8;
+    ///
+    ///     fn key1(&self) -> Self::K1<'_> {
+    ///         self.uuid
+    ///     }
+    ///     fn key2(&self) -> Self::K2<'_> {
+    ///         &self.name
+    ///     }
+    ///     fn key3(&self) -> Self::K3<'_> {
+    ///         &self.serial
+    ///     }
+    ///     fn key4(&self) -> Self::K4<'_> {
+    ///         self.slot
+    ///     }
+    ///     quad_upcast!();
+    /// }
+    ///
+    /// fn record(uuid: u128, name: &str, serial: &str, slot: u8) -> Record {
+    ///     Record {
+    ///         uuid,
+    ///         name: name.to_string(),
+    ///         serial: serial.to_string(),
+    ///         slot,
+    ///     }
+    /// }
+    ///
+    /// let mut map = QuadHashMap::new();
+    /// map.insert_unique(record(1, "sled-a", "SN-0001", 0)).unwrap();
+    /// map.insert_unique(record(2, "sled-b", "SN-0002", 1)).unwrap();
+    ///
+    /// assert!(map.contains_key_unique(&1, &"sled-a", &"SN-0001", &0));
+    /// assert!(!map.contains_key_unique(&1, &"sled-b", &"SN-0001", &0));
+    /// # }
+    /// ```
+    pub fn contains_key_unique<'a, Q1, Q2, Q3, Q4>(
+        &'a self,
+        key1: &Q1,
+        key2: &Q2,
+        key3: &Q3,
+        key4: &Q4,
+    ) -> bool
+    where
+        Q1: Hash + Equivalent<T::K1<'a>> + ?Sized,
+        Q2: Hash + Equivalent<T::K2<'a>> + ?Sized,
+        Q3: Hash + Equivalent<T::K3<'a>> + ?Sized,
+        Q4: Hash + Equivalent<T::K4<'a>> + ?Sized,
+    {
+        self.get_unique(key1, key2, key3, key4).is_some()
+    }
+
+    /// Gets a reference to the unique item associated with the given `key1`,
+    /// `key2`, `key3`, and `key4`, if it exists.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "default-hasher")] {
+    /// use iddqd::{QuadHashItem, QuadHashMap, quad_upcast};
+    ///
+    /// #[derive(Debug, PartialEq, Eq)]
+    /// struct Record {
+    ///     uuid: u128,
+    ///     name: String,
+    ///     serial: String,
+    ///     slot: u8,
+    /// }
+    ///
+    /// impl QuadHashItem for Record {
+    ///     type K1<'a> = u128;
+    ///     type K2<'a> = &'a str;
+    ///     type K3<'a> = &'a str;
+    ///     type K4<'a> = u8;
+    ///
+    ///     fn key1(&self) -> Self::K1<'_> {
+    ///         self.uuid
+    ///     }
+    ///     fn key2(&self) -> Self::K2<'_> {
+    ///         &self.name
+    ///     }
+    ///     fn key3(&self) -> Self::K3<'_> {
+    ///         &self.serial
+    ///     }
+    ///     fn key4(&self) -> Self::K4<'_> {
+    ///         self.slot
+    ///     }
+    ///     quad_upcast!();
+    /// }
+    ///
+    /// fn record(uuid: u128, name: &str, serial: &str, slot: u8) -> Record {
+    ///     Record {
+    ///         uuid,
+    ///         name: name.to_string(),
+    ///         serial: serial.to_string(),
+    ///         slot,
+    ///     }
+    /// }
+    ///
+    /// let mut map = QuadHashMap::new();
+    /// map.insert_unique(record(1, "sled-a", "SN-0001", 0)).unwrap();
+    ///
+    /// // All four keys must match
+    /// assert_eq!(map.get_unique(&1, &"sled-a", &"SN-0001", &0).unwrap().uuid, 1);
+    ///
+    /// // If any key doesn't match, returns None
+    /// assert!(map.get_unique(&1, &"sled-a", &"SN-0001", &1).is_none());
+    /// assert!(map.get_unique(&2, &"sled-a", &"SN-0001", &0).is_none());
+    /// # }
+    /// ```
+    pub fn get_unique<'a, Q1, Q2, Q3, Q4>(
+        &'a self,
+        key1: &Q1,
+        key2: &Q2,
+        key3: &Q3,
+        key4: &Q4,
+    ) -> Option<&'a T>
+    where
+        Q1: Hash + Equivalent<T::K1<'a>> + ?Sized,
+        Q2: Hash + Equivalent<T::K2<'a>> + ?Sized,
+        Q3: Hash + Equivalent<T::K3<'a>> + ?Sized,
+        Q4: Hash + Equivalent<T::K4<'a>> + ?Sized,
+    {
+        let index = self.find1_index(key1)?;
+        let item = &self.items[index];
+        if key2.equivalent(&item.key2())
+            && key3.equivalent(&item.key3())
+            && key4.equivalent(&item.key4())
+        {
+            Some(item)
+        } else {
+            None
+        }
+    }
+
+    /// Gets a mutable reference to the unique item associated with the given
+    /// `key1`, `key2`, `key3`, and `key4`, if it exists.
+    pub fn get_mut_unique<'a, Q1, Q2, Q3, Q4>(
+        &'a mut self,
+        key1: &Q1,
+        key2: &Q2,
+        key3: &Q3,
+        key4: &Q4,
+    ) -> Option<RefMut<'a, T, S>>
+    where
+        Q1: Hash + Equivalent<T::K1<'a>> + ?Sized,
+        Q2: Hash + Equivalent<T::K2<'a>> + ?Sized,
+        Q3: Hash + Equivalent<T::K3<'a>> + ?Sized,
+        Q4: Hash + Equivalent<T::K4<'a>> + ?Sized,
+    {
+        let (dormant_map, index) = {
+            let (map, dormant_map) = DormantMutRef::new(self);
+            let index = map.find1_index(key1)?;
+            let item = &map.items[index];
+            if !key2.equivalent(&item.key2())
+                || !key3.equivalent(&item.key3())
+                || !key4.equivalent(&item.key4())
+            {
+                return None;
+            }
+            (dormant_map, index)
+        };
+
+        // SAFETY: `map` is not used after this point.
+        let awakened_map = unsafe { dormant_map.awaken() };
+        let item = &mut awakened_map.items[index];
+        let state = awakened_map.tables.state.clone();
+        let hashes = awakened_map.tables.make_hashes(&item);
+        Some(RefMut::new(state, hashes, item))
+    }
+
+    /// Removes the item uniquely identified by `key1`, `key2`, `key3`, and
+    /// `key4`, if it exists.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "default-hasher")] {
+    /// use iddqd::{QuadHashItem, QuadHashMap, quad_upcast};
+    ///
+    /// #[derive(Debug, PartialEq, Eq)]
+    /// struct Record {
+    ///     uuid: u128,
+    ///     name: String,
+    ///     serial: String,
+    ///     slot: u8,
+    /// }
+    ///
+    /// impl QuadHashItem for Record {
+    ///     type K1<'a> = u128;
+    ///     type K2<'a> = &'a str;
+    ///     type K3<'a> = &'a str;
+    ///     type K4<'a> = u8;
+    ///
+    ///     fn key1(&self) -> Self::K1<'_> {
+    ///         self.uuid
+    ///     }
+    ///     fn key2(&self) -> Self::K2<'_> {
+    ///         &self.name
+    ///     }
+    ///     fn key3(&self) -> Self::K3<'_> {
+    ///         &self.serial
+    ///     }
+    ///     fn key4(&self) -> Self::K4<'_> {
+    ///         self.slot
+    ///     }
+    ///     quad_upcast!();
+    /// }
+    ///
+    /// fn record(uuid: u128, name: &str, serial: &str, slot: u8) -> Record {
+    ///     Record {
+    ///         uuid,
+    ///         name: name.to_string(),
+    ///         serial: serial.to_string(),
+    ///         slot,
+    ///     }
+    /// }
+    ///
+    /// let mut map = QuadHashMap::new();
+    /// map.insert_unique(record(1, "sled-a", "SN-0001", 0)).unwrap();
+    ///
+    /// // Keys that match different items (or no item) don't remove anything
+    /// assert!(map.remove_unique(&1, &"sled-a", &"SN-0001", &1).is_none());
+    ///
+    /// let removed = map.remove_unique(&1, &"sled-a", &"SN-0001", &0).unwrap();
+    /// assert_eq!(removed.name, "sled-a");
+    /// assert!(map.is_empty());
+    /// # }
+    /// ```
+    pub fn remove_unique<'a, Q1, Q2, Q3, Q4>(
+        &'a mut self,
+        key1: &Q1,
+        key2: &Q2,
+        key3: &Q3,
+        key4: &Q4,
+    ) -> Option<T>
+    where
+        Q1: Hash + Equivalent<T::K1<'a>> + ?Sized,
+        Q2: Hash + Equivalent<T::K2<'a>> + ?Sized,
+        Q3: Hash + Equivalent<T::K3<'a>> + ?Sized,
+        Q4: Hash + Equivalent<T::K4<'a>> + ?Sized,
+    {
+        let (dormant_map, remove_index) = {
+            let (map, dormant_map) = DormantMutRef::new(self);
+            let remove_index = map.find1_index(key1)?;
+            let item = &map.items[remove_index];
+            if !key2.equivalent(&item.key2())
+                || !key3.equivalent(&item.key3())
+                || !key4.equivalent(&item.key4())
+            {
+                return None;
+            }
+            (dormant_map, remove_index)
+        };
+
+        // SAFETY: `map` is not used after this point.
+        let awakened_map = unsafe { dormant_map.awaken() };
+
+        awakened_map.remove_by_index(remove_index)
+    }
+
+    /// Returns true if the map contains the given `key1`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "default-hasher")] {
+    /// use iddqd::{QuadHashItem, QuadHashMap, quad_upcast};
+    ///
+    /// #[derive(Debug, PartialEq, Eq)]
+    /// struct Record {
+    ///     uuid: u128,
+    ///     name: String,
+    ///     serial: String,
+    ///     slot: u8,
+    /// }
+    ///
+    /// impl QuadHashItem for Record {
+    ///     type K1<'a> = u128;
+    ///     type K2<'a> = &'a str;
+    ///     type K3<'a> = &'a str;
+    ///     type K4<'a> = u8;
+    ///
+    ///     fn key1(&self) -> Self::K1<'_> {
+    ///         self.uuid
+    ///     }
+    ///     fn key2(&self) -> Self::K2<'_> {
+    ///         &self.name
+    ///     }
+    ///     fn key3(&self) -> Self::K3<'_> {
+    ///         &self.serial
+    ///     }
+    ///     fn key4(&self) -> Self::K4<'_> {
+    ///         self.slot
+    ///     }
+    ///     quad_upcast!();
+    /// }
+    ///
+    /// fn record(uuid: u128, name: &str, serial: &str, slot: u8) -> Record {
+    ///     Record {
+    ///         uuid,
+    ///         name: name.to_string(),
+    ///         serial: serial.to_string(),
+    ///         slot,
+    ///     }
+    /// }
+    ///
+    /// let mut map = QuadHashMap::new();
+    /// map.insert_unique(record(1, "sled-a", "SN-0001", 0)).unwrap();
+    ///
+    /// assert!(map.contains_key1(&1));
+    /// assert!(!map.contains_key1(&3));
+    /// # }
+    /// ```
+    pub fn contains_key1<'a, Q>(&'a self, key1: &Q) -> bool
+    where
+        Q: Hash + Equivalent<T::K1<'a>> + ?Sized,
+    {
+        self.find1_index(key1).is_some()
+    }
+
+    /// Gets a reference to the value associated with the given `key1`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "default-hasher")] {
+    /// use iddqd::{QuadHashItem, QuadHashMap, quad_upcast};
+    ///
+    /// #[derive(Debug, PartialEq, Eq)]
+    /// struct Record {
+    ///     uuid: u128,
+    ///     name: String,
+    ///     serial: String,
+    ///     slot: u8,
+    /// }
+    ///
+    /// impl QuadHashItem for Record {
+    ///     type K1<'a> = u128;
+    ///     type K2<'a> = &'a str;
+    ///     type K3<'a> = &'a str;
+    ///     type K4<'a> = u8;
+    ///
+    ///     fn key1(&self) -> Self::K1<'_> {
+    ///         self.uuid
+    ///     }
+    ///     fn key2(&self) -> Self::K2<'_> {
+    ///         &self.name
+    ///     }
+    ///     fn key3(&self) -> Self::K3<'_> {
+    ///         &self.serial
+    ///     }
+    ///     fn key4(&self) -> Self::K4<'_> {
+    ///         self.slot
+    ///     }
+    ///     quad_upcast!();
+    /// }
+    ///
+    /// fn record(uuid: u128, name: &str, serial: &str, slot: u8) -> Record {
+    ///     Record {
+    ///         uuid,
+    ///         name: name.to_string(),
+    ///         serial: serial.to_string(),
+    ///         slot,
+    ///     }
+    /// }
+    ///
+    /// let mut map = QuadHashMap::new();
+    /// map.insert_unique(record(1, "sled-a", "SN-0001", 0)).unwrap();
+    ///
+    /// assert_eq!(map.get1(&1).unwrap().uuid, 1);
+    /// assert!(map.get1(&3).is_none());
+    /// # }
+    /// ```
+    pub fn get1<'a, Q>(&'a self, key1: &Q) -> Option<&'a T>
+    where
+        Q: Hash + Equivalent<T::K1<'a>> + ?Sized,
+    {
+        self.find1(key1)
+    }
+
+    /// Gets a mutable reference to the value associated with the given `key1`.
+    pub fn get1_mut<'a, Q>(&'a mut self, key1: &Q) -> Option<RefMut<'a, T, S>>
+    where
+        Q: Hash + Equivalent<T::K1<'a>> + ?Sized,
+    {
+        let (dormant_map, index) = {
+            let (map, dormant_map) = DormantMutRef::new(self);
+            let index = map.find1_index(key1)?;
+            (dormant_map, index)
+        };
+
+        // SAFETY: `map` is not used after this point.
+        let awakened_map = unsafe { dormant_map.awaken() };
+        let item = &mut awakened_map.items[index];
+        let state = awakened_map.tables.state.clone();
+        let hashes = awakened_map.tables.make_hashes(&item);
+        Some(RefMut::new(state, hashes, item))
+    }
+
+    /// Removes an item from the map by its `key1`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "default-hasher")] {
+    /// use iddqd::{QuadHashItem, QuadHashMap, quad_upcast};
+    ///
+    /// #[derive(Debug, PartialEq, Eq)]
+    /// struct Record {
+    ///     uuid: u128,
+    ///     name: String,
+    ///     serial: String,
+    ///     slot: u8,
+    /// }
+    ///
+    /// impl QuadHashItem for Record {
+    ///     type K1<'a> = u128;
+    ///     type K2<'a> = &'a str;
+    ///     type K3<'a> = &'a str;
+    ///     type K4<'a> = u8;
+    ///
+    ///     fn key1(&self) -> Self::K1<'_> {
+    ///         self.uuid
+    ///     }
+    ///     fn key2(&self) -> Self::K2<'_> {
+    ///         &self.name
+    ///     }
+    ///     fn key3(&self) -> Self::K3<'_> {
+    ///         &self.serial
+    ///     }
+    ///     fn key4(&self) -> Self::K4<'_> {
+    ///         self.slot
+    ///     }
+    ///     quad_upcast!();
+    /// }
+    ///
+    /// fn record(uuid: u128, name: &str, serial: &str, slot: u8) -> Record {
+    ///     Record {
+    ///         uuid,
+    ///         name: name.to_string(),
+    ///         serial: serial.to_string(),
+    ///         slot,
+    ///     }
+    /// }
+    ///
+    /// let mut map = QuadHashMap::new();
+    /// map.insert_unique(record(1, "sled-a", "SN-0001", 0)).unwrap();
+    ///
+    /// assert!(map.remove1(&3).is_none());
+    /// let removed = map.remove1(&1).unwrap();
+    /// assert_eq!(removed.uuid, 1);
+    /// assert!(map.is_empty());
+    /// # }
+    /// ```
+    pub fn remove1<'a, Q>(&'a mut self, key1: &Q) -> Option<T>
+    where
+        Q: Hash + Equivalent<T::K1<'a>> + ?Sized,
+    {
+        let (dormant_map, remove_index) = {
+            let (map, dormant_map) = DormantMutRef::new(self);
+            let remove_index = map.find1_index(key1)?;
+            (dormant_map, remove_index)
+        };
+
+        // SAFETY: `map` is not used after this point.
+        let awakened_map = unsafe { dormant_map.awaken() };
+
+        awakened_map.remove_by_index(remove_index)
+    }
+
+    /// Returns true if the map contains the given `key2`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "default-hasher")] {
+    /// use iddqd::{QuadHashItem, QuadHashMap, quad_upcast};
+    ///
+    /// #[derive(Debug, PartialEq, Eq)]
+    /// struct Record {
+    ///     uuid: u128,
+    ///     name: String,
+    ///     serial: String,
+    ///     slot: u8,
+    /// }
+    ///
+    /// impl QuadHashItem for Record {
+    ///     type K1<'a> = u128;
+    ///     type K2<'a> = &'a str;
+    ///     type K3<'a> = &'a str;
+    ///     type K4<'a> = u8;
+    ///
+    ///     fn key1(&self) -> Self::K1<'_> {
+    ///         self.uuid
+    ///     }
+    ///     fn key2(&self) -> Self::K2<'_> {
+    ///         &self.name
+    ///     }
+    ///     fn key3(&self) -> Self::K3<'_> {
+    ///         &self.serial
+    ///     }
+    ///     fn key4(&self) -> Self::K4<'_> {
+    ///         self.slot
+    ///     }
+    ///     quad_upcast!();
+    /// }
+    ///
+    /// fn record(uuid: u128, name: &str, serial: &str, slot: u8) -> Record {
+    ///     Record {
+    ///         uuid,
+    ///         name: name.to_string(),
+    ///         serial: serial.to_string(),
+    ///         slot,
+    ///     }
+    /// }
+    ///
+    /// let mut map = QuadHashMap::new();
+    /// map.insert_unique(record(1, "sled-a", "SN-0001", 0)).unwrap();
+    ///
+    /// assert!(map.contains_key2("sled-a"));
+    /// assert!(!map.contains_key2("sled-c"));
+    /// # }
+    /// ```
+    pub fn contains_key2<'a, Q>(&'a self, key2: &Q) -> bool
+    where
+        Q: Hash + Equivalent<T::K2<'a>> + ?Sized,
+    {
+        self.find2_index(key2).is_some()
+    }
+
+    /// Gets a reference to the value associated with the given `key2`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "default-hasher")] {
+    /// use iddqd::{QuadHashItem, QuadHashMap, quad_upcast};
+    ///
+    /// #[derive(Debug, PartialEq, Eq)]
+    /// struct Record {
+    ///     uuid: u128,
+    ///     name: String,
+    ///     serial: String,
+    ///     slot: u8,
+    /// }
+    ///
+    /// impl QuadHashItem for Record {
+    ///     type K1<'a> = u128;
+    ///     type K2<'a> = &'a str;
+    ///     type K3<'a> = &'a str;
+    ///     type K4<'a> = u8;
+    ///
+    ///     fn key1(&self) -> Self::K1<'_> {
+    ///         self.uuid
+    ///     }
+    ///     fn key2(&self) -> Self::K2<'_> {
+    ///         &self.name
+    ///     }
+    ///     fn key3(&self) -> Self::K3<'_> {
+    ///         &self.serial
+    ///     }
+    ///     fn key4(&self) -> Self::K4<'_> {
+    ///         self.slot
+    ///     }
+    ///     quad_upcast!();
+    /// }
+    ///
+    /// fn record(uuid: u128, name: &str, serial: &str, slot: u8) -> Record {
+    ///     Record {
+    ///         uuid,
+    ///         name: name.to_string(),
+    ///         serial: serial.to_string(),
+    ///         slot,
+    ///     }
+    /// }
+    ///
+    /// let mut map = QuadHashMap::new();
+    /// map.insert_unique(record(1, "sled-a", "SN-0001", 0)).unwrap();
+    ///
+    /// assert_eq!(map.get2("sled-a").unwrap().uuid, 1);
+    /// assert!(map.get2("sled-c").is_none());
+    /// # }
+    /// ```
+    pub fn get2<'a, Q>(&'a self, key2: &Q) -> Option<&'a T>
+    where
+        Q: Hash + Equivalent<T::K2<'a>> + ?Sized,
+    {
+        self.find2(key2)
+    }
+
+    /// Gets a mutable reference to the value associated with the given `key2`.
+    pub fn get2_mut<'a, Q>(&'a mut self, key2: &Q) -> Option<RefMut<'a, T, S>>
+    where
+        Q: Hash + Equivalent<T::K2<'a>> + ?Sized,
+    {
+        let (dormant_map, index) = {
+            let (map, dormant_map) = DormantMutRef::new(self);
+            let index = map.find2_index(key2)?;
+            (dormant_map, index)
+        };
+
+        // SAFETY: `map` is not used after this point.
+        let awakened_map = unsafe { dormant_map.awaken() };
+        let item = &mut awakened_map.items[index];
+        let state = awakened_map.tables.state.clone();
+        let hashes = awakened_map.tables.make_hashes(&item);
+        Some(RefMut::new(state, hashes, item))
+    }
+
+    /// Removes an item from the map by its `key2`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "default-hasher")] {
+    /// use iddqd::{QuadHashItem, QuadHashMap, quad_upcast};
+    ///
+    /// #[derive(Debug, PartialEq, Eq)]
+    /// struct Record {
+    ///     uuid: u128,
+    ///     name: String,
+    ///     serial: String,
+    ///     slot: u8,
+    /// }
+    ///
+    /// impl QuadHashItem for Record {
+    ///     type K1<'a> = u128;
+    ///     type K2<'a> = &'a str;
+    ///     type K3<'a> = &'a str;
+    ///     type K4<'a> = u8;
+    ///
+    ///     fn key1(&self) -> Self::K1<'_> {
+    ///         self.uuid
+    ///     }
+    ///     fn key2(&self) -> Self::K2<'_> {
+    ///         &self.name
+    ///     }
+    ///     fn key3(&self) -> Self::K3<'_> {
+    ///         &self.serial
+    ///     }
+    ///     fn key4(&self) -> Self::K4<'_> {
+    ///         self.slot
+    ///     }
+    ///     quad_upcast!();
+    /// }
+    ///
+    /// fn record(uuid: u128, name: &str, serial: &str, slot: u8) -> Record {
+    ///     Record {
+    ///         uuid,
+    ///         name: name.to_string(),
+    ///         serial: serial.to_string(),
+    ///         slot,
+    ///     }
+    /// }
+    ///
+    /// let mut map = QuadHashMap::new();
+    /// map.insert_unique(record(1, "sled-a", "SN-0001", 0)).unwrap();
+    ///
+    /// assert!(map.remove2("sled-c").is_none());
+    /// let removed = map.remove2("sled-a").unwrap();
+    /// assert_eq!(removed.uuid, 1);
+    /// assert!(map.is_empty());
+    /// # }
+    /// ```
+    pub fn remove2<'a, Q>(&'a mut self, key2: &Q) -> Option<T>
+    where
+        Q: Hash + Equivalent<T::K2<'a>> + ?Sized,
+    {
+        let (dormant_map, remove_index) = {
+            let (map, dormant_map) = DormantMutRef::new(self);
+            let remove_index = map.find2_index(key2)?;
+            (dormant_map, remove_index)
+        };
+
+        // SAFETY: `map` is not used after this point.
+        let awakened_map = unsafe { dormant_map.awaken() };
+
+        awakened_map.remove_by_index(remove_index)
+    }
+
+    /// Returns true if the map contains the given `key3`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "default-hasher")] {
+    /// use iddqd::{QuadHashItem, QuadHashMap, quad_upcast};
+    ///
+    /// #[derive(Debug, PartialEq, Eq)]
+    /// struct Record {
+    ///     uuid: u128,
+    ///     name: String,
+    ///     serial: String,
+    ///     slot: u8,
+    /// }
+    ///
+    /// impl QuadHashItem for Record {
+    ///     type K1<'a> = u128;
+    ///     type K2<'a> = &'a str;
+    ///     type K3<'a> = &'a str;
+    ///     type K4<'a> = u8;
+    ///
+    ///     fn key1(&self) -> Self::K1<'_> {
+    ///         self.uuid
+    ///     }
+    ///     fn key2(&self) -> Self::K2<'_> {
+    ///         &self.name
+    ///     }
+    ///     fn key3(&self) -> Self::K3<'_> {
+    ///         &self.serial
+    ///     }
+    ///     fn key4(&self) -> Self::K4<'_> {
+    ///         self.slot
+    ///     }
+    ///     quad_upcast!();
+    /// }
+    ///
+    /// fn record(uuid: u128, name: &str, serial: &str, slot: u8) -> Record {
+    ///     Record {
+    ///         uuid,
+    ///         name: name.to_string(),
+    ///         serial: serial.to_string(),
+    ///         slot,
+    ///     }
+    /// }
+    ///
+    /// let mut map = QuadHashMap::new();
+    /// map.insert_unique(record(1, "sled-a", "SN-0001", 0)).unwrap();
+    ///
+    /// assert!(map.contains_key3("SN-0001"));
+    /// assert!(!map.contains_key3("SN-0003"));
+    /// # }
+    /// ```
+    pub fn contains_key3<'a, Q>(&'a self, key3: &Q) -> bool
+    where
+        Q: Hash + Equivalent<T::K3<'a>> + ?Sized,
+    {
+        self.find3_index(key3).is_some()
+    }
+
+    /// Gets a reference to the value associated with the given `key3`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "default-hasher")] {
+    /// use iddqd::{QuadHashItem, QuadHashMap, quad_upcast};
+    ///
+    /// #[derive(Debug, PartialEq, Eq)]
+    /// struct Record {
+    ///     uuid: u128,
+    ///     name: String,
+    ///     serial: String,
+    ///     slot: u8,
+    /// }
+    ///
+    /// impl QuadHashItem for Record {
+    ///     type K1<'a> = u128;
+    ///     type K2<'a> = &'a str;
+    ///     type K3<'a> = &'a str;
+    ///     type K4<'a> = u8;
+    ///
+    ///     fn key1(&self) -> Self::K1<'_> {
+    ///         self.uuid
+    ///     }
+    ///     fn key2(&self) -> Self::K2<'_> {
+    ///         &self.name
+    ///     }
+    ///     fn key3(&self) -> Self::K3<'_> {
+    ///         &self.serial
+    ///     }
+    ///     fn key4(&self) -> Self::K4<'_> {
+    ///         self.slot
+    ///     }
+    ///     quad_upcast!();
+    /// }
+    ///
+    /// fn record(uuid: u128, name: &str, serial: &str, slot: u8) -> Record {
+    ///     Record {
+    ///         uuid,
+    ///         name: name.to_string(),
+    ///         serial: serial.to_string(),
+    ///         slot,
+    ///     }
+    /// }
+    ///
+    /// let mut map = QuadHashMap::new();
+    /// map.insert_unique(record(1, "sled-a", "SN-0001", 0)).unwrap();
+    ///
+    /// assert_eq!(map.get3("SN-0001").unwrap().uuid, 1);
+    /// assert!(map.get3("SN-0003").is_none());
+    /// # }
+    /// ```
+    pub fn get3<'a, Q>(&'a self, key3: &Q) -> Option<&'a T>
+    where
+        Q: Hash + Equivalent<T::K3<'a>> + ?Sized,
+    {
+        self.find3(key3)
+    }
+
+    /// Gets a mutable reference to the value associated with the given `key3`.
+    pub fn get3_mut<'a, Q>(&'a mut self, key3: &Q) -> Option<RefMut<'a, T, S>>
+    where
+        Q: Hash + Equivalent<T::K3<'a>> + ?Sized,
+    {
+        let (dormant_map, index) = {
+            let (map, dormant_map) = DormantMutRef::new(self);
+            let index = map.find3_index(key3)?;
+            (dormant_map, index)
+        };
+
+        // SAFETY: `map` is not used after this point.
+        let awakened_map = unsafe { dormant_map.awaken() };
+        let item = &mut awakened_map.items[index];
+        let state = awakened_map.tables.state.clone();
+        let hashes = awakened_map.tables.make_hashes(&item);
+        Some(RefMut::new(state, hashes, item))
+    }
+
+    /// Removes an item from the map by its `key3`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "default-hasher")] {
+    /// use iddqd::{QuadHashItem, QuadHashMap, quad_upcast};
+    ///
+    /// #[derive(Debug, PartialEq, Eq)]
+    /// struct Record {
+    ///     uuid: u128,
+    ///     name: String,
+    ///     serial: String,
+    ///     slot: u8,
+    /// }
+    ///
+    /// impl QuadHashItem for Record {
+    ///     type K1<'a> = u128;
+    ///     type K2<'a> = &'a str;
+    ///     type K3<'a> = &'a str;
+    ///     type K4<'a> = u8;
+    ///
+    ///     fn key1(&self) -> Self::K1<'_> {
+    ///         self.uuid
+    ///     }
+    ///     fn key2(&self) -> Self::K2<'_> {
+    ///         &self.name
+    ///     }
+    ///     fn key3(&self) -> Self::K3<'_> {
+    ///         &self.serial
+    ///     }
+    ///     fn key4(&self) -> Self::K4<'_> {
+    ///         self.slot
+    ///     }
+    ///     quad_upcast!();
+    /// }
+    ///
+    /// fn record(uuid: u128, name: &str, serial: &str, slot: u8) -> Record {
+    ///     Record {
+    ///         uuid,
+    ///         name: name.to_string(),
+    ///         serial: serial.to_string(),
+    ///         slot,
+    ///     }
+    /// }
+    ///
+    /// let mut map = QuadHashMap::new();
+    /// map.insert_unique(record(1, "sled-a", "SN-0001", 0)).unwrap();
+    ///
+    /// assert!(map.remove3("SN-0003").is_none());
+    /// let removed = map.remove3("SN-0001").unwrap();
+    /// assert_eq!(removed.uuid, 1);
+    /// assert!(map.is_empty());
+    /// # }
+    /// ```
+    pub fn remove3<'a, Q>(&'a mut self, key3: &Q) -> Option<T>
+    where
+        Q: Hash + Equivalent<T::K3<'a>> + ?Sized,
+    {
+        let (dormant_map, remove_index) = {
+            let (map, dormant_map) = DormantMutRef::new(self);
+            let remove_index = map.find3_index(key3)?;
+            (dormant_map, remove_index)
+        };
+
+        // SAFETY: `map` is not used after this point.
+        let awakened_map = unsafe { dormant_map.awaken() };
+
+        awakened_map.remove_by_index(remove_index)
+    }
+
+    /// Returns true if the map contains the given `key4`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "default-hasher")] {
+    /// use iddqd::{QuadHashItem, QuadHashMap, quad_upcast};
+    ///
+    /// #[derive(Debug, PartialEq, Eq)]
+    /// struct Record {
+    ///     uuid: u128,
+    ///     name: String,
+    ///     serial: String,
+    ///     slot: u8,
+    /// }
+    ///
+    /// impl QuadHashItem for Record {
+    ///     type K1<'a> = u128;
+    ///     type K2<'a> = &'a str;
+    ///     type K3<'a> = &'a str;
+    ///     type K4<'a> = u8;
+    ///
+    ///     fn key1(&self) -> Self::K1<'_> {
+    ///         self.uuid
+    ///     }
+    ///     fn key2(&self) -> Self::K2<'_> {
+    ///         &self.name
+    ///     }
+    ///     fn key3(&self) -> Self::K3<'_> {
+    ///         &self.serial
+    ///     }
+    ///     fn key4(&self) -> Self::K4<'_> {
+    ///         self.slot
+    ///     }
+    ///     quad_upcast!();
+    /// }
+    ///
+    /// fn record(uuid: u128, name: &str, serial: &str, slot: u8) -> Record {
+    ///     Record {
+    ///         uuid,
+    ///         name: name.to_string(),
+    ///         serial: serial.to_string(),
+    ///         slot,
+    ///     }
+    /// }
+    ///
+    /// let mut map = QuadHashMap::new();
+    /// map.insert_unique(record(1, "sled-a", "SN-0001", 0)).unwrap();
+    ///
+    /// assert!(map.contains_key4(&0));
+    /// assert!(!map.contains_key4(&2));
+    /// # }
+    /// ```
+    pub fn contains_key4<'a, Q>(&'a self, key4: &Q) -> bool
+    where
+        Q: Hash + Equivalent<T::K4<'a>> + ?Sized,
+    {
+        self.find4_index(key4).is_some()
+    }
+
+    /// Gets a reference to the value associated with the given `key4`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "default-hasher")] {
+    /// use iddqd::{QuadHashItem, QuadHashMap, quad_upcast};
+    ///
+    /// #[derive(Debug, PartialEq, Eq)]
+    /// struct Record {
+    ///     uuid: u128,
+    ///     name: String,
+    ///     serial: String,
+    ///     slot: u8,
+    /// }
+    ///
+    /// impl QuadHashItem for Record {
+    ///     type K1<'a> = u128;
+    ///     type K2<'a> = &'a str;
+    ///     type K3<'a> = &'a str;
+    ///     type K4<'a> = u8;
+    ///
+    ///     fn key1(&self) -> Self::K1<'_> {
+    ///         self.uuid
+    ///     }
+    ///     fn key2(&self) -> Self::K2<'_> {
+    ///         &self.name
+    ///     }
+    ///     fn key3(&self) -> Self::K3<'_> {
+    ///         &self.serial
+    ///     }
+    ///     fn key4(&self) -> Self::K4<'_> {
+    ///         self.slot
+    ///     }
+    ///     quad_upcast!();
+    /// }
+    ///
+    /// fn record(uuid: u128, name: &str, serial: &str, slot: u8) -> Record {
+    ///     Record {
+    ///         uuid,
+    ///         name: name.to_string(),
+    ///         serial: serial.to_string(),
+    ///         slot,
+    ///     }
+    /// }
+    ///
+    /// let mut map = QuadHashMap::new();
+    /// map.insert_unique(record(1, "sled-a", "SN-0001", 0)).unwrap();
+    ///
+    /// assert_eq!(map.get4(&0).unwrap().uuid, 1);
+    /// assert!(map.get4(&2).is_none());
+    /// # }
+    /// ```
+    pub fn get4<'a, Q>(&'a self, key4: &Q) -> Option<&'a T>
+    where
+        Q: Hash + Equivalent<T::K4<'a>> + ?Sized,
+    {
+        self.find4(key4)
+    }
+
+    /// Gets a mutable reference to the value associated with the given `key4`.
+    pub fn get4_mut<'a, Q>(&'a mut self, key4: &Q) -> Option<RefMut<'a, T, S>>
+    where
+        Q: Hash + Equivalent<T::K4<'a>> + ?Sized,
+    {
+        let (dormant_map, index) = {
+            let (map, dormant_map) = DormantMutRef::new(self);
+            let index = map.find4_index(key4)?;
+            (dormant_map, index)
+        };
+
+        // SAFETY: `map` is not used after this point.
+        let awakened_map = unsafe { dormant_map.awaken() };
+        let item = &mut awakened_map.items[index];
+        let state = awakened_map.tables.state.clone();
+        let hashes = awakened_map.tables.make_hashes(&item);
+        Some(RefMut::new(state, hashes, item))
+    }
+
+    /// Removes an item from the map by its `key4`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "default-hasher")] {
+    /// use iddqd::{QuadHashItem, QuadHashMap, quad_upcast};
+    ///
+    /// #[derive(Debug, PartialEq, Eq)]
+    /// struct Record {
+    ///     uuid: u128,
+    ///     name: String,
+    ///     serial: String,
+    ///     slot: u8,
+    /// }
+    ///
+    /// impl QuadHashItem for Record {
+    ///     type K1<'a> = u128;
+    ///     type K2<'a> = &'a str;
+    ///     type K3<'a> = &'a str;
+    ///     type K4<'a> = u8;
+    ///
+    ///     fn key1(&self) -> Self::K1<'_> {
+    ///         self.uuid
+    ///     }
+    ///     fn key2(&self) -> Self::K2<'_> {
+    ///         &self.name
+    ///     }
+    ///     fn key3(&self) -> Self::K3<'_> {
+    ///         &self.serial
+    ///     }
+    ///     fn key4(&self) -> Self::K4<'_> {
+    ///         self.slot
+    ///     }
+    ///     quad_upcast!();
+    /// }
+    ///
+    /// fn record(uuid: u128, name: &str, serial: &str, slot: u8) -> Record {
+    ///     Record {
+    ///         uuid,
+    ///         name: name.to_string(),
+    ///         serial: serial.to_string(),
+    ///         slot,
+    ///     }
+    /// }
+    ///
+    /// let mut map = QuadHashMap::new();
+    /// map.insert_unique(record(1, "sled-a", "SN-0001", 0)).unwrap();
+    ///
+    /// assert!(map.remove4(&2).is_none());
+    /// let removed = map.remove4(&0).unwrap();
+    /// assert_eq!(removed.uuid, 1);
+    /// assert!(map.is_empty());
+    /// # }
+    /// ```
+    pub fn remove4<'a, Q>(&'a mut self, key4: &Q) -> Option<T>
+    where
+        Q: Hash + Equivalent<T::K4<'a>> + ?Sized,
+    {
+        let (dormant_map, remove_index) = {
+            let (map, dormant_map) = DormantMutRef::new(self);
+            let remove_index = map.find4_index(key4)?;
+            (dormant_map, remove_index)
+        };
+
+        // SAFETY: `map` is not used after this point.
+        let awakened_map = unsafe { dormant_map.awaken() };
+
+        awakened_map.remove_by_index(remove_index)
+    }
+
+    /// Retrieves an entry by its keys.
+    ///
+    /// Due to borrow checker limitations, this always accepts owned keys rather
+    /// than a borrowed form of them.
+    ///
+    /// As with [`TriHashMap::entry`], the keys may correspond to up to four
+    /// different items, or only some of them may be present. For more
+    /// information, see the type-level documentation for [`Entry`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "default-hasher")] {
+    /// use iddqd::{QuadHashItem, QuadHashMap, quad_hash_map, quad_upcast};
+    ///
+    /// #[derive(Debug, PartialEq, Eq)]
+    /// struct Record {
+    ///     uuid: u128,
+    ///     name: String,
+    ///     serial: String,
+    ///     slot: u8,
+    /// }
+    ///
+    /// impl QuadHashItem for Record {
+    ///     type K1<'a> = u128;
+    ///     type K2<'a> = &'a str;
+    ///     type K3<'a> = &'a str;
+    ///     type K4<'a> = u8;
+    ///
+    ///     fn key1(&self) -> Self::K1<'_> {
+    ///         self.uuid
+    ///     }
+    ///     fn key2(&self) -> Self::K2<'_> {
+    ///         &self.name
+    ///     }
+    ///     fn key3(&self) -> Self::K3<'_> {
+    ///         &self.serial
+    ///     }
+    ///     fn key4(&self) -> Self::K4<'_> {
+    ///         self.slot
+    ///     }
+    ///     quad_upcast!();
+    /// }
+    ///
+    /// fn record(uuid: u128, name: &str, serial: &str, slot: u8) -> Record {
+    ///     Record {
+    ///         uuid,
+    ///         name: name.to_string(),
+    ///         serial: serial.to_string(),
+    ///         slot,
+    ///     }
+    /// }
+    ///
+    /// let mut map = QuadHashMap::new();
+    /// map.insert_unique(record(1, "sled-a", "SN-0001", 0)).unwrap();
+    ///
+    /// // Get an existing entry.
+    /// match map.entry(1, "sled-a", "SN-0001", 0) {
+    ///     quad_hash_map::Entry::Occupied(entry) => {
+    ///         assert_eq!(entry.get().as_unique().unwrap().slot, 0);
+    ///     }
+    ///     quad_hash_map::Entry::Vacant(_) => panic!("Should be occupied"),
+    /// }
+    ///
+    /// // Insert a new entry if it doesn't exist.
+    /// map.entry(2, "sled-b", "SN-0002", 1)
+    ///     .or_insert_with(|| record(2, "sled-b", "SN-0002", 1));
+    /// assert_eq!(map.get4(&1).unwrap().uuid, 2);
+    /// assert_eq!(map.len(), 2);
+    /// # }
+    /// ```
+    ///
+    /// [`TriHashMap::entry`]: crate::TriHashMap::entry
+    pub fn entry<'a>(
+        &'a mut self,
+        key1: T::K1<'_>,
+        key2: T::K2<'_>,
+        key3: T::K3<'_>,
+        key4: T::K4<'_>,
+    ) -> Entry<'a, T, S, A> {
+        // As with TriHashMap::entry, this accepts owned keys and upcasts them
+        // to a shorter lifetime, so that callers don't have the keys borrowed
+        // for the full 'a.
+        let (map, dormant_map) = DormantMutRef::new(self);
+        let key1 = T::upcast_key1(key1);
+        let key2 = T::upcast_key2(key2);
+        let key3 = T::upcast_key3(key3);
+        let key4 = T::upcast_key4(key4);
+        let (index1, index2, index3, index4) = {
+            // The indexes are explicitly typed to show that they have a
+            // trivial Drop impl that doesn't capture anything from map.
+            let index1: Option<ItemIndex> = map.tables.k1_to_item.find_index(
+                &map.tables.state,
+                &key1,
+                |index| map.items[index].key1(),
+            );
+            let index2: Option<ItemIndex> = map.tables.k2_to_item.find_index(
+                &map.tables.state,
+                &key2,
+                |index| map.items[index].key2(),
+            );
+            let index3: Option<ItemIndex> = map.tables.k3_to_item.find_index(
+                &map.tables.state,
+                &key3,
+                |index| map.items[index].key3(),
+            );
+            let index4: Option<ItemIndex> = map.tables.k4_to_item.find_index(
+                &map.tables.state,
+                &key4,
+                |index| map.items[index].key4(),
+            );
+            (index1, index2, index3, index4)
+        };
+
+        match (index1, index2, index3, index4) {
+            (Some(index1), Some(index2), Some(index3), Some(index4))
+                if index1 == index2 && index2 == index3 && index3 == index4 =>
+            {
+                // The item is already in the map.
+                Entry::Occupied(
+                    // SAFETY: `map` is not used after this point.
+                    unsafe {
+                        OccupiedEntry::new(
+                            dormant_map,
+                            EntryIndexes::Unique(index1),
+                        )
+                    },
+                )
+            }
+            (None, None, None, None) => {
+                let hashes = map
+                    .tables
+                    .make_hashes_for_keys::<T>(&key1, &key2, &key3, &key4);
+                Entry::Vacant(
+                    // SAFETY: `map` is not used after this point.
+                    unsafe { VacantEntry::new(dormant_map, hashes) },
+                )
+            }
+            (index1, index2, index3, index4) => Entry::Occupied(
+                // SAFETY: `map` is not used after this point.
+                unsafe {
+                    OccupiedEntry::new(
+                        dormant_map,
+                        EntryIndexes::NonUnique {
+                            index1,
+                            index2,
+                            index3,
+                            index4,
+                        },
+                    )
+                },
+            ),
+        }
+    }
+
+    /// Retains only the elements specified by the predicate.
+    ///
+    /// In other words, remove all items `T` for which `f(RefMut<T>)` returns
+    /// false. The elements are visited in an arbitrary order.
+    ///
+    /// The `RefMut<T, S>` wrapper allows mutable access to the item while
+    /// enforcing that the four keys remain unchanged. If a key is modified
+    /// during iteration, the method will panic.
+    ///
+    /// The remaining items are compacted afterwards, so that no holes are left
+    /// behind by the removed ones. The map's capacity is unchanged.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "default-hasher")] {
+    /// use iddqd::{QuadHashItem, QuadHashMap, quad_upcast};
+    ///
+    /// #[derive(Debug, PartialEq, Eq)]
+    /// struct Record {
+    ///     uuid: u128,
+    ///     name: String,
+    ///     serial: String,
+    ///     slot: u8,
+    /// }
+    ///
+    /// impl QuadHashItem for Record {
+    ///     type K1<'a> = u128;
+    ///     type K2<'a> = &'a str;
+    ///     type K3<'a> = &'a str;
+    ///     type K4<'a> = u8;
+    ///
+    ///     fn key1(&self) -> Self::K1<'_> {
+    ///         self.uuid
+    ///     }
+    ///     fn key2(&self) -> Self::K2<'_> {
+    ///         &self.name
+    ///     }
+    ///     fn key3(&self) -> Self::K3<'_> {
+    ///         &self.serial
+    ///     }
+    ///     fn key4(&self) -> Self::K4<'_> {
+    ///         self.slot
+    ///     }
+    ///     quad_upcast!();
+    /// }
+    ///
+    /// fn record(uuid: u128, name: &str, serial: &str, slot: u8) -> Record {
+    ///     Record {
+    ///         uuid,
+    ///         name: name.to_string(),
+    ///         serial: serial.to_string(),
+    ///         slot,
+    ///     }
+    /// }
+    ///
+    /// let mut map = QuadHashMap::new();
+    /// map.insert_unique(record(1, "sled-a", "SN-0001", 0)).unwrap();
+    /// map.insert_unique(record(2, "sled-b", "SN-0002", 1)).unwrap();
+    /// map.insert_unique(record(3, "sled-c", "SN-0003", 2)).unwrap();
+    ///
+    /// // Retain only items in even slots.
+    /// map.retain(|item| item.slot % 2 == 0);
+    ///
+    /// assert_eq!(map.len(), 2);
+    /// assert!(map.contains_key1(&1));
+    /// assert!(map.contains_key1(&3));
+    /// assert!(!map.contains_key2("sled-b"));
+    /// # }
+    /// ```
+    pub fn retain<'a, F>(&'a mut self, mut f: F)
+    where
+        F: for<'b> FnMut(RefMut<'b, T, S>) -> bool,
+    {
+        let hash_state = self.tables.state.clone();
+        let (_, mut dormant_items) = DormantMutRef::new(&mut self.items);
+        let mut removed_item = None;
+
+        self.tables.k1_to_item.retain(|index| {
+            // Drop the previously-removed item here, at the top of the next
+            // iteration, once the prior `k1_to_item` entry has been erased.
+            // See `TriHashMap::retain` for why.
+            drop(removed_item.take());
+
+            let (item, dormant_items) = {
+                // SAFETY: All uses of `items` ended in the previous iteration.
+                let items = unsafe { dormant_items.reborrow() };
+                let (items, dormant_items) = DormantMutRef::new(items);
+                let item: &'a mut T = items
+                    .get_mut(index)
+                    .expect("all indexes are present in self.items");
+                (item, dormant_items)
+            };
+
+            let (hashes, dormant_item) = {
+                let (item, dormant_item): (&'a mut T, _) =
+                    DormantMutRef::new(item);
+                // Use T::k1(item) rather than item.key() to force the key
+                // trait function to be called for T rather than &mut T.
+                let key1 = T::key1(item);
+                let key2 = T::key2(item);
+                let key3 = T::key3(item);
+                let key4 = T::key4(item);
+                (
+                    [
+                        MapHash::new(hash_state.hash_one(key1)),
+                        MapHash::new(hash_state.hash_one(key2)),
+                        MapHash::new(hash_state.hash_one(key3)),
+                        MapHash::new(hash_state.hash_one(key4)),
+                    ],
+                    dormant_item,
+                )
+            };
+
+            let hash2 = hashes[1].hash();
+            let hash3 = hashes[2].hash();
+            let hash4 = hashes[3].hash();
+            let retain = {
+                // SAFETY: The original item is no longer used after the second
+                // block above. dormant_items, from which item is derived, is
+                // currently dormant.
+                let item = unsafe { dormant_item.awaken() };
+
+                let ref_mut = RefMut::new(hash_state.clone(), hashes, item);
+                f(ref_mut)
+            };
+
+            if retain {
+                true
+            } else {
+                match self
+                    .tables
+                    .k2_to_item
+                    .find_entry_by_hash(hash2, |map2_index| map2_index == index)
+                {
+                    Ok(entry) => entry.remove(),
+                    Err(()) => self.tables.k2_to_item.remove_by_index(index),
+                }
+                match self
+                    .tables
+                    .k3_to_item
+                    .find_entry_by_hash(hash3, |map3_index| map3_index == index)
+                {
+                    Ok(entry) => entry.remove(),
+                    Err(()) => self.tables.k3_to_item.remove_by_index(index),
+                }
+                match self
+                    .tables
+                    .k4_to_item
+                    .find_entry_by_hash(hash4, |map4_index| map4_index == index)
+                {
+                    Ok(entry) => entry.remove(),
+                    Err(()) => self.tables.k4_to_item.remove_by_index(index),
+                }
+
+                // SAFETY: The original items is no longer used after the first
+                // block above, and item + dormant_item have been dropped after
+                // being used above. The work on the k2, k3 and k4 tables
+                // between them borrows only those tables, which are disjoint
+                // from `self.items`.
+                let items = unsafe { dormant_items.awaken() };
+                removed_item = Some(
+                    items
+                        .remove(index)
+                        .expect("all indexes are present in self.items"),
+                );
+
+                false
+            }
+        });
+
+        // Fill the holes left by removed items, so that the map stays
+        // compact.
+        //
+        // SAFETY: The closure above, which held the only reborrows of
+        // `dormant_items`, has returned.
+        let items = unsafe { dormant_items.awaken() };
+        let remap = items.compact();
+        if !remap.is_identity() {
+            self.tables.k1_to_item.remap_indexes(&remap);
+            self.tables.k2_to_item.remap_indexes(&remap);
+            self.tables.k3_to_item.remap_indexes(&remap);
+            self.tables.k4_to_item.remap_indexes(&remap);
+        }
+
+        // Anything in `removed_item` is implicitly dropped now.
+    }
+
+    /// Removes all items for which `f` returns true, returning them.
+    ///
+    /// This is the inverse of [`Self::retain`], except that the predicate
+    /// takes `&T` and the removed items are returned rather than dropped.
+    /// All four key tables are updated as items are removed. The items are
+    /// visited, and returned, in an arbitrary order.
+    ///
+    /// If `f` panics, no items are removed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "default-hasher")] {
+    /// use iddqd::{QuadHashItem, QuadHashMap, quad_upcast};
+    ///
+    /// #[derive(Debug, PartialEq, Eq)]
+    /// struct Record {
+    ///     uuid: u128,
+    ///     name: String,
+    ///     serial: String,
+    ///     slot: u8,
+    /// }
+    ///
+    /// impl QuadHashItem for Record {
+    ///     type K1<'a> = u128;
+    ///     type K2<'a> = &'a str;
+    ///     type K3<'a> = &'a str;
+    ///     type K4<'a> = u8;
+    ///
+    ///     fn key1(&self) -> Self::K1<'_> {
+    ///         self.uuid
+    ///     }
+    ///     fn key2(&self) -> Self::K2<'_> {
+    ///         &self.name
+    ///     }
+    ///     fn key3(&self) -> Self::K3<'_> {
+    ///         &self.serial
+    ///     }
+    ///     fn key4(&self) -> Self::K4<'_> {
+    ///         self.slot
+    ///     }
+    ///     quad_upcast!();
+    /// }
+    ///
+    /// fn record(uuid: u128, name: &str, serial: &str, slot: u8) -> Record {
+    ///     Record {
+    ///         uuid,
+    ///         name: name.to_string(),
+    ///         serial: serial.to_string(),
+    ///         slot,
+    ///     }
+    /// }
+    ///
+    /// let mut map = QuadHashMap::new();
+    /// map.insert_unique(record(1, "sled-a", "SN-0001", 0)).unwrap();
+    /// map.insert_unique(record(2, "sled-b", "SN-0002", 1)).unwrap();
+    ///
+    /// // Decommission everything in slot 0.
+    /// let removed = map.remove_where(|item| item.slot == 0);
+    /// assert_eq!(removed.len(), 1);
+    /// assert_eq!(removed[0].uuid, 1);
+    ///
+    /// // All four key tables have been updated.
+    /// assert_eq!(map.len(), 1);
+    /// assert!(map.get1(&1).is_none());
+    /// assert!(map.get2("sled-a").is_none());
+    /// assert!(map.get3("SN-0001").is_none());
+    /// assert!(map.get4(&0).is_none());
+    /// # }
+    /// ```
+    pub fn remove_where<F>(&mut self, mut f: F) -> Vec<T>
+    where
+        F: FnMut(&T) -> bool,
+    {
+        // Find all the matching indexes first, so that a panic in `f` leaves
+        // the map untouched.
+        let indexes: Vec<ItemIndex> = self
+            .items
+            .iter()
+            .filter_map(|(index, item)| f(item).then_some(index))
+            .collect();
+        indexes
+            .into_iter()
+            .map(|index| {
+                self.remove_by_index(index).expect("index is present in items")
+            })
+            .collect()
+    }
+
+    fn find1<'a, Q>(&'a self, k: &Q) -> Option<&'a T>
+    where
+        Q: Hash + Equivalent<T::K1<'a>> + ?Sized,
+    {
+        self.find1_index(k).map(|ix| &self.items[ix])
+    }
+
+    fn find1_index<'a, Q>(&'a self, k: &Q) -> Option<ItemIndex>
+    where
+        Q: Hash + Equivalent<T::K1<'a>> + ?Sized,
+    {
+        self.tables
+            .k1_to_item
+            .find_index(&self.tables.state, k, |index| self.items[index].key1())
+    }
+
+    fn find2<'a, Q>(&'a self, k: &Q) -> Option<&'a T>
+    where
+        Q: Hash + Equivalent<T::K2<'a>> + ?Sized,
+    {
+        self.find2_index(k).map(|ix| &self.items[ix])
+    }
+
+    fn find2_index<'a, Q>(&'a self, k: &Q) -> Option<ItemIndex>
+    where
+        Q: Hash + Equivalent<T::K2<'a>> + ?Sized,
+    {
+        self.tables
+            .k2_to_item
+            .find_index(&self.tables.state, k, |index| self.items[index].key2())
+    }
+
+    fn find3<'a, Q>(&'a self, k: &Q) -> Option<&'a T>
+    where
+        Q: Hash + Equivalent<T::K3<'a>> + ?Sized,
+    {
+        self.find3_index(k).map(|ix| &self.items[ix])
+    }
+
+    fn find3_index<'a, Q>(&'a self, k: &Q) -> Option<ItemIndex>
+    where
+        Q: Hash + Equivalent<T::K3<'a>> + ?Sized,
+    {
+        self.tables
+            .k3_to_item
+            .find_index(&self.tables.state, k, |index| self.items[index].key3())
+    }
+
+    fn find4<'a, Q>(&'a self, k: &Q) -> Option<&'a T>
+    where
+        Q: Hash + Equivalent<T::K4<'a>> + ?Sized,
+    {
+        self.find4_index(k).map(|ix| &self.items[ix])
+    }
+
+    fn find4_index<'a, Q>(&'a self, k: &Q) -> Option<ItemIndex>
+    where
+        Q: Hash + Equivalent<T::K4<'a>> + ?Sized,
+    {
+        self.tables
+            .k4_to_item
+            .find_index(&self.tables.state, k, |index| self.items[index].key4())
+    }
+
+    fn prepare_insert_overwrite(&self, value: &T) -> PreparedInsertOverwrite {
+        let key1 = value.key1();
+        let key2 = value.key2();
+        let key3 = value.key3();
+        let key4 = value.key4();
+
+        let index1 = self.find1_index(&key1);
+        let index2 = self.find2_index(&key2);
+        let index3 = self.find3_index(&key3);
+        let index4 = self.find4_index(&key4);
+        let hashes =
+            self.tables.make_hashes_for_keys::<T>(&key1, &key2, &key3, &key4);
+
+        let duplicates = PreparedDuplicate::from_indexes(
+            [index1, index2, index3, index4],
+            |index| self.prepare_duplicate(index),
+        );
+
+        PreparedInsertOverwrite {
+            index1,
+            index2,
+            index3,
+            index4,
+            duplicates,
+            hashes,
+        }
+    }
+
+    fn prepare_duplicate(&self, index: ItemIndex) -> PreparedDuplicate {
+        let item = &self.items[index];
+        let hashes = self.tables.make_hashes::<T>(item);
+
+        PreparedDuplicate { index, hashes }
+    }
+
+    fn try_reserve_insert_overwrite_commit(
+        &mut self,
+        needs_new_item_slot: bool,
+    ) -> Result<(), TryReserveError> {
+        if needs_new_item_slot {
+            self.items.try_reserve(1)?;
+        }
+
+        self.tables
+            .k1_to_item
+            .try_reserve(1)
+            .map_err(TryReserveError::from_hashbrown)?;
+
+        self.tables
+            .k2_to_item
+            .try_reserve(1)
+            .map_err(TryReserveError::from_hashbrown)?;
+
+        self.tables
+            .k3_to_item
+            .try_reserve(1)
+            .map_err(TryReserveError::from_hashbrown)?;
+
+        self.tables
+            .k4_to_item
+            .try_reserve(1)
+            .map_err(TryReserveError::from_hashbrown)?;
+
+        Ok(())
+    }
+
+    fn commit_insert_overwrite(
+        &mut self,
+        value: T,
+        prepared: PreparedInsertOverwrite,
+        duplicates: &mut Vec<T>,
+    ) -> ItemIndex {
+        // From here until insertion completes, do not call user code or
+        // allocate. The caller prepared hashes/indexes and reserved capacity.
+        for duplicate in prepared.duplicates {
+            duplicates.push(
+                self.remove_duplicate(duplicate)
+                    .expect("duplicate index was prepared"),
+            );
+        }
+
+        self.insert_unique_with_prepared_hashes(value, prepared.hashes)
+    }
+
+    fn insert_unique_with_prepared_hashes(
+        &mut self,
+        value: T,
+        hashes: [MapHash; 4],
+    ) -> ItemIndex {
+        let [hash1, hash2, hash3, hash4] = hashes;
+        let next_index = self.items.assert_can_grow().insert(value);
+
+        self.tables.k1_to_item.insert_prehashed_unchecked(hash1, next_index);
+        self.tables.k2_to_item.insert_prehashed_unchecked(hash2, next_index);
+        self.tables.k3_to_item.insert_prehashed_unchecked(hash3, next_index);
+        self.tables.k4_to_item.insert_prehashed_unchecked(hash4, next_index);
+
+        next_index
+    }
+
+    pub(super) fn insert_unique_impl(
+        &mut self,
+        value: T,
+    ) -> Result<ItemIndex, DuplicateItem<T, &T>> {
+        match self.insert_unique_or_dup_indexes(value) {
+            Ok(index) => Ok(index),
+            Err((value, duplicates)) => Err(DuplicateItem::__internal_new(
+                value,
+                duplicates.iter().map(|ix| &self.items[*ix]).collect(),
+            )),
+        }
+    }
+
+    fn insert_unique_or_dup_indexes(
+        &mut self,
+        value: T,
+    ) -> Result<ItemIndex, (T, BTreeSet<ItemIndex>)> {
+        let mut duplicates = BTreeSet::new();
+
+        // Check for duplicates *before* inserting the new item, because we
+        // don't want to partially insert the new item and then have to roll
+        // back.
+        let state = &self.tables.state;
+        let (e1, e2, e3, e4) = {
+            let k1 = value.key1();
+            let k2 = value.key2();
+            let k3 = value.key3();
+            let k4 = value.key4();
+
+            let e1 = detect_dup_or_insert(
+                self.tables
+                    .k1_to_item
+                    .entry(state, k1, |index| self.items[index].key1()),
+                &mut duplicates,
+            );
+            let e2 = detect_dup_or_insert(
+                self.tables
+                    .k2_to_item
+                    .entry(state, k2, |index| self.items[index].key2()),
+                &mut duplicates,
+            );
+            let e3 = detect_dup_or_insert(
+                self.tables
+                    .k3_to_item
+                    .entry(state, k3, |index| self.items[index].key3()),
+                &mut duplicates,
+            );
+            let e4 = detect_dup_or_insert(
+                self.tables
+                    .k4_to_item
+                    .entry(state, k4, |index| self.items[index].key4()),
+                &mut duplicates,
+            );
+            (e1, e2, e3, e4)
+        };
+
+        if !duplicates.is_empty() {
+            return Err((value, duplicates));
+        }
+
+        let next_index = self.items.assert_can_grow().insert(value);
+        // e1 through e4 are all Some because if any were None, duplicates
+        // would be non-empty, and we'd have bailed out earlier.
+        e1.unwrap().insert(next_index);
+        e2.unwrap().insert(next_index);
+        e3.unwrap().insert(next_index);
+        e4.unwrap().insert(next_index);
+
+        Ok(next_index)
+    }
+
+    fn remove_by_index(&mut self, remove_index: ItemIndex) -> Option<T> {
+        // As with TriHashMap, compute all four key hashes and look up all four
+        // table entries while `self.items` still holds the value, then remove
+        // from the tables and items in sequence. The lookups match by
+        // `ItemIndex` rather than by user `Eq`, and fall back to a linear scan
+        // if a `mem::forget` on a `RefMut` left a key in the wrong bucket.
+        let item = self.items.get(remove_index)?;
+        let state = &self.tables.state;
+        let hash1 = state.hash_one(item.key1());
+        let hash2 = state.hash_one(item.key2());
+        let hash3 = state.hash_one(item.key3());
+        let hash4 = state.hash_one(item.key4());
+        match self
+            .tables
+            .k1_to_item
+            .find_entry_by_hash(hash1, |index| index == remove_index)
+        {
+            Ok(entry) => entry.remove(),
+            Err(()) => self.tables.k1_to_item.remove_by_index(remove_index),
+        }
+        match self
+            .tables
+            .k2_to_item
+            .find_entry_by_hash(hash2, |index| index == remove_index)
+        {
+            Ok(entry) => entry.remove(),
+            Err(()) => self.tables.k2_to_item.remove_by_index(remove_index),
+        }
+        match self
+            .tables
+            .k3_to_item
+            .find_entry_by_hash(hash3, |index| index == remove_index)
+        {
+            Ok(entry) => entry.remove(),
+            Err(()) => self.tables.k3_to_item.remove_by_index(remove_index),
+        }
+        match self
+            .tables
+            .k4_to_item
+            .find_entry_by_hash(hash4, |index| index == remove_index)
+        {
+            Ok(entry) => entry.remove(),
+            Err(()) => self.tables.k4_to_item.remove_by_index(remove_index),
+        }
+        Some(
+            self.items
+                .remove(remove_index)
+                .expect("items[remove_index] was Occupied above"),
+        )
+    }
+
+    /// Removes the item at `duplicate`, using already-computed key hashes when
+    /// possible.
+    ///
+    /// The caller must ensure that all user-controlled key extraction and
+    /// hashing for the item has already completed, and that the item has not
+    /// changed since. See `TriHashMap::remove_duplicate` for details.
+    fn remove_duplicate(&mut self, duplicate: PreparedDuplicate) -> Option<T> {
+        let _ = self.items.get(duplicate.index)?;
+
+        let [hash1, hash2, hash3, hash4] = duplicate.hashes;
+
+        match self
+            .tables
+            .k1_to_item
+            .find_entry_by_hash(hash1.hash(), |index| index == duplicate.index)
+        {
+            Ok(entry) => entry.remove(),
+            Err(()) => self.tables.k1_to_item.remove_by_index(duplicate.index),
+        }
+
+        match self
+            .tables
+            .k2_to_item
+            .find_entry_by_hash(hash2.hash(), |index| index == duplicate.index)
+        {
+            Ok(entry) => entry.remove(),
+            Err(()) => self.tables.k2_to_item.remove_by_index(duplicate.index),
+        }
+
+        match self
+            .tables
+            .k3_to_item
+            .find_entry_by_hash(hash3.hash(), |index| index == duplicate.index)
+        {
+            Ok(entry) => entry.remove(),
+            Err(()) => self.tables.k3_to_item.remove_by_index(duplicate.index),
+        }
+
+        match self
+            .tables
+            .k4_to_item
+            .find_entry_by_hash(hash4.hash(), |index| index == duplicate.index)
+        {
+            Ok(entry) => entry.remove(),
+            Err(()) => self.tables.k4_to_item.remove_by_index(duplicate.index),
+        }
+
+        Some(
+            self.items
+                .remove(duplicate.index)
+                .expect("items[duplicate.index] was Occupied above"),
+        )
+    }
+
+    fn prepare_entry_index_removal(
+        &self,
+        indexes: EntryIndexes,
+    ) -> Vec<PreparedDuplicate> {
+        match indexes {
+            EntryIndexes::Unique(index) => {
+                PreparedDuplicate::from_indexes([Some(index)], |index| {
+                    self.prepare_duplicate(index)
+                })
+            }
+            EntryIndexes::NonUnique { index1, index2, index3, index4 } => {
+                PreparedDuplicate::from_indexes(
+                    [index1, index2, index3, index4],
+                    |index| self.prepare_duplicate(index),
+                )
+            }
+        }
+    }
+
+    pub(super) fn get_by_entry_index(
+        &self,
+        indexes: EntryIndexes,
+    ) -> OccupiedEntryRef<'_, T> {
+        match indexes {
+            EntryIndexes::Unique(index) => OccupiedEntryRef::Unique(
+                self.items.get(index).expect("index is valid"),
+            ),
+            EntryIndexes::NonUnique { index1, index2, index3, index4 } => {
+                let by_key1 = index1
+                    .map(|k| self.items.get(k).expect("key1 index is valid"));
+                let by_key2 = index2
+                    .map(|k| self.items.get(k).expect("key2 index is valid"));
+                let by_key3 = index3
+                    .map(|k| self.items.get(k).expect("key3 index is valid"));
+                let by_key4 = index4
+                    .map(|k| self.items.get(k).expect("key4 index is valid"));
+                OccupiedEntryRef::NonUnique {
+                    by_key1,
+                    by_key2,
+                    by_key3,
+                    by_key4,
+                }
+            }
+        }
+    }
+
+    pub(super) fn get_by_entry_index_mut(
+        &mut self,
+        indexes: EntryIndexes,
+    ) -> OccupiedEntryMut<'_, T, S> {
+        let state = self.tables.state.clone();
+        // get_disjoint_mut returns None for missing keys, and for keys that
+        // point to the same item as an earlier key.
+        let [item1, item2, item3, item4] =
+            self.items.get_disjoint_mut(indexes.disjoint_keys());
+        let tables = &self.tables;
+
+        if indexes.is_unique() {
+            let item = item1.expect("index is valid");
+            let hashes = tables.make_hashes::<T>(item);
+            OccupiedEntryMut::Unique(RefMut::new(state, hashes, item))
+        } else {
+            OccupiedEntryMut::NonUnique {
+                by_key1: item1.map(|item| {
+                    let hashes = tables.make_hashes::<T>(item);
+                    RefMut::new(state.clone(), hashes, item)
+                }),
+                by_key2: item2.map(|item| {
+                    let hashes = tables.make_hashes::<T>(item);
+                    RefMut::new(state.clone(), hashes, item)
+                }),
+                by_key3: item3.map(|item| {
+                    let hashes = tables.make_hashes::<T>(item);
+                    RefMut::new(state.clone(), hashes, item)
+                }),
+                by_key4: item4.map(|item| {
+                    let hashes = tables.make_hashes::<T>(item);
+                    RefMut::new(state.clone(), hashes, item)
+                }),
+            }
+        }
+    }
+
+    pub(super) fn get_by_index_mut(
+        &mut self,
+        index: ItemIndex,
+    ) -> Option<RefMut<'_, T, S>> {
+        let item = self.items.get_mut(index)?;
+        let state = self.tables.state.clone();
+        let hashes = self.tables.make_hashes::<T>(item);
+        Some(RefMut::new(state, hashes, item))
+    }
+
+    pub(super) fn remove_by_entry_index(
+        &mut self,
+        indexes: EntryIndexes,
+    ) -> Vec<T> {
+        let prepared = self.prepare_entry_index_removal(indexes);
+        let mut old_items = Vec::with_capacity(prepared.len());
+
+        for duplicate in prepared {
+            old_items.push(
+                self.remove_duplicate(duplicate)
+                    .expect("prepared duplicate index was present"),
+            );
+        }
+
+        old_items
+    }
+
+    pub(super) fn replace_at_indexes(
+        &mut self,
+        indexes: EntryIndexes,
+        value: T,
+    ) -> (ItemIndex, Vec<T>) {
+        match indexes {
+            EntryIndexes::Unique(index) => {
+                {
+                    let old_item = &self.items[index];
+                    if old_item.key1() != value.key1() {
+                        panic!("key1 mismatch");
+                    }
+                    if old_item.key2() != value.key2() {
+                        panic!("key2 mismatch");
+                    }
+                    if old_item.key3() != value.key3() {
+                        panic!("key3 mismatch");
+                    }
+                    if old_item.key4() != value.key4() {
+                        panic!("key4 mismatch");
+                    }
+                }
+
+                let mut old_items = Vec::with_capacity(1);
+                let old_item = self.items.replace(index, value);
+                old_items.push(old_item);
+
+                (index, old_items)
+            }
+            EntryIndexes::NonUnique { index1, index2, index3, index4 } => {
+                let prepared = self.prepare_insert_overwrite(&value);
+
+                if prepared.index1 != index1 {
+                    panic!("key1 mismatch");
+                }
+                if prepared.index2 != index2 {
+                    panic!("key2 mismatch");
+                }
+                if prepared.index3 != index3 {
+                    panic!("key3 mismatch");
+                }
+                if prepared.index4 != index4 {
+                    panic!("key4 mismatch");
+                }
+
+                let mut old_items =
+                    Vec::with_capacity(prepared.duplicate_count());
+
+                self.try_reserve_insert_overwrite_commit(
+                    prepared.needs_new_item_slot(),
+                )
+                .expect("reserved item slot");
+
+                let next_index = self.commit_insert_overwrite(
+                    value,
+                    prepared,
+                    &mut old_items,
+                );
+
+                (next_index, old_items)
+            }
+        }
+    }
+}
+
+impl<'a, T, S, A: Allocator> fmt::Debug for QuadHashMap<T, S, A>
+where
+    T: QuadHashItem + fmt::Debug,
+    T::K1<'a>: fmt::Debug,
+    T::K2<'a>: fmt::Debug,
+    T::K3<'a>: fmt::Debug,
+    T::K4<'a>: fmt::Debug,
+    T: 'a,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut map = f.debug_map();
+        for item in self.items.values() {
+            let key: KeyMap<'_, T> = KeyMap {
+                key1: item.key1(),
+                key2: item.key2(),
+                key3: item.key3(),
+                key4: item.key4(),
+            };
+
+            // SAFETY:
+            //
+            // * Lifetime extension: for a type T and two lifetime params 'a and
+            //   'b, T<'a> and T<'b> aren't guaranteed to have the same layout,
+            //   but (a) that is true today and (b) it would be shocking and
+            //   break half the Rust ecosystem if that were to change in the
+            //   future.
+            // * We only use key within the scope of this block before immediately
+            //   dropping it. In particular, map.entry calls key.fmt() without
+            //   holding a reference to it.
+            let key: KeyMap<'a, T> = unsafe {
+                core::mem::transmute::<KeyMap<'_, T>, KeyMap<'a, T>>(key)
+            };
+
+            map.entry(&key, item);
+        }
+        map.finish()
+    }
+}
+
+struct KeyMap<'a, T: QuadHashItem + 'a> {
+    key1: T::K1<'a>,
+    key2: T::K2<'a>,
+    key3: T::K3<'a>,
+    key4: T::K4<'a>,
+}
+
+impl<'a, T: QuadHashItem> fmt::Debug for KeyMap<'a, T>
+where
+    T::K1<'a>: fmt::Debug,
+    T::K2<'a>: fmt::Debug,
+    T::K3<'a>: fmt::Debug,
+    T::K4<'a>: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // As with TriHashMap, show "{k1: abc, k2: xyz, k3: def, k4: uvw}"
+        // rather than a tuple, which would suggest maps of tuples.
+        f.debug_map()
+            .entry(&StrDisplayAsDebug("k1"), &self.key1)
+            .entry(&StrDisplayAsDebug("k2"), &self.key2)
+            .entry(&StrDisplayAsDebug("k3"), &self.key3)
+            .entry(&StrDisplayAsDebug("k4"), &self.key4)
+            .finish()
+    }
+}
+
+impl<T: QuadHashItem + PartialEq, S: Clone + BuildHasher, A: Allocator>
+    PartialEq for QuadHashMap<T, S, A>
+{
+    fn eq(&self, other: &Self) -> bool {
+        // As with TriHashMap, two maps are equal if they hold the same items,
+        // regardless of order. Check that each key in one map points to the
+        // same item in the other map.
+        if self.items.len() != other.items.len() {
+            return false;
+        }
+
+        for item in self.items.values() {
+            let Some(other_ix1) = other.find1_index(&item.key1()) else {
+                return false;
+            };
+            let Some(other_ix2) = other.find2_index(&item.key2()) else {
+                return false;
+            };
+            let Some(other_ix3) = other.find3_index(&item.key3()) else {
+                return false;
+            };
+            let Some(other_ix4) = other.find4_index(&item.key4()) else {
+                return false;
+            };
+
+            if other_ix1 != other_ix2
+                || other_ix1 != other_ix3
+                || other_ix1 != other_ix4
+            {
+                // All the keys were present but they didn't point to the same
+                // item.
+                return false;
+            }
+
+            // Check that the other map's item is the same as this map's
+            // item. (This is what we use the `PartialEq` bound on T for.)
+            let other_item = &other.items[other_ix1];
+            if item != other_item {
+                return false;
+            }
+        }
+
+        true
+    }
+}
+
+// The Eq bound on T ensures that the QuadHashMap forms an equivalence class.
+impl<T: QuadHashItem + Eq, S: Clone + BuildHasher, A: Allocator> Eq
+    for QuadHashMap<T, S, A>
+{
+}
+
+/// The hash of a `QuadHashMap` doesn't depend on the order its items are stored
+/// in, so it is consistent with the `PartialEq` implementation.
+///
+/// Each item is hashed with a fixed-seed hasher, independent of the map's own
+/// hasher, and the results are combined with a commutative sum. As a result,
+/// equal maps hash the same even if they use hashers with different seeds.
+impl<T: QuadHashItem + Hash, S: Clone + BuildHasher, A: Allocator> Hash
+    for QuadHashMap<T, S, A>
+{
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.items.hash_unordered(state);
+    }
+}
+
+/// The `Extend` implementation overwrites duplicates.
+impl<T: QuadHashItem, S: Clone + BuildHasher, A: Allocator> Extend<T>
+    for QuadHashMap<T, S, A>
+{
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        // Keys may already be present in the map, or multiple times in the
+        // iterator. Reserve the entire hint lower bound if the map is empty.
+        // Otherwise reserve half the hint (rounded up), so the map will only
+        // resize twice in the worst case.
+        let iter = iter.into_iter();
+        let reserve = if self.is_empty() {
+            iter.size_hint().0
+        } else {
+            iter.size_hint().0.div_ceil(2)
+        };
+        self.reserve(reserve);
+        for item in iter {
+            self.insert_overwrite(item);
+        }
+    }
+}
+
+impl<'a, T: QuadHashItem, S: Clone + BuildHasher, A: Allocator> IntoIterator
+    for &'a QuadHashMap<T, S, A>
+{
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, T: QuadHashItem, S: Clone + BuildHasher, A: Allocator> IntoIterator
+    for &'a mut QuadHashMap<T, S, A>
+{
+    type Item = RefMut<'a, T, S>;
+    type IntoIter = IterMut<'a, T, S, A>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+impl<T: QuadHashItem, S: Clone + BuildHasher, A: Allocator> IntoIterator
+    for QuadHashMap<T, S, A>
+{
+    type Item = T;
+    type IntoIter = IntoIter<T, A>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter::new(self.items)
+    }
+}
+
+/// The `FromIterator` implementation for `QuadHashMap` overwrites duplicate
+/// items.
+impl<T: QuadHashItem, S: Default + Clone + BuildHasher, A: Default + Allocator>
+    FromIterator<T> for QuadHashMap<T, S, A>
+{
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut map = QuadHashMap::default();
+        map.extend(iter);
+        map
+    }
+}
@@ -0,0 +1,145 @@
+use super::{RefMut, tables::QuadHashMapTables};
+use crate::{
+    DefaultHashBuilder, QuadHashItem,
+    support::{
+        alloc::{Allocator, Global},
+        item_set::{self, ItemSet},
+    },
+};
+use core::{hash::BuildHasher, iter::FusedIterator};
+
+/// An iterator over the elements of a [`QuadHashMap`] by shared reference.
+/// Created by [`QuadHashMap::iter`].
+///
+/// Similar to [`HashMap`], the iteration order is arbitrary and not guaranteed
+/// to be stable.
+///
+/// [`QuadHashMap`]: crate::QuadHashMap
+/// [`QuadHashMap::iter`]: crate::QuadHashMap::iter
+/// [`HashMap`]: std::collections::HashMap
+#[derive(Clone, Debug, Default)]
+pub struct Iter<'a, T: QuadHashItem> {
+    inner: item_set::Values<'a, T>,
+}
+
+impl<'a, T: QuadHashItem> Iter<'a, T> {
+    pub(crate) fn new<A: Allocator>(items: &'a ItemSet<T, A>) -> Self {
+        Self { inner: items.values() }
+    }
+}
+
+impl<'a, T: QuadHashItem> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next()
+    }
+}
+
+impl<T: QuadHashItem> ExactSizeIterator for Iter<'_, T> {
+    #[inline]
+    fn len(&self) -> usize {
+        self.inner.len()
+    }
+}
+
+impl<T: QuadHashItem> FusedIterator for Iter<'_, T> {}
+
+/// An iterator over the elements of a [`QuadHashMap`] by mutable reference.
+/// Created by [`QuadHashMap::iter_mut`].
+///
+/// This iterator returns [`RefMut`] instances.
+///
+/// Similar to [`HashMap`], the iteration order is arbitrary and not guaranteed
+/// to be stable.
+///
+/// [`QuadHashMap`]: crate::QuadHashMap
+/// [`QuadHashMap::iter_mut`]: crate::QuadHashMap::iter_mut
+/// [`HashMap`]: std::collections::HashMap
+#[derive(Debug)]
+pub struct IterMut<
+    'a,
+    T: QuadHashItem,
+    S: Clone + BuildHasher = DefaultHashBuilder,
+    A: Allocator = Global,
+> {
+    tables: &'a QuadHashMapTables<S, A>,
+    inner: item_set::ValuesMut<'a, T>,
+}
+
+impl<'a, T: QuadHashItem, S: Clone + BuildHasher, A: Allocator>
+    IterMut<'a, T, S, A>
+{
+    pub(super) fn new(
+        tables: &'a QuadHashMapTables<S, A>,
+        items: &'a mut ItemSet<T, A>,
+    ) -> Self {
+        Self { tables, inner: items.values_mut() }
+    }
+}
+
+impl<'a, T: QuadHashItem, S: Clone + BuildHasher, A: Allocator> Iterator
+    for IterMut<'a, T, S, A>
+{
+    type Item = RefMut<'a, T, S>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let next = self.inner.next()?;
+        let hashes = self.tables.make_hashes(next);
+        Some(RefMut::new(self.tables.state.clone(), hashes, next))
+    }
+}
+
+impl<T: QuadHashItem, S: Clone + BuildHasher, A: Allocator> ExactSizeIterator
+    for IterMut<'_, T, S, A>
+{
+    #[inline]
+    fn len(&self) -> usize {
+        self.inner.len()
+    }
+}
+
+impl<T: QuadHashItem, S: Clone + BuildHasher, A: Allocator> FusedIterator
+    for IterMut<'_, T, S, A>
+{
+}
+
+/// An iterator over the elements of a [`QuadHashMap`] by ownership. Created by
+/// [`QuadHashMap::into_iter`].
+///
+/// Similar to [`HashMap`], the iteration order is arbitrary and not guaranteed
+/// to be stable.
+///
+/// [`QuadHashMap`]: crate::QuadHashMap
+/// [`QuadHashMap::into_iter`]: crate::QuadHashMap::into_iter
+/// [`HashMap`]: std::collections::HashMap
+#[derive(Debug)]
+pub struct IntoIter<T: QuadHashItem, A: Allocator = Global> {
+    inner: item_set::IntoValues<T, A>,
+}
+
+impl<T: QuadHashItem, A: Allocator> IntoIter<T, A> {
+    pub(crate) fn new(items: ItemSet<T, A>) -> Self {
+        Self { inner: items.into_values() }
+    }
+}
+
+impl<T: QuadHashItem, A: Allocator> Iterator for IntoIter<T, A> {
+    type Item = T;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next()
+    }
+}
+
+impl<T: QuadHashItem, A: Allocator> ExactSizeIterator for IntoIter<T, A> {
+    #[inline]
+    fn len(&self) -> usize {
+        self.inner.len()
+    }
+}
+
+impl<T: QuadHashItem, A: Allocator> FusedIterator for IntoIter<T, A> {}
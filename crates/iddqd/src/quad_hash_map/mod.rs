@@ -0,0 +1,19 @@
+//! A hash map where values are uniquely indexed by four keys.
+//!
+//! For more information, see [`QuadHashMap`].
+
+mod entry;
+mod entry_indexes;
+pub(crate) mod imp;
+mod iter;
+mod ref_mut;
+mod tables;
+pub(crate) mod trait_defs;
+
+pub use entry::{
+    Entry, OccupiedEntry, OccupiedEntryMut, OccupiedEntryRef, VacantEntry,
+};
+pub use imp::QuadHashMap;
+pub use iter::{IntoIter, Iter, IterMut};
+pub use ref_mut::RefMut;
+pub use trait_defs::QuadHashItem;
@@ -0,0 +1,120 @@
+use crate::{
+    QuadHashItem,
+    errors::TryReserveError,
+    internal::{ValidateCompact, ValidationError},
+    support::{
+        alloc::{Allocator, Global, global_alloc},
+        hash_table::MapHashTable,
+        map_hash::MapHash,
+    },
+};
+use core::hash::BuildHasher;
+
+#[derive(Clone, Debug, Default)]
+pub(super) struct QuadHashMapTables<S, A: Allocator> {
+    pub(super) state: S,
+    pub(super) k1_to_item: MapHashTable<A>,
+    pub(super) k2_to_item: MapHashTable<A>,
+    pub(super) k3_to_item: MapHashTable<A>,
+    pub(super) k4_to_item: MapHashTable<A>,
+}
+
+impl<S: BuildHasher> QuadHashMapTables<S, Global> {
+    pub(super) const fn with_hasher(hasher: S) -> Self {
+        Self {
+            state: hasher,
+            k1_to_item: MapHashTable::new_in(global_alloc()),
+            k2_to_item: MapHashTable::new_in(global_alloc()),
+            k3_to_item: MapHashTable::new_in(global_alloc()),
+            k4_to_item: MapHashTable::new_in(global_alloc()),
+        }
+    }
+}
+
+impl<S: BuildHasher, A: Clone + Allocator> QuadHashMapTables<S, A> {
+    pub(super) fn with_capacity_and_hasher_in(
+        capacity: usize,
+        hasher: S,
+        alloc: A,
+    ) -> Self {
+        Self {
+            state: hasher,
+            k1_to_item: MapHashTable::with_capacity_in(capacity, alloc.clone()),
+            k2_to_item: MapHashTable::with_capacity_in(capacity, alloc.clone()),
+            k3_to_item: MapHashTable::with_capacity_in(capacity, alloc.clone()),
+            k4_to_item: MapHashTable::with_capacity_in(capacity, alloc),
+        }
+    }
+}
+
+impl<S: Clone + BuildHasher, A: Allocator> QuadHashMapTables<S, A> {
+    pub(super) fn validate(
+        &self,
+        expected_len: usize,
+        compactness: ValidateCompact,
+    ) -> Result<(), ValidationError> {
+        // Check that all the maps are of the right size.
+        self.k1_to_item.validate(expected_len, compactness).map_err(
+            |error| ValidationError::Table { name: "k1_to_table", error },
+        )?;
+        self.k2_to_item.validate(expected_len, compactness).map_err(
+            |error| ValidationError::Table { name: "k2_to_table", error },
+        )?;
+        self.k3_to_item.validate(expected_len, compactness).map_err(
+            |error| ValidationError::Table { name: "k3_to_table", error },
+        )?;
+        self.k4_to_item.validate(expected_len, compactness).map_err(
+            |error| ValidationError::Table { name: "k4_to_table", error },
+        )?;
+
+        Ok(())
+    }
+
+    pub(super) fn make_hashes<T: QuadHashItem>(
+        &self,
+        item: &T,
+    ) -> [MapHash; 4] {
+        let k1 = item.key1();
+        let k2 = item.key2();
+        let k3 = item.key3();
+        let k4 = item.key4();
+
+        self.make_hashes_for_keys::<T>(&k1, &k2, &k3, &k4)
+    }
+
+    pub(super) fn make_hashes_for_keys<T: QuadHashItem>(
+        &self,
+        key1: &T::K1<'_>,
+        key2: &T::K2<'_>,
+        key3: &T::K3<'_>,
+        key4: &T::K4<'_>,
+    ) -> [MapHash; 4] {
+        [
+            self.k1_to_item.compute_hash(&self.state, key1),
+            self.k2_to_item.compute_hash(&self.state, key2),
+            self.k3_to_item.compute_hash(&self.state, key3),
+            self.k4_to_item.compute_hash(&self.state, key4),
+        ]
+    }
+}
+
+impl<S, A: Allocator> QuadHashMapTables<S, A> {
+    pub(super) fn try_reserve(
+        &mut self,
+        additional: usize,
+    ) -> Result<(), TryReserveError> {
+        self.k1_to_item
+            .try_reserve(additional)
+            .map_err(TryReserveError::from_hashbrown)?;
+        self.k2_to_item
+            .try_reserve(additional)
+            .map_err(TryReserveError::from_hashbrown)?;
+        self.k3_to_item
+            .try_reserve(additional)
+            .map_err(TryReserveError::from_hashbrown)?;
+        self.k4_to_item
+            .try_reserve(additional)
+            .map_err(TryReserveError::from_hashbrown)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,291 @@
+//! Trait definitions for `QuadHashMap`.
+
+use alloc::{boxed::Box, rc::Rc, sync::Arc};
+use core::hash::Hash;
+
+/// An item in a [`QuadHashMap`].
+///
+/// This trait is used to define the keys.
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(feature = "default-hasher")] {
+/// use iddqd::{QuadHashItem, QuadHashMap, quad_upcast};
+///
+/// // Define a struct with four keys.
+/// #[derive(Debug, PartialEq, Eq, Hash)]
+/// struct Record {
+///     uuid: u128,
+///     name: String,
+///     serial: String,
+///     slot: u8,
+/// }
+///
+/// // Implement QuadHashItem for the struct.
+/// impl QuadHashItem for Record {
+///     type K1<'a> = u128;
+///     type K2<'a> = &'a str;
+///     type K3<'a> = &'a str;
+///     type K4<'a> = u8;
+///
+///     fn key1(&self) -> Self::K1<'_> {
+///         self.uuid
+///     }
+///
+///     fn key2(&self) -> Self::K2<'_> {
+///         &self.name
+///     }
+///
+///     fn key3(&self) -> Self::K3<'_> {
+///         &self.serial
+///     }
+///
+///     fn key4(&self) -> Self::K4<'_> {
+///         self.slot
+///     }
+///
+///     quad_upcast!();
+/// }
+///
+/// // Create a QuadHashMap and insert items.
+/// let mut map = QuadHashMap::new();
+/// map.insert_unique(Record {
+///     uuid: 1,
+///     name: "sled-a".to_string(),
+///     serial: "SN-0001".to_string(),
+///     slot: 0,
+/// })
+/// .unwrap();
+/// map.insert_unique(Record {
+///     uuid: 2,
+///     name: "sled-b".to_string(),
+///     serial: "SN-0002".to_string(),
+///     slot: 1,
+/// })
+/// .unwrap();
+/// # }
+/// ```
+///
+/// [`QuadHashMap`]: crate::QuadHashMap
+pub trait QuadHashItem {
+    /// The first key type.
+    type K1<'a>: Eq + Hash
+    where
+        Self: 'a;
+
+    /// The second key type.
+    type K2<'a>: Eq + Hash
+    where
+        Self: 'a;
+
+    /// The third key type.
+    type K3<'a>: Eq + Hash
+    where
+        Self: 'a;
+
+    /// The fourth key type.
+    type K4<'a>: Eq + Hash
+    where
+        Self: 'a;
+
+    /// Retrieves the first key.
+    fn key1(&self) -> Self::K1<'_>;
+
+    /// Retrieves the second key.
+    fn key2(&self) -> Self::K2<'_>;
+
+    /// Retrieves the third key.
+    fn key3(&self) -> Self::K3<'_>;
+
+    /// Retrieves the fourth key.
+    fn key4(&self) -> Self::K4<'_>;
+
+    /// Upcasts the first key to a shorter lifetime, in effect asserting that
+    /// the lifetime `'a` on [`QuadHashItem::K1`] is covariant.
+    ///
+    /// Typically implemented via the [`quad_upcast`] macro.
+    ///
+    /// [`quad_upcast`]: crate::quad_upcast
+    fn upcast_key1<'short, 'long: 'short>(
+        long: Self::K1<'long>,
+    ) -> Self::K1<'short>;
+
+    /// Upcasts the second key to a shorter lifetime, in effect asserting that
+    /// the lifetime `'a` on [`QuadHashItem::K2`] is covariant.
+    ///
+    /// Typically implemented via the [`quad_upcast`] macro.
+    ///
+    /// [`quad_upcast`]: crate::quad_upcast
+    fn upcast_key2<'short, 'long: 'short>(
+        long: Self::K2<'long>,
+    ) -> Self::K2<'short>;
+
+    /// Upcasts the third key to a shorter lifetime, in effect asserting that
+    /// the lifetime `'a` on [`QuadHashItem::K3`] is covariant.
+    ///
+    /// Typically implemented via the [`quad_upcast`] macro.
+    ///
+    /// [`quad_upcast`]: crate::quad_upcast
+    fn upcast_key3<'short, 'long: 'short>(
+        long: Self::K3<'long>,
+    ) -> Self::K3<'short>;
+
+    /// Upcasts the fourth key to a shorter lifetime, in effect asserting that
+    /// the lifetime `'a` on [`QuadHashItem::K4`] is covariant.
+    ///
+    /// Typically implemented via the [`quad_upcast`] macro.
+    ///
+    /// [`quad_upcast`]: crate::quad_upcast
+    fn upcast_key4<'short, 'long: 'short>(
+        long: Self::K4<'long>,
+    ) -> Self::K4<'short>;
+}
+
+macro_rules! impl_for_ref {
+    ($type:ty) => {
+        impl<'b, T: 'b + ?Sized + QuadHashItem> QuadHashItem for $type {
+            type K1<'a>
+                = T::K1<'a>
+            where
+                Self: 'a;
+            type K2<'a>
+                = T::K2<'a>
+            where
+                Self: 'a;
+            type K3<'a>
+                = T::K3<'a>
+            where
+                Self: 'a;
+            type K4<'a>
+                = T::K4<'a>
+            where
+                Self: 'a;
+
+            fn key1(&self) -> Self::K1<'_> {
+                (**self).key1()
+            }
+
+            fn key2(&self) -> Self::K2<'_> {
+                (**self).key2()
+            }
+
+            fn key3(&self) -> Self::K3<'_> {
+                (**self).key3()
+            }
+
+            fn key4(&self) -> Self::K4<'_> {
+                (**self).key4()
+            }
+
+            fn upcast_key1<'short, 'long: 'short>(
+                long: Self::K1<'long>,
+            ) -> Self::K1<'short>
+            where
+                Self: 'long,
+            {
+                T::upcast_key1(long)
+            }
+
+            fn upcast_key2<'short, 'long: 'short>(
+                long: Self::K2<'long>,
+            ) -> Self::K2<'short>
+            where
+                Self: 'long,
+            {
+                T::upcast_key2(long)
+            }
+
+            fn upcast_key3<'short, 'long: 'short>(
+                long: Self::K3<'long>,
+            ) -> Self::K3<'short>
+            where
+                Self: 'long,
+            {
+                T::upcast_key3(long)
+            }
+
+            fn upcast_key4<'short, 'long: 'short>(
+                long: Self::K4<'long>,
+            ) -> Self::K4<'short>
+            where
+                Self: 'long,
+            {
+                T::upcast_key4(long)
+            }
+        }
+    };
+}
+
+impl_for_ref!(&'b T);
+impl_for_ref!(&'b mut T);
+
+macro_rules! impl_for_box {
+    ($type:ty) => {
+        impl<T: ?Sized + QuadHashItem> QuadHashItem for $type {
+            type K1<'a>
+                = T::K1<'a>
+            where
+                Self: 'a;
+
+            type K2<'a>
+                = T::K2<'a>
+            where
+                Self: 'a;
+
+            type K3<'a>
+                = T::K3<'a>
+            where
+                Self: 'a;
+
+            type K4<'a>
+                = T::K4<'a>
+            where
+                Self: 'a;
+
+            fn key1(&self) -> Self::K1<'_> {
+                (**self).key1()
+            }
+
+            fn key2(&self) -> Self::K2<'_> {
+                (**self).key2()
+            }
+
+            fn key3(&self) -> Self::K3<'_> {
+                (**self).key3()
+            }
+
+            fn key4(&self) -> Self::K4<'_> {
+                (**self).key4()
+            }
+
+            fn upcast_key1<'short, 'long: 'short>(
+                long: Self::K1<'long>,
+            ) -> Self::K1<'short> {
+                T::upcast_key1(long)
+            }
+
+            fn upcast_key2<'short, 'long: 'short>(
+                long: Self::K2<'long>,
+            ) -> Self::K2<'short> {
+                T::upcast_key2(long)
+            }
+
+            fn upcast_key3<'short, 'long: 'short>(
+                long: Self::K3<'long>,
+            ) -> Self::K3<'short> {
+                T::upcast_key3(long)
+            }
+
+            fn upcast_key4<'short, 'long: 'short>(
+                long: Self::K4<'long>,
+            ) -> Self::K4<'short> {
+                T::upcast_key4(long)
+            }
+        }
+    };
+}
+
+impl_for_box!(Box<T>);
+impl_for_box!(Rc<T>);
+impl_for_box!(Arc<T>);
@@ -0,0 +1,486 @@
+//! Serialize ID maps as objects keyed by each item's key.
+//!
+//! By default, ID maps serialize as a list of items. The [`serialize`] and
+//! [`deserialize`] functions in this module instead serialize a map as an
+//! object whose keys are the items' keys, which is a common shape for config
+//! files. They're meant to be used with serde's `with` attribute, and work with
+//! any map that implements [`KeyedMap`].
+//!
+//! Each item's key must serialize as a string. When deserializing, each object
+//! key is checked against the key of the item it maps to, and duplicate keys
+//! are rejected.
+//!
+//! Unlike [`IdHashMapAsMap`] and friends, which ignore object keys on input,
+//! this module rejects input where an object key disagrees with its item.
+//!
+//! # Examples
+//!
+//! ```
+//! # #[cfg(feature = "default-hasher")] {
+//! use iddqd::{IdHashItem, IdHashMap, IdOrdItem, IdOrdMap, id_upcast};
+//! # use iddqd_test_utils::serde_json;
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Debug, Serialize, Deserialize)]
+//! struct Server {
+//!     name: String,
+//!     port: u16,
+//! }
+//!
+//! impl IdHashItem for Server {
+//!     type Key<'a> = &'a str;
+//!     fn key(&self) -> Self::Key<'_> {
+//!         &self.name
+//!     }
+//!     id_upcast!();
+//! }
+//!
+//! impl IdOrdItem for Server {
+//!     type Key<'a> = &'a str;
+//!     fn key(&self) -> Self::Key<'_> {
+//!         &self.name
+//!     }
+//!     id_upcast!();
+//! }
+//!
+//! #[derive(Debug, Serialize, Deserialize)]
+//! struct Config {
+//!     #[serde(with = "iddqd::serde::keyed")]
+//!     servers: IdOrdMap<Server>,
+//!     #[serde(with = "iddqd::serde::keyed")]
+//!     backups: IdHashMap<Server>,
+//! }
+//!
+//! let json = r#"{
+//!     "servers": {
+//!         "a": { "name": "a", "port": 80 },
+//!         "b": { "name": "b", "port": 443 }
+//!     },
+//!     "backups": {}
+//! }"#;
+//! let config: Config = serde_json::from_str(json).unwrap();
+//! assert_eq!(config.servers.get("b").unwrap().port, 443);
+//!
+//! // An object key must match the key of its item.
+//! let json = r#"{
+//!     "servers": { "a": { "name": "b", "port": 80 } },
+//!     "backups": {}
+//! }"#;
+//! assert!(serde_json::from_str::<Config>(json).is_err());
+//! # }
+//! ```
+//!
+//! [`IdHashMapAsMap`]: crate::id_hash_map::IdHashMapAsMap
+
+use crate::{
+    IdHashItem, IdHashMap, IdOrdItem, IdOrdMap,
+    support::{alloc::Allocator, size_hint::cautious},
+};
+use alloc::string::{String, ToString};
+use core::{fmt, hash::BuildHasher, marker::PhantomData};
+use serde_core::{
+    Deserialize, Deserializer, Serialize, Serializer,
+    de::{self, MapAccess, Visitor},
+    ser::{self, Impossible, SerializeMap},
+};
+
+/// A map that can be serialized as an object keyed by its items' keys.
+///
+/// This trait is sealed, and implemented for [`IdHashMap`] and [`IdOrdMap`].
+/// The lifetime `'a` is the lifetime at which item keys are serialized.
+///
+/// [`IdOrdMap`]: crate::IdOrdMap
+pub trait KeyedMap<'a>: Default + private::Sealed {
+    /// The type of items in the map.
+    type Item: 'a;
+
+    #[doc(hidden)]
+    fn keyed_len(&self) -> usize;
+
+    #[doc(hidden)]
+    fn keyed_iter<'b>(&'b self) -> impl Iterator<Item = &'b Self::Item>
+    where
+        Self::Item: 'b;
+
+    #[doc(hidden)]
+    fn keyed_reserve(&mut self, additional: usize);
+
+    #[doc(hidden)]
+    fn keyed_key(item: &Self::Item) -> Result<String, KeyError>;
+
+    #[doc(hidden)]
+    fn keyed_insert<E: de::Error>(&mut self, item: Self::Item) -> Result<(), E>
+    where
+        Self::Item: fmt::Debug;
+}
+
+mod private {
+    pub trait Sealed {}
+}
+
+/// Serializes a map as an object keyed by its items' keys.
+///
+/// # Errors
+///
+/// Returns an error if an item's key doesn't serialize as a string.
+pub fn serialize<'a, M, S>(map: &M, serializer: S) -> Result<S::Ok, S::Error>
+where
+    M: KeyedMap<'a>,
+    M::Item: Serialize,
+    S: Serializer,
+{
+    let mut ser_map = serializer.serialize_map(Some(map.keyed_len()))?;
+    for item in map.keyed_iter() {
+        let key = M::keyed_key(item).map_err(ser::Error::custom)?;
+        ser_map.serialize_entry(&key, item)?;
+    }
+    ser_map.end()
+}
+
+/// Deserializes a map from an object keyed by its items' keys.
+///
+/// # Errors
+///
+/// Returns an error if an object key doesn't match the key of its item, if an
+/// item's key doesn't serialize as a string, or if two items have the same
+/// key.
+pub fn deserialize<'a, 'de, M, D>(deserializer: D) -> Result<M, D::Error>
+where
+    M: KeyedMap<'a>,
+    M::Item: Deserialize<'de> + fmt::Debug,
+    D: Deserializer<'de>,
+{
+    deserializer.deserialize_map(KeyedVisitor { _marker: PhantomData })
+}
+
+struct KeyedVisitor<M> {
+    _marker: PhantomData<fn() -> M>,
+}
+
+impl<'a, 'de, M> Visitor<'de> for KeyedVisitor<M>
+where
+    M: KeyedMap<'a>,
+    M::Item: Deserialize<'de> + fmt::Debug,
+{
+    type Value = M;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str("a map from keys to items")
+    }
+
+    fn visit_map<Access>(
+        self,
+        mut map_access: Access,
+    ) -> Result<Self::Value, Access::Error>
+    where
+        Access: MapAccess<'de>,
+    {
+        let mut map = M::default();
+        map.keyed_reserve(cautious::<M::Item>(map_access.size_hint()));
+
+        while let Some((key, item)) =
+            map_access.next_entry::<String, M::Item>()?
+        {
+            let item_key = M::keyed_key(&item).map_err(de::Error::custom)?;
+            if key != item_key {
+                return Err(de::Error::custom(format_args!(
+                    "object key {key:?} does not match item key {item_key:?}"
+                )));
+            }
+            map.keyed_insert(item)?;
+        }
+
+        Ok(map)
+    }
+}
+
+impl<T, S, A> private::Sealed for IdHashMap<T, S, A> where A: Allocator {}
+
+impl<'a, T, S, A> KeyedMap<'a> for IdHashMap<T, S, A>
+where
+    T: 'a + IdHashItem,
+    T::Key<'a>: Serialize,
+    S: Default + Clone + BuildHasher,
+    A: Default + Allocator,
+{
+    type Item = T;
+
+    fn keyed_len(&self) -> usize {
+        self.len()
+    }
+
+    fn keyed_iter<'b>(&'b self) -> impl Iterator<Item = &'b T>
+    where
+        T: 'b,
+    {
+        self.iter()
+    }
+
+    fn keyed_reserve(&mut self, additional: usize) {
+        self.reserve(additional);
+    }
+
+    fn keyed_key(item: &T) -> Result<String, KeyError> {
+        let key = item.key();
+        // SAFETY: see `IdHashMapAsMap::serialize`. The key is only used within
+        // this function, and `key_to_string` doesn't hold on to it.
+        let key =
+            unsafe { core::mem::transmute::<T::Key<'_>, T::Key<'a>>(key) };
+        key_to_string(&key)
+    }
+
+    fn keyed_insert<E: de::Error>(&mut self, item: T) -> Result<(), E>
+    where
+        T: fmt::Debug,
+    {
+        self.insert_unique(item).map_err(E::custom)
+    }
+}
+
+impl<T: IdOrdItem, A: Allocator> private::Sealed for IdOrdMap<T, A> {}
+
+impl<'a, T, A> KeyedMap<'a> for IdOrdMap<T, A>
+where
+    T: 'a + IdOrdItem,
+    T::Key<'a>: Serialize,
+    A: Default + Allocator,
+{
+    type Item = T;
+
+    fn keyed_len(&self) -> usize {
+        self.len()
+    }
+
+    fn keyed_iter<'b>(&'b self) -> impl Iterator<Item = &'b T>
+    where
+        T: 'b,
+    {
+        self.iter()
+    }
+
+    fn keyed_reserve(&mut self, additional: usize) {
+        self.reserve(additional);
+    }
+
+    fn keyed_key(item: &T) -> Result<String, KeyError> {
+        let key = item.key();
+        // SAFETY: see `IdHashMapAsMap::serialize`. The key is only used within
+        // this function, and `key_to_string` doesn't hold on to it.
+        let key =
+            unsafe { core::mem::transmute::<T::Key<'_>, T::Key<'a>>(key) };
+        key_to_string(&key)
+    }
+
+    fn keyed_insert<E: de::Error>(&mut self, item: T) -> Result<(), E>
+    where
+        T: fmt::Debug,
+    {
+        self.insert_unique(item).map_err(E::custom)
+    }
+}
+
+/// The error returned when an item's key doesn't serialize as a string.
+#[doc(hidden)]
+#[derive(Debug)]
+pub struct KeyError {
+    message: String,
+}
+
+impl fmt::Display for KeyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl core::error::Error for KeyError {}
+
+impl ser::Error for KeyError {
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        KeyError { message: msg.to_string() }
+    }
+}
+
+fn key_to_string<K: ?Sized + Serialize>(key: &K) -> Result<String, KeyError> {
+    key.serialize(KeySerializer)
+}
+
+fn not_a_string<T>() -> Result<T, KeyError> {
+    Err(KeyError { message: "item key must serialize as a string".to_string() })
+}
+
+/// A serializer that accepts strings and rejects everything else.
+struct KeySerializer;
+
+impl Serializer for KeySerializer {
+    type Ok = String;
+    type Error = KeyError;
+    type SerializeSeq = Impossible<String, KeyError>;
+    type SerializeTuple = Impossible<String, KeyError>;
+    type SerializeTupleStruct = Impossible<String, KeyError>;
+    type SerializeTupleVariant = Impossible<String, KeyError>;
+    type SerializeMap = Impossible<String, KeyError>;
+    type SerializeStruct = Impossible<String, KeyError>;
+    type SerializeStructVariant = Impossible<String, KeyError>;
+
+    fn serialize_str(self, v: &str) -> Result<String, KeyError> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_char(self, v: char) -> Result<String, KeyError> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+    ) -> Result<String, KeyError> {
+        Ok(variant.to_string())
+    }
+
+    fn serialize_newtype_struct<T: ?Sized + Serialize>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<String, KeyError> {
+        value.serialize(self)
+    }
+
+    fn serialize_bool(self, _v: bool) -> Result<String, KeyError> {
+        not_a_string()
+    }
+
+    fn serialize_i8(self, _v: i8) -> Result<String, KeyError> {
+        not_a_string()
+    }
+
+    fn serialize_i16(self, _v: i16) -> Result<String, KeyError> {
+        not_a_string()
+    }
+
+    fn serialize_i32(self, _v: i32) -> Result<String, KeyError> {
+        not_a_string()
+    }
+
+    fn serialize_i64(self, _v: i64) -> Result<String, KeyError> {
+        not_a_string()
+    }
+
+    fn serialize_u8(self, _v: u8) -> Result<String, KeyError> {
+        not_a_string()
+    }
+
+    fn serialize_u16(self, _v: u16) -> Result<String, KeyError> {
+        not_a_string()
+    }
+
+    fn serialize_u32(self, _v: u32) -> Result<String, KeyError> {
+        not_a_string()
+    }
+
+    fn serialize_u64(self, _v: u64) -> Result<String, KeyError> {
+        not_a_string()
+    }
+
+    fn serialize_f32(self, _v: f32) -> Result<String, KeyError> {
+        not_a_string()
+    }
+
+    fn serialize_f64(self, _v: f64) -> Result<String, KeyError> {
+        not_a_string()
+    }
+
+    fn serialize_bytes(self, _v: &[u8]) -> Result<String, KeyError> {
+        not_a_string()
+    }
+
+    fn serialize_none(self) -> Result<String, KeyError> {
+        not_a_string()
+    }
+
+    fn serialize_some<T: ?Sized + Serialize>(
+        self,
+        _value: &T,
+    ) -> Result<String, KeyError> {
+        not_a_string()
+    }
+
+    fn serialize_unit(self) -> Result<String, KeyError> {
+        not_a_string()
+    }
+
+    fn serialize_unit_struct(
+        self,
+        _name: &'static str,
+    ) -> Result<String, KeyError> {
+        not_a_string()
+    }
+
+    fn serialize_newtype_variant<T: ?Sized + Serialize>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _value: &T,
+    ) -> Result<String, KeyError> {
+        not_a_string()
+    }
+
+    fn serialize_seq(
+        self,
+        _len: Option<usize>,
+    ) -> Result<Self::SerializeSeq, KeyError> {
+        not_a_string()
+    }
+
+    fn serialize_tuple(
+        self,
+        _len: usize,
+    ) -> Result<Self::SerializeTuple, KeyError> {
+        not_a_string()
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleStruct, KeyError> {
+        not_a_string()
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleVariant, KeyError> {
+        not_a_string()
+    }
+
+    fn serialize_map(
+        self,
+        _len: Option<usize>,
+    ) -> Result<Self::SerializeMap, KeyError> {
+        not_a_string()
+    }
+
+    fn serialize_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStruct, KeyError> {
+        not_a_string()
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStructVariant, KeyError> {
+        not_a_string()
+    }
+}
@@ -0,0 +1,7 @@
+//! Serde helpers for ID maps.
+//!
+//! The ID maps implement `Serialize` and `Deserialize` directly. This module
+//! contains helpers for alternative representations, for use with serde's
+//! `with` attribute.
+
+pub mod keyed;
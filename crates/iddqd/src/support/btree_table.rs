@@ -13,10 +13,11 @@ use alloc::{
     vec::Vec,
 };
 use core::{
-    cell::Cell,
+    cell::{Cell, RefCell},
     cmp::Ordering,
     hash::{BuildHasher, Hash},
     marker::PhantomData,
+    ops::{Bound, RangeBounds},
 };
 use equivalent::Comparable;
 
@@ -74,14 +75,18 @@ thread_local! {
 /// External comparator type used via `CMP`'s dynamic scoping.
 type IndexCmp<'a> = dyn Fn(&Index, &Index) -> Ordering + 'a;
 
+/// The hash state used to compute [`MapHash`] values for keys.
+///
+/// We use foldhash directly here because we allow compiling with std but
+/// without the default-hasher. std turns on foldhash but not the default
+/// hasher.
+pub(crate) type HashState = foldhash::fast::FixedState;
+
 /// A B-tree-based table with an external comparator.
 #[derive(Clone, Debug, Default)]
 pub(crate) struct MapBTreeTable {
     items: BTreeMap<Index, ()>,
-    // We use foldhash directly here because we allow compiling with std but
-    // without the default-hasher. std turns on foldhash but not the default
-    // hasher.
-    hash_state: foldhash::fast::FixedState,
+    hash_state: HashState,
 }
 
 impl MapBTreeTable {
@@ -90,7 +95,7 @@ impl MapBTreeTable {
             items: BTreeMap::new(),
             // FixedState::with_seed XORs the passed in seed with a fixed
             // high-entropy value.
-            hash_state: foldhash::fast::FixedState::with_seed(0),
+            hash_state: HashState::with_seed(0),
         }
     }
 
@@ -208,6 +213,194 @@ impl MapBTreeTable {
         ret
     }
 
+    /// Finds an index using a caller-provided comparator.
+    ///
+    /// `f` returns the ordering of the item at the given index relative to the
+    /// target, in the same manner as `slice::binary_search_by`.
+    pub(crate) fn find_index_by<F>(&self, f: F) -> Option<ItemIndex>
+    where
+        F: FnMut(ItemIndex) -> Ordering,
+    {
+        let f = RefCell::new(f);
+        let cmp = search_by_cmp(&f);
+
+        let guard = CmpDropGuard::new(&cmp);
+
+        let ret = match self.items.get_key_value(&Index::sentinel()) {
+            Some((ix, ())) if ix.value() == Index::SENTINEL_VALUE => {
+                panic!("internal map shouldn't store sentinel value")
+            }
+            Some((ix, ())) => Some(ix.value()),
+            None => None,
+        };
+
+        // drop(guard) isn't necessary, but we make it explicit
+        drop(guard);
+        ret
+    }
+
+    /// Returns the indexes whose keys fall within `range`, in key order.
+    ///
+    /// Unlike [`BTreeMap::range`], this doesn't panic if the start of the
+    /// range is greater than the end: the result is simply empty.
+    pub(crate) fn range<K, Q, R, F>(&self, range: R, lookup: F) -> Range<'_>
+    where
+        K: Ord,
+        Q: ?Sized + Comparable<K>,
+        R: RangeBounds<Q>,
+        F: Fn(ItemIndex) -> K,
+    {
+        // Both ends of the range would be represented by the sentinel, and the
+        // comparator can't tell them apart. So find the first and last
+        // matching indexes separately, then take the range between them.
+        let first = self.lower_bound(range.start_bound(), &lookup);
+        let last = self.upper_bound(range.end_bound(), &lookup);
+
+        let (Some(first), Some(last)) = (first, last) else {
+            return Range::default();
+        };
+        let (first, last) = (Index::new(first), Index::new(last));
+        let f = stored_cmp(&lookup);
+        let guard = CmpDropGuard::new(&f);
+        // BTreeMap::range panics if start > end, so check that first.
+        let inner = if f(&first, &last) == Ordering::Greater {
+            btree_map::Range::default()
+        } else {
+            self.items.range(first..=last)
+        };
+        // Iterating over a btree_map::Range doesn't perform any comparisons,
+        // so the comparator is no longer needed.
+        drop(guard);
+        Range::new(inner)
+    }
+
+    /// Returns the first index whose key is above `bound`.
+    pub(crate) fn lower_bound<K, Q, F>(
+        &self,
+        bound: Bound<&Q>,
+        lookup: F,
+    ) -> Option<ItemIndex>
+    where
+        K: Ord,
+        Q: ?Sized + Comparable<K>,
+        F: Fn(ItemIndex) -> K,
+    {
+        let sentinel = Index::sentinel();
+        let (start, key) = match bound {
+            Bound::Unbounded => return self.first(),
+            Bound::Included(key) => (Bound::Included(&sentinel), key),
+            Bound::Excluded(key) => (Bound::Excluded(&sentinel), key),
+        };
+        let f = find_cmp(key, lookup);
+        let guard = CmpDropGuard::new(&f);
+        let ret = self
+            .items
+            .range((start, Bound::Unbounded))
+            .next()
+            .map(|(ix, ())| ix.value());
+        drop(guard);
+        ret
+    }
+
+    /// Returns the last index whose key is below `bound`.
+    pub(crate) fn upper_bound<K, Q, F>(
+        &self,
+        bound: Bound<&Q>,
+        lookup: F,
+    ) -> Option<ItemIndex>
+    where
+        K: Ord,
+        Q: ?Sized + Comparable<K>,
+        F: Fn(ItemIndex) -> K,
+    {
+        let sentinel = Index::sentinel();
+        let (end, key) = match bound {
+            Bound::Unbounded => return self.last(),
+            Bound::Included(key) => (Bound::Included(&sentinel), key),
+            Bound::Excluded(key) => (Bound::Excluded(&sentinel), key),
+        };
+        let f = find_cmp(key, lookup);
+        let guard = CmpDropGuard::new(&f);
+        let ret = self
+            .items
+            .range((Bound::Unbounded, end))
+            .next_back()
+            .map(|(ix, ())| ix.value());
+        drop(guard);
+        ret
+    }
+
+    /// Returns the index that follows `index` in key order.
+    pub(crate) fn next_index<K, F>(
+        &self,
+        index: ItemIndex,
+        lookup: F,
+    ) -> Option<ItemIndex>
+    where
+        K: Ord,
+        F: Fn(ItemIndex) -> K,
+    {
+        let index = Index::new(index);
+        let f = stored_cmp(lookup);
+        let guard = CmpDropGuard::new(&f);
+        let ret = self
+            .items
+            .range((Bound::Excluded(&index), Bound::Unbounded))
+            .next()
+            .map(|(ix, ())| ix.value());
+        drop(guard);
+        ret
+    }
+
+    /// Returns the index that precedes `index` in key order.
+    pub(crate) fn prev_index<K, F>(
+        &self,
+        index: ItemIndex,
+        lookup: F,
+    ) -> Option<ItemIndex>
+    where
+        K: Ord,
+        F: Fn(ItemIndex) -> K,
+    {
+        let index = Index::new(index);
+        let f = stored_cmp(lookup);
+        let guard = CmpDropGuard::new(&f);
+        let ret = self
+            .items
+            .range((Bound::Unbounded, Bound::Excluded(&index)))
+            .next_back()
+            .map(|(ix, ())| ix.value());
+        drop(guard);
+        ret
+    }
+
+    /// Builds a table from indexes whose keys are in strictly increasing
+    /// order.
+    ///
+    /// `BTreeMap`'s `FromIterator` implementation sorts its input and then
+    /// builds the tree bottom-up. For input that's already sorted, that takes
+    /// a linear number of comparisons, rather than a descent from the root for
+    /// each index.
+    pub(crate) fn from_sorted_indexes<K, F, I>(indexes: I, lookup: F) -> Self
+    where
+        K: Ord,
+        F: Fn(ItemIndex) -> K,
+        I: IntoIterator<Item = ItemIndex>,
+    {
+        let f = stored_cmp(lookup);
+        let guard = CmpDropGuard::new(&f);
+        let table = Self {
+            items: indexes
+                .into_iter()
+                .map(|index| (Index::new(index), ()))
+                .collect(),
+            ..Self::new()
+        };
+        drop(guard);
+
+        table
+    }
+
     pub(crate) fn prepare_insert<K, Q, F>(
         &mut self,
         index: ItemIndex,
@@ -321,6 +514,23 @@ impl MapBTreeTable {
         self.items.clear();
     }
 
+    /// Removes and returns the first index in the table.
+    ///
+    /// `BTreeMap::pop_first` walks down the leftmost edge of the tree without
+    /// doing any comparisons, so a comparator guard isn't necessary.
+    #[inline]
+    pub(crate) fn pop_first(&mut self) -> Option<ItemIndex> {
+        self.items.pop_first().map(|(index, ())| index.value())
+    }
+
+    /// Removes and returns the last index in the table.
+    ///
+    /// As with [`Self::pop_first`], this doesn't do any comparisons.
+    #[inline]
+    pub(crate) fn pop_last(&mut self) -> Option<ItemIndex> {
+        self.items.pop_last().map(|(index, ())| index.value())
+    }
+
     pub(crate) fn iter(&self) -> Iter<'_> {
         Iter::new(self.items.keys())
     }
@@ -329,7 +539,7 @@ impl MapBTreeTable {
         IntoIter::new(self.items.into_iter())
     }
 
-    pub(crate) fn state(&self) -> &foldhash::fast::FixedState {
+    pub(crate) fn state(&self) -> &HashState {
         &self.hash_state
     }
 
@@ -361,6 +571,37 @@ impl<'a> Iterator for Iter<'a> {
     }
 }
 
+impl<'a> DoubleEndedIterator for Iter<'a> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.inner.next_back().map(|index| index.value())
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub(crate) struct Range<'a> {
+    inner: btree_map::Range<'a, Index, ()>,
+}
+
+impl<'a> Range<'a> {
+    fn new(inner: btree_map::Range<'a, Index, ()>) -> Self {
+        Self { inner }
+    }
+}
+
+impl<'a> Iterator for Range<'a> {
+    type Item = ItemIndex;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|(index, ())| index.value())
+    }
+}
+
+impl<'a> DoubleEndedIterator for Range<'a> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.inner.next_back().map(|(index, ())| index.value())
+    }
+}
+
 #[derive(Debug)]
 pub(crate) struct IntoIter {
     inner: btree_map::IntoIter<Index, ()>,
@@ -370,6 +611,10 @@ impl IntoIter {
     fn new(inner: btree_map::IntoIter<Index, ()>) -> Self {
         Self { inner }
     }
+
+    pub(crate) fn len(&self) -> usize {
+        self.inner.len()
+    }
 }
 
 impl Iterator for IntoIter {
@@ -380,6 +625,12 @@ impl Iterator for IntoIter {
     }
 }
 
+impl DoubleEndedIterator for IntoIter {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.inner.next_back().map(|(index, ())| index.value())
+    }
+}
+
 pub(crate) struct PreparedBTreeInsert<'a> {
     entry: btree_map::VacantEntry<'a, Index, ()>,
 }
@@ -442,6 +693,55 @@ where
     }
 }
 
+fn search_by_cmp<'a, F>(
+    f: &'a RefCell<F>,
+) -> impl Fn(&Index, &Index) -> Ordering + 'a
+where
+    F: FnMut(ItemIndex) -> Ordering,
+{
+    move |a: &Index, b: &Index| {
+        let (a, b) = (a.value(), b.value());
+        if a == b {
+            // See the comment in find_cmp: items at the same index are always
+            // equal.
+            return Ordering::Equal;
+        }
+        // `f` returns the ordering of a stored item relative to the target,
+        // which is represented by the sentinel.
+        match (a, b) {
+            (Index::SENTINEL_VALUE, v) => (f.borrow_mut())(v).reverse(),
+            (v, Index::SENTINEL_VALUE) => (f.borrow_mut())(v),
+            // Lookups only ever compare the sentinel against stored indexes.
+            (_, _) => {
+                panic!("stored indexes should not be compared in search path")
+            }
+        }
+    }
+}
+
+fn stored_cmp<'a, K, F>(lookup: F) -> impl Fn(&Index, &Index) -> Ordering + 'a
+where
+    F: 'a + Fn(ItemIndex) -> K,
+    K: Ord,
+{
+    move |a: &Index, b: &Index| {
+        let (a, b) = (a.value(), b.value());
+        if a == b {
+            // See the comment in find_cmp: items at the same index are always
+            // equal.
+            return Ordering::Equal;
+        }
+        match (a, b) {
+            (Index::SENTINEL_VALUE, _) | (_, Index::SENTINEL_VALUE) => {
+                panic!("sentinel value should not be invoked in range path")
+            }
+            // As in find_cmp, the tiebreaker preserves bijection when a
+            // pathological user `Ord` returns `Equal` for distinct keys.
+            (a, b) => lookup(a).cmp(&lookup(b)).then_with(|| a.cmp(&b)),
+        }
+    }
+}
+
 fn insert_cmp<'a, K, Q, F>(
     index: ItemIndex,
     key: &'a Q,
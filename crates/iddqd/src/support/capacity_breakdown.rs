@@ -0,0 +1,62 @@
+use alloc::vec::Vec;
+
+/// A breakdown of a map's internal sizing, returned by the
+/// `debug_capacity_breakdown` method on each map type.
+///
+/// This is meant for logging and debugging. Unlike `validate`, producing it
+/// doesn't visit any items, so it's cheap to call. The exact layout of a map's
+/// internals is not part of its stable API, so the values reported here may
+/// change between releases.
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(feature = "default-hasher")] {
+/// use iddqd::{BiHashItem, BiHashMap, bi_upcast};
+///
+/// #[derive(Debug)]
+/// struct Item {
+///     id: u32,
+///     name: &'static str,
+/// }
+///
+/// impl BiHashItem for Item {
+///     type K1<'a> = u32;
+///     type K2<'a> = &'a str;
+///
+///     fn key1(&self) -> Self::K1<'_> {
+///         self.id
+///     }
+///     fn key2(&self) -> Self::K2<'_> {
+///         self.name
+///     }
+///     bi_upcast!();
+/// }
+///
+/// let mut map = BiHashMap::with_capacity(16);
+/// map.insert_unique(Item { id: 1, name: "foo" }).unwrap();
+///
+/// let breakdown = map.debug_capacity_breakdown();
+/// assert_eq!(breakdown.items_len, 1);
+/// assert!(breakdown.items_capacity >= 16);
+/// // One entry per key table.
+/// assert_eq!(breakdown.table_capacities.len(), 2);
+/// assert!(breakdown.table_capacities.iter().all(|&c| c >= 16));
+/// # }
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct CapacityBreakdown {
+    /// The number of items in the map.
+    pub items_len: usize,
+
+    /// The capacity of the item storage.
+    pub items_capacity: usize,
+
+    /// The capacity of each key table, in key order (`key1`, `key2`, and so
+    /// on).
+    ///
+    /// For [`IdOrdMap`](crate::IdOrdMap), this is empty: its key table is a
+    /// B-tree, which doesn't have a capacity.
+    pub table_capacities: Vec<usize>,
+}
@@ -0,0 +1,56 @@
+/// How to handle duplicate keys when extending a map.
+///
+/// Passed to the `extend_with` method on each map type. The [`Extend`]
+/// implementations on the maps always use [`ExtendMode::Overwrite`].
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(feature = "default-hasher")] {
+/// use iddqd::{ExtendMode, IdHashItem, IdHashMap, id_upcast};
+///
+/// #[derive(Debug, PartialEq, Eq)]
+/// struct Item {
+///     id: u32,
+///     value: &'static str,
+/// }
+///
+/// impl IdHashItem for Item {
+///     type Key<'a> = u32;
+///     fn key(&self) -> Self::Key<'_> {
+///         self.id
+///     }
+///     id_upcast!();
+/// }
+///
+/// let mut map = IdHashMap::new();
+/// map.insert_unique(Item { id: 1, value: "a" }).unwrap();
+///
+/// // Unique mode rejects the duplicate.
+/// let err = map
+///     .extend_with([Item { id: 1, value: "b" }], ExtendMode::Unique)
+///     .unwrap_err();
+/// assert_eq!(err.new_item().value, "b");
+///
+/// // Overwrite mode replaces it.
+/// map.extend_with([Item { id: 1, value: "b" }], ExtendMode::Overwrite)
+///     .unwrap();
+/// assert_eq!(map.get(&1).unwrap().value, "b");
+/// # }
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum ExtendMode {
+    /// Replace any existing items that conflict with a new item.
+    ///
+    /// This matches the behavior of `insert_overwrite`, and is what the
+    /// [`Extend`] implementations use.
+    #[default]
+    Overwrite,
+
+    /// Stop at the first item that conflicts with an existing item, and
+    /// return an error.
+    ///
+    /// This matches the behavior of `insert_unique`. Items before the
+    /// conflicting one remain in the map, and items after it are dropped.
+    Unique,
+}
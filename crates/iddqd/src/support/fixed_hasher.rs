@@ -0,0 +1,99 @@
+//! A small hasher with a fixed seed.
+//!
+//! Some hashes computed by this crate only need to be deterministic, not
+//! resistant to HashDoS: the hashes that `Hash` impls compute for items, and
+//! (without `std`) the key hashes that the ordered maps use to detect key
+//! changes. This hasher serves those cases without depending on foldhash,
+//! which is only pulled in by the `std` and `default-hasher` features.
+
+use core::hash::{BuildHasher, Hasher};
+
+/// Builds [`FixedHasher`] instances, all with the same seed.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct FixedState;
+
+impl FixedState {
+    #[inline]
+    pub(crate) const fn new() -> Self {
+        Self
+    }
+}
+
+impl BuildHasher for FixedState {
+    type Hasher = FixedHasher;
+
+    #[inline]
+    fn build_hasher(&self) -> FixedHasher {
+        FixedHasher { hash: SEED }
+    }
+}
+
+// The first digits of pi.
+const SEED: u64 = 0x243f_6a88_85a3_08d3;
+// The multiplier used by FxHash.
+const MULTIPLIER: u64 = 0x517c_c1b7_2722_0a95;
+
+/// A multiply-rotate hasher in the style of FxHash, with a final mixing step
+/// so that all bits of the output depend on the input.
+#[derive(Clone, Debug)]
+pub(crate) struct FixedHasher {
+    hash: u64,
+}
+
+impl FixedHasher {
+    #[inline]
+    fn add(&mut self, word: u64) {
+        self.hash = (self.hash.rotate_left(5) ^ word).wrapping_mul(MULTIPLIER);
+    }
+}
+
+impl Hasher for FixedHasher {
+    fn write(&mut self, bytes: &[u8]) {
+        let mut chunks = bytes.chunks_exact(8);
+        for chunk in &mut chunks {
+            let mut word = [0; 8];
+            word.copy_from_slice(chunk);
+            self.add(u64::from_le_bytes(word));
+        }
+        let rest = chunks.remainder();
+        if !rest.is_empty() {
+            let mut word = [0; 8];
+            word[..rest.len()].copy_from_slice(rest);
+            self.add(u64::from_le_bytes(word));
+        }
+    }
+
+    #[inline]
+    fn write_u8(&mut self, i: u8) {
+        self.add(u64::from(i));
+    }
+
+    #[inline]
+    fn write_u16(&mut self, i: u16) {
+        self.add(u64::from(i));
+    }
+
+    #[inline]
+    fn write_u32(&mut self, i: u32) {
+        self.add(u64::from(i));
+    }
+
+    #[inline]
+    fn write_u64(&mut self, i: u64) {
+        self.add(i);
+    }
+
+    #[inline]
+    fn write_usize(&mut self, i: usize) {
+        self.add(i as u64);
+    }
+
+    #[inline]
+    fn finish(&self) -> u64 {
+        // The splitmix64 finalizer.
+        let mut z = self.hash;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+}
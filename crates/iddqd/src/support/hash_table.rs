@@ -39,7 +39,7 @@ use super::{
 use crate::internal::{
     TableValidationError, ValidateCompact, table_validation_fail,
 };
-use alloc::vec::Vec;
+use alloc::{collections::BTreeSet, vec::Vec};
 use core::{
     fmt,
     hash::{BuildHasher, Hash},
@@ -72,11 +72,22 @@ fn cached_hasher(stored: &HashedIndex) -> u64 {
     stored.hash
 }
 
-#[derive(Clone, Default)]
+#[derive(Default)]
 pub(crate) struct MapHashTable<A: Allocator> {
     pub(super) items: HashTable<HashedIndex, AllocWrapper<A>>,
 }
 
+impl<A: Clone + Allocator> Clone for MapHashTable<A> {
+    fn clone(&self) -> Self {
+        Self { items: self.items.clone() }
+    }
+
+    fn clone_from(&mut self, source: &Self) {
+        // hashbrown reuses the existing allocation where it can.
+        self.items.clone_from(&source.items);
+    }
+}
+
 impl<A: Allocator> fmt::Debug for MapHashTable<A> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("MapHashTable").field("items", &self.items).finish()
@@ -98,6 +109,18 @@ impl<A: Allocator> MapHashTable<A> {
         self.items.len()
     }
 
+    pub(crate) fn capacity(&self) -> usize {
+        self.items.capacity()
+    }
+
+    /// Returns an iterator over the cached hash and index of each entry, in
+    /// table order.
+    pub(crate) fn iter_hashed(
+        &self,
+    ) -> impl Iterator<Item = (u64, ItemIndex)> + '_ {
+        self.items.iter().map(|stored| (stored.hash, stored.ix))
+    }
+
     pub(crate) fn validate(
         &self,
         expected_len: usize,
@@ -175,6 +198,27 @@ impl<A: Allocator> MapHashTable<A> {
             .map(|stored| stored.ix)
     }
 
+    /// Like `find_index`, but also returns the hash stored for the found
+    /// item.
+    ///
+    /// This is the hash of the item's own key, which can differ from the hash
+    /// of `key` if `Q`'s `Hash` implementation doesn't agree with `K`'s.
+    pub(crate) fn find_index_hashed<S: BuildHasher, K, Q, F>(
+        &self,
+        state: &S,
+        key: &Q,
+        lookup: F,
+    ) -> Option<(ItemIndex, MapHash)>
+    where
+        F: Fn(ItemIndex) -> K,
+        Q: ?Sized + Hash + Equivalent<K>,
+    {
+        let hash = state.hash_one(key);
+        self.items
+            .find(hash, |stored| key.equivalent(&lookup(stored.ix)))
+            .map(|stored| (stored.ix, MapHash { hash: stored.hash }))
+    }
+
     pub(crate) fn entry<S: BuildHasher, K: Hash + Eq, F>(
         &mut self,
         state: &S,
@@ -400,3 +444,21 @@ impl<'a, A: Allocator> VacantEntry<'a, A> {
         let _ = self.inner.insert(HashedIndex::new(ix, self.hash));
     }
 }
+
+/// Returns the vacant entry if `entry` is vacant. Otherwise, records the index
+/// of the occupying item in `duplicates` and returns `None`.
+///
+/// Used by the multi-key maps to check every key for duplicates before
+/// inserting into any of their tables.
+pub(crate) fn detect_dup_or_insert<'a, A: Allocator>(
+    entry: Entry<'a, A>,
+    duplicates: &mut BTreeSet<ItemIndex>,
+) -> Option<VacantEntry<'a, A>> {
+    match entry {
+        Entry::Vacant(slot) => Some(slot),
+        Entry::Occupied(slot) => {
+            duplicates.insert(slot.get());
+            None
+        }
+    }
+}
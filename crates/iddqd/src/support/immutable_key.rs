@@ -0,0 +1,67 @@
+/// A marker trait for items whose keys can't be changed through a mutable
+/// reference.
+///
+/// Mutable accessors like `get_mut` normally return a `RefMut` guard, which
+/// hashes the item's keys when it's created and again when it's dropped, and
+/// panics if the keys changed. For items that implement this trait, each map
+/// also provides accessors like `get_mut_plain` that return a plain `&mut T`
+/// without that bookkeeping.
+///
+/// # Contract
+///
+/// By implementing this trait, an item type promises that its key can't change
+/// through `&mut Self`. For [`BiHashMap`](crate::BiHashMap) and
+/// [`TriHashMap`](crate::TriHashMap), this applies to every key. This is
+/// typically the case when keys are derived from private fields that aren't
+/// exposed mutably.
+///
+/// Violating this contract is not undefined behavior, but it is a logic error,
+/// similar to an unlawful `Hash` or `Ord` implementation: the map may fail to
+/// find items, or may report the wrong items for a key.
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(feature = "default-hasher")] {
+/// use iddqd::{IdHashItem, IdHashMap, ImmutableKey, id_upcast};
+///
+/// mod item {
+///     #[derive(Debug)]
+///     pub struct Item {
+///         // Private, so it can't be changed outside this module.
+///         id: u32,
+///         pub value: String,
+///     }
+///
+///     impl Item {
+///         pub fn new(id: u32, value: String) -> Self {
+///             Self { id, value }
+///         }
+///
+///         pub fn id(&self) -> u32 {
+///             self.id
+///         }
+///     }
+/// }
+/// use item::Item;
+///
+/// impl IdHashItem for Item {
+///     type Key<'a> = u32;
+///     fn key(&self) -> Self::Key<'_> {
+///         self.id()
+///     }
+///     id_upcast!();
+/// }
+///
+/// // The key is only reachable through `&self`.
+/// impl ImmutableKey for Item {}
+///
+/// let mut map = IdHashMap::new();
+/// map.insert_unique(Item::new(1, "foo".to_string())).unwrap();
+///
+/// let item: &mut Item = map.get_mut_plain(&1).unwrap();
+/// item.value.push_str("bar");
+/// assert_eq!(map.get(&1).unwrap().value, "foobar");
+/// # }
+/// ```
+pub trait ImmutableKey {}
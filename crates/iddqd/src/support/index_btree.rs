@@ -0,0 +1,1376 @@
+//! An index-keyed B-Tree, used in place of [`super::btree_table`] when the
+//! `std` feature is disabled.
+//!
+//! The std-based table stores item indexes in std's `BTreeMap`, and uses a
+//! thread-local to pass an external comparator to it. Thread-locals aren't
+//! available in no-std environments, so this module provides a small B-Tree
+//! over item indexes whose operations take the comparator as an argument.
+//!
+//! As with `BTreeMap`, lookups, insertions and removals are `O(log n)`. Nodes
+//! are stored in an arena and refer to each other by position, with the root
+//! always at position 0. Each node records its parent, so iterators can step
+//! through the tree without keeping a stack.
+//!
+//! The API mirrors [`super::btree_table`] exactly, so that `IdOrdMap` and
+//! `BiOrdMap` can use either one.
+
+// With std, this module is only compiled for its tests.
+#![cfg_attr(feature = "std", allow(dead_code))]
+
+use super::{
+    ItemIndex, fixed_hasher::FixedState, item_set::IndexRemap,
+    map_hash::MapHash,
+};
+use crate::internal::{
+    TableValidationError, ValidateCompact, table_validation_fail,
+};
+use alloc::vec::{self, Vec};
+use core::{
+    cmp::Ordering,
+    hash::{BuildHasher, Hash},
+    mem,
+    ops::{Bound, RangeBounds},
+};
+use equivalent::Comparable;
+
+/// The hash state used to compute [`MapHash`] values for keys.
+pub(crate) type HashState = FixedState;
+
+/// The maximum number of keys in a node.
+const MAX_KEYS: usize = 11;
+
+/// The minimum number of keys in a node other than the root.
+const MIN_KEYS: usize = MAX_KEYS / 2;
+
+/// The position of a node in [`MapBTreeTable::nodes`].
+type NodeId = usize;
+
+/// The position of the root node, if the tree is non-empty.
+const ROOT: NodeId = 0;
+
+/// Stands in for the parent of the root.
+const NO_NODE: NodeId = usize::MAX;
+
+#[derive(Clone, Debug)]
+struct Node {
+    parent: NodeId,
+    keys: Vec<ItemIndex>,
+    // Empty for leaves. For internal nodes, there is one more child than there
+    // are keys, and the keys in `children[i]` sort before `keys[i]`.
+    children: Vec<NodeId>,
+}
+
+impl Node {
+    #[inline]
+    fn is_leaf(&self) -> bool {
+        self.children.is_empty()
+    }
+}
+
+/// The position of a key in the tree.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct Pos {
+    node: NodeId,
+    offset: usize,
+}
+
+/// A B-Tree of item indexes, ordered by an external comparator.
+#[derive(Clone, Debug)]
+pub(crate) struct MapBTreeTable {
+    // Invariant: in-order traversal visits indexes sorted by the keys of the
+    // corresponding items, with ties (only possible with a pathological `Ord`)
+    // broken by index.
+    //
+    // Empty if and only if the tree is empty.
+    nodes: Vec<Node>,
+    len: usize,
+    hash_state: HashState,
+}
+
+impl Default for MapBTreeTable {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MapBTreeTable {
+    pub(crate) const fn new() -> Self {
+        Self { nodes: Vec::new(), len: 0, hash_state: FixedState::new() }
+    }
+
+    #[doc(hidden)]
+    pub(crate) fn len(&self) -> usize {
+        self.len
+    }
+
+    #[doc(hidden)]
+    pub(crate) fn validate(
+        &self,
+        expected_len: usize,
+        compactness: ValidateCompact,
+    ) -> Result<(), TableValidationError> {
+        if self.len() != expected_len {
+            table_validation_fail!(
+                "expected length {expected_len}, was {}",
+                self.len(),
+            );
+        }
+
+        // Check the shape of the tree before walking it.
+        self.validate_structure()?;
+
+        let mut values: Vec<ItemIndex> = self.iter().collect();
+        if values.len() != self.len() {
+            table_validation_fail!(
+                "iterated over {} values, expected {}",
+                values.len(),
+                self.len(),
+            );
+        }
+        values.sort_unstable();
+        match compactness {
+            ValidateCompact::Compact => {
+                // All items between 0 (inclusive) and self.len() (exclusive)
+                // are present, and there are no duplicates.
+                for (i, index) in values.iter().enumerate() {
+                    if index.as_u32() as usize != i {
+                        table_validation_fail!(
+                            "value at index {i} should be {i}, was {index}",
+                        );
+                    }
+                }
+            }
+            ValidateCompact::NonCompact => {
+                let total = values.len();
+                values.dedup();
+                if values.len() != total {
+                    table_validation_fail!(
+                        "expected {} values with no duplicates, but only found \
+                         {} values (unique values: {:?})",
+                        total,
+                        values.len(),
+                        values,
+                    );
+                }
+            }
+        }
+
+        if values.contains(&ItemIndex::SENTINEL) {
+            table_validation_fail!(
+                "sentinel value should not be stored in map"
+            );
+        }
+
+        Ok(())
+    }
+
+    /// Checks node sizes, parent links, and that all leaves are at the same
+    /// depth.
+    fn validate_structure(&self) -> Result<(), TableValidationError> {
+        if self.nodes.is_empty() {
+            if self.len != 0 {
+                table_validation_fail!("empty tree has length {}", self.len);
+            }
+            return Ok(());
+        }
+
+        let mut key_count = 0;
+        let mut node_count = 0;
+        let mut leaf_depth = None;
+        let mut stack = alloc::vec![(ROOT, NO_NODE, 0_usize)];
+        while let Some((id, parent, depth)) = stack.pop() {
+            node_count += 1;
+            if node_count > self.nodes.len() {
+                table_validation_fail!("tree has a cycle");
+            }
+            let Some(node) = self.nodes.get(id) else {
+                table_validation_fail!("node {id} is out of bounds");
+            };
+            if node.parent != parent {
+                table_validation_fail!(
+                    "node {id} has parent {}, expected {parent}",
+                    node.parent,
+                );
+            }
+            let min_keys = if id == ROOT { 1 } else { MIN_KEYS };
+            if node.keys.len() < min_keys || node.keys.len() > MAX_KEYS {
+                table_validation_fail!(
+                    "node {id} has {} keys, expected between {min_keys} and \
+                     {MAX_KEYS}",
+                    node.keys.len(),
+                );
+            }
+            key_count += node.keys.len();
+
+            if node.is_leaf() {
+                match leaf_depth {
+                    None => leaf_depth = Some(depth),
+                    Some(d) if d != depth => {
+                        table_validation_fail!(
+                            "leaves found at depths {d} and {depth}"
+                        );
+                    }
+                    Some(_) => {}
+                }
+            } else {
+                if node.children.len() != node.keys.len() + 1 {
+                    table_validation_fail!(
+                        "node {id} has {} keys but {} children",
+                        node.keys.len(),
+                        node.children.len(),
+                    );
+                }
+                for &child in &node.children {
+                    stack.push((child, id, depth + 1));
+                }
+            }
+        }
+
+        if node_count != self.nodes.len() {
+            table_validation_fail!(
+                "found {node_count} nodes in tree, but {} in arena",
+                self.nodes.len(),
+            );
+        }
+        if key_count != self.len {
+            table_validation_fail!(
+                "found {key_count} keys in nodes, expected {}",
+                self.len,
+            );
+        }
+
+        Ok(())
+    }
+
+    #[inline]
+    pub(crate) fn first(&self) -> Option<ItemIndex> {
+        self.first_pos().map(|pos| self.key_at(pos))
+    }
+
+    #[inline]
+    pub(crate) fn last(&self) -> Option<ItemIndex> {
+        self.last_pos().map(|pos| self.key_at(pos))
+    }
+
+    pub(crate) fn find_index<K, Q, F>(
+        &self,
+        key: &Q,
+        lookup: F,
+    ) -> Option<ItemIndex>
+    where
+        K: Ord,
+        Q: ?Sized + Comparable<K>,
+        F: Fn(ItemIndex) -> K,
+    {
+        // Return the leftmost match, so that if a pathological key results in
+        // several items with equal keys, the lookup consistently returns the
+        // one with the lowest index.
+        let pos = self.partition_first(|stored| {
+            key.compare(&lookup(stored)) == Ordering::Greater
+        })?;
+        let stored = self.key_at(pos);
+        (key.compare(&lookup(stored)) == Ordering::Equal).then_some(stored)
+    }
+
+    /// Finds an index using a caller-provided comparator.
+    ///
+    /// `f` returns the ordering of the item at the given index relative to the
+    /// target, in the same manner as `slice::binary_search_by`.
+    pub(crate) fn find_index_by<F>(&self, f: F) -> Option<ItemIndex>
+    where
+        F: FnMut(ItemIndex) -> Ordering,
+    {
+        self.search(f).ok().map(|pos| self.key_at(pos))
+    }
+
+    /// Returns the indexes whose keys fall within `range`, in key order.
+    ///
+    /// Unlike [`BTreeMap::range`], this doesn't panic if the start of the
+    /// range is greater than the end: the result is simply empty.
+    ///
+    /// [`BTreeMap::range`]: alloc::collections::BTreeMap::range
+    pub(crate) fn range<K, Q, R, F>(&self, range: R, lookup: F) -> Range<'_>
+    where
+        K: Ord,
+        Q: ?Sized + Comparable<K>,
+        R: RangeBounds<Q>,
+        F: Fn(ItemIndex) -> K,
+    {
+        let start = self.lower_bound_pos(range.start_bound(), &lookup);
+        let end = self.upper_bound_pos(range.end_bound(), &lookup);
+        let (Some(start), Some(end)) = (start, end) else {
+            return Range::default();
+        };
+        let (first, last) = (self.key_at(start), self.key_at(end));
+        if first != last
+            && stored_cmp(first, last, &lookup) == Ordering::Greater
+        {
+            return Range::default();
+        }
+        Range { cursors: Cursors::new(self, start, end) }
+    }
+
+    /// Returns the first index whose key is above `bound`.
+    pub(crate) fn lower_bound<K, Q, F>(
+        &self,
+        bound: Bound<&Q>,
+        lookup: F,
+    ) -> Option<ItemIndex>
+    where
+        K: Ord,
+        Q: ?Sized + Comparable<K>,
+        F: Fn(ItemIndex) -> K,
+    {
+        self.lower_bound_pos(bound, &lookup).map(|pos| self.key_at(pos))
+    }
+
+    /// Returns the last index whose key is below `bound`.
+    pub(crate) fn upper_bound<K, Q, F>(
+        &self,
+        bound: Bound<&Q>,
+        lookup: F,
+    ) -> Option<ItemIndex>
+    where
+        K: Ord,
+        Q: ?Sized + Comparable<K>,
+        F: Fn(ItemIndex) -> K,
+    {
+        self.upper_bound_pos(bound, &lookup).map(|pos| self.key_at(pos))
+    }
+
+    fn lower_bound_pos<K, Q, F>(
+        &self,
+        bound: Bound<&Q>,
+        lookup: &F,
+    ) -> Option<Pos>
+    where
+        K: Ord,
+        Q: ?Sized + Comparable<K>,
+        F: Fn(ItemIndex) -> K,
+    {
+        match bound {
+            Bound::Unbounded => self.first_pos(),
+            Bound::Included(key) => self.partition_first(|stored| {
+                key.compare(&lookup(stored)) == Ordering::Greater
+            }),
+            Bound::Excluded(key) => self.partition_first(|stored| {
+                key.compare(&lookup(stored)) != Ordering::Less
+            }),
+        }
+    }
+
+    fn upper_bound_pos<K, Q, F>(
+        &self,
+        bound: Bound<&Q>,
+        lookup: &F,
+    ) -> Option<Pos>
+    where
+        K: Ord,
+        Q: ?Sized + Comparable<K>,
+        F: Fn(ItemIndex) -> K,
+    {
+        match bound {
+            Bound::Unbounded => self.last_pos(),
+            Bound::Included(key) => self.partition_last(|stored| {
+                key.compare(&lookup(stored)) != Ordering::Less
+            }),
+            Bound::Excluded(key) => self.partition_last(|stored| {
+                key.compare(&lookup(stored)) == Ordering::Greater
+            }),
+        }
+    }
+
+    /// Returns the index that follows `index` in key order.
+    pub(crate) fn next_index<K, F>(
+        &self,
+        index: ItemIndex,
+        lookup: F,
+    ) -> Option<ItemIndex>
+    where
+        K: Ord,
+        F: Fn(ItemIndex) -> K,
+    {
+        let key = lookup(index);
+        self.partition_first(|stored| {
+            insert_cmp(stored, index, &key, &lookup) != Ordering::Greater
+        })
+        .map(|pos| self.key_at(pos))
+    }
+
+    /// Returns the index that precedes `index` in key order.
+    pub(crate) fn prev_index<K, F>(
+        &self,
+        index: ItemIndex,
+        lookup: F,
+    ) -> Option<ItemIndex>
+    where
+        K: Ord,
+        F: Fn(ItemIndex) -> K,
+    {
+        let key = lookup(index);
+        self.partition_last(|stored| {
+            insert_cmp(stored, index, &key, &lookup) == Ordering::Less
+        })
+        .map(|pos| self.key_at(pos))
+    }
+
+    /// Builds a table from indexes whose keys are in strictly increasing
+    /// order.
+    ///
+    /// As with the std-based table, the indexes are sorted regardless. For
+    /// input that's already sorted, the sort takes a linear number of
+    /// comparisons.
+    pub(crate) fn from_sorted_indexes<K, F, I>(indexes: I, lookup: F) -> Self
+    where
+        K: Ord,
+        F: Fn(ItemIndex) -> K,
+        I: IntoIterator<Item = ItemIndex>,
+    {
+        let mut indexes: Vec<ItemIndex> = indexes
+            .into_iter()
+            .inspect(|&index| check_not_sentinel(index))
+            .collect();
+        indexes.sort_by(|&a, &b| stored_cmp(a, b, &lookup));
+
+        let mut table = Self::new();
+        for index in indexes {
+            table.push_last(index);
+        }
+        table
+    }
+
+    pub(crate) fn prepare_insert<K, Q, F>(
+        &mut self,
+        index: ItemIndex,
+        key: &Q,
+        lookup: F,
+    ) -> PreparedBTreeInsert<'_>
+    where
+        K: Ord,
+        Q: ?Sized + Comparable<K>,
+        F: Fn(ItemIndex) -> K,
+    {
+        check_not_sentinel(index);
+
+        // All comparisons (which can call into user code) happen here, before
+        // the table is mutated. As with the std-based table, ties are broken by
+        // index so that a pathological `Ord` cannot merge distinct items.
+        let pos = match self
+            .search(|stored| insert_cmp(stored, index, key, &lookup))
+        {
+            Ok(_) => panic!("internal map already contains index {index}"),
+            Err(pos) => pos,
+        };
+
+        PreparedBTreeInsert { table: self, pos, index }
+    }
+
+    pub(crate) fn prepare_remove<K, F>(
+        &mut self,
+        index: ItemIndex,
+        key: &K,
+        lookup: F,
+    ) -> PreparedBTreeRemove<'_>
+    where
+        F: Fn(ItemIndex) -> K,
+        K: Ord,
+    {
+        // As with the std-based table, a miss here most likely means that a key
+        // was mutated without detection. Signal the caller to fall back to
+        // `remove_exact`.
+        let pos =
+            self.search(|stored| insert_cmp(stored, index, key, &lookup)).ok();
+        PreparedBTreeRemove { table: self, pos }
+    }
+
+    pub(crate) fn remove_exact(&mut self, index: ItemIndex) {
+        // If an item key was changed without detection, the order can be wrong,
+        // and a search may miss this index. Fall back to a linear scan.
+        self.retain(|stored| stored != index);
+    }
+
+    pub(crate) fn retain<F>(&mut self, mut f: F)
+    where
+        F: FnMut(ItemIndex) -> bool,
+    {
+        let indexes = self.iter().collect();
+        let mut guard = RetainGuard { table: self, indexes, read: 0, write: 0 };
+        while guard.read < guard.indexes.len() {
+            let index = guard.indexes[guard.read];
+            let keep = f(index);
+            guard.read += 1;
+            if keep {
+                guard.indexes[guard.write] = index;
+                guard.write += 1;
+            }
+        }
+    }
+
+    /// Rewrites every stored index via `remap`.
+    ///
+    /// [`IndexRemap`] preserves relative order, so the sort order is unchanged
+    /// and no comparisons are required.
+    pub(crate) fn remap_indexes(&mut self, remap: &IndexRemap) {
+        for node in &mut self.nodes {
+            for index in &mut node.keys {
+                *index = remap.remap(*index);
+            }
+        }
+    }
+
+    /// Clears the table, removing all items.
+    #[inline]
+    pub(crate) fn clear(&mut self) {
+        self.nodes.clear();
+        self.len = 0;
+    }
+
+    /// Removes and returns the first index in the table.
+    #[inline]
+    pub(crate) fn pop_first(&mut self) -> Option<ItemIndex> {
+        let pos = self.first_pos()?;
+        Some(self.remove_at(pos))
+    }
+
+    /// Removes and returns the last index in the table.
+    #[inline]
+    pub(crate) fn pop_last(&mut self) -> Option<ItemIndex> {
+        let pos = self.last_pos()?;
+        Some(self.remove_at(pos))
+    }
+
+    pub(crate) fn iter(&self) -> Iter<'_> {
+        match (self.first_pos(), self.last_pos()) {
+            (Some(first), Some(last)) => Iter {
+                cursors: Cursors::new(self, first, last),
+                remaining: self.len,
+            },
+            _ => Iter::default(),
+        }
+    }
+
+    pub(crate) fn into_iter(self) -> IntoIter {
+        let indexes: Vec<ItemIndex> = self.iter().collect();
+        IntoIter { inner: indexes.into_iter() }
+    }
+
+    pub(crate) fn state(&self) -> &HashState {
+        &self.hash_state
+    }
+
+    pub(crate) fn compute_hash<K: Hash>(&self, key: K) -> MapHash {
+        MapHash { hash: self.hash_state.hash_one(key) }
+    }
+
+    // --- Searching ---
+
+    /// Descends from the root, binary searching each node with `cmp`.
+    ///
+    /// `cmp` returns the ordering of a stored index relative to the target.
+    /// Returns the position of a matching index if there is one, and otherwise
+    /// the position in a leaf at which the target would be inserted (or `None`
+    /// if the tree is empty).
+    fn search<F>(&self, mut cmp: F) -> Result<Pos, Option<Pos>>
+    where
+        F: FnMut(ItemIndex) -> Ordering,
+    {
+        if self.nodes.is_empty() {
+            return Err(None);
+        }
+        let mut node = ROOT;
+        loop {
+            let n = &self.nodes[node];
+            match n.keys.binary_search_by(|&stored| cmp(stored)) {
+                Ok(offset) => return Ok(Pos { node, offset }),
+                Err(offset) if n.is_leaf() => {
+                    return Err(Some(Pos { node, offset }));
+                }
+                Err(offset) => node = n.children[offset],
+            }
+        }
+    }
+
+    /// Returns the position of the first index for which `is_before` returns
+    /// false.
+    ///
+    /// `is_before` must return true for a prefix of the indexes in key order,
+    /// and false for the rest.
+    fn partition_first<F>(&self, mut is_before: F) -> Option<Pos>
+    where
+        F: FnMut(ItemIndex) -> bool,
+    {
+        if self.nodes.is_empty() {
+            return None;
+        }
+        let mut found = None;
+        let mut node = ROOT;
+        while node != NO_NODE {
+            let n = &self.nodes[node];
+            let offset = n.keys.partition_point(|&stored| is_before(stored));
+            if offset < n.keys.len() {
+                // Anything in the subtree to the left of this key is closer.
+                found = Some(Pos { node, offset });
+            }
+            node = n.children.get(offset).copied().unwrap_or(NO_NODE);
+        }
+        found
+    }
+
+    /// Returns the position of the last index for which `is_before` returns
+    /// true.
+    ///
+    /// `is_before` must return true for a prefix of the indexes in key order,
+    /// and false for the rest.
+    fn partition_last<F>(&self, mut is_before: F) -> Option<Pos>
+    where
+        F: FnMut(ItemIndex) -> bool,
+    {
+        if self.nodes.is_empty() {
+            return None;
+        }
+        let mut found = None;
+        let mut node = ROOT;
+        while node != NO_NODE {
+            let n = &self.nodes[node];
+            let offset = n.keys.partition_point(|&stored| is_before(stored));
+            if offset > 0 {
+                // Anything in the subtree to the right of this key is closer.
+                found = Some(Pos { node, offset: offset - 1 });
+            }
+            node = n.children.get(offset).copied().unwrap_or(NO_NODE);
+        }
+        found
+    }
+
+    // --- Navigation ---
+
+    #[inline]
+    fn key_at(&self, pos: Pos) -> ItemIndex {
+        self.nodes[pos.node].keys[pos.offset]
+    }
+
+    fn first_pos(&self) -> Option<Pos> {
+        (!self.nodes.is_empty())
+            .then(|| Pos { node: self.leftmost_leaf(ROOT), offset: 0 })
+    }
+
+    fn last_pos(&self) -> Option<Pos> {
+        (!self.nodes.is_empty()).then(|| {
+            let node = self.rightmost_leaf(ROOT);
+            Pos { node, offset: self.nodes[node].keys.len() - 1 }
+        })
+    }
+
+    fn leftmost_leaf(&self, mut node: NodeId) -> NodeId {
+        while let Some(&child) = self.nodes[node].children.first() {
+            node = child;
+        }
+        node
+    }
+
+    fn rightmost_leaf(&self, mut node: NodeId) -> NodeId {
+        while let Some(&child) = self.nodes[node].children.last() {
+            node = child;
+        }
+        node
+    }
+
+    /// Returns the position of `child` within its parent's children.
+    fn child_offset(&self, parent: NodeId, child: NodeId) -> usize {
+        self.nodes[parent]
+            .children
+            .iter()
+            .position(|&c| c == child)
+            .expect("child is linked from its parent")
+    }
+
+    /// Returns the position that follows `pos` in key order.
+    fn next_pos(&self, pos: Pos) -> Option<Pos> {
+        let n = &self.nodes[pos.node];
+        if let Some(&child) = n.children.get(pos.offset + 1) {
+            return Some(Pos { node: self.leftmost_leaf(child), offset: 0 });
+        }
+        if pos.offset + 1 < n.keys.len() {
+            return Some(Pos { node: pos.node, offset: pos.offset + 1 });
+        }
+
+        // This was the last key in a leaf: climb until we arrive at a parent
+        // from a child that has a key after it.
+        let mut node = pos.node;
+        loop {
+            let parent = self.nodes[node].parent;
+            if parent == NO_NODE {
+                return None;
+            }
+            let offset = self.child_offset(parent, node);
+            if offset < self.nodes[parent].keys.len() {
+                return Some(Pos { node: parent, offset });
+            }
+            node = parent;
+        }
+    }
+
+    /// Returns the position that precedes `pos` in key order.
+    fn prev_pos(&self, pos: Pos) -> Option<Pos> {
+        let n = &self.nodes[pos.node];
+        if let Some(&child) = n.children.get(pos.offset) {
+            let node = self.rightmost_leaf(child);
+            return Some(Pos { node, offset: self.nodes[node].keys.len() - 1 });
+        }
+        if pos.offset > 0 {
+            return Some(Pos { node: pos.node, offset: pos.offset - 1 });
+        }
+
+        // This was the first key in a leaf: climb until we arrive at a parent
+        // from a child that has a key before it.
+        let mut node = pos.node;
+        loop {
+            let parent = self.nodes[node].parent;
+            if parent == NO_NODE {
+                return None;
+            }
+            let offset = self.child_offset(parent, node);
+            if offset > 0 {
+                return Some(Pos { node: parent, offset: offset - 1 });
+            }
+            node = parent;
+        }
+    }
+
+    // --- Mutation ---
+
+    fn alloc_node(&mut self, node: Node) -> NodeId {
+        self.nodes.push(node);
+        self.nodes.len() - 1
+    }
+
+    /// Removes the node at `id`, which must already be unlinked from the
+    /// tree, by moving the last node in the arena into its place.
+    ///
+    /// Returns the previous position of the moved node, which is `id` itself
+    /// if `id` was the last node.
+    fn free_node(&mut self, id: NodeId) -> NodeId {
+        let last = self.nodes.len() - 1;
+        self.nodes.swap_remove(id);
+        if id != last {
+            // The root is at position 0 and is never freed this way, so the
+            // moved node has a parent.
+            let parent = self.nodes[id].parent;
+            let offset = self.child_offset(parent, last);
+            self.nodes[parent].children[offset] = id;
+            self.set_parent_of_children(id);
+        }
+        last
+    }
+
+    /// Points the parent links of `id`'s children at `id`.
+    fn set_parent_of_children(&mut self, id: NodeId) {
+        for i in 0..self.nodes[id].children.len() {
+            let child = self.nodes[id].children[i];
+            self.nodes[child].parent = id;
+        }
+    }
+
+    /// Appends an index that sorts after every index in the table.
+    fn push_last(&mut self, index: ItemIndex) {
+        let pos = self
+            .last_pos()
+            .map(|pos| Pos { node: pos.node, offset: pos.offset + 1 });
+        self.insert_at(pos, index);
+    }
+
+    /// Inserts `index` at a leaf position returned by [`Self::search`].
+    fn insert_at(&mut self, pos: Option<Pos>, index: ItemIndex) {
+        self.len += 1;
+        let Some(Pos { node, offset }) = pos else {
+            self.alloc_node(Node {
+                parent: NO_NODE,
+                keys: alloc::vec![index],
+                children: Vec::new(),
+            });
+            return;
+        };
+
+        self.nodes[node].keys.insert(offset, index);
+        self.split_overfull(node);
+    }
+
+    /// Splits `node` if it has too many keys, moving the median key into the
+    /// parent and continuing upwards as necessary.
+    fn split_overfull(&mut self, mut node: NodeId) {
+        while self.nodes[node].keys.len() > MAX_KEYS {
+            let n = &mut self.nodes[node];
+            let mid = n.keys.len() / 2;
+            let right_keys = n.keys.split_off(mid + 1);
+            let median = n.keys.pop().expect("overfull node has keys");
+            let right_children = if n.is_leaf() {
+                Vec::new()
+            } else {
+                n.children.split_off(mid + 1)
+            };
+            let parent = n.parent;
+
+            if parent == NO_NODE {
+                // Splitting the root. To keep the root at position 0, move its
+                // remaining keys and children to a new left node.
+                let left_keys = mem::take(&mut self.nodes[ROOT].keys);
+                let left_children = mem::take(&mut self.nodes[ROOT].children);
+                let left = self.alloc_node(Node {
+                    parent: ROOT,
+                    keys: left_keys,
+                    children: left_children,
+                });
+                self.set_parent_of_children(left);
+                let right = self.alloc_node(Node {
+                    parent: ROOT,
+                    keys: right_keys,
+                    children: right_children,
+                });
+                self.set_parent_of_children(right);
+
+                let root = &mut self.nodes[ROOT];
+                root.keys.push(median);
+                root.children.extend([left, right]);
+                return;
+            }
+
+            let right = self.alloc_node(Node {
+                parent,
+                keys: right_keys,
+                children: right_children,
+            });
+            self.set_parent_of_children(right);
+
+            let offset = self.child_offset(parent, node);
+            let p = &mut self.nodes[parent];
+            p.keys.insert(offset, median);
+            p.children.insert(offset + 1, right);
+            node = parent;
+        }
+    }
+
+    /// Removes and returns the index at `pos`.
+    fn remove_at(&mut self, pos: Pos) -> ItemIndex {
+        self.len -= 1;
+        let Pos { node, offset } = pos;
+        if self.nodes[node].is_leaf() {
+            let removed = self.nodes[node].keys.remove(offset);
+            self.rebalance(node);
+            removed
+        } else {
+            // Replace the key with its predecessor, which is the last key in
+            // the rightmost leaf of the left subtree, and remove that from the
+            // leaf instead.
+            let leaf = self.rightmost_leaf(self.nodes[node].children[offset]);
+            let pred = self.nodes[leaf].keys.pop().expect("leaves have keys");
+            let removed =
+                mem::replace(&mut self.nodes[node].keys[offset], pred);
+            self.rebalance(leaf);
+            removed
+        }
+    }
+
+    /// Restores the minimum size of `node` after a removal, by borrowing a
+    /// key from a sibling or merging with it, and continuing upwards as
+    /// necessary.
+    fn rebalance(&mut self, mut node: NodeId) {
+        loop {
+            let len = self.nodes[node].keys.len();
+            let parent = self.nodes[node].parent;
+            if parent == NO_NODE {
+                if len == 0 {
+                    // The root is empty. Its only child (if any) becomes the
+                    // new root, by moving into position 0.
+                    match self.nodes[ROOT].children.pop() {
+                        Some(child) => {
+                            let keys = mem::take(&mut self.nodes[child].keys);
+                            let children =
+                                mem::take(&mut self.nodes[child].children);
+                            self.nodes[ROOT].keys = keys;
+                            self.nodes[ROOT].children = children;
+                            self.set_parent_of_children(ROOT);
+                            self.free_node(child);
+                        }
+                        None => self.nodes.clear(),
+                    }
+                }
+                return;
+            }
+            if len >= MIN_KEYS {
+                return;
+            }
+
+            let offset = self.child_offset(parent, node);
+            if offset > 0 {
+                let left = self.nodes[parent].children[offset - 1];
+                if self.nodes[left].keys.len() > MIN_KEYS {
+                    self.rotate_right(parent, offset - 1);
+                    return;
+                }
+            }
+            if offset + 1 < self.nodes[parent].children.len() {
+                let right = self.nodes[parent].children[offset + 1];
+                if self.nodes[right].keys.len() > MIN_KEYS {
+                    self.rotate_left(parent, offset);
+                    return;
+                }
+            }
+
+            // Neither sibling has a key to spare, so merge with one of them.
+            node = self.merge(parent, offset.saturating_sub(1));
+        }
+    }
+
+    /// Moves a key from the child to the left of `parent.keys[sep]`, through
+    /// the separator, into the child to the right of it.
+    fn rotate_right(&mut self, parent: NodeId, sep: usize) {
+        let left = self.nodes[parent].children[sep];
+        let right = self.nodes[parent].children[sep + 1];
+
+        let key = self.nodes[left].keys.pop().expect("sibling has keys");
+        let child = self.nodes[left].children.pop();
+        let sep_key = mem::replace(&mut self.nodes[parent].keys[sep], key);
+        self.nodes[right].keys.insert(0, sep_key);
+        if let Some(child) = child {
+            self.nodes[right].children.insert(0, child);
+            self.nodes[child].parent = right;
+        }
+    }
+
+    /// Moves a key from the child to the right of `parent.keys[sep]`, through
+    /// the separator, into the child to the left of it.
+    fn rotate_left(&mut self, parent: NodeId, sep: usize) {
+        let left = self.nodes[parent].children[sep];
+        let right = self.nodes[parent].children[sep + 1];
+
+        let key = self.nodes[right].keys.remove(0);
+        let child = if self.nodes[right].is_leaf() {
+            None
+        } else {
+            Some(self.nodes[right].children.remove(0))
+        };
+        let sep_key = mem::replace(&mut self.nodes[parent].keys[sep], key);
+        self.nodes[left].keys.push(sep_key);
+        if let Some(child) = child {
+            self.nodes[left].children.push(child);
+            self.nodes[child].parent = left;
+        }
+    }
+
+    /// Merges the children on either side of `parent.keys[sep]`, along with
+    /// the separator, into the left child.
+    ///
+    /// Returns the position of `parent`, which can change as a result of
+    /// freeing the right child.
+    fn merge(&mut self, parent: NodeId, sep: usize) -> NodeId {
+        let left = self.nodes[parent].children[sep];
+        let right = self.nodes[parent].children.remove(sep + 1);
+        let sep_key = self.nodes[parent].keys.remove(sep);
+
+        let right_keys = mem::take(&mut self.nodes[right].keys);
+        let right_children = mem::take(&mut self.nodes[right].children);
+        for &child in &right_children {
+            self.nodes[child].parent = left;
+        }
+        let l = &mut self.nodes[left];
+        l.keys.push(sep_key);
+        l.keys.extend(right_keys);
+        l.children.extend(right_children);
+
+        let moved_from = self.free_node(right);
+        if parent == moved_from { right } else { parent }
+    }
+}
+
+/// Rebuilds the table from the indexes kept by [`MapBTreeTable::retain`] when
+/// dropped.
+///
+/// If the predicate panics, the indexes it has already rejected are still
+/// removed, and the rest are kept.
+struct RetainGuard<'a> {
+    table: &'a mut MapBTreeTable,
+    indexes: Vec<ItemIndex>,
+    // Indexes before `read` have been passed to the predicate, and the ones it
+    // kept have been moved to before `write`.
+    read: usize,
+    write: usize,
+}
+
+impl Drop for RetainGuard<'_> {
+    fn drop(&mut self) {
+        if self.read == self.write {
+            // Nothing was removed.
+            return;
+        }
+        self.indexes.copy_within(self.read.., self.write);
+        self.indexes.truncate(self.indexes.len() - (self.read - self.write));
+
+        // The kept indexes are still in order, so the tree can be rebuilt
+        // without any comparisons.
+        self.table.clear();
+        for &index in &self.indexes {
+            self.table.push_last(index);
+        }
+    }
+}
+
+/// A pair of positions that iterate towards each other.
+#[derive(Clone, Debug, Default)]
+struct Cursors<'a> {
+    table: Option<&'a MapBTreeTable>,
+    // Both are `None` once the cursors have met.
+    front: Option<Pos>,
+    back: Option<Pos>,
+}
+
+impl<'a> Cursors<'a> {
+    fn new(table: &'a MapBTreeTable, front: Pos, back: Pos) -> Self {
+        Self { table: Some(table), front: Some(front), back: Some(back) }
+    }
+
+    fn next(&mut self) -> Option<ItemIndex> {
+        let table = self.table?;
+        let front = self.front?;
+        if Some(front) == self.back {
+            self.front = None;
+            self.back = None;
+        } else {
+            self.front = table.next_pos(front);
+            if self.front.is_none() {
+                self.back = None;
+            }
+        }
+        Some(table.key_at(front))
+    }
+
+    fn next_back(&mut self) -> Option<ItemIndex> {
+        let table = self.table?;
+        let back = self.back?;
+        if Some(back) == self.front {
+            self.front = None;
+            self.back = None;
+        } else {
+            self.back = table.prev_pos(back);
+            if self.back.is_none() {
+                self.front = None;
+            }
+        }
+        Some(table.key_at(back))
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub(crate) struct Iter<'a> {
+    cursors: Cursors<'a>,
+    remaining: usize,
+}
+
+impl<'a> Iter<'a> {
+    pub(crate) fn len(&self) -> usize {
+        self.remaining
+    }
+}
+
+impl<'a> Iterator for Iter<'a> {
+    type Item = ItemIndex;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let index = self.cursors.next()?;
+        self.remaining -= 1;
+        Some(index)
+    }
+}
+
+impl<'a> DoubleEndedIterator for Iter<'a> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let index = self.cursors.next_back()?;
+        self.remaining -= 1;
+        Some(index)
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub(crate) struct Range<'a> {
+    cursors: Cursors<'a>,
+}
+
+impl<'a> Iterator for Range<'a> {
+    type Item = ItemIndex;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.cursors.next()
+    }
+}
+
+impl<'a> DoubleEndedIterator for Range<'a> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.cursors.next_back()
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct IntoIter {
+    inner: vec::IntoIter<ItemIndex>,
+}
+
+impl IntoIter {
+    pub(crate) fn len(&self) -> usize {
+        self.inner.len()
+    }
+}
+
+impl Iterator for IntoIter {
+    type Item = ItemIndex;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next()
+    }
+}
+
+impl DoubleEndedIterator for IntoIter {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.inner.next_back()
+    }
+}
+
+pub(crate) struct PreparedBTreeInsert<'a> {
+    table: &'a mut MapBTreeTable,
+    pos: Option<Pos>,
+    index: ItemIndex,
+}
+
+impl PreparedBTreeInsert<'_> {
+    pub(crate) fn insert(self) {
+        self.table.insert_at(self.pos, self.index);
+    }
+}
+
+pub(crate) struct PreparedBTreeRemove<'a> {
+    table: &'a mut MapBTreeTable,
+    pos: Option<Pos>,
+}
+
+impl PreparedBTreeRemove<'_> {
+    pub(crate) fn remove(self) -> bool {
+        match self.pos {
+            Some(pos) => {
+                self.table.remove_at(pos);
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+#[inline]
+fn check_not_sentinel(index: ItemIndex) {
+    if index == ItemIndex::SENTINEL {
+        panic!("btree map overflow, index with value {index:?} was added")
+    }
+}
+
+/// Returns the ordering of two stored indexes, with ties broken by index.
+fn stored_cmp<K, F>(a: ItemIndex, b: ItemIndex, lookup: &F) -> Ordering
+where
+    K: Ord,
+    F: Fn(ItemIndex) -> K,
+{
+    if a == b {
+        return Ordering::Equal;
+    }
+    lookup(a).cmp(&lookup(b)).then_with(|| a.cmp(&b))
+}
+
+/// Returns the ordering of `stored` relative to the item at `index`, whose key
+/// is `key`. (`index` may or may not already be stored in the table.)
+///
+/// Items at the same index are always equal, and the tiebreaker (then_with)
+/// preserves bijection when a pathological user `Ord` returns `Equal` for
+/// distinct keys.
+fn insert_cmp<K, Q, F>(
+    stored: ItemIndex,
+    index: ItemIndex,
+    key: &Q,
+    lookup: &F,
+) -> Ordering
+where
+    K: Ord,
+    Q: ?Sized + Comparable<K>,
+    F: Fn(ItemIndex) -> K,
+{
+    if stored == index {
+        return Ordering::Equal;
+    }
+    key.compare(&lookup(stored)).reverse().then_with(|| stored.cmp(&index))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::support::{alloc::Global, item_set::ItemSet};
+
+    /// A xorshift generator, so that the test is deterministic.
+    struct Rng(u64);
+
+    impl Rng {
+        fn next(&mut self) -> u64 {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 7;
+            self.0 ^= self.0 << 17;
+            self.0
+        }
+
+        fn below(&mut self, n: usize) -> usize {
+            (self.next() % n as u64) as usize
+        }
+    }
+
+    /// Checks the table against a reference list of `(key, index)` pairs.
+    fn check(table: &MapBTreeTable, expected: &mut [(u32, ItemIndex)]) {
+        expected.sort_unstable();
+        table
+            .validate(expected.len(), ValidateCompact::NonCompact)
+            .expect("table is valid");
+
+        let forward: Vec<_> = table.iter().collect();
+        let expected_indexes: Vec<_> =
+            expected.iter().map(|&(_, index)| index).collect();
+        assert_eq!(forward, expected_indexes);
+        let mut backward: Vec<_> = table.iter().rev().collect();
+        backward.reverse();
+        assert_eq!(backward, expected_indexes);
+        assert_eq!(table.first(), expected_indexes.first().copied());
+        assert_eq!(table.last(), expected_indexes.last().copied());
+    }
+
+    #[test]
+    fn random_operations_match_sorted_vec() {
+        let mut rng = Rng(0x2545_f491_4f6c_dd1d);
+        // Keys are drawn from a small range, so that some are duplicated: ties
+        // are broken by index, as with a pathological `Ord`.
+        let mut keys: Vec<u32> = Vec::new();
+        let mut table = MapBTreeTable::new();
+        let mut expected: Vec<(u32, ItemIndex)> = Vec::new();
+
+        for step in 0..4000 {
+            let lookup = |index: ItemIndex| keys[index.as_u32() as usize];
+            match rng.below(10) {
+                0..=5 => {
+                    let index = ItemIndex::new(keys.len() as u32);
+                    let key = rng.below(300) as u32;
+                    keys.push(key);
+                    let lookup =
+                        |index: ItemIndex| keys[index.as_u32() as usize];
+                    table.prepare_insert(index, &key, lookup).insert();
+                    expected.push((key, index));
+                }
+                6 | 7 if !expected.is_empty() => {
+                    let (key, index) =
+                        expected.remove(rng.below(expected.len()));
+                    assert!(table.prepare_remove(index, &key, lookup).remove());
+                }
+                8 => {
+                    let popped = if rng.below(2) == 0 {
+                        table.pop_first()
+                    } else {
+                        table.pop_last()
+                    };
+                    if let Some(index) = popped {
+                        expected.retain(|&(_, i)| i != index);
+                    }
+                }
+                _ if step % 97 == 0 => {
+                    table.retain(|index| index.as_u32() % 3 != 0);
+                    expected.retain(|&(_, index)| index.as_u32() % 3 != 0);
+                }
+                _ if !expected.is_empty() => {
+                    let (_, index) = expected.remove(rng.below(expected.len()));
+                    table.remove_exact(index);
+                }
+                _ => {}
+            }
+            check(&table, &mut expected);
+
+            // Bounds and ranges.
+            let lookup = |index: ItemIndex| keys[index.as_u32() as usize];
+            let a = rng.below(310) as u32;
+            let b = rng.below(310) as u32;
+            let in_range: Vec<_> = expected
+                .iter()
+                .filter(|&&(key, _)| a <= key && key < b)
+                .map(|&(_, index)| index)
+                .collect();
+            assert_eq!(table.range(a..b, lookup).collect::<Vec<_>>(), in_range);
+            let mut rev: Vec<_> = table.range(a..b, lookup).rev().collect();
+            rev.reverse();
+            assert_eq!(rev, in_range);
+
+            assert_eq!(
+                table.lower_bound(Bound::Included(&a), lookup),
+                expected.iter().find(|&&(key, _)| key >= a).map(|&(_, i)| i),
+            );
+            assert_eq!(
+                table.lower_bound(Bound::Excluded(&a), lookup),
+                expected.iter().find(|&&(key, _)| key > a).map(|&(_, i)| i),
+            );
+            assert_eq!(
+                table.upper_bound(Bound::Included(&a), lookup),
+                expected.iter().rfind(|&&(key, _)| key <= a).map(|&(_, i)| i),
+            );
+            assert_eq!(
+                table.upper_bound(Bound::Excluded(&a), lookup),
+                expected.iter().rfind(|&&(key, _)| key < a).map(|&(_, i)| i),
+            );
+
+            assert_eq!(
+                table.find_index(&a, lookup),
+                expected.iter().find(|&&(key, _)| key == a).map(|&(_, i)| i),
+            );
+
+            if !expected.is_empty() {
+                let pos = rng.below(expected.len());
+                let index = expected[pos].1;
+                assert_eq!(
+                    table.next_index(index, lookup),
+                    expected.get(pos + 1).map(|&(_, i)| i),
+                );
+                assert_eq!(
+                    table.prev_index(index, lookup),
+                    pos.checked_sub(1).map(|pos| expected[pos].1),
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn from_sorted_indexes_and_remap() {
+        let keys: Vec<u32> = (0..500).map(|i| (i * 7) % 500).collect();
+        let lookup = |index: ItemIndex| keys[index.as_u32() as usize];
+        let table = MapBTreeTable::from_sorted_indexes(
+            (0..500).map(ItemIndex::new),
+            lookup,
+        );
+        let mut expected: Vec<_> =
+            (0..500).map(|i| (keys[i as usize], ItemIndex::new(i))).collect();
+        check(&table, &mut expected);
+        assert_eq!(table.iter().len(), 500);
+        assert_eq!(table.clone().into_iter().len(), 500);
+
+        // Remove every other index from an item set, and compact it to get a
+        // remap.
+        let mut items: ItemSet<u32, Global> = ItemSet::new();
+        for i in 0..500 {
+            items.assert_can_grow().insert(i);
+        }
+        let mut table = table;
+        table.retain(|index| index.as_u32() % 2 == 0);
+        for i in (1..500).step_by(2) {
+            items.remove(ItemIndex::new(i));
+        }
+        let remap = items.compact();
+        table.remap_indexes(&remap);
+        table
+            .validate(250, ValidateCompact::Compact)
+            .expect("remapped table is compact");
+    }
+}
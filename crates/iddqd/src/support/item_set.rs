@@ -48,6 +48,7 @@
 use super::{
     ItemIndex,
     alloc::{AllocWrapper, Allocator, Global, global_alloc},
+    fixed_hasher::FixedState,
 };
 use crate::{
     errors::TryReserveError,
@@ -56,6 +57,7 @@ use crate::{
 use allocator_api2::vec::Vec;
 use core::{
     fmt,
+    hash::{BuildHasher, Hash, Hasher},
     iter::FusedIterator,
     marker::PhantomData,
     ops::{Index, IndexMut},
@@ -141,7 +143,6 @@ impl<T, A: Allocator> core::ops::Deref for GrowHandle<'_, T, A> {
 
 impl<'a, T, A: Allocator> GrowHandle<'a, T, A> {
     /// Returns the index that [`Self::insert`] will assign.
-    #[cfg_attr(not(feature = "std"), expect(dead_code))]
     #[inline]
     pub(crate) fn next_index(&self) -> ItemIndex {
         if self.free_head == ItemIndex::SENTINEL {
@@ -254,6 +255,29 @@ impl<T: Clone, A: Clone + Allocator> Clone for ItemSet<T, A> {
             len: self.len,
         }
     }
+
+    fn clone_from(&mut self, source: &Self) {
+        // Publish empty metadata first, and only copy the source's metadata
+        // once every slot has been cloned. If `T::clone` panics partway
+        // through, `ClearOnDrop` empties the slots so they match the metadata.
+        self.clear();
+        let slots = ClearOnDrop(&mut self.items);
+        slots.0.extend(source.items.iter().cloned());
+        core::mem::forget(slots);
+        self.free_head = source.free_head;
+        self.len = source.len;
+    }
+}
+
+/// Clears a slot vector when dropped, unless forgotten.
+struct ClearOnDrop<'a, T, A: Allocator>(
+    &'a mut Vec<ItemSlot<T>, AllocWrapper<A>>,
+);
+
+impl<T, A: Allocator> Drop for ClearOnDrop<'_, T, A> {
+    fn drop(&mut self) {
+        self.0.clear();
+    }
 }
 
 impl<T: fmt::Debug, A: Allocator> fmt::Debug for ItemSet<T, A> {
@@ -299,12 +323,20 @@ impl<T, A: Allocator> ItemSet<T, A> {
         &self.items.allocator().0
     }
 
+    /// Returns a shared view of the backing slot buffer.
+    ///
+    /// Used by the ordered maps' shared iterators, which look items up by the
+    /// indexes their B-trees yield.
+    #[inline]
+    pub(crate) fn slots(&self) -> ItemSlots<'_, T> {
+        ItemSlots { slots: &self.items }
+    }
+
     /// Returns the backing slot buffer as a mutable slice.
     ///
     /// Used by [`ItemSlotsPtr::new`] to build an allocator-agnostic raw
     /// pointer over the slot buffer for the per-map `IterMut` iterators.
     #[inline]
-    #[cfg_attr(not(feature = "std"), expect(dead_code))]
     pub(crate) fn slots_mut(&mut self) -> &mut [ItemSlot<T>] {
         &mut self.items
     }
@@ -405,6 +437,12 @@ impl<T, A: Allocator> ItemSet<T, A> {
         self.len as usize
     }
 
+    /// Returns the number of slots, occupied or vacant.
+    #[inline]
+    pub(crate) fn slot_count(&self) -> usize {
+        self.items.len()
+    }
+
     #[inline]
     pub(crate) fn iter(&self) -> Iter<'_, T> {
         Iter::new(self)
@@ -420,11 +458,48 @@ impl<T, A: Allocator> ItemSet<T, A> {
         ValuesMut::new(self)
     }
 
+    #[cfg(feature = "rayon")]
+    #[inline]
+    pub(crate) fn par_values(&self) -> ParValues<'_, T>
+    where
+        T: Sync,
+    {
+        ParValues::new(self)
+    }
+
     #[inline]
     pub(crate) fn into_values(self) -> IntoValues<T, A> {
         IntoValues::new(self)
     }
 
+    /// Feeds the items into `state` in a way that doesn't depend on the slots
+    /// they're stored in.
+    ///
+    /// Each item is hashed with a fixed-seed hasher, and the results are
+    /// summed. The map's own hasher can't be used for this, since two equal
+    /// maps may have hashers with different seeds.
+    pub(crate) fn hash_unordered<H: Hasher>(&self, state: &mut H)
+    where
+        T: Hash,
+    {
+        let hash_state = FixedState::new();
+        let sum = self.values().fold(0u64, |sum, item| {
+            sum.wrapping_add(hash_state.hash_one(item))
+        });
+        state.write_usize(self.len());
+        state.write_u64(sum);
+    }
+
+    #[inline]
+    pub(crate) fn into_iter_indexed(self) -> IntoIter<T, A> {
+        IntoIter::new(self)
+    }
+
+    #[inline]
+    pub(crate) fn into_values_shrinking(self) -> IntoValuesShrinking<T, A> {
+        IntoValuesShrinking::new(self)
+    }
+
     #[inline]
     pub(crate) fn get(&self, index: ItemIndex) -> Option<&T> {
         self.items.get(index.as_u32() as usize).and_then(ItemSlot::as_ref)
@@ -544,6 +619,19 @@ impl<T, A: Allocator> ItemSet<T, A> {
         self.items.clear();
     }
 
+    /// Removes all items from the set, returning them as an iterator.
+    ///
+    /// Preserves `items.capacity()`, like [`clear`](Self::clear). The set is
+    /// empty as soon as this returns, even if the iterator is leaked.
+    pub(crate) fn drain(&mut self) -> Drain<'_, T, A> {
+        let remaining = self.len();
+        // As with `clear`, publish the post-drain metadata first. `Vec::drain`
+        // also empties `items` up front.
+        self.free_head = ItemIndex::SENTINEL;
+        self.len = 0;
+        Drain { inner: self.items.drain(..), remaining }
+    }
+
     /// This method assumes that value has the same ID. It also asserts
     /// that `index` is valid (and panics if it isn't).
     #[inline]
@@ -695,6 +783,46 @@ impl<T, A: Allocator> IndexMut<ItemIndex> for ItemSet<T, A> {
     }
 }
 
+// --- ItemSlots ------------------------------------------------------------
+
+/// A shared view of an [`ItemSet`]'s slot buffer.
+///
+/// Like [`ItemSlotsPtr`], this deliberately does not mention `ItemSet<T, A>`,
+/// so that iterators holding it stay allocator-agnostic.
+pub(crate) struct ItemSlots<'a, T> {
+    slots: &'a [ItemSlot<T>],
+}
+
+impl<T> Clone for ItemSlots<'_, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for ItemSlots<'_, T> {}
+
+impl<T> fmt::Debug for ItemSlots<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ItemSlots").field("slots", &self.slots.len()).finish()
+    }
+}
+
+impl<'a, T> ItemSlots<'a, T> {
+    /// Returns a reference to the item at `index`, for the full lifetime `'a`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds or the slot is vacant, the same as
+    /// indexing into an [`ItemSet`].
+    #[inline]
+    pub(crate) fn item(self, index: ItemIndex) -> &'a T {
+        self.slots
+            .get(index.as_u32() as usize)
+            .and_then(ItemSlot::as_ref)
+            .unwrap_or_else(|| panic!("ItemSet index not found: {index}"))
+    }
+}
+
 // --- ItemSlotsPtr ---------------------------------------------------------
 
 /// A raw pointer into the start of an [`ItemSet`]'s slot buffer, with the same
@@ -749,7 +877,6 @@ impl<'a, T> ItemSlotsPtr<'a, T> {
     ///
     /// The returned handle borrows `slots` for `'a`.
     #[inline]
-    #[cfg_attr(not(feature = "std"), expect(dead_code))]
     pub(crate) fn new(slots: &'a mut [ItemSlot<T>]) -> Self {
         Self {
             start_ptr: slots.as_mut_ptr(),
@@ -779,7 +906,6 @@ impl<'a, T> ItemSlotsPtr<'a, T> {
     /// once. That is the only thing that keeps the returned `&mut T`
     /// references disjoint and aliasing-free.
     #[inline]
-    #[cfg_attr(not(feature = "std"), expect(dead_code))]
     pub(crate) unsafe fn get_mut(&mut self, index: ItemIndex) -> &'a mut T {
         let raw_index = index.as_u32() as usize;
         // Belt-and-suspenders bounds check. The outer index tables only ever
@@ -940,6 +1066,54 @@ impl<T> ExactSizeIterator for Values<'_, T> {
 
 impl<T> FusedIterator for Values<'_, T> {}
 
+/// A parallel iterator over `&item` references in an [`ItemSet`].
+///
+/// Splits the slot buffer across threads, skipping vacant slots. Since
+/// the number of occupied slots in each half of a split isn't known, this
+/// can't be an `IndexedParallelIterator`.
+#[cfg(feature = "rayon")]
+pub(crate) struct ParValues<'a, T> {
+    slots: &'a [ItemSlot<T>],
+}
+
+#[cfg(feature = "rayon")]
+impl<'a, T> ParValues<'a, T> {
+    fn new<A: Allocator>(set: &'a ItemSet<T, A>) -> Self {
+        Self { slots: &set.items }
+    }
+}
+
+#[cfg(feature = "rayon")]
+impl<T> Clone for ParValues<'_, T> {
+    fn clone(&self) -> Self {
+        Self { slots: self.slots }
+    }
+}
+
+#[cfg(feature = "rayon")]
+impl<T> fmt::Debug for ParValues<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ParValues").finish_non_exhaustive()
+    }
+}
+
+#[cfg(feature = "rayon")]
+impl<'a, T: Sync> rayon::iter::ParallelIterator for ParValues<'a, T> {
+    type Item = &'a T;
+
+    fn drive_unindexed<C>(self, consumer: C) -> C::Result
+    where
+        C: rayon::iter::plumbing::UnindexedConsumer<Self::Item>,
+    {
+        use rayon::iter::IntoParallelIterator;
+
+        self.slots
+            .into_par_iter()
+            .filter_map(ItemSlot::as_ref)
+            .drive_unindexed(consumer)
+    }
+}
+
 /// An iterator over `&mut item` references in an [`ItemSet`].
 pub(crate) struct ValuesMut<'a, T> {
     inner: core::slice::IterMut<'a, ItemSlot<T>>,
@@ -1047,6 +1221,188 @@ impl<T, A: Allocator> ExactSizeIterator for IntoValues<T, A> {
 
 impl<T, A: Allocator> FusedIterator for IntoValues<T, A> {}
 
+/// An owning iterator over `(index, item)` pairs in an [`ItemSet`].
+pub(crate) struct IntoIter<T, A: Allocator> {
+    inner: core::iter::Enumerate<
+        allocator_api2::vec::IntoIter<ItemSlot<T>, AllocWrapper<A>>,
+    >,
+    remaining: usize,
+}
+
+impl<T, A: Allocator> IntoIter<T, A> {
+    fn new(set: ItemSet<T, A>) -> Self {
+        let remaining = set.len();
+        let consuming = set.into_consuming();
+        Self { inner: consuming.items.into_iter().enumerate(), remaining }
+    }
+}
+
+impl<T: fmt::Debug, A: Allocator> fmt::Debug for IntoIter<T, A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("IntoIter").field("remaining", &self.remaining).finish()
+    }
+}
+
+impl<T, A: Allocator> Iterator for IntoIter<T, A> {
+    type Item = (ItemIndex, T);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        for (i, slot) in self.inner.by_ref() {
+            if let ItemSlot::Occupied(v) = slot {
+                debug_assert!(
+                    self.remaining > 0,
+                    "iterator yielded more items than ItemSet::len()",
+                );
+                self.remaining -= 1;
+                return Some((ItemIndex::new(i as u32), v));
+            }
+        }
+        None
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<T, A: Allocator> ExactSizeIterator for IntoIter<T, A> {
+    #[inline]
+    fn len(&self) -> usize {
+        self.remaining
+    }
+}
+
+impl<T, A: Allocator> FusedIterator for IntoIter<T, A> {}
+
+/// A draining iterator over the items in an [`ItemSet`].
+pub(crate) struct Drain<'a, T, A: Allocator> {
+    inner: allocator_api2::vec::Drain<'a, ItemSlot<T>, AllocWrapper<A>>,
+    remaining: usize,
+}
+
+impl<T: fmt::Debug, A: Allocator> fmt::Debug for Drain<'_, T, A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Drain").field("remaining", &self.remaining).finish()
+    }
+}
+
+impl<T, A: Allocator> Iterator for Drain<'_, T, A> {
+    type Item = T;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        for slot in self.inner.by_ref() {
+            if let ItemSlot::Occupied(v) = slot {
+                debug_assert!(
+                    self.remaining > 0,
+                    "iterator yielded more items than ItemSet::len()",
+                );
+                self.remaining -= 1;
+                return Some(v);
+            }
+        }
+        None
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<T, A: Allocator> ExactSizeIterator for Drain<'_, T, A> {
+    #[inline]
+    fn len(&self) -> usize {
+        self.remaining
+    }
+}
+
+impl<T, A: Allocator> FusedIterator for Drain<'_, T, A> {}
+
+/// An owning iterator over the items in an [`ItemSet`] that releases backing
+/// storage as it goes.
+///
+/// Slots are popped off the end of the buffer. Whenever the number of
+/// remaining slots drops to half the capacity, the buffer is shrunk to fit.
+/// Each shrink copies at most half as many slots as the previous one, so the
+/// total copying work is linear in the original number of slots.
+pub(crate) struct IntoValuesShrinking<T, A: Allocator> {
+    items: Vec<ItemSlot<T>, AllocWrapper<A>>,
+    remaining: usize,
+}
+
+impl<T, A: Allocator> IntoValuesShrinking<T, A> {
+    /// Buffers at or below this capacity are not shrunk further: the
+    /// reallocation would cost more than the memory it frees.
+    const MIN_SHRINK_CAPACITY: usize = 16;
+
+    fn new(set: ItemSet<T, A>) -> Self {
+        let remaining = set.len();
+        let consuming = set.into_consuming();
+        Self { items: consuming.items, remaining }
+    }
+
+    #[inline]
+    fn maybe_shrink(&mut self) {
+        let capacity = self.items.capacity();
+        if capacity > Self::MIN_SHRINK_CAPACITY
+            && self.items.len() <= capacity / 2
+        {
+            self.items.shrink_to_fit();
+        }
+    }
+
+    #[inline]
+    pub(crate) fn capacity(&self) -> usize {
+        self.items.capacity()
+    }
+}
+
+impl<T: fmt::Debug, A: Allocator> fmt::Debug for IntoValuesShrinking<T, A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("IntoValuesShrinking")
+            .field("remaining", &self.remaining)
+            .field("capacity", &self.items.capacity())
+            .finish()
+    }
+}
+
+impl<T, A: Allocator> Iterator for IntoValuesShrinking<T, A> {
+    type Item = T;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some(slot) = self.items.pop() {
+            self.maybe_shrink();
+            if let ItemSlot::Occupied(v) = slot {
+                debug_assert!(
+                    self.remaining > 0,
+                    "iterator yielded more items than ItemSet::len()",
+                );
+                self.remaining -= 1;
+                return Some(v);
+            }
+        }
+        None
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<T, A: Allocator> ExactSizeIterator for IntoValuesShrinking<T, A> {
+    #[inline]
+    fn len(&self) -> usize {
+        self.remaining
+    }
+}
+
+impl<T, A: Allocator> FusedIterator for IntoValuesShrinking<T, A> {}
+
 /// An [`ItemSet`] consumed into an owned, by-index take-only version.
 ///
 /// Produced by [`ItemSet::into_consuming`]. The free chain is no longer
@@ -1069,7 +1425,6 @@ impl<T, A: Allocator> ConsumingItemSet<T, A> {
     ///
     /// Returns `None` if `index` is out of bounds or the slot has
     /// already been taken. O(1) regardless of position.
-    #[cfg_attr(not(feature = "std"), expect(dead_code))]
     #[inline]
     pub(crate) fn take(&mut self, index: ItemIndex) -> Option<T> {
         let slot = self.items.get_mut(index.as_u32() as usize)?;
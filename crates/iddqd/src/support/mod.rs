@@ -2,19 +2,31 @@ pub(crate) mod alloc;
 pub(crate) mod borrow;
 #[cfg(feature = "std")]
 pub(crate) mod btree_table;
+pub(crate) mod capacity_breakdown;
 #[cfg(feature = "daft")]
 pub(crate) mod daft_utils;
+pub(crate) mod extend_mode;
+pub(crate) mod fixed_hasher;
 pub(crate) mod fmt_utils;
 pub(crate) mod hash_builder;
 pub(crate) mod hash_table;
 #[cfg(soteria)]
 pub(crate) mod hash_table_reference;
+pub(crate) mod immutable_key;
+#[cfg(any(test, not(feature = "std")))]
+pub(crate) mod index_btree;
 pub(crate) mod item_index;
 pub(crate) mod item_set;
 pub(crate) mod map_hash;
-#[cfg(feature = "schemars08")]
+#[cfg(feature = "schemars1")]
+pub(crate) mod schemars1_utils;
+#[cfg(any(feature = "schemars08", feature = "schemars1"))]
 pub(crate) mod schemars_utils;
 #[cfg(feature = "serde")]
 pub(crate) mod size_hint;
 
 pub(crate) use item_index::ItemIndex;
+// Without std, the ordered maps use a bundled B-Tree that takes comparators
+// directly. See the index_btree module for details.
+#[cfg(not(feature = "std"))]
+pub(crate) use index_btree as btree_table;
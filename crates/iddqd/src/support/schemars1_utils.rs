@@ -0,0 +1,63 @@
+//! Utilities for schemars 1.x support.
+//!
+//! These produce schemas of the same shape as the schemars 0.8 helpers in
+//! `schemars_utils`.
+
+use super::schemars_utils::{IDDQD_CRATE_NAME, IDDQD_CRATE_VERSION};
+use schemars1::{JsonSchema, Schema, SchemaGenerator, json_schema};
+
+/// Helper function to create the `x-rust-type` extension for a given path and
+/// type parameter.
+fn rust_type_extension<T>(
+    path: &'static str,
+    generator: &mut SchemaGenerator,
+) -> serde_json::Value
+where
+    T: JsonSchema,
+{
+    serde_json::json!({
+        "crate": IDDQD_CRATE_NAME,
+        "version": IDDQD_CRATE_VERSION,
+        "path": path,
+        "parameters": [generator.subschema_for::<T>()]
+    })
+}
+
+/// Creates a schema with common properties for iddqd map types.
+///
+/// All iddqd map types serialize as arrays of their values. See
+/// `schemars_utils::array_validation` for why `uniqueItems` is set.
+pub(crate) fn create_map_schema<T>(
+    title: &str,
+    rust_type_path: &'static str,
+    generator: &mut SchemaGenerator,
+) -> Schema
+where
+    T: JsonSchema,
+{
+    json_schema!({
+        "title": title,
+        "type": "array",
+        "items": generator.subschema_for::<T>(),
+        "uniqueItems": true,
+        "x-rust-type": rust_type_extension::<T>(rust_type_path, generator),
+    })
+}
+
+/// Creates a schema for iddqd map types serialized as JSON objects.
+/// This is used by the AsMap wrapper types.
+pub(crate) fn create_object_schema<V>(
+    title: &str,
+    rust_type_path: &'static str,
+    generator: &mut SchemaGenerator,
+) -> Schema
+where
+    V: JsonSchema,
+{
+    json_schema!({
+        "title": title,
+        "type": "object",
+        "additionalProperties": generator.subschema_for::<V>(),
+        "x-rust-type": rust_type_extension::<V>(rust_type_path, generator),
+    })
+}
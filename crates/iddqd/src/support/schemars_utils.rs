@@ -1,9 +1,14 @@
 //! Utilities for schemars support.
+//!
+//! The constants here are shared with the schemars 1.x support in
+//! `schemars1_utils`. Everything else targets schemars 0.8.
 
+#[cfg(feature = "schemars08")]
 use alloc::{
     boxed::Box,
     string::{String, ToString},
 };
+#[cfg(feature = "schemars08")]
 use schemars::schema::{
     ArrayValidation, InstanceType, Metadata, ObjectValidation, Schema,
     SchemaObject, SingleOrVec,
@@ -18,6 +23,7 @@ pub(crate) static IDDQD_CRATE_NAME: &str = "iddqd";
 /// across breaking changes.
 pub(crate) static IDDQD_CRATE_VERSION: &str = "*";
 
+#[cfg(feature = "schemars08")]
 /// Helper function to create array validation for map types.
 /// All iddqd map types serialize as arrays of their values.
 pub(crate) fn array_validation<T>(
@@ -53,6 +59,7 @@ where
     })
 }
 
+#[cfg(feature = "schemars08")]
 /// Helper function to create the `extension` table for a given path and
 /// type parameter.
 pub(crate) fn make_extension_table<T>(
@@ -75,6 +82,7 @@ where
     .collect()
 }
 
+#[cfg(feature = "schemars08")]
 /// Creates a schema object with common properties for iddqd map types.
 pub(crate) fn create_map_schema<T>(
     title: &str,
@@ -96,6 +104,7 @@ where
     })
 }
 
+#[cfg(feature = "schemars08")]
 /// Helper function to create object validation for map types serialized as objects.
 pub(crate) fn object_validation<V>(
     generator: &mut schemars::r#gen::SchemaGenerator,
@@ -109,6 +118,7 @@ where
     })
 }
 
+#[cfg(feature = "schemars08")]
 /// Creates a schema object for iddqd map types serialized as JSON objects.
 /// This is used by the AsMap wrapper types.
 pub(crate) fn create_object_schema<V>(
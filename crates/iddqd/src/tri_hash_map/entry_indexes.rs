@@ -0,0 +1,47 @@
+use crate::support::ItemIndex;
+
+#[derive(Clone, Copy, Debug)]
+pub(super) enum EntryIndexes {
+    Unique(ItemIndex),
+    NonUnique {
+        // Invariant: at least one index is Some, and the indexes don't all
+        // point to the same item. Unlike with BiHashMap, two of the three
+        // indexes may be equal to each other.
+        index1: Option<ItemIndex>,
+        index2: Option<ItemIndex>,
+        index3: Option<ItemIndex>,
+    },
+}
+
+impl EntryIndexes {
+    #[inline]
+    pub(super) fn is_unique(&self) -> bool {
+        matches!(self, EntryIndexes::Unique(_))
+    }
+
+    /// Returns the indexes in a form suitable for `ItemSet::get_disjoint_mut`.
+    ///
+    /// Missing keys are represented by `ItemIndex::SENTINEL`, which is never a
+    /// valid index. `get_disjoint_mut` returns `None` for those, as well as for
+    /// any index that repeats an earlier one, so each item is borrowed at most
+    /// once.
+    #[inline]
+    pub(super) fn disjoint_keys(&self) -> [&ItemIndex; 3] {
+        match self {
+            EntryIndexes::Unique(index) => {
+                [index, &ItemIndex::SENTINEL, &ItemIndex::SENTINEL]
+            }
+            EntryIndexes::NonUnique { index1, index2, index3 } => {
+                debug_assert!(
+                    index1.is_some() || index2.is_some() || index3.is_some(),
+                    "at least one index must be Some"
+                );
+                [
+                    index1.as_ref().unwrap_or(&ItemIndex::SENTINEL),
+                    index2.as_ref().unwrap_or(&ItemIndex::SENTINEL),
+                    index3.as_ref().unwrap_or(&ItemIndex::SENTINEL),
+                ]
+            }
+        }
+    }
+}
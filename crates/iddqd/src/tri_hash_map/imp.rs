@@ -1,14 +1,22 @@
-use super::{IntoIter, Iter, IterMut, RefMut, tables::TriHashMapTables};
+use super::{
+    IntoIter, Iter, IterMut, Key1s, Key2s, Key3s, RefMut,
+    entry::{
+        Entry, OccupiedEntry, OccupiedEntryMut, OccupiedEntryRef, VacantEntry,
+    },
+    entry_indexes::EntryIndexes,
+    tables::TriHashMapTables,
+};
 use crate::{
-    DefaultHashBuilder, TriHashItem,
-    errors::{DuplicateItem, TryReserveError},
+    CapacityBreakdown, DefaultHashBuilder, ExtendMode, ImmutableKey,
+    TriHashItem,
+    errors::{DuplicateItem, MapIntegrityError, TryReserveError},
     internal::ValidationError,
     support::{
         ItemIndex,
         alloc::{Allocator, Global, global_alloc},
         borrow::DormantMutRef,
         fmt_utils::StrDisplayAsDebug,
-        hash_table,
+        hash_table::detect_dup_or_insert,
         item_set::ItemSet,
         map_hash::MapHash,
     },
@@ -16,7 +24,7 @@ use crate::{
 use alloc::{collections::BTreeSet, vec::Vec};
 use core::{
     fmt,
-    hash::{BuildHasher, Hash},
+    hash::{BuildHasher, Hash, Hasher},
 };
 use equivalent::Equivalent;
 
@@ -52,6 +60,9 @@ impl PreparedDuplicate {
 #[derive(Debug)]
 #[must_use]
 struct PreparedInsertOverwrite {
+    index1: Option<ItemIndex>,
+    index2: Option<ItemIndex>,
+    index3: Option<ItemIndex>,
     duplicates: Vec<PreparedDuplicate>,
     hashes: [MapHash; 3],
 }
@@ -136,7 +147,7 @@ pub struct TriHashMap<T, S = DefaultHashBuilder, A: Allocator = Global> {
     pub(super) items: ItemSet<T, A>,
     // Invariant: the values (ItemIndex) in these tables are valid indexes into
     // `items`, and are a 1:1 mapping.
-    tables: TriHashMapTables<S, A>,
+    pub(super) tables: TriHashMapTables<S, A>,
 }
 
 impl<T: TriHashItem, S: Default, A: Allocator + Default> Default
@@ -243,6 +254,60 @@ impl<T: TriHashItem> TriHashMap<T> {
             ),
         }
     }
+
+    /// Creates a new `TriHashMap` with the given capacity, returning an error if
+    /// allocation fails.
+    ///
+    /// This is a fallible version of [`TriHashMap::with_capacity`].
+    ///
+    /// # Errors
+    ///
+    /// If the capacity overflows, or the allocator reports a failure, then an
+    /// error is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "default-hasher")] {
+    /// use iddqd::{TriHashItem, TriHashMap, tri_upcast};
+    ///
+    /// #[derive(Debug, PartialEq, Eq)]
+    /// struct Person {
+    ///     id: u32,
+    ///     email: String,
+    ///     phone: String,
+    ///     name: String,
+    /// }
+    ///
+    /// impl TriHashItem for Person {
+    ///     type K1<'a> = u32;
+    ///     type K2<'a> = &'a str;
+    ///     type K3<'a> = &'a str;
+    ///
+    ///     fn key1(&self) -> Self::K1<'_> {
+    ///         self.id
+    ///     }
+    ///     fn key2(&self) -> Self::K2<'_> {
+    ///         &self.email
+    ///     }
+    ///     fn key3(&self) -> Self::K3<'_> {
+    ///         &self.phone
+    ///     }
+    ///     tri_upcast!();
+    /// }
+    ///
+    /// let map: TriHashMap<Person> = TriHashMap::try_with_capacity(10)
+    ///     .expect("allocation should succeed");
+    /// assert!(map.capacity() >= 10);
+    /// assert!(map.is_empty());
+    /// # }
+    /// ```
+    pub fn try_with_capacity(capacity: usize) -> Result<Self, TryReserveError> {
+        Self::try_with_capacity_and_hasher(
+            capacity,
+            DefaultHashBuilder::default(),
+        )
+    }
 }
 
 impl<T: TriHashItem, S: BuildHasher> TriHashMap<T, S> {
@@ -338,6 +403,64 @@ impl<T: TriHashItem, S: BuildHasher> TriHashMap<T, S> {
             ),
         }
     }
+
+    /// Creates a new `TriHashMap` with the given capacity and hasher, returning
+    /// an error if allocation fails.
+    ///
+    /// This is a fallible version of [`TriHashMap::with_capacity_and_hasher`].
+    ///
+    /// # Errors
+    ///
+    /// If the capacity overflows, or the allocator reports a failure, then an
+    /// error is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use iddqd::{TriHashItem, TriHashMap, tri_upcast};
+    /// use std::collections::hash_map::RandomState;
+    ///
+    /// #[derive(Debug, PartialEq, Eq)]
+    /// struct Person {
+    ///     id: u32,
+    ///     email: String,
+    ///     phone: String,
+    ///     name: String,
+    /// }
+    ///
+    /// impl TriHashItem for Person {
+    ///     type K1<'a> = u32;
+    ///     type K2<'a> = &'a str;
+    ///     type K3<'a> = &'a str;
+    ///
+    ///     fn key1(&self) -> Self::K1<'_> {
+    ///         self.id
+    ///     }
+    ///     fn key2(&self) -> Self::K2<'_> {
+    ///         &self.email
+    ///     }
+    ///     fn key3(&self) -> Self::K3<'_> {
+    ///         &self.phone
+    ///     }
+    ///     tri_upcast!();
+    /// }
+    ///
+    /// let hasher = RandomState::new();
+    /// let map: TriHashMap<Person, _> =
+    ///     TriHashMap::try_with_capacity_and_hasher(10, hasher)
+    ///         .expect("allocation should succeed");
+    /// assert!(map.capacity() >= 10);
+    /// assert!(map.is_empty());
+    /// ```
+    pub fn try_with_capacity_and_hasher(
+        capacity: usize,
+        hasher: S,
+    ) -> Result<Self, TryReserveError> {
+        let mut map = Self::with_hasher(hasher);
+        map.items.try_reserve(capacity)?;
+        map.tables.try_reserve(capacity)?;
+        Ok(map)
+    }
 }
 
 #[cfg(feature = "default-hasher")]
@@ -457,6 +580,69 @@ impl<T: TriHashItem, A: Clone + Allocator>
             ),
         }
     }
+
+    /// Creates an empty `TriHashMap` with the specified capacity using the
+    /// given allocator, returning an error if allocation fails.
+    ///
+    /// This is a fallible version of [`TriHashMap::with_capacity_in`].
+    ///
+    /// # Errors
+    ///
+    /// If the capacity overflows, or the allocator reports a failure, then an
+    /// error is returned.
+    ///
+    /// # Examples
+    ///
+    /// Using the [`bumpalo`](https://docs.rs/bumpalo) allocator:
+    ///
+    /// ```
+    /// # #[cfg(all(feature = "default-hasher", feature = "allocator-api2"))] {
+    /// use iddqd::{TriHashItem, TriHashMap, tri_upcast};
+    /// # use iddqd_test_utils::bumpalo;
+    ///
+    /// #[derive(Debug, PartialEq, Eq)]
+    /// struct Person {
+    ///     id: u32,
+    ///     email: String,
+    ///     phone: String,
+    ///     name: String,
+    /// }
+    ///
+    /// impl TriHashItem for Person {
+    ///     type K1<'a> = u32;
+    ///     type K2<'a> = &'a str;
+    ///     type K3<'a> = &'a str;
+    ///
+    ///     fn key1(&self) -> Self::K1<'_> {
+    ///         self.id
+    ///     }
+    ///     fn key2(&self) -> Self::K2<'_> {
+    ///         &self.email
+    ///     }
+    ///     fn key3(&self) -> Self::K3<'_> {
+    ///         &self.phone
+    ///     }
+    ///     tri_upcast!();
+    /// }
+    ///
+    /// let bump = bumpalo::Bump::new();
+    /// let map: TriHashMap<Person, _, &bumpalo::Bump> =
+    ///     TriHashMap::try_with_capacity_in(10, &bump)
+    ///         .expect("allocation should succeed");
+    /// assert!(map.capacity() >= 10);
+    /// assert!(map.is_empty());
+    /// # }
+    /// ```
+    pub fn try_with_capacity_in(
+        capacity: usize,
+        alloc: A,
+    ) -> Result<Self, TryReserveError> {
+        Self::try_with_capacity_and_hasher_in(
+            capacity,
+            DefaultHashBuilder::default(),
+            alloc,
+        )
+    }
 }
 
 impl<T: TriHashItem, S: Clone + BuildHasher, A: Clone + Allocator>
@@ -583,6 +769,72 @@ impl<T: TriHashItem, S: Clone + BuildHasher, A: Clone + Allocator>
             ),
         }
     }
+
+    /// Creates a new, empty `TriHashMap` with the given capacity, hasher, and
+    /// allocator, returning an error if allocation fails.
+    ///
+    /// This is a fallible version of
+    /// [`TriHashMap::with_capacity_and_hasher_in`].
+    ///
+    /// # Errors
+    ///
+    /// If the capacity overflows, or the allocator reports a failure, then an
+    /// error is returned.
+    ///
+    /// # Examples
+    ///
+    /// Using the [`bumpalo`](https://docs.rs/bumpalo) allocator:
+    ///
+    /// ```
+    /// # #[cfg(feature = "allocator-api2")] {
+    /// use iddqd::{TriHashItem, TriHashMap, tri_upcast};
+    /// use std::collections::hash_map::RandomState;
+    /// # use iddqd_test_utils::bumpalo;
+    ///
+    /// #[derive(Debug, PartialEq, Eq)]
+    /// struct Person {
+    ///     id: u32,
+    ///     email: String,
+    ///     phone: String,
+    ///     name: String,
+    /// }
+    ///
+    /// impl TriHashItem for Person {
+    ///     type K1<'a> = u32;
+    ///     type K2<'a> = &'a str;
+    ///     type K3<'a> = &'a str;
+    ///
+    ///     fn key1(&self) -> Self::K1<'_> {
+    ///         self.id
+    ///     }
+    ///     fn key2(&self) -> Self::K2<'_> {
+    ///         &self.email
+    ///     }
+    ///     fn key3(&self) -> Self::K3<'_> {
+    ///         &self.phone
+    ///     }
+    ///     tri_upcast!();
+    /// }
+    ///
+    /// let bump = bumpalo::Bump::new();
+    /// let hasher = RandomState::new();
+    /// let map: TriHashMap<Person, _, &bumpalo::Bump> =
+    ///     TriHashMap::try_with_capacity_and_hasher_in(10, hasher, &bump)
+    ///         .expect("allocation should succeed");
+    /// assert!(map.capacity() >= 10);
+    /// assert!(map.is_empty());
+    /// # }
+    /// ```
+    pub fn try_with_capacity_and_hasher_in(
+        capacity: usize,
+        hasher: S,
+        alloc: A,
+    ) -> Result<Self, TryReserveError> {
+        let mut map = Self::with_hasher_in(hasher, alloc);
+        map.items.try_reserve(capacity)?;
+        map.tables.try_reserve(capacity)?;
+        Ok(map)
+    }
 }
 
 impl<T: TriHashItem, S: Default + Clone + BuildHasher, A: Allocator + Default>
@@ -659,7 +911,11 @@ impl<T: TriHashItem, S: Default + Clone + BuildHasher, A: Allocator + Default>
     ///         email: "dup@example.com".to_string(),
     ///     },
     /// ];
-    /// assert!(TriHashMap::<Item>::from_iter_unique(duplicate_items).is_err());
+    /// let err =
+    ///     TriHashMap::<Item>::from_iter_unique(duplicate_items).unwrap_err();
+    /// assert_eq!(err.new_item().id, 2);
+    /// assert_eq!(err.duplicates().len(), 1);
+    /// assert_eq!(err.duplicates()[0].id, 1);
     /// # }
     /// ```
     pub fn from_iter_unique<I: IntoIterator<Item = T>>(
@@ -791,6 +1047,107 @@ impl<T: TriHashItem, S: Clone + BuildHasher, A: Allocator> TriHashMap<T, S, A> {
         self.items.capacity()
     }
 
+    /// Returns a breakdown of the map's internal sizing, for debugging.
+    ///
+    /// This reports the length and capacity of the item storage, and the
+    /// capacity of each key table. It doesn't visit any items, so it's much
+    /// cheaper than [`Self::validate`].
+    pub fn debug_capacity_breakdown(&self) -> CapacityBreakdown {
+        CapacityBreakdown {
+            items_len: self.items.len(),
+            items_capacity: self.items.capacity(),
+            table_capacities: vec![
+                self.tables.k1_to_item.capacity(),
+                self.tables.k2_to_item.capacity(),
+                self.tables.k3_to_item.capacity(),
+            ],
+        }
+    }
+
+    /// Returns the number of items in the map, along with the number of items
+    /// the map can hold before one of its key tables needs to reallocate.
+    ///
+    /// The capacity is the smallest of the key tables' capacities. To see each
+    /// key table individually, use [`Self::occupancy1`], [`Self::occupancy2`],
+    /// and [`Self::occupancy3`].
+    ///
+    /// This is useful for tuning the capacity passed to `with_capacity` when
+    /// building other maps of a similar size.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "default-hasher")] {
+    /// use iddqd::{TriHashItem, TriHashMap, tri_upcast};
+    ///
+    /// #[derive(Debug, PartialEq, Eq)]
+    /// struct Person {
+    ///     id: u32,
+    ///     email: String,
+    ///     phone: String,
+    /// }
+    ///
+    /// impl TriHashItem for Person {
+    ///     type K1<'a> = u32;
+    ///     type K2<'a> = &'a str;
+    ///     type K3<'a> = &'a str;
+    ///
+    ///     fn key1(&self) -> Self::K1<'_> {
+    ///         self.id
+    ///     }
+    ///     fn key2(&self) -> Self::K2<'_> {
+    ///         &self.email
+    ///     }
+    ///     fn key3(&self) -> Self::K3<'_> {
+    ///         &self.phone
+    ///     }
+    ///     tri_upcast!();
+    /// }
+    ///
+    /// let mut map: TriHashMap<Person> = TriHashMap::with_capacity(10);
+    /// map.insert_unique(Person {
+    ///     id: 1,
+    ///     email: "alice@example.com".to_string(),
+    ///     phone: "555-1234".to_string(),
+    /// })
+    /// .unwrap();
+    ///
+    /// let (len, capacity) = map.occupancy();
+    /// assert_eq!(len, 1);
+    /// assert!(capacity >= 10);
+    /// assert!(map.occupancy1().1 >= capacity);
+    /// assert!(map.occupancy2().1 >= capacity);
+    /// assert!(map.occupancy3().1 >= capacity);
+    /// # }
+    /// ```
+    pub fn occupancy(&self) -> (usize, usize) {
+        let capacity = self
+            .tables
+            .k1_to_item
+            .capacity()
+            .min(self.tables.k2_to_item.capacity())
+            .min(self.tables.k3_to_item.capacity());
+        (self.items.len(), capacity)
+    }
+
+    /// Returns the number of items in the map, along with the number of items
+    /// the first key table can hold without reallocating.
+    pub fn occupancy1(&self) -> (usize, usize) {
+        (self.items.len(), self.tables.k1_to_item.capacity())
+    }
+
+    /// Returns the number of items in the map, along with the number of items
+    /// the second key table can hold without reallocating.
+    pub fn occupancy2(&self) -> (usize, usize) {
+        (self.items.len(), self.tables.k2_to_item.capacity())
+    }
+
+    /// Returns the number of items in the map, along with the number of items
+    /// the third key table can hold without reallocating.
+    pub fn occupancy3(&self) -> (usize, usize) {
+        (self.items.len(), self.tables.k3_to_item.capacity())
+    }
+
     /// Returns true if the map is empty.
     ///
     /// # Examples
@@ -1069,21 +1426,9 @@ impl<T: TriHashItem, S: Clone + BuildHasher, A: Allocator> TriHashMap<T, S, A> {
     pub fn try_reserve(
         &mut self,
         additional: usize,
-    ) -> Result<(), crate::errors::TryReserveError> {
+    ) -> Result<(), TryReserveError> {
         self.items.try_reserve(additional)?;
-        self.tables
-            .k1_to_item
-            .try_reserve(additional)
-            .map_err(crate::errors::TryReserveError::from_hashbrown)?;
-        self.tables
-            .k2_to_item
-            .try_reserve(additional)
-            .map_err(crate::errors::TryReserveError::from_hashbrown)?;
-        self.tables
-            .k3_to_item
-            .try_reserve(additional)
-            .map_err(crate::errors::TryReserveError::from_hashbrown)?;
-        Ok(())
+        self.tables.try_reserve(additional)
     }
 
     /// Shrinks the capacity of the map as much as possible. It will drop
@@ -1357,78 +1702,71 @@ impl<T: TriHashItem, S: Clone + BuildHasher, A: Allocator> TriHashMap<T, S, A> {
         IterMut::new(&self.tables, &mut self.items)
     }
 
-    /// Checks general invariants of the map.
+    /// Iterates over the first keys in the map.
     ///
-    /// The code below always upholds these invariants, but it's useful to have
-    /// an explicit check for tests.
-    #[doc(hidden)]
-    pub fn validate(
-        &self,
-        compactness: crate::internal::ValidateCompact,
-    ) -> Result<(), ValidationError>
-    where
-        T: fmt::Debug,
-    {
-        self.validate_structural(compactness)?;
-
-        // Check that the indexes are all correct.
-        //
-        // Unlike the structural checks, this re-looks up each key through the
-        // user `Hash`, so it only holds when that `Hash` is lawful.
-        for (ix, item) in self.items.iter() {
-            let key1 = item.key1();
-            let key2 = item.key2();
-            let key3 = item.key3();
-
-            let Some(ix1) = self.find1_index(&key1) else {
-                return Err(ValidationError::general(format!(
-                    "item at index {ix} has no key1 index"
-                )));
-            };
-            let Some(ix2) = self.find2_index(&key2) else {
-                return Err(ValidationError::general(format!(
-                    "item at index {ix} has no key2 index"
-                )));
-            };
-            let Some(ix3) = self.find3_index(&key3) else {
-                return Err(ValidationError::general(format!(
-                    "item at index {ix} has no key3 index"
-                )));
-            };
-
-            if ix1 != ix || ix2 != ix || ix3 != ix {
-                return Err(ValidationError::general(format!(
-                    "item at index {ix} has inconsistent indexes: {ix1}/{ix2}/{ix3}"
-                )));
-            }
-        }
-
-        Ok(())
-    }
-
-    /// Checks the structural invariants of the map:
+    /// Similar to [`HashMap`], the iteration order is arbitrary and not
+    /// guaranteed to be stable.
     ///
-    /// * The item set is well-formed.
-    /// * Each per-key hash table holds exactly one entry per live item, with no
-    ///   duplicate `ItemIndex`es.
+    /// # Examples
     ///
-    /// Unlike [`validate`](Self::validate), this does not re-look-up keys
-    /// through the user `Hash`, so it holds regardless of whether that `Hash`
-    /// is lawful. A buggy hasher can desync the logical key→item mapping, but
-    /// it must never break these structural invariants! Doing so would be
-    /// unsoundness, e.g. duplicate indexes enabling mutable aliasing.
-    #[doc(hidden)]
-    pub fn validate_structural(
-        &self,
-        compactness: crate::internal::ValidateCompact,
-    ) -> Result<(), ValidationError> {
-        self.items.validate(compactness)?;
-        self.tables.validate(self.len(), compactness)?;
-        Ok(())
+    /// ```
+    /// # #[cfg(feature = "default-hasher")] {
+    /// use iddqd::{TriHashItem, TriHashMap, tri_upcast};
+    ///
+    /// #[derive(Debug, PartialEq, Eq)]
+    /// struct Person {
+    ///     id: u32,
+    ///     email: String,
+    ///     phone: String,
+    /// }
+    ///
+    /// impl TriHashItem for Person {
+    ///     type K1<'a> = u32;
+    ///     type K2<'a> = &'a str;
+    ///     type K3<'a> = &'a str;
+    ///
+    ///     fn key1(&self) -> Self::K1<'_> {
+    ///         self.id
+    ///     }
+    ///     fn key2(&self) -> Self::K2<'_> {
+    ///         &self.email
+    ///     }
+    ///     fn key3(&self) -> Self::K3<'_> {
+    ///         &self.phone
+    ///     }
+    ///     tri_upcast!();
+    /// }
+    ///
+    /// let mut map = TriHashMap::new();
+    /// map.insert_unique(Person {
+    ///     id: 1,
+    ///     email: "alice@example.com".to_string(),
+    ///     phone: "555-1234".to_string(),
+    /// })
+    /// .unwrap();
+    /// map.insert_unique(Person {
+    ///     id: 2,
+    ///     email: "bob@example.com".to_string(),
+    ///     phone: "555-5678".to_string(),
+    /// })
+    /// .unwrap();
+    ///
+    /// let mut keys: Vec<u32> = map.key1s().collect();
+    /// keys.sort();
+    /// assert_eq!(keys, [1, 2]);
+    /// # }
+    /// ```
+    ///
+    /// [`HashMap`]: std::collections::HashMap
+    #[inline]
+    pub fn key1s(&self) -> Key1s<'_, T> {
+        Key1s::new(self.iter())
     }
 
-    /// Inserts a value into the map, removing any conflicting items and
-    /// returning a list of those items.
+    /// Iterates over the second keys in the map.
+    ///
+    /// Similar to [`HashMap`], the iteration order is arbitrary and not
+    /// guaranteed to be stable.
     ///
     /// # Examples
     ///
@@ -1441,7 +1779,6 @@ impl<T: TriHashItem, S: Clone + BuildHasher, A: Allocator> TriHashMap<T, S, A> {
     ///     id: u32,
     ///     email: String,
     ///     phone: String,
-    ///     name: String,
     /// }
     ///
     /// impl TriHashItem for Person {
@@ -1462,45 +1799,35 @@ impl<T: TriHashItem, S: Clone + BuildHasher, A: Allocator> TriHashMap<T, S, A> {
     /// }
     ///
     /// let mut map = TriHashMap::new();
-    ///
-    /// // First insertion - no conflicts
-    /// let overwritten = map.insert_overwrite(Person {
+    /// map.insert_unique(Person {
     ///     id: 1,
     ///     email: "alice@example.com".to_string(),
     ///     phone: "555-1234".to_string(),
-    ///     name: "Alice".to_string(),
-    /// });
-    /// assert!(overwritten.is_empty());
+    /// })
+    /// .unwrap();
+    /// map.insert_unique(Person {
+    ///     id: 2,
+    ///     email: "bob@example.com".to_string(),
+    ///     phone: "555-5678".to_string(),
+    /// })
+    /// .unwrap();
     ///
-    /// // Overwrite with same id - returns the old item
-    /// let overwritten = map.insert_overwrite(Person {
-    ///     id: 1,
-    ///     email: "alice.new@example.com".to_string(),
-    ///     phone: "555-9999".to_string(),
-    ///     name: "Alice New".to_string(),
-    /// });
-    /// assert_eq!(overwritten.len(), 1);
-    /// assert_eq!(overwritten[0].name, "Alice");
+    /// let mut keys: Vec<&str> = map.key2s().collect();
+    /// keys.sort();
+    /// assert_eq!(keys, ["alice@example.com", "bob@example.com"]);
     /// # }
     /// ```
-    #[doc(alias = "insert")]
-    pub fn insert_overwrite(&mut self, value: T) -> Vec<T> {
-        let prepared = self.prepare_insert_overwrite(&value);
-
-        let mut duplicates = Vec::with_capacity(prepared.duplicate_count());
-
-        self.try_reserve_insert_overwrite_commit(
-            prepared.needs_new_item_slot(),
-        )
-        .expect("reserved space successfully");
-
-        self.commit_insert_overwrite(value, prepared, &mut duplicates);
-
-        duplicates
+    ///
+    /// [`HashMap`]: std::collections::HashMap
+    #[inline]
+    pub fn key2s(&self) -> Key2s<'_, T> {
+        Key2s::new(self.iter())
     }
 
-    /// Inserts a value into the set, returning an error if any duplicates were
-    /// added.
+    /// Iterates over the third keys in the map.
+    ///
+    /// Similar to [`HashMap`], the iteration order is arbitrary and not
+    /// guaranteed to be stable.
     ///
     /// # Examples
     ///
@@ -1513,7 +1840,6 @@ impl<T: TriHashItem, S: Clone + BuildHasher, A: Allocator> TriHashMap<T, S, A> {
     ///     id: u32,
     ///     email: String,
     ///     phone: String,
-    ///     name: String,
     /// }
     ///
     /// impl TriHashItem for Person {
@@ -1534,126 +1860,1063 @@ impl<T: TriHashItem, S: Clone + BuildHasher, A: Allocator> TriHashMap<T, S, A> {
     /// }
     ///
     /// let mut map = TriHashMap::new();
+    /// map.insert_unique(Person {
+    ///     id: 1,
+    ///     email: "alice@example.com".to_string(),
+    ///     phone: "555-1234".to_string(),
+    /// })
+    /// .unwrap();
+    /// map.insert_unique(Person {
+    ///     id: 2,
+    ///     email: "bob@example.com".to_string(),
+    ///     phone: "555-5678".to_string(),
+    /// })
+    /// .unwrap();
     ///
-    /// // Successful insertion
-    /// assert!(
-    ///     map.insert_unique(Person {
-    ///         id: 1,
-    ///         email: "alice@example.com".to_string(),
-    ///         phone: "555-1234".to_string(),
-    ///         name: "Alice".to_string(),
-    ///     })
-    ///     .is_ok()
-    /// );
-    /// assert!(
-    ///     map.insert_unique(Person {
-    ///         id: 2,
-    ///         email: "bob@example.com".to_string(),
-    ///         phone: "555-5678".to_string(),
-    ///         name: "Bob".to_string(),
-    ///     })
-    ///     .is_ok()
-    /// );
-    ///
-    /// // Duplicate key1
-    /// assert!(
-    ///     map.insert_unique(Person {
-    ///         id: 1,
-    ///         email: "charlie@example.com".to_string(),
-    ///         phone: "555-9999".to_string(),
-    ///         name: "Charlie".to_string(),
-    ///     })
-    ///     .is_err()
-    /// );
-    ///
-    /// // Duplicate key2
-    /// assert!(
-    ///     map.insert_unique(Person {
-    ///         id: 3,
-    ///         email: "alice@example.com".to_string(),
-    ///         phone: "555-7777".to_string(),
-    ///         name: "Alice2".to_string(),
-    ///     })
-    ///     .is_err()
-    /// );
-    ///
-    /// // Duplicate key3
-    /// assert!(
-    ///     map.insert_unique(Person {
-    ///         id: 4,
-    ///         email: "dave@example.com".to_string(),
-    ///         phone: "555-1234".to_string(),
-    ///         name: "Dave".to_string(),
-    ///     })
-    ///     .is_err()
-    /// );
+    /// let mut keys: Vec<&str> = map.key3s().collect();
+    /// keys.sort();
+    /// assert_eq!(keys, ["555-1234", "555-5678"]);
     /// # }
     /// ```
-    pub fn insert_unique(
-        &mut self,
-        value: T,
-    ) -> Result<(), DuplicateItem<T, &T>> {
-        match self.insert_unique_or_dup_indexes(value) {
-            Ok(_) => Ok(()),
-            Err((value, duplicates)) => Err(DuplicateItem::__internal_new(
-                value,
-                duplicates.iter().map(|ix| &self.items[*ix]).collect(),
-            )),
-        }
+    ///
+    /// [`HashMap`]: std::collections::HashMap
+    #[inline]
+    pub fn key3s(&self) -> Key3s<'_, T> {
+        Key3s::new(self.iter())
     }
 
-    fn insert_unique_or_dup_indexes(
-        &mut self,
-        value: T,
+    /// Iterates over the items in the map.
+    ///
+    /// This is the same as [`Self::iter`], and is provided for symmetry with
+    /// [`HashMap::values`].
+    ///
+    /// [`HashMap::values`]: std::collections::HashMap::values
+    #[inline]
+    pub fn values(&self) -> Iter<'_, T> {
+        self.iter()
+    }
+
+    /// Iterates over the items in the map, allowing for mutation.
+    ///
+    /// This is the same as [`Self::iter_mut`], and is provided for symmetry with
+    /// [`HashMap::values_mut`].
+    ///
+    /// [`HashMap::values_mut`]: std::collections::HashMap::values_mut
+    #[inline]
+    pub fn values_mut(&mut self) -> IterMut<'_, T, S, A> {
+        self.iter_mut()
+    }
+
+    /// Consumes the map, returning its items as a [`Vec`].
+    ///
+    /// Similar to [`HashMap`], the order of the items is arbitrary. This is
+    /// equivalent to `map.into_iter().collect::<Vec<_>>()`, and is provided to
+    /// make it explicit at the call site that the order doesn't matter.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "default-hasher")] {
+    /// use iddqd::{TriHashItem, TriHashMap, tri_upcast};
+    ///
+    /// #[derive(Debug, PartialEq, Eq)]
+    /// struct Item {
+    ///     id: u32,
+    ///     name: &'static str,
+    ///     code: char,
+    /// }
+    ///
+    /// impl TriHashItem for Item {
+    ///     type K1<'a> = u32;
+    ///     type K2<'a> = &'a str;
+    ///     type K3<'a> = char;
+    ///
+    ///     fn key1(&self) -> Self::K1<'_> {
+    ///         self.id
+    ///     }
+    ///     fn key2(&self) -> Self::K2<'_> {
+    ///         self.name
+    ///     }
+    ///     fn key3(&self) -> Self::K3<'_> {
+    ///         self.code
+    ///     }
+    ///     tri_upcast!();
+    /// }
+    ///
+    /// let mut map = TriHashMap::new();
+    /// map.insert_unique(Item { id: 2, name: "bar", code: 'b' }).unwrap();
+    /// map.insert_unique(Item { id: 1, name: "foo", code: 'f' }).unwrap();
+    ///
+    /// let mut items = map.into_vec();
+    /// items.sort_unstable_by_key(|item| item.id);
+    /// assert_eq!(
+    ///     items,
+    ///     vec![
+    ///         Item { id: 1, name: "foo", code: 'f' },
+    ///         Item { id: 2, name: "bar", code: 'b' },
+    ///     ],
+    /// );
+    /// # }
+    /// ```
+    ///
+    /// [`HashMap`]: std::collections::HashMap
+    pub fn into_vec(self) -> Vec<T> {
+        self.into_iter().collect()
+    }
+
+    /// Checks that the map's internal invariants hold.
+    ///
+    /// See [`MapIntegrityError`] for what this checks, and when it can fail.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "default-hasher")] {
+    /// use iddqd::{TriHashItem, TriHashMap, tri_upcast};
+    ///
+    /// #[derive(Debug, PartialEq, Eq)]
+    /// struct Person {
+    ///     id: u32,
+    ///     email: String,
+    ///     phone: String,
+    ///     name: String,
+    /// }
+    ///
+    /// impl TriHashItem for Person {
+    ///     type K1<'a> = u32;
+    ///     type K2<'a> = &'a str;
+    ///     type K3<'a> = &'a str;
+    ///
+    ///     fn key1(&self) -> Self::K1<'_> {
+    ///         self.id
+    ///     }
+    ///     fn key2(&self) -> Self::K2<'_> {
+    ///         &self.email
+    ///     }
+    ///     fn key3(&self) -> Self::K3<'_> {
+    ///         &self.phone
+    ///     }
+    ///     tri_upcast!();
+    /// }
+    ///
+    /// let mut map = TriHashMap::new();
+    /// map.insert_unique(Person {
+    ///     id: 1,
+    ///     email: "alice@example.com".to_string(),
+    ///     phone: "555-1234".to_string(),
+    ///     name: "Alice".to_string(),
+    /// })
+    /// .unwrap();
+    /// map.remove1(&1);
+    ///
+    /// assert_eq!(map.check_invariants(), Ok(()));
+    /// # }
+    /// ```
+    pub fn check_invariants(&self) -> Result<(), MapIntegrityError> {
+        MapIntegrityError::check_storage(&self.items)?;
+        MapIntegrityError::check_table(
+            "key1",
+            &self.items,
+            self.tables.k1_to_item.iter_hashed().map(|(_, ix)| ix),
+        )?;
+        MapIntegrityError::check_table(
+            "key2",
+            &self.items,
+            self.tables.k2_to_item.iter_hashed().map(|(_, ix)| ix),
+        )?;
+        MapIntegrityError::check_table(
+            "key3",
+            &self.items,
+            self.tables.k3_to_item.iter_hashed().map(|(_, ix)| ix),
+        )?;
+
+        for (ix, item) in self.items.iter() {
+            MapIntegrityError::check_lookup(
+                "key1",
+                ix,
+                self.find1_index(&item.key1()),
+            )?;
+            MapIntegrityError::check_lookup(
+                "key2",
+                ix,
+                self.find2_index(&item.key2()),
+            )?;
+            MapIntegrityError::check_lookup(
+                "key3",
+                ix,
+                self.find3_index(&item.key3()),
+            )?;
+        }
+
+        Ok(())
+    }
+
+    /// Checks general invariants of the map.
+    ///
+    /// The code below always upholds these invariants, but it's useful to have
+    /// an explicit check for tests.
+    #[doc(hidden)]
+    pub fn validate(
+        &self,
+        compactness: crate::internal::ValidateCompact,
+    ) -> Result<(), ValidationError>
+    where
+        T: fmt::Debug,
+    {
+        self.validate_structural(compactness)?;
+
+        // Check that the indexes are all correct.
+        //
+        // Unlike the structural checks, this re-looks up each key through the
+        // user `Hash`, so it only holds when that `Hash` is lawful.
+        for (ix, item) in self.items.iter() {
+            let key1 = item.key1();
+            let key2 = item.key2();
+            let key3 = item.key3();
+
+            let Some(ix1) = self.find1_index(&key1) else {
+                return Err(ValidationError::general(format!(
+                    "item at index {ix} has no key1 index"
+                )));
+            };
+            let Some(ix2) = self.find2_index(&key2) else {
+                return Err(ValidationError::general(format!(
+                    "item at index {ix} has no key2 index"
+                )));
+            };
+            let Some(ix3) = self.find3_index(&key3) else {
+                return Err(ValidationError::general(format!(
+                    "item at index {ix} has no key3 index"
+                )));
+            };
+
+            if ix1 != ix || ix2 != ix || ix3 != ix {
+                return Err(ValidationError::general(format!(
+                    "item at index {ix} has inconsistent indexes: {ix1}/{ix2}/{ix3}"
+                )));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Checks the structural invariants of the map:
+    ///
+    /// * The item set is well-formed.
+    /// * Each per-key hash table holds exactly one entry per live item, with no
+    ///   duplicate `ItemIndex`es.
+    ///
+    /// Unlike [`validate`](Self::validate), this does not re-look-up keys
+    /// through the user `Hash`, so it holds regardless of whether that `Hash`
+    /// is lawful. A buggy hasher can desync the logical key→item mapping, but
+    /// it must never break these structural invariants! Doing so would be
+    /// unsoundness, e.g. duplicate indexes enabling mutable aliasing.
+    #[doc(hidden)]
+    pub fn validate_structural(
+        &self,
+        compactness: crate::internal::ValidateCompact,
+    ) -> Result<(), ValidationError> {
+        self.items.validate(compactness)?;
+        self.tables.validate(self.len(), compactness)?;
+        Ok(())
+    }
+
+    /// Inserts a value into the map, removing any conflicting items and
+    /// returning a list of those items.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "default-hasher")] {
+    /// use iddqd::{TriHashItem, TriHashMap, tri_upcast};
+    ///
+    /// #[derive(Debug, PartialEq, Eq)]
+    /// struct Person {
+    ///     id: u32,
+    ///     email: String,
+    ///     phone: String,
+    ///     name: String,
+    /// }
+    ///
+    /// impl TriHashItem for Person {
+    ///     type K1<'a> = u32;
+    ///     type K2<'a> = &'a str;
+    ///     type K3<'a> = &'a str;
+    ///
+    ///     fn key1(&self) -> Self::K1<'_> {
+    ///         self.id
+    ///     }
+    ///     fn key2(&self) -> Self::K2<'_> {
+    ///         &self.email
+    ///     }
+    ///     fn key3(&self) -> Self::K3<'_> {
+    ///         &self.phone
+    ///     }
+    ///     tri_upcast!();
+    /// }
+    ///
+    /// let mut map = TriHashMap::new();
+    ///
+    /// // First insertion - no conflicts
+    /// let overwritten = map.insert_overwrite(Person {
+    ///     id: 1,
+    ///     email: "alice@example.com".to_string(),
+    ///     phone: "555-1234".to_string(),
+    ///     name: "Alice".to_string(),
+    /// });
+    /// assert!(overwritten.is_empty());
+    ///
+    /// // Overwrite with same id - returns the old item
+    /// let overwritten = map.insert_overwrite(Person {
+    ///     id: 1,
+    ///     email: "alice.new@example.com".to_string(),
+    ///     phone: "555-9999".to_string(),
+    ///     name: "Alice New".to_string(),
+    /// });
+    /// assert_eq!(overwritten.len(), 1);
+    /// assert_eq!(overwritten[0].name, "Alice");
+    /// # }
+    /// ```
+    #[doc(alias = "insert")]
+    pub fn insert_overwrite(&mut self, value: T) -> Vec<T> {
+        let prepared = self.prepare_insert_overwrite(&value);
+
+        let mut duplicates = Vec::with_capacity(prepared.duplicate_count());
+
+        self.try_reserve_insert_overwrite_commit(
+            prepared.needs_new_item_slot(),
+        )
+        .expect("reserved space successfully");
+
+        self.commit_insert_overwrite(value, prepared, &mut duplicates);
+
+        duplicates
+    }
+
+    /// Inserts a value into the map, replacing at most one existing item.
+    ///
+    /// If the new item conflicts with a single existing item (on any number of
+    /// keys), that item is removed and returned. If it conflicts with two or
+    /// more distinct items, the map is left unchanged and an error is returned
+    /// with all of the conflicting items.
+    ///
+    /// Displacing several items is usually a sign that keys have gotten out of
+    /// sync, which [`Self::insert_overwrite`] would silently hide.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "default-hasher")] {
+    /// use iddqd::{TriHashItem, TriHashMap, tri_upcast};
+    ///
+    /// #[derive(Debug, PartialEq, Eq)]
+    /// struct Person {
+    ///     id: u32,
+    ///     email: String,
+    ///     phone: String,
+    /// }
+    ///
+    /// impl TriHashItem for Person {
+    ///     type K1<'a> = u32;
+    ///     type K2<'a> = &'a str;
+    ///     type K3<'a> = &'a str;
+    ///
+    ///     fn key1(&self) -> Self::K1<'_> {
+    ///         self.id
+    ///     }
+    ///     fn key2(&self) -> Self::K2<'_> {
+    ///         &self.email
+    ///     }
+    ///     fn key3(&self) -> Self::K3<'_> {
+    ///         &self.phone
+    ///     }
+    ///     tri_upcast!();
+    /// }
+    ///
+    /// fn person(id: u32, email: &str, phone: &str) -> Person {
+    ///     Person { id, email: email.to_string(), phone: phone.to_string() }
+    /// }
+    ///
+    /// let mut map = TriHashMap::new();
+    /// map.insert_unique(person(1, "alice@example.com", "555-1234")).unwrap();
+    /// map.insert_unique(person(2, "bob@example.com", "555-5678")).unwrap();
+    ///
+    /// // No conflicts.
+    /// let new = person(3, "carol@example.com", "555-0000");
+    /// assert_eq!(map.insert_expect_single_conflict(new).unwrap(), None);
+    ///
+    /// // A conflict with a single item replaces it.
+    /// let new = person(1, "alice@example.com", "555-4321");
+    /// let replaced = map.insert_expect_single_conflict(new).unwrap();
+    /// assert_eq!(replaced.unwrap().phone, "555-1234");
+    ///
+    /// // Conflicts with two different items are an error.
+    /// let new = person(1, "bob@example.com", "555-9999");
+    /// let err = map.insert_expect_single_conflict(new).unwrap_err();
+    /// let ids: Vec<_> = err.duplicates().iter().map(|p| p.id).collect();
+    /// assert_eq!(ids, [1, 2]);
+    /// assert_eq!(map.len(), 3);
+    /// # }
+    /// ```
+    pub fn insert_expect_single_conflict(
+        &mut self,
+        value: T,
+    ) -> Result<Option<T>, DuplicateItem<T, &T>> {
+        let prepared = self.prepare_insert_overwrite(&value);
+
+        if prepared.duplicate_count() > 1 {
+            let duplicates = prepared
+                .duplicates
+                .iter()
+                .map(|duplicate| &self.items[duplicate.index])
+                .collect();
+            return Err(DuplicateItem::__internal_new(value, duplicates));
+        }
+
+        let mut duplicates = Vec::with_capacity(prepared.duplicate_count());
+
+        self.try_reserve_insert_overwrite_commit(
+            prepared.needs_new_item_slot(),
+        )
+        .expect("reserved space successfully");
+
+        self.commit_insert_overwrite(value, prepared, &mut duplicates);
+
+        Ok(duplicates.pop())
+    }
+
+    /// Inserts a value into the set, returning an error if any duplicates were
+    /// added.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "default-hasher")] {
+    /// use iddqd::{TriHashItem, TriHashMap, tri_upcast};
+    ///
+    /// #[derive(Debug, PartialEq, Eq)]
+    /// struct Person {
+    ///     id: u32,
+    ///     email: String,
+    ///     phone: String,
+    ///     name: String,
+    /// }
+    ///
+    /// impl TriHashItem for Person {
+    ///     type K1<'a> = u32;
+    ///     type K2<'a> = &'a str;
+    ///     type K3<'a> = &'a str;
+    ///
+    ///     fn key1(&self) -> Self::K1<'_> {
+    ///         self.id
+    ///     }
+    ///     fn key2(&self) -> Self::K2<'_> {
+    ///         &self.email
+    ///     }
+    ///     fn key3(&self) -> Self::K3<'_> {
+    ///         &self.phone
+    ///     }
+    ///     tri_upcast!();
+    /// }
+    ///
+    /// let mut map = TriHashMap::new();
+    ///
+    /// // Successful insertion
+    /// assert!(
+    ///     map.insert_unique(Person {
+    ///         id: 1,
+    ///         email: "alice@example.com".to_string(),
+    ///         phone: "555-1234".to_string(),
+    ///         name: "Alice".to_string(),
+    ///     })
+    ///     .is_ok()
+    /// );
+    /// assert!(
+    ///     map.insert_unique(Person {
+    ///         id: 2,
+    ///         email: "bob@example.com".to_string(),
+    ///         phone: "555-5678".to_string(),
+    ///         name: "Bob".to_string(),
+    ///     })
+    ///     .is_ok()
+    /// );
+    ///
+    /// // Duplicate key1
+    /// assert!(
+    ///     map.insert_unique(Person {
+    ///         id: 1,
+    ///         email: "charlie@example.com".to_string(),
+    ///         phone: "555-9999".to_string(),
+    ///         name: "Charlie".to_string(),
+    ///     })
+    ///     .is_err()
+    /// );
+    ///
+    /// // Duplicate key2
+    /// assert!(
+    ///     map.insert_unique(Person {
+    ///         id: 3,
+    ///         email: "alice@example.com".to_string(),
+    ///         phone: "555-7777".to_string(),
+    ///         name: "Alice2".to_string(),
+    ///     })
+    ///     .is_err()
+    /// );
+    ///
+    /// // Duplicate key3
+    /// assert!(
+    ///     map.insert_unique(Person {
+    ///         id: 4,
+    ///         email: "dave@example.com".to_string(),
+    ///         phone: "555-1234".to_string(),
+    ///         name: "Dave".to_string(),
+    ///     })
+    ///     .is_err()
+    /// );
+    /// # }
+    /// ```
+    pub fn insert_unique(
+        &mut self,
+        value: T,
+    ) -> Result<(), DuplicateItem<T, &T>> {
+        let _ = self.insert_unique_impl(value)?;
+        Ok(())
+    }
+
+    /// Extends the map with the contents of an iterator, handling duplicates
+    /// according to `mode`.
+    ///
+    /// With [`ExtendMode::Overwrite`], this behaves like calling
+    /// [`TriHashMap::insert_overwrite`] for each item, and never returns an
+    /// error. This is what the [`Extend`] implementation does.
+    ///
+    /// With [`ExtendMode::Unique`], this behaves like calling
+    /// [`TriHashMap::insert_unique`] for each item, stopping at the first item
+    /// that conflicts with an existing one. Items before the conflicting one
+    /// remain in the map, and the rest of the iterator is dropped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "default-hasher")] {
+    /// use iddqd::{ExtendMode, TriHashItem, TriHashMap, tri_upcast};
+    ///
+    /// #[derive(Debug, PartialEq, Eq)]
+    /// struct Person {
+    ///     id: u32,
+    ///     email: String,
+    ///     phone: String,
+    /// }
+    ///
+    /// impl TriHashItem for Person {
+    ///     type K1<'a> = u32;
+    ///     type K2<'a> = &'a str;
+    ///     type K3<'a> = &'a str;
+    ///
+    ///     fn key1(&self) -> Self::K1<'_> {
+    ///         self.id
+    ///     }
+    ///     fn key2(&self) -> Self::K2<'_> {
+    ///         &self.email
+    ///     }
+    ///     fn key3(&self) -> Self::K3<'_> {
+    ///         &self.phone
+    ///     }
+    ///     tri_upcast!();
+    /// }
+    ///
+    /// fn person(id: u32, email: &str, phone: &str) -> Person {
+    ///     Person { id, email: email.to_string(), phone: phone.to_string() }
+    /// }
+    ///
+    /// let mut map = TriHashMap::new();
+    /// map.insert_unique(person(1, "alice@example.com", "555-1234")).unwrap();
+    ///
+    /// // Unique mode stops at the first item that conflicts on any key.
+    /// let new_items = vec![
+    ///     person(2, "bob@example.com", "555-5678"),
+    ///     person(3, "carol@example.com", "555-1234"),
+    ///     person(4, "dave@example.com", "555-0000"),
+    /// ];
+    /// let err = map.extend_with(new_items, ExtendMode::Unique).unwrap_err();
+    /// assert_eq!(err.new_item().id, 3);
+    /// assert_eq!(err.duplicates()[0].id, 1);
+    /// assert_eq!(map.len(), 2);
+    /// assert!(map.contains_key1(&2));
+    /// assert!(!map.contains_key1(&4));
+    ///
+    /// // Overwrite mode replaces duplicates.
+    /// let new_items = vec![person(3, "carol@example.com", "555-1234")];
+    /// map.extend_with(new_items, ExtendMode::Overwrite).unwrap();
+    /// assert_eq!(map.get3("555-1234").unwrap().id, 3);
+    /// assert!(!map.contains_key1(&1));
+    /// # }
+    /// ```
+    pub fn extend_with<I: IntoIterator<Item = T>>(
+        &mut self,
+        iter: I,
+        mode: ExtendMode,
+    ) -> Result<(), DuplicateItem<T, &T>> {
+        // Keys may already be present in the map, or multiple times in the
+        // iterator. Reserve the entire hint lower bound if the map is empty.
+        // Otherwise reserve half the hint (rounded up), so the map will only
+        // resize twice in the worst case.
+        let iter = iter.into_iter();
+        let reserve = if self.is_empty() {
+            iter.size_hint().0
+        } else {
+            iter.size_hint().0.div_ceil(2)
+        };
+        self.reserve(reserve);
+        match mode {
+            ExtendMode::Overwrite => {
+                for item in iter {
+                    self.insert_overwrite(item);
+                }
+            }
+            ExtendMode::Unique => {
+                for item in iter {
+                    if let Err((item, duplicates)) =
+                        self.insert_unique_or_dup_indexes(item)
+                    {
+                        return Err(DuplicateItem::__internal_new(
+                            item,
+                            duplicates
+                                .iter()
+                                .map(|ix| &self.items[*ix])
+                                .collect(),
+                        ));
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn insert_unique_or_dup_indexes(
+        &mut self,
+        value: T,
     ) -> Result<ItemIndex, (T, BTreeSet<ItemIndex>)> {
         let mut duplicates = BTreeSet::new();
 
-        // Check for duplicates *before* inserting the new item, because we
-        // don't want to partially insert the new item and then have to roll
-        // back.
-        let state = &self.tables.state;
-        let (e1, e2, e3) = {
-            let k1 = value.key1();
-            let k2 = value.key2();
-            let k3 = value.key3();
+        // Check for duplicates *before* inserting the new item, because we
+        // don't want to partially insert the new item and then have to roll
+        // back.
+        let state = &self.tables.state;
+        let (e1, e2, e3) = {
+            let k1 = value.key1();
+            let k2 = value.key2();
+            let k3 = value.key3();
+
+            let e1 = detect_dup_or_insert(
+                self.tables
+                    .k1_to_item
+                    .entry(state, k1, |index| self.items[index].key1()),
+                &mut duplicates,
+            );
+            let e2 = detect_dup_or_insert(
+                self.tables
+                    .k2_to_item
+                    .entry(state, k2, |index| self.items[index].key2()),
+                &mut duplicates,
+            );
+            let e3 = detect_dup_or_insert(
+                self.tables
+                    .k3_to_item
+                    .entry(state, k3, |index| self.items[index].key3()),
+                &mut duplicates,
+            );
+            (e1, e2, e3)
+        };
+
+        if !duplicates.is_empty() {
+            return Err((value, duplicates));
+        }
+
+        let next_index = self.items.assert_can_grow().insert(value);
+        // e1, e2 and e3 are all Some because if they were None, duplicates
+        // would be non-empty, and we'd have bailed out earlier.
+        e1.unwrap().insert(next_index);
+        e2.unwrap().insert(next_index);
+        e3.unwrap().insert(next_index);
+
+        Ok(next_index)
+    }
+
+    /// Returns true if the map contains a single item that matches all three
+    /// keys.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "default-hasher")] {
+    /// use iddqd::{TriHashItem, TriHashMap, tri_upcast};
+    ///
+    /// #[derive(Debug, PartialEq, Eq)]
+    /// struct Person {
+    ///     id: u32,
+    ///     email: String,
+    ///     phone: String,
+    ///     name: String,
+    /// }
+    ///
+    /// impl TriHashItem for Person {
+    ///     type K1<'a> = u32;
+    ///     type K2<'a> = &'a str;
+    ///     type K3<'a> = &'a str;
+    ///
+    ///     fn key1(&self) -> Self::K1<'_> {
+    ///         self.id
+    ///     }
+    ///     fn key2(&self) -> Self::K2<'_> {
+    ///         &self.email
+    ///     }
+    ///     fn key3(&self) -> Self::K3<'_> {
+    ///         &self.phone
+    ///     }
+    ///     tri_upcast!();
+    /// }
+    ///
+    /// let mut map = TriHashMap::new();
+    /// map.insert_unique(Person {
+    ///     id: 1,
+    ///     email: "alice@example.com".to_string(),
+    ///     phone: "555-1234".to_string(),
+    ///     name: "Alice".to_string(),
+    /// }).unwrap();
+    /// map.insert_unique(Person {
+    ///     id: 2,
+    ///     email: "bob@example.com".to_string(),
+    ///     phone: "555-5678".to_string(),
+    ///     name: "Bob".to_string(),
+    /// }).unwrap();
+    ///
+    /// assert!(map.contains_key_unique(&1, &"alice@example.com", &"555-1234"));
+    /// assert!(map.contains_key_unique(&2, &"bob@example.com", &"555-5678"));
+    /// assert!(!map.contains_key_unique(&1, &"bob@example.com", &"555-1234")); // key1 exists but key2 doesn't match
+    /// assert!(!map.contains_key_unique(&3, &"charlie@example.com", &"555-9999")); // none of the keys exist
+    /// # }
+    /// ```
+    pub fn contains_key_unique<'a, Q1, Q2, Q3>(
+        &'a self,
+        key1: &Q1,
+        key2: &Q2,
+        key3: &Q3,
+    ) -> bool
+    where
+        Q1: Hash + Equivalent<T::K1<'a>> + ?Sized,
+        Q2: Hash + Equivalent<T::K2<'a>> + ?Sized,
+        Q3: Hash + Equivalent<T::K3<'a>> + ?Sized,
+    {
+        self.get_unique(key1, key2, key3).is_some()
+    }
+
+    /// Returns true if the map contains an item that matches any of `key1`,
+    /// `key2`, or `key3`.
+    ///
+    /// Unlike [`contains_key_unique`](Self::contains_key_unique), the keys
+    /// don't have to belong to the same item. This is useful for checking
+    /// whether [`insert_unique`](Self::insert_unique) would fail.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "default-hasher")] {
+    /// use iddqd::{TriHashItem, TriHashMap, tri_upcast};
+    ///
+    /// #[derive(Debug, PartialEq, Eq)]
+    /// struct Person {
+    ///     id: u32,
+    ///     email: String,
+    ///     phone: String,
+    /// }
+    ///
+    /// impl TriHashItem for Person {
+    ///     type K1<'a> = u32;
+    ///     type K2<'a> = &'a str;
+    ///     type K3<'a> = &'a str;
+    ///
+    ///     fn key1(&self) -> Self::K1<'_> {
+    ///         self.id
+    ///     }
+    ///     fn key2(&self) -> Self::K2<'_> {
+    ///         &self.email
+    ///     }
+    ///     fn key3(&self) -> Self::K3<'_> {
+    ///         &self.phone
+    ///     }
+    ///     tri_upcast!();
+    /// }
+    ///
+    /// let mut map = TriHashMap::new();
+    /// map.insert_unique(Person {
+    ///     id: 1,
+    ///     email: "alice@example.com".to_string(),
+    ///     phone: "555-1234".to_string(),
+    /// }).unwrap();
+    ///
+    /// assert!(map.contains_any(&1, &"bob@example.com", &"555-5678")); // key1 exists
+    /// assert!(map.contains_any(&2, &"bob@example.com", &"555-1234")); // key3 exists
+    /// assert!(!map.contains_any(&2, &"bob@example.com", &"555-5678")); // none of the keys exist
+    /// # }
+    /// ```
+    pub fn contains_any<'a, Q1, Q2, Q3>(
+        &'a self,
+        key1: &Q1,
+        key2: &Q2,
+        key3: &Q3,
+    ) -> bool
+    where
+        Q1: Hash + Equivalent<T::K1<'a>> + ?Sized,
+        Q2: Hash + Equivalent<T::K2<'a>> + ?Sized,
+        Q3: Hash + Equivalent<T::K3<'a>> + ?Sized,
+    {
+        self.contains_key1(key1)
+            || self.contains_key2(key2)
+            || self.contains_key3(key3)
+    }
+
+    /// Gets a reference to the unique item associated with the given `key1`,
+    /// `key2`, and `key3`, if it exists.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "default-hasher")] {
+    /// use iddqd::{TriHashItem, TriHashMap, tri_upcast};
+    ///
+    /// #[derive(Debug, PartialEq, Eq)]
+    /// struct Person {
+    ///     id: u32,
+    ///     email: String,
+    ///     phone: String,
+    ///     name: String,
+    /// }
+    ///
+    /// impl TriHashItem for Person {
+    ///     type K1<'a> = u32;
+    ///     type K2<'a> = &'a str;
+    ///     type K3<'a> = &'a str;
+    ///
+    ///     fn key1(&self) -> Self::K1<'_> {
+    ///         self.id
+    ///     }
+    ///     fn key2(&self) -> Self::K2<'_> {
+    ///         &self.email
+    ///     }
+    ///     fn key3(&self) -> Self::K3<'_> {
+    ///         &self.phone
+    ///     }
+    ///     tri_upcast!();
+    /// }
+    ///
+    /// let mut map = TriHashMap::new();
+    /// map.insert_unique(Person {
+    ///     id: 1,
+    ///     email: "alice@example.com".to_string(),
+    ///     phone: "555-1234".to_string(),
+    ///     name: "Alice".to_string(),
+    /// })
+    /// .unwrap();
+    ///
+    /// // All three keys must match
+    /// assert_eq!(
+    ///     map.get_unique(&1, &"alice@example.com", &"555-1234").unwrap().name,
+    ///     "Alice"
+    /// );
+    ///
+    /// // If any key doesn't match, returns None
+    /// assert!(map.get_unique(&1, &"wrong@example.com", &"555-1234").is_none());
+    /// assert!(map.get_unique(&2, &"alice@example.com", &"555-1234").is_none());
+    /// # }
+    /// ```
+    pub fn get_unique<'a, Q1, Q2, Q3>(
+        &'a self,
+        key1: &Q1,
+        key2: &Q2,
+        key3: &Q3,
+    ) -> Option<&'a T>
+    where
+        Q1: Hash + Equivalent<T::K1<'a>> + ?Sized,
+        Q2: Hash + Equivalent<T::K2<'a>> + ?Sized,
+        Q3: Hash + Equivalent<T::K3<'a>> + ?Sized,
+    {
+        let index = self.find1_index(key1)?;
+        let item = &self.items[index];
+        if key2.equivalent(&item.key2()) && key3.equivalent(&item.key3()) {
+            Some(item)
+        } else {
+            None
+        }
+    }
 
-            let e1 = detect_dup_or_insert(
-                self.tables
-                    .k1_to_item
-                    .entry(state, k1, |index| self.items[index].key1()),
-                &mut duplicates,
-            );
-            let e2 = detect_dup_or_insert(
-                self.tables
-                    .k2_to_item
-                    .entry(state, k2, |index| self.items[index].key2()),
-                &mut duplicates,
-            );
-            let e3 = detect_dup_or_insert(
-                self.tables
-                    .k3_to_item
-                    .entry(state, k3, |index| self.items[index].key3()),
-                &mut duplicates,
-            );
-            (e1, e2, e3)
+    /// Gets references to the items associated with `key1`, `key2`, and
+    /// `key3` separately.
+    ///
+    /// The elements of the returned tuple are the items matching `key1`,
+    /// `key2`, and `key3` respectively. If a single item matches more than one
+    /// key, it is returned in each of those positions.
+    ///
+    /// This is useful for reporting which keys of a new item conflict with
+    /// existing items, for example before calling [`Self::insert_unique`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "default-hasher")] {
+    /// use iddqd::{TriHashItem, TriHashMap, tri_upcast};
+    ///
+    /// #[derive(Debug, PartialEq, Eq)]
+    /// struct Person {
+    ///     id: u32,
+    ///     email: String,
+    ///     phone: String,
+    /// }
+    ///
+    /// impl TriHashItem for Person {
+    ///     type K1<'a> = u32;
+    ///     type K2<'a> = &'a str;
+    ///     type K3<'a> = &'a str;
+    ///
+    ///     fn key1(&self) -> Self::K1<'_> {
+    ///         self.id
+    ///     }
+    ///     fn key2(&self) -> Self::K2<'_> {
+    ///         &self.email
+    ///     }
+    ///     fn key3(&self) -> Self::K3<'_> {
+    ///         &self.phone
+    ///     }
+    ///     tri_upcast!();
+    /// }
+    ///
+    /// let mut map = TriHashMap::new();
+    /// map.insert_unique(Person {
+    ///     id: 1,
+    ///     email: "alice@example.com".to_string(),
+    ///     phone: "555-1234".to_string(),
+    /// })
+    /// .unwrap();
+    ///
+    /// let new = Person {
+    ///     id: 2,
+    ///     email: "alice@example.com".to_string(),
+    ///     phone: "555-5678".to_string(),
+    /// };
+    /// assert!(map.insert_unique(new).is_err());
+    ///
+    /// // Only the email is already in use.
+    /// let (by_id, by_email, by_phone) =
+    ///     map.get_conflicts(&2, &"alice@example.com", &"555-5678");
+    /// assert!(by_id.is_none());
+    /// assert_eq!(by_email.unwrap().id, 1);
+    /// assert!(by_phone.is_none());
+    /// # }
+    /// ```
+    pub fn get_conflicts<'a, Q1, Q2, Q3>(
+        &'a self,
+        key1: &Q1,
+        key2: &Q2,
+        key3: &Q3,
+    ) -> (Option<&'a T>, Option<&'a T>, Option<&'a T>)
+    where
+        Q1: Hash + Equivalent<T::K1<'a>> + ?Sized,
+        Q2: Hash + Equivalent<T::K2<'a>> + ?Sized,
+        Q3: Hash + Equivalent<T::K3<'a>> + ?Sized,
+    {
+        (self.find1(key1), self.find2(key2), self.find3(key3))
+    }
+
+    /// Gets a mutable reference to the unique item associated with the given
+    /// `key1`, `key2`, and `key3`, if it exists.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "default-hasher")] {
+    /// use iddqd::{TriHashItem, TriHashMap, tri_upcast};
+    ///
+    /// #[derive(Debug, PartialEq, Eq)]
+    /// struct Person {
+    ///     id: u32,
+    ///     email: String,
+    ///     phone: String,
+    ///     name: String,
+    /// }
+    ///
+    /// impl TriHashItem for Person {
+    ///     type K1<'a> = u32;
+    ///     type K2<'a> = &'a str;
+    ///     type K3<'a> = &'a str;
+    ///
+    ///     fn key1(&self) -> Self::K1<'_> {
+    ///         self.id
+    ///     }
+    ///     fn key2(&self) -> Self::K2<'_> {
+    ///         &self.email
+    ///     }
+    ///     fn key3(&self) -> Self::K3<'_> {
+    ///         &self.phone
+    ///     }
+    ///     tri_upcast!();
+    /// }
+    ///
+    /// let mut map = TriHashMap::new();
+    /// map.insert_unique(Person {
+    ///     id: 1,
+    ///     email: "alice@example.com".to_string(),
+    ///     phone: "555-1234".to_string(),
+    ///     name: "Alice".to_string(),
+    /// })
+    /// .unwrap();
+    ///
+    /// // Modify the item through the mutable reference
+    /// if let Some(mut person) =
+    ///     map.get_mut_unique(&1, &"alice@example.com", &"555-1234")
+    /// {
+    ///     person.name = "Alice Updated".to_string();
+    /// }
+    ///
+    /// // Verify the change
+    /// assert_eq!(map.get1(&1).unwrap().name, "Alice Updated");
+    /// # }
+    /// ```
+    pub fn get_mut_unique<'a, Q1, Q2, Q3>(
+        &'a mut self,
+        key1: &Q1,
+        key2: &Q2,
+        key3: &Q3,
+    ) -> Option<RefMut<'a, T, S>>
+    where
+        Q1: Hash + Equivalent<T::K1<'a>> + ?Sized,
+        Q2: Hash + Equivalent<T::K2<'a>> + ?Sized,
+        Q3: Hash + Equivalent<T::K3<'a>> + ?Sized,
+    {
+        let (dormant_map, index) = {
+            let (map, dormant_map) = DormantMutRef::new(self);
+            let index = map.find1_index(key1)?;
+            let item = &map.items[index];
+            if !key2.equivalent(&item.key2()) || !key3.equivalent(&item.key3())
+            {
+                return None;
+            }
+            (dormant_map, index)
         };
 
-        if !duplicates.is_empty() {
-            return Err((value, duplicates));
-        }
-
-        let next_index = self.items.assert_can_grow().insert(value);
-        // e1, e2 and e3 are all Some because if they were None, duplicates
-        // would be non-empty, and we'd have bailed out earlier.
-        e1.unwrap().insert(next_index);
-        e2.unwrap().insert(next_index);
-        e3.unwrap().insert(next_index);
-
-        Ok(next_index)
+        // SAFETY: `map` is not used after this point.
+        let awakened_map = unsafe { dormant_map.awaken() };
+        let item = &mut awakened_map.items[index];
+        let state = awakened_map.tables.state.clone();
+        let hashes = awakened_map.tables.make_hashes(&item);
+        Some(RefMut::new(state, hashes, item))
     }
 
-    /// Returns true if the map contains a single item that matches all three
-    /// keys.
+    /// Gets a reference to the unique item associated with the given `key1`
+    /// and `key2`, if it exists.
+    ///
+    /// The item is looked up by `key1`, and then its second key is checked
+    /// against `key2`. This is useful when the third key isn't known; if it
+    /// is, use [`Self::get_unique`].
     ///
     /// # Examples
     ///
@@ -1692,36 +2955,172 @@ impl<T: TriHashItem, S: Clone + BuildHasher, A: Allocator> TriHashMap<T, S, A> {
     ///     email: "alice@example.com".to_string(),
     ///     phone: "555-1234".to_string(),
     ///     name: "Alice".to_string(),
-    /// }).unwrap();
+    /// })
+    /// .unwrap();
+    ///
+    /// // Both keys must match.
+    /// assert_eq!(map.get12(&1, &"alice@example.com").unwrap().name, "Alice");
+    ///
+    /// // If either key doesn't match, returns None.
+    /// assert!(map.get12(&1, &"wrong@example.com").is_none());
+    /// assert!(map.get12(&2, &"alice@example.com").is_none());
+    /// # }
+    /// ```
+    pub fn get12<'a, Q1, Q2>(&'a self, key1: &Q1, key2: &Q2) -> Option<&'a T>
+    where
+        Q1: Hash + Equivalent<T::K1<'a>> + ?Sized,
+        Q2: Hash + Equivalent<T::K2<'a>> + ?Sized,
+    {
+        let index = self.find1_index(key1)?;
+        let item = &self.items[index];
+        if key2.equivalent(&item.key2()) { Some(item) } else { None }
+    }
+
+    /// Gets a mutable reference to the unique item associated with the given
+    /// `key1` and `key2`, if it exists.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "default-hasher")] {
+    /// use iddqd::{TriHashItem, TriHashMap, tri_upcast};
+    ///
+    /// #[derive(Debug, PartialEq, Eq)]
+    /// struct Person {
+    ///     id: u32,
+    ///     email: String,
+    ///     phone: String,
+    ///     name: String,
+    /// }
+    ///
+    /// impl TriHashItem for Person {
+    ///     type K1<'a> = u32;
+    ///     type K2<'a> = &'a str;
+    ///     type K3<'a> = &'a str;
+    ///
+    ///     fn key1(&self) -> Self::K1<'_> {
+    ///         self.id
+    ///     }
+    ///     fn key2(&self) -> Self::K2<'_> {
+    ///         &self.email
+    ///     }
+    ///     fn key3(&self) -> Self::K3<'_> {
+    ///         &self.phone
+    ///     }
+    ///     tri_upcast!();
+    /// }
+    ///
+    /// let mut map = TriHashMap::new();
     /// map.insert_unique(Person {
-    ///     id: 2,
-    ///     email: "bob@example.com".to_string(),
-    ///     phone: "555-5678".to_string(),
-    ///     name: "Bob".to_string(),
-    /// }).unwrap();
+    ///     id: 1,
+    ///     email: "alice@example.com".to_string(),
+    ///     phone: "555-1234".to_string(),
+    ///     name: "Alice".to_string(),
+    /// })
+    /// .unwrap();
     ///
-    /// assert!(map.contains_key_unique(&1, &"alice@example.com", &"555-1234"));
-    /// assert!(map.contains_key_unique(&2, &"bob@example.com", &"555-5678"));
-    /// assert!(!map.contains_key_unique(&1, &"bob@example.com", &"555-1234")); // key1 exists but key2 doesn't match
-    /// assert!(!map.contains_key_unique(&3, &"charlie@example.com", &"555-9999")); // none of the keys exist
+    /// // Modify the item through the mutable reference.
+    /// if let Some(mut person) = map.get12_mut(&1, &"alice@example.com") {
+    ///     person.name = "Alice Updated".to_string();
+    /// }
+    ///
+    /// // Verify the change.
+    /// assert_eq!(map.get1(&1).unwrap().name, "Alice Updated");
     /// # }
     /// ```
-    pub fn contains_key_unique<'a, Q1, Q2, Q3>(
-        &'a self,
+    pub fn get12_mut<'a, Q1, Q2>(
+        &'a mut self,
         key1: &Q1,
         key2: &Q2,
-        key3: &Q3,
-    ) -> bool
+    ) -> Option<RefMut<'a, T, S>>
     where
         Q1: Hash + Equivalent<T::K1<'a>> + ?Sized,
         Q2: Hash + Equivalent<T::K2<'a>> + ?Sized,
+    {
+        let (dormant_map, index) = {
+            let (map, dormant_map) = DormantMutRef::new(self);
+            let index = map.find1_index(key1)?;
+            if !key2.equivalent(&map.items[index].key2()) {
+                return None;
+            }
+            (dormant_map, index)
+        };
+
+        // SAFETY: `map` is not used after this point.
+        let awakened_map = unsafe { dormant_map.awaken() };
+        let item = &mut awakened_map.items[index];
+        let state = awakened_map.tables.state.clone();
+        let hashes = awakened_map.tables.make_hashes(&item);
+        Some(RefMut::new(state, hashes, item))
+    }
+
+    /// Gets a reference to the unique item associated with the given `key1`
+    /// and `key3`, if it exists.
+    ///
+    /// The item is looked up by `key1`, and then its third key is checked
+    /// against `key3`. This is useful when the second key isn't known; if it
+    /// is, use [`Self::get_unique`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "default-hasher")] {
+    /// use iddqd::{TriHashItem, TriHashMap, tri_upcast};
+    ///
+    /// #[derive(Debug, PartialEq, Eq)]
+    /// struct Person {
+    ///     id: u32,
+    ///     email: String,
+    ///     phone: String,
+    ///     name: String,
+    /// }
+    ///
+    /// impl TriHashItem for Person {
+    ///     type K1<'a> = u32;
+    ///     type K2<'a> = &'a str;
+    ///     type K3<'a> = &'a str;
+    ///
+    ///     fn key1(&self) -> Self::K1<'_> {
+    ///         self.id
+    ///     }
+    ///     fn key2(&self) -> Self::K2<'_> {
+    ///         &self.email
+    ///     }
+    ///     fn key3(&self) -> Self::K3<'_> {
+    ///         &self.phone
+    ///     }
+    ///     tri_upcast!();
+    /// }
+    ///
+    /// let mut map = TriHashMap::new();
+    /// map.insert_unique(Person {
+    ///     id: 1,
+    ///     email: "alice@example.com".to_string(),
+    ///     phone: "555-1234".to_string(),
+    ///     name: "Alice".to_string(),
+    /// })
+    /// .unwrap();
+    ///
+    /// // Both keys must match.
+    /// assert_eq!(map.get13(&1, &"555-1234").unwrap().name, "Alice");
+    ///
+    /// // If either key doesn't match, returns None.
+    /// assert!(map.get13(&1, &"555-0000").is_none());
+    /// assert!(map.get13(&2, &"555-1234").is_none());
+    /// # }
+    /// ```
+    pub fn get13<'a, Q1, Q3>(&'a self, key1: &Q1, key3: &Q3) -> Option<&'a T>
+    where
+        Q1: Hash + Equivalent<T::K1<'a>> + ?Sized,
         Q3: Hash + Equivalent<T::K3<'a>> + ?Sized,
     {
-        self.get_unique(key1, key2, key3).is_some()
+        let index = self.find1_index(key1)?;
+        let item = &self.items[index];
+        if key3.equivalent(&item.key3()) { Some(item) } else { None }
     }
 
-    /// Gets a reference to the unique item associated with the given `key1`,
-    /// `key2`, and `key3`, if it exists.
+    /// Gets a mutable reference to the unique item associated with the given
+    /// `key1` and `key3`, if it exists.
     ///
     /// # Examples
     ///
@@ -1763,39 +3162,111 @@ impl<T: TriHashItem, S: Clone + BuildHasher, A: Allocator> TriHashMap<T, S, A> {
     /// })
     /// .unwrap();
     ///
-    /// // All three keys must match
-    /// assert_eq!(
-    ///     map.get_unique(&1, &"alice@example.com", &"555-1234").unwrap().name,
-    ///     "Alice"
-    /// );
+    /// // Modify the item through the mutable reference.
+    /// if let Some(mut person) = map.get13_mut(&1, &"555-1234") {
+    ///     person.name = "Alice Updated".to_string();
+    /// }
     ///
-    /// // If any key doesn't match, returns None
-    /// assert!(map.get_unique(&1, &"wrong@example.com", &"555-1234").is_none());
-    /// assert!(map.get_unique(&2, &"alice@example.com", &"555-1234").is_none());
+    /// // Verify the change.
+    /// assert_eq!(map.get1(&1).unwrap().name, "Alice Updated");
     /// # }
     /// ```
-    pub fn get_unique<'a, Q1, Q2, Q3>(
-        &'a self,
+    pub fn get13_mut<'a, Q1, Q3>(
+        &'a mut self,
         key1: &Q1,
-        key2: &Q2,
         key3: &Q3,
-    ) -> Option<&'a T>
+    ) -> Option<RefMut<'a, T, S>>
+    where
+        Q1: Hash + Equivalent<T::K1<'a>> + ?Sized,
+        Q3: Hash + Equivalent<T::K3<'a>> + ?Sized,
+    {
+        let (dormant_map, index) = {
+            let (map, dormant_map) = DormantMutRef::new(self);
+            let index = map.find1_index(key1)?;
+            if !key3.equivalent(&map.items[index].key3()) {
+                return None;
+            }
+            (dormant_map, index)
+        };
+
+        // SAFETY: `map` is not used after this point.
+        let awakened_map = unsafe { dormant_map.awaken() };
+        let item = &mut awakened_map.items[index];
+        let state = awakened_map.tables.state.clone();
+        let hashes = awakened_map.tables.make_hashes(&item);
+        Some(RefMut::new(state, hashes, item))
+    }
+
+    /// Gets a reference to the unique item associated with the given `key2`
+    /// and `key3`, if it exists.
+    ///
+    /// The item is looked up by `key2`, and then its third key is checked
+    /// against `key3`. This is useful when the first key isn't known; if it
+    /// is, use [`Self::get_unique`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "default-hasher")] {
+    /// use iddqd::{TriHashItem, TriHashMap, tri_upcast};
+    ///
+    /// #[derive(Debug, PartialEq, Eq)]
+    /// struct Person {
+    ///     id: u32,
+    ///     email: String,
+    ///     phone: String,
+    ///     name: String,
+    /// }
+    ///
+    /// impl TriHashItem for Person {
+    ///     type K1<'a> = u32;
+    ///     type K2<'a> = &'a str;
+    ///     type K3<'a> = &'a str;
+    ///
+    ///     fn key1(&self) -> Self::K1<'_> {
+    ///         self.id
+    ///     }
+    ///     fn key2(&self) -> Self::K2<'_> {
+    ///         &self.email
+    ///     }
+    ///     fn key3(&self) -> Self::K3<'_> {
+    ///         &self.phone
+    ///     }
+    ///     tri_upcast!();
+    /// }
+    ///
+    /// let mut map = TriHashMap::new();
+    /// map.insert_unique(Person {
+    ///     id: 1,
+    ///     email: "alice@example.com".to_string(),
+    ///     phone: "555-1234".to_string(),
+    ///     name: "Alice".to_string(),
+    /// })
+    /// .unwrap();
+    ///
+    /// // Both keys must match.
+    /// assert_eq!(
+    ///     map.get23(&"alice@example.com", &"555-1234").unwrap().name,
+    ///     "Alice"
+    /// );
+    ///
+    /// // If either key doesn't match, returns None.
+    /// assert!(map.get23(&"alice@example.com", &"555-0000").is_none());
+    /// assert!(map.get23(&"wrong@example.com", &"555-1234").is_none());
+    /// # }
+    /// ```
+    pub fn get23<'a, Q2, Q3>(&'a self, key2: &Q2, key3: &Q3) -> Option<&'a T>
     where
-        Q1: Hash + Equivalent<T::K1<'a>> + ?Sized,
         Q2: Hash + Equivalent<T::K2<'a>> + ?Sized,
         Q3: Hash + Equivalent<T::K3<'a>> + ?Sized,
     {
-        let index = self.find1_index(key1)?;
+        let index = self.find2_index(key2)?;
         let item = &self.items[index];
-        if key2.equivalent(&item.key2()) && key3.equivalent(&item.key3()) {
-            Some(item)
-        } else {
-            None
-        }
+        if key3.equivalent(&item.key3()) { Some(item) } else { None }
     }
 
     /// Gets a mutable reference to the unique item associated with the given
-    /// `key1`, `key2`, and `key3`, if it exists.
+    /// `key2` and `key3`, if it exists.
     ///
     /// # Examples
     ///
@@ -1837,34 +3308,28 @@ impl<T: TriHashItem, S: Clone + BuildHasher, A: Allocator> TriHashMap<T, S, A> {
     /// })
     /// .unwrap();
     ///
-    /// // Modify the item through the mutable reference
-    /// if let Some(mut person) =
-    ///     map.get_mut_unique(&1, &"alice@example.com", &"555-1234")
-    /// {
+    /// // Modify the item through the mutable reference.
+    /// if let Some(mut person) = map.get23_mut(&"alice@example.com", &"555-1234") {
     ///     person.name = "Alice Updated".to_string();
     /// }
     ///
-    /// // Verify the change
+    /// // Verify the change.
     /// assert_eq!(map.get1(&1).unwrap().name, "Alice Updated");
     /// # }
     /// ```
-    pub fn get_mut_unique<'a, Q1, Q2, Q3>(
+    pub fn get23_mut<'a, Q2, Q3>(
         &'a mut self,
-        key1: &Q1,
         key2: &Q2,
         key3: &Q3,
     ) -> Option<RefMut<'a, T, S>>
     where
-        Q1: Hash + Equivalent<T::K1<'a>> + ?Sized,
         Q2: Hash + Equivalent<T::K2<'a>> + ?Sized,
         Q3: Hash + Equivalent<T::K3<'a>> + ?Sized,
     {
         let (dormant_map, index) = {
             let (map, dormant_map) = DormantMutRef::new(self);
-            let index = map.find1_index(key1)?;
-            let item = &map.items[index];
-            if !key2.equivalent(&item.key2()) || !key3.equivalent(&item.key3())
-            {
+            let index = map.find2_index(key2)?;
+            if !key3.equivalent(&map.items[index].key3()) {
                 return None;
             }
             (dormant_map, index)
@@ -2134,6 +3599,28 @@ impl<T: TriHashItem, S: Clone + BuildHasher, A: Allocator> TriHashMap<T, S, A> {
         Some(RefMut::new(state, hashes, item))
     }
 
+    /// Gets a plain mutable reference to the value associated with the given
+    /// `key1`.
+    ///
+    /// Unlike [`Self::get1_mut`], this doesn't wrap the item in a
+    /// [`RefMut`] that checks the keys on drop. It is only available for items
+    /// that implement [`ImmutableKey`].
+    pub fn get1_mut_plain<'a, Q>(&'a mut self, key1: &Q) -> Option<&'a mut T>
+    where
+        Q: Hash + Equivalent<T::K1<'a>> + ?Sized,
+        T: ImmutableKey,
+    {
+        let (dormant_map, index) = {
+            let (map, dormant_map) = DormantMutRef::new(self);
+            let index = map.find1_index(key1)?;
+            (dormant_map, index)
+        };
+
+        // SAFETY: `map` is not used after this point.
+        let awakened_map = unsafe { dormant_map.awaken() };
+        Some(&mut awakened_map.items[index])
+    }
+
     /// Removes an item from the map by its `key1`.
     ///
     /// # Examples
@@ -2371,6 +3858,28 @@ impl<T: TriHashItem, S: Clone + BuildHasher, A: Allocator> TriHashMap<T, S, A> {
         Some(RefMut::new(state, hashes, item))
     }
 
+    /// Gets a plain mutable reference to the value associated with the given
+    /// `key2`.
+    ///
+    /// Unlike [`Self::get2_mut`], this doesn't wrap the item in a
+    /// [`RefMut`] that checks the keys on drop. It is only available for items
+    /// that implement [`ImmutableKey`].
+    pub fn get2_mut_plain<'a, Q>(&'a mut self, key2: &Q) -> Option<&'a mut T>
+    where
+        Q: Hash + Equivalent<T::K2<'a>> + ?Sized,
+        T: ImmutableKey,
+    {
+        let (dormant_map, index) = {
+            let (map, dormant_map) = DormantMutRef::new(self);
+            let index = map.find2_index(key2)?;
+            (dormant_map, index)
+        };
+
+        // SAFETY: `map` is not used after this point.
+        let awakened_map = unsafe { dormant_map.awaken() };
+        Some(&mut awakened_map.items[index])
+    }
+
     /// Removes an item from the map by its `key2`.
     ///
     /// # Examples
@@ -2534,81 +4043,401 @@ impl<T: TriHashItem, S: Clone + BuildHasher, A: Allocator> TriHashMap<T, S, A> {
     /// assert!(map.get3("555-5678").is_none());
     /// # }
     /// ```
-    pub fn get3<'a, Q>(&'a self, key3: &Q) -> Option<&'a T>
-    where
-        Q: Hash + Equivalent<T::K3<'a>> + ?Sized,
-    {
-        self.find3(key3)
+    pub fn get3<'a, Q>(&'a self, key3: &Q) -> Option<&'a T>
+    where
+        Q: Hash + Equivalent<T::K3<'a>> + ?Sized,
+    {
+        self.find3(key3)
+    }
+
+    /// Gets a mutable reference to the value associated with the given `key3`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "default-hasher")] {
+    /// use iddqd::{TriHashItem, TriHashMap, tri_upcast};
+    ///
+    /// #[derive(Debug, PartialEq, Eq)]
+    /// struct Person {
+    ///     id: u32,
+    ///     email: String,
+    ///     phone: String,
+    ///     name: String,
+    /// }
+    ///
+    /// impl TriHashItem for Person {
+    ///     type K1<'a> = u32;
+    ///     type K2<'a> = &'a str;
+    ///     type K3<'a> = &'a str;
+    ///
+    ///     fn key1(&self) -> Self::K1<'_> {
+    ///         self.id
+    ///     }
+    ///     fn key2(&self) -> Self::K2<'_> {
+    ///         &self.email
+    ///     }
+    ///     fn key3(&self) -> Self::K3<'_> {
+    ///         &self.phone
+    ///     }
+    ///     tri_upcast!();
+    /// }
+    ///
+    /// let mut map = TriHashMap::new();
+    /// map.insert_unique(Person {
+    ///     id: 1,
+    ///     email: "alice@example.com".to_string(),
+    ///     phone: "555-1234".to_string(),
+    ///     name: "Alice".to_string(),
+    /// })
+    /// .unwrap();
+    ///
+    /// if let Some(mut person) = map.get3_mut("555-1234") {
+    ///     person.name = "Alice Updated".to_string();
+    /// }
+    ///
+    /// assert_eq!(map.get3("555-1234").unwrap().name, "Alice Updated");
+    /// # }
+    /// ```
+    pub fn get3_mut<'a, Q>(&'a mut self, key3: &Q) -> Option<RefMut<'a, T, S>>
+    where
+        Q: Hash + Equivalent<T::K3<'a>> + ?Sized,
+    {
+        let (dormant_map, index) = {
+            let (map, dormant_map) = DormantMutRef::new(self);
+            let index = map.find3_index(key3)?;
+            (dormant_map, index)
+        };
+
+        // SAFETY: `map` is not used after this point.
+        let awakened_map = unsafe { dormant_map.awaken() };
+        let item = &mut awakened_map.items[index];
+        let state = awakened_map.tables.state.clone();
+        let hashes = awakened_map.tables.make_hashes(&item);
+        Some(RefMut::new(state, hashes, item))
+    }
+
+    /// Gets a plain mutable reference to the value associated with the given
+    /// `key3`.
+    ///
+    /// Unlike [`Self::get3_mut`], this doesn't wrap the item in a
+    /// [`RefMut`] that checks the keys on drop. It is only available for items
+    /// that implement [`ImmutableKey`].
+    pub fn get3_mut_plain<'a, Q>(&'a mut self, key3: &Q) -> Option<&'a mut T>
+    where
+        Q: Hash + Equivalent<T::K3<'a>> + ?Sized,
+        T: ImmutableKey,
+    {
+        let (dormant_map, index) = {
+            let (map, dormant_map) = DormantMutRef::new(self);
+            let index = map.find3_index(key3)?;
+            (dormant_map, index)
+        };
+
+        // SAFETY: `map` is not used after this point.
+        let awakened_map = unsafe { dormant_map.awaken() };
+        Some(&mut awakened_map.items[index])
+    }
+
+    /// Removes an item from the map by its `key3`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "default-hasher")] {
+    /// use iddqd::{TriHashItem, TriHashMap, tri_upcast};
+    ///
+    /// #[derive(Debug, PartialEq, Eq)]
+    /// struct Person {
+    ///     id: u32,
+    ///     email: String,
+    ///     phone: String,
+    ///     name: String,
+    /// }
+    ///
+    /// impl TriHashItem for Person {
+    ///     type K1<'a> = u32;
+    ///     type K2<'a> = &'a str;
+    ///     type K3<'a> = &'a str;
+    ///
+    ///     fn key1(&self) -> Self::K1<'_> {
+    ///         self.id
+    ///     }
+    ///     fn key2(&self) -> Self::K2<'_> {
+    ///         &self.email
+    ///     }
+    ///     fn key3(&self) -> Self::K3<'_> {
+    ///         &self.phone
+    ///     }
+    ///     tri_upcast!();
+    /// }
+    ///
+    /// let mut map = TriHashMap::new();
+    /// map.insert_unique(Person {
+    ///     id: 1,
+    ///     email: "alice@example.com".to_string(),
+    ///     phone: "555-1234".to_string(),
+    ///     name: "Alice".to_string(),
+    /// })
+    /// .unwrap();
+    ///
+    /// let removed = map.remove3("555-1234");
+    /// assert!(removed.is_some());
+    /// assert_eq!(removed.unwrap().name, "Alice");
+    /// assert!(map.is_empty());
+    /// # }
+    /// ```
+    pub fn remove3<'a, Q>(&'a mut self, key3: &Q) -> Option<T>
+    where
+        Q: Hash + Equivalent<T::K3<'a>> + ?Sized,
+    {
+        let (dormant_map, remove_index) = {
+            let (map, dormant_map) = DormantMutRef::new(self);
+            let remove_index = map.find3_index(key3)?;
+            (dormant_map, remove_index)
+        };
+
+        // SAFETY: `map` is not used after this point.
+        let awakened_map = unsafe { dormant_map.awaken() };
+
+        awakened_map.remove_by_index(remove_index)
+    }
+
+    /// Retrieves an entry by its keys.
+    ///
+    /// Due to borrow checker limitations, this always accepts owned keys rather
+    /// than a borrowed form of them.
+    ///
+    /// # Differences from single-key entries
+    ///
+    /// The [`Entry`] returned by this method differs from those provided
+    /// for the other map types, because it is possible for some of the three
+    /// keys provided to correspond to existing entries, while others do not.
+    /// The keys may also correspond to up to three different items.
+    ///
+    /// For more information, and examples covering non-unique entries, see the
+    /// type-level documentation for [`Entry`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "default-hasher")] {
+    /// use iddqd::{TriHashItem, TriHashMap, tri_hash_map, tri_upcast};
+    ///
+    /// #[derive(Debug, PartialEq, Eq)]
+    /// struct Item {
+    ///     id: u32,
+    ///     name: String,
+    ///     code: char,
+    ///     value: i32,
+    /// }
+    ///
+    /// impl TriHashItem for Item {
+    ///     type K1<'a> = u32;
+    ///     type K2<'a> = &'a str;
+    ///     type K3<'a> = char;
+    ///
+    ///     fn key1(&self) -> Self::K1<'_> {
+    ///         self.id
+    ///     }
+    ///     fn key2(&self) -> Self::K2<'_> {
+    ///         &self.name
+    ///     }
+    ///     fn key3(&self) -> Self::K3<'_> {
+    ///         self.code
+    ///     }
+    ///     tri_upcast!();
+    /// }
+    ///
+    /// let mut map = TriHashMap::new();
+    /// map.insert_unique(Item {
+    ///     id: 1,
+    ///     name: "foo".to_string(),
+    ///     code: 'f',
+    ///     value: 42,
+    /// })
+    /// .unwrap();
+    ///
+    /// // Get an existing entry.
+    /// match map.entry(1, "foo", 'f') {
+    ///     tri_hash_map::Entry::Occupied(entry) => {
+    ///         assert_eq!(entry.get().as_unique().unwrap().value, 42);
+    ///     }
+    ///     tri_hash_map::Entry::Vacant(_) => panic!("Should be occupied"),
+    /// }
+    ///
+    /// // Insert a new entry if it doesn't exist.
+    /// {
+    ///     let mut entry = map.entry(2, "bar", 'b').or_insert_with(|| Item {
+    ///         id: 2,
+    ///         name: "bar".to_string(),
+    ///         code: 'b',
+    ///         value: 99,
+    ///     });
+    ///     entry.as_unique().unwrap().value += 1;
+    /// }
+    ///
+    /// assert_eq!(map.get1(&2).unwrap().value, 100);
+    /// assert_eq!(map.len(), 2);
+    /// # }
+    /// ```
+    ///
+    /// For an expanded example, see the type-level documentation for [`Entry`].
+    pub fn entry<'a>(
+        &'a mut self,
+        key1: T::K1<'_>,
+        key2: T::K2<'_>,
+        key3: T::K3<'_>,
+    ) -> Entry<'a, T, S, A> {
+        // As with BiHashMap::entry, this accepts owned keys and upcasts them
+        // to a shorter lifetime, so that callers don't have the keys borrowed
+        // for the full 'a.
+        let (map, dormant_map) = DormantMutRef::new(self);
+        let key1 = T::upcast_key1(key1);
+        let key2 = T::upcast_key2(key2);
+        let key3 = T::upcast_key3(key3);
+        let (index1, index2, index3) = {
+            // The indexes are explicitly typed to show that they have a
+            // trivial Drop impl that doesn't capture anything from map.
+            let index1: Option<ItemIndex> = map.tables.k1_to_item.find_index(
+                &map.tables.state,
+                &key1,
+                |index| map.items[index].key1(),
+            );
+            let index2: Option<ItemIndex> = map.tables.k2_to_item.find_index(
+                &map.tables.state,
+                &key2,
+                |index| map.items[index].key2(),
+            );
+            let index3: Option<ItemIndex> = map.tables.k3_to_item.find_index(
+                &map.tables.state,
+                &key3,
+                |index| map.items[index].key3(),
+            );
+            (index1, index2, index3)
+        };
+
+        match (index1, index2, index3) {
+            (Some(index1), Some(index2), Some(index3))
+                if index1 == index2 && index2 == index3 =>
+            {
+                // The item is already in the map.
+                Entry::Occupied(
+                    // SAFETY: `map` is not used after this point.
+                    unsafe {
+                        OccupiedEntry::new(
+                            dormant_map,
+                            EntryIndexes::Unique(index1),
+                        )
+                    },
+                )
+            }
+            (None, None, None) => {
+                let hashes =
+                    map.tables.make_hashes_for_keys::<T>(&key1, &key2, &key3);
+                Entry::Vacant(
+                    // SAFETY: `map` is not used after this point.
+                    unsafe { VacantEntry::new(dormant_map, hashes) },
+                )
+            }
+            (index1, index2, index3) => Entry::Occupied(
+                // SAFETY: `map` is not used after this point.
+                unsafe {
+                    OccupiedEntry::new(
+                        dormant_map,
+                        EntryIndexes::NonUnique { index1, index2, index3 },
+                    )
+                },
+            ),
+        }
     }
 
-    /// Gets a mutable reference to the value associated with the given `key3`.
+    /// Consumes the map, moving its items into a new map that uses `alloc`.
+    ///
+    /// Items are moved rather than cloned, and the key tables are rebuilt in
+    /// the new allocator. The new map uses the same hasher as this one. This is
+    /// useful for moving a map between arenas, for example between processing
+    /// phases.
+    ///
+    /// Requires the `allocator-api2` feature to be enabled.
     ///
     /// # Examples
     ///
+    /// Using the [`bumpalo`](https://docs.rs/bumpalo) allocator:
+    ///
     /// ```
-    /// # #[cfg(feature = "default-hasher")] {
+    /// # #[cfg(feature = "allocator-api2")] {
     /// use iddqd::{TriHashItem, TriHashMap, tri_upcast};
+    /// use std::collections::hash_map::RandomState;
+    /// # use iddqd_test_utils::bumpalo;
     ///
     /// #[derive(Debug, PartialEq, Eq)]
-    /// struct Person {
+    /// struct Item {
     ///     id: u32,
-    ///     email: String,
-    ///     phone: String,
     ///     name: String,
+    ///     code: char,
     /// }
     ///
-    /// impl TriHashItem for Person {
+    /// impl TriHashItem for Item {
     ///     type K1<'a> = u32;
     ///     type K2<'a> = &'a str;
-    ///     type K3<'a> = &'a str;
+    ///     type K3<'a> = char;
     ///
     ///     fn key1(&self) -> Self::K1<'_> {
     ///         self.id
     ///     }
     ///     fn key2(&self) -> Self::K2<'_> {
-    ///         &self.email
+    ///         &self.name
     ///     }
     ///     fn key3(&self) -> Self::K3<'_> {
-    ///         &self.phone
+    ///         self.code
     ///     }
     ///     tri_upcast!();
     /// }
     ///
-    /// let mut map = TriHashMap::new();
-    /// map.insert_unique(Person {
-    ///     id: 1,
-    ///     email: "alice@example.com".to_string(),
-    ///     phone: "555-1234".to_string(),
-    ///     name: "Alice".to_string(),
-    /// })
-    /// .unwrap();
-    ///
-    /// if let Some(mut person) = map.get3_mut("555-1234") {
-    ///     person.name = "Alice Updated".to_string();
-    /// }
+    /// let phase1 = bumpalo::Bump::new();
+    /// let mut map: TriHashMap<Item, _, &bumpalo::Bump> =
+    ///     TriHashMap::with_hasher_in(RandomState::new(), &phase1);
+    /// map.insert_unique(Item { id: 1, name: "foo".to_string(), code: 'f' })
+    ///     .unwrap();
     ///
-    /// assert_eq!(map.get3("555-1234").unwrap().name, "Alice Updated");
+    /// // Move the map into a second arena, so the first one can be reset.
+    /// let phase2 = bumpalo::Bump::new();
+    /// let map = map.into_alloc(&phase2);
+    /// drop(phase1);
+    /// assert_eq!(map.get1(&1).unwrap().name, "foo");
+    /// assert_eq!(map.get3(&'f').unwrap().id, 1);
     /// # }
     /// ```
-    pub fn get3_mut<'a, Q>(&'a mut self, key3: &Q) -> Option<RefMut<'a, T, S>>
-    where
-        Q: Hash + Equivalent<T::K3<'a>> + ?Sized,
-    {
-        let (dormant_map, index) = {
-            let (map, dormant_map) = DormantMutRef::new(self);
-            let index = map.find3_index(key3)?;
-            (dormant_map, index)
-        };
+    pub fn into_alloc<A2: Clone + Allocator>(
+        self,
+        alloc: A2,
+    ) -> TriHashMap<T, S, A2> {
+        let mut map = TriHashMap::with_capacity_and_hasher_in(
+            self.len(),
+            self.tables.state,
+            alloc,
+        );
+        for item in self.items.into_values() {
+            // Keys in this map are already unique, and capacity has been
+            // reserved up front, so there's no need to check for duplicates.
+            let hashes = map.tables.make_hashes(&item);
+            map.insert_unique_with_prepared_hashes(item, hashes);
+        }
 
-        // SAFETY: `map` is not used after this point.
-        let awakened_map = unsafe { dormant_map.awaken() };
-        let item = &mut awakened_map.items[index];
-        let state = awakened_map.tables.state.clone();
-        let hashes = awakened_map.tables.make_hashes(&item);
-        Some(RefMut::new(state, hashes, item))
+        map
     }
 
-    /// Removes an item from the map by its `key3`.
+    /// Consumes the map, transforming each item with `f` and collecting the
+    /// results into a new map.
+    ///
+    /// The new map uses the same hasher and allocator as this one. Keys may
+    /// change under `f`, as long as the transformed items remain unique. If a
+    /// transformed item conflicts with one produced earlier, this returns a
+    /// [`DuplicateItem`] error containing the new item and the conflicting
+    /// items, and drops the rest of the map.
+    ///
+    /// To overwrite duplicates instead, use `into_iter().map(f).collect()`.
     ///
     /// # Examples
     ///
@@ -2616,19 +4445,17 @@ impl<T: TriHashItem, S: Clone + BuildHasher, A: Allocator> TriHashMap<T, S, A> {
     /// # #[cfg(feature = "default-hasher")] {
     /// use iddqd::{TriHashItem, TriHashMap, tri_upcast};
     ///
-    /// #[derive(Debug, PartialEq, Eq)]
+    /// #[derive(Debug)]
     /// struct Person {
     ///     id: u32,
     ///     email: String,
     ///     phone: String,
-    ///     name: String,
     /// }
     ///
     /// impl TriHashItem for Person {
     ///     type K1<'a> = u32;
     ///     type K2<'a> = &'a str;
     ///     type K3<'a> = &'a str;
-    ///
     ///     fn key1(&self) -> Self::K1<'_> {
     ///         self.id
     ///     }
@@ -2644,32 +4471,62 @@ impl<T: TriHashItem, S: Clone + BuildHasher, A: Allocator> TriHashMap<T, S, A> {
     /// let mut map = TriHashMap::new();
     /// map.insert_unique(Person {
     ///     id: 1,
-    ///     email: "alice@example.com".to_string(),
+    ///     email: "Alice@Example.com".to_string(),
     ///     phone: "555-1234".to_string(),
-    ///     name: "Alice".to_string(),
+    /// })
+    /// .unwrap();
+    /// map.insert_unique(Person {
+    ///     id: 2,
+    ///     email: "bob@example.com".to_string(),
+    ///     phone: "555-5678".to_string(),
     /// })
     /// .unwrap();
     ///
-    /// let removed = map.remove3("555-1234");
-    /// assert!(removed.is_some());
-    /// assert_eq!(removed.unwrap().name, "Alice");
-    /// assert!(map.is_empty());
+    /// // Normalize emails to lowercase.
+    /// let map = map
+    ///     .map(|p| Person { email: p.email.to_lowercase(), ..p })
+    ///     .expect("normalized emails are unique");
+    /// assert_eq!(map.get2("alice@example.com").unwrap().id, 1);
+    ///
+    /// // Mapping every item to the same phone number produces an error.
+    /// let err =
+    ///     map.map(|p| Person { phone: "555-0000".to_string(), ..p }).unwrap_err();
+    /// assert_eq!(err.duplicates().len(), 1);
     /// # }
     /// ```
-    pub fn remove3<'a, Q>(&'a mut self, key3: &Q) -> Option<T>
+    pub fn map<U, F>(
+        self,
+        mut f: F,
+    ) -> Result<TriHashMap<U, S, A>, DuplicateItem<U>>
     where
-        Q: Hash + Equivalent<T::K3<'a>> + ?Sized,
+        U: TriHashItem,
+        F: FnMut(T) -> U,
+        A: Clone,
     {
-        let (dormant_map, remove_index) = {
-            let (map, dormant_map) = DormantMutRef::new(self);
-            let remove_index = map.find3_index(key3)?;
-            (dormant_map, remove_index)
-        };
-
-        // SAFETY: `map` is not used after this point.
-        let awakened_map = unsafe { dormant_map.awaken() };
+        let alloc = self.items.allocator().clone();
+        let mut map = TriHashMap::with_capacity_and_hasher_in(
+            self.len(),
+            self.tables.state,
+            alloc,
+        );
+        for item in self.items.into_values() {
+            if let Err((value, indexes)) =
+                map.insert_unique_or_dup_indexes(f(item))
+            {
+                // As in `from_iter_unique`, removal produces owned duplicates
+                // without requiring U: Clone.
+                let duplicates = indexes
+                    .iter()
+                    .map(|ix| {
+                        map.remove_by_index(*ix)
+                            .expect("duplicate index is present")
+                    })
+                    .collect();
+                return Err(DuplicateItem::__internal_new(value, duplicates));
+            }
+        }
 
-        awakened_map.remove_by_index(remove_index)
+        Ok(map)
     }
 
     /// Retains only the elements specified by the predicate.
@@ -2681,10 +4538,8 @@ impl<T: TriHashItem, S: Clone + BuildHasher, A: Allocator> TriHashMap<T, S, A> {
     /// enforcing that the three keys (`K1`, `K2`, `K3`) remain unchanged. If
     /// a key is modified during iteration, the method will panic.
     ///
-    /// # Performance considerations
-    ///
-    /// This method may leave the internal storage fragmented. If you need
-    /// compact storage afterward, call `make_compact()`.
+    /// The remaining items are compacted afterwards, so that no holes are left
+    /// behind by the removed ones. The map's capacity is unchanged.
     ///
     /// # Examples
     ///
@@ -2856,9 +4711,109 @@ impl<T: TriHashItem, S: Clone + BuildHasher, A: Allocator> TriHashMap<T, S, A> {
             }
         });
 
+        // Fill the holes left by removed items, so that the map stays
+        // compact.
+        //
+        // SAFETY: The closure above, which held the only reborrows of
+        // `dormant_items`, has returned.
+        let items = unsafe { dormant_items.awaken() };
+        let remap = items.compact();
+        if !remap.is_identity() {
+            self.tables.k1_to_item.remap_indexes(&remap);
+            self.tables.k2_to_item.remap_indexes(&remap);
+            self.tables.k3_to_item.remap_indexes(&remap);
+        }
+
         // Anything in `removed_item` is implicitly dropped now.
     }
 
+    /// Removes all items for which `f` returns true, returning them.
+    ///
+    /// This is the inverse of [`Self::retain`], except that the predicate takes
+    /// `&T` and the removed items are returned rather than dropped. All three
+    /// key tables are updated as items are removed. The items are visited, and
+    /// returned, in an arbitrary order.
+    ///
+    /// If `f` panics, no items are removed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "default-hasher")] {
+    /// use iddqd::{TriHashItem, TriHashMap, tri_upcast};
+    ///
+    /// #[derive(Debug, PartialEq, Eq)]
+    /// struct Item {
+    ///     id: u32,
+    ///     name: String,
+    ///     code: char,
+    ///     group: u32,
+    /// }
+    ///
+    /// impl TriHashItem for Item {
+    ///     type K1<'a> = u32;
+    ///     type K2<'a> = &'a str;
+    ///     type K3<'a> = char;
+    ///
+    ///     fn key1(&self) -> Self::K1<'_> {
+    ///         self.id
+    ///     }
+    ///     fn key2(&self) -> Self::K2<'_> {
+    ///         &self.name
+    ///     }
+    ///     fn key3(&self) -> Self::K3<'_> {
+    ///         self.code
+    ///     }
+    ///     tri_upcast!();
+    /// }
+    ///
+    /// let mut map = TriHashMap::new();
+    /// map.insert_unique(Item {
+    ///     id: 1,
+    ///     name: "foo".to_string(),
+    ///     code: 'f',
+    ///     group: 1,
+    /// })
+    /// .unwrap();
+    /// map.insert_unique(Item {
+    ///     id: 2,
+    ///     name: "bar".to_string(),
+    ///     code: 'b',
+    ///     group: 2,
+    /// })
+    /// .unwrap();
+    ///
+    /// // Invalidate everything in group 1.
+    /// let removed = map.remove_where(|item| item.group == 1);
+    /// assert_eq!(removed.len(), 1);
+    /// assert_eq!(removed[0].id, 1);
+    ///
+    /// // All three key tables have been updated.
+    /// assert_eq!(map.len(), 1);
+    /// assert!(map.get1(&1).is_none());
+    /// assert!(map.get2("foo").is_none());
+    /// assert!(map.get3(&'f').is_none());
+    /// # }
+    /// ```
+    pub fn remove_where<F>(&mut self, mut f: F) -> Vec<T>
+    where
+        F: FnMut(&T) -> bool,
+    {
+        // Find all the matching indexes first, so that a panic in `f` leaves
+        // the map untouched.
+        let indexes: Vec<ItemIndex> = self
+            .items
+            .iter()
+            .filter_map(|(index, item)| f(item).then_some(index))
+            .collect();
+        indexes
+            .into_iter()
+            .map(|index| {
+                self.remove_by_index(index).expect("index is present in items")
+            })
+            .collect()
+    }
+
     fn find1<'a, Q>(&'a self, k: &Q) -> Option<&'a T>
     where
         Q: Hash + Equivalent<T::K1<'a>> + ?Sized,
@@ -2922,7 +4877,7 @@ impl<T: TriHashItem, S: Clone + BuildHasher, A: Allocator> TriHashMap<T, S, A> {
             |index| self.prepare_duplicate(index),
         );
 
-        PreparedInsertOverwrite { duplicates, hashes }
+        PreparedInsertOverwrite { index1, index2, index3, duplicates, hashes }
     }
 
     fn prepare_duplicate(&self, index: ItemIndex) -> PreparedDuplicate {
@@ -3104,6 +5059,176 @@ impl<T: TriHashItem, S: Clone + BuildHasher, A: Allocator> TriHashMap<T, S, A> {
                 .expect("items[duplicate.index] was Occupied above"),
         )
     }
+
+    fn prepare_entry_index_removal(
+        &self,
+        indexes: EntryIndexes,
+    ) -> Vec<PreparedDuplicate> {
+        match indexes {
+            EntryIndexes::Unique(index) => {
+                PreparedDuplicate::from_indexes([Some(index)], |index| {
+                    self.prepare_duplicate(index)
+                })
+            }
+            EntryIndexes::NonUnique { index1, index2, index3 } => {
+                PreparedDuplicate::from_indexes(
+                    [index1, index2, index3],
+                    |index| self.prepare_duplicate(index),
+                )
+            }
+        }
+    }
+
+    pub(super) fn get_by_entry_index(
+        &self,
+        indexes: EntryIndexes,
+    ) -> OccupiedEntryRef<'_, T> {
+        match indexes {
+            EntryIndexes::Unique(index) => OccupiedEntryRef::Unique(
+                self.items.get(index).expect("index is valid"),
+            ),
+            EntryIndexes::NonUnique { index1, index2, index3 } => {
+                let by_key1 = index1
+                    .map(|k| self.items.get(k).expect("key1 index is valid"));
+                let by_key2 = index2
+                    .map(|k| self.items.get(k).expect("key2 index is valid"));
+                let by_key3 = index3
+                    .map(|k| self.items.get(k).expect("key3 index is valid"));
+                OccupiedEntryRef::NonUnique { by_key1, by_key2, by_key3 }
+            }
+        }
+    }
+
+    pub(super) fn get_by_entry_index_mut(
+        &mut self,
+        indexes: EntryIndexes,
+    ) -> OccupiedEntryMut<'_, T, S> {
+        let state = self.tables.state.clone();
+        // get_disjoint_mut returns None for missing keys, and for keys that
+        // point to the same item as an earlier key.
+        let [item1, item2, item3] =
+            self.items.get_disjoint_mut(indexes.disjoint_keys());
+        let tables = &self.tables;
+
+        if indexes.is_unique() {
+            let item = item1.expect("index is valid");
+            let hashes = tables.make_hashes::<T>(item);
+            OccupiedEntryMut::Unique(RefMut::new(state, hashes, item))
+        } else {
+            OccupiedEntryMut::NonUnique {
+                by_key1: item1.map(|item| {
+                    let hashes = tables.make_hashes::<T>(item);
+                    RefMut::new(state.clone(), hashes, item)
+                }),
+                by_key2: item2.map(|item| {
+                    let hashes = tables.make_hashes::<T>(item);
+                    RefMut::new(state.clone(), hashes, item)
+                }),
+                by_key3: item3.map(|item| {
+                    let hashes = tables.make_hashes::<T>(item);
+                    RefMut::new(state.clone(), hashes, item)
+                }),
+            }
+        }
+    }
+
+    pub(super) fn get_by_index_mut(
+        &mut self,
+        index: ItemIndex,
+    ) -> Option<RefMut<'_, T, S>> {
+        let item = self.items.get_mut(index)?;
+        let state = self.tables.state.clone();
+        let hashes = self.tables.make_hashes::<T>(item);
+        Some(RefMut::new(state, hashes, item))
+    }
+
+    pub(super) fn insert_unique_impl(
+        &mut self,
+        value: T,
+    ) -> Result<ItemIndex, DuplicateItem<T, &T>> {
+        match self.insert_unique_or_dup_indexes(value) {
+            Ok(index) => Ok(index),
+            Err((value, duplicates)) => Err(DuplicateItem::__internal_new(
+                value,
+                duplicates.iter().map(|ix| &self.items[*ix]).collect(),
+            )),
+        }
+    }
+
+    pub(super) fn remove_by_entry_index(
+        &mut self,
+        indexes: EntryIndexes,
+    ) -> Vec<T> {
+        let prepared = self.prepare_entry_index_removal(indexes);
+        let mut old_items = Vec::with_capacity(prepared.len());
+
+        for duplicate in prepared {
+            old_items.push(
+                self.remove_duplicate(duplicate)
+                    .expect("prepared duplicate index was present"),
+            );
+        }
+
+        old_items
+    }
+
+    pub(super) fn replace_at_indexes(
+        &mut self,
+        indexes: EntryIndexes,
+        value: T,
+    ) -> (ItemIndex, Vec<T>) {
+        match indexes {
+            EntryIndexes::Unique(index) => {
+                {
+                    let old_item = &self.items[index];
+                    if old_item.key1() != value.key1() {
+                        panic!("key1 mismatch");
+                    }
+                    if old_item.key2() != value.key2() {
+                        panic!("key2 mismatch");
+                    }
+                    if old_item.key3() != value.key3() {
+                        panic!("key3 mismatch");
+                    }
+                }
+
+                let mut old_items = Vec::with_capacity(1);
+                let old_item = self.items.replace(index, value);
+                old_items.push(old_item);
+
+                (index, old_items)
+            }
+            EntryIndexes::NonUnique { index1, index2, index3 } => {
+                let prepared = self.prepare_insert_overwrite(&value);
+
+                if prepared.index1 != index1 {
+                    panic!("key1 mismatch");
+                }
+                if prepared.index2 != index2 {
+                    panic!("key2 mismatch");
+                }
+                if prepared.index3 != index3 {
+                    panic!("key3 mismatch");
+                }
+
+                let mut old_items =
+                    Vec::with_capacity(prepared.duplicate_count());
+
+                self.try_reserve_insert_overwrite_commit(
+                    prepared.needs_new_item_slot(),
+                )
+                .expect("reserved item slot");
+
+                let next_index = self.commit_insert_overwrite(
+                    value,
+                    prepared,
+                    &mut old_items,
+                );
+
+                (next_index, old_items)
+            }
+        }
+    }
 }
 
 impl<'a, T, S, A: Allocator> fmt::Debug for TriHashMap<T, S, A>
@@ -3230,39 +5355,29 @@ impl<T: TriHashItem + Eq, S: Clone + BuildHasher, A: Allocator> Eq
 {
 }
 
-/// The `Extend` implementation overwrites duplicates. In the future, there will
-/// also be an `extend_unique` method that will return an error.
-impl<T: TriHashItem, S: Clone + BuildHasher, A: Allocator> Extend<T>
+/// The hash of a `TriHashMap` doesn't depend on the order its items are stored
+/// in, so it is consistent with the `PartialEq` implementation.
+///
+/// Each item is hashed with a fixed-seed hasher, independent of the map's own
+/// hasher, and the results are combined with a commutative sum. As a result,
+/// equal maps hash the same even if they use hashers with different seeds.
+impl<T: TriHashItem + Hash, S: Clone + BuildHasher, A: Allocator> Hash
     for TriHashMap<T, S, A>
 {
-    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
-        // Keys may already be present in the map, or multiple times in the
-        // iterator. Reserve the entire hint lower bound if the map is empty.
-        // Otherwise reserve half the hint (rounded up), so the map will only
-        // resize twice in the worst case.
-        let iter = iter.into_iter();
-        let reserve = if self.is_empty() {
-            iter.size_hint().0
-        } else {
-            iter.size_hint().0.div_ceil(2)
-        };
-        self.reserve(reserve);
-        for item in iter {
-            self.insert_overwrite(item);
-        }
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.items.hash_unordered(state);
     }
 }
 
-fn detect_dup_or_insert<'a, A: Allocator>(
-    item: hash_table::Entry<'a, A>,
-    duplicates: &mut BTreeSet<ItemIndex>,
-) -> Option<hash_table::VacantEntry<'a, A>> {
-    match item {
-        hash_table::Entry::Vacant(slot) => Some(slot),
-        hash_table::Entry::Occupied(slot) => {
-            duplicates.insert(slot.get());
-            None
-        }
+/// The `Extend` implementation overwrites duplicates. To return an error on
+/// duplicates instead, use [`TriHashMap::extend_with`] with
+/// [`ExtendMode::Unique`].
+impl<T: TriHashItem, S: Clone + BuildHasher, A: Allocator> Extend<T>
+    for TriHashMap<T, S, A>
+{
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        // Overwrite mode never returns an error.
+        let _ = self.extend_with(iter, ExtendMode::Overwrite);
     }
 }
 
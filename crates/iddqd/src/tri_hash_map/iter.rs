@@ -35,6 +35,11 @@ impl<'a, T: TriHashItem> Iterator for Iter<'a, T> {
     fn next(&mut self) -> Option<Self::Item> {
         self.inner.next()
     }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
 }
 
 impl<T: TriHashItem> ExactSizeIterator for Iter<'_, T> {
@@ -46,6 +51,123 @@ impl<T: TriHashItem> ExactSizeIterator for Iter<'_, T> {
 
 impl<T: TriHashItem> FusedIterator for Iter<'_, T> {}
 
+/// An iterator over the first keys of a [`TriHashMap`].
+///
+/// Created by [`TriHashMap::key1s`].
+///
+/// Similar to [`HashMap`], the iteration order is arbitrary and not guaranteed
+/// to be stable.
+///
+/// [`TriHashMap`]: crate::TriHashMap
+/// [`TriHashMap::key1s`]: crate::TriHashMap::key1s
+/// [`HashMap`]: std::collections::HashMap
+#[derive(Clone, Debug, Default)]
+pub struct Key1s<'a, T: TriHashItem> {
+    inner: Iter<'a, T>,
+}
+
+impl<'a, T: TriHashItem> Key1s<'a, T> {
+    pub(super) fn new(inner: Iter<'a, T>) -> Self {
+        Self { inner }
+    }
+}
+
+impl<'a, T: TriHashItem> Iterator for Key1s<'a, T> {
+    type Item = T::K1<'a>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(T::key1)
+    }
+}
+
+impl<T: TriHashItem> ExactSizeIterator for Key1s<'_, T> {
+    #[inline]
+    fn len(&self) -> usize {
+        self.inner.len()
+    }
+}
+
+impl<T: TriHashItem> FusedIterator for Key1s<'_, T> {}
+
+/// An iterator over the second keys of a [`TriHashMap`].
+///
+/// Created by [`TriHashMap::key2s`].
+///
+/// Similar to [`HashMap`], the iteration order is arbitrary and not guaranteed
+/// to be stable.
+///
+/// [`TriHashMap`]: crate::TriHashMap
+/// [`TriHashMap::key2s`]: crate::TriHashMap::key2s
+/// [`HashMap`]: std::collections::HashMap
+#[derive(Clone, Debug, Default)]
+pub struct Key2s<'a, T: TriHashItem> {
+    inner: Iter<'a, T>,
+}
+
+impl<'a, T: TriHashItem> Key2s<'a, T> {
+    pub(super) fn new(inner: Iter<'a, T>) -> Self {
+        Self { inner }
+    }
+}
+
+impl<'a, T: TriHashItem> Iterator for Key2s<'a, T> {
+    type Item = T::K2<'a>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(T::key2)
+    }
+}
+
+impl<T: TriHashItem> ExactSizeIterator for Key2s<'_, T> {
+    #[inline]
+    fn len(&self) -> usize {
+        self.inner.len()
+    }
+}
+
+impl<T: TriHashItem> FusedIterator for Key2s<'_, T> {}
+
+/// An iterator over the third keys of a [`TriHashMap`].
+///
+/// Created by [`TriHashMap::key3s`].
+///
+/// Similar to [`HashMap`], the iteration order is arbitrary and not guaranteed
+/// to be stable.
+///
+/// [`TriHashMap`]: crate::TriHashMap
+/// [`TriHashMap::key3s`]: crate::TriHashMap::key3s
+/// [`HashMap`]: std::collections::HashMap
+#[derive(Clone, Debug, Default)]
+pub struct Key3s<'a, T: TriHashItem> {
+    inner: Iter<'a, T>,
+}
+
+impl<'a, T: TriHashItem> Key3s<'a, T> {
+    pub(super) fn new(inner: Iter<'a, T>) -> Self {
+        Self { inner }
+    }
+}
+
+impl<'a, T: TriHashItem> Iterator for Key3s<'a, T> {
+    type Item = T::K3<'a>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(T::key3)
+    }
+}
+
+impl<T: TriHashItem> ExactSizeIterator for Key3s<'_, T> {
+    #[inline]
+    fn len(&self) -> usize {
+        self.inner.len()
+    }
+}
+
+impl<T: TriHashItem> FusedIterator for Key3s<'_, T> {}
+
 /// An iterator over the elements of a [`TriHashMap`] by mutable reference.
 /// Created by [`TriHashMap::iter_mut`].
 ///
@@ -90,6 +212,11 @@ impl<'a, T: TriHashItem, S: Clone + BuildHasher, A: Allocator> Iterator
         let hashes = self.tables.make_hashes(next);
         Some(RefMut::new(self.tables.state.clone(), hashes, next))
     }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
 }
 
 impl<T: TriHashItem, S: Clone + BuildHasher, A: Allocator> ExactSizeIterator
@@ -133,6 +260,11 @@ impl<T: TriHashItem, A: Allocator> Iterator for IntoIter<T, A> {
     fn next(&mut self) -> Option<Self::Item> {
         self.inner.next()
     }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
 }
 
 impl<T: TriHashItem, A: Allocator> ExactSizeIterator for IntoIter<T, A> {
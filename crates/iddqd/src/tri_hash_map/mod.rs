@@ -2,13 +2,21 @@
 //!
 //! For more information, see [`TriHashMap`].
 
+#[cfg(feature = "arbitrary")]
+mod arbitrary_impls;
 #[cfg(feature = "daft")]
 mod daft_impls;
+mod entry;
+mod entry_indexes;
 pub(crate) mod imp;
 mod iter;
 #[cfg(feature = "proptest")]
 mod proptest_impls;
+#[cfg(feature = "rayon")]
+mod rayon_impls;
 mod ref_mut;
+#[cfg(feature = "schemars1")]
+mod schemars1_impls;
 #[cfg(feature = "schemars08")]
 mod schemars_impls;
 #[cfg(feature = "serde")]
@@ -18,8 +26,11 @@ pub(crate) mod trait_defs;
 
 #[cfg(feature = "daft")]
 pub use daft_impls::{ByK1, ByK2, ByK3, Diff, MapLeaf};
+pub use entry::{
+    Entry, OccupiedEntry, OccupiedEntryMut, OccupiedEntryRef, VacantEntry,
+};
 pub use imp::TriHashMap;
-pub use iter::{IntoIter, Iter, IterMut};
+pub use iter::{IntoIter, Iter, IterMut, Key1s, Key2s, Key3s};
 #[cfg(all(feature = "proptest", feature = "default-hasher"))]
 pub use proptest_impls::prop_strategy;
 #[cfg(feature = "proptest")]
@@ -27,6 +38,8 @@ pub use proptest_impls::{
     TriHashMapStrategy, TriHashMapValueTree, prop_strategy_with_hasher,
     prop_strategy_with_hasher_in,
 };
+#[cfg(feature = "rayon")]
+pub use rayon_impls::ParIter;
 pub use ref_mut::RefMut;
 #[cfg(feature = "serde")]
 pub use serde_impls::TriHashMapAsMap;
@@ -0,0 +1,83 @@
+//! Parallel iteration over [`TriHashMap`]s with [`rayon`].
+
+use super::{TriHashItem, TriHashMap};
+use crate::support::{alloc::Allocator, item_set::ParValues};
+use rayon::iter::{
+    IntoParallelIterator, ParallelIterator, plumbing::UnindexedConsumer,
+};
+
+/// A parallel iterator over the elements of an [`TriHashMap`] by shared
+/// reference.
+///
+/// Created by calling [`par_iter`] (or [`into_par_iter`] on a reference) with
+/// the `rayon` feature enabled.
+///
+/// Similar to [`TriHashMap::iter`], the iteration order is arbitrary.
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(feature = "default-hasher")] {
+/// use iddqd::{TriHashItem, TriHashMap, tri_upcast};
+/// use rayon::prelude::*;
+///
+/// struct Item {
+///     id: u32,
+///     name: String,
+///     value: u64,
+/// }
+///
+/// impl TriHashItem for Item {
+///     type K1<'a> = u32;
+///     type K2<'a> = &'a str;
+///     type K3<'a> = u64;
+///     fn key1(&self) -> Self::K1<'_> {
+///         self.id
+///     }
+///     fn key2(&self) -> Self::K2<'_> {
+///         &self.name
+///     }
+///     fn key3(&self) -> Self::K3<'_> {
+///         self.value
+///     }
+///     tri_upcast!();
+/// }
+///
+/// let map: TriHashMap<Item> = (0..100)
+///     .map(|id| Item { id, name: id.to_string(), value: u64::from(id) * 2 })
+///     .collect();
+/// let total: u64 = map.par_iter().map(|item| item.value).sum();
+/// assert_eq!(total, 9900);
+/// # }
+/// ```
+///
+/// [`par_iter`]: rayon::iter::IntoParallelRefIterator::par_iter
+/// [`into_par_iter`]: rayon::iter::IntoParallelIterator::into_par_iter
+#[derive(Clone, Debug)]
+pub struct ParIter<'a, T: TriHashItem> {
+    inner: ParValues<'a, T>,
+}
+
+impl<'a, T: TriHashItem + Sync> ParallelIterator for ParIter<'a, T> {
+    type Item = &'a T;
+
+    #[inline]
+    fn drive_unindexed<C>(self, consumer: C) -> C::Result
+    where
+        C: UnindexedConsumer<Self::Item>,
+    {
+        self.inner.drive_unindexed(consumer)
+    }
+}
+
+impl<'a, T: TriHashItem + Sync, S, A: Allocator> IntoParallelIterator
+    for &'a TriHashMap<T, S, A>
+{
+    type Iter = ParIter<'a, T>;
+    type Item = &'a T;
+
+    #[inline]
+    fn into_par_iter(self) -> Self::Iter {
+        ParIter { inner: self.items.par_values() }
+    }
+}
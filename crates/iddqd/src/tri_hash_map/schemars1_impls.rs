@@ -0,0 +1,61 @@
+//! Schemars 1.x implementations for TriHashMap.
+
+use crate::{
+    support::{
+        alloc::Allocator,
+        schemars1_utils::{create_map_schema, create_object_schema},
+    },
+    tri_hash_map::{
+        imp::TriHashMap, serde_impls::TriHashMapAsMap, trait_defs::TriHashItem,
+    },
+};
+use alloc::borrow::Cow;
+use schemars1::{JsonSchema, Schema, SchemaGenerator};
+
+impl<T, S, A> JsonSchema for TriHashMap<T, S, A>
+where
+    T: JsonSchema + TriHashItem,
+    A: Allocator,
+{
+    fn schema_name() -> Cow<'static, str> {
+        alloc::format!("TriHashMap_of_{}", T::schema_name()).into()
+    }
+
+    fn schema_id() -> Cow<'static, str> {
+        alloc::format!("iddqd::TriHashMap<{}>", T::schema_id()).into()
+    }
+
+    fn json_schema(generator: &mut SchemaGenerator) -> Schema {
+        create_map_schema::<T>("TriHashMap", "iddqd::TriHashMap", generator)
+    }
+
+    fn inline_schema() -> bool {
+        true
+    }
+}
+
+impl<T, S, A> JsonSchema for TriHashMapAsMap<T, S, A>
+where
+    T: JsonSchema + TriHashItem,
+    A: Allocator,
+{
+    fn schema_name() -> Cow<'static, str> {
+        alloc::format!("TriHashMapAsMap_of_{}", T::schema_name()).into()
+    }
+
+    fn schema_id() -> Cow<'static, str> {
+        alloc::format!("iddqd::TriHashMapAsMap<{}>", T::schema_id()).into()
+    }
+
+    fn json_schema(generator: &mut SchemaGenerator) -> Schema {
+        create_object_schema::<T>(
+            "TriHashMapAsMap",
+            "iddqd::TriHashMap",
+            generator,
+        )
+    }
+
+    fn inline_schema() -> bool {
+        true
+    }
+}
@@ -1,5 +1,6 @@
 use crate::{
     TriHashItem,
+    errors::TryReserveError,
     internal::{ValidateCompact, ValidationError},
     support::{
         alloc::{Allocator, Global, global_alloc},
@@ -89,3 +90,21 @@ impl<S: Clone + BuildHasher, A: Allocator> TriHashMapTables<S, A> {
         ]
     }
 }
+
+impl<S, A: Allocator> TriHashMapTables<S, A> {
+    pub(super) fn try_reserve(
+        &mut self,
+        additional: usize,
+    ) -> Result<(), TryReserveError> {
+        self.k1_to_item
+            .try_reserve(additional)
+            .map_err(TryReserveError::from_hashbrown)?;
+        self.k2_to_item
+            .try_reserve(additional)
+            .map_err(TryReserveError::from_hashbrown)?;
+        self.k3_to_item
+            .try_reserve(additional)
+            .map_err(TryReserveError::from_hashbrown)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,157 @@
+use arbitrary::{Arbitrary, Unstructured};
+use hegel::{TestCase, generators as gs};
+use iddqd::{
+    BiHashItem, BiHashMap, IdHashItem, IdHashMap, IdOrdItem, IdOrdMap,
+    TriHashItem, TriHashMap, bi_upcast, id_upcast,
+    internal::{ValidateChaos, ValidateCompact},
+    tri_upcast,
+};
+use iddqd_test_utils::test_item::{Alloc, HashBuilder};
+
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+struct ArbItem {
+    key1: u8,
+    key2: u8,
+    key3: u8,
+    value: u32,
+}
+
+impl<'a> Arbitrary<'a> for ArbItem {
+    fn arbitrary(u: &mut Unstructured<'a>) -> arbitrary::Result<Self> {
+        // Use small key spaces so that duplicates are common.
+        Ok(Self {
+            key1: u.int_in_range(0..=7)?,
+            key2: u.int_in_range(0..=7)?,
+            key3: u.int_in_range(0..=7)?,
+            value: u.arbitrary()?,
+        })
+    }
+}
+
+impl IdHashItem for ArbItem {
+    type Key<'a> = u8;
+
+    fn key(&self) -> Self::Key<'_> {
+        self.key1
+    }
+
+    id_upcast!();
+}
+
+impl IdOrdItem for ArbItem {
+    type Key<'a> = u8;
+
+    fn key(&self) -> Self::Key<'_> {
+        self.key1
+    }
+
+    id_upcast!();
+}
+
+impl BiHashItem for ArbItem {
+    type K1<'a> = u8;
+    type K2<'a> = u8;
+
+    fn key1(&self) -> Self::K1<'_> {
+        self.key1
+    }
+
+    fn key2(&self) -> Self::K2<'_> {
+        self.key2
+    }
+
+    bi_upcast!();
+}
+
+impl TriHashItem for ArbItem {
+    type K1<'a> = u8;
+    type K2<'a> = u8;
+    type K3<'a> = u8;
+
+    fn key1(&self) -> Self::K1<'_> {
+        self.key1
+    }
+
+    fn key2(&self) -> Self::K2<'_> {
+        self.key2
+    }
+
+    fn key3(&self) -> Self::K3<'_> {
+        self.key3
+    }
+
+    tri_upcast!();
+}
+
+/// Models `insert_overwrite` over `items`, where `conflicts` returns true if
+/// two items share a key.
+fn overwrite_model(
+    items: Vec<ArbItem>,
+    conflicts: impl Fn(&ArbItem, &ArbItem) -> bool,
+) -> Vec<ArbItem> {
+    let mut model: Vec<ArbItem> = Vec::new();
+    for item in items {
+        model.retain(|e| !conflicts(e, &item));
+        model.push(item);
+    }
+    model.sort();
+    model
+}
+
+fn sorted<'a>(items: impl IntoIterator<Item = &'a ArbItem>) -> Vec<ArbItem> {
+    let mut items: Vec<_> = items.into_iter().cloned().collect();
+    items.sort();
+    items
+}
+
+#[hegel::test(test_cases = 256)]
+fn arbitrary_collapses_duplicates(tc: TestCase) {
+    let bytes = tc.draw(gs::vecs(gs::integers::<u8>()).max_size(256));
+    let take_rest = tc.draw(gs::booleans());
+
+    // Maps are generated from the same sequence of items as a Vec.
+    fn generate<'a, M: Arbitrary<'a>>(bytes: &'a [u8], take_rest: bool) -> M {
+        let u = Unstructured::new(bytes);
+        if take_rest {
+            M::arbitrary_take_rest(u).unwrap()
+        } else {
+            M::arbitrary(&mut { u }).unwrap()
+        }
+    }
+    let items: Vec<ArbItem> = generate(&bytes, take_rest);
+
+    let map: IdHashMap<ArbItem, HashBuilder, Alloc> =
+        generate(&bytes, take_rest);
+    map.validate(ValidateCompact::NonCompact).expect("map is valid");
+    assert_eq!(
+        sorted(&map),
+        overwrite_model(items.clone(), |a, b| a.key1 == b.key1),
+    );
+
+    let map: IdOrdMap<ArbItem> = generate(&bytes, take_rest);
+    map.validate(ValidateCompact::NonCompact, ValidateChaos::No)
+        .expect("map is valid");
+    assert_eq!(
+        sorted(&map),
+        overwrite_model(items.clone(), |a, b| a.key1 == b.key1),
+    );
+
+    let map: BiHashMap<ArbItem, HashBuilder, Alloc> =
+        generate(&bytes, take_rest);
+    map.validate(ValidateCompact::NonCompact).expect("map is valid");
+    assert_eq!(
+        sorted(&map),
+        overwrite_model(items.clone(), |a, b| a.key1 == b.key1
+            || a.key2 == b.key2),
+    );
+
+    let map: TriHashMap<ArbItem, HashBuilder, Alloc> =
+        generate(&bytes, take_rest);
+    map.validate(ValidateCompact::NonCompact).expect("map is valid");
+    assert_eq!(
+        sorted(&map),
+        overwrite_model(items, |a, b| a.key1 == b.key1
+            || a.key2 == b.key2
+            || a.key3 == b.key3),
+    );
+}
@@ -4,11 +4,12 @@ use crate::hegel_support::{
 };
 use hegel::{TestCase, generators as gs};
 use iddqd::{
-    BiHashItem, BiHashMap, bi_hash_map, bi_upcast, internal::ValidateCompact,
+    BiHashItem, BiHashMap, BiMap, ExtendMode, ImmutableKey, bi_hash_map,
+    bi_upcast, internal::ValidateCompact,
 };
 use iddqd_test_utils::{
     borrowed_item::BorrowedItem,
-    eq_props::{assert_eq_props, assert_ne_props},
+    eq_props::{assert_eq_props, assert_hash_eq, assert_ne_props},
     naive_map::NaiveMap,
     test_item::{
         Alloc, HashBuilder, ItemMap, TestItem, TestKey1, TestKey2,
@@ -153,6 +154,13 @@ fn test_insert_unique() {
         assert_eq!(*e2, v4);
     }
 
+    // contains_either matches keys from different items, and predicts whether
+    // insert_unique would fail.
+    assert!(map.contains_either(&v1.key1(), &v4.key2()));
+    assert!(map.contains_either(&v2.key1(), &v2.key2()));
+    let v5 = TestItem::new(2, 'c', "x", "v");
+    assert!(!map.contains_either(&v5.key1(), &v5.key2()));
+
     // Check that the *unique methods work.
     assert!(map.contains_key_unique(&v4.key1(), &v4.key2()));
     assert_eq!(map.get_unique(&v4.key1(), &v4.key2()), Some(&v4));
@@ -203,6 +211,97 @@ fn test_extend() {
     assert_eq!(map.get1(&TestKey1::new(&4)).unwrap().value, "z");
 }
 
+#[test]
+fn test_extend_with_unique() {
+    let mut map = BiHashMap::<TestItem, HashBuilder, Alloc>::make_new();
+    let existing = TestItem::new(1, 'a', "x", "v");
+    map.insert_unique(existing.clone()).unwrap();
+
+    // Stops at the first duplicate: items before it are kept, items after it
+    // are dropped.
+    let conflicting = TestItem::new(1, 'c', "z", "conflict");
+    let items = vec![
+        TestItem::new(2, 'b', "y", "w"),
+        conflicting.clone(),
+        TestItem::new(3, 'd', "q", "dropped"),
+    ];
+    let error = map.extend_with(items, ExtendMode::Unique).unwrap_err();
+    assert_eq!(error.new_item(), &conflicting);
+    assert_eq!(error.duplicates(), &[&existing]);
+
+    assert_eq!(map.len(), 2);
+    assert_eq!(map.get1(&TestKey1::new(&2)).unwrap().value, "w");
+    assert_eq!(map.get1(&TestKey1::new(&3)), None);
+    assert_eq!(map.get1(&TestKey1::new(&1)).unwrap().value, "v");
+
+    // Overwrite mode matches `extend`.
+    map.extend_with(vec![conflicting], ExtendMode::Overwrite).unwrap();
+    assert_eq!(map.get1(&TestKey1::new(&1)).unwrap().value, "conflict");
+}
+
+#[test]
+fn bi_map_pairs() {
+    let mut map = BiMap::<u32, char, HashBuilder, Alloc>::make_new();
+    map.insert(1, 'a').unwrap();
+    map.insert(2, 'b').unwrap();
+
+    assert_eq!(map.get_by_left(&1), Some(&'a'));
+    assert_eq!(map.get_by_right(&'b'), Some(&2));
+    assert_eq!(map.get_by_left(&3), None);
+    assert_eq!(map.get_by_right(&'c'), None);
+
+    // A pair that conflicts on either side is rejected.
+    let error = map.insert(1, 'c').unwrap_err();
+    assert_eq!(error.duplicates(), [&(1, 'a')]);
+    let error = map.insert(3, 'b').unwrap_err();
+    assert_eq!(error.duplicates(), [&(2, 'b')]);
+
+    // The general BiHashMap API works on pairs too.
+    assert_eq!(map.remove2(&'a'), Some((1, 'a')));
+    map.insert(1, 'c').unwrap();
+    assert_eq!(map.get_by_right(&'c'), Some(&1));
+    map.validate(ValidateCompact::NonCompact).expect("map is valid");
+}
+
+#[test]
+fn map_transforms_items() {
+    let mut map = BiHashMap::<TestItem, HashBuilder, Alloc>::make_new();
+    for i in 0..4 {
+        map.insert_unique(TestItem::new(
+            i,
+            (b'a' + i) as char,
+            i.to_string(),
+            "v",
+        ))
+        .unwrap();
+    }
+
+    let mapped = map
+        .map(|mut item| {
+            item.value = format!("mapped-{}", item.key1);
+            item
+        })
+        .expect("keys are unchanged");
+    mapped.validate(ValidateCompact::Compact).expect("map is valid");
+    assert_eq!(mapped.len(), 4);
+    for i in 0..4 {
+        assert_eq!(
+            mapped.get1(&TestKey1::new(&i)).unwrap().value,
+            format!("mapped-{i}")
+        );
+    }
+
+    // Collapsing a key reports the first conflict.
+    let error = mapped
+        .map(|mut item| {
+            item.key2 = 'z';
+            item
+        })
+        .unwrap_err();
+    assert_eq!(error.duplicates().len(), 1);
+    assert_ne!(error.new_item().value, error.duplicates()[0].value);
+}
+
 // Example-based test for insert_overwrite.
 //
 // Can be used to write down examples seen from the property-based operation
@@ -258,6 +357,7 @@ impl BiHashMapMachine {
     fn check_valid(&mut self, change: CompactnessChange) {
         self.compactness = change.apply(self.compactness);
         self.map.validate(self.compactness).expect("map should be valid");
+        self.map.check_invariants().expect("map invariants should hold");
     }
 }
 
@@ -383,6 +483,45 @@ impl BiHashMapMachine {
         assert_eq!(map_res, naive_res);
     }
 
+    #[rule]
+    fn get_conflicts(&mut self, tc: TestCase) {
+        let (key1, key2) = draw_lookup_keys12(&tc, &self.naive);
+        let map_res =
+            self.map.get_conflicts(&TestKey1::new(&key1), &TestKey2::new(key2));
+        let naive_res = (self.naive.get1(key1), self.naive.get2(key2));
+
+        assert_eq!(map_res, naive_res);
+    }
+
+    #[rule]
+    fn get_pair_mut(&mut self, tc: TestCase) {
+        let (key1, key2) = draw_lookup_keys12(&tc, &self.naive);
+        let (by_key1, by_key2) =
+            self.map.get_pair_mut(&TestKey1::new(&key1), &TestKey2::new(key2));
+        let map_res =
+            (by_key1.map(|r| (*r).clone()), by_key2.map(|r| (*r).clone()));
+        let naive_res = match (self.naive.get1(key1), self.naive.get2(key2)) {
+            (Some(item1), Some(item2)) if item1 == item2 => (Some(item1), None),
+            res => res,
+        };
+
+        assert_eq!(map_res, (naive_res.0.cloned(), naive_res.1.cloned()));
+        self.check_valid(CompactnessChange::NoChange);
+    }
+
+    #[rule]
+    fn iter_sorted_by_key(&mut self, _: TestCase) {
+        let mut naive_items: Vec<_> = self.naive.iter().collect();
+
+        naive_items.sort_unstable_by_key(|item| item.key1);
+        let map_res: Vec<_> = self.map.iter_sorted_by_key1().collect();
+        assert_eq!(map_res, naive_items);
+
+        naive_items.sort_unstable_by_key(|item| item.key2);
+        let map_res: Vec<_> = self.map.iter_sorted_by_key2().collect();
+        assert_eq!(map_res, naive_items);
+    }
+
     #[rule]
     fn get_mut_unique(&mut self, tc: TestCase) {
         let (key1, key2) = draw_lookup_keys12(&tc, &self.naive);
@@ -439,7 +578,7 @@ impl BiHashMapMachine {
             let contains = item.value.contains(ch);
             if equals { contains } else { !contains }
         });
-        self.check_valid(CompactnessChange::NoLongerCompact);
+        self.check_valid(CompactnessChange::BecomesCompact);
     }
 
     #[rule]
@@ -457,7 +596,7 @@ impl BiHashMapMachine {
             let matches = item.key1 % modulo == remainder;
             if equals { matches } else { !matches }
         });
-        self.check_valid(CompactnessChange::NoLongerCompact);
+        self.check_valid(CompactnessChange::BecomesCompact);
     }
 
     #[rule]
@@ -559,6 +698,35 @@ fn proptest_permutation_eq(tc: TestCase) {
             .unwrap();
     assert_eq_props(&map1, &map3);
     assert_eq_props(&map3, &map4);
+    assert_hash_eq(&map1, &map2);
+    assert_hash_eq(&map3, &map4);
+}
+
+#[hegel::test(test_cases = 64)]
+fn proptest_remove_where(tc: TestCase) {
+    let set = draw_fill_batch(&tc);
+    let modulus = tc.draw(gs::integers::<u8>().min_value(1).max_value(4));
+    let mut map = BiHashMap::<TestItem, HashBuilder, Alloc>::from_iter_unique(
+        set.clone(),
+    )
+    .unwrap();
+
+    let mut removed = map.remove_where(|item| item.key1 % modulus == 0);
+    removed.sort();
+    let (mut expected_removed, mut expected_kept): (Vec<_>, Vec<_>) =
+        set.into_iter().partition(|item| item.key1 % modulus == 0);
+    expected_removed.sort();
+    expected_kept.sort();
+
+    assert_eq!(removed, expected_removed);
+    let mut kept: Vec<_> = map.iter().cloned().collect();
+    kept.sort();
+    assert_eq!(kept, expected_kept);
+    for item in &removed {
+        assert!(map.get1(&item.key1()).is_none());
+        assert!(map.get2(&item.key2()).is_none());
+    }
+    map.validate(ValidateCompact::NonCompact).expect("map is valid");
 }
 
 // Test various conditions for non-equality.
@@ -870,6 +1038,165 @@ fn entry_nonunique_writes_through_both_keys() {
     assert_eq!(map.get1(&5).unwrap().payload, 2222);
 }
 
+#[test]
+fn keys_and_values() {
+    let mut map = BiHashMap::<TestItem, HashBuilder, Alloc>::make_new();
+    map.insert_unique(TestItem::new(1, 'a', "x", "v1")).unwrap();
+    map.insert_unique(TestItem::new(2, 'b', "y", "v2")).unwrap();
+    map.insert_unique(TestItem::new(3, 'c', "z", "v3")).unwrap();
+
+    assert_eq!(map.key1s().len(), 3);
+    assert!(map.key1s().eq(map.iter().map(|item| item.key1())));
+    assert!(map.key2s().eq(map.iter().map(|item| item.key2())));
+    assert!(map.values().eq(map.iter()));
+
+    for mut item in map.values_mut() {
+        item.value.push_str("-updated");
+    }
+    for item in map.values() {
+        assert!(item.value.ends_with("-updated"), "{item:?} was updated");
+    }
+}
+
+#[test]
+fn iter_exact_size_and_fused() {
+    let mut map = BiHashMap::<TestItem, HashBuilder, Alloc>::make_new();
+    map.insert_unique(TestItem::new(1, 'a', "x", "v1")).unwrap();
+    map.insert_unique(TestItem::new(2, 'b', "y", "v2")).unwrap();
+    map.insert_unique(TestItem::new(3, 'c', "z", "v3")).unwrap();
+    map.insert_unique(TestItem::new(4, 'd', "w", "v4")).unwrap();
+    // Leave a hole in the underlying storage, which must not be counted.
+    map.remove1(&TestKey1::new(&2)).unwrap();
+
+    let mut iter = map.iter();
+    assert_eq!(iter.len(), 3);
+    assert_eq!(iter.size_hint(), (3, Some(3)));
+    iter.next().unwrap();
+    assert_eq!(iter.len(), 2);
+    assert_eq!(iter.by_ref().count(), 2);
+    assert_eq!(iter.len(), 0);
+    assert!(iter.next().is_none());
+    assert!(iter.next().is_none());
+
+    let mut iter_mut = map.iter_mut().fuse();
+    assert_eq!(iter_mut.size_hint(), (3, Some(3)));
+    assert_eq!(iter_mut.by_ref().count(), 3);
+    assert!(iter_mut.next().is_none());
+    assert!(iter_mut.next().is_none());
+
+    let mut into_iter = IntoIterator::into_iter(map).fuse();
+    assert_eq!(into_iter.len(), 3);
+    assert_eq!(into_iter.by_ref().map(|item| item.key1).sum::<u8>(), 8);
+    assert_eq!(into_iter.len(), 0);
+    assert!(into_iter.next().is_none());
+    assert!(into_iter.next().is_none());
+}
+
+#[test]
+fn get_mut_plain() {
+    #[derive(Clone, Debug)]
+    struct Item {
+        id: u32,
+        name: &'static str,
+        value: u32,
+    }
+
+    impl BiHashItem for Item {
+        type K1<'a> = u32;
+        type K2<'a> = &'a str;
+        fn key1(&self) -> Self::K1<'_> {
+            self.id
+        }
+        fn key2(&self) -> Self::K2<'_> {
+            self.name
+        }
+        bi_upcast!();
+    }
+
+    impl ImmutableKey for Item {}
+
+    let mut map = BiHashMap::<Item, HashBuilder, Alloc>::make_new();
+    map.insert_unique(Item { id: 1, name: "foo", value: 0 }).unwrap();
+
+    map.get1_mut_plain(&1).unwrap().value += 1;
+    map.get2_mut_plain("foo").unwrap().value += 10;
+    assert_eq!(map.get1(&1).unwrap().value, 11);
+
+    assert!(map.get1_mut_plain(&2).is_none());
+    assert!(map.get2_mut_plain("bar").is_none());
+    map.validate(ValidateCompact::Compact).expect("map is valid");
+}
+
+#[test]
+fn entry_ref_borrowed_keys() {
+    #[derive(Clone, Debug, PartialEq, Eq)]
+    struct NamedItem {
+        first: String,
+        last: String,
+        value: u32,
+    }
+
+    impl BiHashItem for NamedItem {
+        type K1<'a> = &'a str;
+        type K2<'a> = &'a str;
+        fn key1(&self) -> Self::K1<'_> {
+            &self.first
+        }
+        fn key2(&self) -> Self::K2<'_> {
+            &self.last
+        }
+        bi_upcast!();
+    }
+
+    fn item(first: &str, last: &str, value: u32) -> NamedItem {
+        NamedItem { first: first.to_owned(), last: last.to_owned(), value }
+    }
+
+    let mut map = BiHashMap::<NamedItem, HashBuilder, Alloc>::make_new();
+    map.insert_unique(item("a", "b", 1)).unwrap();
+    map.insert_unique(item("c", "d", 2)).unwrap();
+
+    // The queries are dropped before the entries are used.
+    let entry = {
+        let (first, last) = (String::from("a"), String::from("b"));
+        map.entry_ref(first.as_str(), last.as_str())
+    };
+    let bi_hash_map::Entry::Occupied(mut entry) = entry else {
+        panic!("expected OccupiedEntry");
+    };
+    assert!(entry.is_unique());
+    entry.get_mut().as_unique().unwrap().value = 10;
+    assert_eq!(map.get1("a").unwrap().value, 10);
+
+    // Keys matching two different items.
+    let bi_hash_map::Entry::Occupied(entry) = map.entry_ref("a", "d") else {
+        panic!("expected OccupiedEntry");
+    };
+    assert!(entry.is_non_unique());
+    assert_eq!(entry.get().by_key1(), Some(&item("a", "b", 10)));
+    assert_eq!(entry.get().by_key2(), Some(&item("c", "d", 2)));
+
+    // Only one key matches.
+    let bi_hash_map::Entry::Occupied(entry) = map.entry_ref("x", "b") else {
+        panic!("expected OccupiedEntry");
+    };
+    assert!(entry.is_non_unique());
+    assert_eq!(entry.get().by_key1(), None);
+    assert_eq!(entry.get().by_key2(), Some(&item("a", "b", 10)));
+
+    // Neither key matches: the entry hashes must agree with the inserted item.
+    let entry = {
+        let (first, last) = (String::from("e"), String::from("f"));
+        map.entry_ref(first.as_str(), last.as_str())
+    };
+    let bi_hash_map::Entry::Vacant(entry) = entry else {
+        panic!("expected VacantEntry");
+    };
+    entry.insert(item("e", "f", 3));
+    assert_eq!(map.get2("f").unwrap().value, 3);
+    map.validate(ValidateCompact::Compact).expect("map is valid");
+}
+
 #[test]
 #[should_panic = "key1 hashes do not match"]
 fn insert_panics_for_non_matching_key1() {
@@ -1073,6 +1400,7 @@ fn test_retain_none() {
 
     assert_eq!(map.len(), 0);
     assert!(map.is_empty());
+    map.validate(ValidateCompact::Compact).expect("map should be valid");
 }
 
 #[test]
@@ -1173,9 +1501,12 @@ fn test_clear_makes_compact() {
     map.validate(ValidateCompact::NonCompact)
         .expect("map should be valid but non-compact");
 
-    // Clear should make it compact again
+    // Clear should make it compact again, keeping its capacity.
+    let capacity = map.capacity();
     map.clear();
     assert!(map.is_empty());
+    assert_eq!(map.len(), 0);
+    assert_eq!(map.capacity(), capacity);
     map.validate(ValidateCompact::Compact)
         .expect("cleared map should be compact");
 }
@@ -0,0 +1,278 @@
+use hegel::{TestCase, generators as gs};
+use iddqd::{
+    BiOrdItem, BiOrdMap, ExtendMode, bi_upcast, internal::ValidateCompact,
+};
+use iddqd_test_utils::{eq_props::assert_hash_eq, test_item::HashBuilder};
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+struct OrdItem {
+    key1: u8,
+    key2: String,
+    value: u32,
+}
+
+impl OrdItem {
+    fn new(key1: u8, key2: &str, value: u32) -> Self {
+        Self { key1, key2: key2.to_owned(), value }
+    }
+}
+
+impl BiOrdItem for OrdItem {
+    type K1<'a> = u8;
+    type K2<'a> = &'a str;
+
+    fn key1(&self) -> Self::K1<'_> {
+        self.key1
+    }
+
+    fn key2(&self) -> Self::K2<'_> {
+        &self.key2
+    }
+
+    bi_upcast!();
+}
+
+fn make_map() -> BiOrdMap<OrdItem, HashBuilder> {
+    BiOrdMap::with_hasher(HashBuilder::default())
+}
+
+#[test]
+fn debug_impls() {
+    let mut map = make_map();
+    map.insert_unique(OrdItem::new(20, "b", 1)).unwrap();
+    map.insert_unique(OrdItem::new(1, "a", 0)).unwrap();
+
+    assert_eq!(
+        format!("{map:?}"),
+        // Iteration is in key1 order.
+        "{1: OrdItem { key1: 1, key2: \"a\", value: 0 }, \
+          20: OrdItem { key1: 20, key2: \"b\", value: 1 }}",
+    );
+}
+
+#[test]
+fn test_insert_unique() {
+    let mut map = make_map();
+    map.insert_unique(OrdItem::new(1, "a", 0)).unwrap();
+    map.insert_unique(OrdItem::new(2, "b", 0)).unwrap();
+
+    let error = map.insert_unique(OrdItem::new(1, "c", 0)).unwrap_err();
+    assert_eq!(error.duplicates(), vec![&OrdItem::new(1, "a", 0)]);
+
+    let error = map.insert_unique(OrdItem::new(3, "b", 0)).unwrap_err();
+    assert_eq!(error.duplicates(), vec![&OrdItem::new(2, "b", 0)]);
+
+    // A conflict with two different items reports both.
+    let error = map.insert_unique(OrdItem::new(1, "b", 0)).unwrap_err();
+    assert_eq!(
+        error.duplicates(),
+        vec![&OrdItem::new(1, "a", 0), &OrdItem::new(2, "b", 0)],
+    );
+
+    assert_eq!(map.len(), 2);
+    map.validate(ValidateCompact::Compact).expect("map is valid");
+}
+
+#[test]
+fn sorted_iter_and_range() {
+    let mut map = make_map();
+    for (key1, key2) in [(5, "e"), (1, "a"), (9, "i"), (3, "c")] {
+        map.insert_unique(OrdItem::new(key1, key2, 0)).unwrap();
+    }
+
+    let keys: Vec<_> = map.iter().map(|item| item.key1).collect();
+    assert_eq!(keys, [1, 3, 5, 9]);
+
+    let keys: Vec<_> = map.range(2..9).map(|item| item.key1).collect();
+    assert_eq!(keys, [3, 5]);
+    let keys: Vec<_> = map.range(..=5).rev().map(|item| item.key1).collect();
+    assert_eq!(keys, [5, 3, 1]);
+    #[expect(clippy::reversed_empty_ranges)]
+    let empty = map.range(9..2).count();
+    assert_eq!(empty, 0);
+}
+
+#[test]
+fn remove_keeps_tables_consistent() {
+    let mut map = make_map();
+    map.insert_unique(OrdItem::new(1, "a", 0)).unwrap();
+    map.insert_unique(OrdItem::new(2, "b", 0)).unwrap();
+
+    assert_eq!(map.remove1(&3), None);
+    assert_eq!(map.remove1(&1), Some(OrdItem::new(1, "a", 0)));
+    assert!(!map.contains_key2("a"));
+    assert_eq!(map.remove2("b"), Some(OrdItem::new(2, "b", 0)));
+    assert!(!map.contains_key1(&2));
+    assert!(map.is_empty());
+    map.validate(ValidateCompact::NonCompact).expect("map is valid");
+
+    // Freed slots are reused.
+    map.insert_unique(OrdItem::new(1, "b", 0)).unwrap();
+    assert_eq!(map.get2("b"), Some(&OrdItem::new(1, "b", 0)));
+    map.validate(ValidateCompact::NonCompact).expect("map is valid");
+}
+
+#[test]
+fn insertion_order_eq() {
+    let mut map1 = make_map();
+    let mut map2 = make_map();
+    map1.insert_unique(OrdItem::new(1, "a", 0)).unwrap();
+    map1.insert_unique(OrdItem::new(2, "b", 0)).unwrap();
+    map2.insert_unique(OrdItem::new(2, "b", 0)).unwrap();
+    map2.insert_unique(OrdItem::new(1, "a", 0)).unwrap();
+    assert_eq!(map1, map2);
+    assert_hash_eq(&map1, &map2);
+
+    map2.remove1(&2);
+    map2.insert_unique(OrdItem::new(2, "b", 1)).unwrap();
+    assert_ne!(map1, map2);
+}
+
+#[test]
+fn get_mut() {
+    let mut map = make_map();
+    map.insert_unique(OrdItem::new(1, "a", 0)).unwrap();
+    map.insert_unique(OrdItem::new(2, "b", 0)).unwrap();
+
+    map.get1_mut(&1).unwrap().value = 10;
+    map.get2_mut("b").unwrap().value = 20;
+    assert!(map.get1_mut(&3).is_none());
+    assert!(map.get2_mut("c").is_none());
+
+    assert_eq!(map.get2("a").unwrap().value, 10);
+    assert_eq!(map.get1(&2).unwrap().value, 20);
+    let item = map.get1_mut(&2).unwrap().into_ref();
+    assert_eq!(item, &OrdItem::new(2, "b", 20));
+    map.validate(ValidateCompact::Compact).expect("map is valid");
+}
+
+#[test]
+#[should_panic(expected = "key1 changed during RefMut borrow")]
+fn get_mut_panics_if_key1_changes() {
+    let mut map = make_map();
+    map.insert_unique(OrdItem::new(1, "a", 0)).unwrap();
+    map.get2_mut("a").unwrap().key1 = 2;
+}
+
+#[test]
+#[should_panic(expected = "key2 changed during RefMut borrow")]
+fn get_mut_panics_if_key2_changes() {
+    let mut map = make_map();
+    map.insert_unique(OrdItem::new(1, "a", 0)).unwrap();
+    map.get1_mut(&1).unwrap().key2 = "b".to_owned();
+}
+
+#[test]
+fn test_insert_overwrite() {
+    let mut map = make_map();
+    assert_eq!(map.insert_overwrite(OrdItem::new(1, "a", 0)), vec![]);
+    assert_eq!(map.insert_overwrite(OrdItem::new(2, "b", 0)), vec![]);
+
+    // Conflicts with a single item by both keys.
+    assert_eq!(
+        map.insert_overwrite(OrdItem::new(1, "a", 1)),
+        vec![OrdItem::new(1, "a", 0)],
+    );
+
+    // Conflicts with two different items.
+    let mut removed = map.insert_overwrite(OrdItem::new(1, "b", 2));
+    removed.sort();
+    assert_eq!(removed, vec![OrdItem::new(1, "a", 1), OrdItem::new(2, "b", 0)]);
+    assert_eq!(map.len(), 1);
+    assert_eq!(map.get1(&1), Some(&OrdItem::new(1, "b", 2)));
+    map.validate(ValidateCompact::NonCompact).expect("map is valid");
+}
+
+#[test]
+fn test_extend_with_unique() {
+    let mut map = make_map();
+    map.insert_unique(OrdItem::new(1, "a", 0)).unwrap();
+
+    let items = vec![
+        OrdItem::new(2, "b", 0),
+        OrdItem::new(3, "a", 0),
+        OrdItem::new(4, "d", 0),
+    ];
+    let error = map.extend_with(items, ExtendMode::Unique).unwrap_err();
+    assert_eq!(error.new_item(), &OrdItem::new(3, "a", 0));
+    assert_eq!(error.duplicates(), vec![&OrdItem::new(1, "a", 0)]);
+    assert_eq!(map.len(), 2);
+    assert!(!map.contains_key1(&4));
+    map.validate(ValidateCompact::Compact).expect("map is valid");
+}
+
+#[test]
+fn from_iter_and_into_iter() {
+    let items = vec![
+        OrdItem::new(3, "c", 0),
+        OrdItem::new(1, "a", 0),
+        OrdItem::new(2, "b", 0),
+        // Overwrites the first item.
+        OrdItem::new(3, "c", 1),
+    ];
+    let map: BiOrdMap<OrdItem, HashBuilder> = items.into_iter().collect();
+    map.validate(ValidateCompact::NonCompact).expect("map is valid");
+
+    let mut into_iter = map.into_iter();
+    assert_eq!(into_iter.len(), 3);
+    assert_eq!(into_iter.next_back(), Some(OrdItem::new(3, "c", 1)));
+    assert_eq!(
+        into_iter.collect::<Vec<_>>(),
+        vec![OrdItem::new(1, "a", 0), OrdItem::new(2, "b", 0)],
+    );
+}
+
+#[hegel::test(test_cases = 256)]
+fn proptest_ops(tc: TestCase) {
+    let mut map = make_map();
+    let mut naive: Vec<OrdItem> = Vec::new();
+
+    for _ in 0..tc.draw(gs::integers::<usize>().max_value(64)) {
+        // Use small key spaces so that conflicts are common.
+        let key1 = tc.draw(gs::integers::<u8>().max_value(15));
+        let key2 = tc.draw(gs::integers::<u8>().max_value(15)).to_string();
+        match tc.draw(gs::integers::<u8>().max_value(2)) {
+            0 => {
+                let item = OrdItem { key1, key2, value: 0 };
+                let mut expected: Vec<_> = naive
+                    .iter()
+                    .filter(|e| e.key1 == item.key1 || e.key2 == item.key2)
+                    .collect();
+                match map.insert_unique(item.clone()) {
+                    Ok(()) => {
+                        assert!(expected.is_empty(), "expected {expected:?}");
+                        naive.push(item);
+                    }
+                    Err(error) => {
+                        let mut duplicates = error.duplicates().to_vec();
+                        duplicates.sort();
+                        expected.sort();
+                        assert_eq!(duplicates, expected);
+                    }
+                }
+            }
+            1 => {
+                let expected = naive
+                    .iter()
+                    .position(|e| e.key1 == key1)
+                    .map(|ix| naive.swap_remove(ix));
+                assert_eq!(map.remove1(&key1), expected);
+            }
+            _ => {
+                let expected = naive
+                    .iter()
+                    .position(|e| e.key2 == key2)
+                    .map(|ix| naive.swap_remove(ix));
+                assert_eq!(map.remove2(key2.as_str()), expected);
+            }
+        }
+
+        map.validate(ValidateCompact::NonCompact).expect("map is valid");
+        naive.sort();
+        assert_eq!(map.iter().cloned().collect::<Vec<_>>(), naive);
+        for e in &naive {
+            assert_eq!(map.get1(&e.key1), Some(e));
+            assert_eq!(map.get2(e.key2.as_str()), Some(e));
+        }
+    }
+}
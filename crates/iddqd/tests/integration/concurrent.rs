@@ -0,0 +1,139 @@
+use iddqd::{
+    ConcurrentIdHashMap, IdHashItem, id_upcast, internal::ValidateCompact,
+};
+use iddqd_test_utils::{
+    borrowed_item::BorrowedItem,
+    test_item::{HashBuilder, TestItem, TestKey1},
+};
+use std::{borrow::Cow, path::Path, sync::Arc, thread};
+
+fn make_map(shard_count: usize) -> ConcurrentIdHashMap<TestItem, HashBuilder> {
+    ConcurrentIdHashMap::with_shard_count_and_hasher(
+        shard_count,
+        HashBuilder::default(),
+    )
+}
+
+#[test]
+fn basic_operations() {
+    let map = make_map(4);
+    assert!(map.is_empty());
+    assert_eq!(map.shard_count(), 4);
+
+    let item1 = TestItem::new(1, 'a', "x", "v1");
+    let item2 = TestItem::new(2, 'b', "y", "v2");
+    map.insert_unique(item1.clone()).unwrap();
+    map.insert_unique(item2.clone()).unwrap();
+    assert_eq!(map.len(), 2);
+
+    // Duplicates are reported with owned copies of the conflicting items.
+    let dup = TestItem::new(1, 'c', "z", "v3");
+    let error = map.insert_unique(dup.clone()).unwrap_err();
+    assert_eq!(error.new_item(), &dup);
+    assert_eq!(error.duplicates(), std::slice::from_ref(&item1));
+
+    assert!(map.contains_key(TestKey1::new(&1)));
+    assert_eq!(map.get(TestKey1::new(&2)), Some(item2.clone()));
+    assert_eq!(
+        map.get_with(TestKey1::new(&2), |item| item.value.clone()),
+        Some("v2".to_owned())
+    );
+    assert_eq!(map.get(TestKey1::new(&3)), None);
+
+    assert_eq!(map.insert_overwrite(dup.clone()), Some(item1));
+    assert_eq!(map.remove(TestKey1::new(&1)), Some(dup));
+    assert_eq!(map.remove(TestKey1::new(&1)), None);
+    assert_eq!(map.len(), 1);
+
+    map.clear();
+    assert!(map.is_empty());
+}
+
+// TestItem isn't Sync, so use a simpler item type for the multithreaded test.
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct SimpleItem {
+    key: u8,
+}
+
+impl IdHashItem for SimpleItem {
+    type Key<'a> = u8;
+
+    fn key(&self) -> Self::Key<'_> {
+        self.key
+    }
+
+    id_upcast!();
+}
+
+#[test]
+fn concurrent_inserts_and_removes() {
+    const THREADS: u8 = 8;
+    const PER_THREAD: u8 = 16;
+
+    let map: Arc<ConcurrentIdHashMap<SimpleItem, HashBuilder>> =
+        Arc::new(ConcurrentIdHashMap::with_shard_count_and_hasher(
+            4,
+            HashBuilder::default(),
+        ));
+    let handles: Vec<_> = (0..THREADS)
+        .map(|t| {
+            let map = Arc::clone(&map);
+            thread::spawn(move || {
+                for i in 0..PER_THREAD {
+                    let key1 = t * PER_THREAD + i;
+                    map.insert_unique(SimpleItem { key: key1 }).unwrap();
+                }
+                // Remove every other item inserted by this thread.
+                for i in (0..PER_THREAD).step_by(2) {
+                    let key1 = t * PER_THREAD + i;
+                    map.remove(key1).unwrap();
+                }
+            })
+        })
+        .collect();
+    for handle in handles {
+        handle.join().unwrap();
+    }
+
+    let expected = usize::from(THREADS) * usize::from(PER_THREAD) / 2;
+    assert_eq!(map.len(), expected);
+
+    let map = Arc::into_inner(map).unwrap();
+    let shards = map.into_shards();
+    assert_eq!(shards.len(), 4);
+    let mut keys = Vec::new();
+    for shard in &shards {
+        shard.validate(ValidateCompact::NonCompact).expect("shard is valid");
+        keys.extend(shard.iter().map(|item| item.key));
+    }
+    keys.sort_unstable();
+    let expected_keys: Vec<u8> =
+        (0..THREADS * PER_THREAD).filter(|k| k % 2 == 1).collect();
+    assert_eq!(keys, expected_keys);
+}
+
+#[test]
+fn borrowed_item() {
+    let key = String::from("foo");
+    let map: ConcurrentIdHashMap<BorrowedItem<'_>, HashBuilder> =
+        ConcurrentIdHashMap::with_shard_count_and_hasher(
+            2,
+            HashBuilder::default(),
+        );
+    map.insert_unique(BorrowedItem {
+        key1: &key,
+        key2: Cow::Borrowed(b"foo"),
+        key3: Path::new("foo"),
+    })
+    .unwrap();
+
+    assert!(map.contains_key("foo"));
+    assert_eq!(map.get_with("foo", |item| item.key3), Some(Path::new("foo")));
+    assert!(map.remove("foo").is_some());
+}
+
+#[test]
+#[should_panic = "shard_count must be non-zero"]
+fn zero_shards_panics() {
+    make_map(0);
+}
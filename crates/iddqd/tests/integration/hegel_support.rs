@@ -153,16 +153,8 @@ pub(crate) fn draw_lookup_keys123(
     )
 }
 
-#[cfg(any(
-    feature = "std",
-    all(feature = "default-hasher", feature = "allocator-api2")
-))]
 pub(crate) const MAX_PANIC_KEY: u32 = 63;
 
-#[cfg(any(
-    feature = "std",
-    all(feature = "default-hasher", feature = "allocator-api2")
-))]
 pub(crate) fn draw_armed(tc: &TestCase) -> Option<u32> {
     use iddqd_test_utils::panic_safety::observe_output_path;
 
@@ -3,11 +3,12 @@ use crate::hegel_support::{
 };
 use hegel::{TestCase, generators as gs};
 use iddqd::{
-    IdHashItem, IdHashMap, id_hash_map, id_upcast, internal::ValidateCompact,
+    ExtendMode, IdHashItem, IdHashMap, id_hash_map, id_upcast,
+    internal::ValidateCompact,
 };
 use iddqd_test_utils::{
     borrowed_item::BorrowedItem,
-    eq_props::{assert_eq_props, assert_ne_props},
+    eq_props::{assert_eq_props, assert_hash_eq, assert_ne_props},
     naive_map::NaiveMap,
     test_item::{
         Alloc, HashBuilder, ItemMap, TestItem, TestKey1, assert_iter_eq,
@@ -15,6 +16,8 @@ use iddqd_test_utils::{
 };
 use std::{
     borrow::Cow,
+    collections::BTreeSet,
+    hash::BuildHasher,
     path::{Path, PathBuf},
 };
 
@@ -93,6 +96,34 @@ fn with_capacity() {
     assert!(map.capacity() >= 1024);
 }
 
+#[cfg(feature = "default-hasher")]
+#[test]
+fn try_with_capacity() {
+    let map = IdHashMap::<TestItem>::try_with_capacity(1024).unwrap();
+    assert!(map.capacity() >= 1024);
+    assert!(map.is_empty());
+
+    IdHashMap::<TestItem>::try_with_capacity(usize::MAX)
+        .expect_err("capacity overflow is reported as an error");
+}
+
+#[test]
+fn try_with_capacity_and_hasher() {
+    let map = IdHashMap::<TestItem, HashBuilder>::try_with_capacity_and_hasher(
+        1024,
+        HashBuilder::default(),
+    )
+    .unwrap();
+    assert!(map.capacity() >= 1024);
+    assert!(map.is_empty());
+
+    IdHashMap::<TestItem, HashBuilder>::try_with_capacity_and_hasher(
+        usize::MAX,
+        HashBuilder::default(),
+    )
+    .expect_err("capacity overflow is reported as an error");
+}
+
 #[test]
 fn test_insert_unique() {
     let mut map = IdHashMap::<TestItem, HashBuilder, Alloc>::make_new();
@@ -142,6 +173,26 @@ fn test_insert_unique() {
     assert_eq!(*e2, v1);
 }
 
+#[test]
+fn test_try_insert() {
+    let mut map = IdHashMap::<TestItem, HashBuilder, Alloc>::make_new();
+
+    // A successful insertion returns a reference to the stored item.
+    let v1 = TestItem::new(20, 'a', "x", "v");
+    assert_eq!(map.try_insert(v1.clone()).unwrap(), &v1);
+
+    // A duplicate key returns the existing item in the error, and leaves the
+    // map unchanged.
+    let v2 = TestItem::new(20, 'b', "y", "w");
+    let error = map.try_insert(v2.clone()).unwrap_err();
+    assert_eq!(error.new_item(), &v2);
+    assert_eq!(error.duplicates(), vec![&v1]);
+    assert_eq!(map.len(), 1);
+    assert_eq!(map.get(&TestKey1::new(&20)), Some(&v1));
+
+    map.validate(ValidateCompact::Compact).expect("map should be valid");
+}
+
 // Test that the unsafe block within RefMut doesn't trip up miri.
 #[test]
 fn test_ref_mut_aliasing() {
@@ -176,6 +227,73 @@ fn test_extend() {
     assert_eq!(map.get(&TestKey1::new(&2)).unwrap().value, "w");
 }
 
+#[test]
+fn test_extend_with_unique() {
+    let mut map = IdHashMap::<TestItem, HashBuilder, Alloc>::make_new();
+    let existing = TestItem::new(1, 'a', "x", "v");
+    map.insert_unique(existing.clone()).unwrap();
+
+    // Stops at the first duplicate: items before it are kept, items after it
+    // are dropped.
+    let conflicting = TestItem::new(1, 'c', "z", "conflict");
+    let items = vec![
+        TestItem::new(2, 'b', "y", "w"),
+        conflicting.clone(),
+        TestItem::new(3, 'd', "q", "dropped"),
+    ];
+    let error = map.extend_with(items, ExtendMode::Unique).unwrap_err();
+    assert_eq!(error.new_item(), &conflicting);
+    assert_eq!(error.duplicates(), &[&existing]);
+
+    assert_eq!(map.len(), 2);
+    assert_eq!(map.get(&TestKey1::new(&2)).unwrap().value, "w");
+    assert_eq!(map.get(&TestKey1::new(&3)), None);
+    assert_eq!(map.get(&TestKey1::new(&1)).unwrap().value, "v");
+
+    // Overwrite mode matches `extend`.
+    map.extend_with(vec![conflicting], ExtendMode::Overwrite).unwrap();
+    assert_eq!(map.get(&TestKey1::new(&1)).unwrap().value, "conflict");
+}
+
+#[test]
+fn map_transforms_items() {
+    let mut map = IdHashMap::<TestItem, HashBuilder, Alloc>::make_new();
+    for i in 0..4 {
+        map.insert_unique(TestItem::new(
+            i,
+            (b'a' + i) as char,
+            i.to_string(),
+            "v",
+        ))
+        .unwrap();
+    }
+
+    let mapped = map
+        .map(|mut item| {
+            item.value = format!("mapped-{}", item.key1);
+            item
+        })
+        .expect("keys are unchanged");
+    mapped.validate(ValidateCompact::Compact).expect("map is valid");
+    assert_eq!(mapped.len(), 4);
+    for i in 0..4 {
+        assert_eq!(
+            mapped.get(&TestKey1::new(&i)).unwrap().value,
+            format!("mapped-{i}")
+        );
+    }
+
+    // Collapsing a key reports the first conflict.
+    let error = mapped
+        .map(|mut item| {
+            item.key1 = 0;
+            item
+        })
+        .unwrap_err();
+    assert_eq!(error.duplicates().len(), 1);
+    assert_ne!(error.new_item().value, error.duplicates()[0].value);
+}
+
 #[test]
 fn from_iter_unique_duplicate_key_reports_error() {
     let existing = TestItem::new(1, 'a', "x", "first");
@@ -258,6 +376,7 @@ impl IdHashMapMachine {
     fn check_valid(&mut self, change: CompactnessChange) {
         self.compactness = change.apply(self.compactness);
         self.map.validate(self.compactness).expect("map should be valid");
+        self.map.check_invariants().expect("map invariants should hold");
     }
 }
 
@@ -366,7 +485,7 @@ impl IdHashMapMachine {
             let contains = item.value.contains(ch);
             if equals { contains } else { !contains }
         });
-        self.check_valid(CompactnessChange::NoLongerCompact);
+        self.check_valid(CompactnessChange::BecomesCompact);
     }
 
     #[rule]
@@ -384,7 +503,7 @@ impl IdHashMapMachine {
             let matches = item.key1 % modulo == remainder;
             if equals { matches } else { !matches }
         });
-        self.check_valid(CompactnessChange::NoLongerCompact);
+        self.check_valid(CompactnessChange::BecomesCompact);
     }
 
     #[rule]
@@ -486,6 +605,8 @@ fn proptest_permutation_eq(tc: TestCase) {
             .unwrap();
     assert_eq_props(&map1, &map3);
     assert_eq_props(&map3, &map4);
+    assert_hash_eq(&map1, &map2);
+    assert_hash_eq(&map3, &map4);
 }
 
 // Test various conditions for non-equality.
@@ -557,6 +678,673 @@ fn test_permutation_eq_examples() {
     }
 }
 
+#[test]
+fn get_many() {
+    let mut map = IdHashMap::<TestItem, HashBuilder, Alloc>::make_new();
+    let item1 = TestItem::new(1, 'a', "x", "v1");
+    let item2 = TestItem::new(2, 'b', "y", "v2");
+    map.insert_unique(item1.clone()).unwrap();
+    map.insert_unique(item2.clone()).unwrap();
+
+    let [a, b, missing, a_again] = map.get_many([
+        &TestKey1::new(&1),
+        &TestKey1::new(&2),
+        &TestKey1::new(&3),
+        &TestKey1::new(&1),
+    ]);
+    assert_eq!(a, Some(&item1));
+    assert_eq!(b, Some(&item2));
+    assert_eq!(missing, None);
+    assert_eq!(a_again, Some(&item1));
+
+    let [] = map.get_many::<0, TestKey1<'_>>([]);
+}
+
+#[test]
+fn get_disjoint_mut() {
+    let mut map = IdHashMap::<TestItem, HashBuilder, Alloc>::make_new();
+    map.insert_unique(TestItem::new(1, 'a', "x", "v1")).unwrap();
+    map.insert_unique(TestItem::new(2, 'b', "y", "v2")).unwrap();
+
+    {
+        let [a, missing, b] = map.get_disjoint_mut([
+            &TestKey1::new(&1),
+            &TestKey1::new(&3),
+            &TestKey1::new(&2),
+        ]);
+        assert!(missing.is_none());
+        let (mut a, mut b) = (a.unwrap(), b.unwrap());
+        std::mem::swap(&mut a.value, &mut b.value);
+    }
+    assert_eq!(map.get(&TestKey1::new(&1)).unwrap().value, "v2");
+    assert_eq!(map.get(&TestKey1::new(&2)).unwrap().value, "v1");
+
+    // Missing keys may repeat.
+    {
+        let [x, y] =
+            map.get_disjoint_mut([&TestKey1::new(&3), &TestKey1::new(&3)]);
+        assert!(x.is_none() && y.is_none());
+    }
+
+    let [] = map.get_disjoint_mut::<0, TestKey1<'_>>([]);
+}
+
+#[test]
+#[should_panic(expected = "duplicate keys passed to get_disjoint_mut")]
+fn get_disjoint_mut_panics_on_duplicate_keys() {
+    let mut map = IdHashMap::<TestItem, HashBuilder, Alloc>::make_new();
+    map.insert_unique(TestItem::new(1, 'a', "x", "v")).unwrap();
+    let _ = map.get_disjoint_mut([&TestKey1::new(&1), &TestKey1::new(&1)]);
+}
+
+#[test]
+fn keys_and_values() {
+    let mut map = IdHashMap::<TestItem, HashBuilder, Alloc>::make_new();
+    map.insert_unique(TestItem::new(1, 'a', "x", "v1")).unwrap();
+    map.insert_unique(TestItem::new(2, 'b', "y", "v2")).unwrap();
+    map.insert_unique(TestItem::new(3, 'c', "z", "v3")).unwrap();
+
+    assert_eq!(map.keys().len(), 3);
+    assert!(map.keys().eq(map.iter().map(|item| item.key())));
+    assert!(map.values().eq(map.iter()));
+
+    for mut item in map.values_mut() {
+        item.value.push_str("-updated");
+    }
+    for item in map.values() {
+        assert!(item.value.ends_with("-updated"), "{item:?} was updated");
+    }
+}
+
+#[test]
+fn iter_exact_size_and_fused() {
+    let mut map = IdHashMap::<TestItem, HashBuilder, Alloc>::make_new();
+    map.insert_unique(TestItem::new(1, 'a', "x", "v1")).unwrap();
+    map.insert_unique(TestItem::new(2, 'b', "y", "v2")).unwrap();
+    map.insert_unique(TestItem::new(3, 'c', "z", "v3")).unwrap();
+    map.insert_unique(TestItem::new(4, 'd', "w", "v4")).unwrap();
+    // Leave a hole in the underlying storage, which must not be counted.
+    map.remove(&TestKey1::new(&2)).unwrap();
+
+    let mut iter = map.iter();
+    assert_eq!(iter.len(), 3);
+    assert_eq!(iter.size_hint(), (3, Some(3)));
+    iter.next().unwrap();
+    assert_eq!(iter.len(), 2);
+    assert_eq!(iter.by_ref().count(), 2);
+    assert_eq!(iter.len(), 0);
+    assert!(iter.next().is_none());
+    assert!(iter.next().is_none());
+
+    let mut iter_mut = map.iter_mut().fuse();
+    assert_eq!(iter_mut.size_hint(), (3, Some(3)));
+    assert_eq!(iter_mut.by_ref().count(), 3);
+    assert!(iter_mut.next().is_none());
+    assert!(iter_mut.next().is_none());
+
+    let mut into_iter = IntoIterator::into_iter(map).fuse();
+    assert_eq!(into_iter.len(), 3);
+    assert_eq!(into_iter.by_ref().map(|item| item.key1).sum::<u8>(), 8);
+    assert_eq!(into_iter.len(), 0);
+    assert!(into_iter.next().is_none());
+    assert!(into_iter.next().is_none());
+}
+
+#[test]
+fn into_keys_and_values() {
+    let mut map = IdHashMap::<SimpleItem, HashBuilder, Alloc>::make_new();
+    map.insert_unique(SimpleItem { key: 20 }).unwrap();
+    map.insert_unique(SimpleItem { key: 1 }).unwrap();
+    map.insert_unique(SimpleItem { key: 10 }).unwrap();
+
+    let mut keys: Vec<u32> = map.clone().into_keys().collect();
+    keys.sort_unstable();
+    assert_eq!(keys, [1, 10, 20]);
+
+    let mut values: Vec<u32> = map.into_values().map(|item| item.key).collect();
+    values.sort_unstable();
+    assert_eq!(values, [1, 10, 20]);
+
+    // Keys that borrow from outside the item can outlive the map.
+    let keys: Vec<&str> = {
+        let mut map =
+            IdHashMap::<BorrowedItem<'_>, HashBuilder, Alloc>::default();
+        for key1 in ["foo", "bar"] {
+            map.insert_unique(BorrowedItem {
+                key1,
+                key2: Cow::Borrowed(b"x"),
+                key3: Path::new("x"),
+            })
+            .unwrap();
+        }
+        map.into_keys().collect()
+    };
+    assert_eq!(keys.len(), 2);
+    assert!(keys.contains(&"foo") && keys.contains(&"bar"));
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn std_hashmap_roundtrip() {
+    use iddqd::errors::FromStdHashMapError;
+    use std::collections::HashMap;
+
+    let mut map = IdHashMap::<SimpleItem, HashBuilder, Alloc>::make_new();
+    for key in [20, 1, 10] {
+        map.insert_unique(SimpleItem { key }).unwrap();
+    }
+
+    let std_map: HashMap<u32, SimpleItem> = map.into_std_hashmap();
+    let mut keys: Vec<_> =
+        std_map.iter().map(|(k, item)| (*k, item.key)).collect();
+    keys.sort_unstable();
+    assert_eq!(keys, [(1, 1), (10, 10), (20, 20)]);
+
+    let map =
+        IdHashMap::<SimpleItem, HashBuilder, Alloc>::try_from_std_hashmap(
+            std_map,
+        )
+        .expect("keys match");
+    assert_eq!(map.len(), 3);
+    assert!(map.contains_key(&10));
+    map.validate(ValidateCompact::Compact).expect("map should be valid");
+
+    // An item stored under a different key is rejected.
+    let std_map = HashMap::from([
+        (1_u32, SimpleItem { key: 1 }),
+        (2, SimpleItem { key: 3 }),
+    ]);
+    let error =
+        IdHashMap::<SimpleItem, HashBuilder, Alloc>::try_from_std_hashmap(
+            std_map,
+        )
+        .unwrap_err();
+    let FromStdHashMapError::KeyMismatch(error) = error else {
+        panic!("expected a key mismatch, found {error:?}");
+    };
+    assert_eq!(*error.key(), 2);
+    assert_eq!(error.item().key, 3);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn std_hashmap_lossy_key_conversion() {
+    use iddqd::errors::FromStdHashMapError;
+    use std::{
+        collections::HashMap,
+        hash::{Hash, Hasher},
+    };
+
+    // A key that compares case-insensitively, but converts into a `String`
+    // that preserves case.
+    #[derive(Debug)]
+    struct CaseInsensitive<'a>(&'a str);
+
+    impl PartialEq for CaseInsensitive<'_> {
+        fn eq(&self, other: &Self) -> bool {
+            self.0.eq_ignore_ascii_case(other.0)
+        }
+    }
+
+    impl Eq for CaseInsensitive<'_> {}
+
+    impl Hash for CaseInsensitive<'_> {
+        fn hash<H: Hasher>(&self, state: &mut H) {
+            for byte in self.0.bytes() {
+                state.write_u8(byte.to_ascii_lowercase());
+            }
+        }
+    }
+
+    impl From<CaseInsensitive<'_>> for String {
+        fn from(key: CaseInsensitive<'_>) -> Self {
+            key.0.to_owned()
+        }
+    }
+
+    #[derive(Debug)]
+    struct Item(String);
+
+    impl IdHashItem for Item {
+        type Key<'a> = CaseInsensitive<'a>;
+        fn key(&self) -> Self::Key<'_> {
+            CaseInsensitive(&self.0)
+        }
+        id_upcast!();
+    }
+
+    let std_map = HashMap::from([
+        ("foo".to_owned(), Item("foo".to_owned())),
+        ("FOO".to_owned(), Item("FOO".to_owned())),
+    ]);
+    let error =
+        IdHashMap::<Item, HashBuilder, Alloc>::try_from_std_hashmap(std_map)
+            .unwrap_err();
+    let FromStdHashMapError::Duplicate(error) = error else {
+        panic!("expected a duplicate, found {error:?}");
+    };
+    let (new, duplicates) = error.into_parts();
+    assert_eq!(duplicates.len(), 1);
+    let mut keys = [new.0, duplicates[0].0.clone()];
+    keys.sort();
+    assert_eq!(keys, ["FOO", "foo"]);
+}
+
+#[test]
+fn contains_key_by() {
+    let mut map = IdHashMap::<TestItem, HashBuilder, Alloc>::make_new();
+    // The key isn't built for an empty map.
+    assert!(!map.contains_key_by(|| panic!("key built for an empty map")));
+
+    map.insert_unique(TestItem::new(1, 'a', "x", "v")).unwrap();
+    let mut calls = 0;
+    let key = 1;
+    assert!(map.contains_key_by(|| {
+        calls += 1;
+        TestKey1::new(&key)
+    }));
+    assert_eq!(calls, 1);
+    assert!(!map.contains_key_by(|| TestKey1::new(&2)));
+}
+
+#[hegel::test(test_cases = 256)]
+fn proptest_set_operations(tc: TestCase) {
+    let make_map = |items: Vec<TestItem>| {
+        let mut map = IdHashMap::<TestItem, HashBuilder, Alloc>::make_new();
+        for item in items {
+            map.insert_overwrite(item);
+        }
+        map
+    };
+    let a = make_map(tc.draw(gs::vecs(test_item()).max_size(32)));
+    let b = make_map(tc.draw(gs::vecs(test_item()).max_size(32)));
+
+    let a_keys: BTreeSet<u8> = a.iter().map(|item| item.key1).collect();
+    let b_keys: BTreeSet<u8> = b.iter().map(|item| item.key1).collect();
+
+    // Set operations compare by key, and yield each item at most once.
+    fn sorted<'a>(iter: impl Iterator<Item = &'a TestItem>) -> Vec<TestItem> {
+        let mut items: Vec<_> = iter.cloned().collect();
+        items.sort();
+        items
+    }
+    fn from<'a>(
+        map: &'a IdHashMap<TestItem, HashBuilder, Alloc>,
+        keys: impl IntoIterator<Item = &'a u8>,
+    ) -> impl Iterator<Item = &'a TestItem> {
+        keys.into_iter().filter_map(|k| map.get(&TestKey1::new(k)))
+    }
+
+    assert_eq!(
+        sorted(a.intersection(&b)),
+        sorted(from(&a, a_keys.intersection(&b_keys))),
+    );
+    assert_eq!(
+        sorted(a.difference(&b)),
+        sorted(from(&a, a_keys.difference(&b_keys))),
+    );
+    assert_eq!(
+        sorted(a.symmetric_difference(&b)),
+        sorted(
+            from(&a, a_keys.difference(&b_keys))
+                .chain(from(&b, b_keys.difference(&a_keys)))
+        ),
+    );
+    assert_eq!(
+        sorted(a.union(&b)),
+        sorted(a.iter().chain(from(&b, b_keys.difference(&a_keys)))),
+    );
+}
+
+#[hegel::test(test_cases = 64)]
+fn proptest_into_iter_dropping(tc: TestCase) {
+    let items = draw_fill_batch(&tc);
+    let mut map = IdHashMap::<TestItem, HashBuilder, Alloc>::make_new();
+    for item in items.clone() {
+        map.insert_unique(item).expect("batch keys are unique");
+    }
+    // Leave some holes in the item set.
+    for item in items.iter().step_by(3) {
+        map.remove(&item.key()).expect("item is present");
+    }
+
+    let mut expected: Vec<_> = map.iter().cloned().collect();
+    expected.sort();
+    let mut iter = map.into_iter_dropping();
+    assert_eq!(iter.len(), expected.len());
+    let mut actual = Vec::new();
+    while let Some(item) = iter.next() {
+        actual.push(item);
+        assert_eq!(iter.len(), expected.len() - actual.len());
+    }
+    actual.sort();
+    assert_eq!(actual, expected);
+}
+
+#[hegel::test(test_cases = 64)]
+fn proptest_into_vec(tc: TestCase) {
+    let mut items = draw_fill_batch(&tc);
+    let map = IdHashMap::<TestItem, HashBuilder, Alloc>::from_iter_unique(
+        items.clone(),
+    )
+    .unwrap();
+
+    let mut actual = map.into_vec();
+    actual.sort();
+    items.sort();
+    assert_eq!(actual, items);
+}
+
+#[hegel::test(test_cases = 64)]
+fn proptest_drain(tc: TestCase) {
+    let items = draw_fill_batch(&tc);
+    let mut map = IdHashMap::<TestItem, HashBuilder, Alloc>::make_new();
+    for item in items.clone() {
+        map.insert_unique(item).unwrap();
+    }
+    let capacity = map.capacity();
+    // Take some items, then drop the iterator.
+    let take = tc.draw(gs::integers::<usize>().max_value(items.len()));
+
+    let mut drain = map.drain();
+    assert_eq!(drain.len(), items.len());
+    let mut taken: Vec<_> = drain.by_ref().take(take).collect();
+    assert_eq!(drain.len(), items.len() - take);
+    drop(drain);
+
+    // The taken items are distinct items from the map.
+    taken.sort_by_key(|item| item.key1);
+    taken.dedup_by_key(|item| item.key1);
+    assert_eq!(taken.len(), take);
+    assert!(taken.iter().all(|item| items.contains(item)));
+
+    assert!(map.is_empty());
+    assert_eq!(map.capacity(), capacity);
+    map.validate(ValidateCompact::Compact).expect("drained map is valid");
+}
+
+#[test]
+fn drain_leaked_leaves_map_empty() {
+    let mut map = IdHashMap::<TestItem, HashBuilder, Alloc>::make_new();
+    for i in 0..8 {
+        map.insert_unique(TestItem::new(i, 'a', "x", "v")).unwrap();
+    }
+
+    let mut drain = map.drain();
+    drain.next().unwrap();
+    std::mem::forget(drain);
+
+    // The remaining items are leaked, but the map is still valid.
+    assert!(map.is_empty());
+    map.validate(ValidateCompact::Compact)
+        .expect("map is valid after leaking a drain");
+    map.insert_unique(TestItem::new(0, 'a', "x", "v")).unwrap();
+    assert_eq!(map.len(), 1);
+}
+
+#[hegel::test(test_cases = 64)]
+fn proptest_clone_from(tc: TestCase) {
+    let source_items = draw_fill_batch(&tc);
+    let dest_items = draw_fill_batch(&tc);
+
+    let mut source = IdHashMap::<TestItem, HashBuilder, Alloc>::make_new();
+    for item in source_items.clone() {
+        source.insert_unique(item).expect("set is deduplicated");
+    }
+    // Leave some holes in the source's item set.
+    for item in source_items.iter().step_by(3) {
+        source.remove(&item.key()).expect("item is present");
+    }
+    let mut dest =
+        IdHashMap::<TestItem, HashBuilder, Alloc>::from_iter_unique(dest_items)
+            .expect("set is deduplicated");
+
+    dest.clone_from(&source);
+    dest.validate(ValidateCompact::NonCompact).expect("map is valid");
+    assert_eq_props(&dest, &source);
+    for item in &source {
+        assert_eq!(dest.get(&item.key()), Some(item));
+    }
+}
+
+#[test]
+fn clone_from_panic_leaves_dest_empty() {
+    #[derive(Debug)]
+    struct PanickyClone {
+        key: u32,
+    }
+
+    impl Clone for PanickyClone {
+        fn clone(&self) -> Self {
+            if self.key == 3 {
+                panic!("clone panicked");
+            }
+            Self { key: self.key }
+        }
+    }
+
+    impl IdHashItem for PanickyClone {
+        type Key<'a> = u32;
+
+        fn key(&self) -> Self::Key<'_> {
+            self.key
+        }
+
+        id_upcast!();
+    }
+
+    let source =
+        IdHashMap::<PanickyClone, HashBuilder, Alloc>::from_iter_unique(
+            (0..8).map(|key| PanickyClone { key }),
+        )
+        .unwrap();
+    let mut dest =
+        IdHashMap::<PanickyClone, HashBuilder, Alloc>::from_iter_unique(
+            (10..12).map(|key| PanickyClone { key }),
+        )
+        .unwrap();
+
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        dest.clone_from(&source);
+    }));
+    assert!(result.is_err());
+    assert!(dest.is_empty());
+    dest.validate(ValidateCompact::Compact).expect("map is valid");
+
+    dest.insert_unique(PanickyClone { key: 1 }).unwrap();
+    assert_eq!(dest.get(&1).map(|item| item.key), Some(1));
+}
+
+#[test]
+fn debug_capacity_breakdown_tracks_sizing() {
+    let mut map = IdHashMap::<TestItem, HashBuilder, Alloc>::make_new();
+    let breakdown = map.debug_capacity_breakdown();
+    assert_eq!(breakdown.items_len, 0);
+    assert_eq!(breakdown.table_capacities.len(), 1);
+
+    map.reserve(32);
+    for i in 0..8 {
+        map.insert_unique(TestItem::new(i, 'a', "x", "v")).unwrap();
+    }
+    let breakdown = map.debug_capacity_breakdown();
+    assert_eq!(breakdown.items_len, 8);
+    assert_eq!(breakdown.items_capacity, map.capacity());
+    assert!(breakdown.items_capacity >= 32);
+    assert!(breakdown.table_capacities[0] >= 32);
+
+    map.shrink_to_fit();
+    let breakdown = map.debug_capacity_breakdown();
+    assert_eq!(breakdown.items_len, 8);
+    assert!(breakdown.items_capacity >= 8);
+    assert!(breakdown.table_capacities[0] >= 8);
+    assert!(breakdown.table_capacities[0] < 32);
+}
+
+// `IdHashItem` is implemented for `&T`, so a map of references can serve as a
+// cheap view over a subset of another map's items.
+#[test]
+fn reference_view_map() {
+    let mut map = IdHashMap::<TestItem, HashBuilder, Alloc>::make_new();
+    for i in 0..8 {
+        map.insert_unique(TestItem::new(i, 'a', "x", "v")).unwrap();
+    }
+
+    let mut view = IdHashMap::<&TestItem, HashBuilder, Alloc>::default();
+    for item in map.iter().filter(|item| item.key1 % 2 == 0) {
+        view.insert_unique(item).unwrap();
+    }
+
+    assert_eq!(view.len(), 4);
+    let key = TestKey1::new(&2);
+    assert!(std::ptr::eq(*view.get(&key).unwrap(), map.get(&key).unwrap()));
+    assert!(view.get(&TestKey1::new(&3)).is_none());
+    view.validate(ValidateCompact::Compact).expect("view is valid");
+}
+
+#[test]
+fn into_iter_dropping_releases_storage() {
+    let mut map = IdHashMap::<TestItem, HashBuilder, Alloc>::make_new();
+    for i in 0..=255 {
+        map.insert_unique(TestItem::new(i, 'a', "x", "v")).unwrap();
+    }
+
+    let mut iter = map.into_iter_dropping();
+    let initial = iter.capacity();
+    assert!(initial >= 256);
+    let mut prev = initial;
+    for _ in 0..240 {
+        iter.next().expect("item remains");
+        // Capacity never grows, and stays within twice what's left.
+        assert!(iter.capacity() <= prev);
+        assert!(iter.capacity() <= (2 * iter.len()).max(16));
+        prev = iter.capacity();
+    }
+    assert_eq!(iter.len(), 16);
+    assert!(iter.capacity() < initial);
+}
+
+#[test]
+fn raw_table_layout() {
+    let mut map = IdHashMap::<TestItem, HashBuilder, Alloc>::make_new();
+    for i in 0..4 {
+        map.insert_unique(TestItem::new(i, 'a', "x", "v")).unwrap();
+    }
+    map.remove(&TestKey1::new(&1)).unwrap();
+
+    let raw = map.raw_table();
+    // Removing an item leaves a vacant slot behind.
+    assert_eq!(raw.slot_count(), 4);
+    assert_eq!(raw.get_slot(1), None);
+    assert_eq!(raw.get_slot(4), None);
+    let slots: Vec<_> = raw.slots().map(|(ix, item)| (ix, item.key1)).collect();
+    assert_eq!(slots, [(0, 0), (2, 2), (3, 3)]);
+
+    // The key table has one entry per occupied slot, with the cached hash of
+    // that slot's key.
+    let mut table: Vec<_> = raw
+        .key_table()
+        .map(|(hash, ix)| {
+            let item = raw.get_slot(ix).expect("key table points at an item");
+            assert_eq!(hash, raw.hasher().hash_one(item.key()));
+            ix
+        })
+        .collect();
+    table.sort_unstable();
+    assert_eq!(table, [0, 2, 3]);
+
+    // Compaction renumbers the slots.
+    map.shrink_to_fit();
+    let raw = map.raw_table();
+    assert_eq!(raw.slot_count(), 3);
+    assert_eq!(raw.slots().count(), 3);
+}
+
+#[test]
+fn remove_full_reports_slot_index() {
+    let mut map = IdHashMap::<TestItem, HashBuilder, Alloc>::make_new();
+    for i in 0..4 {
+        map.insert_unique(TestItem::new(i, 'a', "x", "v")).unwrap();
+    }
+
+    let (index, item) = map.remove_full(&TestKey1::new(&2)).unwrap();
+    assert_eq!(index, 2);
+    assert_eq!(item.key1, 2);
+    assert_eq!(map.raw_table().get_slot(2), None);
+    assert_eq!(map.remove_full(&TestKey1::new(&2)), None);
+
+    // Reinserting the item reuses the slot it was removed from.
+    map.insert_unique(item.clone()).unwrap();
+    assert_eq!(map.raw_table().get_slot(2), Some(&item));
+    map.validate(ValidateCompact::Compact).expect("map should be valid");
+}
+
+#[test]
+fn index_lookups_survive_unrelated_removals() {
+    let mut map = IdHashMap::<TestItem, HashBuilder, Alloc>::make_new();
+    for i in 0..8 {
+        map.insert_unique(TestItem::new(i, 'a', "x", "v")).unwrap();
+    }
+    let indexes: Vec<_> =
+        (0..8).map(|i| map.get_index_of(&TestKey1::new(&i)).unwrap()).collect();
+
+    for i in [1, 4, 6] {
+        map.remove(&TestKey1::new(&i)).unwrap();
+    }
+    for (i, &index) in indexes.iter().enumerate() {
+        let key1 = i as u8;
+        if [1, 4, 6].contains(&key1) {
+            assert_eq!(map.get_index_of(&TestKey1::new(&key1)), None);
+            assert_eq!(map.get_by_index(index), None);
+        } else {
+            assert_eq!(map.get_index_of(&TestKey1::new(&key1)), Some(index));
+            assert_eq!(map.get_by_index(index).unwrap().key1, key1);
+        }
+    }
+    assert_eq!(map.get_by_index(usize::MAX), None);
+}
+
+#[test]
+fn into_iter_indexed_matches_get_index_of() {
+    let mut map = IdHashMap::<TestItem, HashBuilder, Alloc>::make_new();
+    for i in 0..8 {
+        map.insert_unique(TestItem::new(i, 'a', "x", "v")).unwrap();
+    }
+    for i in [1, 4, 6] {
+        map.remove(&TestKey1::new(&i)).unwrap();
+    }
+    let mut expected: Vec<_> = map
+        .iter()
+        .map(|item| (map.get_index_of(&item.key()).unwrap(), item.key1))
+        .collect();
+    expected.sort_unstable();
+
+    let iter = map.into_iter_indexed();
+    assert_eq!(iter.len(), 5);
+    let mut actual: Vec<_> =
+        iter.map(|(index, item)| (index, item.key1)).collect();
+    actual.sort_unstable();
+    assert_eq!(actual, expected);
+}
+
+#[test]
+fn remove_all_returns_items_in_input_order() {
+    let mut map = IdHashMap::<TestItem, HashBuilder, Alloc>::make_new();
+    for i in 0..5 {
+        map.insert_unique(TestItem::new(i, 'a', "x", "v")).unwrap();
+    }
+
+    // Missing and repeated keys are skipped.
+    let keys = [3, 7, 0, 3, 4];
+    let removed = map.remove_all(&keys.each_ref().map(TestKey1::new));
+    let removed: Vec<_> = removed.iter().map(|item| item.key1).collect();
+    assert_eq!(removed, [3, 0, 4]);
+
+    let mut remaining: Vec<_> = map.iter().map(|item| item.key1).collect();
+    remaining.sort_unstable();
+    assert_eq!(remaining, [1, 2]);
+    map.validate(ValidateCompact::NonCompact).expect("map should be valid");
+}
+
 #[test]
 #[should_panic(expected = "key changed during RefMut borrow")]
 fn get_mut_panics_if_key_changes() {
@@ -565,6 +1353,102 @@ fn get_mut_panics_if_key_changes() {
     map.get_mut(&TestKey1::new(&128)).unwrap().key1 = 2;
 }
 
+#[test]
+#[should_panic(expected = "key changed during RefMut borrow")]
+fn into_ref_panics_if_key_changed() {
+    let mut map = IdHashMap::<TestItem, HashBuilder, Alloc>::make_new();
+    map.insert_unique(TestItem::new(128, 'b', "y", "x")).unwrap();
+    let mut item = map.get_mut(&TestKey1::new(&128)).unwrap();
+    item.key1 = 2;
+    // Converting to a shared reference still checks the key.
+    let _ = item.into_ref();
+}
+
+#[test]
+fn with_mut_returns_closure_result() {
+    let mut map = IdHashMap::<TestItem, HashBuilder, Alloc>::make_new();
+    map.insert_unique(TestItem::new(128, 'b', "y", "x")).unwrap();
+
+    let old = map.with_mut(&TestKey1::new(&128), |item| {
+        std::mem::replace(&mut item.value, "z".to_owned())
+    });
+    assert_eq!(old.as_deref(), Some("x"));
+    assert_eq!(map.get(&TestKey1::new(&128)).unwrap().value, "z");
+    assert_eq!(
+        map.with_mut(&TestKey1::new(&0), |_| unreachable!()),
+        None::<()>
+    );
+}
+
+#[test]
+#[should_panic(expected = "key changed during with_mut call")]
+fn with_mut_panics_if_key_changes() {
+    let mut map = IdHashMap::<TestItem, HashBuilder, Alloc>::make_new();
+    map.insert_unique(TestItem::new(128, 'b', "y", "x")).unwrap();
+    map.with_mut(&TestKey1::new(&128), |item| item.key1 = 2);
+}
+
+#[test]
+fn with_mut_checks_against_stored_hash() {
+    use iddqd::Equivalent;
+    use std::hash::{Hash, Hasher};
+
+    // A hasher that returns the last `u64` written to it.
+    #[derive(Clone, Default)]
+    struct IdentityState;
+
+    impl BuildHasher for IdentityState {
+        type Hasher = IdentityHasher;
+
+        fn build_hasher(&self) -> IdentityHasher {
+            IdentityHasher(0)
+        }
+    }
+
+    struct IdentityHasher(u64);
+
+    impl Hasher for IdentityHasher {
+        fn write(&mut self, _bytes: &[u8]) {
+            unimplemented!("only u32 and u64 are written")
+        }
+
+        fn write_u32(&mut self, i: u32) {
+            self.0 = u64::from(i);
+        }
+
+        fn write_u64(&mut self, i: u64) {
+            self.0 = i;
+        }
+
+        fn finish(&self) -> u64 {
+            self.0
+        }
+    }
+
+    // A lookup key whose hash differs from the item key's hash, but which
+    // lands in the same bucket with the same control byte in a small table.
+    struct OffsetLookup(u32);
+
+    impl Hash for OffsetLookup {
+        fn hash<H: Hasher>(&self, state: &mut H) {
+            state.write_u64(u64::from(self.0) + (1 << 20));
+        }
+    }
+
+    impl Equivalent<u32> for OffsetLookup {
+        fn equivalent(&self, key: &u32) -> bool {
+            self.0 == *key
+        }
+    }
+
+    let mut map = IdHashMap::<SimpleItem, IdentityState>::default();
+    map.insert_unique(SimpleItem { key: 3 }).unwrap();
+
+    // The key isn't changed, so this must not panic even though the lookup
+    // key hashes differently.
+    assert_eq!(map.with_mut(&OffsetLookup(3), |item| item.key), Some(3));
+}
+
 #[test]
 fn entry_examples() {
     let mut map = IdHashMap::<TestItem, HashBuilder, Alloc>::make_new();
@@ -616,6 +1500,194 @@ fn entry_examples() {
     assert!(and_modify_called);
 }
 
+#[test]
+fn entry_ref_borrowed_key() {
+    #[derive(Clone, Debug, PartialEq, Eq)]
+    struct NamedItem {
+        name: String,
+        value: u32,
+    }
+
+    impl IdHashItem for NamedItem {
+        type Key<'a> = &'a str;
+        fn key(&self) -> Self::Key<'_> {
+            &self.name
+        }
+        id_upcast!();
+    }
+
+    fn item(name: &str, value: u32) -> NamedItem {
+        NamedItem { name: name.to_owned(), value }
+    }
+
+    let mut map = IdHashMap::<NamedItem, HashBuilder, Alloc>::make_new();
+    map.insert_unique(item("a", 1)).unwrap();
+
+    // The query is dropped before the entry is used.
+    let entry = {
+        let name = String::from("a");
+        map.entry_ref(name.as_str())
+    };
+    let id_hash_map::Entry::Occupied(mut entry) = entry else {
+        panic!("expected OccupiedEntry");
+    };
+    entry.get_mut().value = 10;
+    assert_eq!(map.get("a").unwrap().value, 10);
+
+    // A vacant entry's hash must agree with the inserted item.
+    let entry = {
+        let name = String::from("b");
+        map.entry_ref(name.as_str())
+    };
+    let id_hash_map::Entry::Vacant(entry) = entry else {
+        panic!("expected VacantEntry");
+    };
+    entry.insert(item("b", 2));
+    assert_eq!(map.get("b").unwrap().value, 2);
+    map.validate(ValidateCompact::Compact).expect("map is valid");
+}
+
+#[test]
+fn entry_or_try_insert_with() {
+    let mut map = IdHashMap::<TestItem, HashBuilder, Alloc>::make_new();
+    let item1 = TestItem::new(0, 'a', "x", "v");
+
+    // An error leaves the map unchanged.
+    let res = map
+        .entry(item1.key())
+        .or_try_insert_with(|| Err::<TestItem, _>("fetch failed"));
+    assert_eq!(res.map(|r| r.into_ref().clone()), Err("fetch failed"));
+    assert!(map.is_empty());
+    map.validate(ValidateCompact::Compact).expect("map should be valid");
+
+    // Success inserts the item.
+    let res = map
+        .entry(item1.key())
+        .or_try_insert_with(|| Ok::<_, &str>(item1.clone()));
+    assert_eq!(res.unwrap().into_ref(), &item1);
+    assert_eq!(map.len(), 1);
+
+    // An occupied entry doesn't call the function.
+    let res = map.entry(item1.key()).or_try_insert_with(|| -> Result<_, ()> {
+        panic!("or_try_insert_with called for existing key")
+    });
+    assert_eq!(res.unwrap().into_ref(), &item1);
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct CounterItem {
+    key: u32,
+    count: usize,
+}
+
+impl IdHashItem for CounterItem {
+    type Key<'a> = u32;
+
+    fn key(&self) -> Self::Key<'_> {
+        self.key
+    }
+
+    id_upcast!();
+}
+
+impl From<u32> for CounterItem {
+    fn from(key: u32) -> Self {
+        // Key 999 deliberately maps to a mismatched item.
+        if key == 999 {
+            CounterItem { key: 0, count: 0 }
+        } else {
+            CounterItem { key, count: 0 }
+        }
+    }
+}
+
+#[test]
+fn get_or_insert_default() {
+    let mut map = IdHashMap::<CounterItem, HashBuilder, Alloc>::make_new();
+    for key in [1, 2, 1, 3, 1] {
+        map.get_or_insert_default(key).count += 1;
+    }
+
+    assert_eq!(map.len(), 3);
+    assert_eq!(map.get(&1).unwrap().count, 3);
+    assert_eq!(map.get(&2).unwrap().count, 1);
+    assert_eq!(map.get(&3).unwrap().count, 1);
+    map.validate(ValidateCompact::Compact).expect("map should be valid");
+}
+
+#[test]
+#[should_panic = "key hashes do not match"]
+fn get_or_insert_default_panics_for_non_matching_key() {
+    let mut map = IdHashMap::<CounterItem, HashBuilder, Alloc>::make_new();
+    map.get_or_insert_default(999);
+}
+
+#[test]
+fn get_or_insert_with() {
+    let mut map = IdHashMap::<TestItem, HashBuilder, Alloc>::make_new();
+    let item1 = TestItem::new(1, 'a', "x", "v1");
+    map.insert_unique(item1.clone()).unwrap();
+
+    // An existing item is returned without calling the closure.
+    let item = map.get_or_insert_with(TestKey1::new(&1), || {
+        panic!("closure should not be called for a present key")
+    });
+    assert_eq!(item, &item1);
+
+    let item2 = TestItem::new(2, 'b', "y", "v2");
+    let item = map.get_or_insert_with(TestKey1::new(&2), || item2.clone());
+    assert_eq!(item, &item2);
+
+    assert_eq!(map.len(), 2);
+    map.validate(ValidateCompact::Compact).expect("map should be valid");
+}
+
+#[test]
+#[should_panic = "must insert a value with the same key used for the lookup"]
+fn get_or_insert_with_panics_for_non_matching_key() {
+    let mut map = IdHashMap::<TestItem, HashBuilder, Alloc>::make_new();
+    map.get_or_insert_with(TestKey1::new(&1), || {
+        TestItem::new(2, 'a', "x", "v")
+    });
+}
+
+#[test]
+fn merge_resolves_conflicts() {
+    let mut map = IdHashMap::<TestItem, HashBuilder, Alloc>::make_new();
+    map.insert_unique(TestItem::new(1, 'a', "x", "old")).unwrap();
+    map.insert_unique(TestItem::new(2, 'b', "y", "old")).unwrap();
+
+    let mut other = IdHashMap::<TestItem, HashBuilder, Alloc>::make_new();
+    other.insert_unique(TestItem::new(2, 'c', "z", "new")).unwrap();
+    other.insert_unique(TestItem::new(3, 'd', "w", "new")).unwrap();
+
+    let mut calls = Vec::new();
+    map.merge(other, |existing, incoming| {
+        calls.push((existing.value.clone(), incoming.value.clone()));
+        TestItem::new(existing.key1, incoming.key2, existing.key3, "merged")
+    });
+
+    // `resolve` is only called for the conflicting key.
+    assert_eq!(calls, [("old".to_owned(), "new".to_owned())]);
+    assert_eq!(map.len(), 3);
+    assert_eq!(
+        map.get(&TestKey1::new(&2)),
+        Some(&TestItem::new(2, 'c', "y", "merged")),
+    );
+    assert_eq!(map.get(&TestKey1::new(&3)).unwrap().value, "new");
+    map.validate(ValidateCompact::NonCompact).expect("map should be valid");
+}
+
+#[test]
+#[should_panic = "key hashes do not match"]
+fn merge_panics_for_non_matching_key() {
+    let mut map = IdHashMap::<TestItem, HashBuilder, Alloc>::make_new();
+    map.insert_unique(TestItem::new(1, 'a', "x", "v")).unwrap();
+    let mut other = IdHashMap::<TestItem, HashBuilder, Alloc>::make_new();
+    other.insert_unique(TestItem::new(1, 'b', "y", "w")).unwrap();
+    map.merge(other, |_, _| TestItem::new(2, 'a', "x", "v"));
+}
+
 #[test]
 #[should_panic = "key hashes do not match"]
 fn insert_panics_for_non_matching_key() {
@@ -768,6 +1840,7 @@ fn test_retain_none() {
 
     assert_eq!(map.len(), 0);
     assert!(map.is_empty());
+    map.validate(ValidateCompact::Compact).expect("map should be valid");
 }
 
 #[test]
@@ -866,9 +1939,12 @@ fn test_clear_makes_compact() {
     map.validate(ValidateCompact::NonCompact)
         .expect("map should be valid but non-compact");
 
-    // Clear should make it compact again
+    // Clear should make it compact again, keeping its capacity.
+    let capacity = map.capacity();
     map.clear();
     assert!(map.is_empty());
+    assert_eq!(map.len(), 0);
+    assert_eq!(map.capacity(), capacity);
     map.validate(ValidateCompact::Compact)
         .expect("cleared map should be compact");
 }
@@ -3,12 +3,12 @@ use crate::hegel_support::{
 };
 use hegel::{TestCase, generators as gs};
 use iddqd::{
-    IdOrdItem, IdOrdMap, id_ord_map, id_upcast,
+    ExtendMode, IdOrdItem, IdOrdMap, id_ord_map, id_upcast,
     internal::{ValidateChaos, ValidateCompact},
 };
 use iddqd_test_utils::{
     borrowed_item::BorrowedItem,
-    eq_props::{assert_eq_props, assert_ne_props},
+    eq_props::{assert_eq_props, assert_hash_eq, assert_ne_props},
     naive_map::NaiveMap,
     test_item::{
         ChaosEq, ChaosOrd, ItemMap, KeyChaos, TestItem, TestKey1,
@@ -18,6 +18,9 @@ use iddqd_test_utils::{
 };
 use std::{
     borrow::Cow,
+    cmp::Ordering,
+    collections::{BTreeMap, BTreeSet},
+    ops::{Bound, RangeBounds},
     path::{Path, PathBuf},
 };
 
@@ -41,6 +44,75 @@ fn test_extend() {
     assert_eq!(map.get(&TestKey1::new(&2)).unwrap().value, "w");
 }
 
+#[test]
+fn test_extend_with_unique() {
+    let mut map = IdOrdMap::<TestItem>::make_new();
+    let existing = TestItem::new(1, 'a', "x", "v");
+    map.insert_unique(existing.clone()).unwrap();
+
+    // Stops at the first duplicate: items before it are kept, items after it
+    // are dropped.
+    let conflicting = TestItem::new(1, 'c', "z", "conflict");
+    let items = vec![
+        TestItem::new(2, 'b', "y", "w"),
+        conflicting.clone(),
+        TestItem::new(3, 'd', "q", "dropped"),
+    ];
+    let error = map.extend_with(items, ExtendMode::Unique).unwrap_err();
+    assert_eq!(error.new_item(), &conflicting);
+    assert_eq!(error.duplicates(), &[&existing]);
+
+    assert_eq!(map.len(), 2);
+    assert_eq!(map.get(&TestKey1::new(&2)).unwrap().value, "w");
+    assert_eq!(map.get(&TestKey1::new(&3)), None);
+    assert_eq!(map.get(&TestKey1::new(&1)).unwrap().value, "v");
+
+    // Overwrite mode matches `extend`.
+    map.extend_with(vec![conflicting], ExtendMode::Overwrite).unwrap();
+    assert_eq!(map.get(&TestKey1::new(&1)).unwrap().value, "conflict");
+}
+
+#[test]
+fn map_transforms_items() {
+    let mut map = IdOrdMap::<TestItem>::make_new();
+    for i in 0..4 {
+        map.insert_unique(TestItem::new(
+            i,
+            (b'a' + i) as char,
+            i.to_string(),
+            "v",
+        ))
+        .unwrap();
+    }
+
+    let mapped = map
+        .map(|mut item| {
+            item.value = format!("mapped-{}", item.key1);
+            item
+        })
+        .expect("keys are unchanged");
+    mapped
+        .validate(ValidateCompact::Compact, ValidateChaos::No)
+        .expect("map is valid");
+    assert_eq!(mapped.len(), 4);
+    for i in 0..4 {
+        assert_eq!(
+            mapped.get(&TestKey1::new(&i)).unwrap().value,
+            format!("mapped-{i}")
+        );
+    }
+
+    // Collapsing a key reports the first conflict.
+    let error = mapped
+        .map(|mut item| {
+            item.key1 = 0;
+            item
+        })
+        .unwrap_err();
+    assert_eq!(error.duplicates().len(), 1);
+    assert_ne!(error.new_item().value, error.duplicates()[0].value);
+}
+
 #[derive(Clone, Debug)]
 struct SimpleItem {
     key: u32,
@@ -273,6 +345,7 @@ impl IdOrdMapMachine {
         self.map
             .validate(self.compactness, ValidateChaos::No)
             .expect("map should be valid");
+        self.map.check_invariants().expect("map invariants should hold");
     }
 }
 
@@ -375,6 +448,9 @@ impl IdOrdMapMachine {
         let naive_res = self.naive.first();
 
         assert_eq!(map_res, naive_res);
+
+        let map_kv = self.map.first_key_value();
+        assert_eq!(map_kv, naive_res.map(|item| (item.key(), item)));
     }
 
     #[rule]
@@ -383,6 +459,9 @@ impl IdOrdMapMachine {
         let naive_res = self.naive.last();
 
         assert_eq!(map_res, naive_res);
+
+        let map_kv = self.map.last_key_value();
+        assert_eq!(map_kv, naive_res.map(|item| (item.key(), item)));
     }
 
     #[rule]
@@ -463,7 +542,7 @@ impl IdOrdMapMachine {
             let contains = item.value.contains(ch);
             if equals { contains } else { !contains }
         });
-        self.check_valid(CompactnessChange::NoLongerCompact);
+        self.check_valid(CompactnessChange::BecomesCompact);
     }
 
     #[rule]
@@ -481,7 +560,7 @@ impl IdOrdMapMachine {
             let matches = item.key1 % modulo == remainder;
             if equals { matches } else { !matches }
         });
-        self.check_valid(CompactnessChange::NoLongerCompact);
+        self.check_valid(CompactnessChange::BecomesCompact);
     }
 
     #[rule]
@@ -560,6 +639,293 @@ fn proptest_permutation_eq(tc: TestCase) {
     let map4 = IdOrdMap::from_iter_unique(set2).unwrap();
     assert_eq_props(&map1, &map3);
     assert_eq_props(&map3, &map4);
+    assert_hash_eq(&map1, &map2);
+    assert_hash_eq(&map3, &map4);
+}
+
+#[hegel::test(test_cases = 64)]
+fn proptest_ord_matches_sorted_vec(tc: TestCase) {
+    let set1 = draw_fill_batch(&tc);
+    let set2 = draw_fill_batch(&tc);
+
+    let map1 = IdOrdMap::<TestItem>::from_iter_unique(set1).unwrap();
+    let map2 = IdOrdMap::<TestItem>::from_iter_unique(set2).unwrap();
+    let vec1: Vec<_> = map1.iter().collect();
+    let vec2: Vec<_> = map2.iter().collect();
+
+    assert_eq!(map1.cmp(&map2), vec1.cmp(&vec2));
+    assert_eq!(map1.partial_cmp(&map2), Some(vec1.cmp(&vec2)));
+    assert_eq!(map2.cmp(&map1), vec2.cmp(&vec1));
+    assert_eq!(map1.cmp(&map1), Ordering::Equal);
+}
+
+#[hegel::test(test_cases = 64)]
+fn proptest_into_sorted_vec(tc: TestCase) {
+    let mut items = draw_fill_batch(&tc);
+    let map = IdOrdMap::<TestItem>::from_iter_unique(items.clone()).unwrap();
+    assert_eq!(map.clone().into_vec(), map.iter().cloned().collect::<Vec<_>>());
+
+    items.sort_by(|a, b| a.key().cmp(&b.key()));
+    assert_eq!(map.into_sorted_vec(), items);
+}
+
+#[hegel::test(test_cases = 64)]
+fn proptest_drain(tc: TestCase) {
+    let items = draw_fill_batch(&tc);
+    let mut map = IdOrdMap::<TestItem>::from_iter_unique(items).unwrap();
+    let expected: Vec<_> = map.iter().cloned().collect();
+    let capacity = map.capacity();
+    // Take some items from each end, then drop the iterator.
+    let front = tc.draw(gs::integers::<usize>().max_value(expected.len()));
+    let back =
+        tc.draw(gs::integers::<usize>().max_value(expected.len() - front));
+
+    let mut drain = map.drain();
+    assert_eq!(drain.len(), expected.len());
+    let actual_front: Vec<_> = drain.by_ref().take(front).collect();
+    let actual_back: Vec<_> = drain.by_ref().rev().take(back).collect();
+    assert_eq!(drain.len(), expected.len() - front - back);
+    drop(drain);
+
+    assert_eq!(actual_front, expected[..front]);
+    assert!(actual_back.iter().eq(expected.iter().rev().take(back)));
+    assert!(map.is_empty());
+    assert_eq!(map.capacity(), capacity);
+    map.validate(ValidateCompact::Compact, ValidateChaos::No)
+        .expect("drained map is valid");
+}
+
+#[hegel::test(test_cases = 64)]
+fn proptest_iter_double_ended(tc: TestCase) {
+    let items = draw_fill_batch(&tc);
+    let mut map = IdOrdMap::<TestItem>::from_iter_unique(items).unwrap();
+    let mut expected: Vec<_> = map.iter().cloned().collect();
+    // Take some items from each end, then collect the rest from the front.
+    let front = tc.draw(gs::integers::<usize>().max_value(expected.len()));
+    let back =
+        tc.draw(gs::integers::<usize>().max_value(expected.len() - front));
+    let middle = expected.len() - front - back;
+
+    assert!(map.iter().rev().eq(expected.iter().rev()));
+
+    let mut iter = map.iter();
+    assert_eq!(iter.len(), expected.len());
+    assert!(iter.by_ref().take(front).eq(&expected[..front]));
+    assert!(
+        iter.by_ref().rev().take(back).eq(expected.iter().rev().take(back))
+    );
+    assert_eq!(iter.len(), middle);
+    assert!(iter.eq(&expected[front..front + middle]));
+
+    let mut iter_mut = map.iter_mut();
+    assert_eq!(iter_mut.len(), expected.len());
+    for mut item in iter_mut.by_ref().rev().take(back) {
+        item.value.push_str("-back");
+    }
+    assert_eq!(iter_mut.len(), expected.len() - back);
+    for item in expected.iter_mut().rev().take(back) {
+        item.value.push_str("-back");
+    }
+    assert!(map.iter().eq(&expected));
+
+    let mut into_iter = IntoIterator::into_iter(map);
+    assert_eq!(into_iter.len(), expected.len());
+    let actual_back: Vec<_> = into_iter.by_ref().rev().take(back).collect();
+    assert_eq!(into_iter.len(), expected.len() - back);
+    let actual_front: Vec<_> = into_iter.collect();
+    assert!(actual_back.iter().eq(expected.iter().rev().take(back)));
+    assert_eq!(actual_front, expected[..expected.len() - back]);
+}
+
+#[hegel::test(test_cases = 256)]
+fn proptest_split_off(tc: TestCase) {
+    let mut model = draw_fill_batch(&tc);
+    model.sort_by_key(|item| item.key1);
+    let mut map =
+        IdOrdMap::<TestItem>::from_iter_unique(model.clone()).unwrap();
+
+    // Remove some items first, so that the map isn't compact going in.
+    let remove_count = tc.draw(gs::integers::<usize>().max_value(model.len()));
+    for _ in 0..remove_count {
+        let index = tc.draw(gs::integers::<usize>().max_value(model.len() - 1));
+        let item = model.remove(index);
+        map.remove(&item.key()).expect("item is present");
+    }
+
+    let split_key = tc.draw(gs::integers::<u8>());
+    let other = map.split_off(&TestKey1::new(&split_key));
+
+    let split_at = model.partition_point(|item| item.key1 < split_key);
+    assert_iter_eq(map.clone(), model[..split_at].iter().collect());
+    assert_iter_eq(other.clone(), model[split_at..].iter().collect());
+    map.validate(ValidateCompact::Compact, ValidateChaos::No)
+        .expect("self should be valid");
+    other
+        .validate(ValidateCompact::Compact, ValidateChaos::No)
+        .expect("other should be valid");
+}
+
+#[hegel::test(test_cases = 256)]
+fn proptest_append(tc: TestCase) {
+    let items =
+        if tc.draw(gs::booleans()) { draw_fill_batch(&tc) } else { Vec::new() };
+    let other_items = draw_fill_batch(&tc);
+
+    // Items from `other` replace items in `self` with the same key.
+    let mut model = BTreeMap::new();
+    for item in items.iter().chain(&other_items) {
+        model.insert(item.key1, item.clone());
+    }
+
+    let mut map = IdOrdMap::<TestItem>::from_iter_unique(items).unwrap();
+    let mut other =
+        IdOrdMap::<TestItem>::from_iter_unique(other_items).unwrap();
+    map.append(&mut other);
+
+    assert_iter_eq(map.clone(), model.values().collect());
+    assert!(other.is_empty());
+    map.validate(ValidateCompact::NonCompact, ValidateChaos::No)
+        .expect("self should be valid");
+    other
+        .validate(ValidateCompact::Compact, ValidateChaos::No)
+        .expect("other should be valid");
+}
+
+#[hegel::test(test_cases = 256)]
+fn proptest_cursor_mut(tc: TestCase) {
+    let mut model = draw_fill_batch(&tc);
+    model.sort_by_key(|item| item.key1);
+    let mut map =
+        IdOrdMap::<TestItem>::from_iter_unique(model.clone()).unwrap();
+
+    // Start the cursor at a random bound, tracking its position as the index
+    // of the item after it in the model.
+    let start_key = tc.draw(gs::integers::<u8>());
+    let start = TestKey1::new(&start_key);
+    let (mut pos, mut cursor) = match tc.draw(gs::integers::<u8>().max_value(2))
+    {
+        0 => (
+            model.partition_point(|item| item.key1 < start_key),
+            map.lower_bound_mut(Bound::Included(&start)),
+        ),
+        1 => (
+            model.partition_point(|item| item.key1 <= start_key),
+            map.lower_bound_mut(Bound::Excluded(&start)),
+        ),
+        _ => (0, map.lower_bound_mut(Bound::<&TestKey1>::Unbounded)),
+    };
+
+    let op_count = tc.draw(gs::integers::<usize>().max_value(32));
+    for _ in 0..op_count {
+        match tc.draw(gs::integers::<u8>().max_value(5)) {
+            0 => {
+                let expected = model.get(pos);
+                assert_eq!(cursor.next(), expected);
+                if expected.is_some() {
+                    pos += 1;
+                }
+            }
+            1 => {
+                let expected = pos.checked_sub(1).map(|p| &model[p]);
+                assert_eq!(cursor.prev(), expected);
+                if expected.is_some() {
+                    pos -= 1;
+                }
+            }
+            2 => {
+                let expected = (pos < model.len()).then(|| model.remove(pos));
+                assert_eq!(cursor.remove_next(), expected);
+            }
+            3 => {
+                let expected = (pos > 0).then(|| {
+                    pos -= 1;
+                    model.remove(pos)
+                });
+                assert_eq!(cursor.remove_prev(), expected);
+            }
+            op => {
+                let item = tc.draw(test_item());
+                let fits = (pos == 0 || model[pos - 1].key1 < item.key1)
+                    && (pos == model.len() || item.key1 < model[pos].key1);
+                let result = if op == 4 {
+                    cursor.insert_after(item.clone())
+                } else {
+                    cursor.insert_before(item.clone())
+                };
+                match result {
+                    Ok(()) => {
+                        assert!(fits, "inserted out-of-order item {item:?}");
+                        model.insert(pos, item);
+                        if op == 5 {
+                            pos += 1;
+                        }
+                    }
+                    Err(error) => {
+                        assert!(!fits, "rejected in-order item {item:?}");
+                        assert_eq!(error.into_item(), item);
+                    }
+                }
+            }
+        }
+        assert_eq!(cursor.peek_prev(), pos.checked_sub(1).map(|p| &model[p]));
+        assert_eq!(cursor.peek_next(), model.get(pos));
+    }
+
+    map.validate(ValidateCompact::NonCompact, ValidateChaos::No)
+        .expect("map is valid after cursor operations");
+    assert!(map.iter().eq(model.iter()));
+}
+
+#[test]
+fn drain_leaked_leaves_map_valid() {
+    let mut map = IdOrdMap::<TestItem>::make_new();
+    for i in 0..8 {
+        map.insert_unique(TestItem::new(i, 'a', "x", "v")).unwrap();
+    }
+
+    let mut drain = map.drain();
+    assert_eq!(drain.next().unwrap().key1, 0);
+    std::mem::forget(drain);
+
+    // The yielded item is gone, and the rest are still present.
+    assert_eq!(map.len(), 7);
+    assert_eq!(map.first().unwrap().key1, 1);
+    map.validate(ValidateCompact::NonCompact, ValidateChaos::No)
+        .expect("map is valid after leaking a drain");
+}
+
+#[test]
+fn remove_where_returns_items_in_key_order() {
+    let mut map = IdOrdMap::<TestItem>::make_new();
+    for i in [5, 2, 7, 4, 1, 6] {
+        map.insert_unique(TestItem::new(i, 'a', "x", "v")).unwrap();
+    }
+
+    let removed = map.remove_where(|item| item.key1 % 2 == 0);
+    let removed_keys: Vec<_> = removed.iter().map(|item| item.key1).collect();
+    assert_eq!(removed_keys, vec![2, 4, 6]);
+    let kept_keys: Vec<_> = map.iter().map(|item| item.key1).collect();
+    assert_eq!(kept_keys, vec![1, 5, 7]);
+    map.validate(ValidateCompact::NonCompact, ValidateChaos::No)
+        .expect("map is valid");
+}
+
+#[test]
+fn ord_examples() {
+    let empty = IdOrdMap::<TestItem>::make_new();
+    let mut map1 = IdOrdMap::<TestItem>::make_new();
+    map1.insert_unique(TestItem::new(1, 'a', "x", "v")).unwrap();
+    let mut map2 = map1.clone();
+    map2.insert_unique(TestItem::new(2, 'b', "y", "v")).unwrap();
+    let mut map3 = IdOrdMap::<TestItem>::make_new();
+    map3.insert_unique(TestItem::new(2, 'a', "x", "v")).unwrap();
+
+    // The empty map sorts first, and a prefix sorts before a longer map.
+    assert!(empty < map1);
+    assert!(map1 < map2);
+    // Items are compared in key order, so the first differing item decides.
+    assert!(map2 < map3);
+    assert_eq!(map3.cmp(&map3.clone()), Ordering::Equal);
 }
 
 // Test various conditions for non-equality.
@@ -787,6 +1153,8 @@ fn test_retain_none() {
 
     assert_eq!(map.len(), 0);
     assert!(map.is_empty());
+    map.validate(ValidateCompact::Compact, ValidateChaos::No)
+        .expect("map should be valid");
 }
 
 #[test]
@@ -873,6 +1241,173 @@ fn test_retain_preserves_ordering() {
     assert_eq!(keys, vec![1, 3, 5, 7]);
 }
 
+#[test]
+fn range_matches_btree_set() {
+    // Keys with gaps, so that bounds fall both on and between keys. Remove one
+    // item to leave a hole in the item set as well.
+    let mut map = IdOrdMap::new();
+    let mut expected = BTreeSet::new();
+    for key in (0..20).step_by(2) {
+        map.insert_unique(SimpleItem { key }).unwrap();
+        expected.insert(key);
+    }
+    map.remove(&6).unwrap();
+    expected.remove(&6);
+
+    let bounds =
+        |b: u32| [Bound::Unbounded, Bound::Included(b), Bound::Excluded(b)];
+    for lo in 0..21 {
+        for hi in 0..21 {
+            for start in bounds(lo) {
+                for end in bounds(hi) {
+                    let range = (start, end);
+                    let actual: Vec<_> =
+                        map.range(range).map(|item| item.key).collect();
+                    // BTreeSet::range panics on inverted or empty excluded
+                    // ranges, while IdOrdMap::range returns nothing.
+                    let expected: Vec<_> = expected
+                        .iter()
+                        .copied()
+                        .filter(|key| range.contains(key))
+                        .collect();
+                    assert_eq!(actual, expected, "range {range:?}");
+
+                    let mut reversed: Vec<_> =
+                        map.range(range).rev().map(|item| item.key).collect();
+                    reversed.reverse();
+                    assert_eq!(reversed, expected, "reversed range {range:?}");
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn range_rev_half_open() {
+    let mut map = IdOrdMap::new();
+    for key in [5, 1, 9, 3, 7] {
+        map.insert_unique(SimpleItem { key }).unwrap();
+    }
+
+    // Walk the half-open range from the top down.
+    let keys: Vec<_> = map.range(3..9).rev().map(|item| item.key).collect();
+    assert_eq!(keys, [7, 5, 3]);
+
+    // Mixing both ends meets in the middle without repeating items.
+    let mut range = map.range(1..);
+    assert_eq!(range.next().map(|item| item.key), Some(1));
+    assert_eq!(range.next_back().map(|item| item.key), Some(9));
+    assert_eq!(range.next_back().map(|item| item.key), Some(7));
+    assert_eq!(range.next().map(|item| item.key), Some(3));
+    assert_eq!(range.next().map(|item| item.key), Some(5));
+    assert!(range.next().is_none());
+    assert!(range.next_back().is_none());
+}
+
+#[test]
+fn iter_by_derived_order() {
+    let mut map = IdOrdMap::<TestItem>::make_new();
+    map.insert_unique(TestItem::new(1, 'c', "x", "v")).unwrap();
+    map.insert_unique(TestItem::new(2, 'a', "x", "v")).unwrap();
+    map.insert_unique(TestItem::new(3, 'b', "x", "v")).unwrap();
+    map.insert_unique(TestItem::new(4, 'a', "x", "v")).unwrap();
+    map.insert_unique(TestItem::new(0, 'z', "x", "v")).unwrap();
+    // Leave a hole in the item set.
+    map.remove(&TestKey1::new(&0)).unwrap();
+
+    // Ties on key2 are broken by key order.
+    let iter = map.iter_by(|item| item.key2);
+    assert_eq!(iter.len(), 4);
+    let keys: Vec<_> = iter.map(|item| item.key1).collect();
+    assert_eq!(keys, [2, 4, 3, 1]);
+
+    // The derived key may borrow from the item.
+    let keys: Vec<_> = map
+        .iter_by(|item| std::cmp::Reverse(item.key3.as_str()))
+        .rev()
+        .map(|item| item.key1)
+        .collect();
+    assert_eq!(keys, [4, 3, 2, 1]);
+}
+
+#[test]
+fn keys_and_values() {
+    let mut map = IdOrdMap::<TestItem>::make_new();
+    map.insert_unique(TestItem::new(3, 'c', "z", "v3")).unwrap();
+    map.insert_unique(TestItem::new(1, 'a', "x", "v1")).unwrap();
+    map.insert_unique(TestItem::new(2, 'b', "y", "v2")).unwrap();
+
+    // Keys are returned in sorted order.
+    assert_eq!(map.keys().len(), 3);
+    assert!(map.keys().eq([1, 2, 3].iter().map(TestKey1::new)));
+    assert!(map.values().eq(map.iter()));
+
+    for mut item in map.values_mut() {
+        item.value.push_str("-updated");
+    }
+    for item in map.values() {
+        assert!(item.value.ends_with("-updated"), "{item:?} was updated");
+    }
+}
+
+#[test]
+fn into_keys_and_values() {
+    let mut map = IdOrdMap::<SimpleItem>::make_new();
+    map.insert_unique(SimpleItem { key: 20 }).unwrap();
+    map.insert_unique(SimpleItem { key: 1 }).unwrap();
+    map.insert_unique(SimpleItem { key: 10 }).unwrap();
+
+    let keys: Vec<u32> = map.clone().into_keys().collect();
+    // Keys are returned in sorted order.
+    assert_eq!(keys, [1, 10, 20]);
+
+    let values: Vec<u32> = map.into_values().map(|item| item.key).collect();
+    assert_eq!(values, [1, 10, 20]);
+
+    // Keys that borrow from outside the item can outlive the map.
+    let keys: Vec<&str> = {
+        let mut map = IdOrdMap::<BorrowedItem<'_>>::new();
+        for key1 in ["foo", "bar"] {
+            map.insert_unique(BorrowedItem {
+                key1,
+                key2: Cow::Borrowed(b"x"),
+                key3: Path::new("x"),
+            })
+            .unwrap();
+        }
+        map.into_keys().collect()
+    };
+    assert_eq!(keys.len(), 2);
+    assert!(keys.contains(&"foo") && keys.contains(&"bar"));
+}
+
+#[test]
+fn search_by() {
+    let mut map = IdOrdMap::<TestItem>::make_new();
+    for key1 in [10, 2, 7, 4, 12] {
+        map.insert_unique(TestItem::new(key1, 'a', "x", "v")).unwrap();
+    }
+
+    // Searching by key1 matches get.
+    for key1 in 0..16 {
+        let found = map.search_by(|item| item.key1.cmp(&key1));
+        assert_eq!(found, map.get(&TestKey1::new(&key1)), "key1 = {key1}");
+    }
+
+    // Search by a coarser projection of the key: any item in the bucket may
+    // be returned.
+    let found = map.search_by(|item| (item.key1 / 5).cmp(&1)).unwrap();
+    assert_eq!(found.key1, 7);
+    let found = map.search_by(|item| (item.key1 / 5).cmp(&2)).unwrap();
+    assert!(found.key1 == 10 || found.key1 == 12, "found {found:?}");
+    assert!(map.search_by(|item| (item.key1 / 5).cmp(&3)).is_none());
+
+    // A comparator inconsistent with the key order doesn't break the map.
+    let _ = map.search_by(|item| (item.key1 % 3).cmp(&1));
+    map.validate(ValidateCompact::Compact, ValidateChaos::No)
+        .expect("map should be valid");
+}
+
 #[test]
 fn test_retain_empty_map() {
     let mut map = IdOrdMap::<TestItem>::make_new();
@@ -903,9 +1438,12 @@ fn test_clear_makes_compact() {
     map.validate(ValidateCompact::NonCompact, ValidateChaos::No)
         .expect("map should be valid but non-compact");
 
-    // Clear should make it compact again.
+    // Clear should make it compact again, keeping its capacity.
+    let capacity = map.capacity();
     map.clear();
     assert!(map.is_empty());
+    assert_eq!(map.len(), 0);
+    assert_eq!(map.capacity(), capacity);
     map.validate(ValidateCompact::Compact, ValidateChaos::No)
         .expect("cleared map should be compact");
 }
@@ -1012,6 +1550,21 @@ fn borrowed_item() {
         };
         let mut v = entry.get_mut();
         v.key2 = Cow::Borrowed(b"quux");
+        drop(v);
+
+        // entry_ref accepts a borrowed query that can be dropped before the
+        // entry is used.
+        let entry = {
+            let query = String::from("foo");
+            map.entry_ref(query.as_str())
+        };
+        let id_ord_map::Entry::Occupied(entry) = entry else {
+            panic!("Entry should be occupied")
+        };
+        assert_eq!(entry.get().key1, "foo");
+
+        let entry = map.entry_ref("missing");
+        assert!(matches!(entry, id_ord_map::Entry::Vacant(_)));
     }
 
     entry_api_tests(&mut map);
@@ -1049,6 +1602,7 @@ fn borrowed_item_retain_non_static() {
 
 mod macro_tests {
     use super::*;
+    use iddqd::{Comparable, Equivalent};
 
     #[derive(Debug, PartialEq)]
     struct User {
@@ -1098,6 +1652,66 @@ mod macro_tests {
             User { id: 1, name: "Bob".to_string() },
         };
     }
+
+    #[derive(Debug)]
+    struct Artifact {
+        name: String,
+        version: u32,
+    }
+
+    #[derive(Debug, Eq, PartialEq, PartialOrd, Ord)]
+    struct ArtifactKey<'a> {
+        name: &'a str,
+        version: u32,
+    }
+
+    impl IdOrdItem for Artifact {
+        type Key<'a> = ArtifactKey<'a>;
+        fn key(&self) -> Self::Key<'_> {
+            ArtifactKey { name: &self.name, version: self.version }
+        }
+        id_upcast!();
+    }
+
+    struct OwnedArtifactKey {
+        name: String,
+        version: u32,
+    }
+
+    iddqd::impl_equivalent_borrowed!(
+        OwnedArtifactKey => ArtifactKey<'a>,
+        |key| ArtifactKey { name: &key.name, version: key.version },
+    );
+
+    #[test]
+    fn macro_equivalent_borrowed() {
+        let map = id_ord_map! {
+            Artifact { name: "a".to_string(), version: 1 },
+            Artifact { name: "a".to_string(), version: 2 },
+            Artifact { name: "b".to_string(), version: 1 },
+        };
+
+        let owned = |name: &str, version| OwnedArtifactKey {
+            name: name.to_string(),
+            version,
+        };
+        assert_eq!(map.get(&owned("a", 2)).unwrap().version, 2);
+        assert_eq!(map.get(&owned("b", 1)).unwrap().name, "b");
+        assert!(map.get(&owned("b", 2)).is_none());
+
+        // The generated impls go in both directions.
+        let key = ArtifactKey { name: "a", version: 1 };
+        assert!(Equivalent::equivalent(&key, &owned("a", 1)));
+        assert!(!Equivalent::equivalent(&key, &owned("a", 2)));
+        assert_eq!(
+            Comparable::compare(&key, &owned("b", 0)),
+            std::cmp::Ordering::Less,
+        );
+        assert_eq!(
+            Comparable::compare(&owned("b", 0), &key),
+            std::cmp::Ordering::Greater,
+        );
+    }
 }
 
 #[cfg(feature = "serde")]
@@ -1114,6 +1728,42 @@ mod serde_tests {
         let values = draw_random_batch(&tc);
         assert_serialize_roundtrip::<IdOrdMap<TestItem>>(values);
     }
+
+    #[hegel::test(test_cases = 256)]
+    fn proptest_deserialize_any_order(tc: TestCase) {
+        use iddqd::internal::{ValidateChaos, ValidateCompact};
+        use iddqd_test_utils::serde_json;
+
+        // Serializing a Vec rather than a map lets the input be in any order,
+        // and contain duplicates.
+        let values: Vec<TestItem> = draw_random_batch(&tc);
+        let mut sorted = values.clone();
+        sorted.sort_by_key(|item| item.key1);
+
+        for input in [values, sorted] {
+            let json = serde_json::to_string(&input).unwrap();
+            let result = serde_json::from_str::<IdOrdMap<TestItem>>(&json);
+
+            let mut keys: Vec<u8> =
+                input.iter().map(|item| item.key1).collect();
+            keys.sort_unstable();
+            let has_duplicates = keys.windows(2).any(|w| w[0] == w[1]);
+
+            match result {
+                Ok(map) => {
+                    assert!(!has_duplicates, "duplicates should be rejected");
+                    map.validate(ValidateCompact::Compact, ValidateChaos::No)
+                        .expect("map should be valid");
+                    let map_keys: Vec<u8> =
+                        map.iter().map(|item| item.key1).collect();
+                    assert_eq!(map_keys, keys);
+                }
+                Err(error) => {
+                    assert!(has_duplicates, "unexpected error: {error}");
+                }
+            }
+        }
+    }
 }
 
 #[cfg(feature = "proptest")]
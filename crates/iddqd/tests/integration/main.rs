@@ -1,19 +1,25 @@
+#[cfg(feature = "arbitrary")]
+mod arbitrary_tests;
 mod bi_hash_map;
+mod bi_ord_map;
+#[cfg(feature = "concurrent")]
+mod concurrent;
 mod hegel_support;
 mod id_hash_map;
-#[cfg(feature = "std")]
 mod id_ord_map;
-#[cfg(all(feature = "std", feature = "default-hasher"))]
+#[cfg(feature = "default-hasher")]
 mod pathological;
+mod quad_hash_map;
+#[cfg(feature = "rayon")]
+mod rayon_tests;
+#[cfg(feature = "schemars1")]
+mod schemars1_tests;
 #[cfg(feature = "schemars08")]
 mod schemars_tests;
-#[cfg(all(feature = "serde", feature = "std", feature = "default-hasher"))]
+#[cfg(all(feature = "serde", feature = "default-hasher"))]
+mod serde_keyed;
+#[cfg(all(feature = "serde", feature = "default-hasher"))]
 mod serde_size_hint;
-#[cfg(all(
-    feature = "std",
-    feature = "default-hasher",
-    target_pointer_width = "64",
-    not(miri)
-))]
+#[cfg(all(feature = "default-hasher", target_pointer_width = "64", not(miri)))]
 mod size_tests;
 mod tri_hash_map;
@@ -7,8 +7,10 @@
 
 use core::cell::Cell;
 use iddqd::{
-    BiHashItem, BiHashMap, Comparable, Equivalent, IdHashItem, IdHashMap,
-    IdOrdItem, IdOrdMap, TriHashItem, TriHashMap, bi_hash_map, bi_upcast,
+    BiHashItem, BiHashMap, Comparable, Equivalent, ExtendMode, IdHashItem,
+    IdHashMap, IdOrdItem, IdOrdMap, TriHashItem, TriHashMap, bi_hash_map,
+    bi_upcast,
+    errors::MapIntegrityError,
     id_hash_map, id_ord_map, id_upcast,
     internal::{ValidateChaos, ValidateCompact},
     tri_upcast,
@@ -417,6 +419,18 @@ fn lying_eq_no_ub() {
     assert_eq!(count, len);
 }
 
+#[test]
+fn lying_eq_check_invariants_reports_missing_key() {
+    let mut map = IdHashMap::<LyingEqItem>::new();
+    map.insert_unique(LyingEqItem { id: 0 }).unwrap();
+    // The item can't be found through its own key, since Eq always returns
+    // false.
+    assert_eq!(
+        map.check_invariants(),
+        Err(MapIntegrityError::MissingKey { table: "key" }),
+    );
+}
+
 // Test: pathological Eq on hash keys must not let table-driven mutable paths
 // yield overlapping indexes after remove/reinsert.
 
@@ -1108,6 +1122,70 @@ fn id_hash_flip_key_from_iter_unique_inserts_under_stale_hash() {
     );
 }
 
+#[test]
+fn id_hash_flip_key_try_insert_reports_first_duplicate() {
+    let mut map =
+        IdHashMap::<FlipItem, foldhash::fast::FixedState>::with_hasher(
+            foldhash::fast::FixedState::with_seed(0),
+        );
+    for id in 0..4u32 {
+        map.insert_unique(FlipItem::plain(id)).unwrap();
+    }
+
+    // The first key call reports a duplicate. Any later call would return a
+    // fresh key, so the error must come from the first attempt.
+    let error = map
+        .try_insert(FlipItem::flips_after_first_key_call(1, 99))
+        .expect_err("the first key call collides with key 1");
+    assert_eq!(error.duplicates().len(), 1);
+    assert_eq!(error.duplicates()[0].id, 1);
+
+    assert_eq!(map.len(), 4);
+    map.validate(ValidateCompact::Compact)
+        .expect("map is unchanged after a failed try_insert");
+}
+
+#[test]
+fn id_hash_flip_key_extend_with_unique_reports_first_duplicate() {
+    let mut map =
+        IdHashMap::<FlipItem, foldhash::fast::FixedState>::with_hasher(
+            foldhash::fast::FixedState::with_seed(0),
+        );
+    map.insert_unique(FlipItem::plain(1)).unwrap();
+
+    // The error must come from the first attempt to insert the flipping item,
+    // and the items after it must not be inserted.
+    let error = map
+        .extend_with(
+            [FlipItem::flips_after_first_key_call(1, 99), FlipItem::plain(2)],
+            ExtendMode::Unique,
+        )
+        .expect_err("the first key call collides with key 1");
+    assert_eq!(error.duplicates()[0].id, 1);
+
+    assert_eq!(map.len(), 1);
+    map.validate(ValidateCompact::Compact)
+        .expect("map is unchanged after a failed extend_with");
+}
+
+#[test]
+fn id_ord_flip_key_extend_with_unique_reports_first_duplicate() {
+    let mut map = IdOrdMap::<FlipItem>::new();
+    map.insert_unique(FlipItem::plain(1)).unwrap();
+
+    let error = map
+        .extend_with(
+            [FlipItem::flips_after_first_key_call(1, 99), FlipItem::plain(2)],
+            ExtendMode::Unique,
+        )
+        .expect_err("the first key call collides with key 1");
+    assert_eq!(error.duplicates()[0].id, 1);
+
+    assert_eq!(map.len(), 1);
+    map.validate(ValidateCompact::Compact, ValidateChaos::No)
+        .expect("map is unchanged after a failed extend_with");
+}
+
 #[test]
 fn id_ord_flip_key_insert_overwrite_inserts_logical_duplicate() {
     let mut map = IdOrdMap::<FlipItem>::new();
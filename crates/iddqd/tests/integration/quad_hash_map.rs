@@ -0,0 +1,466 @@
+use hegel::{TestCase, generators as gs};
+use iddqd::{
+    ExtendMode, QuadHashItem, QuadHashMap, internal::ValidateCompact,
+    quad_hash_map, quad_upcast,
+};
+use iddqd_test_utils::{
+    eq_props::assert_hash_eq,
+    test_item::{Alloc, HashBuilder},
+};
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+struct QuadItem {
+    key1: u8,
+    key2: char,
+    key3: String,
+    key4: u16,
+    value: u32,
+}
+
+impl QuadItem {
+    fn new(key1: u8, key2: char, key3: &str, key4: u16, value: u32) -> Self {
+        Self { key1, key2, key3: key3.to_owned(), key4, value }
+    }
+}
+
+impl QuadHashItem for QuadItem {
+    type K1<'a> = u8;
+    type K2<'a> = char;
+    type K3<'a> = &'a str;
+    type K4<'a> = u16;
+
+    fn key1(&self) -> Self::K1<'_> {
+        self.key1
+    }
+
+    fn key2(&self) -> Self::K2<'_> {
+        self.key2
+    }
+
+    fn key3(&self) -> Self::K3<'_> {
+        &self.key3
+    }
+
+    fn key4(&self) -> Self::K4<'_> {
+        self.key4
+    }
+
+    quad_upcast!();
+}
+
+fn make_map() -> QuadHashMap<QuadItem, HashBuilder, Alloc> {
+    QuadHashMap::with_hasher_in(HashBuilder::default(), Alloc::default())
+}
+
+#[test]
+fn debug_impls() {
+    let mut map = make_map();
+    map.insert_unique(QuadItem::new(1, 'a', "x", 10, 0)).unwrap();
+    map.insert_unique(QuadItem::new(20, 'b', "y", 11, 1)).unwrap();
+
+    assert_eq!(
+        format!("{map:?}"),
+        // Iteration is in insertion order.
+        "{{k1: 1, k2: 'a', k3: \"x\", k4: 10}: \
+          QuadItem { key1: 1, key2: 'a', key3: \"x\", key4: 10, value: 0 }, \
+          {k1: 20, k2: 'b', k3: \"y\", k4: 11}: \
+          QuadItem { key1: 20, key2: 'b', key3: \"y\", key4: 11, value: 1 }}",
+    );
+}
+
+#[test]
+fn test_insert_unique() {
+    let mut map = make_map();
+    map.insert_unique(QuadItem::new(1, 'a', "x", 10, 0)).unwrap();
+    map.insert_unique(QuadItem::new(2, 'b', "y", 11, 0)).unwrap();
+
+    // A conflict on any single key is rejected.
+    for item in [
+        QuadItem::new(1, 'c', "z", 12, 0),
+        QuadItem::new(3, 'a', "z", 12, 0),
+        QuadItem::new(3, 'c', "x", 12, 0),
+        QuadItem::new(3, 'c', "z", 10, 0),
+    ] {
+        let error = map.insert_unique(item.clone()).unwrap_err();
+        assert_eq!(error.new_item(), &item);
+        assert_eq!(
+            error.duplicates(),
+            vec![&QuadItem::new(1, 'a', "x", 10, 0)],
+        );
+    }
+
+    // A conflict with two different items reports both, in index order.
+    let error =
+        map.insert_unique(QuadItem::new(1, 'c', "z", 11, 0)).unwrap_err();
+    assert_eq!(
+        error.duplicates(),
+        vec![
+            &QuadItem::new(1, 'a', "x", 10, 0),
+            &QuadItem::new(2, 'b', "y", 11, 0),
+        ],
+    );
+
+    assert_eq!(map.len(), 2);
+    map.validate(ValidateCompact::Compact).expect("map is valid");
+}
+
+#[test]
+fn test_insert_overwrite() {
+    let mut map = make_map();
+    map.insert_unique(QuadItem::new(1, 'a', "x", 10, 0)).unwrap();
+    map.insert_unique(QuadItem::new(2, 'b', "y", 11, 0)).unwrap();
+    map.insert_unique(QuadItem::new(3, 'c', "z", 12, 0)).unwrap();
+
+    // Conflicts with the first item on key1 and the third on key4.
+    let mut overwritten =
+        map.insert_overwrite(QuadItem::new(1, 'd', "w", 12, 1));
+    overwritten.sort();
+    assert_eq!(
+        overwritten,
+        vec![
+            QuadItem::new(1, 'a', "x", 10, 0),
+            QuadItem::new(3, 'c', "z", 12, 0),
+        ],
+    );
+    assert_eq!(map.len(), 2);
+    assert_eq!(map.get4(&12).unwrap().value, 1);
+    assert!(map.get2(&'a').is_none());
+    assert!(map.get3("z").is_none());
+    map.validate(ValidateCompact::NonCompact).expect("map is valid");
+}
+
+#[test]
+fn get_and_remove() {
+    let item = QuadItem::new(1, 'a', "x", 10, 0);
+    let mut map = make_map();
+    map.insert_unique(item.clone()).unwrap();
+    map.insert_unique(QuadItem::new(2, 'b', "y", 11, 0)).unwrap();
+
+    assert_eq!(map.get1(&1), Some(&item));
+    assert_eq!(map.get2(&'a'), Some(&item));
+    assert_eq!(map.get3("x"), Some(&item));
+    assert_eq!(map.get4(&10), Some(&item));
+    assert_eq!(map.get_unique(&1, &'a', "x", &10), Some(&item));
+    assert_eq!(map.get_unique(&1, &'a', "x", &11), None);
+    assert!(map.contains_key_unique(&1, &'a', "x", &10));
+
+    map.get4_mut(&10).unwrap().value = 5;
+    assert_eq!(map.get1(&1).unwrap().value, 5);
+
+    assert_eq!(map.remove_unique(&1, &'b', "x", &10), None);
+    assert_eq!(map.remove4(&10).unwrap().key1, 1);
+    assert!(!map.contains_key1(&1));
+    assert!(!map.contains_key2(&'a'));
+    assert!(!map.contains_key3("x"));
+    assert!(!map.contains_key4(&10));
+    assert_eq!(map.remove3("y").unwrap().key1, 2);
+    assert!(map.is_empty());
+    map.validate(ValidateCompact::NonCompact).expect("map is valid");
+}
+
+#[test]
+fn test_extend_with_unique() {
+    let mut map = make_map();
+    map.insert_unique(QuadItem::new(1, 'a', "x", 10, 0)).unwrap();
+
+    let error = map
+        .extend_with(
+            [
+                QuadItem::new(2, 'b', "y", 11, 0),
+                QuadItem::new(3, 'c', "z", 10, 0),
+                QuadItem::new(4, 'd', "w", 13, 0),
+            ],
+            ExtendMode::Unique,
+        )
+        .unwrap_err();
+    assert_eq!(error.new_item(), &QuadItem::new(3, 'c', "z", 10, 0));
+    assert_eq!(error.duplicates(), vec![&QuadItem::new(1, 'a', "x", 10, 0)]);
+
+    // Items before the conflicting one were inserted.
+    assert_eq!(map.len(), 2);
+    assert!(map.contains_key1(&2));
+    assert!(!map.contains_key1(&4));
+    map.validate(ValidateCompact::Compact).expect("map is valid");
+}
+
+#[test]
+fn from_iter_unique() {
+    let items =
+        [QuadItem::new(1, 'a', "x", 10, 0), QuadItem::new(2, 'b', "y", 11, 0)];
+    let map = QuadHashMap::<_, HashBuilder, Alloc>::from_iter_unique(
+        items.iter().cloned(),
+    )
+    .unwrap();
+    assert_eq!(map.len(), 2);
+    map.validate(ValidateCompact::Compact).expect("map is valid");
+
+    // A value conflicting with two items reports both of them.
+    let error = QuadHashMap::<_, HashBuilder, Alloc>::from_iter_unique(
+        items.iter().cloned().chain([QuadItem::new(1, 'c', "y", 12, 0)]),
+    )
+    .unwrap_err();
+    assert_eq!(error.new_item(), &QuadItem::new(1, 'c', "y", 12, 0));
+    let mut duplicates = error.duplicates().to_vec();
+    duplicates.sort();
+    assert_eq!(duplicates, items);
+}
+
+#[test]
+fn entry_examples() {
+    let mut map = make_map();
+    map.insert_unique(QuadItem::new(1, 'a', "x", 10, 0)).unwrap();
+    map.insert_unique(QuadItem::new(2, 'b', "y", 11, 0)).unwrap();
+
+    // All keys missing: vacant.
+    match map.entry(3, 'c', "z", 12) {
+        quad_hash_map::Entry::Vacant(entry) => {
+            entry.insert(QuadItem::new(3, 'c', "z", 12, 0)).value = 5;
+        }
+        quad_hash_map::Entry::Occupied(_) => panic!("should be vacant"),
+    }
+    assert_eq!(map.get4(&12).unwrap().value, 5);
+
+    // All keys match one item: unique.
+    match map.entry(3, 'c', "z", 12) {
+        quad_hash_map::Entry::Occupied(mut entry) => {
+            assert!(entry.is_unique());
+            entry.get_mut().as_unique().unwrap().value = 6;
+        }
+        quad_hash_map::Entry::Vacant(_) => panic!("should be occupied"),
+    }
+    assert_eq!(map.get1(&3).unwrap().value, 6);
+
+    // Key2 and key3 point at the same item, and key4 is missing.
+    match map.entry(1, 'b', "y", 20) {
+        quad_hash_map::Entry::Occupied(mut entry) => {
+            assert!(entry.is_non_unique());
+            {
+                let entry_ref = entry.get();
+                assert_eq!(entry_ref.by_key1().unwrap().key1, 1);
+                assert_eq!(entry_ref.by_key2().unwrap().key1, 2);
+                assert_eq!(entry_ref.by_key3().unwrap().key1, 2);
+                assert!(entry_ref.by_key4().is_none());
+            }
+            {
+                // The second item is only borrowed once, for key2.
+                let mut entry_mut = entry.get_mut();
+                assert!(entry_mut.by_key1().is_some());
+                assert!(entry_mut.by_key2().is_some());
+                assert!(entry_mut.by_key3().is_none());
+                assert!(entry_mut.by_key4().is_none());
+            }
+            let mut removed = entry.remove();
+            removed.sort();
+            assert_eq!(
+                removed,
+                vec![
+                    QuadItem::new(1, 'a', "x", 10, 0),
+                    QuadItem::new(2, 'b', "y", 11, 0),
+                ],
+            );
+        }
+        quad_hash_map::Entry::Vacant(_) => panic!("should be occupied"),
+    }
+    assert_eq!(map.len(), 1);
+    map.validate(ValidateCompact::NonCompact).expect("map is valid");
+}
+
+#[test]
+#[should_panic(expected = "key4 hashes do not match")]
+fn entry_insert_panics_on_key_mismatch() {
+    let mut map = make_map();
+    let quad_hash_map::Entry::Vacant(entry) = map.entry(1, 'a', "x", 10) else {
+        panic!("should be vacant");
+    };
+    entry.insert(QuadItem::new(1, 'a', "x", 11, 0));
+}
+
+#[test]
+fn retain_and_remove_where() {
+    let mut map = make_map();
+    for i in 0..8 {
+        map.insert_unique(QuadItem::new(
+            i,
+            char::from(b'a' + i),
+            &i.to_string(),
+            u16::from(i),
+            u32::from(i),
+        ))
+        .unwrap();
+    }
+
+    map.retain(|item| item.value % 2 == 0);
+    assert_eq!(map.len(), 4);
+    // Retain compacts the map, like the other hash maps.
+    map.validate(ValidateCompact::Compact).expect("map is valid");
+    for i in 0..8 {
+        assert_eq!(map.contains_key3(i.to_string().as_str()), i % 2 == 0);
+    }
+
+    let mut removed = map.remove_where(|item| item.key1 < 4);
+    removed.sort();
+    assert_eq!(
+        removed.iter().map(|item| item.key1).collect::<Vec<_>>(),
+        vec![0, 2],
+    );
+    assert!(!map.contains_key2(&'a'));
+    assert!(!map.contains_key4(&2));
+    map.validate(ValidateCompact::NonCompact).expect("map is valid");
+
+    map.shrink_to_fit();
+    map.validate(ValidateCompact::Compact).expect("map is valid");
+    let mut items = map.into_vec();
+    items.sort();
+    assert_eq!(
+        items.iter().map(|item| item.key1).collect::<Vec<_>>(),
+        vec![4, 6],
+    );
+}
+
+#[test]
+fn permutation_eq() {
+    let items = [
+        QuadItem::new(1, 'a', "x", 10, 0),
+        QuadItem::new(2, 'b', "y", 11, 0),
+        QuadItem::new(3, 'c', "z", 12, 0),
+    ];
+    let map1: QuadHashMap<_, HashBuilder, Alloc> =
+        items.iter().cloned().collect();
+    let map2: QuadHashMap<_, HashBuilder, Alloc> =
+        items.iter().rev().cloned().collect();
+    assert_eq!(map1, map2);
+    assert_hash_eq(&map1, &map2);
+
+    // Same keys but a different value.
+    let mut map3 = map2.clone();
+    map3.get1_mut(&1).unwrap().value = 1;
+    assert_ne!(map1, map3);
+
+    // The same key1 and key2 pointing to different items.
+    let map4: QuadHashMap<_, HashBuilder, Alloc> = [
+        QuadItem::new(1, 'b', "x", 10, 0),
+        QuadItem::new(2, 'a', "y", 11, 0),
+        QuadItem::new(3, 'c', "z", 12, 0),
+    ]
+    .into_iter()
+    .collect();
+    assert_ne!(map1, map4);
+}
+
+#[test]
+#[should_panic(expected = "key4 changed during RefMut borrow")]
+fn get_mut_panics_if_key4_changes() {
+    let mut map = make_map();
+    map.insert_unique(QuadItem::new(1, 'a', "x", 10, 0)).unwrap();
+    map.get1_mut(&1).unwrap().key4 = 11;
+}
+
+#[test]
+fn macro_basic() {
+    let map = quad_hash_map! {
+        HashBuilder;
+        QuadItem::new(1, 'a', "x", 10, 0),
+        QuadItem::new(2, 'b', "y", 11, 0),
+    };
+    assert_eq!(map.len(), 2);
+    assert_eq!(map.get4(&11).unwrap().key1, 2);
+}
+
+#[test]
+#[should_panic(expected = "DuplicateItem")]
+fn macro_panics_on_duplicate() {
+    let _ = quad_hash_map! {
+        HashBuilder;
+        QuadItem::new(1, 'a', "x", 10, 0),
+        QuadItem::new(2, 'b', "y", 10, 0),
+    };
+}
+
+#[hegel::composite]
+fn quad_item(tc: TestCase) -> QuadItem {
+    // Use small key spaces so that conflicts are common.
+    let key1 = tc.draw(gs::integers::<u8>().max_value(15));
+    let key2 = char::from(tc.draw(gs::integers::<u8>().max_value(15)) + b'a');
+    let key3 = tc.draw(gs::integers::<u8>().max_value(15)).to_string();
+    let key4 = tc.draw(gs::integers::<u16>().max_value(15));
+    let value = tc.draw(gs::integers::<u32>());
+    QuadItem { key1, key2, key3, key4, value }
+}
+
+fn conflicts(a: &QuadItem, b: &QuadItem) -> bool {
+    a.key1 == b.key1 || a.key2 == b.key2 || a.key3 == b.key3 || a.key4 == b.key4
+}
+
+#[hegel::test(test_cases = 256)]
+fn proptest_ops(tc: TestCase) {
+    let mut map = make_map();
+    let mut naive: Vec<QuadItem> = Vec::new();
+
+    for _ in 0..tc.draw(gs::integers::<usize>().max_value(64)) {
+        let item = tc.draw(quad_item());
+        match tc.draw(gs::integers::<u8>().max_value(3)) {
+            0 => {
+                let result = map.insert_unique(item.clone());
+                let mut expected: Vec<_> =
+                    naive.iter().filter(|e| conflicts(e, &item)).collect();
+                match result {
+                    Ok(()) => {
+                        assert!(expected.is_empty(), "expected {expected:?}");
+                        naive.push(item);
+                    }
+                    Err(error) => {
+                        let mut duplicates = error.duplicates().to_vec();
+                        duplicates.sort();
+                        expected.sort();
+                        assert_eq!(duplicates, expected);
+                    }
+                }
+            }
+            1 => {
+                let mut overwritten = map.insert_overwrite(item.clone());
+                let (mut expected, kept): (Vec<_>, Vec<_>) =
+                    naive.drain(..).partition(|e| conflicts(e, &item));
+                naive = kept;
+                naive.push(item);
+                overwritten.sort();
+                expected.sort();
+                assert_eq!(overwritten, expected);
+            }
+            2 => {
+                let removed = map.remove2(&item.key2);
+                let expected = naive
+                    .iter()
+                    .position(|e| e.key2 == item.key2)
+                    .map(|ix| naive.swap_remove(ix));
+                assert_eq!(removed, expected);
+            }
+            _ => {
+                let removed = map.remove_unique(
+                    &item.key1,
+                    &item.key2,
+                    item.key3.as_str(),
+                    &item.key4,
+                );
+                let expected = naive
+                    .iter()
+                    .position(|e| {
+                        e.key1 == item.key1
+                            && e.key2 == item.key2
+                            && e.key3 == item.key3
+                            && e.key4 == item.key4
+                    })
+                    .map(|ix| naive.swap_remove(ix));
+                assert_eq!(removed, expected);
+            }
+        }
+
+        map.validate(ValidateCompact::NonCompact).expect("map is valid");
+        assert_eq!(map.len(), naive.len());
+        for e in &naive {
+            assert_eq!(map.get1(&e.key1), Some(e));
+            assert_eq!(map.get2(&e.key2), Some(e));
+            assert_eq!(map.get3(e.key3.as_str()), Some(e));
+            assert_eq!(map.get4(&e.key4), Some(e));
+        }
+    }
+}
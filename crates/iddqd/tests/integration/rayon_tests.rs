@@ -0,0 +1,129 @@
+use hegel::{TestCase, generators as gs};
+use iddqd::{
+    BiHashItem, BiHashMap, IdHashItem, IdHashMap, TriHashItem, TriHashMap,
+    bi_upcast, id_upcast, tri_upcast,
+};
+use iddqd_test_utils::test_item::{Alloc, HashBuilder};
+use rayon::prelude::*;
+use std::collections::HashSet;
+
+// The common `TestItem` uses `Cell`s for chaos testing, so it isn't `Sync`.
+// Use a simpler item here.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+struct ParItem {
+    id: u32,
+    name: String,
+    code: u64,
+}
+
+impl ParItem {
+    fn new(id: u32) -> Self {
+        Self { id, name: id.to_string(), code: u64::from(id) * 3 }
+    }
+}
+
+impl IdHashItem for ParItem {
+    type Key<'a> = u32;
+
+    fn key(&self) -> Self::Key<'_> {
+        self.id
+    }
+
+    id_upcast!();
+}
+
+impl BiHashItem for ParItem {
+    type K1<'a> = u32;
+    type K2<'a> = &'a str;
+
+    fn key1(&self) -> Self::K1<'_> {
+        self.id
+    }
+
+    fn key2(&self) -> Self::K2<'_> {
+        &self.name
+    }
+
+    bi_upcast!();
+}
+
+impl TriHashItem for ParItem {
+    type K1<'a> = u32;
+    type K2<'a> = &'a str;
+    type K3<'a> = u64;
+
+    fn key1(&self) -> Self::K1<'_> {
+        self.id
+    }
+
+    fn key2(&self) -> Self::K2<'_> {
+        &self.name
+    }
+
+    fn key3(&self) -> Self::K3<'_> {
+        self.code
+    }
+
+    tri_upcast!();
+}
+
+/// Draws the IDs to insert, and the subset of them to remove afterwards so
+/// that the underlying storage has holes.
+fn draw_ids(tc: &TestCase) -> (Vec<u32>, HashSet<u32>) {
+    let ids: Vec<u32> = tc
+        .draw(gs::hashsets(gs::integers::<u32>()).max_size(512))
+        .into_iter()
+        .collect();
+    let remove =
+        ids.iter().copied().filter(|_| tc.draw(gs::booleans())).collect();
+    (ids, remove)
+}
+
+fn sorted<'a>(items: impl IntoIterator<Item = &'a ParItem>) -> Vec<ParItem> {
+    let mut items: Vec<_> = items.into_iter().cloned().collect();
+    items.sort();
+    items
+}
+
+#[hegel::test(test_cases = 64)]
+fn par_iter_matches_iter(tc: TestCase) {
+    let (ids, remove) = draw_ids(&tc);
+
+    let mut id_map = IdHashMap::<ParItem, HashBuilder, Alloc>::with_hasher_in(
+        HashBuilder::default(),
+        Alloc::default(),
+    );
+    let mut bi_map = BiHashMap::<ParItem, HashBuilder, Alloc>::with_hasher_in(
+        HashBuilder::default(),
+        Alloc::default(),
+    );
+    let mut tri_map = TriHashMap::<ParItem, HashBuilder, Alloc>::with_hasher_in(
+        HashBuilder::default(),
+        Alloc::default(),
+    );
+    for &id in &ids {
+        id_map.insert_unique(ParItem::new(id)).unwrap();
+        bi_map.insert_unique(ParItem::new(id)).unwrap();
+        tri_map.insert_unique(ParItem::new(id)).unwrap();
+    }
+    for id in &remove {
+        id_map.remove(id).unwrap();
+        bi_map.remove1(id).unwrap();
+        tri_map.remove1(id).unwrap();
+    }
+
+    let expected = sorted(id_map.iter());
+    assert_eq!(expected.len(), ids.len() - remove.len());
+
+    let actual: Vec<&ParItem> = id_map.par_iter().collect();
+    assert_eq!(sorted(actual), expected);
+    let actual: Vec<&ParItem> = (&bi_map).into_par_iter().collect();
+    assert_eq!(sorted(actual), expected);
+    let actual: Vec<&ParItem> = tri_map.par_iter().collect();
+    assert_eq!(sorted(actual), expected);
+
+    assert_eq!(
+        tri_map.par_iter().map(|item| item.code).sum::<u64>(),
+        expected.iter().map(|item| item.code).sum::<u64>(),
+    );
+}
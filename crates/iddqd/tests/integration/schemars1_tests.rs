@@ -0,0 +1,204 @@
+use expectorate::assert_contents;
+use iddqd::{
+    BiHashItem, BiHashMap, IdHashItem, IdHashMap, IdOrdItem, IdOrdMap,
+    TriHashItem, TriHashMap, bi_hash_map::BiHashMapAsMap, bi_upcast,
+    id_hash_map::IdHashMapAsMap, id_ord_map::IdOrdMapAsMap, id_upcast,
+    tri_hash_map::TriHashMapAsMap, tri_upcast,
+};
+use schemars1::{JsonSchema, schema_for};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+#[schemars(crate = "schemars1")]
+struct TestUser {
+    age: u32,
+    email: String,
+    id: u32,
+    name: String,
+}
+
+impl IdHashItem for TestUser {
+    type Key<'a> = &'a str;
+
+    fn key(&self) -> Self::Key<'_> {
+        &self.name
+    }
+
+    id_upcast!();
+}
+
+impl BiHashItem for TestUser {
+    type K1<'a> = &'a str;
+    type K2<'a> = u32;
+
+    fn key1(&self) -> Self::K1<'_> {
+        &self.name
+    }
+
+    fn key2(&self) -> Self::K2<'_> {
+        self.id
+    }
+
+    bi_upcast!();
+}
+
+impl TriHashItem for TestUser {
+    type K1<'a> = &'a str;
+    type K2<'a> = u32;
+    type K3<'a> = &'a str;
+
+    fn key1(&self) -> Self::K1<'_> {
+        &self.name
+    }
+
+    fn key2(&self) -> Self::K2<'_> {
+        self.id
+    }
+
+    fn key3(&self) -> Self::K3<'_> {
+        &self.email
+    }
+
+    tri_upcast!();
+}
+
+impl IdOrdItem for TestUser {
+    type Key<'a> = &'a str;
+
+    fn key(&self) -> Self::Key<'_> {
+        &self.name
+    }
+
+    id_upcast!();
+}
+
+#[test]
+fn schema_fixtures() {
+    let schema = schema_for!(IdHashMap<TestUser>);
+    assert_contents(
+        "tests/output/schemars1/id_hash_map_schema.json",
+        &to_string_pretty_ln(&schema),
+    );
+
+    let schema = schema_for!(IdHashMapAsMap<TestUser>);
+    assert_contents(
+        "tests/output/schemars1/id_hash_map_as_map_schema.json",
+        &to_string_pretty_ln(&schema),
+    );
+
+    let schema = schema_for!(IdOrdMap<TestUser>);
+    assert_contents(
+        "tests/output/schemars1/id_ord_map_schema.json",
+        &to_string_pretty_ln(&schema),
+    );
+
+    let schema = schema_for!(IdOrdMapAsMap<TestUser>);
+    assert_contents(
+        "tests/output/schemars1/id_ord_map_as_map_schema.json",
+        &to_string_pretty_ln(&schema),
+    );
+
+    let schema = schema_for!(BiHashMap<TestUser>);
+    assert_contents(
+        "tests/output/schemars1/bi_hash_map_schema.json",
+        &to_string_pretty_ln(&schema),
+    );
+
+    let schema = schema_for!(BiHashMapAsMap<TestUser>);
+    assert_contents(
+        "tests/output/schemars1/bi_hash_map_as_map_schema.json",
+        &to_string_pretty_ln(&schema),
+    );
+
+    let schema = schema_for!(TriHashMap<TestUser>);
+    assert_contents(
+        "tests/output/schemars1/tri_hash_map_schema.json",
+        &to_string_pretty_ln(&schema),
+    );
+
+    let schema = schema_for!(TriHashMapAsMap<TestUser>);
+    assert_contents(
+        "tests/output/schemars1/tri_hash_map_as_map_schema.json",
+        &to_string_pretty_ln(&schema),
+    );
+}
+
+#[test]
+fn container_fixtures() {
+    #[derive(JsonSchema)]
+    #[schemars(crate = "schemars1")]
+    #[expect(unused)]
+    struct Container {
+        users_bi: BiHashMap<TestUser>,
+        users_hash: IdHashMap<TestUser>,
+        users_ord: IdOrdMap<TestUser>,
+        users_tri: TriHashMap<TestUser>,
+    }
+
+    // Verify the container can generate a schema.
+    let schema = schema_for!(Container);
+    assert_contents(
+        "tests/output/schemars1/container_schema.json",
+        &to_string_pretty_ln(&schema),
+    );
+
+    // A simple container with just IdHashMap<TestUser>. This fixture is
+    // used by `typify-types.rs` to show end-to-end usage.
+    #[derive(JsonSchema)]
+    #[schemars(crate = "schemars1")]
+    #[expect(unused)]
+    struct SimpleContainer {
+        users: IdHashMap<TestUser>,
+    }
+
+    let schema = schema_for!(SimpleContainer);
+    assert_contents(
+        "tests/output/schemars1/simple_container_schema.json",
+        &to_string_pretty_ln(&schema),
+    );
+
+    // Container using the AsMap types with serde's `with` attribute.
+    #[derive(JsonSchema)]
+    #[schemars(crate = "schemars1")]
+    #[expect(unused)]
+    struct ContainerAsMap {
+        #[serde(with = "BiHashMapAsMap::<TestUser>")]
+        users_bi: BiHashMap<TestUser>,
+        #[serde(with = "IdHashMapAsMap::<TestUser>")]
+        users_hash: IdHashMap<TestUser>,
+        #[serde(with = "IdOrdMapAsMap::<TestUser>")]
+        users_ord: IdOrdMap<TestUser>,
+        #[serde(with = "TriHashMapAsMap::<TestUser>")]
+        users_tri: TriHashMap<TestUser>,
+    }
+
+    let schema = schema_for!(ContainerAsMap);
+    assert_contents(
+        "tests/output/schemars1/container_as_map_schema.json",
+        &to_string_pretty_ln(&schema),
+    );
+}
+
+fn to_string_pretty_ln<T: Serialize>(data: &T) -> String {
+    // schemars 1.x represents schemas as JSON values, so the order of keys
+    // depends on whether serde_json's `preserve_order` feature is enabled.
+    // Sort keys so that the same fixture is generated either way.
+    let value = sort_keys(serde_json::to_value(data).unwrap());
+    let mut s = serde_json::to_string_pretty(&value).unwrap();
+    s.push('\n');
+    s
+}
+
+fn sort_keys(value: serde_json::Value) -> serde_json::Value {
+    match value {
+        serde_json::Value::Array(values) => {
+            values.into_iter().map(sort_keys).collect()
+        }
+        serde_json::Value::Object(map) => {
+            let mut entries: Vec<_> = map.into_iter().collect();
+            entries.sort_by(|(a, _), (b, _)| a.cmp(b));
+            entries.into_iter().map(|(k, v)| (k, sort_keys(v))).collect()
+        }
+        other => other,
+    }
+}
@@ -1,11 +1,10 @@
 use expectorate::assert_contents;
 use iddqd::{
-    BiHashItem, BiHashMap, IdHashItem, IdHashMap, TriHashItem, TriHashMap,
-    bi_hash_map::BiHashMapAsMap, bi_upcast, id_hash_map::IdHashMapAsMap,
-    id_upcast, tri_hash_map::TriHashMapAsMap, tri_upcast,
+    BiHashItem, BiHashMap, IdHashItem, IdHashMap, IdOrdItem, IdOrdMap,
+    TriHashItem, TriHashMap, bi_hash_map::BiHashMapAsMap, bi_upcast,
+    id_hash_map::IdHashMapAsMap, id_ord_map::IdOrdMapAsMap, id_upcast,
+    tri_hash_map::TriHashMapAsMap, tri_upcast,
 };
-#[cfg(feature = "std")]
-use iddqd::{IdOrdItem, IdOrdMap, id_ord_map::IdOrdMapAsMap};
 use schemars::{JsonSchema, schema_for};
 use serde::{Deserialize, Serialize};
 
@@ -64,7 +63,6 @@ impl TriHashItem for TestUser {
     tri_upcast!();
 }
 
-#[cfg(feature = "std")]
 impl IdOrdItem for TestUser {
     type Key<'a> = &'a str;
 
@@ -89,20 +87,17 @@ fn schema_fixtures() {
         &to_string_pretty_ln(&schema),
     );
 
-    #[cfg(feature = "std")]
-    {
-        let schema = schema_for!(IdOrdMap<TestUser>);
-        assert_contents(
-            "tests/output/id_ord_map_schema.json",
-            &to_string_pretty_ln(&schema),
-        );
-
-        let schema = schema_for!(IdOrdMapAsMap<TestUser>);
-        assert_contents(
-            "tests/output/id_ord_map_as_map_schema.json",
-            &to_string_pretty_ln(&schema),
-        );
-    }
+    let schema = schema_for!(IdOrdMap<TestUser>);
+    assert_contents(
+        "tests/output/id_ord_map_schema.json",
+        &to_string_pretty_ln(&schema),
+    );
+
+    let schema = schema_for!(IdOrdMapAsMap<TestUser>);
+    assert_contents(
+        "tests/output/id_ord_map_as_map_schema.json",
+        &to_string_pretty_ln(&schema),
+    );
 
     let schema = schema_for!(BiHashMap<TestUser>);
     assert_contents(
@@ -129,7 +124,6 @@ fn schema_fixtures() {
     );
 }
 
-#[cfg(feature = "std")]
 #[test]
 fn container_fixtures() {
     #[derive(JsonSchema)]
@@ -0,0 +1,151 @@
+use crate::hegel_support::draw_random_batch;
+use hegel::TestCase;
+use iddqd::{
+    IdHashItem, IdHashMap, IdOrdItem, IdOrdMap, id_upcast, serde::keyed,
+};
+use iddqd_test_utils::serde_json;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+struct Item {
+    name: String,
+    value: u32,
+}
+
+impl IdHashItem for Item {
+    type Key<'a> = &'a str;
+    fn key(&self) -> Self::Key<'_> {
+        &self.name
+    }
+    id_upcast!();
+}
+
+impl IdOrdItem for Item {
+    type Key<'a> = &'a str;
+    fn key(&self) -> Self::Key<'_> {
+        &self.name
+    }
+    id_upcast!();
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct NumberKeyed {
+    id: u32,
+}
+
+impl IdOrdItem for NumberKeyed {
+    type Key<'a> = u32;
+    fn key(&self) -> Self::Key<'_> {
+        self.id
+    }
+    id_upcast!();
+}
+
+fn to_json<'a, M: keyed::KeyedMap<'a>>(map: &M) -> serde_json::Result<String>
+where
+    M::Item: Serialize,
+{
+    let mut out = Vec::new();
+    keyed::serialize(map, &mut serde_json::Serializer::new(&mut out))?;
+    Ok(String::from_utf8(out).unwrap())
+}
+
+fn from_json<'a, M: keyed::KeyedMap<'a>>(json: &str) -> serde_json::Result<M>
+where
+    M::Item: for<'de> Deserialize<'de> + std::fmt::Debug,
+{
+    keyed::deserialize(&mut serde_json::Deserializer::from_str(json))
+}
+
+// Checks that both the hash and ordered maps reject `json` with an error
+// containing `message`.
+fn assert_rejected(json: &str, message: &str) {
+    let hash_error = from_json::<IdHashMap<Item>>(json).unwrap_err();
+    assert!(
+        hash_error.to_string().contains(message),
+        "IdHashMap error {hash_error:?} contains {message:?}",
+    );
+    let ord_error = from_json::<IdOrdMap<Item>>(json).unwrap_err();
+    assert!(
+        ord_error.to_string().contains(message),
+        "IdOrdMap error {ord_error:?} contains {message:?}",
+    );
+}
+
+#[test]
+fn rejects_duplicate_key() {
+    assert_rejected(
+        r#"{"a": {"name": "a", "value": 1}, "a": {"name": "a", "value": 2}}"#,
+        "conflicts with existing",
+    );
+}
+
+#[test]
+fn rejects_mismatched_key() {
+    assert_rejected(
+        r#"{"a": {"name": "b", "value": 1}}"#,
+        r#"object key "a" does not match item key "b""#,
+    );
+    // A mismatch is rejected even if it would otherwise be a duplicate.
+    assert_rejected(
+        r#"{"a": {"name": "a", "value": 1}, "b": {"name": "a", "value": 2}}"#,
+        "does not match item key",
+    );
+}
+
+#[test]
+fn rejects_non_string_key() {
+    let mut map = IdOrdMap::new();
+    map.insert_unique(NumberKeyed { id: 1 }).unwrap();
+    let error = to_json(&map).unwrap_err();
+    assert!(
+        error.to_string().contains("must serialize as a string"),
+        "error {error:?} mentions string keys",
+    );
+
+    let error =
+        from_json::<IdOrdMap<NumberKeyed>>(r#"{"1": {"id": 1}}"#).unwrap_err();
+    assert!(
+        error.to_string().contains("must serialize as a string"),
+        "error {error:?} mentions string keys",
+    );
+}
+
+#[test]
+fn with_attribute() {
+    #[derive(Debug, Serialize, Deserialize)]
+    struct Config {
+        #[serde(with = "keyed")]
+        hash: IdHashMap<Item>,
+        #[serde(with = "keyed")]
+        ord: IdOrdMap<Item>,
+    }
+
+    let json = r#"{"hash":{"x":{"name":"x","value":1}},"ord":{"y":{"name":"y","value":2}}}"#;
+    let config: Config = serde_json::from_str(json).unwrap();
+    assert_eq!(config.hash.get("x").unwrap().value, 1);
+    assert_eq!(config.ord.get("y").unwrap().value, 2);
+    assert_eq!(serde_json::to_string(&config).unwrap(), json);
+}
+
+#[hegel::test(test_cases = 256)]
+fn proptest_roundtrip(tc: TestCase) {
+    let mut hash_map = IdHashMap::<Item>::new();
+    let mut ord_map = IdOrdMap::<Item>::new();
+    for item in draw_random_batch(&tc) {
+        let item = Item { name: item.key3, value: u32::from(item.key1) };
+        hash_map.insert_overwrite(item.clone());
+        ord_map.insert_overwrite(item);
+    }
+
+    let json = to_json(&hash_map).unwrap();
+    let deserialized: IdHashMap<Item> = from_json(&json).unwrap();
+    assert_eq!(deserialized, hash_map);
+
+    // The ordered map serializes in key order, so it round-trips to the same
+    // JSON.
+    let json = to_json(&ord_map).unwrap();
+    let deserialized: IdOrdMap<Item> = from_json(&json).unwrap();
+    assert_eq!(deserialized, ord_map);
+    assert_eq!(to_json(&deserialized).unwrap(), json);
+}
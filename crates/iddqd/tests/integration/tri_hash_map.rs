@@ -4,12 +4,12 @@ use crate::hegel_support::{
 };
 use hegel::{TestCase, generators as gs};
 use iddqd::{
-    TriHashItem, TriHashMap, internal::ValidateCompact, tri_hash_map,
-    tri_upcast,
+    ExtendMode, ImmutableKey, TriHashItem, TriHashMap,
+    internal::ValidateCompact, tri_hash_map, tri_upcast,
 };
 use iddqd_test_utils::{
     borrowed_item::BorrowedItem,
-    eq_props::{assert_eq_props, assert_ne_props},
+    eq_props::{assert_eq_props, assert_hash_eq, assert_ne_props},
     naive_map::NaiveMap,
     test_item::{
         Alloc, HashBuilder, ItemMap, TestItem, TestKey1, TestKey2, TestKey3,
@@ -134,6 +134,73 @@ fn test_extend() {
     assert_eq!(map.get1(&TestKey1::new(&50)).unwrap().value, "stays as is");
 }
 
+#[test]
+fn test_extend_with_unique() {
+    let mut map = TriHashMap::<TestItem, HashBuilder, Alloc>::make_new();
+    let existing = TestItem::new(1, 'a', "x", "v");
+    map.insert_unique(existing.clone()).unwrap();
+
+    // Stops at the first duplicate: items before it are kept, items after it
+    // are dropped.
+    let conflicting = TestItem::new(1, 'c', "z", "conflict");
+    let items = vec![
+        TestItem::new(2, 'b', "y", "w"),
+        conflicting.clone(),
+        TestItem::new(3, 'd', "q", "dropped"),
+    ];
+    let error = map.extend_with(items, ExtendMode::Unique).unwrap_err();
+    assert_eq!(error.new_item(), &conflicting);
+    assert_eq!(error.duplicates(), &[&existing]);
+
+    assert_eq!(map.len(), 2);
+    assert_eq!(map.get1(&TestKey1::new(&2)).unwrap().value, "w");
+    assert_eq!(map.get1(&TestKey1::new(&3)), None);
+    assert_eq!(map.get1(&TestKey1::new(&1)).unwrap().value, "v");
+
+    // Overwrite mode matches `extend`.
+    map.extend_with(vec![conflicting], ExtendMode::Overwrite).unwrap();
+    assert_eq!(map.get1(&TestKey1::new(&1)).unwrap().value, "conflict");
+}
+
+#[test]
+fn map_transforms_items() {
+    let mut map = TriHashMap::<TestItem, HashBuilder, Alloc>::make_new();
+    for i in 0..4 {
+        map.insert_unique(TestItem::new(
+            i,
+            (b'a' + i) as char,
+            i.to_string(),
+            "v",
+        ))
+        .unwrap();
+    }
+
+    let mapped = map
+        .map(|mut item| {
+            item.value = format!("mapped-{}", item.key1);
+            item
+        })
+        .expect("keys are unchanged");
+    mapped.validate(ValidateCompact::Compact).expect("map is valid");
+    assert_eq!(mapped.len(), 4);
+    for i in 0..4 {
+        assert_eq!(
+            mapped.get1(&TestKey1::new(&i)).unwrap().value,
+            format!("mapped-{i}")
+        );
+    }
+
+    // Collapsing a key reports the first conflict.
+    let error = mapped
+        .map(|mut item| {
+            item.key3 = "same".to_owned();
+            item
+        })
+        .unwrap_err();
+    assert_eq!(error.duplicates().len(), 1);
+    assert_ne!(error.new_item().value, error.duplicates()[0].value);
+}
+
 #[test]
 fn with_capacity() {
     let map = TriHashMap::<TestItem, HashBuilder>::with_capacity_and_hasher(
@@ -197,6 +264,13 @@ fn test_insert_unique() {
         assert_eq!(*e2, v5);
     }
 
+    // contains_any matches keys from different items, and predicts whether
+    // insert_unique would fail.
+    assert!(map.contains_any(&v1.key1(), &v5.key2(), &v5.key3()));
+    assert!(map.contains_any(&v4.key1(), &v4.key2(), &v4.key3()));
+    let v6 = TestItem::new(2, 'c', "z", "v");
+    assert!(!map.contains_any(&v6.key1(), &v6.key2(), &v6.key3()));
+
     // Check that the *unique methods work.
     assert!(map.contains_key_unique(&v5.key1(), &v5.key2(), &v5.key3()));
     assert_eq!(map.get_unique(&v5.key1(), &v5.key2(), &v5.key3()), Some(&v5));
@@ -229,6 +303,104 @@ fn test_ref_mut_aliasing() {
     }
 }
 
+#[test]
+fn keys_and_values() {
+    let mut map = TriHashMap::<TestItem, HashBuilder, Alloc>::make_new();
+    map.insert_unique(TestItem::new(1, 'a', "x", "v1")).unwrap();
+    map.insert_unique(TestItem::new(2, 'b', "y", "v2")).unwrap();
+    map.insert_unique(TestItem::new(3, 'c', "z", "v3")).unwrap();
+
+    assert_eq!(map.key1s().len(), 3);
+    assert!(map.key1s().eq(map.iter().map(|item| item.key1())));
+    assert!(map.key2s().eq(map.iter().map(|item| item.key2())));
+    assert!(map.key3s().eq(map.iter().map(|item| item.key3())));
+    assert!(map.values().eq(map.iter()));
+
+    for mut item in map.values_mut() {
+        item.value.push_str("-updated");
+    }
+    for item in map.values() {
+        assert!(item.value.ends_with("-updated"), "{item:?} was updated");
+    }
+}
+
+#[test]
+fn iter_exact_size_and_fused() {
+    let mut map = TriHashMap::<TestItem, HashBuilder, Alloc>::make_new();
+    map.insert_unique(TestItem::new(1, 'a', "x", "v1")).unwrap();
+    map.insert_unique(TestItem::new(2, 'b', "y", "v2")).unwrap();
+    map.insert_unique(TestItem::new(3, 'c', "z", "v3")).unwrap();
+    map.insert_unique(TestItem::new(4, 'd', "w", "v4")).unwrap();
+    // Leave a hole in the underlying storage, which must not be counted.
+    map.remove1(&TestKey1::new(&2)).unwrap();
+
+    let mut iter = map.iter();
+    assert_eq!(iter.len(), 3);
+    assert_eq!(iter.size_hint(), (3, Some(3)));
+    iter.next().unwrap();
+    assert_eq!(iter.len(), 2);
+    assert_eq!(iter.by_ref().count(), 2);
+    assert_eq!(iter.len(), 0);
+    assert!(iter.next().is_none());
+    assert!(iter.next().is_none());
+
+    let mut iter_mut = map.iter_mut().fuse();
+    assert_eq!(iter_mut.size_hint(), (3, Some(3)));
+    assert_eq!(iter_mut.by_ref().count(), 3);
+    assert!(iter_mut.next().is_none());
+    assert!(iter_mut.next().is_none());
+
+    let mut into_iter = IntoIterator::into_iter(map).fuse();
+    assert_eq!(into_iter.len(), 3);
+    assert_eq!(into_iter.by_ref().map(|item| item.key1).sum::<u8>(), 8);
+    assert_eq!(into_iter.len(), 0);
+    assert!(into_iter.next().is_none());
+    assert!(into_iter.next().is_none());
+}
+
+#[test]
+fn get_mut_plain() {
+    #[derive(Clone, Debug)]
+    struct Item {
+        id: u32,
+        name: &'static str,
+        code: char,
+        value: u32,
+    }
+
+    impl TriHashItem for Item {
+        type K1<'a> = u32;
+        type K2<'a> = &'a str;
+        type K3<'a> = char;
+        fn key1(&self) -> Self::K1<'_> {
+            self.id
+        }
+        fn key2(&self) -> Self::K2<'_> {
+            self.name
+        }
+        fn key3(&self) -> Self::K3<'_> {
+            self.code
+        }
+        tri_upcast!();
+    }
+
+    impl ImmutableKey for Item {}
+
+    let mut map = TriHashMap::<Item, HashBuilder, Alloc>::make_new();
+    map.insert_unique(Item { id: 1, name: "foo", code: 'f', value: 0 })
+        .unwrap();
+
+    map.get1_mut_plain(&1).unwrap().value += 1;
+    map.get2_mut_plain("foo").unwrap().value += 10;
+    map.get3_mut_plain(&'f').unwrap().value += 100;
+    assert_eq!(map.get1(&1).unwrap().value, 111);
+
+    assert!(map.get1_mut_plain(&2).is_none());
+    assert!(map.get2_mut_plain("bar").is_none());
+    assert!(map.get3_mut_plain(&'b').is_none());
+    map.validate(ValidateCompact::Compact).expect("map is valid");
+}
+
 // Example-based test for insert_overwrite.
 //
 // Can be used to write down examples seen from the property-based operation
@@ -248,6 +420,112 @@ fn test_insert_overwrite() {
     map.validate(ValidateCompact::NonCompact).expect("validation failed");
 }
 
+#[test]
+fn entry_examples() {
+    let mut map = TriHashMap::<TestItem, HashBuilder, Alloc>::make_new();
+    let item1 = TestItem::new(0, 'a', "x", "v");
+
+    let tri_hash_map::Entry::Vacant(entry) =
+        map.entry(item1.key1(), item1.key2(), item1.key3())
+    else {
+        panic!("expected VacantEntry")
+    };
+    let mut entry = entry.insert_entry(item1.clone());
+
+    assert!(entry.is_unique());
+    assert!(!entry.is_non_unique());
+    assert_eq!(entry.get().as_unique(), Some(&item1));
+    assert_eq!(entry.get().by_key1(), Some(&item1));
+    assert_eq!(entry.get().by_key2(), Some(&item1));
+    assert_eq!(entry.get().by_key3(), Some(&item1));
+    assert_eq!(entry.get_mut().as_unique().unwrap().into_ref(), &item1);
+    assert_eq!(entry.get_mut().by_key3().unwrap().into_ref(), &item1);
+    assert_eq!(entry.into_ref().as_unique(), Some(&item1));
+
+    // item2 shares key1 and key3 with item1, and key2 with nothing.
+    let item2 = TestItem::new(0, 'b', "x", "v");
+    let tri_hash_map::Entry::Occupied(mut entry) =
+        map.entry(item2.key1(), item2.key2(), item2.key3())
+    else {
+        panic!("expected OccupiedEntry")
+    };
+
+    assert!(entry.is_non_unique());
+    assert_eq!(entry.get().as_unique(), None);
+    assert_eq!(entry.get().by_key1(), Some(&item1));
+    assert_eq!(entry.get().by_key2(), None);
+    assert_eq!(entry.get().by_key3(), Some(&item1));
+    // item1 is only borrowed mutably once, through key1.
+    {
+        let mut entry_mut = entry.get_mut();
+        assert!(entry_mut.as_unique().is_none());
+        assert_eq!(entry_mut.by_key1().unwrap().into_ref(), &item1);
+        assert!(entry_mut.by_key2().is_none());
+        assert!(entry_mut.by_key3().is_none());
+        let mut count = 0;
+        entry_mut.for_each(|_| count += 1);
+        assert_eq!(count, 1);
+    }
+
+    let old_items = entry.insert(item2.clone());
+    assert_eq!(old_items, vec![item1]);
+    assert!(entry.is_unique());
+    assert_eq!(entry.get().as_unique(), Some(&item2));
+
+    let removed = entry.remove();
+    assert_eq!(removed, vec![item2]);
+    assert_eq!(map.len(), 0);
+
+    // Set up three items, and an entry whose keys point at all three.
+    let item3 = TestItem::new(1, 'c', "y", "v");
+    let item4 = TestItem::new(2, 'd', "z", "v");
+    let item5 = TestItem::new(3, 'e', "w", "v");
+    for item in [&item3, &item4, &item5] {
+        map.entry(item.key1(), item.key2(), item.key3())
+            .or_insert(item.clone());
+    }
+    assert_eq!(map.len(), 3);
+
+    {
+        let mut entry_mut = map
+            .entry(item3.key1(), item4.key2(), item5.key3())
+            .or_insert_with(|| panic!("entry should be occupied"));
+        assert_eq!(entry_mut.by_key1().unwrap().into_ref(), &item3);
+        assert_eq!(entry_mut.by_key2().unwrap().into_ref(), &item4);
+        assert_eq!(entry_mut.by_key3().unwrap().into_ref(), &item5);
+    }
+
+    let tri_hash_map::Entry::Occupied(mut entry) =
+        map.entry(item3.key1(), item4.key2(), item5.key3())
+    else {
+        panic!("expected OccupiedEntry")
+    };
+    let item6 = TestItem::new(1, 'd', "w", "v");
+    let mut old_items = entry.insert(item6.clone());
+    old_items.sort();
+    assert_eq!(old_items, vec![item3, item4, item5]);
+    assert_eq!(entry.get().as_unique(), Some(&item6));
+    assert_eq!(map.len(), 1);
+
+    map.validate(ValidateCompact::NonCompact).expect("validation failed");
+}
+
+#[test]
+#[should_panic(expected = "key2 mismatch")]
+fn entry_insert_panics_on_key_mismatch() {
+    let mut map = TriHashMap::<TestItem, HashBuilder, Alloc>::make_new();
+    map.insert_unique(TestItem::new(0, 'a', "x", "v")).unwrap();
+
+    let tri_hash_map::Entry::Occupied(mut entry) =
+        map.entry(TestKey1::new(&0), TestKey2::new('b'), TestKey3::new("x"))
+    else {
+        panic!("expected OccupiedEntry")
+    };
+    // The new item's key2 belongs to an existing item, but the entry's key2
+    // doesn't.
+    entry.insert(TestItem::new(0, 'a', "x", "w"));
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum CompactnessChange {
     /// The operation makes the map non-compact.
@@ -284,6 +562,7 @@ impl TriHashMapMachine {
     fn check_valid(&mut self, change: CompactnessChange) {
         self.compactness = change.apply(self.compactness);
         self.map.validate(self.compactness).expect("map should be valid");
+        self.map.check_invariants().expect("map invariants should hold");
     }
 }
 
@@ -330,6 +609,62 @@ impl TriHashMapMachine {
         self.check_valid(CompactnessChange::NoLongerCompact);
     }
 
+    #[rule]
+    fn entry_insert_overwrite(&mut self, tc: TestCase) {
+        let item = tc.draw(test_item());
+        let map_res =
+            match self.map.entry(item.key1(), item.key2(), item.key3()) {
+                tri_hash_map::Entry::Occupied(mut entry) => {
+                    let mut dups = entry.insert(item.clone());
+                    dups.sort();
+                    Some(dups)
+                }
+                tri_hash_map::Entry::Vacant(_) => None,
+            };
+
+        let occupied = self.naive.get1(item.key1).is_some()
+            || self.naive.get2(item.key2).is_some()
+            || self.naive.get3(&item.key3).is_some();
+        let naive_res = occupied.then(|| {
+            let mut dups = self.naive.insert_overwrite(item.clone());
+            dups.sort();
+            dups
+        });
+
+        assert_eq!(
+            map_res, naive_res,
+            "map and naive map should agree on Entry::insert dups"
+        );
+        self.check_valid(CompactnessChange::NoLongerCompact);
+    }
+
+    #[rule]
+    fn entry_remove(&mut self, tc: TestCase) {
+        let (key1, key2, key3) = draw_lookup_keys123(&tc, &self.naive);
+
+        let map_res = match self.map.entry(
+            TestKey1::new(&key1),
+            TestKey2::new(key2),
+            TestKey3::new(&key3),
+        ) {
+            tri_hash_map::Entry::Occupied(entry) => {
+                let mut removed = entry.remove();
+                removed.sort();
+                removed
+            }
+            tri_hash_map::Entry::Vacant(_) => Vec::new(),
+        };
+
+        let mut naive_res = self.naive.entry_remove123(key1, key2, &key3);
+        naive_res.sort();
+
+        assert_eq!(
+            map_res, naive_res,
+            "map and naive map should agree on Entry::remove items"
+        );
+        self.check_valid(CompactnessChange::NoLongerCompact);
+    }
+
     #[rule]
     fn get1(&mut self, tc: TestCase) {
         let key1 = draw_lookup_key1(&tc, &self.naive);
@@ -370,6 +705,58 @@ impl TriHashMapMachine {
         assert_eq!(map_res, naive_res);
     }
 
+    #[rule]
+    fn get_conflicts(&mut self, tc: TestCase) {
+        let (key1, key2, key3) = draw_lookup_keys123(&tc, &self.naive);
+        let map_res = self.map.get_conflicts(
+            &TestKey1::new(&key1),
+            &TestKey2::new(key2),
+            &TestKey3::new(&key3),
+        );
+        let naive_res = (
+            self.naive.get1(key1),
+            self.naive.get2(key2),
+            self.naive.get3(&key3),
+        );
+
+        assert_eq!(map_res, naive_res);
+    }
+
+    #[rule]
+    fn get_by_two(&mut self, tc: TestCase) {
+        let (key1, key2, key3) = draw_lookup_keys123(&tc, &self.naive);
+        let (k1, k2, k3) =
+            (TestKey1::new(&key1), TestKey2::new(key2), TestKey3::new(&key3));
+
+        assert_eq!(
+            self.map.get12(&k1, &k2),
+            self.naive.get_unique12(key1, key2)
+        );
+        assert_eq!(
+            self.map.get13(&k1, &k3),
+            self.naive.get_unique13(key1, &key3)
+        );
+        assert_eq!(
+            self.map.get23(&k2, &k3),
+            self.naive.get_unique23(key2, &key3)
+        );
+    }
+
+    #[rule]
+    fn get_by_two_mut(&mut self, tc: TestCase) {
+        let (key1, key2, key3) = draw_lookup_keys123(&tc, &self.naive);
+        let (k1, k2, k3) =
+            (TestKey1::new(&key1), TestKey2::new(key2), TestKey3::new(&key3));
+
+        let map_res = self.map.get12_mut(&k1, &k2).map(|r| (*r).clone());
+        assert_eq!(map_res.as_ref(), self.naive.get_unique12(key1, key2));
+        let map_res = self.map.get13_mut(&k1, &k3).map(|r| (*r).clone());
+        assert_eq!(map_res.as_ref(), self.naive.get_unique13(key1, &key3));
+        let map_res = self.map.get23_mut(&k2, &k3).map(|r| (*r).clone());
+        assert_eq!(map_res.as_ref(), self.naive.get_unique23(key2, &key3));
+        self.check_valid(CompactnessChange::NoChange);
+    }
+
     #[rule]
     fn get_mut_unique(&mut self, tc: TestCase) {
         let (key1, key2, key3) = draw_lookup_keys123(&tc, &self.naive);
@@ -444,7 +831,7 @@ impl TriHashMapMachine {
             let contains = item.value.contains(ch);
             if equals { contains } else { !contains }
         });
-        self.check_valid(CompactnessChange::NoLongerCompact);
+        self.check_valid(CompactnessChange::BecomesCompact);
     }
 
     #[rule]
@@ -462,7 +849,7 @@ impl TriHashMapMachine {
             let matches = item.key1 % modulo == remainder;
             if equals { matches } else { !matches }
         });
-        self.check_valid(CompactnessChange::NoLongerCompact);
+        self.check_valid(CompactnessChange::BecomesCompact);
     }
 
     #[rule]
@@ -565,6 +952,84 @@ fn proptest_permutation_eq(tc: TestCase) {
             .unwrap();
     assert_eq_props(&map1, &map3);
     assert_eq_props(&map3, &map4);
+    assert_hash_eq(&map1, &map2);
+    assert_hash_eq(&map3, &map4);
+}
+
+#[hegel::test(test_cases = 64)]
+fn proptest_into_alloc(tc: TestCase) {
+    let set = draw_fill_batch(&tc);
+    let mut map = TriHashMap::<TestItem, HashBuilder, Alloc>::make_new();
+    for item in set.clone() {
+        map.insert_unique(item).expect("set is deduplicated");
+    }
+    // Leave some holes in the item set.
+    for item in set.iter().step_by(3) {
+        map.remove1(&item.key1()).expect("item is present");
+    }
+    let expected = map.clone();
+
+    let moved = map.into_alloc(Alloc::default());
+    moved.validate(ValidateCompact::Compact).expect("map is valid");
+    assert_eq_props(&moved, &expected);
+}
+
+#[hegel::test(test_cases = 256)]
+fn proptest_insert_expect_single_conflict(tc: TestCase) {
+    let mut map = TriHashMap::<TestItem, HashBuilder, Alloc>::make_new();
+    for item in draw_fill_batch(&tc) {
+        map.insert_unique(item).expect("set is deduplicated");
+    }
+    let item = tc.draw(test_item());
+
+    // insert_overwrite on a copy of the map tells us how many items the new
+    // item conflicts with.
+    let original = map.clone();
+    let mut overwritten_map = map.clone();
+    let mut overwritten = overwritten_map.insert_overwrite(item.clone());
+    overwritten.sort();
+
+    match map.insert_expect_single_conflict(item) {
+        Ok(replaced) => {
+            assert!(overwritten.len() <= 1, "overwritten: {overwritten:?}");
+            assert_eq!(replaced, overwritten.pop());
+            assert_eq_props(&map, &overwritten_map);
+        }
+        Err(error) => {
+            let mut duplicates: Vec<_> =
+                error.duplicates().iter().map(|&item| item.clone()).collect();
+            duplicates.sort();
+            assert_eq!(duplicates, overwritten);
+            assert!(duplicates.len() > 1, "duplicates: {duplicates:?}");
+            assert_eq_props(&map, &original);
+        }
+    }
+    map.validate(ValidateCompact::NonCompact).expect("map is valid");
+}
+
+#[test]
+fn occupancy_matches_capacity_breakdown() {
+    let mut map = TriHashMap::<TestItem, HashBuilder, Alloc>::make_new();
+    map.reserve(32);
+    for i in 0..8 {
+        let key3 = format!("x{i}");
+        map.insert_unique(TestItem::new(i, char::from(b'a' + i), key3, "v"))
+            .unwrap();
+    }
+
+    let breakdown = map.debug_capacity_breakdown();
+    let per_key = [map.occupancy1(), map.occupancy2(), map.occupancy3()];
+    for (&(len, capacity), &table_capacity) in
+        per_key.iter().zip(&breakdown.table_capacities)
+    {
+        assert_eq!(len, 8);
+        assert_eq!(capacity, table_capacity);
+    }
+
+    let (len, capacity) = map.occupancy();
+    assert_eq!(len, 8);
+    assert!(capacity >= 32);
+    assert_eq!(capacity, *breakdown.table_capacities.iter().min().unwrap());
 }
 
 // Test various conditions for non-equality.
@@ -884,6 +1349,7 @@ fn test_retain_none() {
 
     assert_eq!(map.len(), 0);
     assert!(map.is_empty());
+    map.validate(ValidateCompact::Compact).expect("map should be valid");
 }
 
 #[test]
@@ -989,9 +1455,12 @@ fn test_clear_makes_compact() {
     map.validate(ValidateCompact::NonCompact)
         .expect("map should be valid but non-compact");
 
-    // Clear should make it compact again
+    // Clear should make it compact again, keeping its capacity.
+    let capacity = map.capacity();
     map.clear();
     assert!(map.is_empty());
+    assert_eq!(map.len(), 0);
+    assert_eq!(map.capacity(), capacity);
     map.validate(ValidateCompact::Compact)
         .expect("cleared map should be compact");
 }